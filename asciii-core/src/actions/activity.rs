@@ -0,0 +1,308 @@
+//! Activity feed read from the history of the storage repository, see `asciii log`.
+//!
+//! The feed is parsed from `git log --unified=0 -p`, see `Repository::activity()`.
+//! Edited fields are guessed from the changed lines, nested keys get the name of their section,
+//! git puts that in the hunk header, e.g. `invoice/date`.
+
+use std::fmt;
+use std::path::Path;
+
+use crate::storage::archive_index;
+
+/// What happened to a project in one commit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    Created,
+    Edited(Vec<String>),
+    Payed,
+    Archived,
+    Deleted,
+}
+
+impl Change {
+    /// Untranslated name, used by `--porcelain`.
+    pub fn kind(&self) -> &'static str {
+        match *self {
+            Change::Created => "created",
+            Change::Edited(_) => "edited",
+            Change::Payed => "payed",
+            Change::Archived => "archived",
+            Change::Deleted => "deleted",
+        }
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Change::Created => write!(f, "{}", lformat!("created")),
+            Change::Edited(ref fields) => write!(f, "{}", lformat!("edited {}", fields.join(", "))),
+            Change::Payed => write!(f, "{}", lformat!("payed")),
+            Change::Archived => write!(f, "{}", lformat!("archived")),
+            Change::Deleted => write!(f, "{}", lformat!("deleted")),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Activity {
+    /// abbreviated commit hash
+    pub commit: String,
+    /// `YYYY-MM-DD`
+    pub date: String,
+    pub author: String,
+    /// name of the project file without extension
+    pub project: String,
+    pub change: Change,
+}
+
+/// Turns `3d`, `2w`, `6m` or `1y` into something `git log --since` understands,
+/// anything else is passed on as it is.
+pub fn since(span: &str) -> String {
+    let span = span.trim();
+    let (count, unit) = span.split_at(span.len().saturating_sub(1));
+    let unit = match unit {
+        "h" => "hours",
+        "d" => "days",
+        "w" => "weeks",
+        "m" => "months",
+        "y" => "years",
+        _ => return span.to_owned()
+    };
+    match count.parse::<u32>() {
+        Ok(count) => format!("{}.{}.ago", count, unit),
+        Err(_) => span.to_owned()
+    }
+}
+
+/// Reads the output of `Repository::activity()`, newest first.
+///
+/// Only files with `extension` count, a project that shows up below `archive` was archived.
+pub fn parse_log(log: &str, archive: &str, extension: &str) -> Vec<Activity> {
+    log.split('\x1e')
+       .filter(|record| !record.trim().is_empty())
+       .flat_map(|record| parse_commit(record, archive, extension))
+       .collect()
+}
+
+/// Groups activities by project, projects with the latest activity first.
+pub fn by_project(activities: &[Activity]) -> Vec<(&str, Vec<&Activity>)> {
+    let mut projects: Vec<(&str, Vec<&Activity>)> = Vec::new();
+    for activity in activities {
+        match projects.iter_mut().find(|(project, _)| *project == activity.project) {
+            Some((_, list)) => list.push(activity),
+            None => projects.push((&activity.project, vec![activity]))
+        }
+    }
+    projects
+}
+
+fn parse_commit(record: &str, archive: &str, extension: &str) -> Vec<Activity> {
+    let mut lines = record.lines();
+    let header = lines.next().unwrap_or_default().split('\x1f').collect::<Vec<_>>();
+    let (commit, date, author) = match header.as_slice() {
+        [commit, date, author, ..] => (*commit, *date, *author),
+        _ => return Vec::new()
+    };
+
+    let mut files = Vec::new();
+    for line in lines {
+        if let Some(paths) = line.strip_prefix("diff --git ") {
+            files.push(FileDiff::new(paths));
+        } else if let Some(file) = files.last_mut() {
+            file.read(line);
+        }
+    }
+
+    files.into_iter()
+         .filter(|file| Path::new(&file.path).extension().is_some_and(|e| e == extension))
+         .filter(|file| !archive_index::is_index(Path::new(&file.path)))
+         .flat_map(|file| {
+             let project = Path::new(&file.path).file_stem().unwrap_or_default().to_string_lossy().into_owned();
+             file.changes(archive).into_iter().map(move |change| Activity {
+                 commit: commit.to_owned(),
+                 date: date.to_owned(),
+                 author: author.to_owned(),
+                 project: project.clone(),
+                 change,
+             })
+         })
+         .collect()
+}
+
+/// One file in a commit.
+#[derive(Debug, Default)]
+struct FileDiff {
+    old_path: String,
+    path: String,
+    created: bool,
+    deleted: bool,
+    in_hunk: bool,
+    section: String,
+    fields: Vec<String>,
+    payed: bool,
+}
+
+impl FileDiff {
+    /// `paths` looks like `a/old b/new`
+    fn new(paths: &str) -> FileDiff {
+        let (old_path, path) = match paths.find(" b/") {
+            Some(i) => (paths[..i].trim_start_matches("a/"), &paths[i + 3..]),
+            None => (paths, paths)
+        };
+        FileDiff { old_path: old_path.to_owned(), path: path.to_owned(), ..FileDiff::default() }
+    }
+
+    fn read(&mut self, line: &str) {
+        if let Some(hunk) = line.strip_prefix("@@") {
+            self.in_hunk = true;
+            self.section = hunk.split_once("@@").and_then(|(_, context)| key(context)).unwrap_or_default().to_owned();
+        } else if !self.in_hunk {
+            if line.starts_with("new file mode") {
+                self.created = true;
+            } else if line.starts_with("deleted file mode") {
+                self.deleted = true;
+            } else if let Some(path) = line.strip_prefix("rename from ") {
+                self.old_path = path.to_owned();
+            } else if let Some(path) = line.strip_prefix("rename to ") {
+                self.path = path.to_owned();
+            }
+        } else if let Some(content) = line.strip_prefix('+').or_else(|| line.strip_prefix('-')) {
+            self.read_change(content, line.starts_with('+'));
+        }
+    }
+
+    fn read_change(&mut self, content: &str, added: bool) {
+        let name = match key(content) {
+            Some(name) => name,
+            None => return
+        };
+        let nested = content.starts_with(char::is_whitespace) || content.starts_with('-');
+        if !nested {
+            self.section = name.to_owned();
+        }
+        let field = if nested && !self.section.is_empty() {
+            format!("{}/{}", self.section, name)
+        } else {
+            name.to_owned()
+        };
+
+        if added && name == "payed_date" && !value(content).is_empty() {
+            self.payed = true;
+        }
+        if !self.fields.contains(&field) {
+            self.fields.push(field);
+        }
+    }
+
+    fn changes(self, archive: &str) -> Vec<Change> {
+        let archived = |path: &str| Path::new(path).starts_with(archive);
+        if self.deleted {
+            return vec![Change::Deleted];
+        }
+        if self.created {
+            return vec![if archived(&self.path) { Change::Archived } else { Change::Created }];
+        }
+
+        let mut changes = Vec::new();
+        if archived(&self.path) && !archived(&self.old_path) {
+            changes.push(Change::Archived);
+        }
+        if self.payed {
+            changes.push(Change::Payed);
+        }
+        let fields = self.fields.into_iter()
+                                .filter(|f| !(self.payed && f.ends_with("payed_date")))
+                                .collect::<Vec<_>>();
+        if !fields.is_empty() {
+            changes.push(Change::Edited(fields));
+        }
+        changes
+    }
+}
+
+/// The key of a yaml line like `  key: value` or `- key: value`.
+fn key(line: &str) -> Option<&str> {
+    let line = line.trim().trim_start_matches("- ").trim_start();
+    let name = line.split(':').next()?.trim();
+    if line.contains(':') && !name.is_empty() && !name.starts_with('#') && !name.contains(char::is_whitespace) {
+        Some(name)
+    } else {
+        None
+    }
+}
+
+/// The value of a yaml line like `key: value # comment`, placeholders count as empty.
+fn value(line: &str) -> &str {
+    let value = line.split_once(':').map(|(_, value)| value).unwrap_or_default();
+    let value = value.split(" #").next().unwrap_or_default().trim();
+    if value.starts_with("##") { "" } else { value }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOG: &str = "\x1e1f2e3d4\x1f2024-12-31\x1fMax\x1fpayed Party
+
+diff --git a/working/party/party.yml b/working/party/party.yml
+index 1111111..2222222 100644
+--- a/working/party/party.yml
++++ b/working/party/party.yml
+@@ -36 +36 @@ invoice:
+-  payed_date:
++  payed_date: 31.12.2024 # finally
+@@ -80 +80 @@ hours:
+-    Klaus: 2
++    Klaus: 3
+\x1eaaaaaaa\x1f2024-12-01\x1fMoritz\x1fold stuff
+
+diff --git a/working/old/old.yml b/archive/2024/R001_old/old.yml
+similarity index 98%
+rename from working/old/old.yml
+rename to archive/2024/R001_old/old.yml
+diff --git a/archive/2024/.index.yml b/archive/2024/.index.yml
+new file mode 100644
+index 0000000..3333333
+--- /dev/null
++++ b/archive/2024/.index.yml
+@@ -0,0 +1,2 @@
++- ident: R001_old
++  name: Old
+diff --git a/working/party/party.yml b/working/party/party.yml
+new file mode 100644
+index 0000000..1111111
+--- /dev/null
++++ b/working/party/party.yml
+@@ -0,0 +1,2 @@
++event:
++  name: Party
+diff --git a/working/party/offer.tex b/working/party/offer.tex
+new file mode 100644
+";
+
+    #[test]
+    fn parses_changes() {
+        let activities = parse_log(LOG, "archive", "yml");
+        let changes = activities.iter().map(|a| (a.project.as_str(), a.change.clone())).collect::<Vec<_>>();
+        assert_eq!(changes, vec![
+            ("party", Change::Payed),
+            ("party", Change::Edited(vec!["hours/Klaus".into()])),
+            ("old", Change::Archived),
+            ("party", Change::Created),
+        ]);
+        assert_eq!(activities[0].author, "Max");
+        assert_eq!(activities[2].date, "2024-12-01");
+
+        let projects = by_project(&activities);
+        assert_eq!(projects.iter().map(|(p, a)| (*p, a.len())).collect::<Vec<_>>(), vec![("party", 3), ("old", 1)]);
+    }
+
+    #[test]
+    fn since_spans() {
+        assert_eq!(since("2w"), "2.weeks.ago");
+        assert_eq!(since("10d"), "10.days.ago");
+        assert_eq!(since("2024-01-01"), "2024-01-01");
+        assert_eq!(since("w"), "w");
+    }
+}
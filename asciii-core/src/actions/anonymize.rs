@@ -0,0 +1,302 @@
+//! Project files without personal data, for bug reports and demos, see `asciii export --anonymize`.
+//!
+//! Every word of the names of the client, the manager and the caterers is replaced by a made up one,
+//! wherever it is in the file: the fields themselves, the event name, description and location and the comments.
+//! Email and address of the client are replaced as a whole. The same name always becomes the same fake
+//! in every project and every run, so repeat clients and staff stay recognizable, unless two names of one project
+//! would end up with the same fake; then one of them gets another one, nobody's hours are merged.
+//! Products and hours are untouched and so are all sums.
+
+use std::path::{Path, PathBuf};
+
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+
+use crate::error::AsciiiError;
+use crate::project::Project;
+use crate::util::yaml;
+use crate::Yaml;
+
+pub(crate) const FIRST_NAMES: &[&str] = &[
+    "Anna", "Ben", "Clara", "David", "Emma", "Felix", "Greta", "Hannes", "Ida", "Jonas", "Karla", "Lukas",
+    "Marie", "Noah", "Olga", "Paul", "Ronja", "Simon", "Tilda", "Yusuf",
+];
+
+const LAST_NAMES: &[&str] = &[
+    "Albers", "Brandt", "Conrad", "Dietrich", "Ebert", "Fischer", "Gerber", "Hahn", "Jung", "Kraus", "Lang",
+    "Mohr", "Nagel", "Otto", "Pohl", "Roth", "Sauer", "Thiel", "Vogel", "Winter",
+];
+
+const STREETS: &[&str] = &[
+    "Ahornweg", "Bahnhofstraße", "Gartenstraße", "Hauptstraße", "Lindenallee", "Mühlgasse", "Schulstraße", "Waldweg",
+];
+
+const CITIES: &[&str] = &["01067 Dresden", "04109 Leipzig", "09111 Chemnitz", "02826 Görlitz", "08056 Zwickau"];
+
+/// A field and how its value is faked.
+type Fake = (&'static str, fn(&str) -> String);
+
+/// FNV-1a, unlike `DefaultHasher` it is the same on every machine and with every compiler.
+fn hash(salt: &str, original: &str) -> u64 {
+    salt.bytes().chain(original.trim().bytes())
+        .fold(0xcbf2_9ce4_8422_2325, |hash, byte| (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3))
+}
+
+fn pick(list: &[&'static str], hash: u64) -> &'static str {
+    list[(hash % list.len() as u64) as usize]
+}
+
+pub fn fake_first_name(original: &str) -> &'static str {
+    pick(FIRST_NAMES, hash("first", original))
+}
+
+pub fn fake_last_name(original: &str) -> &'static str {
+    pick(LAST_NAMES, hash("last", original))
+}
+
+/// `Vorname Nachname`
+pub fn fake_full_name(original: &str) -> String {
+    let hash = hash("full", original);
+    format!("{} {}", pick(FIRST_NAMES, hash), pick(LAST_NAMES, hash / FIRST_NAMES.len() as u64))
+}
+
+/// Somewhere at `example.org`, which is never delivered to.
+pub fn fake_email(original: &str) -> String {
+    let hash = hash("email", original);
+    format!("{}.{}@example.org",
+            pick(FIRST_NAMES, hash).to_lowercase(),
+            pick(LAST_NAMES, hash / FIRST_NAMES.len() as u64).to_lowercase())
+}
+
+/// An address with as many lines as `original`, ending in street and city.
+pub fn fake_address(original: &str) -> String {
+    let lines = original.trim().lines().count().max(1);
+    let hash = hash("address", original);
+    let street = format!("{} {}", pick(STREETS, hash), hash % 97 + 1);
+    let city = pick(CITIES, hash / STREETS.len() as u64).to_owned();
+    let mut address = (2..lines).map(|_| fake_full_name(original)).collect::<Vec<_>>();
+    match lines {
+        1 => address.push(street),
+        _ => address.extend([street, city]),
+    }
+    address.join("\n")
+}
+
+lazy_static! {
+    static ref WORD: Regex = Regex::new(r"\p{Alphabetic}+").unwrap();
+    static ref SLUG_WORD: Regex = Regex::new(r"[A-Za-z]+").unwrap();
+}
+
+/// How a word looks in the name of a project directory.
+fn slug(word: &str) -> String {
+    deunicode::deunicode(word).to_lowercase().replace(' ', "-")
+}
+
+/// Every word of the names of the people in one project and the fake it becomes.
+///
+/// No two words get the same fake.
+#[derive(Debug, Default)]
+pub struct Fakes {
+    words: Vec<(String, String)>,
+}
+
+impl Fakes {
+    /// The people of `project`, see the module documentation.
+    pub fn of(project: &Project) -> Result<Fakes, AsciiiError> {
+        Ok(Fakes::from_yaml(&yaml::parse(project.file_content())?))
+    }
+
+    fn from_yaml(parsed: &Yaml) -> Fakes {
+        let mut fakes = Fakes::default();
+        if let Some(name) = yaml::get_str(parsed, "client/first_name") {
+            fakes.add(name, fake_first_name);
+        }
+        if let Some(name) = yaml::get_str(parsed, "client/last_name") {
+            fakes.add(name, fake_last_name);
+        }
+        if let Some(manager) = yaml::get_str(parsed, "manager") {
+            let manager = manager.trim();
+            let (first, last) = manager.split_once(char::is_whitespace).unwrap_or((manager, ""));
+            fakes.add(first, fake_first_name);
+            fakes.add(last, fake_last_name);
+        }
+        // unknown caterers keep their placeholder
+        let caterers = yaml::get_hash(parsed, "hours/caterers").into_iter().flat_map(|hash| hash.keys()).filter_map(Yaml::as_str);
+        for caterer in caterers {
+            fakes.add(caterer, fake_first_name);
+        }
+        fakes
+    }
+
+    /// Gives every word of `name` a fake, lower case words like "von" stay.
+    fn add(&mut self, name: &str, fake: fn(&str) -> &'static str) {
+        for word in WORD.find_iter(name).map(|word| word.as_str()) {
+            if !word.starts_with(char::is_uppercase) || self.fake(word).is_some() {
+                continue;
+            }
+            let mut candidate = fake(word).to_owned();
+            let mut tries = 1;
+            while self.words.iter().any(|(_, taken)| *taken == candidate) {
+                candidate = match tries {
+                    1..=99 => fake(&format!("{} {}", word, tries)).to_owned(),
+                    // more names than fakes
+                    _ => format!("{} {}", fake(word), tries - 98),
+                };
+                tries += 1;
+            }
+            self.words.push((word.to_owned(), candidate));
+        }
+    }
+
+    fn fake(&self, word: &str) -> Option<&str> {
+        self.words.iter().find(|(original, _)| original == word).map(|(_, fake)| fake.as_str())
+    }
+
+    /// `text` with every word of a name replaced, everything else stays as it is.
+    pub fn text(&self, text: &str) -> String {
+        WORD.replace_all(text, |word: &Captures<'_>| self.fake(&word[0]).unwrap_or(&word[0]).to_owned()).into_owned()
+    }
+
+    /// `path` with the names replaced in directory and file names, which are slugged like `party-zahl`.
+    pub fn path(&self, path: &Path) -> PathBuf {
+        path.components()
+            .map(|component| {
+                let component = component.as_os_str().to_string_lossy();
+                SLUG_WORD.replace_all(&component, |word: &Captures<'_>| {
+                    self.words.iter()
+                        .find(|(original, _)| slug(original) == word[0].to_lowercase())
+                        .map(|(_, fake)| match word[0].chars().all(char::is_lowercase) {
+                            true => slug(fake),
+                            false => deunicode::deunicode(fake),
+                        })
+                        .unwrap_or_else(|| word[0].to_owned())
+                }).into_owned()
+            })
+            .collect()
+    }
+}
+
+/// The content of the file of `project` with fake people, nothing is written.
+///
+/// Fields the project doesn't have stay missing.
+pub fn anonymize(project: &Project) -> Result<String, AsciiiError> {
+    let parsed = yaml::parse(project.file_content())?;
+    let content = Fakes::from_yaml(&parsed).text(project.file_content());
+
+    // even without the names these say too much
+    let fakes: [Fake; 2] = [
+        ("client/email", fake_email),
+        ("client/address", fake_address),
+    ];
+    let fields = fakes.iter()
+                      .filter_map(|(path, fake)| yaml::get_str(&parsed, path)
+                                                      .filter(|value| !value.trim().is_empty())
+                                                      .map(|value| (*path, fake(value))))
+                      .collect::<Vec<_>>();
+    let fields = fields.iter().map(|(path, value)| (*path, value.as_str())).collect::<Vec<_>>();
+    Project::from_file_content(&content)?.content_with_fields(&fields)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project::spec::{HasEmployees, IsClient, Redeemable};
+
+    const PROJECT: &str = "---
+client:
+  title: Herr
+  first_name: Graf
+  last_name: Zahl # the count
+  email: graf@zahl.de
+  address: |
+    Graf Zahl
+    Sesamstraße 1
+    12345 Fernsehen
+
+event:
+  name: Zählparty bei Zahl
+  location: Schloss von Graf Zahl
+  dates:
+  - begin: 13.03.2024
+  description: |
+    Hendrik holt den Schlüssel bei Graf Zahl ab. # Ute kommt später
+
+products:
+  \"Kaffee\":
+    amount: 4
+    price: 2.5
+
+hours:
+  salary: 8.0
+  caterers:
+    Klaus: 10
+    \"unknown\" : 2
+    Ute: 3
+    Friedrich: 4
+    Anna Maria: 1
+    Bert: 1
+    Carl: 1
+    Dora: 1
+    Egon: 1
+    Fritz: 1
+    Gustav: 1
+    Heike: 1
+    Inge: 1
+    Jana: 1
+    Kurt: 1
+    Lena: 1
+    Moritz: 1
+    Nina: 1
+    Oskar: 1
+    Petra: 1
+    Rudi: 1
+    Sabine: 1
+
+manager: Hendrik Sollich
+tax: 0.19
+";
+
+    const ORIGINALS: &[&str] = &[
+        "Graf", "Zahl", "graf@zahl.de", "Sesamstraße", "Hendrik", "Sollich", "Klaus", "Ute", "Friedrich", "Maria", "Sabine",
+    ];
+
+    #[test]
+    fn people_are_replaced_sums_stay() {
+        let project = Project::from_file_content(PROJECT).unwrap();
+        let content = anonymize(&project).unwrap();
+        let anonymized = Project::from_file_content(&content).unwrap();
+
+        for original in ORIGINALS {
+            assert!(!content.contains(original), "{} is still in\n{}", original, content);
+        }
+        assert!(content.contains("# the count"));
+        assert!(content.contains("\"unknown\" : 2"));
+        assert!(content.contains("Zählparty bei "));
+        assert_eq!(anonymized.client().title().ok(), project.client().title().ok());
+        assert_eq!(anonymized.client().email().unwrap().rsplit_once('@').unwrap().1, "example.org");
+        assert_eq!(anonymized.client().address().unwrap().lines().count(), 3);
+        assert_eq!(anonymized.sum_sold().unwrap(), project.sum_sold().unwrap());
+
+        // more caterers than fake names, still nobody's hours are merged
+        let (employees, fake_employees) = (project.hours().employees().unwrap(), anonymized.hours().employees().unwrap());
+        assert_eq!(fake_employees.len(), employees.len());
+        assert_eq!(anonymized.hours().total_time(), project.hours().total_time());
+        assert_eq!(anonymized.hours().net_wages(), project.hours().net_wages());
+
+        // the same in every run
+        assert_eq!(anonymize(&project).unwrap(), content);
+        assert_eq!(fake_full_name("Graf Zahl"), fake_full_name(" Graf Zahl"));
+    }
+
+    #[test]
+    fn paths_are_replaced() {
+        let project = Project::from_file_content(PROJECT).unwrap();
+        let fakes = Fakes::of(&project).unwrap();
+        let path = fakes.path(Path::new("archive/2024/R001_zaehlparty-bei-zahl/Zaehlparty bei Zahl.yml"));
+        let path = path.to_string_lossy();
+        assert!(path.starts_with("archive/2024/R001_zaehlparty-bei-"), "{}", path);
+        assert!(path.ends_with(".yml"), "{}", path);
+        assert!(!path.split(|c: char| !c.is_alphanumeric()).any(|word| word.eq_ignore_ascii_case("zahl")), "{}", path);
+        assert_eq!(fakes.text("Graf Zahl"), format!("{} {}", fake_first_name("Graf"), fake_last_name("Zahl")));
+    }
+}
@@ -0,0 +1,170 @@
+//! Letterhead of the generated documents, see `asciii extras branding`.
+//!
+//! It is described in `extras/branding.yml` of the storage, files are relative to `extras/`:
+//!
+//! ```yaml
+//! logo: branding/logo.png
+//! signature: branding/signature.png
+//! footer: "Verein e.V. · Musterstraße 1 · 01234 Stadt"
+//! bank:
+//!   name: Sparkasse
+//!   holder: Verein e.V.
+//!   iban: DE02 1203 0000 0000 2020 51
+//!   bic: BYLADEM1001
+//! ```
+//!
+//! Templates find it as `branding`, the backends turn the files into paths they understand.
+//! Everything is optional, without the file there is no branding at all.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::{ActionError, AsciiiError};
+use crate::storage::{Storable, Storage};
+use crate::util;
+use crate::util::yaml::{self, Yaml};
+
+pub const BRANDING_FILE: &str = "branding.yml";
+
+/// Where `import_asset()` puts files, within `extras/`.
+pub const ASSETS_DIR: &str = "branding";
+
+#[cfg_attr(feature = "serialization", derive(Serialize))]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BankDetails {
+    pub name: Option<String>,
+    pub holder: Option<String>,
+    pub iban: Option<String>,
+    pub bic: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Branding {
+    /// absolute, or relative to `extras/`
+    pub logo: Option<PathBuf>,
+    /// absolute, or relative to `extras/`
+    pub signature: Option<PathBuf>,
+    pub footer: Option<String>,
+    pub bank: Option<BankDetails>,
+}
+
+fn text(entry: &Yaml, key: &str) -> Option<String> {
+    yaml::get_to_string(entry, key).map(|value| value.trim().to_owned()).filter(|value| !value.is_empty())
+}
+
+impl Branding {
+    /// Reads the content of a `branding.yml`.
+    pub fn parse(content: &str) -> Result<Branding, AsciiiError> {
+        let document = yaml::parse(content)?;
+        let document = match document {
+            Yaml::Hash(_) => document,
+            // nothing but comments
+            _ if content.lines().all(|line| line.trim().is_empty() || line.trim_start().starts_with('#')) => return Ok(Branding::default()),
+            _ => return Err(ActionError::Failed(lformat!("{} must be a map of logo, signature, footer and bank", BRANDING_FILE)).into())
+        };
+        let bank = match &document["bank"] {
+            bank @ Yaml::Hash(_) => Some(BankDetails {
+                name: text(bank, "name"),
+                holder: text(bank, "holder"),
+                iban: text(bank, "iban"),
+                bic: text(bank, "bic"),
+            }),
+            _ => None,
+        };
+        Ok(Branding {
+            logo: text(&document, "logo").map(PathBuf::from),
+            signature: text(&document, "signature").map(PathBuf::from),
+            footer: text(&document, "footer"),
+            bank: bank.filter(|bank| *bank != BankDetails::default()),
+        })
+    }
+
+    /// Content of a `branding.yml` describing this.
+    pub fn to_yaml(&self) -> String {
+        fn line(content: &mut String, key: &str, value: Option<String>) {
+            if let Some(value) = value {
+                content.push_str(&format!("{}: {:?}\n", key, value));
+            }
+        }
+        let mut content = String::from("# letterhead of the generated documents, see `asciii extras branding`\n");
+        line(&mut content, "logo", self.logo.as_ref().map(|path| path.display().to_string()));
+        line(&mut content, "signature", self.signature.as_ref().map(|path| path.display().to_string()));
+        line(&mut content, "footer", self.footer.clone());
+        if let Some(bank) = &self.bank {
+            content.push_str("bank:\n");
+            line(&mut content, "  name", bank.name.clone());
+            line(&mut content, "  holder", bank.holder.clone());
+            line(&mut content, "  iban", bank.iban.clone());
+            line(&mut content, "  bic", bank.bic.clone());
+        }
+        content
+    }
+
+    /// The files, by what they are, relative to `extras`.
+    pub fn assets(&self, extras: &Path) -> Vec<(&'static str, PathBuf)> {
+        [("logo", &self.logo), ("signature", &self.signature)].iter()
+            .filter_map(|(what, path)| Some((*what, extras.join(path.as_ref()?))))
+            .collect()
+    }
+
+    /// The files that are not there.
+    pub fn missing_assets(&self, extras: &Path) -> Vec<(&'static str, PathBuf)> {
+        self.assets(extras).into_iter().filter(|(_, path)| !path.is_file()).collect()
+    }
+}
+
+/// The branding of the storage, `None` if there is no `branding.yml`.
+pub fn configured<L: Storable>(storage: &Storage<L>) -> Result<Option<Branding>, AsciiiError> {
+    let path = storage.get_extra_file(BRANDING_FILE)?;
+    if !path.exists() {
+        return Ok(None);
+    }
+    Branding::parse(&fs::read_to_string(&path)?).map(Some)
+}
+
+/// Writes `extras/branding.yml`, creating `extras/` if necessary.
+pub fn save<L: Storable>(storage: &Storage<L>, branding: &Branding) -> Result<PathBuf, AsciiiError> {
+    fs::create_dir_all(storage.extras_dir())?;
+    let path = storage.get_extra_file(BRANDING_FILE)?;
+    util::atomic_write(&path, branding.to_yaml())?;
+    Ok(path)
+}
+
+/// Copies `file` to `extras/branding/` as `<what>.<extension>`, returns where it is relative to `extras/`.
+pub fn import_asset<L: Storable>(storage: &Storage<L>, what: &str, file: &Path) -> Result<PathBuf, AsciiiError> {
+    if !file.is_file() {
+        return Err(ActionError::Failed(lformat!("{} does not exist", file.display())).into());
+    }
+    let name = match file.extension() {
+        Some(extension) => Path::new(what).with_extension(extension),
+        None => PathBuf::from(what),
+    };
+    let relative = Path::new(ASSETS_DIR).join(name);
+    let target = storage.extras_dir().join(&relative);
+    fs::create_dir_all(storage.extras_dir().join(ASSETS_DIR))?;
+    if target != file {
+        fs::copy(file, &target)?;
+    }
+    Ok(relative)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn branding_round_trip() {
+        let branding = Branding::parse("logo: branding/logo.png\nfooter: Verein e.V.\nbank:\n  iban: DE02 1203\n  bic: BYLADEM1001\n").unwrap();
+        assert_eq!(branding.logo, Some(PathBuf::from("branding/logo.png")));
+        assert_eq!(branding.signature, None);
+        assert_eq!(branding.bank.as_ref().and_then(|bank| bank.iban.as_deref()), Some("DE02 1203"));
+        assert_eq!(Branding::parse(&branding.to_yaml()).unwrap(), branding);
+
+        let extras = Path::new("/nonexistent/extras");
+        assert_eq!(branding.missing_assets(extras), vec![("logo", extras.join("branding/logo.png"))]);
+
+        assert_eq!(Branding::parse("# not yet\n").unwrap(), Branding::default());
+        assert_eq!(Branding::parse("bank: {}\n").unwrap().bank, None);
+        assert!(Branding::parse("- logo.png\n").is_err());
+    }
+}
@@ -0,0 +1,112 @@
+//! Closing a year for the books, see `asciii close-year`.
+//!
+//! A year can be closed once every project of it is payed or canceled,
+//! those of them still in the working directory are archived.
+//! The income and VAT reports of the year are written to `extras/closing/<year>/`,
+//! the year is recorded as closed, committed and tagged `closing-<year>` if there is a repository.
+//! From then on its archive is read-only, see `storage::closing`.
+
+use std::fs;
+use std::path::PathBuf;
+
+use crate::error::{ActionError, AsciiiError};
+use crate::project::spec::{IsProject, Redeemable};
+use crate::project::Project;
+use crate::storage::archive_index;
+use crate::storage::closing::ClosingError;
+use crate::storage::{Storable, Storage, StorageDir, StorageError};
+use crate::util;
+
+use super::report::{IncomeReport, VatReport};
+
+/// What `close_year()` did.
+#[derive(Debug, Clone)]
+pub struct Closing {
+    pub year: i32,
+    /// projects that were moved into the archive
+    pub archived: Vec<PathBuf>,
+    /// the income and the VAT report
+    pub reports: Vec<PathBuf>,
+    /// git tag, if there is a repository
+    pub tag: Option<String>,
+}
+
+/// Name of the git tag of closing `year`.
+pub fn tag_name(year: i32) -> String {
+    format!("closing-{}", year)
+}
+
+/// Projects that are neither payed nor canceled.
+pub fn unsettled(projects: &[Project]) -> Vec<&Project> {
+    projects.iter().filter(|project| !project.is_payed() && !project.canceled()).collect()
+}
+
+/// Closes `year`, see the module documentation.
+///
+/// Nothing is changed if a project of the year is neither payed nor canceled.
+pub fn close_year(storage: &Storage<Project>, year: i32) -> Result<Closing, AsciiiError> {
+    let closed_years = storage.closed_years();
+    if let Some(closed) = closed_years.closed()?.into_iter().find(|closed| closed.year == year) {
+        return Err(ClosingError::AlreadyClosed(year, closed.date).into());
+    }
+
+    let mut stragglers = storage.open_projects(StorageDir::Working)?;
+    stragglers.retain(|project| project.year() == Some(year));
+    let archive = if storage.list_years()?.contains(&year) {
+        storage.open_projects(StorageDir::Archive(year))?.projects
+    } else {
+        Vec::new()
+    };
+
+    let unsettled = unsettled(&archive).into_iter().chain(unsettled(&stragglers)).collect::<Vec<_>>();
+    if !unsettled.is_empty() {
+        let names = unsettled.iter().map(|project| project.short_desc()).collect::<Vec<_>>();
+        return Err(ActionError::ValidationFailed(lformat!("{} projects of {} are neither payed nor canceled: {}",
+                                                          names.len(), year, names.join(", "))).into());
+    }
+
+    let archived = if stragglers.is_empty() {
+        Vec::new()
+    } else {
+        storage.archive_projects(stragglers.iter().map(|project| (project, year)))?
+    };
+
+    // invoices of a year may be archived in the next one
+    let all = storage.open_projects(StorageDir::All)?;
+    let dir = storage.extras_dir().join("closing").join(year.to_string());
+    fs::create_dir_all(&dir)?;
+    let income = dir.join("income.csv");
+    let vat = dir.join("vat.csv");
+    util::atomic_write(&income, IncomeReport::new(&all, year)?.to_csv()?)?;
+    util::atomic_write(&vat, VatReport::new(&all, year, None)?.to_csv()?)?;
+    let reports = vec![income, vat];
+
+    let tag = storage.repository().map(|_| tag_name(year));
+    let closed_before = fs::read_to_string(closed_years.file()).ok();
+    closed_years.close(year, tag.as_deref())?;
+
+    let message = lformat!("close {}", year);
+    let mut changes = vec![storage.journal().write(closed_years.file(), closed_before.as_deref())];
+    changes.extend(reports.iter().map(|report| storage.journal().write(report, None)));
+    storage.record(&message, changes);
+
+    if let Some(repo) = storage.repository() {
+        let mut paths = archived.clone();
+        paths.extend(reports.iter().cloned());
+        paths.push(closed_years.file().to_owned());
+        let index = archive_index::path(&storage.archive_dir().join(year.to_string()));
+        if !archived.is_empty() && index.exists() {
+            paths.push(index);
+        }
+        if !(repo.add(&paths).success() && repo.commit_paths(&paths, &message).success()) {
+            return Err(StorageError::GitProcessFailed.into());
+        }
+        if let Some(ref tag) = tag {
+            if !repo.tag(tag, &message).success() {
+                return Err(StorageError::GitProcessFailed.into());
+            }
+        }
+    }
+
+    Ok(Closing { year, archived, reports, tag })
+}
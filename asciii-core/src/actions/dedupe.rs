@@ -0,0 +1,250 @@
+//! Clients that are written differently in different projects, see `asciii client dedupe`.
+//!
+//! Every distinct client name and email of the projects is a `Spelling`.
+//! Two of them are likely the same client if their emails match, or, unless they have different emails,
+//! if their names only differ in case, umlauts ("Müller" and "Mueller"), punctuation or a typo.
+//! The canonical spelling of a group is the one `extras/clients.yml` knows, otherwise the most used one,
+//! `rewrite()` sets the client of all other projects to it.
+
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use crate::error::{ActionError, AsciiiError};
+use crate::project::spec::IsClient;
+use crate::project::Project;
+use crate::storage::clients::ClientDatabase;
+use crate::storage::{Storable, Storage, StorageError};
+use crate::util;
+
+/// One way a client is written in the projects.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Spelling {
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub email: Option<String>,
+    /// files of the projects written like this
+    pub projects: Vec<PathBuf>,
+}
+
+impl Spelling {
+    /// Full name, or only the last name, e.g. of a company.
+    pub fn name(&self) -> String {
+        match (&self.first_name, &self.last_name) {
+            (Some(first), Some(last)) => format!("{} {}", first, last),
+            (None, Some(last)) => last.clone(),
+            (Some(first), None) => first.clone(),
+            (None, None) => String::new(),
+        }
+    }
+
+    fn of(project: &Project) -> Option<Spelling> {
+        let text = |field: Option<&str>| field.map(str::trim).filter(|text| !text.is_empty()).map(ToOwned::to_owned);
+        let client = project.client();
+        let spelling = Spelling {
+            first_name: text(client.first_name().ok()),
+            last_name: text(client.last_name().ok()),
+            email: text(client.email().ok()),
+            projects: vec![project.file()],
+        };
+        if spelling.name().is_empty() { None } else { Some(spelling) }
+    }
+
+    fn is_written_like(&self, other: &Spelling) -> bool {
+        self.first_name == other.first_name && self.last_name == other.last_name && self.email == other.email
+    }
+
+    /// Whether this and `other` are likely the same client.
+    pub fn is_similar(&self, other: &Spelling) -> bool {
+        match (self.email.as_deref(), other.email.as_deref()) {
+            (Some(a), Some(b)) if a.eq_ignore_ascii_case(b) => return true,
+            // an email decides, like in `extras/clients.yml`
+            (Some(_), Some(_)) => return false,
+            _ => {}
+        }
+        let (a, b) = (normalize(&self.name()), normalize(&other.name()));
+        let length = a.chars().count().min(b.chars().count());
+        let tolerated = if length < 5 { 0 } else { (length / 8).max(1) };
+        util::edit_distance(&a, &b) <= tolerated
+    }
+}
+
+/// `name` in lower case, with umlauts spelled out and without punctuation.
+pub fn normalize(name: &str) -> String {
+    let mut normalized = String::new();
+    for c in name.to_lowercase().chars() {
+        match c {
+            'ä' => normalized.push_str("ae"),
+            'ö' => normalized.push_str("oe"),
+            'ü' => normalized.push_str("ue"),
+            'ß' => normalized.push_str("ss"),
+            'á' | 'à' | 'â' => normalized.push('a'),
+            'é' | 'è' | 'ê' => normalized.push('e'),
+            'ó' | 'ò' | 'ô' => normalized.push('o'),
+            c if c.is_alphanumeric() => normalized.push(c),
+            _ => normalized.push(' '),
+        }
+    }
+    normalized.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Spellings that are likely the same client.
+#[derive(Debug, Clone)]
+pub struct Duplicates {
+    /// what the others should become
+    pub canonical: Spelling,
+    pub others: Vec<Spelling>,
+}
+
+/// The distinct spellings of the clients of `projects`.
+pub fn spellings(projects: &[Project]) -> Vec<Spelling> {
+    let mut spellings: Vec<Spelling> = Vec::new();
+    for spelling in projects.iter().filter_map(Spelling::of) {
+        match spellings.iter_mut().find(|known| known.is_written_like(&spelling)) {
+            Some(known) => known.projects.extend(spelling.projects),
+            None => spellings.push(spelling),
+        }
+    }
+    spellings
+}
+
+/// Groups of spellings in `projects` that are likely the same client, see the module documentation.
+pub fn find(projects: &[Project], database: &ClientDatabase) -> Vec<Duplicates> {
+    let spellings = spellings(projects);
+
+    // every spelling starts in a group of its own, groups of similar ones are merged unless their emails differ
+    let mut group = (0..spellings.len()).collect::<Vec<_>>();
+    for i in 0..spellings.len() {
+        for j in (i + 1)..spellings.len() {
+            let (from, to) = (group[j], group[i]);
+            if from == to || !spellings[i].is_similar(&spellings[j]) {
+                continue;
+            }
+            let emails = |g: usize| spellings.iter().zip(&group).filter(move |(_, h)| **h == g).filter_map(|(spelling, _)| spelling.email.as_deref());
+            if emails(from).any(|a| emails(to).any(|b| !a.eq_ignore_ascii_case(b))) {
+                continue;
+            }
+            group.iter_mut().filter(|g| **g == from).for_each(|g| *g = to);
+        }
+    }
+
+    let mut groups = BTreeMap::<usize, Vec<Spelling>>::new();
+    for (spelling, g) in spellings.into_iter().zip(group) {
+        groups.entry(g).or_default().push(spelling);
+    }
+    groups.into_values()
+          .filter(|spellings| spellings.len() > 1)
+          .map(|mut spellings| {
+              let known = |spelling: &Spelling| database.find(&[&spelling.name()], spelling.email.as_deref()).is_some();
+              // known first, then the most used, then the one with an email, then the longest name
+              spellings.sort_by_key(|spelling| (!known(spelling),
+                                                Reverse(spelling.projects.len()),
+                                                spelling.email.is_none(),
+                                                Reverse(spelling.name().chars().count())));
+              let canonical = spellings.remove(0);
+              Duplicates { canonical, others: spellings }
+          })
+          .collect()
+}
+
+/// Sets the client of the projects of `duplicates.others` to `duplicates.canonical`, as one commit.
+///
+/// Title and address stay as they are, an email is only set if the canonical spelling has one.
+/// Projects archived in closed years are skipped.
+/// Returns the changed project files.
+pub fn rewrite(storage: &Storage<Project>, duplicates: &Duplicates) -> Result<Vec<PathBuf>, AsciiiError> {
+    let canonical = &duplicates.canonical;
+    let mut fields = Vec::new();
+    if let Some(ref first_name) = canonical.first_name {
+        fields.push(("client/first_name", first_name.as_str()));
+    }
+    if let Some(ref last_name) = canonical.last_name {
+        fields.push(("client/last_name", last_name.as_str()));
+    }
+    if let Some(ref email) = canonical.email {
+        fields.push(("client/email", email.as_str()));
+    }
+
+    let files = duplicates.others.iter().flat_map(|other| other.projects.iter()).collect::<Vec<_>>();
+    let mut projects = Vec::new();
+    for file in &files {
+        let project = Project::open_file(file)?;
+        match storage.ensure_open(&project) {
+            Ok(()) => projects.push(project),
+            Err(e) => log::warn!("{}: {}", file.display(), e),
+        }
+    }
+    let locks = projects.iter().map(|project| storage.lock(project)).collect::<Result<Vec<_>, _>>()?;
+
+    // nothing is written unless every project can be changed
+    let contents = projects.iter()
+                           .map(|project| project.content_with_fields(&fields))
+                           .collect::<Result<Vec<_>, _>>()?;
+    let mut changes = Vec::new();
+    let mut changed = Vec::new();
+    for (project, content) in projects.iter().zip(contents) {
+        if content == project.file_content() {
+            continue;
+        }
+        util::atomic_write(project.file(), &content)?;
+        changes.push(storage.journal().write(&project.file(), Some(project.file_content())));
+        changed.push(project.file());
+    }
+    drop(locks);
+    if changed.is_empty() {
+        return Err(ActionError::Failed(lformat!("nothing to change for {}", canonical.name())).into());
+    }
+
+    let message = lformat!("write the client of {} projects as {}", changed.len(), canonical.name());
+    storage.record(&message, changes);
+    for file in &changed {
+        storage.field_changed(file, "client");
+    }
+    if let Some(repo) = storage.repository() {
+        if !(repo.add(&changed).success() && repo.commit_paths(&changed, &message).success()) {
+            return Err(StorageError::GitProcessFailed.into());
+        }
+    }
+    Ok(changed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project(file: &str, client: &str) -> Project {
+        let mut project = Project::from_file_content(&format!("event:\n  name: Fest\nclient:\n{}", client)).unwrap();
+        project.set_file(&PathBuf::from(file));
+        project
+    }
+
+    #[test]
+    fn likely_duplicates() {
+        assert_eq!(normalize(" Müller  GmbH & Co."), "mueller gmbh co");
+
+        let projects = vec![project("/a.yml", "  last_name: Müller GmbH\n"),
+                            project("/b.yml", "  last_name: Mueller GmbH\n"),
+                            project("/c.yml", "  last_name: Mueller GmbH\n"),
+                            project("/d.yml", "  first_name: Erika\n  last_name: Mustermann\n  email: erika@example.com\n"),
+                            project("/e.yml", "  first_name: Erika\n  last_name: Musterman\n"),
+                            project("/f.yml", "  first_name: E.\n  last_name: Mustermann\n  email: ERIKA@example.com\n"),
+                            project("/g.yml", "  first_name: Erika\n  last_name: Mustermann\n  email: other@example.com\n"),
+                            project("/h.yml", "  last_name: Bert\n"),
+                            project("/i.yml", "  last_name: Bart\n")];
+        assert_eq!(spellings(&projects).len(), 8);
+
+        let duplicates = find(&projects, &ClientDatabase::default());
+        assert_eq!(duplicates.len(), 2);
+        assert_eq!(duplicates[0].canonical.name(), "Mueller GmbH");
+        assert_eq!(duplicates[0].canonical.projects.len(), 2);
+        assert_eq!(duplicates[0].others[0].name(), "Müller GmbH");
+
+        // an email decides, short names must match exactly
+        assert_eq!(duplicates[1].canonical.name(), "Erika Mustermann");
+        assert_eq!(duplicates[1].others.len(), 2);
+        assert!(duplicates[1].others.iter().all(|other| other.email.as_deref() != Some("other@example.com")));
+
+        let database = ClientDatabase::parse("- name: Müller GmbH\n").unwrap();
+        assert_eq!(find(&projects, &database)[0].canonical.name(), "Müller GmbH");
+    }
+}
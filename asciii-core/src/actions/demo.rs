@@ -0,0 +1,282 @@
+//! A storage full of made up projects to try asciii on, see `asciii init --demo`.
+//!
+//! Three years of projects: the older ones payed and archived, some canceled, some invoices still open,
+//! and the coming events offered, accepted or not even offered yet. A few clients come back.
+//! The same day always gives the same projects, names and addresses come from `anonymize`.
+
+use std::collections::HashSet;
+
+use chrono::prelude::*;
+use chrono::Duration;
+
+use crate::actions::anonymize;
+use crate::error::AsciiiError;
+use crate::project::Project;
+use crate::storage::{Storable, Storage};
+
+/// Projects per year.
+pub const PER_YEAR: u32 = 12;
+
+const EVENTS: &[(&str, &str)] = &[
+    ("Sommerfest", "Grillen im Innenhof"),
+    ("Hochzeit", "Empfang nach der Trauung"),
+    ("Konferenz", "Kaffeepausen für zwei Tage"),
+    ("Jubiläum", "Sektempfang und Buffet"),
+    ("Weihnachtsfeier", "Glühwein und Plätzchen"),
+    ("Workshop", "Getränke und Mittagessen"),
+    ("Abschlussfeier", "Feier nach der Zeugnisübergabe"),
+    ("Tag der offenen Tür", "Stand im Foyer"),
+];
+
+const LOCATIONS: &[&str] = &["Foyer", "Hörsaal 1", "Innenhof", "Mensa", "Festsaal"];
+
+const CATERERS: &[&str] = &["Klaus", "Maxime", "Sebastian", "Johanna", "Ute", "Friedrich"];
+
+const CATALOGE: &str = "cataloge:
+  product: &kaffee       { name: Kaffee          , price: 2.5  , unit: 1l  }
+  product: &tee          { name: Tee             , price: 1.75 , unit: 1l  }
+  product: &wasser       { name: Mineralwasser   , price: 0.61 , unit: 1l  }
+  product: &apfelsaft    { name: Apfelsaft       , price: 1.64 , unit: 1l  }
+  product: &broetchen    { name: halbe Brötchen  , price: 1.16 , unit: stk }
+  product: &kuchen       { name: Kuchen          , price: 0.94 , unit: stk }
+  product: &suppe        { name: Suppe           , price: 3.10 , unit: Portion }
+";
+
+const PRODUCTS: &[&str] = &["kaffee", "tee", "wasser", "apfelsaft", "broetchen", "kuchen", "suppe"];
+
+/// How far a project got, in order, also used by `testing::TempStorage::advance()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Stage {
+    Draft,
+    Offered,
+    Accepted,
+    Invoiced,
+    Payed,
+    Canceled,
+}
+
+/// One generated project, not written yet.
+#[derive(Debug, Clone)]
+pub struct DemoProject {
+    pub name: String,
+    pub stage: Stage,
+    pub event: Date<Utc>,
+    /// year and number
+    pub invoice: Option<(i32, u32)>,
+    /// year of the archive it belongs into
+    pub archive: Option<i32>,
+    pub content: String,
+}
+
+/// Always rolls the same numbers, so the demo is reproducible.
+struct Dice(u64);
+
+impl Dice {
+    fn roll(&mut self, sides: u32) -> u32 {
+        // xorshift64
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 % u64::from(sides.max(1))) as u32
+    }
+
+    fn pick<'a, T>(&mut self, list: &'a [T]) -> &'a T {
+        &list[self.roll(list.len() as u32) as usize]
+    }
+}
+
+struct Client {
+    title: &'static str,
+    first_name: &'static str,
+    last_name: &'static str,
+    email: String,
+    address: String,
+}
+
+impl Client {
+    fn new(index: u32) -> Client {
+        let seed = format!("demo client {}", index);
+        let first = index as usize % anonymize::FIRST_NAMES.len();
+        let first_name = anonymize::FIRST_NAMES[first];
+        let last_name = anonymize::fake_last_name(&seed);
+        Client {
+            title: match first % 2 { 0 => "Frau", _ => "Herr" },
+            first_name,
+            last_name,
+            email: format!("{}.{}@example.org", first_name.to_lowercase(), last_name.to_lowercase()),
+            address: format!("{} {}\n{}", first_name, last_name, anonymize::fake_address(&format!("{}\n\n", seed))),
+        }
+    }
+}
+
+fn dmy(date: Date<Utc>) -> String {
+    date.format("%d.%m.%Y").to_string()
+}
+
+/// `PER_YEAR` projects for each of the two years before the one `today` is in and for that year.
+pub fn projects(today: Date<Utc>) -> Vec<DemoProject> {
+    let mut dice = Dice(0x5eed_a5c1_1100_0000 ^ u64::from(today.num_days_from_ce() as u32));
+    let clients = (0..10).map(Client::new).collect::<Vec<_>>();
+    let mut projects = Vec::new();
+    let mut names = HashSet::new();
+
+    for year in today.year() - 2..=today.year() {
+        let mut invoice_number = 0;
+        for index in 0..PER_YEAR {
+            let month = 1 + index * 12 / PER_YEAR;
+            let event = Utc.ymd(year, month, 1 + dice.roll(28));
+            let (kind, description) = *dice.pick(EVENTS);
+            let client = dice.pick(&clients);
+            let mut name = format!("{} {}", kind, client.last_name);
+            // the same kind of event for the same client again
+            if !names.insert(name.clone()) {
+                name = format!("{} {}", name, event.format("%Y-%m"));
+                names.insert(name.clone());
+            }
+
+            let offered = event - Duration::days(30 + i64::from(dice.roll(30)));
+            let invoiced = event + Duration::days(3 + i64::from(dice.roll(7)));
+            let payed = invoiced + Duration::days(5 + i64::from(dice.roll(35)));
+            let chance = dice.roll(10);
+            let stage = if chance == 0 {
+                Stage::Canceled
+            } else if event > today {
+                match chance {
+                    1..=3 => Stage::Draft,
+                    4..=6 => Stage::Offered,
+                    _ => Stage::Accepted,
+                }
+            } else if invoiced > today || chance == 1 && year == today.year() {
+                Stage::Accepted
+            } else if payed > today || chance == 2 {
+                Stage::Invoiced
+            } else {
+                Stage::Payed
+            };
+
+            let invoice = match stage {
+                Stage::Invoiced | Stage::Payed => {
+                    invoice_number += 1;
+                    Some((year, invoice_number))
+                }
+                _ => None,
+            };
+            let archive = match stage {
+                Stage::Payed | Stage::Canceled if year < today.year() => Some(year),
+                _ => None,
+            };
+
+            let mut content = format!("--- # asciii document  (version: {}, template: demo)\n\n", env!("CARGO_PKG_VERSION"));
+            content += &format!("client:\n  title: {}\n  first_name: {}\n  last_name: {}\n\n  email: {}\n  address: |\n",
+                                client.title, client.first_name, client.last_name, client.email);
+            for line in client.address.lines() {
+                content += &format!("    {}\n", line);
+            }
+            content += &format!("\nevent:\n  name: {}\n  location: {}\n  dates:\n  - begin: {}\n    times:\n    - begin: \"{}:00\"\n      end: \"{}:00\"\n\n  description: |\n    {}\n\n",
+                                name, dice.pick(LOCATIONS), dmy(event), 10 + dice.roll(6), 18 + dice.roll(5), description);
+
+            content += "offer:\n";
+            if stage != Stage::Draft {
+                content += &format!("  date: {}\n  appendix: 1\n  sent_date: {}\n", dmy(offered), dmy(offered + Duration::days(1)));
+            }
+            if !matches!(stage, Stage::Draft | Stage::Offered) {
+                content += &format!("  accepted_date: {}\n  accepted_via: email\n", dmy(offered + Duration::days(7)));
+            }
+            content += "\ninvoice:\n";
+            if let Some((_, number)) = invoice {
+                content += &format!("  number: {}\n  date: {}\n", number, dmy(invoiced));
+            }
+            if stage == Stage::Payed {
+                content += &format!("  payed_date: {}\n", dmy(payed));
+            }
+
+            content += "\n";
+            content += CATALOGE;
+            content += "\nproducts:\n";
+            let mut products = PRODUCTS.to_vec();
+            for _ in 0..2 + dice.roll(4) {
+                let product = products.remove(dice.roll(products.len() as u32) as usize);
+                let amount = 5 + dice.roll(40);
+                content += &format!("  *{}:\n    amount: {}\n", product, amount);
+                if matches!(stage, Stage::Invoiced | Stage::Payed) {
+                    content += &format!("    returned: {}\n", dice.roll(amount / 3 + 1));
+                }
+            }
+
+            content += "\nhours:\n  salary: 8.0\n  caterers:\n";
+            let mut caterers = CATERERS.to_vec();
+            for _ in 0..1 + dice.roll(3) {
+                let caterer = caterers.remove(dice.roll(caterers.len() as u32) as usize);
+                content += &format!("    {}: {}\n", caterer, 2 + dice.roll(8));
+            }
+            if stage == Stage::Payed {
+                content += &format!("  wages_date: {}\n", dmy(event + Duration::days(14)));
+            }
+
+            content += &format!("\nmanager: {}\n\ncanceled: {}\ntax: 0.19\n\nmeta:\n  template: demo\n  format: 3.0.0\ncreated: {}\n...\n",
+                                dice.pick(CATERERS), stage == Stage::Canceled, dmy(offered.min(today)));
+
+            projects.push(DemoProject { name, stage, event, invoice, archive, content });
+        }
+    }
+    projects
+}
+
+/// Writes `projects()` into `storage`, issues their invoice numbers and archives the finished ones.
+///
+/// Returns how many projects were created.
+pub fn populate(storage: &Storage<Project>, today: Date<Utc>) -> Result<usize, AsciiiError> {
+    let ledger = storage.ledger();
+    let demo = projects(today);
+    for demo in &demo {
+        let project = storage.create_project_from_content(&demo.name, &demo.content)?;
+        if let Some((year, number)) = demo.invoice {
+            ledger.issue(year, number, &project.short_desc())?;
+        }
+        if let Some(year) = demo.archive {
+            storage.archive_project(&project, year)?;
+        }
+    }
+    Ok(demo.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project::spec::{HasEmployees, Invoicable, IsProject, Redeemable};
+
+    #[test]
+    fn demo_projects() {
+        let today = Utc.ymd(2025, 6, 15);
+        let demo = projects(today);
+        assert_eq!(demo.len(), 3 * PER_YEAR as usize);
+
+        let stages = demo.iter().map(|demo| format!("{:?}", demo.stage)).collect::<HashSet<_>>();
+        for stage in ["Draft", "Offered", "Accepted", "Invoiced", "Payed", "Canceled"] {
+            assert!(stages.contains(stage), "no {} project", stage);
+        }
+
+        let names = demo.iter().map(|demo| demo.name.as_str()).collect::<HashSet<_>>();
+        assert_eq!(names.len(), demo.len());
+
+        let mut numbers = HashSet::new();
+        for demo in &demo {
+            let project = Project::from_file_content(&demo.content).unwrap();
+            assert_eq!(project.name().ok(), Some(demo.name.as_str()));
+            assert_eq!(project.canceled(), demo.stage == Stage::Canceled);
+            assert_eq!(project.is_payed(), demo.stage == Stage::Payed);
+            assert!(project.sum_sold().is_ok(), "{}", demo.content);
+            assert!(project.hours().total_time().unwrap_or(0.0) > 0.0);
+            if let Some((year, number)) = demo.invoice {
+                assert_eq!(project.invoice().number().ok(), Some(number as i64));
+                assert!(numbers.insert((year, number)));
+            }
+            if demo.archive.is_some() {
+                assert!(demo.event.year() < 2025);
+            }
+        }
+
+        // the same day, the same demo
+        assert_eq!(projects(today)[7].content, demo[7].content);
+    }
+}
@@ -0,0 +1,263 @@
+//! What happened in one week, for the weekly meeting, see `asciii digest --week`.
+//!
+//! New projects are read from the history of the repository (see `activity`) and from `created`,
+//! offers sent, invoices issued and payments received from the dates in the projects.
+//! Upcoming events are those of the week after.
+//! The digest is Markdown, so it reads well as the body of a mail, too.
+
+use std::ffi::OsStr;
+use std::fmt::Write;
+
+use bill::Currency;
+use chrono::prelude::*;
+use chrono::Duration;
+
+use crate::actions::activity::{Activity, Change};
+use crate::actions::graph::client_of;
+use crate::project::spec::{Invoicable, IsProject, Offerable, Redeemable};
+use crate::project::Project;
+use crate::storage::Storable;
+use crate::util;
+
+/// Monday to Sunday.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Week {
+    pub monday: Date<Utc>,
+}
+
+impl Week {
+    /// The week `date` is in.
+    pub fn containing(date: Date<Utc>) -> Week {
+        Week { monday: date - Duration::days(i64::from(date.weekday().num_days_from_monday())) }
+    }
+
+    /// The week before the one `today` is in, the last complete one.
+    pub fn last(today: Date<Utc>) -> Week {
+        Week::containing(today - Duration::weeks(1))
+    }
+
+    /// An ISO week like `2024-W10` or `2024-10`.
+    pub fn parse(week: &str) -> Option<Week> {
+        let (year, number) = week.trim().split_once('-')?;
+        let number = number.trim_start_matches(['W', 'w']);
+        NaiveDate::from_isoywd_opt(year.parse().ok()?, number.parse().ok()?, Weekday::Mon)
+            .map(|monday| Week { monday: Date::from_utc(monday, Utc) })
+    }
+
+    pub fn sunday(&self) -> Date<Utc> {
+        self.monday + Duration::days(6)
+    }
+
+    pub fn next(&self) -> Week {
+        Week { monday: self.monday + Duration::weeks(1) }
+    }
+
+    pub fn contains(&self, date: Date<Utc>) -> bool {
+        self.monday <= date && date <= self.sunday()
+    }
+
+    /// `2024-W10`
+    pub fn label(&self) -> String {
+        let week = self.monday.iso_week();
+        format!("{}-W{:02}", week.year(), week.week())
+    }
+}
+
+/// One project in one section of the digest.
+#[derive(Debug, Clone)]
+pub struct Line {
+    pub project: String,
+    pub client: Option<String>,
+    /// offer or invoice number
+    pub number: Option<String>,
+    pub date: Date<Utc>,
+    pub amount: Option<Currency>,
+}
+
+impl Line {
+    fn of(project: &Project, date: Date<Utc>) -> Line {
+        Line { project: project.short_desc(), client: client_of(project), number: None, date, amount: None }
+    }
+
+    fn to_markdown(&self) -> String {
+        let mut line = format!("- {} ", self.date.format("%d.%m."));
+        if let Some(ref number) = self.number {
+            line += &format!("{} ", number);
+        }
+        line += &format!("**{}**", self.project);
+        if let Some(ref client) = self.client {
+            line += &format!(", {}", client);
+        }
+        if let Some(ref amount) = self.amount {
+            line += &format!(": {}", util::currency_to_string(amount));
+        }
+        line
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Digest {
+    pub week: Week,
+    pub created: Vec<Line>,
+    pub offers: Vec<Line>,
+    pub invoices: Vec<Line>,
+    pub payments: Vec<Line>,
+    /// events in the week after
+    pub upcoming: Vec<Line>,
+}
+
+impl Digest {
+    /// Digest of `week` from `projects`, working and archived, and `activities` read from the repository.
+    ///
+    /// Projects created in the week are found either way, the history also knows those without `created`.
+    pub fn new(projects: &[Project], activities: &[Activity], week: Week) -> Digest {
+        let ident = |project: &Project| project.file().file_stem().and_then(OsStr::to_str).map(ToOwned::to_owned);
+        let lines_in = |week: Week, date: &dyn Fn(&Project) -> Option<Date<Utc>>| {
+            let mut lines = projects.iter()
+                                    .filter_map(|project| date(project).filter(|date| week.contains(*date)).map(|date| (project, date)))
+                                    .collect::<Vec<_>>();
+            lines.sort_by_key(|(_, date)| *date);
+            lines
+        };
+        let lines = |date: &dyn Fn(&Project) -> Option<Date<Utc>>| lines_in(week, date);
+
+        let mut created = lines(&|project| project.created_date()).into_iter()
+                                                                 .map(|(project, date)| (ident(project), Line::of(project, date)))
+                                                                 .collect::<Vec<_>>();
+        for activity in activities.iter().filter(|activity| activity.change == Change::Created) {
+            let date = match NaiveDate::parse_from_str(&activity.date, "%Y-%m-%d") {
+                Ok(date) => Date::from_utc(date, Utc),
+                Err(_) => continue,
+            };
+            if !week.contains(date) || created.iter().any(|(ident, _)| ident.as_deref() == Some(activity.project.as_str())) {
+                continue;
+            }
+            let line = match projects.iter().find(|project| ident(project).as_deref() == Some(activity.project.as_str())) {
+                Some(project) => Line::of(project, date),
+                // deleted since
+                None => Line { project: activity.project.clone(), client: None, number: None, date, amount: None },
+            };
+            created.push((Some(activity.project.clone()), line));
+        }
+        let mut created = created.into_iter().map(|(_, line)| line).collect::<Vec<_>>();
+        created.sort_by_key(|line| line.date);
+
+        let offers = lines(&|project| project.offer().sent_date().ok())
+            .into_iter()
+            .map(|(project, date)| Line {
+                number: project.offer().number().ok(),
+                amount: project.bills().ok().map(|(offer, _)| offer.net_total()),
+                ..Line::of(project, date)
+            })
+            .collect();
+        let invoiced = |date: &dyn Fn(&Project) -> Option<Date<Utc>>| {
+            lines(date).into_iter()
+                       .map(|(project, date)| Line {
+                           number: project.invoice().number_str(),
+                           amount: project.sum_sold().ok(),
+                           ..Line::of(project, date)
+                       })
+                       .collect()
+        };
+        let invoices = invoiced(&|project| project.invoice().date().ok());
+        let payments = invoiced(&|project| project.payed_date().ok());
+
+        let upcoming = lines_in(week.next(), &|project| project.event_date().ok().filter(|_| !project.canceled()))
+            .into_iter()
+            .map(|(project, date)| Line::of(project, date))
+            .collect();
+
+        Digest { week, created, offers, invoices, payments, upcoming }
+    }
+
+    /// Sum of the payments received.
+    pub fn received(&self) -> Option<Currency> {
+        let mut amounts = self.payments.iter().filter_map(|line| line.amount);
+        let first = amounts.next()?;
+        Some(amounts.fold(first, |sum, amount| sum + amount))
+    }
+
+    /// Subject of the mail.
+    pub fn subject(&self) -> String {
+        lformat!("Digest of {} ({} - {})",
+                 self.week.label(),
+                 self.week.monday.format("%d.%m.%Y"),
+                 self.week.sunday().format("%d.%m.%Y"))
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = format!("# {}\n", self.subject());
+        let sections = [(lformat!("New projects"), &self.created),
+                        (lformat!("Offers sent"), &self.offers),
+                        (lformat!("Invoices issued"), &self.invoices),
+                        (lformat!("Payments received"), &self.payments)];
+        for (title, lines) in sections.iter() {
+            section(&mut markdown, title, lines);
+        }
+        if let Some(received) = self.received() {
+            let _ = writeln!(markdown, "\n{}", lformat!("Received {} in total.", util::currency_to_string(&received)));
+        }
+        let next = self.week.next();
+        let title = lformat!("Upcoming events ({} - {})", next.monday.format("%d.%m."), next.sunday().format("%d.%m."));
+        section(&mut markdown, &title, &self.upcoming);
+        markdown
+    }
+}
+
+fn section(markdown: &mut String, title: &str, lines: &[Line]) {
+    let _ = writeln!(markdown, "\n## {} ({})\n", title, lines.len());
+    if lines.is_empty() {
+        let _ = writeln!(markdown, "{}", lformat!("nothing"));
+    }
+    for line in lines {
+        let _ = writeln!(markdown, "{}", line.to_markdown());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    fn project(ident: &str, content: &str) -> Project {
+        let content = format!("event:\n  name: {}\n  dates:\n  - begin: 13.03.2024\nhours:\n  salary: 8.0\n  caterers:\n    Klaus: 10\n{}", ident, content);
+        let mut project = Project::from_file_content(&content).unwrap();
+        project.set_file(&PathBuf::from(format!("/storage/working/{0}/{0}.yml", ident)));
+        project
+    }
+
+    fn activity(project: &str, date: &str) -> Activity {
+        Activity { commit: "1f2e3d4".into(), date: date.into(), author: "Max".into(), project: project.into(), change: Change::Created }
+    }
+
+    #[test]
+    fn weekly_digest() {
+        let week = Week::parse("2024-W10").unwrap();
+        assert_eq!(week.monday, Utc.ymd(2024, 3, 4));
+        assert_eq!(Week::parse("2024-10"), Some(week));
+        assert_eq!(Week::last(Utc.ymd(2024, 3, 11)), week);
+        assert_eq!(Week::last(Utc.ymd(2024, 3, 17)), week);
+        assert_eq!(week.label(), "2024-W10");
+
+        let projects = vec![project("sommerfest", "created: 05.03.2024\noffer:\n  date: 05.03.2024\n  sent_date: 06.03.2024\n"),
+                            project("hochzeit", "invoice:\n  number: 42\n  date: 01.03.2024\n  payed_date: 08.03.2024\n"),
+                            project("abgesagt", "canceled: true\n")];
+        let activities = vec![activity("hochzeit", "2024-03-07"),
+                              activity("sommerfest", "2024-03-06"),
+                              activity("alt", "2024-02-01")];
+        let digest = Digest::new(&projects, &activities, week);
+        assert_eq!(digest.created.iter().map(|line| line.project.as_str()).collect::<Vec<_>>(), vec!["sommerfest", "hochzeit"]);
+        assert_eq!(digest.offers.len(), 1);
+        assert!(digest.invoices.is_empty());
+        assert_eq!(digest.payments[0].number.as_deref(), Some("R042"));
+        assert_eq!(digest.received(), digest.payments[0].amount);
+        // canceled events don't come
+        assert_eq!(digest.upcoming.len(), 2);
+        assert_eq!(digest.upcoming[0].date, Utc.ymd(2024, 3, 13));
+
+        let markdown = digest.to_markdown();
+        assert!(markdown.starts_with("# "));
+        assert!(markdown.contains("**hochzeit**"));
+    }
+}
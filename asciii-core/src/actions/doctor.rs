@@ -0,0 +1,426 @@
+//! Checks the environment asciii runs in, see `asciii doctor`.
+//!
+//! Every check produces a `Check` with a status and, if something is off, a hint on how to fix it.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use tempdir::TempDir;
+
+use crate::attachments::{RemoteAttachment, Webdav};
+use crate::error::AsciiiError;
+use crate::config::ConfigReader;
+use crate::project::Project;
+use crate::project::spec::IsProject;
+use crate::storage::{self, Repair, Storable};
+use crate::templater::{IsKeyword, Templater};
+use crate::util::yaml;
+
+use super::branding;
+
+/// Outcome of a single check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Ok,
+    Warning,
+    Failed,
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Status::Ok      => write!(f, " ok "),
+            Status::Warning => write!(f, "warn"),
+            Status::Failed  => write!(f, "fail"),
+        }
+    }
+}
+
+/// Result of a single check.
+#[derive(Debug, Clone)]
+pub struct Check {
+    pub topic: &'static str,
+    pub status: Status,
+    pub message: String,
+    /// what to do about it
+    pub hint: Option<String>,
+}
+
+impl Check {
+    fn ok(topic: &'static str, message: String) -> Check {
+        Check { topic, status: Status::Ok, message, hint: None }
+    }
+
+    fn warning(topic: &'static str, message: String, hint: String) -> Check {
+        Check { topic, status: Status::Warning, message, hint: Some(hint) }
+    }
+
+    fn failed(topic: &'static str, message: String, hint: String) -> Check {
+        Check { topic, status: Status::Failed, message, hint: Some(hint) }
+    }
+}
+
+impl fmt::Display for Check {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] {}: {}", self.status, self.topic, self.message)?;
+        if let Some(ref hint) = self.hint {
+            write!(f, "\n       {}", hint)?;
+        }
+        Ok(())
+    }
+}
+
+/// Runs all checks.
+pub fn run() -> Vec<Check> {
+    let mut checks = Vec::new();
+    check_config(&mut checks);
+    let storage_ok = check_storage(&mut checks);
+    check_git(&mut checks);
+    check_toolchain(&mut checks);
+    if storage_ok {
+        check_templates(&mut checks);
+        check_slugs(&mut checks);
+        check_attachments(&mut checks);
+        check_branding(&mut checks);
+    }
+    checks
+}
+
+/// Repairs the storage before the checks run, see `Storage::repair()`.
+///
+/// With `git` the storage also becomes a git repository if it isn't one yet.
+pub fn fix(git: bool) -> Vec<Check> {
+    let mut checks = Vec::new();
+    let storage = match storage::Storage::<Project>::builder().config(std::sync::Arc::clone(&crate::CONFIG)).build() {
+        Ok(storage) => storage,
+        Err(e) => {
+            checks.push(Check::failed("fix", e.to_string(), lformat!("fix \"path\" with `asciii config --set-root <dir>`")));
+            return checks;
+        }
+    };
+
+    match storage.repair() {
+        Ok(repairs) => checks.extend(repairs.iter().map(|repair| Check::ok("fix", match repair {
+            Repair::Created(dir) => lformat!("created {}", dir.display()),
+            Repair::Permissions(path) => lformat!("gave you read and write access to {}", path.display()),
+            Repair::Template(file) => lformat!("wrote the default template {}", file.display()),
+        }))),
+        Err(e) => {
+            checks.push(Check::failed("fix", e.to_string(), lformat!("check the permissions of {}", storage.root_dir().display())));
+            return checks;
+        }
+    }
+
+    if git && !storage.root_dir().join(".git").exists() {
+        checks.push(match super::init_repository(storage.root_dir()) {
+            Ok(()) => Check::ok("fix", lformat!("initialized a git repository in {}", storage.root_dir().display())),
+            Err(e) => Check::failed("fix", e.to_string(), lformat!("install git and make sure it is in your PATH")),
+        });
+    }
+    checks
+}
+
+fn check_config(checks: &mut Vec<Check>) {
+    let path = ConfigReader::path_home();
+    if !path.exists() {
+        checks.push(Check::warning("config",
+                                   lformat!("{} does not exist, using defaults", path.display()),
+                                   lformat!("run `asciii config --init`")));
+    } else if let Err(e) = yaml::open(&path) {
+        checks.push(Check::failed("config",
+                                  lformat!("{} can't be parsed: {}", path.display(), e),
+                                  lformat!("fix the yaml syntax with `asciii config --edit`")));
+    } else {
+        checks.push(Check::ok("config", lformat!("{} is valid yaml", path.display())));
+    }
+
+    for key in &["path", "dirs/storage", "dirs/working", "dirs/archive", "dirs/templates"] {
+        if crate::CONFIG.get_str_or(key).is_none() {
+            checks.push(Check::failed("config",
+                                      lformat!("{:?} is not set", key),
+                                      lformat!("set {:?} with `asciii config --edit`", key)));
+        }
+    }
+
+    match crate::CONFIG.get_str_or("user/name") {
+        Some(name) if !name.trim().is_empty() && name.list_keywords().is_empty() => {
+            checks.push(Check::ok("config", lformat!("user name is {:?}", name)))
+        }
+        _ => checks.push(Check::warning("config",
+                                        lformat!("user name is not set"),
+                                        lformat!("set \"user/name\" with `asciii config --edit`")))
+    }
+}
+
+/// Returns `false` if the storage can't be used at all.
+fn check_storage(checks: &mut Vec<Check>) -> bool {
+    let root = storage::get_storage_path();
+    if !root.exists() {
+        checks.push(Check::failed("storage",
+                                  lformat!("{} does not exist", root.display()),
+                                  lformat!("clone your storage with `asciii bootstrap <repo>` or fix \"path\" with `asciii config --set-root <dir>`")));
+        return false;
+    }
+    checks.push(Check::ok("storage", lformat!("found {}", root.display())));
+
+    let mut complete = true;
+    for key in &["dirs/working", "dirs/archive", "dirs/templates"] {
+        let dir = root.join(crate::CONFIG.get_str(key));
+        if !dir.is_dir() {
+            complete = false;
+            checks.push(Check::failed("storage",
+                                      lformat!("{} does not exist", dir.display()),
+                                      lformat!("create it with `mkdir -p {}`", dir.display())));
+        }
+    }
+
+    let working = root.join(crate::CONFIG.get_str("dirs/working"));
+    if working.is_dir() {
+        checks.push(check_writable(&working));
+    }
+
+    complete
+}
+
+fn check_writable(dir: &Path) -> Check {
+    match TempDir::new_in(dir, "asciii-doctor") {
+        Ok(_) => Check::ok("storage", lformat!("{} is writable", dir.display())),
+        Err(e) => Check::failed("storage",
+                                lformat!("can't write to {}: {}", dir.display(), e),
+                                lformat!("check the permissions of {}", dir.display()))
+    }
+}
+
+/// Output of a successful command, `None` if it can't be run or fails.
+fn command_output(program: &str, args: &[&str], dir: Option<&Path>) -> Option<String> {
+    let mut command = Command::new(program);
+    command.args(args);
+    if let Some(dir) = dir {
+        command.current_dir(dir);
+    }
+    command.output()
+           .ok()
+           .filter(|out| out.status.success())
+           .and_then(|out| String::from_utf8(out.stdout).ok())
+           .map(|s| s.trim().to_owned())
+}
+
+fn check_git(checks: &mut Vec<Check>) {
+    match command_output("git", &["--version"], None) {
+        Some(version) => checks.push(Check::ok("git", version)),
+        None => {
+            checks.push(Check::failed("git",
+                                      lformat!("git is not available"),
+                                      lformat!("install git and make sure it is in your PATH")));
+            return;
+        }
+    }
+
+    let root = storage::get_storage_path();
+    if !root.exists() {
+        return;
+    }
+
+    if !root.join(".git").exists() {
+        checks.push(Check::warning("git",
+                                   lformat!("{} is not a git repository", root.display()),
+                                   lformat!("run `git init` in {} or clone it with `asciii bootstrap <repo>`", root.display())));
+        return;
+    }
+
+    for key in &["user.name", "user.email"] {
+        match command_output("git", &["config", key], Some(&root)).filter(|v| !v.is_empty()) {
+            Some(value) => checks.push(Check::ok("git", lformat!("{} is {:?}", key, value))),
+            None => checks.push(Check::failed("git",
+                                              lformat!("{} is not set, commits will fail", key),
+                                              lformat!("run `git config --global {} <value>`", key)))
+        }
+    }
+}
+
+fn check_toolchain(checks: &mut Vec<Check>) {
+    let convert_tool = crate::CONFIG.get_str("document_export/convert_tool");
+    match command_output(convert_tool, &["--version"], None) {
+        Some(version) => checks.push(Check::ok("toolchain",
+                                               version.lines().next().unwrap_or(convert_tool).to_owned())),
+        None => checks.push(Check::failed("toolchain",
+                                          lformat!("{:?} can't be run", convert_tool),
+                                          lformat!("install {} or set \"document_export/convert_tool\" with `asciii config --edit`", convert_tool)))
+    }
+}
+
+fn template_files(dir: &Path, extension: &str) -> Vec<PathBuf> {
+    storage::list_path_content(dir)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|p| p.to_string_lossy().ends_with(&format!(".{}", extension)))
+        .collect()
+}
+
+fn check_templates(checks: &mut Vec<Check>) {
+    let dir = storage::get_storage_path().join(crate::CONFIG.get_str("dirs/templates"));
+
+    let project_templates = template_files(&dir, crate::CONFIG.get_str("extensions/project_template"));
+    if project_templates.is_empty() {
+        checks.push(Check::failed("templates",
+                                  lformat!("no project templates in {}", dir.display()),
+                                  lformat!("add at least a default.{}", crate::CONFIG.get_str("extensions/project_template"))));
+    }
+
+    for path in project_templates {
+        // every keyword is filled with a plain word, the rest has to be valid yaml already
+        let result = Templater::from_file(&path)
+            .map_err(AsciiiError::from)
+            .and_then(|mut t| yaml::parse(&t.fill_template(|_| String::from("filled")).filled));
+        checks.push(match result {
+            Ok(_) => Check::ok("templates", lformat!("{} is valid", path.display())),
+            Err(e) => Check::failed("templates",
+                                    lformat!("{} is invalid: {}", path.display(), e),
+                                    lformat!("fix the template, new projects can't be created from it"))
+        });
+    }
+
+    let export_template = dir.join(format!("{}.{}",
+                                           crate::CONFIG.get_str("document_export/default_template"),
+                                           crate::CONFIG.get_str("extensions/output_template")));
+    checks.push(check_export_template(&export_template));
+}
+
+/// Projects named before `slugs` changed have to be found by their names still, and not mistaken for others.
+fn check_slugs(checks: &mut Vec<Check>) {
+    let storage = match storage::setup::<Project>() {
+        Ok(storage) => storage,
+        Err(_) => return, // check_storage reports that
+    };
+    let projects = match storage.open_projects(storage::StorageDir::All) {
+        Ok(projects) => projects,
+        Err(_) => return,
+    };
+    let strategy = storage.slug_strategy();
+    let legacy = storage::SlugStrategy::default();
+
+    let (mut current, mut old) = (0, 0);
+    for project in projects.iter() {
+        let name = match IsProject::name(project) {
+            Ok(name) => name,
+            Err(_) => continue,
+        };
+        let stem = project.file().file_stem().map(|stem| stem.to_string_lossy().into_owned()).unwrap_or_default();
+        let by_old_slug = stem != strategy.slugify(name);
+        if by_old_slug && stem != legacy.slugify(name) {
+            continue; // named some other way, e.g. renamed later
+        }
+
+        let dir = match project.dir().parent() {
+            Some(parent) if parent == storage.working_dir() => storage::StorageDir::Working,
+            Some(parent) => match parent.file_name().and_then(|year| year.to_str()).and_then(|year| year.parse().ok()) {
+                Some(year) => storage::StorageDir::Archive(year),
+                None => continue,
+            },
+            None => continue,
+        };
+        match storage.get_project_dir(name, dir) {
+            Ok(found) if found == project.dir() => if by_old_slug { old += 1 } else { current += 1 },
+            Ok(found) => checks.push(Check::failed("slugs",
+                                                   lformat!("{:?} now resolves to {} instead of {}", name, found.display(), project.dir().display()),
+                                                   lformat!("rename the directory of one of the projects"))),
+            Err(_) => checks.push(Check::failed("slugs",
+                                                lformat!("{} can't be found by its name {:?} anymore", project.dir().display(), name),
+                                                lformat!("check \"slugs\" with `asciii config --show slugs`"))),
+        }
+    }
+    checks.push(Check::ok("slugs", lformat!("{} projects are found by their names, {} of them by the old slug", current + old, old)));
+}
+
+/// Every remote attachment of the working projects has to be on the server, with the size it was uploaded with.
+fn check_attachments(checks: &mut Vec<Check>) {
+    let projects = match storage::setup::<Project>().and_then(|storage| storage.open_projects(storage::StorageDir::Working)) {
+        Ok(projects) => projects,
+        Err(_) => return, // check_storage reports that
+    };
+    let listed = projects.iter()
+                         .flat_map(|project| RemoteAttachment::of(project).into_iter().map(move |a| (project, a)))
+                         .collect::<Vec<_>>();
+    if listed.is_empty() {
+        return;
+    }
+
+    let webdav = match Webdav::configured() {
+        Some(webdav) => webdav,
+        None => {
+            checks.push(Check::warning("attachments",
+                                       lformat!("{} remote attachments but no WebDAV server", listed.len()),
+                                       lformat!("set \"attachments/webdav/url\" with `asciii config --edit`")));
+            return;
+        }
+    };
+
+    let mut found = 0;
+    for (project, attachment) in &listed {
+        match webdav.size(&attachment.url) {
+            Ok(Some(size)) if size == attachment.size => found += 1,
+            Ok(Some(size)) => checks.push(Check::warning("attachments",
+                                                         lformat!("{} of {} has {} bytes instead of {}", attachment.name, project.short_desc(), size, attachment.size),
+                                                         lformat!("upload it again with `asciii attach --remote`"))),
+            Ok(None) => checks.push(Check::failed("attachments",
+                                                  lformat!("{} of {} is missing on the server", attachment.name, project.short_desc()),
+                                                  lformat!("upload it again with `asciii attach --remote`"))),
+            Err(e) => checks.push(Check::failed("attachments",
+                                                e.to_string(),
+                                                lformat!("check \"attachments/webdav\" and ASCIII_WEBDAV_PASSWORD"))),
+        }
+    }
+    if found > 0 {
+        checks.push(Check::ok("attachments", lformat!("{} of {} remote attachments are on {}", found, listed.len(), webdav.url)));
+    }
+}
+
+/// The files `extras/branding.yml` refers to have to be there, documents would miss them otherwise.
+fn check_branding(checks: &mut Vec<Check>) {
+    let storage = match storage::setup::<Project>() {
+        Ok(storage) => storage,
+        Err(_) => return, // check_storage reports that
+    };
+    let branding = match branding::configured(&storage) {
+        Ok(Some(branding)) => branding,
+        Ok(None) => return,
+        Err(e) => {
+            checks.push(Check::failed("branding", e.to_string(), lformat!("fix {} or run `asciii extras branding`", branding::BRANDING_FILE)));
+            return;
+        }
+    };
+
+    let missing = branding.missing_assets(storage.extras_dir());
+    for (what, path) in &missing {
+        checks.push(Check::failed("branding",
+                                  lformat!("the {} {} does not exist", what, path.display()),
+                                  lformat!("fix {} or run `asciii extras branding`", branding::BRANDING_FILE)));
+    }
+    if missing.is_empty() {
+        checks.push(Check::ok("branding", lformat!("{} assets of {} are there", branding.assets(storage.extras_dir()).len(), branding::BRANDING_FILE)));
+    }
+}
+
+#[cfg(feature = "document_export")]
+fn check_export_template(path: &Path) -> Check {
+    if !path.exists() {
+        return Check::failed("templates",
+                             lformat!("{} does not exist", path.display()),
+                             lformat!("add it or set \"document_export/default_template\", documents can't be made without it"));
+    }
+    let result = std::fs::read_to_string(path)
+        .map_err(AsciiiError::from)
+        .and_then(|content| handlebars::Template::compile(&content).map(|_| ()).map_err(Into::into));
+    match result {
+        Ok(()) => Check::ok("templates", lformat!("{} is valid", path.display())),
+        Err(e) => Check::failed("templates",
+                                lformat!("{} is invalid: {}", path.display(), e),
+                                lformat!("fix the template or set \"document_export/default_template\", documents can't be made without it"))
+    }
+}
+
+#[cfg(not(feature = "document_export"))]
+fn check_export_template(path: &Path) -> Check {
+    Check::ok("templates", lformat!("skipped {}, document export is not enabled in this build", path.display()))
+}
@@ -0,0 +1,35 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ActionError {
+
+    #[error("unexpected response from service")]
+    ActionError,
+
+    #[error("Adding Failed")]
+    AddingFailed,
+
+    #[error("Nothing found for {:?}", _0)]
+    NothingFound(Vec<String>),
+
+    /// a check like `asciii validate` found something, with a summary of what
+    #[error("{}", _0)]
+    ValidationFailed(String),
+
+    #[error(transparent)]
+    Csv(#[from] csv::Error),
+
+    #[error(transparent)]
+    Utf8(#[from] std::string::FromUtf8Error),
+
+    #[error(transparent)]
+    Time(#[from] std::time::SystemTimeError),
+
+    #[cfg(feature = "meta")]
+    #[error(transparent)]
+    Toml(#[from] toml::de::Error),
+
+    /// anything else, with a localized message
+    #[error("{}", _0)]
+    Failed(String),
+}
@@ -0,0 +1,123 @@
+//! Clients, their projects and which project followed which, as a graphviz graph, see `asciii export --graphviz`.
+//!
+//! A project names the one it follows up on with `follows`, by the name of its file or by its invoice number,
+//! `asciii duplicate` does that for you. Clients are matched by their entry in `extras/clients.yml`, or by their full name.
+//!
+//! ```sh
+//! asciii export --graphviz | dot -Tsvg > clients.svg
+//! ```
+
+use std::collections::BTreeMap;
+use std::ffi::OsStr;
+use std::fmt::Write;
+
+use crate::project::spec::{Invoicable, IsClient, IsProject};
+use crate::project::Project;
+use crate::storage::Storable;
+
+/// Quoted for dot.
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n"))
+}
+
+/// Name of the file of `project`, without extension.
+fn file_ident(project: &Project) -> Option<String> {
+    project.file().file_stem().and_then(OsStr::to_str).map(ToOwned::to_owned)
+}
+
+/// Who `project` is for, `None` without a name.
+pub fn client_of(project: &Project) -> Option<String> {
+    project.known_client()
+           .map(|known| known.name.clone())
+           .or_else(|| project.client().full_name())
+           .map(|name| name.trim().to_owned())
+           .filter(|name| !name.is_empty())
+}
+
+/// The project `follows` refers to.
+pub fn followed<'a>(projects: &'a [Project], follows: &str) -> Option<&'a Project> {
+    projects.iter().find(|project| file_ident(project).as_deref() == Some(follows))
+            .or_else(|| projects.iter().find(|project| project.invoice().number_str().as_deref() == Some(follows)))
+}
+
+/// The graph of `projects` in the dot language.
+///
+/// Clients are ellipses, projects boxes, follow-ups are dashed edges.
+/// `follows` that point to none of `projects` are left out.
+pub fn dot(projects: &[Project]) -> String {
+    let node = |project: &Project| format!("project:{}", file_ident(project).unwrap_or_else(|| project.short_desc()));
+
+    let mut clients = BTreeMap::new();
+    for project in projects {
+        if let Some(client) = client_of(project) {
+            *clients.entry(client).or_insert(0) += 1;
+        }
+    }
+
+    let mut dot = String::from("digraph asciii {\n    rankdir=LR;\n    node [shape=box];\n");
+    for (client, count) in &clients {
+        let label = if *count > 1 { format!("{} ({})", client, count) } else { client.clone() };
+        let _ = writeln!(dot, "    {} [label={}, shape=ellipse];", quote(&format!("client:{}", client)), quote(&label));
+    }
+    for project in projects {
+        let mut label = project.short_desc();
+        if let Some(number) = project.invoice().number_str() {
+            label = format!("{} {}", number, label);
+        }
+        if let Ok(date) = project.event_date() {
+            label = format!("{}\n{}", label, date.format("%d.%m.%Y"));
+        }
+        let _ = writeln!(dot, "    {} [label={}];", quote(&node(project)), quote(&label));
+    }
+    for project in projects {
+        if let Some(client) = client_of(project) {
+            let _ = writeln!(dot, "    {} -> {};", quote(&format!("client:{}", client)), quote(&node(project)));
+        }
+        if let Some(earlier) = project.follows().and_then(|follows| followed(projects, follows)) {
+            let _ = writeln!(dot, "    {} -> {} [style=dashed];", quote(&node(earlier)), quote(&node(project)));
+        }
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    fn project(ident: &str, content: &str) -> Project {
+        let content = format!("event:\n  name: {}\n  dates:\n  - begin: 01.03.2024\nhours:\n  salary: 8.0\n{}", ident, content);
+        in_file(ident, &content)
+    }
+
+    fn in_file(ident: &str, content: &str) -> Project {
+        let mut project = Project::from_file_content(content).unwrap();
+        project.set_file(&PathBuf::from(format!("/storage/working/{0}/{0}.yml", ident)));
+        project
+    }
+
+    #[test]
+    fn client_graph() {
+        let first = project("sommerfest", "client:\n  first_name: Erika\n  last_name: Mustermann\ninvoice:\n  number: 42\n");
+        let copy = in_file("sommerfest2", &first.duplicate_content("Sommerfest 2").unwrap());
+        assert_eq!(copy.follows(), Some("sommerfest"));
+
+        let projects = vec![first,
+                            copy,
+                            project("herbstfest", &format!("follows: {}\n", "R042")),
+                            project("einzeln", "follows: nirgends\n")];
+        assert_eq!(client_of(&projects[0]).as_deref(), Some("Erika Mustermann"));
+        assert_eq!(client_of(&projects[2]), None);
+        assert!(followed(&projects, "R042").is_some());
+
+        let dot = dot(&projects);
+        assert!(dot.starts_with("digraph asciii {"));
+        assert!(dot.contains("\"client:Erika Mustermann\" [label=\"Erika Mustermann (2)\", shape=ellipse];"));
+        assert!(dot.contains("\"client:Erika Mustermann\" -> \"project:sommerfest2\";"));
+        assert!(dot.contains("\"project:sommerfest\" -> \"project:sommerfest2\" [style=dashed];"));
+        assert!(dot.contains("\"project:sommerfest\" -> \"project:herbstfest\" [style=dashed];"));
+        assert_eq!(dot.matches("style=dashed").count(), 2);
+    }
+}
@@ -0,0 +1,154 @@
+//! Creates projects from other sources, see `asciii import`.
+//!
+//! A csv file holds one project per row, its header names the template keywords the columns fill in,
+//! e.g. `PROJECT-NAME,DATE-EVENT,DESCRIPTION`.
+//! Headers are matched case insensitive with `_` and spaces read as `-`, so `date event` fills `DATE-EVENT` too.
+//! Other headers can be mapped explicitly (`Kunde=CLIENT-NAME`), the `TEMPLATE` column picks a template per row.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::error::{ActionError, AsciiiError};
+use crate::project::Project;
+use crate::storage::{Storable, Storage};
+
+pub mod legacy;
+pub mod eml;
+#[cfg(feature="serialization")] pub mod foreign;
+#[cfg(feature="serialization")] pub mod invoice_ninja;
+
+/// Keyword that names the new project.
+const NAME: &str = "PROJECT-NAME";
+
+/// Keyword that picks the template of a row.
+const TEMPLATE: &str = "TEMPLATE";
+
+/// A project to be created.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Row {
+    /// line in the csv file
+    pub line: u64,
+    pub name: Option<String>,
+    pub template: Option<String>,
+    pub fill: HashMap<String, String>,
+}
+
+/// Result of importing a single row.
+#[derive(Debug)]
+pub struct Outcome {
+    pub line: u64,
+    pub name: Option<String>,
+    pub result: Result<PathBuf, AsciiiError>,
+}
+
+impl Outcome {
+    pub fn is_ok(&self) -> bool {
+        self.result.is_ok()
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = self.name.as_deref().unwrap_or("");
+        match self.result {
+            Ok(ref file) => write!(f, "{:>4} {} {:?}: {}", self.line, lformat!("ok"), name, file.display()),
+            Err(ref e) => write!(f, "{:>4} {} {:?}: {}", self.line, lformat!("failed"), name, e),
+        }
+    }
+}
+
+/// Turns a csv header into the keyword it fills.
+fn keyword(header: &str, mapping: &[(String, String)]) -> String {
+    mapping.iter()
+           .find(|(column, _)| column.trim() == header.trim())
+           .map(|(_, keyword)| keyword.trim().to_owned())
+           .unwrap_or_else(|| header.trim().to_uppercase().replace(['_', ' '], "-"))
+}
+
+/// Reads the rows of a csv file, `mapping` pairs headers with keywords.
+pub fn csv_rows(path: &Path, mapping: &[(String, String)], delimiter: u8) -> Result<Vec<Row>, AsciiiError> {
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .flexible(true)
+        .from_path(path)
+        .map_err(|e| ActionError::Failed(format!("{}: {}", lformat!("can't read {}", path.display()), e)))?;
+
+    let keywords = reader.headers()?
+                         .iter()
+                         .map(|header| keyword(header, mapping))
+                         .collect::<Vec<String>>();
+    log::debug!("csv columns fill {:?}", keywords);
+
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        let record = record?;
+        let mut fill = keywords.iter()
+                               .zip(record.iter())
+                               .filter(|(_, value)| !value.trim().is_empty())
+                               .map(|(keyword, value)| (keyword.clone(), value.trim().to_owned()))
+                               .collect::<HashMap<String, String>>();
+        rows.push(Row {
+            line: record.position().map_or(0, |p| p.line()),
+            name: fill.remove(NAME),
+            template: fill.remove(TEMPLATE),
+            fill,
+        });
+    }
+    Ok(rows)
+}
+
+/// Creates a project for every row, a failing row does not stop the others.
+///
+/// With `dry_run` nothing is created, the rows are only checked.
+pub fn create_projects(storage: &Storage<Project>, rows: &[Row], default_template: &str, dry_run: bool) -> Vec<Outcome> {
+    rows.iter()
+        .map(|row| Outcome {
+            line: row.line,
+            name: row.name.clone(),
+            result: create_project(storage, row, default_template, dry_run),
+        })
+        .collect()
+}
+
+fn create_project(storage: &Storage<Project>, row: &Row, default_template: &str, dry_run: bool) -> Result<PathBuf, AsciiiError> {
+    let name = row.name.as_ref()
+                  .ok_or_else(|| ActionError::Failed(lformat!("no {} given", NAME)))?;
+    let template = row.template.as_deref().unwrap_or(default_template);
+    let fill = row.fill.iter()
+                  .map(|(keyword, value)| (keyword.as_str(), value.clone()))
+                  .collect::<HashMap<&str, String>>();
+
+    if dry_run {
+        let (_, file) = storage.new_project_paths(name)?;
+        Project::from_template(name, &storage.get_template_file(template)?, &fill)?;
+        return Ok(file);
+    }
+    Ok(storage.create_project(name, template, &fill)?.file())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempdir::TempDir;
+
+    #[test]
+    fn rows_from_csv() {
+        let dir = TempDir::new("asciii-import").unwrap();
+        let path = dir.path().join("bookings.csv");
+        fs::write(&path, "Project Name;date_event;Kunde;template\n\"Party; Hard\";01.05.2021;Graf Zahl;\nFest;;;fest\n").unwrap();
+
+        let mapping = vec![("Kunde".to_owned(), "CLIENT-NAME".to_owned())];
+        let rows = csv_rows(&path, &mapping, b';').unwrap();
+
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].line, 2);
+        assert_eq!(rows[0].name.as_deref(), Some("Party; Hard"));
+        assert_eq!(rows[0].template, None);
+        assert_eq!(rows[0].fill.get("DATE-EVENT").map(String::as_str), Some("01.05.2021"));
+        assert_eq!(rows[0].fill.get("CLIENT-NAME").map(String::as_str), Some("Graf Zahl"));
+        assert_eq!(rows[1].template.as_deref(), Some("fest"));
+        assert!(rows[1].fill.is_empty());
+    }
+}
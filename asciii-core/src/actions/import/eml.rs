@@ -0,0 +1,331 @@
+//! Creates a project from a booking request email, see `asciii import eml`.
+//!
+//! The client is taken from the `From` header, the event name from the subject
+//! and the text of the mail becomes the event description.
+//! The regexes under `import/eml` in the config look for these in the text instead,
+//! their first group is taken, e.g. `event_date: 'am (\d+\.\d+\.\d{4})'`.
+//! The original mail is copied into the new project folder.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::NaiveDate;
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::error::{ActionError, AsciiiError};
+use crate::project::Project;
+use crate::storage::{Storable, Storage};
+use crate::util;
+
+/// The parts of an email that matter here.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Mail {
+    /// header names are lowercase, folded lines are joined
+    pub headers: Vec<(String, String)>,
+    /// the first text part, decoded
+    pub text: String,
+}
+
+impl Mail {
+    /// Reads an email as it is stored in a `.eml` file.
+    pub fn parse(content: &str) -> Mail {
+        let content = content.replace("\r\n", "\n");
+        let (head, body) = content.split_once("\n\n").unwrap_or((&content, ""));
+        let headers = parse_headers(head);
+        let text = text_of(&headers, body);
+        Mail { headers, text }
+    }
+
+    /// The first header called `name`.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        let name = name.to_lowercase();
+        self.headers.iter()
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+fn parse_headers(head: &str) -> Vec<(String, String)> {
+    let mut headers: Vec<(String, String)> = Vec::new();
+    for line in head.lines() {
+        if line.starts_with([' ', '\t']) {
+            if let Some((_, value)) = headers.last_mut() {
+                value.push(' ');
+                value.push_str(line.trim());
+            }
+        } else if let Some((key, value)) = line.split_once(':') {
+            headers.push((key.trim().to_lowercase(), value.trim().to_owned()));
+        }
+    }
+    for (_, value) in &mut headers {
+        *value = decode_words(value);
+    }
+    headers
+}
+
+/// A parameter like `boundary` of a header value like `multipart/mixed; boundary="xyz"`.
+fn parameter<'a>(value: &'a str, name: &str) -> Option<&'a str> {
+    value.split(';')
+        .skip(1)
+        .filter_map(|param| param.split_once('='))
+        .find(|(key, _)| key.trim().eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.trim().trim_matches('"'))
+}
+
+fn header<'a>(headers: &'a [(String, String)], name: &str) -> &'a str {
+    headers.iter().find(|(key, _)| key == name).map_or("", |(_, value)| value.as_str())
+}
+
+/// The first `text/plain` part of `body`, decoded.
+fn text_of(headers: &[(String, String)], body: &str) -> String {
+    let content_type = header(headers, "content-type");
+    if content_type.to_lowercase().starts_with("multipart/") {
+        let boundary = match parameter(content_type, "boundary") {
+            Some(boundary) => format!("--{}", boundary),
+            None => return String::new(),
+        };
+        return body.split(&boundary)
+            .skip(1)
+            .filter(|part| !part.starts_with("--"))
+            .map(|part| Mail::parse(part.trim_start_matches('\n')))
+            .find(|part| part.header("content-type").is_none_or(|t| t.to_lowercase().starts_with("text/plain"))
+                         && !part.text.trim().is_empty())
+            .map(|part| part.text)
+            .unwrap_or_default();
+    }
+
+    let charset = parameter(content_type, "charset").unwrap_or("utf-8");
+    let bytes = match header(headers, "content-transfer-encoding").to_lowercase().as_str() {
+        "quoted-printable" => decode_quoted_printable(body),
+        "base64" => base64::decode(body.split_whitespace().collect::<String>()).unwrap_or_default(),
+        _ => body.as_bytes().to_vec(),
+    };
+    decode_charset(&bytes, charset).trim_end().to_owned()
+}
+
+fn decode_charset(bytes: &[u8], charset: &str) -> String {
+    if charset.eq_ignore_ascii_case("iso-8859-1") || charset.eq_ignore_ascii_case("latin1") {
+        bytes.iter().map(|&b| b as char).collect()
+    } else {
+        String::from_utf8_lossy(bytes).into_owned()
+    }
+}
+
+fn decode_quoted_printable(text: &str) -> Vec<u8> {
+    let text = text.replace("=\n", "");
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes.get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[i], hex) {
+            (b'=', Some(byte)) => { decoded.push(byte); i += 3; }
+            (byte, _) => { decoded.push(byte); i += 1; }
+        }
+    }
+    decoded
+}
+
+lazy_static! {
+    static ref ENCODED_WORD: Regex = Regex::new(r"=\?([^?]+)\?([bBqQ])\?([^?]*)\?=").unwrap();
+    static ref BETWEEN_WORDS: Regex = Regex::new(r"\?=\s+=\?").unwrap();
+}
+
+/// Decodes header words encoded as described in RFC 2047.
+fn decode_words(value: &str) -> String {
+    // whitespace between two encoded words is dropped
+    let value = BETWEEN_WORDS.replace_all(value, "?==?");
+    ENCODED_WORD.replace_all(&value, |caps: &regex::Captures<'_>| {
+        let bytes = if caps[2].eq_ignore_ascii_case("b") {
+            base64::decode(&caps[3]).unwrap_or_default()
+        } else {
+            decode_quoted_printable(&caps[3].replace('_', " "))
+        };
+        decode_charset(&bytes, &caps[1])
+    }).into_owned()
+}
+
+/// Regexes from `import/eml`, empty ones are not used.
+#[derive(Debug, Clone, Default)]
+pub struct Patterns {
+    pub event_name: Option<Regex>,
+    pub event_date: Option<Regex>,
+    pub client_name: Option<Regex>,
+    pub client_email: Option<Regex>,
+}
+
+impl Patterns {
+    pub fn configured() -> Result<Patterns, AsciiiError> {
+        let pattern = |key: &str| -> Result<Option<Regex>, AsciiiError> {
+            let path = format!("import/eml/{}", key);
+            match crate::CONFIG.get_str_or(&path).filter(|p| !p.is_empty()) {
+                Some(pattern) => Regex::new(pattern)
+                    .map(Some)
+                    .map_err(|e| ActionError::Failed(format!("{}: {}", path, e)).into()),
+                None => Ok(None),
+            }
+        };
+        Ok(Patterns {
+            event_name: pattern("event_name")?,
+            event_date: pattern("event_date")?,
+            client_name: pattern("client_name")?,
+            client_email: pattern("client_email")?,
+        })
+    }
+}
+
+fn first_group(pattern: &Option<Regex>, text: &str) -> Option<String> {
+    let caps = pattern.as_ref()?.captures(text)?;
+    caps.get(1).or_else(|| caps.get(0))
+        .map(|m| m.as_str().trim().to_owned())
+        .filter(|m| !m.is_empty())
+}
+
+/// What a booking request asks for.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Booking {
+    pub event_name: String,
+    pub event_date: Option<NaiveDate>,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub email: Option<String>,
+    pub description: String,
+}
+
+/// Splits `"Graf Zahl" <graf@example.com>` into name and address.
+fn split_address(from: &str) -> (Option<String>, Option<String>) {
+    match from.rsplit_once('<') {
+        Some((name, address)) => {
+            let name = name.trim().trim_matches('"').trim();
+            (Some(name.to_owned()).filter(|n| !n.is_empty()),
+             Some(address.trim_end_matches('>').trim().to_owned()))
+        }
+        None => (None, Some(from.trim().to_owned()).filter(|a| a.contains('@'))),
+    }
+}
+
+fn parse_date(date: &str) -> Option<NaiveDate> {
+    util::yaml::parse_dmy_date(date)
+        .map(|date| date.naive_utc())
+        .or_else(|| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok())
+}
+
+fn strip_reply_prefixes(subject: &str) -> &str {
+    let mut subject = subject.trim();
+    while let Some((prefix, rest)) = subject.split_once(':') {
+        if !["re", "aw", "fw", "fwd", "wg"].contains(&prefix.trim().to_lowercase().as_str()) {
+            break;
+        }
+        subject = rest.trim();
+    }
+    subject
+}
+
+impl Booking {
+    pub fn from_mail(mail: &Mail, patterns: &Patterns) -> Booking {
+        let (sender, address) = split_address(mail.header("from").unwrap_or(""));
+        let name = first_group(&patterns.client_name, &mail.text).or(sender);
+        let (first_name, last_name) = match name.as_deref().map(str::trim).and_then(|n| n.rsplit_once(' ')) {
+            Some((first, last)) => (Some(first.trim().to_owned()), Some(last.to_owned())),
+            None => (None, name),
+        };
+
+        Booking {
+            event_name: first_group(&patterns.event_name, &mail.text)
+                .unwrap_or_else(|| strip_reply_prefixes(mail.header("subject").unwrap_or("")).to_owned()),
+            event_date: first_group(&patterns.event_date, &mail.text).as_deref().and_then(parse_date),
+            first_name,
+            last_name,
+            email: first_group(&patterns.client_email, &mail.text).or(address),
+            description: mail.text.trim().to_owned(),
+        }
+    }
+
+    /// Fields of the new project, as `Project::set_field()` takes them.
+    pub fn fields(&self) -> Vec<(&'static str, String)> {
+        [("client/first_name", self.first_name.clone()),
+         ("client/last_name", self.last_name.clone()),
+         ("client/email", self.email.clone()),
+         ("event/description", Some(self.description.clone()).filter(|d| !d.is_empty()))]
+            .iter()
+            .filter_map(|(field, value)| Some((*field, value.clone()?)))
+            .collect()
+    }
+
+    /// Content of the new project, made from `template`.
+    pub fn project_content(&self, storage: &Storage<Project>, template: &str) -> Result<String, AsciiiError> {
+        let mut fill = HashMap::new();
+        fill.insert("DESCRIPTION", String::new());
+        if let Some(date) = self.event_date {
+            fill.insert("DATE-EVENT", date.format("%d.%m.%Y").to_string());
+        }
+        let project = Project::from_template(&self.event_name, &storage.get_template_file(template)?, &fill)?;
+        let fields = self.fields();
+        project.content_with_fields(&fields.iter().map(|(f, v)| (*f, v.as_str())).collect::<Vec<_>>())
+    }
+}
+
+/// Creates a project for the booking request in `file`, with a copy of the mail in its folder.
+///
+/// With `dry_run` nothing is created, the content of the project is returned instead.
+pub fn import(storage: &Storage<Project>, file: &Path, template: &str, dry_run: bool) -> Result<(PathBuf, String), AsciiiError> {
+    let content = fs::read(file)?;
+    let mail = Mail::parse(&String::from_utf8_lossy(&content));
+    let booking = Booking::from_mail(&mail, &Patterns::configured()?);
+    if booking.event_name.is_empty() {
+        return Err(ActionError::Failed(lformat!("no event name in {}", file.display())).into());
+    }
+
+    let project_content = booking.project_content(storage, template)?;
+    if dry_run {
+        return Ok((storage.new_project_paths(&booking.event_name)?.1, project_content));
+    }
+
+    let project = storage.create_project_from_content(&booking.event_name, &project_content)?;
+    let attachment = project.dir().join(file.file_name().unwrap_or_else(|| "request.eml".as_ref()));
+    fs::write(&attachment, &content)?;
+    Ok((project.file(), project_content))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAIL: &str = "From: =?utf-8?q?Graf_Zahl?= <graf@example.com>\r\n\
+                        Subject: AW: Kaffee =?utf-8?b?ZsO8cg==?= die Party\r\n\
+                        Content-Type: multipart/alternative; boundary=\"xyz\"\r\n\
+                        \r\n\
+                        --xyz\r\n\
+                        Content-Type: text/plain; charset=utf-8\r\n\
+                        Content-Transfer-Encoding: quoted-printable\r\n\
+                        \r\n\
+                        Hallo, wir feiern am 01.05.2021 und h=C3=A4tten gern =\r\n\
+                        Kaffee.\r\n\
+                        --xyz\r\n\
+                        Content-Type: text/html\r\n\
+                        \r\n\
+                        <p>Hallo</p>\r\n\
+                        --xyz--\r\n";
+
+    #[test]
+    fn bookings_from_mails() {
+        let mail = Mail::parse(MAIL);
+        assert_eq!(mail.header("Subject"), Some("AW: Kaffee für die Party"));
+        assert_eq!(mail.text, "Hallo, wir feiern am 01.05.2021 und hätten gern Kaffee.");
+
+        let booking = Booking::from_mail(&mail, &Patterns::default());
+        assert_eq!(booking.event_name, "Kaffee für die Party");
+        assert_eq!(booking.first_name.as_deref(), Some("Graf"));
+        assert_eq!(booking.last_name.as_deref(), Some("Zahl"));
+        assert_eq!(booking.email.as_deref(), Some("graf@example.com"));
+        assert_eq!(booking.event_date, None);
+
+        let patterns = Patterns { event_date: Regex::new(r"am (\d+\.\d+\.\d{4})").ok(), ..Patterns::default() };
+        let booking = Booking::from_mail(&mail, &patterns);
+        assert_eq!(booking.event_date, NaiveDate::from_ymd_opt(2021, 5, 1));
+    }
+}
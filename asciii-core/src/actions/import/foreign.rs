@@ -0,0 +1,238 @@
+//! Creates projects from invoices of other tools, see `asciii import json`.
+//!
+//! Every tool gets an `Adapter` that reads its export into `ForeignInvoice`s,
+//! which are then written as asciii projects, one per invoice.
+//! `Generic` reads `ForeignInvoice`s as they are, for tools without an adapter:
+//!
+//! ```json
+//! [{"name": "Party Hard", "number": "2021-041", "date": "2021-05-01", "payed_date": "2021-05-12",
+//!   "client": {"first_name": "Graf", "last_name": "Zahl", "email": "graf@example.com"},
+//!   "items": [{"name": "Kaffee", "price": 2.5, "amount": 4, "tax": 19}]}]
+//! ```
+
+use std::path::PathBuf;
+
+use chrono::NaiveDate;
+use yaml_rust::{Yaml, YamlEmitter};
+use yaml_rust::yaml::Hash;
+
+use crate::error::{ActionError, AsciiiError};
+use crate::project::Project;
+use crate::storage::{Storable, Storage};
+
+use super::Outcome;
+
+pub use super::invoice_ninja::InvoiceNinja;
+
+/// Version of the format imported invoices are written in.
+const FORMAT: &str = "3.0.0";
+
+/// An invoice as another tool knows it.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ForeignInvoice {
+    /// becomes the event name, the client and number are used if there is none
+    pub name: Option<String>,
+    /// the invoice number of the other tool, kept as the official number
+    pub number: Option<String>,
+    pub date: Option<NaiveDate>,
+    pub payed_date: Option<NaiveDate>,
+    pub client: ForeignClient,
+    pub items: Vec<ForeignItem>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ForeignClient {
+    pub title: Option<String>,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub email: Option<String>,
+    pub address: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ForeignItem {
+    pub name: String,
+    /// net price of one unit
+    pub price: f64,
+    pub amount: f64,
+    /// in percent, the configured `defaults/tax` if missing
+    pub tax: Option<f64>,
+}
+
+/// Reads the export of another tool.
+pub trait Adapter {
+    /// The name `asciii import json --from` knows it by.
+    fn name(&self) -> &'static str;
+
+    fn read(&self, content: &str) -> Result<Vec<ForeignInvoice>, AsciiiError>;
+}
+
+/// A JSON list of `ForeignInvoice`s.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Generic;
+
+impl Adapter for Generic {
+    fn name(&self) -> &'static str {
+        "generic"
+    }
+
+    fn read(&self, content: &str) -> Result<Vec<ForeignInvoice>, AsciiiError> {
+        Ok(serde_json::from_str(content)?)
+    }
+}
+
+/// All known adapters.
+pub fn adapters() -> Vec<Box<dyn Adapter>> {
+    vec![Box::new(InvoiceNinja), Box::new(Generic)]
+}
+
+/// The adapter called `name`.
+pub fn adapter(name: &str) -> Result<Box<dyn Adapter>, AsciiiError> {
+    adapters().into_iter()
+        .find(|adapter| adapter.name() == name)
+        .ok_or_else(|| ActionError::Failed(lformat!("there is no importer for {:?}", name)).into())
+}
+
+fn string(value: &str) -> Yaml {
+    Yaml::String(value.to_owned())
+}
+
+fn section(entries: Vec<(&str, Yaml)>) -> Yaml {
+    Yaml::Hash(entries.into_iter()
+               .filter(|(_, value)| !value.is_null())
+               .map(|(key, value)| (string(key), value))
+               .collect::<Hash>())
+}
+
+fn opt_string(value: &Option<String>) -> Yaml {
+    value.as_deref().map_or(Yaml::Null, string)
+}
+
+fn opt_date(date: Option<NaiveDate>) -> Yaml {
+    date.map_or(Yaml::Null, |date| Yaml::String(date.format("%d.%m.%Y").to_string()))
+}
+
+fn real(value: f64) -> Yaml {
+    Yaml::Real(value.to_string())
+}
+
+impl ForeignInvoice {
+    /// The name of the project, see `name`.
+    pub fn project_name(&self) -> String {
+        self.name.clone().unwrap_or_else(|| {
+            let client = self.client.last_name.as_deref().unwrap_or("");
+            format!("{} {}", client, self.number.as_deref().unwrap_or("")).trim().to_owned()
+        })
+    }
+
+    /// The trailing digits of `number`, asciii counts invoices itself.
+    fn number_digits(&self) -> Option<i64> {
+        let number = self.number.as_deref()?;
+        let digits = number.trim_start_matches(|c: char| !c.is_ascii_digit());
+        digits.rsplit(|c: char| !c.is_ascii_digit()).next()?.parse().ok()
+    }
+
+    /// The content of a project file for this invoice.
+    pub fn to_project_content(&self) -> Result<String, AsciiiError> {
+        let client = &self.client;
+        let products = self.items.iter()
+            .map(|item| (string(&item.name), section(vec![
+                ("price", real(item.price)),
+                ("amount", real(item.amount)),
+                ("sold", real(item.amount)),
+                ("tax", item.tax.map_or(Yaml::Null, |tax| real(tax / 100.0))),
+            ])))
+            .collect::<Hash>();
+
+        let doc = section(vec![
+            ("client", section(vec![
+                // a missing title would be looked for in the old `client: "title\nname"` format
+                ("title", string(client.title.as_deref().unwrap_or(""))),
+                ("first_name", opt_string(&client.first_name)),
+                ("last_name", opt_string(&client.last_name)),
+                ("email", opt_string(&client.email)),
+                ("address", opt_string(&client.address)),
+            ])),
+            ("event", section(vec![
+                ("name", string(&self.project_name())),
+                ("dates", self.date.map_or(Yaml::Null, |_| Yaml::Array(vec![section(vec![("begin", opt_date(self.date))])]))),
+            ])),
+            ("offer", section(vec![
+                ("date", opt_date(self.date)),
+                ("appendix", Yaml::Integer(1)),
+            ])),
+            ("invoice", section(vec![
+                ("number", self.number_digits().map_or(Yaml::Null, Yaml::Integer)),
+                ("date", opt_date(self.date)),
+                ("payed_date", opt_date(self.payed_date)),
+                ("official", opt_string(&self.number)),
+            ])),
+            ("products", Yaml::Hash(products)),
+            // services were billed as items already
+            ("hours", section(vec![("salary", real(0.0))])),
+            ("manager", crate::CONFIG.get_str_or("user/name").map_or(Yaml::Null, string)),
+            ("canceled", Yaml::Boolean(false)),
+            ("created", opt_date(self.date)),
+            ("meta", section(vec![("format", string(FORMAT))])),
+        ]);
+
+        let mut content = String::new();
+        YamlEmitter::new(&mut content).dump(&doc)
+            .map_err(|e| ActionError::Failed(format!("{:?}", e)))?;
+        content.push('\n');
+        Project::from_file_content(&content)?;
+        Ok(content)
+    }
+}
+
+/// Creates a project for every invoice, a failing one does not stop the others.
+///
+/// With `dry_run` nothing is created, the invoices are only converted.
+pub fn create_projects(storage: &Storage<Project>, invoices: &[ForeignInvoice], dry_run: bool) -> Vec<Outcome> {
+    invoices.iter()
+        .zip(1..)
+        .map(|(invoice, line)| Outcome {
+            line,
+            name: Some(invoice.project_name()),
+            result: create_project(storage, invoice, dry_run),
+        })
+        .collect()
+}
+
+fn create_project(storage: &Storage<Project>, invoice: &ForeignInvoice, dry_run: bool) -> Result<PathBuf, AsciiiError> {
+    let name = invoice.project_name();
+    let content = invoice.to_project_content()?;
+    if dry_run {
+        return Ok(storage.new_project_paths(&name)?.1);
+    }
+    Ok(storage.create_project_from_content(&name, &content)?.file())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project::spec::*;
+
+    #[test]
+    fn generic_invoices_become_projects() {
+        let json = r#"[{"number": "RE-2021-041", "date": "2021-05-01", "payed_date": "2021-05-12",
+                        "client": {"first_name": "Graf", "last_name": "Zahl"},
+                        "items": [{"name": "Kaffee", "price": 2.5, "amount": 4, "tax": 19},
+                                  {"name": "Service", "price": 10, "amount": 2, "tax": 0}]}]"#;
+        let invoices = adapter("generic").unwrap().read(json).unwrap();
+        assert_eq!(invoices[0].project_name(), "Zahl RE-2021-041");
+
+        let project = Project::from_file_content(&invoices[0].to_project_content().unwrap()).unwrap();
+        assert_eq!(project.client().last_name(), Ok("Zahl"));
+        assert_eq!(project.field("invoice/number"), Some("41".into()));
+        assert_eq!(project.field("invoice/official"), Some("RE-2021-041".into()));
+        assert_eq!(project.field("invoice/payed_date"), Some("12.05.2021".into()));
+        assert_eq!(project.field("event/dates/0/begin"), Some("01.05.2021".into()));
+        assert_eq!(project.sum_sold().unwrap().value(), 3190);
+
+        assert!(adapter("word").is_err());
+    }
+}
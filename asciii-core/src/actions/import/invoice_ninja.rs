@@ -0,0 +1,154 @@
+//! Reads the JSON export of [InvoiceNinja](https://invoiceninja.com).
+//!
+//! Both the v4 export (`invoices`, `invoice_items`, `qty`) and the v5 one (`data`, `line_items`, `quantity`) are read.
+//! Clients are looked up by `client_id` in `clients`, payments by `invoice_id` in `payments`.
+//! An invoice counts as payed on the date of its last payment, once nothing is left to pay.
+
+use chrono::NaiveDate;
+use serde_json::Value;
+
+use crate::error::{ActionError, AsciiiError};
+
+use super::foreign::{Adapter, ForeignClient, ForeignInvoice, ForeignItem};
+
+/// See the module documentation.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct InvoiceNinja;
+
+/// The first of `keys` that is set.
+fn first<'a>(value: &'a Value, keys: &[&str]) -> Option<&'a Value> {
+    keys.iter().map(|key| &value[key]).find(|value| !value.is_null())
+}
+
+fn text(value: &Value, keys: &[&str]) -> Option<String> {
+    match first(value, keys)? {
+        Value::String(s) if s.trim().is_empty() => None,
+        Value::String(s) => Some(s.trim().to_owned()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// Numbers, which InvoiceNinja sometimes writes as strings.
+fn number(value: &Value, keys: &[&str]) -> Option<f64> {
+    match first(value, keys)? {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+fn date(value: &Value, keys: &[&str]) -> Option<NaiveDate> {
+    text(value, keys).and_then(|date| NaiveDate::parse_from_str(date.get(..10)?, "%Y-%m-%d").ok())
+}
+
+fn list<'a>(value: &'a Value, keys: &[&str]) -> &'a [Value] {
+    first(value, keys).and_then(Value::as_array).map_or(&[], Vec::as_slice)
+}
+
+fn client(client: &Value) -> ForeignClient {
+    let contact = list(client, &["contacts"]).first().unwrap_or(&Value::Null);
+    let street = [text(client, &["address1"]), text(client, &["address2"])];
+    let city = [text(client, &["postal_code"]), text(client, &["city"])];
+    let address = street.iter().flatten().cloned()
+        .chain(Some(city.iter().flatten().cloned().collect::<Vec<_>>().join(" ")).filter(|line| !line.is_empty()))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    ForeignClient {
+        title: None,
+        first_name: text(contact, &["first_name"]),
+        last_name: text(contact, &["last_name"]).or_else(|| text(client, &["name", "display_name"])),
+        email: text(contact, &["email"]),
+        address: Some(address).filter(|address| !address.is_empty()),
+    }
+}
+
+fn item(item: &Value) -> ForeignItem {
+    ForeignItem {
+        name: text(item, &["product_key", "notes"]).unwrap_or_else(|| lformat!("unnamed")),
+        price: number(item, &["cost"]).unwrap_or(0.0),
+        amount: number(item, &["quantity", "qty"]).unwrap_or(1.0),
+        tax: number(item, &["tax_rate1"]),
+    }
+}
+
+impl Adapter for InvoiceNinja {
+    fn name(&self) -> &'static str {
+        "invoiceninja"
+    }
+
+    fn read(&self, content: &str) -> Result<Vec<ForeignInvoice>, AsciiiError> {
+        let export: Value = serde_json::from_str(content)?;
+        let invoices = list(&export, &["invoices", "data"]);
+        if invoices.is_empty() && first(&export, &["invoices", "data"]).is_none() {
+            return Err(ActionError::Failed(lformat!("no invoices in this file")).into());
+        }
+        let clients = list(&export, &["clients"]);
+        let payments = list(&export, &["payments"]);
+
+        Ok(invoices.iter()
+           .filter(|invoice| !invoice["is_deleted"].as_bool().unwrap_or(false))
+           .map(|invoice| {
+               let id = first(invoice, &["id"]);
+               let client_of_invoice = match first(invoice, &["client"]) {
+                   Some(client) if client.is_object() => client,
+                   _ => first(invoice, &["client_id"])
+                       .and_then(|client_id| clients.iter().find(|client| first(client, &["id"]) == Some(client_id)))
+                       .unwrap_or(&Value::Null),
+               };
+               let last_payment = payments.iter()
+                   .filter(|payment| id.is_some() && first(payment, &["invoice_id"]) == id)
+                   .filter_map(|payment| date(payment, &["payment_date", "date"]))
+                   .max();
+               let balance = number(invoice, &["balance"]).unwrap_or(0.0);
+
+               ForeignInvoice {
+                   name: None,
+                   number: text(invoice, &["number", "invoice_number"]),
+                   date: date(invoice, &["date", "invoice_date"]),
+                   payed_date: last_payment.filter(|_| balance.abs() < 0.005),
+                   client: client(client_of_invoice),
+                   items: list(invoice, &["line_items", "invoice_items"]).iter().map(item).collect(),
+               }
+           })
+           .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXPORT: &str = r#"{
+        "clients": [{"id": 7, "name": "Zahl GmbH", "address1": "Nummernhöllenstraße 666", "postal_code": "01234", "city": "Countilvania",
+                     "contacts": [{"first_name": "Graf", "last_name": "Zahl", "email": "graf@example.com"}]}],
+        "invoices": [{"id": 1, "client_id": 7, "invoice_number": "0041", "invoice_date": "2021-05-01", "balance": "0.00",
+                      "invoice_items": [{"product_key": "Kaffee", "cost": "2.50", "qty": "4", "tax_rate1": "19"}]},
+                     {"id": 2, "client_id": 7, "invoice_number": "0042", "invoice_date": "2021-06-01", "balance": 10,
+                      "invoice_items": [{"notes": "Service", "cost": 10, "qty": 1}]},
+                     {"id": 3, "is_deleted": true}],
+        "payments": [{"invoice_id": 1, "payment_date": "2021-05-12", "amount": 11.9},
+                     {"invoice_id": 2, "payment_date": "2021-06-03", "amount": 1.9}]
+    }"#;
+
+    #[test]
+    fn reads_exports() {
+        let invoices = InvoiceNinja.read(EXPORT).unwrap();
+        assert_eq!(invoices.len(), 2);
+
+        let payed = &invoices[0];
+        assert_eq!(payed.number.as_deref(), Some("0041"));
+        assert_eq!(payed.date, NaiveDate::from_ymd_opt(2021, 5, 1));
+        assert_eq!(payed.payed_date, NaiveDate::from_ymd_opt(2021, 5, 12));
+        assert_eq!(payed.client.last_name.as_deref(), Some("Zahl"));
+        assert_eq!(payed.client.address.as_deref(), Some("Nummernhöllenstraße 666\n01234 Countilvania"));
+        assert_eq!(payed.items, vec![ForeignItem { name: "Kaffee".into(), price: 2.5, amount: 4.0, tax: Some(19.0) }]);
+        assert!(payed.to_project_content().is_ok());
+
+        assert_eq!(invoices[1].payed_date, None);
+        assert_eq!(invoices[1].items[0].name, "Service");
+
+        assert!(InvoiceNinja.read("{}").is_err());
+    }
+}
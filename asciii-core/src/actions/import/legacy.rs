@@ -0,0 +1,354 @@
+//! Converts projects of the ruby `ascii-invoicer` into the current format, see `asciii import legacy`.
+//!
+//! The old files kept most fields at the top level, `client` and `signature` were multi line strings
+//! and `date` could be a range like `01-03.05.2013`. Fields are moved according to `MOVED`,
+//! dates are normalized to `dd.mm.YYYY`.
+//! Whatever can't be mapped ends up under `legacy:` in the new file and is reported, so nothing is lost.
+
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{Datelike, NaiveDate};
+use yaml_rust::{Yaml, YamlEmitter};
+use yaml_rust::yaml::Hash;
+
+use crate::error::{ActionError, AsciiiError};
+use crate::project::Project;
+use crate::storage::{Storable, Storage};
+use crate::util::yaml;
+
+/// Old top level fields and where they are now.
+pub const MOVED: &[(&str, &str)] = &[
+    ("email",        "client/email"),
+    ("address",      "client/address"),
+    ("description",  "event/description"),
+    ("rnumber",      "invoice/number"),
+    ("invoice_date", "invoice/date"),
+    ("payed_date",   "invoice/payed_date"),
+    ("wages_date",   "hours/wages_date"),
+];
+
+/// Top level fields that mean the same in both formats.
+const KEPT: &[&str] = &[
+    "client", "event", "offer", "invoice", "hours", "products", "cataloge",
+    "manager", "canceled", "tax", "created", "lang", "messages", "expenses", "meta",
+];
+
+/// Fields that hold dates once everything is moved.
+const DATES: &[&str] = &["created", "offer/date", "invoice/date", "invoice/payed_date", "hours/wages_date"];
+
+/// Date formats of the old files, the first one is also the current one.
+const DATE_FORMATS: &[&str] = &["%d.%m.%Y", "%d.%m.%y", "%Y-%m-%d"];
+
+/// Version of the format imported files are converted to.
+const FORMAT: &str = "3.0.0";
+
+/// A converted project file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Converted {
+    pub name: String,
+    pub content: String,
+    /// old fields that were kept under `legacy:`
+    pub unmapped: Vec<String>,
+    /// fields that were mapped but have odd values
+    pub problems: Vec<String>,
+}
+
+/// Converts the content of an old project file, `fallback_name` names projects without an event name.
+pub fn convert(content: &str, fallback_name: &str) -> Result<Converted, AsciiiError> {
+    let old = yaml::parse(content)?;
+    let old = old.as_hash()
+        .ok_or_else(|| ActionError::Failed(lformat!("not a project file")))?;
+
+    let mut new = Hash::new();
+    let mut legacy = Hash::new();
+    let mut unmapped = Vec::new();
+    let mut problems = Vec::new();
+
+    for (key, value) in old {
+        let Some(key) = key.as_str() else { continue };
+        match (key, value) {
+            ("client", Yaml::String(client)) => convert_client(client, &mut new),
+            ("event", Yaml::String(name)) => insert(&mut new, "event/name", Yaml::String(name.clone())),
+            ("signature", Yaml::String(signature)) => {
+                if let Some(manager) = signature.lines().map(str::trim).rfind(|line| !line.is_empty()) {
+                    insert(&mut new, "manager", Yaml::String(manager.to_owned()));
+                }
+            }
+            ("date", Yaml::String(date)) => match convert_date_range(date) {
+                Some(dates) => insert(&mut new, "event/dates", Yaml::Array(vec![dates])),
+                None => {
+                    problems.push(lformat!("{:?} is not a date or date range: {:?}", "date", date));
+                    legacy.insert(Yaml::String(key.to_owned()), value.clone());
+                }
+            },
+            ("manumber", Yaml::String(number)) => match convert_offer_number(number) {
+                Some((date, appendix)) => {
+                    insert(&mut new, "offer/date", Yaml::String(date));
+                    insert(&mut new, "offer/appendix", Yaml::Integer(appendix));
+                }
+                None => {
+                    unmapped.push(key.to_owned());
+                    legacy.insert(Yaml::String(key.to_owned()), value.clone());
+                }
+            },
+            ("format", _) => {}
+            _ => {
+                if let Some((_, to)) = MOVED.iter().find(|(from, _)| *from == key) {
+                    insert(&mut new, to, value.clone());
+                } else if KEPT.contains(&key) {
+                    merge(&mut new, key, value.clone());
+                } else {
+                    unmapped.push(key.to_owned());
+                    legacy.insert(Yaml::String(key.to_owned()), value.clone());
+                }
+            }
+        }
+    }
+
+    for path in DATES {
+        let Some(date) = get(&new, path) else { continue };
+        match date.as_str().map(normalize_date) {
+            Some(Some(normalized)) => insert(&mut new, path, Yaml::String(normalized)),
+            _ => problems.push(lformat!("{:?} is not a date: {:?}", path, yaml_to_string(date))),
+        }
+    }
+
+    insert(&mut new, "meta/format", Yaml::String(FORMAT.into()));
+    if !legacy.is_empty() {
+        new.insert(Yaml::String("legacy".into()), Yaml::Hash(legacy));
+    }
+
+    let name = get(&new, "event/name")
+        .and_then(Yaml::as_str)
+        .map(ToOwned::to_owned)
+        .unwrap_or_else(|| fallback_name.to_owned());
+
+    let mut content = String::new();
+    YamlEmitter::new(&mut content).dump(&Yaml::Hash(new))
+        .map_err(|e| ActionError::Failed(format!("{:?}", e)))?;
+    content.push('\n');
+
+    Project::from_file_content(&content)?;
+    Ok(Converted { name, content, unmapped, problems })
+}
+
+/// `"Herr\nGraf Zahl"` becomes title, first and last name.
+fn convert_client(client: &str, new: &mut Hash) {
+    let mut lines = client.lines().map(str::trim).filter(|line| !line.is_empty());
+    if let Some(title) = lines.next() {
+        insert(new, "client/title", Yaml::String(title.to_owned()));
+    }
+    if let Some(name) = lines.next() {
+        match name.rsplit_once(' ') {
+            Some((first, last)) => {
+                insert(new, "client/first_name", Yaml::String(first.trim().to_owned()));
+                insert(new, "client/last_name", Yaml::String(last.to_owned()));
+            }
+            None => insert(new, "client/last_name", Yaml::String(name.to_owned())),
+        }
+    }
+}
+
+/// `"01.05.2013"` or `"01-03.05.2013"` becomes `{begin, end}`.
+fn convert_date_range(date: &str) -> Option<Yaml> {
+    let mut range = Hash::new();
+    if let Some(begin) = normalize_date(date) {
+        range.insert(Yaml::String("begin".into()), Yaml::String(begin));
+    } else {
+        let (first_day, last) = date.trim().split_once('-')?;
+        let month_and_year = &last[last.find('.')?..];
+        range.insert(Yaml::String("begin".into()), Yaml::String(normalize_date(&format!("{}{}", first_day, month_and_year))?));
+        range.insert(Yaml::String("end".into()), Yaml::String(normalize_date(last)?));
+    }
+    Some(Yaml::Hash(range))
+}
+
+/// `"A20130502-2"` becomes the offer date and appendix.
+fn convert_offer_number(number: &str) -> Option<(String, i64)> {
+    let (date, appendix) = number.trim().strip_prefix('A')?.split_once('-')?;
+    let date = NaiveDate::parse_from_str(date, "%Y%m%d").ok()?;
+    Some((date.format(DATE_FORMATS[0]).to_string(), appendix.parse().ok()?))
+}
+
+/// Any of `DATE_FORMATS` in the current format.
+///
+/// `%Y` also reads two digit years as 13 AD, those are left to `%y`.
+fn normalize_date(date: &str) -> Option<String> {
+    DATE_FORMATS.iter()
+        .filter_map(|format| NaiveDate::parse_from_str(date.trim(), format).ok())
+        .find(|date| date.year() >= 1000)
+        .map(|date| date.format(DATE_FORMATS[0]).to_string())
+}
+
+fn yaml_to_string(value: &Yaml) -> String {
+    match *value {
+        Yaml::String(ref s) | Yaml::Real(ref s) => s.clone(),
+        Yaml::Integer(i) => i.to_string(),
+        _ => format!("{:?}", value),
+    }
+}
+
+fn get<'a>(doc: &'a Hash, path: &str) -> Option<&'a Yaml> {
+    let mut parts = path.split('/');
+    let mut current = doc.get(&Yaml::String(parts.next()?.to_owned()))?;
+    for part in parts {
+        current = current.as_hash()?.get(&Yaml::String(part.to_owned()))?;
+    }
+    Some(current)
+}
+
+/// Sets `path` in `doc`, creating the sections on the way.
+fn insert(doc: &mut Hash, path: &str, value: Yaml) {
+    match path.split_once('/') {
+        Some((section, rest)) => {
+            let entry = doc.entry(Yaml::String(section.to_owned())).or_insert_with(|| Yaml::Hash(Hash::new()));
+            if entry.as_hash().is_none() {
+                *entry = Yaml::Hash(Hash::new());
+            }
+            if let Yaml::Hash(ref mut section) = *entry {
+                insert(section, rest, value);
+            }
+        }
+        None => {
+            doc.insert(Yaml::String(path.to_owned()), value);
+        }
+    }
+}
+
+/// Like `insert()`, but sections that are already there are merged, keeping what is set.
+fn merge(doc: &mut Hash, key: &str, value: Yaml) {
+    match (doc.get_mut(&Yaml::String(key.to_owned())), value) {
+        (Some(Yaml::Hash(existing)), Yaml::Hash(value)) => {
+            for (k, v) in value {
+                existing.entry(k).or_insert(v);
+            }
+        }
+        (_, value) => {
+            doc.insert(Yaml::String(key.to_owned()), value);
+        }
+    }
+}
+
+/// Project files at or below `path`, a single file is taken as it is.
+pub fn find_files(path: &Path) -> Result<Vec<PathBuf>, AsciiiError> {
+    if path.is_file() {
+        return Ok(vec![path.to_owned()]);
+    }
+    let extension = crate::CONFIG.get_to_string("extensions.project_file");
+    let mut files = Vec::new();
+    let mut dirs = vec![path.to_owned()];
+    while let Some(dir) = dirs.pop() {
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                dirs.push(path);
+            } else if path.extension().and_then(|e| e.to_str()) == Some(extension.as_str()) {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Result of importing a single old project file.
+#[derive(Debug)]
+pub struct Outcome {
+    pub source: PathBuf,
+    pub result: Result<Converted, AsciiiError>,
+    /// the new project file
+    pub file: Option<PathBuf>,
+}
+
+impl Outcome {
+    pub fn is_ok(&self) -> bool {
+        self.result.is_ok()
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.result {
+            Ok(ref converted) => {
+                let file = self.file.as_ref().map(|file| file.display().to_string()).unwrap_or_default();
+                write!(f, "{} {:?}: {} -> {}", lformat!("ok"), converted.name, self.source.display(), file)?;
+                for key in &converted.unmapped {
+                    write!(f, "\n    {}", lformat!("kept {:?} under legacy", key))?;
+                }
+                for problem in &converted.problems {
+                    write!(f, "\n    {}", problem)?;
+                }
+                Ok(())
+            }
+            Err(ref e) => write!(f, "{} {}: {}", lformat!("failed"), self.source.display(), e),
+        }
+    }
+}
+
+/// Converts every old project file at or below `path` into a new project in the working directory.
+///
+/// With `dry_run` nothing is created, the files are only converted.
+pub fn import(storage: &Storage<Project>, path: &Path, dry_run: bool) -> Result<Vec<Outcome>, AsciiiError> {
+    Ok(find_files(path)?
+        .into_iter()
+        .map(|source| {
+            let fallback_name = source.file_stem().and_then(|s| s.to_str()).unwrap_or("imported").to_owned();
+            let mut file = None;
+            let result = fs::read_to_string(&source)
+                .map_err(AsciiiError::from)
+                .and_then(|content| convert(&content, &fallback_name))
+                .and_then(|converted| {
+                    file = Some(if dry_run {
+                        storage.new_project_paths(&converted.name)?.1
+                    } else {
+                        storage.create_project_from_content(&converted.name, &converted.content)?.file()
+                    });
+                    Ok(converted)
+                });
+            Outcome { source, result, file }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project::spec::*;
+
+    const OLD: &str = "client: |\n  Herr\n  Graf Zahl\naddress: |\n  Nummernhöllenstraße 666\n  01234 Countilvania\nemail: graf@example.com\nevent: Party Hard\ndate: 01-03.11.2013\nmanumber: A20131020-2\nrnumber: 36\ninvoice_date: 2013-12-06\npayed_date: 08.12.13\nsignature: |\n  Mit freundlichen Grüßen\n  Tester\nformat: 1.0\nhours:\n  salary: 8.0\n  caterers:\n    Maxime: 2\nproducts:\n  Kaffee:\n    amount: 5\n    price: 2.5\nroom: Foyer\n";
+
+    #[test]
+    fn converts_old_fields() {
+        let converted = convert(OLD, "party").unwrap();
+        assert_eq!(converted.name, "Party Hard");
+        assert_eq!(converted.unmapped, vec!["room".to_owned()]);
+        assert!(converted.problems.is_empty(), "{:?}", converted.problems);
+
+        let project = Project::from_file_content(&converted.content).unwrap();
+        assert_eq!(project.name(), Ok("Party Hard"));
+        assert_eq!(project.responsible(), Ok("Tester"));
+        assert_eq!(project.client().title(), Ok("Herr"));
+        assert_eq!(project.client().first_name(), Ok("Graf"));
+        assert_eq!(project.client().last_name(), Ok("Zahl"));
+        assert_eq!(project.client().email(), Ok("graf@example.com"));
+        assert_eq!(project.field("event/dates/0/begin"), Some("01.11.2013".into()));
+        assert_eq!(project.field("event/dates/0/end"), Some("03.11.2013".into()));
+        assert_eq!(project.field("offer/date"), Some("20.10.2013".into()));
+        assert_eq!(project.field("offer/appendix"), Some("2".into()));
+        assert_eq!(project.field("invoice/number"), Some("36".into()));
+        assert_eq!(project.field("invoice/date"), Some("06.12.2013".into()));
+        assert_eq!(project.field("invoice/payed_date"), Some("08.12.2013".into()));
+        assert_eq!(project.field("meta/format"), Some(FORMAT.into()));
+        assert_eq!(project.field("legacy/room"), Some("Foyer".into()));
+        assert!(project.sum_sold().is_ok());
+    }
+
+    #[test]
+    fn reports_odd_dates() {
+        let converted = convert("event: Fest\ndate: someday\ninvoice_date: tomorrow\n", "fest").unwrap();
+        assert_eq!(converted.problems.len(), 2);
+        assert!(convert("- not\n- a project\n", "list").is_err());
+    }
+}
@@ -0,0 +1,184 @@
+//! Equipment that is rented out, and whether there is enough of it, see `asciii validate`.
+//!
+//! Products in the `cataloge` of a project can say how many there are in `stock`:
+//!
+//! ```yaml
+//! cataloge:
+//!   stehtisch: &stehtisch { name: Stehtisch, price: 8.0, unit: Stück, stock: 20 }
+//! ```
+//!
+//! Every project books the offered amount from the first day of its event to the last one.
+//! Projects whose events overlap share the stock, if more is booked on one day than there is,
+//! each of them gets a warning. If projects disagree on the stock, the smallest count is used.
+
+use chrono::NaiveDate;
+
+use crate::project::error::{Diagnostic, Severity};
+use crate::project::spec::{HasEvents, IsProject, Redeemable};
+use crate::project::Project;
+use crate::storage::Storable;
+
+/// Counts of the products in the `cataloge` of `project` that have a `stock`.
+pub fn stock(project: &Project) -> Vec<(String, f64)> {
+    let cataloge = match project.yaml()["cataloge"].as_hash() {
+        Some(cataloge) => cataloge,
+        None => return Vec::new(),
+    };
+    cataloge.values()
+            .filter_map(|product| {
+                let name = product["name"].as_str()?;
+                let stock = &product["stock"];
+                let count = stock.as_f64().or_else(|| stock.as_i64().map(|count| count as f64))?;
+                Some((name.to_owned(), count))
+            })
+            .collect()
+}
+
+/// First and last day of the events of `project`.
+pub fn booked_days(project: &Project) -> Option<(NaiveDate, NaiveDate)> {
+    let events = project.events().filter(|events| !events.is_empty());
+    match events {
+        Some(events) => {
+            let first = events.iter().map(|event| event.begin).min()?;
+            let last = events.iter().map(|event| event.end.unwrap_or(event.begin)).max()?;
+            Some((first, last))
+        }
+        None => project.event_date().ok().map(|date| (date.naive_utc(), date.naive_utc())),
+    }
+}
+
+/// One project's share of a `Shortage`.
+#[derive(Debug, Clone)]
+pub struct Booking<'a> {
+    pub project: &'a Project,
+    pub amount: f64,
+}
+
+/// More of a product is booked on `date` than there is.
+#[derive(Debug, Clone)]
+pub struct Shortage<'a> {
+    pub product: String,
+    pub date: NaiveDate,
+    pub stock: f64,
+    pub bookings: Vec<Booking<'a>>,
+}
+
+impl<'a> Shortage<'a> {
+    pub fn booked(&self) -> f64 {
+        self.bookings.iter().map(|booking| booking.amount).sum()
+    }
+
+    /// A warning for each project involved.
+    pub fn diagnostics(&self) -> Vec<Diagnostic> {
+        let names = self.bookings.iter()
+                                 .map(|booking| booking.project.short_desc())
+                                 .collect::<Vec<_>>()
+                                 .join(", ");
+        self.bookings.iter()
+            .map(|booking| Diagnostic {
+                file: booking.project.file(),
+                field: "products".into(),
+                stage: "offer".into(),
+                severity: Severity::Warning,
+                message: lformat!("{} {} booked on {} but only {} in stock ({})",
+                                  self.booked(), self.product, self.date.format("%d.%m.%Y"), self.stock, names),
+            })
+            .collect()
+    }
+}
+
+struct Rental<'a> {
+    index: usize,
+    project: &'a Project,
+    first: NaiveDate,
+    last: NaiveDate,
+    amount: f64,
+}
+
+/// Days on which `projects` book more of a product than there is in stock, one per product and overlap.
+pub fn shortages(projects: &[Project]) -> Vec<Shortage<'_>> {
+    let mut stocks: Vec<(String, f64)> = Vec::new();
+    for (name, count) in projects.iter().filter(|project| !project.canceled()).flat_map(stock) {
+        match stocks.iter_mut().find(|(known, _)| known.eq_ignore_ascii_case(&name)) {
+            Some((_, known)) => *known = known.min(count),
+            None => stocks.push((name, count)),
+        }
+    }
+
+    let mut shortages = Vec::new();
+    for (product, stock) in stocks {
+        let rentals = projects.iter()
+                              .enumerate()
+                              .filter(|(_, project)| !project.canceled())
+                              .filter_map(|(index, project)| {
+                                  let (first, last) = booked_days(project)?;
+                                  let (offer, _) = project.bills().ok()?;
+                                  let amount = offer.as_items_with_tax().into_iter()
+                                                    .filter(|(_, item)| item.product.name.eq_ignore_ascii_case(&product))
+                                                    .map(|(_, item)| item.amount)
+                                                    .sum::<f64>();
+                                  Some(Rental { index, project, first, last, amount }).filter(|rental| rental.amount > 0.0)
+                              })
+                              .collect::<Vec<_>>();
+
+        // the most is booked at the first day of one of the rentals
+        let mut reported: Vec<Vec<usize>> = Vec::new();
+        let mut days = rentals.iter().map(|rental| rental.first).collect::<Vec<_>>();
+        days.sort();
+        days.dedup();
+        for date in days {
+            let overlapping = rentals.iter()
+                                     .filter(|rental| rental.first <= date && date <= rental.last)
+                                     .collect::<Vec<_>>();
+            let involved = overlapping.iter().map(|rental| rental.index).collect::<Vec<_>>();
+            let shortage = Shortage {
+                product: product.clone(),
+                date,
+                stock,
+                bookings: overlapping.iter().map(|rental| Booking { project: rental.project, amount: rental.amount }).collect(),
+            };
+            if shortage.booked() > stock && !reported.contains(&involved) {
+                reported.push(involved);
+                shortages.push(shortage);
+            }
+        }
+    }
+    shortages
+}
+
+/// Warnings for every project that takes part in a `Shortage`.
+pub fn diagnostics(projects: &[Project]) -> Vec<Diagnostic> {
+    shortages(projects).iter().flat_map(Shortage::diagnostics).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rental(name: &str, dates: &str, tables: u32) -> Project {
+        Project::from_file_content(&format!(
+            "event:\n  name: {}\n  dates:\n{}\
+             cataloge:\n  tisch: &tisch {{ name: Stehtisch, price: 8.0, unit: Stück, stock: 20 }}\n\
+             products:\n  *tisch:\n    amount: {}\nhours:\n  salary: 8.0\n",
+            name, dates, tables)).unwrap()
+    }
+
+    #[test]
+    fn overlapping_rentals() {
+        let projects = vec![rental("Sommerfest", "  - begin: 01.07.2024\n    end: 03.07.2024\n", 12),
+                            rental("Hochzeit", "  - begin: 03.07.2024\n", 10),
+                            rental("Herbstfest", "  - begin: 01.10.2024\n", 20)];
+        assert_eq!(stock(&projects[0]), vec![("Stehtisch".to_owned(), 20.0)]);
+        assert_eq!(booked_days(&projects[0]).map(|(_, last)| last), NaiveDate::from_ymd_opt(2024, 7, 3));
+
+        let shortages = shortages(&projects);
+        assert_eq!(shortages.len(), 1);
+        assert_eq!(shortages[0].date, NaiveDate::from_ymd_opt(2024, 7, 3).unwrap());
+        assert_eq!(shortages[0].booked(), 22.0);
+        assert_eq!(diagnostics(&projects).len(), 2);
+
+        let apart = vec![rental("Sommerfest", "  - begin: 01.07.2024\n    end: 02.07.2024\n", 12),
+                         rental("Hochzeit", "  - begin: 03.07.2024\n", 10)];
+        assert!(super::shortages(&apart).is_empty());
+    }
+}
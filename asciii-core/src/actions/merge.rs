@@ -0,0 +1,184 @@
+//! Merging two projects about the same event, see `asciii merge`.
+//!
+//! The richer project is kept. Every settable field (`spec::SETTABLE_FIELDS`) it lacks is taken from the other one,
+//! fields both have but disagree on are conflicts the caller decides.
+//! Lists it lacks, like the products, are copied over as they are written.
+
+use std::fs;
+use std::path::PathBuf;
+
+use crate::error::{ActionError, AsciiiError};
+use crate::project::spec::SETTABLE_FIELDS;
+use crate::project::Project;
+use crate::storage::Storable;
+use crate::util::yaml::{self, Yaml};
+
+/// Lists copied as a whole, with the blocks they depend on, `cataloge` holds the anchors of `products`.
+const LISTS: &[(&str, &[&str])] = &[
+    ("products",       &["cataloge", "products"]),
+    ("event/dates",    &["event/dates"]),
+    ("hours/caterers", &["hours/caterers"]),
+];
+
+/// Both projects have a different value for `field`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Conflict {
+    pub field: &'static str,
+    pub kept: String,
+    pub other: String,
+}
+
+impl Conflict {
+    /// The longer value, it most likely says more.
+    pub fn richer(&self) -> &str {
+        if self.other.trim().len() > self.kept.trim().len() { &self.other } else { &self.kept }
+    }
+}
+
+/// What merging `other` into the kept project changes.
+#[derive(Debug, Default)]
+pub struct Merge {
+    /// fields only the other project has
+    pub fill: Vec<(&'static str, String)>,
+    pub conflicts: Vec<Conflict>,
+    /// lists only the other project has
+    pub lists: Vec<&'static str>,
+}
+
+fn value(project: &Project, field: &str) -> Option<String> {
+    yaml::get_to_string(project.yaml(), field)
+        .map(|value| value.trim().to_owned())
+        .filter(|value| !value.is_empty() && !value.starts_with("##"))
+}
+
+/// Lists without a single value count as missing, templates leave those behind.
+fn is_empty(yaml: &Yaml) -> bool {
+    match *yaml {
+        Yaml::Null | Yaml::BadValue => true,
+        Yaml::String(ref s) => s.trim().is_empty() || s.starts_with("##"),
+        Yaml::Array(ref items) => items.iter().all(is_empty),
+        Yaml::Hash(ref hash) => hash.values().all(is_empty),
+        _ => false
+    }
+}
+
+fn has_list(project: &Project, list: &str) -> bool {
+    yaml::get(project.yaml(), list).is_some_and(|yaml| !is_empty(yaml))
+}
+
+/// How much a project has to say, the richer one of two is kept.
+pub fn richness(project: &Project) -> usize {
+    SETTABLE_FIELDS.iter().filter(|(field, _)| value(project, field).is_some()).count()
+    + LISTS.iter().filter(|(list, _)| has_list(project, list)).count()
+}
+
+/// Compares `kept` to `other`, nothing is changed yet.
+pub fn plan(kept: &Project, other: &Project) -> Merge {
+    let mut merge = Merge::default();
+    for &(field, _) in SETTABLE_FIELDS {
+        match (value(kept, field), value(other, field)) {
+            (None, Some(theirs)) => merge.fill.push((field, theirs)),
+            (Some(ours), Some(theirs)) if ours != theirs => merge.conflicts.push(Conflict { field, kept: ours, other: theirs }),
+            _ => {}
+        }
+    }
+    merge.lists = LISTS.iter()
+                       .filter(|(list, _)| !has_list(kept, list) && has_list(other, list))
+                       .map(|&(list, _)| list)
+                       .collect();
+    merge
+}
+
+/// Content of the kept project after the merge, `choices` are the decided conflicts.
+pub fn merged_content(kept: &Project, other: &Project, merge: &Merge, choices: &[(&'static str, String)]) -> Result<String, AsciiiError> {
+    let fields = merge.fill.iter().chain(choices)
+                      .filter(|(field, new)| value(kept, field).as_ref() != Some(new))
+                      .map(|(field, new)| (*field, new.as_str()))
+                      .collect::<Vec<_>>();
+    let mut content = kept.content_with_fields(&fields)?;
+
+    for &(list, blocks) in LISTS.iter().filter(|(list, _)| merge.lists.contains(list)) {
+        for block in blocks {
+            let text = match yaml::block_in_text(other.file_content(), block) {
+                Some(text) => text,
+                None => continue
+            };
+            if let Some(removed) = yaml::remove_in_text(&content, block) {
+                content = removed;
+            }
+            content = match yaml::insert_block_in_text(&content, block, &text) {
+                Some(inserted) => inserted,
+                None => return Err(ActionError::Failed(lformat!("can't copy {} from the other project", list)).into())
+            };
+        }
+    }
+
+    // anchors and indentation may not fit together, better fail than write a broken project
+    let merged = Project::from_file_content(&content)?;
+    for &(list, _) in LISTS.iter().filter(|(list, _)| merge.lists.contains(list)) {
+        if !has_list(&merged, list) {
+            return Err(ActionError::Failed(lformat!("can't copy {} from the other project", list)).into());
+        }
+    }
+    Ok(content)
+}
+
+/// Moves every file but the project file from `other` to the directory of `kept`.
+///
+/// Files that `kept` already has get the name of the other directory appended, nothing is overwritten.
+/// Returns where each file went.
+pub fn move_attachments(kept: &Project, other: &Project) -> Result<Vec<(PathBuf, PathBuf)>, AsciiiError> {
+    let suffix = other.dir().file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+    let mut moved = Vec::new();
+    for entry in fs::read_dir(other.dir())? {
+        let from = entry?.path();
+        if from == other.file() {
+            continue;
+        }
+        let name = from.file_name().unwrap_or_default();
+        let mut to = kept.dir().join(name);
+        if to.exists() {
+            let stem = from.file_stem().unwrap_or_default().to_string_lossy();
+            to = match from.extension() {
+                Some(extension) => kept.dir().join(format!("{}-{}.{}", stem, suffix, extension.to_string_lossy())),
+                None => kept.dir().join(format!("{}-{}", stem, suffix))
+            };
+        }
+        if to.exists() {
+            return Err(ActionError::Failed(lformat!("{} already exists", to.display())).into());
+        }
+        fs::rename(&from, &to)?;
+        moved.push((from, to));
+    }
+    Ok(moved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RICH: &str = "event:\n  name: Party\n  location: Mensa\n  dates:\n  - begin:\nmanager: Max\ntax: 0.19\noffer:\n  date: 01.12.2024\ninvoice:\n  number: 41\n";
+    const POOR: &str = "client:\n  email: graf@zahl.de\nevent:\n  name: Party Hard\n  dates:\n  - begin: 24.12.2024\ncataloge:\n  product: &tee { name: Tee, price: 1.5, unit: 1l }\nproducts:\n  *tee:\n    amount: 2\nmanager: Max\n";
+
+    #[test]
+    fn keeps_richer_values() {
+        let rich = Project::from_file_content(RICH).unwrap();
+        let poor = Project::from_file_content(POOR).unwrap();
+        assert!(richness(&rich) >= richness(&poor));
+
+        let merge = plan(&rich, &poor);
+        assert_eq!(merge.fill, vec![("client/email", "graf@zahl.de".to_owned())]);
+        assert_eq!(merge.conflicts.len(), 1);
+        assert_eq!(merge.conflicts[0].richer(), "Party Hard");
+        assert_eq!(merge.lists, vec!["products", "event/dates"]);
+
+        let choices = merge.conflicts.iter().map(|c| (c.field, c.richer().to_owned())).collect::<Vec<_>>();
+        let merged = Project::from_file_content(&merged_content(&rich, &poor, &merge, &choices).unwrap()).unwrap();
+        assert_eq!(merged.field("event/name"), Some("Party Hard".into()));
+        assert_eq!(merged.field("event/location"), Some("Mensa".into()));
+        assert_eq!(merged.field("client/email"), Some("graf@zahl.de".into()));
+        assert_eq!(merged.field("invoice/number"), Some("41".into()));
+        assert!(has_list(&merged, "products"));
+        assert!(has_list(&merged, "event/dates"));
+    }
+}
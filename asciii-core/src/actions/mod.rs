@@ -6,31 +6,52 @@ use bill::Currency;
 use icalendar::Calendar;
 #[cfg(feature = "meta")]
 use toml;
-use anyhow::Error;
 
 use std::fmt::Write;
 #[cfg(feature = "meta")] use std::fs;
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::collections::HashMap;
 use std::process::Command;
 
+use crate::error::AsciiiError;
 use crate::util;
-use crate::storage::{self, StorageDir, Storable};
+use crate::storage::{self, SortOrder, StorageDir, Storable};
 use crate::project::Project;
 use crate::project::spec::*;
 
 pub mod error;
+pub mod activity;
+pub mod anonymize;
+pub mod branding;
+pub mod closing;
+pub mod dedupe;
+pub mod demo;
+pub mod digest;
+pub mod doctor;
+pub mod graph;
+pub mod import;
+pub mod inventory;
+pub mod merge;
+pub mod report;
+pub mod staffing;
+pub mod summary;
+pub mod template;
+pub mod timesheet;
+pub mod users;
 use self::error::*;
 
 /// Helper method that passes projects matching the `search_terms` to the passt closure `f`
-pub fn with_projects<F>(dir:StorageDir, search_terms: &[&str], f:F) -> Result<(), Error>
-    where F:Fn(&Project)->Result<(), Error>
+///
+/// `f` may fail with errors of its own, as long as an `AsciiiError` can be turned into them.
+pub fn with_projects<F, E>(dir:StorageDir, search_terms: &[&str], f:F) -> Result<(), E>
+    where F:Fn(&Project)->Result<(), E>,
+          E: From<AsciiiError>
 {
     log::trace!("with_projects({:?})", search_terms);
     let projects = storage::setup::<Project>()?.search_projects_any(dir, search_terms)?;
     if projects.is_empty() {
-        anyhow::bail!(ActionError::NothingFound(search_terms.iter().map(ToString::to_string).collect() ));
+        return Err(AsciiiError::from(ActionError::NothingFound(search_terms.iter().map(ToString::to_string).collect())).into());
     }
     for project in projects {
         f(&project)?;
@@ -38,14 +59,14 @@ pub fn with_projects<F>(dir:StorageDir, search_terms: &[&str], f:F) -> Result<()
     Ok(())
 }
 
-pub fn csv(year:i32) -> Result<String, Error> {
+pub fn csv(year:i32) -> Result<String, AsciiiError> {
     let mut projects = storage::setup::<Project>()?.open_projects(StorageDir::Year(year))?;
-    projects.sort_by(|pa,pb| pa.index().unwrap_or_else(||"zzzz".to_owned()).cmp( &pb.index().unwrap_or_else(||"zzzz".to_owned())));
+    SortOrder::with_config(&crate::CONFIG).sort(&mut projects);
     projects_to_csv(&projects)
 }
 
 /// Produces a csv string from a list of `Project`s
-pub fn projects_to_csv(projects:&[Project]) -> Result<String, Error>{
+pub fn projects_to_csv(projects:&[Project]) -> Result<String, AsciiiError>{
     let mut string = String::new();
     let splitter = ";";
 
@@ -80,55 +101,58 @@ pub fn projects_to_csv(projects:&[Project]) -> Result<String, Error>{
 }
 
 
-fn open_payments(projects: &[Project]) -> Currency {
-   projects.iter()
-           .filter(|&p| !p.canceled() && !p.is_payed() && p.age().unwrap_or(0) > 0)
-           .filter_map(|p| p.sum_sold().ok())
-           .fold(Currency::default(), |acc, x| acc + x)
-}
-
-fn open_wages(projects: &[Project]) -> Currency {
-    projects.iter()
-            .filter(|p| !p.canceled() && p.age().unwrap_or(0) > 0)
-            .filter_map(|p| p.hours().net_wages())
-            .fold(Currency::default(), |acc, x| acc + x)
-}
-
-fn unpayed_employees(projects: &[Project]) -> HashMap<String, Currency> {
-    let mut buckets = HashMap::new();
-    let employees = projects.iter()
-                            .filter(|p| !p.canceled() && p.age().unwrap_or(0) > 0)
-                            .filter_map(|p| p.hours().employees().ok())
-                            .flat_map(IntoIterator::into_iter);
-
-    for employee in employees {
-        let bucket = buckets.entry(employee.name.clone()).or_insert_with(Currency::new);
-        *bucket = *bucket + employee.salary;
-    }
-    buckets
-}
-
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct Dues {
     pub acc_sum_sold: Currency,
     pub acc_wages: Currency,
     pub unpayed_employees: HashMap<String, Currency>,
 }
 
+impl Dues {
+    /// Adds what is still open in `project`, canceled projects and those still to come count for nothing.
+    fn add(&mut self, project: &Project) {
+        if project.canceled() || project.age().unwrap_or(0) <= 0 {
+            return;
+        }
+        if !project.is_payed() {
+            if let Ok(sum) = project.sum_sold() {
+                self.acc_sum_sold = self.acc_sum_sold + sum;
+            }
+        }
+        if let Some(wages) = project.hours().net_wages() {
+            self.acc_wages = self.acc_wages + wages;
+        }
+        for employee in project.hours().employees().unwrap_or_default() {
+            let bucket = self.unpayed_employees.entry(employee.name.clone()).or_default();
+            *bucket = *bucket + employee.salary;
+        }
+    }
+}
+
 /// Command DUES
-pub fn dues() -> Result<Dues, Error> {
-    let projects = storage::setup::<Project>()?.open_projects(StorageDir::Working)?;
-    let acc_sum_sold: Currency = open_payments(&projects);
-    let acc_wages = open_wages(&projects);
-    let unpayed_employees = unpayed_employees(&projects);
+pub fn dues() -> Result<Dues, AsciiiError> {
+    let storage = storage::setup::<Project>()?;
+    let mut dues = Dues::default();
+    // added up while opening, no need to keep every project around
+    for project in storage.iter_projects(StorageDir::Working)? {
+        dues.add(&project);
+    }
+    Ok(dues)
+}
 
-    Ok(Dues{ acc_sum_sold, acc_wages, unpayed_employees})
+/// The next invoice number of `year`, from the ledger and the numbers projects of that year already carry.
+pub fn next_invoice_number(storage: &storage::Storage<Project>, year: i32) -> Result<u32, AsciiiError> {
+    let projects = storage.open_projects(StorageDir::Year(year))?;
+    let known = projects.iter()
+                        .filter_map(|p| p.invoice().number().ok())
+                        .filter_map(|n| u32::try_from(n).ok());
+    storage.ledger().next_number(year, known)
 }
 
 /// Testing only, tries to run complete spec on all projects.
 /// TODO: make this not panic :D
 /// TODO: move this to `spec::all_the_things`
-pub fn spec() -> Result<(), Error> {
+pub fn spec() -> Result<(), AsciiiError> {
     use crate::project::spec::*;
     let projects = storage::setup::<Project>()?.open_projects(StorageDir::Working)?;
     //let projects = super::execute(||storage.open_projects(StorageDir::All));
@@ -157,7 +181,7 @@ pub fn spec() -> Result<(), Error> {
     Ok(())
 }
 
-pub fn delete_project_confirmation(dir: StorageDir, search_terms:&[&str]) -> Result<(), Error> {
+pub fn delete_project_confirmation(dir: StorageDir, search_terms:&[&str]) -> Result<(), AsciiiError> {
     let storage = storage::setup_with_git::<Project>()?;
     for project in storage.search_projects_any(dir, search_terms)? {
         storage.delete_project_if(&project, || {
@@ -169,43 +193,58 @@ pub fn delete_project_confirmation(dir: StorageDir, search_terms:&[&str]) -> Res
     Ok(())
 }
 
-pub fn archive_projects(search_terms:&[&str], manual_year:Option<i32>, force:bool) -> Result<Vec<PathBuf>, Error>{
+pub fn archive_projects(search_terms:&[&str], manual_year:Option<i32>, force:bool) -> Result<Vec<PathBuf>, AsciiiError>{
     log::trace!("archive_projects matching ({:?},{:?},{:?})", search_terms, manual_year,force);
     storage::setup_with_git::<Project>()?.archive_projects_if(search_terms, manual_year, || force)
 }
 
-pub fn archive_all_projects() -> Result<Vec<PathBuf>, Error> {
+pub fn archive_all_projects() -> Result<Vec<PathBuf>, AsciiiError> {
+    let storage = storage::setup_with_git::<Project>()?;
+    let projects = storage.open_projects(StorageDir::Working)?;
+    let ready = projects.iter()
+                        .filter(|p| p.is_ready_for_archive().is_empty())
+                        .inspect(|p| log::info!("{}", lformat!("we could get rid of: {}", p.name().unwrap_or(""))))
+                        .map(|p| (p, p.year().unwrap()));
+    storage.archive_projects(ready)
+}
+
+/// Command ARCHIVE --fix-prefixes <YEAR>
+pub fn fix_archive_prefixes(year:i32) -> Result<Vec<(PathBuf, PathBuf)>, AsciiiError> {
+    storage::setup_with_git::<Project>()?.fix_archive_prefixes(year)
+}
+
+/// Command ARCHIVE --reindex <YEAR>
+pub fn reindex_archive(year:i32) -> Result<PathBuf, AsciiiError> {
     let storage = storage::setup_with_git::<Project>()?;
-    let mut moved_files = Vec::new();
-    for project in storage.open_projects(StorageDir::Working)?
-                        .iter()
-                        .filter(|p| p.is_ready_for_archive().is_empty()) {
-        log::info!("{}", lformat!("we could get rid of: {}", project.name().unwrap_or("")));
-        moved_files.push(project.dir());
-        moved_files.append(&mut storage.archive_project(project, project.year().unwrap())?);
+    if !storage.list_years()?.contains(&year) {
+        return Err(ActionError::Failed(lformat!("there is no archive of {}", year)).into());
     }
-    Ok(moved_files)
+    let index = storage.update_archive_index(year)?;
+    if let Some(repo) = storage.repository() {
+        repo.add(std::slice::from_ref(&index));
+    }
+    Ok(index)
 }
 
 /// Command UNARCHIVE <YEAR> <NAME>
 /// TODO: return a list of files that have to be updated in git
-pub fn unarchive_projects(year:i32, search_terms:&[&str]) -> Result<Vec<PathBuf>, Error> {
+pub fn unarchive_projects(year:i32, search_terms:&[&str]) -> Result<Vec<PathBuf>, AsciiiError> {
     storage::setup_with_git::<Project>()?.unarchive_projects(year, search_terms)
 }
 
 /// Produces a calendar from the selected `StorageDir`
-pub fn calendar(dir: StorageDir) -> Result<String, Error> {
+pub fn calendar(dir: StorageDir) -> Result<String, AsciiiError> {
     calendar_with_tasks(dir, true)
 }
 
 /// Command CALENDAR
 ///
 /// Produces a calendar including tasks from the selected `StorageDir`
-pub fn calendar_and_tasks(dir: StorageDir) -> Result<String, Error> {
+pub fn calendar_and_tasks(dir: StorageDir) -> Result<String, AsciiiError> {
     calendar_with_tasks(dir, false)
 }
 
-pub fn calendar_with_tasks(dir: StorageDir, show_tasks: bool) -> Result<String, Error> {
+pub fn calendar_with_tasks(dir: StorageDir, show_tasks: bool) -> Result<String, AsciiiError> {
     let storage = storage::setup::<Project>()?;
     let mut cal = Calendar::new();
     if show_tasks {
@@ -221,7 +260,7 @@ pub fn calendar_with_tasks(dir: StorageDir, show_tasks: bool) -> Result<String,
 
 /// Clone the repo
 ///
-pub fn clone_remote(url: &str, to: &str) -> Result<(), Error> {
+pub fn clone_remote(url: &str, to: &str) -> Result<(), AsciiiError> {
     log::trace!("cloning {:?} to {:?}", url, to);
     Command::new("git")
         .args(&["clone", url, to])
@@ -230,6 +269,22 @@ pub fn clone_remote(url: &str, to: &str) -> Result<(), Error> {
     Ok(())
 }
 
+/// Runs `git init` in `path`, unless it is a repository already.
+pub fn init_repository(path: &Path) -> Result<(), AsciiiError> {
+    if path.join(".git").exists() {
+        return Ok(());
+    }
+    log::trace!("initializing repository in {:?}", path);
+    let status = Command::new("git")
+        .args(["init", "--quiet"])
+        .arg(path)
+        .status()?;
+    if !status.success() {
+        return Err(ActionError::Failed(lformat!("can't initialize a git repository in {}", path.display())).into());
+    }
+    Ok(())
+}
+
 /// Shared extra information stored in the repo
 #[cfg_attr(feature = "meta", derive(Serialize))]
 #[cfg_attr(feature = "meta", derive(Deserialize))]
@@ -250,7 +305,7 @@ pub struct ApiKeys {
 
 /// Parses meta store
 #[cfg(feature = "meta")]
-pub fn parse_meta() -> Result<MetaStore, Error> {
+pub fn parse_meta() -> Result<MetaStore, AsciiiError> {
     let path = storage::setup::<Project>()?.get_extra_file("meta.toml")?;
     let file_content = fs::read_to_string(&path)?;
     let store: MetaStore = toml::from_str(&file_content)?;
@@ -260,11 +315,11 @@ pub fn parse_meta() -> Result<MetaStore, Error> {
 
 /// get ApiKeys for server
 #[cfg(feature = "meta")]
-pub fn get_api_keys() -> Result<ApiKeys, Error> {
+pub fn get_api_keys() -> Result<ApiKeys, AsciiiError> {
     Ok(parse_meta()?.api)
 }
 
-pub fn store_meta() -> Result<(), Error> {
+pub fn store_meta() -> Result<(), AsciiiError> {
     let storage = storage::setup_with_git::<Project>()?;
     let repo = storage.get_repository()?;
     let path = storage.get_extra_file("meta.toml")?;
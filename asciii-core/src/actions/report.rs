@@ -0,0 +1,499 @@
+//! Sums for the tax office, see `asciii report`.
+//!
+//! Reports go by the date of the invoice, not by the year a project is archived in.
+//! Canceled projects and projects without an invoice date are left out.
+//!
+//! Wages count as gross wages (hours times salary), expenses are taken from the `expenses` of each project.
+//! Revenue of projects billed in another currency is converted to euro by the rate of their invoice date, see `exchange`.
+//!
+//! `NumberReport` checks invoice numbers for gaps and duplicates instead, canceled projects keep their numbers there.
+//! `ClientReport` sums revenue per client for `asciii stats clients`, clients are told apart by their names.
+
+use bill::{Currency, Tax};
+use chrono::prelude::*;
+
+use crate::error::AsciiiError;
+use crate::exchange::Exchange;
+use crate::project::spec::{HasEmployees, Invoicable, IsClient, IsProject, Redeemable};
+use crate::project::Project;
+use crate::storage::ledger::Issued;
+use crate::storage::Storable;
+use crate::util::to_currency;
+
+/// Revenue of a single tax rate.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VatRow {
+    pub tax: Tax,
+    /// before tax
+    pub net: Currency,
+    pub vat: Currency,
+}
+
+/// Revenue and VAT per tax rate of all invoices in a year or quarter.
+#[derive(Debug, Clone)]
+pub struct VatReport {
+    pub year: i32,
+    /// `1..=4`, `None` for the whole year
+    pub quarter: Option<u32>,
+    /// ordered by tax rate
+    pub rows: Vec<VatRow>,
+    /// number of invoices included
+    pub invoices: usize,
+}
+
+/// Tax rate as percentage, e.g. `7%` or `5.5%`.
+pub fn percent(tax: Tax) -> String {
+    format!("{}%", (tax.value() * 10_000.0).round() / 100.0)
+}
+
+/// Quarter of a date, `1..=4`.
+fn quarter_of(date: Date<Utc>) -> u32 {
+    date.month0() / 3 + 1
+}
+
+impl VatReport {
+    pub fn new(projects: &[Project], year: i32, quarter: Option<u32>) -> Result<VatReport, AsciiiError> {
+        VatReport::with_exchange(projects, year, quarter, &mut Exchange::new())
+    }
+
+    /// Like `new()`, converting other currencies with `exchange`.
+    pub fn with_exchange(projects: &[Project], year: i32, quarter: Option<u32>, exchange: &mut Exchange) -> Result<VatReport, AsciiiError> {
+        let mut rows: Vec<VatRow> = Vec::new();
+        let mut invoices = 0;
+
+        for project in projects.iter().filter(|p| !p.canceled()) {
+            let date = match project.invoice().date() {
+                Ok(date) => date,
+                Err(_) => continue
+            };
+            if date.year() != year || quarter.is_some_and(|q| quarter_of(date) != q) {
+                continue;
+            }
+
+            let (_, invoice) = project.bills()?;
+            for (tax, items) in invoice.iter() {
+                let row = match rows.iter().position(|r| r.tax == *tax) {
+                    Some(index) => &mut rows[index],
+                    None => {
+                        rows.push(VatRow { tax: *tax, net: Currency::default(), vat: Currency::default() });
+                        rows.last_mut().unwrap()
+                    }
+                };
+                // `gross_sum()` is what the bill crate calls the sum before tax
+                let currency = project.billing_currency();
+                row.net = row.net + exchange.to_home(items.gross_sum(), currency, date.naive_utc())?;
+                row.vat = row.vat + exchange.to_home(items.tax_sum(), currency, date.naive_utc())?;
+            }
+            invoices += 1;
+        }
+
+        rows.sort_by_key(|r| r.tax);
+        Ok(VatReport { year, quarter, rows, invoices })
+    }
+
+    /// `2024` or `Q2 2024`
+    pub fn period(&self) -> String {
+        match self.quarter {
+            Some(quarter) => format!("Q{} {}", quarter, self.year),
+            None => self.year.to_string(),
+        }
+    }
+
+    pub fn total_net(&self) -> Currency {
+        self.rows.iter().fold(Currency::default(), |acc, r| acc + r.net)
+    }
+
+    pub fn total_vat(&self) -> Currency {
+        self.rows.iter().fold(Currency::default(), |acc, r| acc + r.vat)
+    }
+
+    /// One line per tax rate and a total, separated by `;` like `asciii csv`.
+    pub fn to_csv(&self) -> Result<String, AsciiiError> {
+        let mut writer = csv::WriterBuilder::new().delimiter(b';').from_writer(Vec::new());
+        writer.write_record(&[lformat!("Tax rate"), lformat!("Net"), lformat!("VAT"), lformat!("Gross")])?;
+        for row in &self.rows {
+            writer.write_record(&[percent(row.tax),
+                                  format!("{:.2}", row.net.as_float()),
+                                  format!("{:.2}", row.vat.as_float()),
+                                  format!("{:.2}", (row.net + row.vat).as_float())])?;
+        }
+        writer.write_record(&[lformat!("Total"),
+                              format!("{:.2}", self.total_net().as_float()),
+                              format!("{:.2}", self.total_vat().as_float()),
+                              format!("{:.2}", (self.total_net() + self.total_vat()).as_float())])?;
+        Ok(String::from_utf8(writer.into_inner()?)?)
+    }
+}
+
+/// Income of one month.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IncomeRow {
+    /// invoiced, before tax
+    pub revenue: Currency,
+    pub wages: Currency,
+    pub expenses: Currency,
+}
+
+impl Default for IncomeRow {
+    fn default() -> Self {
+        IncomeRow { revenue: to_currency(0.0), wages: to_currency(0.0), expenses: to_currency(0.0) }
+    }
+}
+
+impl IncomeRow {
+    pub fn profit(&self) -> Currency {
+        self.revenue - self.wages - self.expenses
+    }
+
+    fn add(&mut self, other: &IncomeRow) {
+        self.revenue = self.revenue + other.revenue;
+        self.wages = self.wages + other.wages;
+        self.expenses = self.expenses + other.expenses;
+    }
+}
+
+/// Revenue, wages, expenses and profit per month of a year.
+#[derive(Debug, Clone)]
+pub struct IncomeReport {
+    pub year: i32,
+    /// January first
+    pub months: [IncomeRow; 12],
+}
+
+impl IncomeReport {
+    pub fn new(projects: &[Project], year: i32) -> Result<IncomeReport, AsciiiError> {
+        IncomeReport::with_exchange(projects, year, &mut Exchange::new())
+    }
+
+    /// Like `new()`, converting other currencies with `exchange`.
+    pub fn with_exchange(projects: &[Project], year: i32, exchange: &mut Exchange) -> Result<IncomeReport, AsciiiError> {
+        let mut months = [IncomeRow::default(); 12];
+
+        for project in projects.iter().filter(|p| !p.canceled()) {
+            let date = match project.invoice().date() {
+                Ok(date) if date.year() == year => date,
+                _ => continue
+            };
+            let (_, invoice) = project.bills()?;
+            months[date.month0() as usize].add(&IncomeRow {
+                revenue: exchange.to_home(invoice.gross_total(), project.billing_currency(), date.naive_utc())?,
+                wages: project.hours().gross_wages().unwrap_or_else(|| to_currency(0.0)),
+                expenses: project.expenses(),
+            });
+        }
+
+        Ok(IncomeReport { year, months })
+    }
+
+    pub fn total(&self) -> IncomeRow {
+        let mut total = IncomeRow::default();
+        for month in &self.months {
+            total.add(month);
+        }
+        total
+    }
+
+    /// One line per month and a total, separated by `;` like `asciii csv`.
+    pub fn to_csv(&self) -> Result<String, AsciiiError> {
+        let mut writer = csv::WriterBuilder::new().delimiter(b';').from_writer(Vec::new());
+        writer.write_record(&[lformat!("Month"), lformat!("Revenue"), lformat!("Wages"), lformat!("Expenses"), lformat!("Profit")])?;
+        let total = self.total();
+        let months = self.months.iter().enumerate().map(|(i, row)| (format!("{:02}/{}", i + 1, self.year), row));
+        for (month, row) in months.chain(std::iter::once((lformat!("Total"), &total))) {
+            writer.write_record(&[month,
+                                  format!("{:.2}", row.revenue.as_float()),
+                                  format!("{:.2}", row.wages.as_float()),
+                                  format!("{:.2}", row.expenses.as_float()),
+                                  format!("{:.2}", row.profit().as_float())])?;
+        }
+        Ok(String::from_utf8(writer.into_inner()?)?)
+    }
+
+    #[cfg(feature = "serialization")]
+    pub fn to_json(&self) -> Result<String, AsciiiError> {
+        let row = |row: &IncomeRow| serde_json::json!({
+            "revenue":  row.revenue.as_float(),
+            "wages":    row.wages.as_float(),
+            "expenses": row.expenses.as_float(),
+            "profit":   row.profit().as_float(),
+        });
+        let report = serde_json::json!({
+            "year": self.year,
+            "months": self.months.iter().map(row).collect::<Vec<_>>(),
+            "total": row(&self.total()),
+        });
+        Ok(serde_json::to_string_pretty(&report)?)
+    }
+}
+
+/// Invoice numbers of a year that don't add up, from the projects and the ledger.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NumberReport {
+    pub year: i32,
+    pub highest: u32,
+    /// neither issued nor carried by a project
+    pub gaps: Vec<u32>,
+    /// carried by more than one project, with their names
+    pub duplicates: Vec<(u32, Vec<String>)>,
+    /// carried by a project but never issued, only if the ledger knows the year
+    pub unrecorded: Vec<(u32, String)>,
+    /// issued, but no project carries it anymore
+    pub orphaned: Vec<Issued>,
+}
+
+impl NumberReport {
+    /// Numbers count for the year of the invoice date, or the year of the project without one.
+    pub fn new(projects: &[Project], issued: &[Issued], year: i32) -> NumberReport {
+        let mut carried: Vec<(u32, String)> = projects.iter()
+            .filter(|project| project.invoice().date().ok().map(|date| date.year()).or_else(|| project.year()) == Some(year))
+            .filter_map(|project| {
+                let number = u32::try_from(project.invoice().number().ok()?).ok()?;
+                Some((number, project.short_desc()))
+            })
+            .collect();
+        carried.sort();
+
+        let issued = issued.iter().filter(|issued| issued.year == year).collect::<Vec<_>>();
+        let known = |number: u32| carried.iter().any(|(n, _)| *n == number) || issued.iter().any(|i| i.number == number);
+        let highest = carried.iter().map(|(n, _)| *n)
+                             .chain(issued.iter().map(|i| i.number))
+                             .max()
+                             .unwrap_or(0);
+
+        let mut duplicates: Vec<(u32, Vec<String>)> = Vec::new();
+        for (number, name) in &carried {
+            match duplicates.iter_mut().find(|(n, _)| n == number) {
+                Some((_, names)) => names.push(name.clone()),
+                None => duplicates.push((*number, vec![name.clone()])),
+            }
+        }
+        duplicates.retain(|(_, names)| names.len() > 1);
+
+        let unrecorded = if issued.is_empty() {
+            Vec::new()
+        } else {
+            carried.iter()
+                   .filter(|(number, _)| !issued.iter().any(|i| i.number == *number))
+                   .cloned()
+                   .collect()
+        };
+
+        NumberReport {
+            year,
+            highest,
+            gaps: (1..highest).filter(|number| !known(*number)).collect(),
+            duplicates,
+            unrecorded,
+            orphaned: issued.iter()
+                            .filter(|i| !carried.iter().any(|(n, _)| *n == i.number))
+                            .map(|i| (*i).clone())
+                            .collect(),
+        }
+    }
+
+    /// Nothing to complain about.
+    pub fn is_clean(&self) -> bool {
+        self.gaps.is_empty() && self.duplicates.is_empty() && self.unrecorded.is_empty() && self.orphaned.is_empty()
+    }
+}
+
+/// Revenue of one client.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClientRow {
+    /// as written in the latest invoice
+    pub client: String,
+    pub projects: usize,
+    /// invoiced, before tax
+    pub revenue: Currency,
+    /// average days from invoice to payment, of the payed invoices
+    pub payment_delay: Option<f64>,
+}
+
+impl ClientRow {
+    pub fn average_revenue(&self) -> Currency {
+        to_currency(self.revenue.as_float() / self.projects.max(1) as f64)
+    }
+}
+
+/// Revenue, projects and payment delay per client, the best clients first.
+#[derive(Debug, Clone)]
+pub struct ClientReport {
+    /// `None` for all years
+    pub year: Option<i32>,
+    pub rows: Vec<ClientRow>,
+}
+
+/// `first last`, or whatever there is of the client.
+fn client_name(project: &Project) -> String {
+    let client = project.client();
+    client.full_name()
+          .or_else(|| client.last_name().ok().map(ToOwned::to_owned))
+          .or_else(|| client.email().ok().map(ToOwned::to_owned))
+          .map(|name| name.split_whitespace().collect::<Vec<_>>().join(" "))
+          .filter(|name| !name.is_empty())
+          .unwrap_or_else(|| lformat!("unknown"))
+}
+
+impl ClientReport {
+    pub fn new(projects: &[Project], year: Option<i32>) -> Result<ClientReport, AsciiiError> {
+        ClientReport::with_exchange(projects, year, &mut Exchange::new())
+    }
+
+    /// Like `new()`, converting other currencies with `exchange`.
+    pub fn with_exchange(projects: &[Project], year: Option<i32>, exchange: &mut Exchange) -> Result<ClientReport, AsciiiError> {
+        // row, date of the latest invoice, days until payment of every payed invoice
+        let mut clients: Vec<(ClientRow, Date<Utc>, Vec<i64>)> = Vec::new();
+
+        let mut invoiced = projects.iter()
+            .filter(|p| !p.canceled())
+            .filter_map(|p| p.invoice().date().ok().map(|date| (p, date)))
+            .filter(|(_, date)| year.is_none_or(|year| date.year() == year))
+            .collect::<Vec<_>>();
+        invoiced.sort_by_key(|(_, date)| *date);
+
+        for (project, date) in invoiced {
+            let (_, invoice) = project.bills()?;
+            let revenue = exchange.to_home(invoice.gross_total(), project.billing_currency(), date.naive_utc())?;
+            let delay = project.payed_date().ok().map(|payed| payed.signed_duration_since(date).num_days());
+            let name = client_name(project);
+
+            match clients.iter_mut().find(|(row, _, _)| row.client.to_lowercase() == name.to_lowercase()) {
+                Some((row, latest, delays)) => {
+                    row.client = name;
+                    row.projects += 1;
+                    row.revenue = row.revenue + revenue;
+                    *latest = date;
+                    delays.extend(delay);
+                }
+                None => clients.push((ClientRow { client: name, projects: 1, revenue, payment_delay: None }, date, delay.into_iter().collect())),
+            }
+        }
+
+        let mut rows = clients.into_iter()
+            .map(|(row, _, delays)| ClientRow {
+                payment_delay: if delays.is_empty() { None } else { Some(delays.iter().sum::<i64>() as f64 / delays.len() as f64) },
+                .. row
+            })
+            .collect::<Vec<_>>();
+        rows.sort_by(|a, b| b.revenue.cmp(&a.revenue).then_with(|| a.client.cmp(&b.client)));
+        Ok(ClientReport { year, rows })
+    }
+
+    pub fn total_revenue(&self) -> Currency {
+        self.rows.iter().fold(Currency::default(), |acc, r| acc + r.revenue)
+    }
+
+    /// One line per client, separated by `;` like `asciii csv`.
+    pub fn to_csv(&self) -> Result<String, AsciiiError> {
+        let mut writer = csv::WriterBuilder::new().delimiter(b';').from_writer(Vec::new());
+        writer.write_record(&[lformat!("Client"), lformat!("Projects"), lformat!("Revenue"), lformat!("Average"), lformat!("Payment delay")])?;
+        for row in &self.rows {
+            writer.write_record(&[row.client.clone(),
+                                  row.projects.to_string(),
+                                  format!("{:.2}", row.revenue.as_float()),
+                                  format!("{:.2}", row.average_revenue().as_float()),
+                                  row.payment_delay.map(|days| format!("{:.1}", days)).unwrap_or_default()])?;
+        }
+        Ok(String::from_utf8(writer.into_inner()?)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project(invoice_date: &str, products: &str) -> Project {
+        Project::from_file_content(&format!("tax: 0.19\nhours:\n  salary: 8.0\n  tax: 0.19\ninvoice:\n  number: 1\n  date: {}\nproducts:\n{}", invoice_date, products)).unwrap()
+    }
+
+    #[test]
+    fn vat_per_rate() {
+        let projects = vec![project("12.05.2024", "  Kaffee: { amount: 2, price: 10 }\n  Buch: { amount: 1, price: 10, tax: 0.07 }\n"),
+                            project("30.06.2024", "  Kaffee: { amount: 1, price: 10 }\n"),
+                            project("01.07.2024", "  Kaffee: { amount: 5, price: 10 }\n")];
+
+        let report = VatReport::new(&projects, 2024, Some(2)).unwrap();
+        assert_eq!(report.invoices, 2);
+        assert_eq!(report.period(), "Q2 2024");
+        assert_eq!(report.rows.iter().map(|r| (r.tax.value(), r.net.as_float(), r.vat.as_float())).collect::<Vec<_>>(),
+                   vec![(0.07, 10.0, 0.7), (0.19, 30.0, 5.7)]);
+
+        assert_eq!(report.to_csv().unwrap().lines().nth(1), Some("7%;10.00;0.70;10.70"));
+
+        let year = VatReport::new(&projects, 2024, None).unwrap();
+        assert_eq!(year.invoices, 3);
+        assert_eq!(year.total_net().as_float(), 90.0);
+    }
+
+    #[test]
+    fn income_per_month() {
+        let projects = vec![project("12.05.2024", "  Kaffee: { amount: 2, price: 10 }\ncaterers:\n  Maxime: 1\nexpenses:\n  Zelt: 5\n"),
+                            project("30.05.2024", "  Kaffee: { amount: 1, price: 10 }\n"),
+                            project("01.07.2025", "  Kaffee: { amount: 5, price: 10 }\n")];
+
+        let report = IncomeReport::new(&projects, 2024).unwrap();
+        let may = report.months[4];
+        assert_eq!(may.revenue.as_float(), 30.0);
+        assert_eq!(may.expenses.as_float(), 5.0);
+        assert_eq!(report.total().profit().as_float(), 30.0 - may.wages.as_float() - 5.0);
+        assert_eq!(report.to_csv().unwrap().lines().count(), 14);
+    }
+
+    #[test]
+    fn other_currencies_in_euro() {
+        use crate::exchange::Rates;
+
+        let rates = Rates::parse("<Cube time='2024-05-10'><Cube currency='USD' rate='2.0'/></Cube>").unwrap();
+        let dollars = Project::from_file_content("currency: USD\ntax: 0.19\nhours:\n  salary: 8.0\ninvoice:\n  number: 1\n  date: 12.05.2024\nproducts:\n  Kaffee: { amount: 2, price: 10 }\n").unwrap();
+        let projects = vec![dollars, project("12.05.2024", "  Kaffee: { amount: 1, price: 10 }\n")];
+
+        let report = VatReport::with_exchange(&projects, 2024, None, &mut Exchange::with_rates(rates)).unwrap();
+        assert_eq!(report.total_net().as_float(), 20.0);
+        assert_eq!(report.total_vat().as_float(), 3.8);
+    }
+
+    #[test]
+    fn revenue_per_client() {
+        let invoiced = |client: &str, date: &str, payed: &str, price: u32| Project::from_file_content(
+            &format!("client:\n  first_name: {}\n  last_name: Zahl\ntax: 0.19\nhours:\n  salary: 8.0\ninvoice:\n  number: 1\n  date: {}\n  payed_date: {}\nproducts:\n  Kaffee: {{ amount: 1, price: {} }}\n",
+                     client, date, payed, price)).unwrap();
+        let projects = vec![invoiced("graf", "10.01.2024", "20.01.2024", 10),
+                            invoiced("Graf", "10.03.2024", "30.03.2024", 30),
+                            invoiced("Ernie", "10.02.2024", "~", 25),
+                            invoiced("Ernie", "10.02.2023", "~", 100)];
+
+        let report = ClientReport::new(&projects, Some(2024)).unwrap();
+        assert_eq!(report.rows.iter().map(|r| (r.client.as_str(), r.projects, r.revenue.as_float(), r.payment_delay)).collect::<Vec<_>>(),
+                   vec![("Graf Zahl", 2, 40.0, Some(15.0)), ("Ernie Zahl", 1, 25.0, None)]);
+        assert_eq!(report.rows[0].average_revenue().as_float(), 20.0);
+        assert_eq!(report.to_csv().unwrap().lines().nth(1), Some("Graf Zahl;2;40.00;20.00;15.0"));
+
+        let all = ClientReport::new(&projects, None).unwrap();
+        assert_eq!(all.rows[0].client, "Ernie Zahl");
+        assert_eq!(all.total_revenue().as_float(), 165.0);
+    }
+
+    #[test]
+    fn numbers_with_gaps_and_duplicates() {
+        use chrono::NaiveDate;
+
+        let numbered = |number: u32, date: &str| Project::from_file_content(
+            &format!("event:\n  name: Nr{}\ninvoice:\n  number: {}\n  date: {}\n", number, number, date)).unwrap();
+        let projects = vec![numbered(1, "10.01.2024"), numbered(2, "11.01.2024"), numbered(2, "12.01.2024"),
+                            numbered(5, "13.01.2024"), numbered(3, "01.02.2025")];
+        let issued = |number| Issued { year: 2024, number, project: format!("Nr{}", number), date: NaiveDate::from_ymd_opt(2024, 1, 10).unwrap() };
+
+        let report = NumberReport::new(&projects, &[issued(1), issued(2), issued(4)], 2024);
+        assert_eq!(report.highest, 5);
+        assert_eq!(report.gaps, vec![3]);
+        assert_eq!(report.duplicates, vec![(2, vec!["Nr2".to_owned(), "Nr2".to_owned()])]);
+        assert_eq!(report.unrecorded, vec![(5, "Nr5".to_owned())]);
+        assert_eq!(report.orphaned, vec![issued(4)]);
+        assert!(!report.is_clean());
+
+        // years from before the ledger only have gaps
+        let report = NumberReport::new(&projects, &[], 2025);
+        assert_eq!(report.gaps, vec![1, 2]);
+        assert!(report.unrecorded.is_empty());
+    }
+}
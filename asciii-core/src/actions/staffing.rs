@@ -0,0 +1,144 @@
+//! Who works at which event, see `asciii staff`.
+//!
+//! Projects say how many employees their event needs in `hours/staff`:
+//!
+//! ```yaml
+//! hours:
+//!   salary: 8.0
+//!   staff: 4
+//!   caterers:
+//!     Ernie: 0
+//!     Bert: 0
+//! ```
+//!
+//! Everyone listed in `caterers` counts as assigned, whether they have hours yet or not.
+//! Events without `staff` are listed, but never understaffed.
+//! `schedule()` is the calendar of one of them, see `asciii calendar --employee`.
+
+use chrono::NaiveDate;
+use icalendar::Calendar;
+
+use crate::project::spec::{HasEmployees, HasEvents, IsProject};
+use crate::project::Project;
+
+use super::inventory::booked_days;
+
+/// Staff of one event.
+#[derive(Debug, Clone)]
+pub struct Staffing<'a> {
+    pub project: &'a Project,
+    pub first: NaiveDate,
+    pub last: NaiveDate,
+    /// `hours/staff`
+    pub required: Option<u32>,
+    pub assigned: Vec<String>,
+}
+
+impl<'a> Staffing<'a> {
+    /// How many employees are still needed.
+    pub fn missing(&self) -> u32 {
+        self.required.unwrap_or(0).saturating_sub(self.assigned.len() as u32)
+    }
+
+    pub fn is_understaffed(&self) -> bool {
+        self.missing() > 0
+    }
+}
+
+/// First and last day of `month` in `year`.
+pub fn days_of(year: i32, month: u32) -> Option<(NaiveDate, NaiveDate)> {
+    let first = NaiveDate::from_ymd_opt(year, month, 1)?;
+    let next = if month == 12 { NaiveDate::from_ymd_opt(year + 1, 1, 1)? } else { NaiveDate::from_ymd_opt(year, month + 1, 1)? };
+    Some((first, next.pred_opt()?))
+}
+
+/// Events of `projects` on days of `month` in `year` that are not over before `today`, ordered by their first day.
+///
+/// Canceled projects are left out.
+pub fn plan(projects: &[Project], year: i32, month: u32, today: NaiveDate) -> Vec<Staffing<'_>> {
+    let (month_begins, month_ends) = match days_of(year, month) {
+        Some(days) => days,
+        None => return Vec::new(),
+    };
+    let mut plan = projects.iter()
+                           .filter(|project| !project.canceled())
+                           .filter_map(|project| {
+                               let (first, last) = booked_days(project)?;
+                               if last < month_begins || first > month_ends || last < today {
+                                   return None;
+                               }
+                               Some(Staffing {
+                                   project,
+                                   first,
+                                   last,
+                                   required: project.hours().staff().ok(),
+                                   assigned: project.hours().assigned(),
+                               })
+                           })
+                           .collect::<Vec<_>>();
+    plan.sort_by_key(|staffing| staffing.first);
+    plan
+}
+
+/// Whether `employee` is assigned to `project`, names are compared ignoring case.
+pub fn is_assigned(project: &Project, employee: &str) -> bool {
+    let employee = employee.trim();
+    project.hours().assigned().iter().any(|name| name.eq_ignore_ascii_case(employee))
+}
+
+/// Calendar of the events `employee` is assigned to, canceled projects are left out.
+pub fn schedule<'a>(projects: impl IntoIterator<Item = &'a Project>, employee: &str) -> Calendar {
+    let mut calendar = Calendar::new();
+    for project in projects.into_iter().filter(|project| !project.canceled() && is_assigned(project, employee)) {
+        calendar.append(&mut project.to_ical());
+    }
+    calendar
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(name: &str, date: &str, staff: &str, caterers: &str) -> Project {
+        Project::from_file_content(&format!("event:\n  name: {}\n  dates:\n  - begin: {}\n\
+                                             hours:\n  salary: 8.0\n{}  caterers:\n{}",
+                                            name, date, staff, caterers)).unwrap()
+    }
+
+    #[test]
+    fn staffing_plan() {
+        let today = NaiveDate::from_ymd_opt(2024, 7, 10).unwrap();
+        assert_eq!(days_of(2024, 2).map(|(_, last)| last), NaiveDate::from_ymd_opt(2024, 2, 29));
+        assert_eq!(days_of(2024, 12).map(|(_, last)| last), NaiveDate::from_ymd_opt(2024, 12, 31));
+
+        let projects = vec![event("Hochzeit", "20.07.2024", "  staff: 3\n", "    Ernie: 0\n    unknown: 0\n"),
+                            event("Sommerfest", "12.07.2024", "  staff: 2\n", "    Ernie: 0\n    Bert: 4\n"),
+                            event("Vorbei", "01.07.2024", "  staff: 2\n", "    Ernie: 0\n"),
+                            event("Herbstfest", "01.10.2024", "", "    Ernie: 0\n")];
+        let plan = plan(&projects, 2024, 7, today);
+        assert_eq!(plan.len(), 2);
+        assert_eq!(plan[0].project.name().unwrap(), "Sommerfest");
+        assert!(!plan[0].is_understaffed());
+        assert_eq!(plan[1].assigned, vec!["Ernie".to_owned()]);
+        assert_eq!(plan[1].missing(), 2);
+    }
+
+    #[test]
+    fn personal_schedule() {
+        let projects = vec![event("Hochzeit", "20.07.2024", "", "    Ernie: 0
+"),
+                            event("Sommerfest", "12.07.2024", "", "    Bert: 4
+    ernie: 2
+"),
+                            event("Herbstfest", "01.10.2024", "", "    Bert: 0
+")];
+        assert!(is_assigned(&projects[1], "Ernie "));
+        assert!(!is_assigned(&projects[2], "Ernie"));
+        assert!(!is_assigned(&projects[0], "unknown"));
+
+        let schedule = schedule(&projects, "ernie").to_string();
+        assert_eq!(schedule.matches("BEGIN:VEVENT").count(), 2);
+        assert!(schedule.contains("SUMMARY:Sommerfest"));
+        assert!(!schedule.contains("Herbstfest"));
+    }
+}
@@ -0,0 +1,218 @@
+//! Health of the whole storage at a glance, see `asciii status --json`.
+//!
+//! Meant for scripts and monitoring: how many working projects are in which `Lifecycle` stage,
+//! invoices that are past their `payment_due()`, payed projects still waiting to be archived,
+//! projects that can't be opened and whether the repository is in sync with its upstream.
+
+use std::path::PathBuf;
+
+use bill::Currency;
+use chrono::prelude::*;
+
+use crate::error::AsciiiError;
+use crate::project::spec::{Invoicable, IsProject, Offerable, Redeemable};
+use crate::project::Project;
+use crate::storage::{Storable, Storage, StorageDir};
+
+/// How far a project has come.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lifecycle {
+    /// nothing sent yet
+    Draft,
+    /// waiting for the client
+    Offered,
+    Accepted,
+    /// waiting for the money
+    Invoiced,
+    Payed,
+    Canceled,
+}
+
+impl Lifecycle {
+    pub const ALL: [Lifecycle; 6] = [Lifecycle::Draft, Lifecycle::Offered, Lifecycle::Accepted,
+                                     Lifecycle::Invoiced, Lifecycle::Payed, Lifecycle::Canceled];
+
+    pub fn of(project: &Project) -> Lifecycle {
+        if project.canceled() {
+            Lifecycle::Canceled
+        } else if project.is_payed() {
+            Lifecycle::Payed
+        } else if project.invoice().number().is_ok() {
+            Lifecycle::Invoiced
+        } else if project.offer().accepted_date().is_ok() {
+            Lifecycle::Accepted
+        } else if project.offer().sent_date().is_ok() || project.offer().generated_date().is_ok() {
+            Lifecycle::Offered
+        } else {
+            Lifecycle::Draft
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Lifecycle::Draft    => "draft",
+            Lifecycle::Offered  => "offered",
+            Lifecycle::Accepted => "accepted",
+            Lifecycle::Invoiced => "invoiced",
+            Lifecycle::Payed    => "payed",
+            Lifecycle::Canceled => "canceled",
+        }
+    }
+}
+
+/// An invoice that should have been payed by now.
+#[derive(Debug, Clone)]
+pub struct Overdue {
+    pub project: String,
+    pub invoice: Option<String>,
+    pub due: Date<Utc>,
+    /// since `due`
+    pub days: i64,
+    pub amount: Option<Currency>,
+}
+
+/// Changes of the repository, `ahead` and `behind` only with an upstream branch.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GitSync {
+    pub changed: usize,
+    pub ahead: Option<usize>,
+    pub behind: Option<usize>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Summary {
+    /// working projects per stage
+    pub stages: Vec<(Lifecycle, usize)>,
+    pub archived: usize,
+    pub overdue: Vec<Overdue>,
+    /// payed projects in the working directory
+    pub payed_unarchived: Vec<String>,
+    /// project files that can't be opened
+    pub broken: Vec<PathBuf>,
+    /// `None` without a repository
+    pub git: Option<GitSync>,
+}
+
+impl Summary {
+    /// Summary of the working `projects` as of `today`, without the archive and the repository.
+    pub fn new(projects: &[Project], today: Date<Utc>) -> Summary {
+        let stages = Lifecycle::ALL.iter()
+                                   .map(|&stage| (stage, projects.iter().filter(|project| Lifecycle::of(project) == stage).count()))
+                                   .collect();
+        let mut overdue = projects.iter()
+                                  .filter(|project| Lifecycle::of(project) == Lifecycle::Invoiced)
+                                  .filter_map(|project| {
+                                      let due = project.payment_due().filter(|due| *due < today)?;
+                                      Some(Overdue {
+                                          project: project.short_desc(),
+                                          invoice: project.invoice().number_str(),
+                                          due,
+                                          days: (today - due).num_days(),
+                                          amount: project.sum_sold().ok(),
+                                      })
+                                  })
+                                  .collect::<Vec<_>>();
+        overdue.sort_by_key(|overdue| overdue.due);
+        Summary {
+            stages,
+            archived: 0,
+            overdue,
+            payed_unarchived: projects.iter()
+                                      .filter(|project| Lifecycle::of(project) == Lifecycle::Payed)
+                                      .map(Storable::short_desc)
+                                      .collect(),
+            broken: Vec::new(),
+            git: None,
+        }
+    }
+
+    /// Summary of everything in `storage`.
+    pub fn of_storage(storage: &Storage<Project>, today: Date<Utc>) -> Result<Summary, AsciiiError> {
+        let (projects, failures) = storage.open_projects_with_failures(StorageDir::Working)?;
+        let mut summary = Summary::new(&projects, today);
+        // counted, not opened
+        for year in storage.list_years()? {
+            summary.archived += storage.list_project_folders(StorageDir::Archive(year))?.len();
+        }
+        summary.broken = failures.into_iter().map(|failure| failure.path).collect();
+        if let Some(repo) = storage.repository() {
+            let (ahead, behind) = match repo.ahead_behind()? {
+                Some((ahead, behind)) => (Some(ahead), Some(behind)),
+                None => (None, None),
+            };
+            summary.git = Some(GitSync { changed: repo.changed_files()?, ahead, behind });
+        }
+        Ok(summary)
+    }
+
+    /// Whether anything needs attention.
+    pub fn is_healthy(&self) -> bool {
+        self.overdue.is_empty() && self.broken.is_empty()
+            && self.git.as_ref().is_none_or(|git| git.behind.unwrap_or(0) == 0)
+    }
+
+    #[cfg(feature = "serialization")]
+    pub fn to_json(&self) -> Result<String, AsciiiError> {
+        let stages = self.stages.iter()
+                                .map(|(stage, count)| (stage.as_str().to_owned(), serde_json::json!(count)))
+                                .collect::<serde_json::Map<_, _>>();
+        let overdue = self.overdue.iter()
+                                  .map(|overdue| serde_json::json!({
+                                      "project": overdue.project,
+                                      "invoice": overdue.invoice,
+                                      "due":     overdue.due.format("%Y-%m-%d").to_string(),
+                                      "days":    overdue.days,
+                                      "amount":  overdue.amount.map(|amount| amount.as_float()),
+                                  }))
+                                  .collect::<Vec<_>>();
+        let git = self.git.as_ref().map(|git| serde_json::json!({
+            "changed": git.changed,
+            "ahead":   git.ahead,
+            "behind":  git.behind,
+        }));
+        let summary = serde_json::json!({
+            "healthy":          self.is_healthy(),
+            "stages":           stages,
+            "working":          self.stages.iter().map(|(_, count)| count).sum::<usize>(),
+            "archived":         self.archived,
+            "overdue_invoices": overdue,
+            "payed_unarchived": self.payed_unarchived,
+            "broken":           self.broken,
+            "git":              git,
+        });
+        Ok(serde_json::to_string_pretty(&summary)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project(content: &str) -> Project {
+        Project::from_file_content(&format!("event:\n  name: Fest\n  dates:\n  - begin: 01.03.2024\nhours:\n  salary: 8.0\n{}", content)).unwrap()
+    }
+
+    #[test]
+    fn storage_summary() {
+        let projects = vec![project(""),
+                            project("offer:\n  date: 01.02.2024\n  sent_date: 02.02.2024\n"),
+                            project("invoice:\n  number: 1\n  date: 01.03.2024\n"),
+                            project("invoice:\n  number: 2\n  date: 28.03.2024\n"),
+                            project("invoice:\n  number: 3\n  date: 01.03.2024\n  payed_date: 10.03.2024\n"),
+                            project("canceled: true\ninvoice:\n  number: 4\n  date: 01.03.2024\n")];
+        assert_eq!(Lifecycle::of(&projects[0]), Lifecycle::Draft);
+        assert_eq!(Lifecycle::of(&projects[1]), Lifecycle::Offered);
+
+        let summary = Summary::new(&projects, Utc.ymd(2024, 4, 1));
+        let count = |stage| summary.stages.iter().find(|(s, _)| *s == stage).map(|(_, count)| *count);
+        assert_eq!(count(Lifecycle::Invoiced), Some(2));
+        assert_eq!(count(Lifecycle::Canceled), Some(1));
+        assert_eq!(count(Lifecycle::Accepted), Some(0));
+
+        // due two weeks after the invoice date
+        assert_eq!(summary.overdue.len(), 1);
+        assert_eq!(summary.overdue[0].invoice, projects[2].invoice().number_str());
+        assert_eq!(summary.payed_unarchived.len(), 1);
+        assert!(!summary.is_healthy());
+    }
+}
@@ -0,0 +1,230 @@
+//! Scaffolding and linting of templates, see `asciii template`.
+//!
+//! Project templates are filled with sample values for every known placeholder and declared variable and read back as a project.
+//! Document templates are then rendered against that sample project, referencing a field it does not have is an error.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::project::error::Severity;
+use crate::project::Project;
+use crate::templater::{self, split_front_matter, IsKeyword, Variable, VariableKind};
+
+/// Placeholders asciii knows about: keyword, sample value and where the value comes from.
+pub const PLACEHOLDERS: &[(&str, &str, &str)] = &[
+    ("PROJECT-NAME",   "Sample Project",    "name given to `asciii new`"),
+    ("TEMPLATE",       "sample",            "name of this template"),
+    ("VERSION",        "0.0.0",             "version of asciii that created the project"),
+    ("DATE-CREATED",   "01.12.2024",        "day the project was created"),
+    ("DATE-EVENT",     "24.12.2024",        "`--date`, defaults to two weeks from now"),
+    ("TIME-START",     "10:00",             "`--time`"),
+    ("TIME-END",       "14:00",             "`--time_end`"),
+    ("DESCRIPTION",    "Sample description", "`--description`"),
+    ("MANAGER",        "Max Mustermann",    "`--manager`, defaults to `user/name`"),
+    ("TAX",            "0.19",              "`defaults/tax`"),
+    ("SALARY",         "8.0",               "`defaults/salary`"),
+    ("INVOICE-NUMBER", "1",                 "left for `asciii invoice`"),
+    ("INVOICE-DATE",   "27.12.2024",        "left for `asciii set`"),
+    ("PAYED-DATE",     "31.12.2024",        "left for `asciii set`"),
+];
+
+/// Something `check_project_template()` or `check_document_template()` noticed.
+#[derive(Debug, Clone)]
+pub struct Finding {
+    pub template: PathBuf,
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl Finding {
+    fn new(template: &Path, severity: Severity, message: String) -> Finding {
+        Finding { template: template.to_owned(), severity, message }
+    }
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = self.template.file_name().map(|n| n.to_string_lossy()).unwrap_or_default();
+        write!(f, "[{}] {}: {}", self.severity, name, self.message)
+    }
+}
+
+const HEADER: &str = r###"--- # asciii document  (version: ##VERSION##, template: ##TEMPLATE##)
+# vim:set ft=yaml:
+#
+# Placeholders are keywords between double hashes, these are filled in:
+"###;
+
+const BODY: &str = r###"#
+# Everything else can be set later with `asciii set <project> <field> <value>`.
+
+client:
+  title:     # Herr # Frau # Mr, Ms, Mrs
+  first_name:
+  last_name:
+  email:
+  address: |
+
+event:
+  name: ##PROJECT-NAME##
+  location:
+  dates:
+  - begin: ##DATE-EVENT##
+    times:
+    -  begin: "##TIME-START##"
+       end:   "##TIME-END##"
+  description: |
+    ##DESCRIPTION##
+
+offer:
+  date: ##DATE-CREATED##
+  appendix: 1
+
+invoice:
+  number:     ##INVOICE-NUMBER##
+  date:       ##INVOICE-DATE##
+  payed_date: ##PAYED-DATE##
+
+cataloge:
+  product: &sample { name: Sample, price: 1.0, unit: stk }
+
+products:
+  *sample:
+    amount: 1
+
+hours:
+  salary: ##SALARY##
+  caterers:
+    "unknown" : 0
+
+manager: ##MANAGER##
+canceled: no
+tax: ##TAX##
+
+meta:
+  invoicer_version: ##VERSION##
+  template: ##TEMPLATE##
+  format: 3.0.0
+created: ##DATE-CREATED##
+...
+"###;
+
+/// Content of a new project template with every placeholder in place and explained.
+pub fn scaffold() -> String {
+    let explained = PLACEHOLDERS.iter()
+        .map(|(keyword, _, source)| format!("#   {:<15} {}\n", keyword, source))
+        .collect::<String>();
+
+    [HEADER, &explained, BODY].concat()
+}
+
+/// Sample value of a declared variable without a default.
+fn sample_of(kind: VariableKind) -> &'static str {
+    match kind {
+        VariableKind::Text    => "sample",
+        VariableKind::Integer => "1",
+        VariableKind::Decimal => "1.0",
+        VariableKind::Bool    => "true",
+        VariableKind::Date    => "24.12.2024",
+        VariableKind::Time    => "10:00",
+    }
+}
+
+/// Fills every placeholder with its sample value, declared `variables` with their default, unknown ones with `sample`.
+pub fn sample_content(template: &str, variables: &[Variable]) -> String {
+    let mut samples = PLACEHOLDERS.iter()
+                                  .map(|&(keyword, sample, _)| (keyword, sample.to_owned()))
+                                  .collect::<HashMap<&str, String>>();
+    for variable in variables {
+        samples.insert(&variable.name, variable.default.clone().unwrap_or_else(|| sample_of(variable.kind).to_owned()));
+    }
+    template.map_keywords(|keyword| samples.get(keyword).cloned().unwrap_or_else(|| String::from("sample")))
+}
+
+/// Checks a project template, returns the sample project it makes if it can be read at all.
+pub fn check_project_template(path: &Path) -> (Vec<Finding>, Option<Project>) {
+    let mut findings = Vec::new();
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) => return (vec![Finding::new(path, Severity::Error, e.to_string())], None)
+    };
+    let variables = templater::variables(&content).unwrap_or_else(|e| {
+        findings.push(Finding::new(path, Severity::Error, lformat!("the front-matter is broken: {}", e)));
+        Vec::new()
+    });
+    let template = split_front_matter(&content).1;
+
+    let mut keywords = template.list_keywords();
+    keywords.sort();
+    keywords.dedup();
+    for keyword in &keywords {
+        if !PLACEHOLDERS.iter().any(|(known, _, _)| known == keyword) && !variables.iter().any(|variable| &variable.name == keyword) {
+            findings.push(Finding::new(path, Severity::Warning,
+                                       lformat!("unknown placeholder {:?}, it is never filled in", keyword)));
+        }
+    }
+    for variable in variables.iter().filter(|variable| !keywords.contains(&variable.name)) {
+        findings.push(Finding::new(path, Severity::Warning, lformat!("the variable {:?} is not used", variable.name)));
+    }
+    if !keywords.iter().any(|k| k == "PROJECT-NAME") {
+        findings.push(Finding::new(path, Severity::Warning, lformat!("the project name is never filled in")));
+    }
+
+    match Project::from_file_content(&sample_content(template, &variables)) {
+        Ok(project) => (findings, Some(project)),
+        Err(e) => {
+            findings.push(Finding::new(path, Severity::Error, lformat!("sample project can't be read: {}", e)));
+            (findings, None)
+        }
+    }
+}
+
+/// Renders a document template against a sample project, as offer and as invoice.
+#[cfg(feature = "document_export")]
+pub fn check_document_template(path: &Path, backend: &'static dyn crate::document_export::backend::DocumentBackend, sample: &Project) -> Vec<Finding> {
+    use crate::project::BillType;
+    use crate::project::export::{Complete, ExportTarget};
+
+    let exported: Complete = sample.export();
+    [BillType::Offer, BillType::Invoice].iter()
+        .filter_map(|&bill_type| crate::document_export::check_template(&exported, bill_type, path, backend).err()
+                                     .map(|e| (bill_type, e)))
+        .map(|(bill_type, e)| Finding::new(path, Severity::Error, lformat!("{} can't be rendered: {}", bill_type.to_string(), e)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempdir::TempDir;
+
+    #[test]
+    fn scaffold_is_clean() {
+        let dir = TempDir::new("asciii-template").unwrap();
+        let path = dir.path().join("new.tyml");
+        fs::write(&path, scaffold()).unwrap();
+
+        let (findings, sample) = check_project_template(&path);
+        assert!(findings.is_empty(), "{:?}", findings);
+        assert_eq!(sample.unwrap().field("event/name"), Some("Sample Project".into()));
+    }
+
+    #[test]
+    fn unknown_placeholders() {
+        let dir = TempDir::new("asciii-template").unwrap();
+        let path = dir.path().join("broken.tyml");
+        fs::write(&path, "event:\n  name: ##LOCATION##\n").unwrap();
+
+        let (findings, sample) = check_project_template(&path);
+        assert!(findings.iter().any(|f| f.message.contains("LOCATION")));
+        assert!(findings.iter().any(|f| f.message.contains("project name")));
+        assert!(sample.is_some());
+
+        fs::write(&path, "event: [\n").unwrap();
+        let (findings, sample) = check_project_template(&path);
+        assert!(findings.iter().any(|f| f.severity == Severity::Error));
+        assert!(sample.is_none());
+    }
+}
@@ -0,0 +1,135 @@
+//! Hours of one employee across all projects of a month, see `asciii timesheet`.
+//!
+//! Hours are not tracked per day, every project counts on the date of its event.
+
+use bill::Currency;
+use chrono::prelude::*;
+
+use crate::error::AsciiiError;
+use crate::project::spec::{HasEmployees, IsProject};
+use crate::project::Project;
+
+/// Hours an employee worked on one project.
+#[derive(Debug, Clone)]
+pub struct Entry {
+    pub date: Date<Utc>,
+    pub project: String,
+    pub employee: String,
+    pub time: f64,
+    pub salary: Currency,
+    pub wage: Currency,
+}
+
+/// Everything an employee worked in one month.
+#[derive(Debug, Clone)]
+pub struct Timesheet {
+    /// name as searched for
+    pub employee: String,
+    pub year: i32,
+    pub month: u32,
+    /// ordered by date
+    pub entries: Vec<Entry>,
+}
+
+/// Parses `2024-06` into year and month.
+pub fn parse_month(month: &str) -> Option<(i32, u32)> {
+    let (year, month) = month.trim().split_once('-')?;
+    let (year, month) = (year.parse().ok()?, month.parse().ok()?);
+    if (1..=12).contains(&month) {
+        Some((year, month))
+    } else {
+        None
+    }
+}
+
+impl Timesheet {
+    /// Collects the hours of every employee whose name contains `employee` (ignoring case),
+    /// canceled projects don't count.
+    pub fn new(projects: &[Project], employee: &str, year: i32, month: u32) -> Timesheet {
+        let needle = employee.to_lowercase();
+        let mut entries = projects.iter()
+            .filter(|p| !p.canceled())
+            .filter_map(|p| p.event_date().ok().map(|date| (p, date)))
+            .filter(|(_, date)| date.year() == year && date.month() == month)
+            .flat_map(|(p, date)| {
+                let project = p.name().unwrap_or("").to_owned();
+                p.hours().employees()
+                 .unwrap_or_default()
+                 .into_iter()
+                 .filter(|e| e.name.to_lowercase().contains(&needle))
+                 .map(move |e| Entry {
+                     date,
+                     project: project.clone(),
+                     employee: e.name,
+                     time: e.time,
+                     salary: e.salary,
+                     wage: e.wage,
+                 })
+            })
+            .collect::<Vec<Entry>>();
+        entries.sort_by(|a, b| a.date.cmp(&b.date).then_with(|| a.project.cmp(&b.project)));
+
+        Timesheet { employee: employee.to_owned(), year, month, entries }
+    }
+
+    pub fn total_time(&self) -> f64 {
+        self.entries.iter().map(|e| e.time).sum()
+    }
+
+    pub fn total_wage(&self) -> Currency {
+        self.entries.iter().fold(Currency::default(), |acc, e| acc + e.wage)
+    }
+
+    /// One line per entry and a total, separated by `;` like `asciii csv`.
+    pub fn to_csv(&self) -> Result<String, AsciiiError> {
+        let mut writer = csv::WriterBuilder::new().delimiter(b';').from_writer(Vec::new());
+        writer.write_record(&[lformat!("Date"), lformat!("Project"), lformat!("Employee"),
+                              lformat!("Hours"), lformat!("Salary"), lformat!("Wage")])?;
+        for entry in &self.entries {
+            writer.write_record(&[entry.date.format("%d.%m.%Y").to_string(),
+                                  entry.project.clone(),
+                                  entry.employee.clone(),
+                                  entry.time.to_string(),
+                                  format!("{:.2}", entry.salary.as_float()),
+                                  format!("{:.2}", entry.wage.as_float())])?;
+        }
+        writer.write_record(&[lformat!("Total"), String::new(), String::new(),
+                              self.total_time().to_string(),
+                              String::new(),
+                              format!("{:.2}", self.total_wage().as_float())])?;
+        Ok(String::from_utf8(writer.into_inner()?)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project(name: &str, date: &str, caterers: &str) -> Project {
+        Project::from_file_content(&format!("event:\n  name: {}\n  dates:\n  - begin: {}\nhours:\n  salary: 10\n  caterers:\n{}",
+                                            name, date, caterers)).unwrap()
+    }
+
+    #[test]
+    fn hours_of_one_month() {
+        let projects = vec![project("Second", "12.06.2024", "    Maxime: 2\n    Sebastian: 1\n"),
+                            project("First", "01.06.2024", "    maxime: 3.5\n"),
+                            project("Other Month", "01.07.2024", "    Maxime: 8\n")];
+
+        let sheet = Timesheet::new(&projects, "Maxime", 2024, 6);
+        assert_eq!(sheet.entries.iter().map(|e| e.project.as_str()).collect::<Vec<_>>(), vec!["First", "Second"]);
+        assert_eq!(sheet.total_time(), 5.5);
+        assert_eq!(sheet.total_wage().as_float(), 55.0);
+
+        let csv = sheet.to_csv().unwrap();
+        assert_eq!(csv.lines().nth(1), Some("01.06.2024;First;maxime;3.5;10.00;35.00"));
+        assert_eq!(csv.lines().count(), 4);
+    }
+
+    #[test]
+    fn months() {
+        assert_eq!(parse_month("2024-06"), Some((2024, 6)));
+        assert_eq!(parse_month("2024-13"), None);
+        assert_eq!(parse_month("june"), None);
+    }
+}
@@ -0,0 +1,177 @@
+//! Managers who can be responsible for projects, see `asciii user`.
+//!
+//! They are listed in `extras/users.yml` of the storage, entries are names or names with an email:
+//!
+//! ```yaml
+//! - name: Max Mustermann
+//!   email: max@example.com
+//! - Erika Musterfrau
+//! ```
+//!
+//! Without that file the only known manager is you, from `user/name` or `git config user.name`,
+//! and `asciii validate` does not check managers at all.
+
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::error::{ActionError, AsciiiError};
+use crate::project::error::{Diagnostic, Severity};
+use crate::project::spec::IsProject;
+use crate::project::Project;
+use crate::storage::{Storable, Storage};
+use crate::util;
+use crate::util::yaml::{self, Yaml};
+
+pub const USERS_FILE: &str = "users.yml";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct User {
+    pub name: String,
+    pub email: Option<String>,
+}
+
+impl User {
+    pub fn new(name: &str, email: Option<&str>) -> User {
+        User { name: name.trim().to_owned(), email: email.map(|e| e.trim().to_owned()) }
+    }
+
+    /// Names are compared ignoring case and surrounding whitespace.
+    pub fn is(&self, name: &str) -> bool {
+        self.name.to_lowercase() == name.trim().to_lowercase()
+    }
+}
+
+impl fmt::Display for User {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.email {
+            Some(ref email) => write!(f, "{} <{}>", self.name, email),
+            None => write!(f, "{}", self.name)
+        }
+    }
+}
+
+/// Reads the content of a `users.yml`.
+pub fn parse(content: &str) -> Result<Vec<User>, AsciiiError> {
+    let entries = match yaml::parse(content)? {
+        Yaml::Array(entries) => entries,
+        // nothing but comments
+        _ if content.lines().all(|line| line.trim().is_empty() || line.trim_start().starts_with('#')) => Vec::new(),
+        _ => return Err(ActionError::Failed(lformat!("{} must be a list of users", USERS_FILE)).into())
+    };
+    entries.iter()
+           .map(|entry| match *entry {
+               Yaml::String(ref name) => Ok(User::new(name, None)),
+               Yaml::Hash(_) => match yaml::get_str(entry, "name") {
+                   Some(name) => Ok(User::new(name, yaml::get_str(entry, "email"))),
+                   None => Err(ActionError::Failed(lformat!("user without a name in {}", USERS_FILE)).into())
+               },
+               _ => Err(ActionError::Failed(lformat!("user without a name in {}", USERS_FILE)).into())
+           })
+           .collect()
+}
+
+/// Content of a `users.yml` listing `users`.
+pub fn to_yaml(users: &[User]) -> String {
+    let mut content = String::from("# managers who can be responsible for projects, see `asciii user`\n");
+    for user in users {
+        content.push_str(&format!("- name: {:?}\n", user.name));
+        if let Some(ref email) = user.email {
+            content.push_str(&format!("  email: {:?}\n", email));
+        }
+    }
+    content
+}
+
+/// Users listed in the storage, `None` if there is no `users.yml`.
+pub fn listed<L: Storable>(storage: &Storage<L>) -> Result<Option<Vec<User>>, AsciiiError> {
+    let path = storage.get_extra_file(USERS_FILE)?;
+    if !path.exists() {
+        return Ok(None);
+    }
+    parse(&fs::read_to_string(&path)?).map(Some)
+}
+
+/// Users listed in the storage or just you.
+pub fn load<L: Storable>(storage: &Storage<L>) -> Result<Vec<User>, AsciiiError> {
+    if let Some(users) = listed(storage)? {
+        return Ok(users);
+    }
+    let mut users = Vec::new();
+    let names = crate::CONFIG.get_str_or("user/name").map(ToOwned::to_owned).into_iter()
+                             .chain(util::git_user_name());
+    for name in names.filter(|name| !name.trim().is_empty()) {
+        if !users.iter().any(|user: &User| user.is(&name)) {
+            users.push(User::new(&name, None));
+        }
+    }
+    Ok(users)
+}
+
+/// Writes `extras/users.yml`, creating `extras/` if necessary.
+pub fn save<L: Storable>(storage: &Storage<L>, users: &[User]) -> Result<PathBuf, AsciiiError> {
+    fs::create_dir_all(storage.extras_dir())?;
+    let path = storage.get_extra_file(USERS_FILE)?;
+    util::atomic_write(&path, to_yaml(users))?;
+    Ok(path)
+}
+
+pub fn add<L: Storable>(storage: &Storage<L>, user: User) -> Result<PathBuf, AsciiiError> {
+    let mut users = load(storage)?;
+    if users.iter().any(|known| known.is(&user.name)) {
+        return Err(ActionError::Failed(lformat!("{} is already listed", user.name)).into());
+    }
+    users.push(user);
+    save(storage, &users)
+}
+
+pub fn remove<L: Storable>(storage: &Storage<L>, name: &str) -> Result<PathBuf, AsciiiError> {
+    let mut users = load(storage)?;
+    let count = users.len();
+    users.retain(|user| !user.is(name));
+    if users.len() == count {
+        return Err(ActionError::NothingFound(vec![name.to_owned()]).into());
+    }
+    save(storage, &users)
+}
+
+/// One warning for every project whose manager is not one of `users`.
+pub fn unknown_managers(projects: &[Project], users: &[User]) -> Vec<Diagnostic> {
+    projects.iter()
+            .filter_map(|project| project.responsible().ok()
+                                         .filter(|manager| !users.iter().any(|user| user.is(manager)))
+                                         .map(|manager| Diagnostic {
+                                             file: project.file(),
+                                             field: "manager".into(),
+                                             stage: "offer".into(),
+                                             severity: Severity::Warning,
+                                             message: lformat!("{:?} is not listed in {}", manager, USERS_FILE),
+                                         }))
+            .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roundtrip() {
+        let users = parse("- name: Max Mustermann\n  email: max@example.com\n- Erika Musterfrau\n").unwrap();
+        assert_eq!(users, vec![User::new("Max Mustermann", Some("max@example.com")),
+                               User::new("Erika Musterfrau", None)]);
+        assert_eq!(parse(&to_yaml(&users)).unwrap(), users);
+        assert!(parse(&to_yaml(&[])).unwrap().is_empty());
+        assert!(parse("- email: nobody@example.com\n").is_err());
+    }
+
+    #[test]
+    fn flags_unknown_managers() {
+        let known = Project::from_file_content("manager: max mustermann\n").unwrap();
+        let unknown = Project::from_file_content("manager: Hans\n").unwrap();
+        let users = vec![User::new("Max Mustermann", None)];
+
+        let diagnostics = unknown_managers(&[known, unknown], &users);
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].message.contains("Hans"));
+    }
+}
@@ -0,0 +1,303 @@
+//! Files that belong to a project, next to the project file or on a WebDAV server like Nextcloud.
+//!
+//! Large files don't belong into git. With `attachments/webdav/url` set, `asciii attach --remote` uploads them
+//! to `<url>/<project dir>/<file>` and lists them under `attachments` in the project file instead:
+//!
+//! ```yaml
+//! attachments:
+//!   - name: "plan.pdf"
+//!     url: "https://cloud.example.com/remote.php/dav/files/me/asciii/party/plan.pdf"
+//!     share: "https://cloud.example.com/s/Xy7kP"
+//!     size: 183424
+//! ```
+//!
+//! `share` is a public link made with the Nextcloud sharing API if the url points into a Nextcloud,
+//! otherwise it is the url itself. `asciii doctor` checks that every listed file is still on the server.
+//! Requests go through `curl` (see `util::http`), the password is read from `ASCIII_WEBDAV_PASSWORD`
+//! and handed to curl on its stdin, never on the command line.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use yaml_rust::Yaml;
+
+use crate::config::ConfigReader;
+use crate::error::{ActionError, AsciiiError};
+use crate::project::Project;
+use crate::storage::Storable;
+use crate::util::http::{Request, Secret};
+use crate::util::percent_encode;
+use crate::util::yaml;
+
+lazy_static! {
+    static ref SHARE_URL: Regex = Regex::new(r"<url>([^<]+)</url>").unwrap();
+}
+
+/// A file on the WebDAV server, as listed in the project file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteAttachment {
+    pub name: String,
+    /// where the file is on the server
+    pub url: String,
+    /// where others can download it
+    pub share: String,
+    /// in bytes, at the time of the upload
+    pub size: u64,
+}
+
+impl RemoteAttachment {
+    fn from_yaml(yaml: &Yaml) -> Option<RemoteAttachment> {
+        let url = yaml::get_string(yaml, "url")?;
+        Some(RemoteAttachment {
+            name: yaml::get_string(yaml, "name")?,
+            share: yaml::get_string(yaml, "share").unwrap_or_else(|| url.clone()),
+            size: yaml::get_int(yaml, "size").map_or(0, |size| size.max(0) as u64),
+            url,
+        })
+    }
+
+    fn to_lines(&self) -> String {
+        format!("  - name: {:?}\n    url: {:?}\n    share: {:?}\n    size: {}",
+                self.name, self.url, self.share, self.size)
+    }
+
+    /// The remote attachments listed in `project`.
+    pub fn of(project: &Project) -> Vec<RemoteAttachment> {
+        list(project.yaml())
+    }
+}
+
+fn list(doc: &Yaml) -> Vec<RemoteAttachment> {
+    yaml::get(doc, "attachments")
+        .and_then(Yaml::as_vec)
+        .map(|entries| entries.iter().filter_map(RemoteAttachment::from_yaml).collect())
+        .unwrap_or_default()
+}
+
+/// `content` of a project file with `attachment` listed, replacing one of the same name.
+pub fn with_remote(content: &str, attachment: &RemoteAttachment) -> Result<String, AsciiiError> {
+    let mut attachments = list(&yaml::parse(content)?);
+    attachments.retain(|a| a.name != attachment.name);
+    attachments.push(attachment.clone());
+
+    let block = std::iter::once(String::from("attachments:"))
+        .chain(attachments.iter().map(RemoteAttachment::to_lines))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let without = yaml::remove_in_text(content, "attachments").unwrap_or_else(|| content.to_owned());
+    let changed = yaml::insert_block_in_text(&without, "attachments", &block)
+        .ok_or_else(|| ActionError::Failed(lformat!("can't list {} in the project file", attachment.name)))?;
+
+    // make sure the result still reads like a project
+    if !list(&yaml::parse(&changed)?).contains(attachment) {
+        return Err(ActionError::Failed(lformat!("can't list {} in the project file", attachment.name)).into());
+    }
+    Ok(changed)
+}
+
+/// Copies `file` next to the project file, nothing is overwritten.
+pub fn attach_local(project: &Project, file: &Path) -> Result<PathBuf, AsciiiError> {
+    let name = file.file_name()
+                   .ok_or_else(|| ActionError::Failed(lformat!("{} is not a file", file.display())))?;
+    let target = project.dir().join(name);
+    if target.exists() {
+        return Err(ActionError::Failed(lformat!("{} already exists", target.display())).into());
+    }
+    fs::copy(file, &target)?;
+    Ok(target)
+}
+
+/// The WebDAV server in `attachments/webdav`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Webdav {
+    /// folder all projects go into, without a trailing `/`
+    pub url: String,
+    pub user: Option<String>,
+    /// `user` with `ASCIII_WEBDAV_PASSWORD`, kept out of `Debug`
+    auth: Option<Secret>,
+}
+
+impl Webdav {
+    /// `None` if there is no `attachments/webdav/url`.
+    pub fn from_config(config: &ConfigReader) -> Option<Webdav> {
+        let url = config.get_str_or("attachments/webdav/url").map(str::trim).filter(|url| !url.is_empty())?;
+        let user = config.get_str_or("attachments/webdav/user").filter(|user| !user.is_empty()).map(ToOwned::to_owned);
+        let password = std::env::var("ASCIII_WEBDAV_PASSWORD").unwrap_or_default();
+        Some(Webdav {
+            url: url.trim_end_matches('/').to_owned(),
+            auth: user.as_deref().map(|user| Secret::basic_auth(user, &password)),
+            user,
+        })
+    }
+
+    /// From `crate::CONFIG`.
+    pub fn configured() -> Option<Webdav> {
+        Webdav::from_config(&crate::CONFIG)
+    }
+
+    fn curl(&self) -> Request {
+        let request = Request::new(lformat!("curl is needed for remote attachments"));
+        match self.auth {
+            Some(ref auth) => request.secret(auth.clone()),
+            None => request,
+        }
+    }
+
+    /// Where `name` of the project in `folder` goes.
+    pub fn remote_url(&self, folder: &str, name: &str) -> String {
+        format!("{}/{}/{}", self.url, percent_encode(folder), percent_encode(name))
+    }
+
+    /// Creates the folder at `url`, it may exist already.
+    fn make_folder(&self, url: &str) -> Result<(), AsciiiError> {
+        let output = self.curl().args(["--request", "MKCOL", "--output", "/dev/null", "--write-out", "%{http_code}", url]).idempotent().output()?;
+        match String::from_utf8_lossy(&output.stdout).trim() {
+            // 405: there is something already
+            "201" | "405" => Ok(()),
+            code => Err(ActionError::Failed(lformat!("can't create {} on the server ({})", url, code)).into()),
+        }
+    }
+
+    /// Uploads `file` into `folder`, a file of the same name is replaced.
+    pub fn upload(&self, file: &Path, folder: &str) -> Result<RemoteAttachment, AsciiiError> {
+        let name = file.file_name()
+                       .map(|name| name.to_string_lossy().into_owned())
+                       .ok_or_else(|| ActionError::Failed(lformat!("{} is not a file", file.display())))?;
+        let size = fs::metadata(file)?.len();
+
+        self.make_folder(&format!("{}/{}", self.url, percent_encode(folder)))?;
+        let url = self.remote_url(folder, &name);
+        log::debug!("uploading {} to {}", file.display(), url);
+        // large files take as long as they take
+        let output = self.curl()
+                         .arg("--fail").arg("--upload-file").arg(file).args(["--output", "/dev/null", &url])
+                         .idempotent()
+                         .timeout(None)
+                         .output()?;
+        if !output.status.success() {
+            return Err(ActionError::Failed(lformat!("uploading {} failed: {}", name, String::from_utf8_lossy(&output.stderr).trim())).into());
+        }
+
+        let share = match self.share(&url) {
+            Ok(Some(share)) => share,
+            Ok(None) => url.clone(),
+            Err(e) => {
+                log::warn!("{}", lformat!("no share link for {}: {}", name, e));
+                url.clone()
+            }
+        };
+        Ok(RemoteAttachment { name, url, share, size })
+    }
+
+    /// Size of the file at `url`, `None` if it is not there.
+    pub fn size(&self, url: &str) -> Result<Option<u64>, AsciiiError> {
+        let output = self.curl().args(["--head", "--fail", url]).idempotent().output()?;
+        match output.status.code() {
+            Some(0) => Ok(content_length(&String::from_utf8_lossy(&output.stdout))),
+            // the server answered with an error, e.g. 404
+            Some(22) => Ok(None),
+            _ => Err(ActionError::Failed(lformat!("can't reach {}: {}", url, String::from_utf8_lossy(&output.stderr).trim())).into()),
+        }
+    }
+
+    /// A public link to `url` if the server is a Nextcloud, `None` otherwise.
+    fn share(&self, url: &str) -> Result<Option<String>, AsciiiError> {
+        let (server, path) = match nextcloud_path(url) {
+            Some(found) => found,
+            None => return Ok(None),
+        };
+        let output = self.curl()
+            .args(["--fail", "--header", "OCS-APIRequest: true"])
+            .args(["--data-urlencode", &format!("path={}", path)])
+            .args(["--data", "shareType=3"])
+            .arg(format!("{}/ocs/v2.php/apps/files_sharing/api/v1/shares", server))
+            .output()?;
+        if !output.status.success() {
+            return Err(ActionError::Failed(String::from_utf8_lossy(&output.stderr).trim().to_owned()).into());
+        }
+        Ok(SHARE_URL.captures(&String::from_utf8_lossy(&output.stdout)).map(|caps| caps[1].to_owned()))
+    }
+}
+
+/// `Content-Length` of the headers `curl --head` printed, the last response counts.
+fn content_length(headers: &str) -> Option<u64> {
+    headers.lines()
+           .rev()
+           .filter_map(|line| line.split_once(':'))
+           .filter(|(name, _)| name.trim().eq_ignore_ascii_case("content-length"))
+           .filter_map(|(_, value)| value.trim().parse().ok())
+           .next()
+}
+
+/// The Nextcloud server and the path of the file as its user sees it, from a WebDAV url.
+fn nextcloud_path(url: &str) -> Option<(String, String)> {
+    let (server, files) = url.split_once("/remote.php/dav/files/")?;
+    let (_user, path) = files.split_once('/')?;
+    let path = percent_decode(path);
+    Some((server.to_owned(), format!("/{}", path)))
+}
+
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match (bytes[i], text.get(i + 1..i + 3).and_then(|hex| u8::from_str_radix(hex, 16).ok())) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attachment(name: &str, size: u64) -> RemoteAttachment {
+        RemoteAttachment {
+            name: name.into(),
+            url: format!("https://cloud.example.com/remote.php/dav/files/me/asciii/party/{}", name),
+            share: "https://cloud.example.com/s/Xy7kP".into(),
+            size,
+        }
+    }
+
+    #[test]
+    fn listed_in_the_project_file() {
+        let content = "event:\n  name: Party # the big one\nmanager: Max\n";
+        let once = with_remote(content, &attachment("plan.pdf", 10)).unwrap();
+        assert!(once.starts_with("event:\n  name: Party # the big one\n"));
+
+        let twice = with_remote(&once, &attachment("Gäste liste.ods", 20)).unwrap();
+        let replaced = with_remote(&twice, &attachment("plan.pdf", 30)).unwrap();
+        let project = Project::from_file_content(&replaced).unwrap();
+        let listed = RemoteAttachment::of(&project);
+        assert_eq!(listed, vec![attachment("Gäste liste.ods", 20), attachment("plan.pdf", 30)]);
+    }
+
+    #[test]
+    fn nextcloud_urls() {
+        let webdav = Webdav { url: "https://cloud.example.com/remote.php/dav/files/me/asciii".into(), user: None, auth: None };
+        let url = webdav.remote_url("party", "Gäste liste.ods");
+        assert_eq!(url, "https://cloud.example.com/remote.php/dav/files/me/asciii/party/G%C3%A4ste%20liste.ods");
+        assert_eq!(nextcloud_path(&url), Some(("https://cloud.example.com".into(), "/asciii/party/Gäste liste.ods".into())));
+        assert_eq!(nextcloud_path("https://dav.example.com/asciii/party/plan.pdf"), None);
+
+        assert_eq!(content_length("HTTP/1.1 200 OK\r\nContent-Length: 183424\r\nETag: x\r\n"), Some(183_424));
+    }
+
+    #[test]
+    fn password_is_not_printed() {
+        let webdav = Webdav { url: "https://dav.example.com".into(), user: Some("me".into()), auth: Some(Secret::basic_auth("me", "hunter2")) };
+        assert!(!format!("{:?}", webdav).contains("hunter2"));
+    }
+}
@@ -0,0 +1,314 @@
+//! Handles config files and default config.
+//!
+//! Looks for `DEFAULT_LOCATION` and patches unset fields from `DEFAULT_CONFIG`
+//!
+//! Settings are looked up in this order:
+//!
+//! 1. `.asciii.yml` in the current directory
+//! 2. `.asciii.yml` in the storage root, once `storage::setup()` found the storage
+//! 3. `~/.asciii.yml`
+//! 4. `DEFAULT_CONFIG`
+
+#![warn(missing_docs,
+        missing_copy_implementations,
+        trivial_casts, trivial_numeric_casts,
+        unsafe_code,
+        unstable_features,
+        unused_import_braces, unused_qualifications)]
+//#![warn(missing_debug_implementations)]
+
+
+use std::env::{self, current_dir};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+#[cfg(not(target_arch = "wasm32"))]
+use dirs::home_dir;
+#[cfg(target_arch = "wasm32")]
+use crate::util::dirs::home_dir;
+
+use crate::error::{AsciiiError, ConfigError};
+use crate::util::yaml::{self, Yaml};
+
+pub mod schema;
+
+/// Name of the configfile
+pub const DEFAULT_LOCATION: &str = ".asciii.yml";
+
+/// Default configuration that will be used if a value is not set in yaml file at `DEFAULT_LOCATION`
+pub const DEFAULT_CONFIG: &str = include_str!("../default_config.yml");
+
+/// Looks for a configuration yaml in your `HOME_DIR`
+///
+/// Nothing is read before the first lookup, so creating one is cheap.
+/// `crate::CONFIG` is the one the command line tool uses, libraries can pass their own,
+/// see `Storage::setup()` and `ListConfig::from_config()`.
+#[derive(Debug)]
+pub struct ConfigReader {
+    /// Path of config file
+    pub path: PathBuf,
+    /// `.asciii.yml` in the current directory, if it is looked at
+    local_path: Option<PathBuf>,
+    layers: OnceLock<Layers>,
+    /// config file in the storage root and its content
+    storage: OnceLock<(PathBuf, Yaml)>,
+}
+
+#[derive(Debug)]
+struct Layers {
+    defaults: Yaml,
+    custom: Yaml,
+    local: Yaml,
+}
+
+impl ConfigReader {
+    /// The Path of the config file.
+    pub fn path_home() -> PathBuf {
+        let home = home_dir().expect("Can't find HOME DIRECTORY");
+        home.join(DEFAULT_LOCATION)
+    }
+
+    /// Config from `path_home()` and `DEFAULT_LOCATION`, read on the first lookup.
+    pub fn try_new() -> Result<ConfigReader, AsciiiError> {
+        Ok(ConfigReader {
+            path: ConfigReader::path_home(),
+            local_path: Some(PathBuf::from(DEFAULT_LOCATION)),
+            layers: OnceLock::new(),
+            storage: OnceLock::new(),
+        })
+    }
+
+    /// Config from `content` on top of `DEFAULT_CONFIG`, no files are looked at.
+    ///
+    /// `path` is where `asciii config` would write changes to.
+    pub fn from_content(path: &Path, content: &str) -> Result<ConfigReader, AsciiiError> {
+        let layers = Layers {
+            defaults: yaml::parse(DEFAULT_CONFIG)?,
+            custom: yaml::parse(content)?,
+            local: Yaml::Null,
+        };
+        Ok(ConfigReader {
+            path: path.to_owned(),
+            local_path: None,
+            layers: OnceLock::from(layers),
+            storage: OnceLock::new(),
+        })
+    }
+
+    fn layers(&self) -> &Layers {
+        self.layers.get_or_init(|| {
+            let _timer = crate::util::timing::start("config");
+            log::trace!("reading config {}", self.path.display());
+            let layers = Layers {
+                defaults: yaml::parse(DEFAULT_CONFIG).expect("default config is broken"),
+                custom: yaml::open(&self.path).unwrap_or(Yaml::Null),
+                local: self.local_path.as_ref().and_then(|path| yaml::open(path).ok()).unwrap_or(Yaml::Null),
+            };
+
+            let problems = schema::check(&layers.custom).into_iter().map(|p| (self.path.clone(), p))
+                .chain(schema::check(&layers.local).into_iter().map(|p| (PathBuf::from(DEFAULT_LOCATION), p)));
+            for (path, problem) in problems {
+                log::warn!("{}: {}", path.display(), problem);
+            }
+
+            if !self.path.exists() {
+                log::warn!("{} does not exist, falling back to defaults", self.path.display());
+            }
+
+            if let (Some(local_path), Some(home_dir), Ok(current_dir)) = (&self.local_path, home_dir(), current_dir()) {
+                if local_path.exists() && current_dir != home_dir {
+                    log::warn!("{} exists, this overrides defaults and user settings",
+                          local_path.display())
+                }
+            }
+            layers
+        })
+    }
+
+    /// Checks user settings, storage and local overrides against `schema::KEYS`.
+    pub fn problems(&self) -> Vec<(PathBuf, schema::ConfigProblem)> {
+        let layers = self.layers();
+        let storage = self.storage.get().map(|(path, content)| (path.clone(), schema::check(content)));
+        schema::check(&layers.custom).into_iter().map(|p| (self.path.clone(), p))
+            .chain(storage.into_iter().flat_map(|(path, problems)| problems.into_iter().map(move |p| (path.clone(), p))))
+            .chain(schema::check(&layers.local).into_iter().map(|p| (PathBuf::from(DEFAULT_LOCATION), p)))
+            .collect()
+    }
+
+    /// Sets `key` in the config file at `file`, keeping the rest of the file as it is.
+    ///
+    /// The value has to fit the type of the key in `schema::KEYS`, the file is created if necessary.
+    pub fn set_in_file(file: &Path, key: &str, value: &str) -> Result<(), AsciiiError> {
+        let value = schema::check_value(key, value)?;
+        let content = if file.exists() { std::fs::read_to_string(file)? } else { String::from("---\n") };
+        let content = yaml::set_in_text(&content, key, &value)
+            .ok_or_else(|| ConfigError::CantSet(key.to_owned(), file.to_owned()))?;
+        crate::util::atomic_write(file, content)?;
+        Ok(())
+    }
+
+    /// Reads `.asciii.yml` in the storage root, its settings override the user config from now on.
+    ///
+    /// Only the first storage counts, `dirs/storage` and `path` can't be overridden this way.
+    pub fn merge_storage_config(&self, storage_root: &Path) {
+        let path = storage_root.join(DEFAULT_LOCATION);
+        if self.storage.get().is_some() || !path.exists() || path == self.path {
+            return;
+        }
+
+        let content = match yaml::open(&path) {
+            Ok(content) => content,
+            Err(e) => {
+                log::warn!("{}: {}", path.display(), e);
+                return;
+            }
+        };
+        for problem in schema::check(&content) {
+            log::warn!("{}: {}", path.display(), problem);
+        }
+        for key in &["path", "dirs/storage"] {
+            if yaml::get(&content, key).is_some() {
+                log::warn!("{}: {:?} has no effect in a storage config", path.display(), key);
+            }
+        }
+        log::debug!("using storage config {}", path.display());
+        let _ = self.storage.set((path, content));
+    }
+
+    /// Config files in the order they are looked at, `DEFAULT_CONFIG` last.
+    fn lookup_order(&self) -> impl Iterator<Item = &Yaml> {
+        let layers = self.layers();
+        std::iter::once(&layers.local)
+            .chain(self.storage.get().map(|(_, content)| content))
+            .chain([&layers.custom, &layers.defaults])
+    }
+
+    fn envify_path(path: &str) -> String {
+        path.split(|c| c == '/' || c == '.')
+            .map(str::to_uppercase)
+            .fold(String::from("ASCIII"), |mut acc, w| {
+            acc.push('_');
+            acc.push_str(&w);
+            acc
+        })
+    }
+
+
+    /// Looks up path in ENV
+    ///
+    /// Paths are translatet from `top/middle/child/node` to `ASCIII_TOP_MIDDLE_CHILD_NODE`
+    pub fn var_get(path: &str) -> Option<String> {
+        env::var(Self::envify_path(path)).ok()
+    }
+
+    /// Returns whatever it finds in that position
+    ///
+    /// Supports simple path syntax: `top/middle/child/node`
+    pub fn get(&self, path: &str) -> Option<&Yaml> {
+        self.lookup_order().find_map(|layer| yaml::get(layer, path))
+    }
+
+    /// Returns the first character.
+    ///
+    /// # Panics
+    /// This panics if nothing is found.
+    /// You should have a default config for everything that you use.
+    pub fn get_char(&self, key: &str) -> Option<char> {
+        self.get_str(key).chars().next()
+    }
+
+    /// Returns the string in the position or an empty string
+    pub fn get_str_or(&self, key: &str) -> Option<&str> {
+        self.lookup_order().find_map(|layer| yaml::get_str(layer, key))
+    }
+
+    /// Returns the string in the position or an empty string
+    pub fn var_get_str(&self, key: &str) -> String {
+        Self::var_get(key).unwrap_or_else(|| self.get_str(key).into())
+    }
+
+    /// Returns the string in the position or an empty string
+    pub fn get_str(&self, key: &str) -> &str {
+        self.lookup_order().find_map(|layer| yaml::get_str(layer, key))
+            .unwrap_or_else(|| panic!("{}", format!("Config file {} in field {} does not contain a string value",
+                             DEFAULT_LOCATION,
+                             key)))
+    }
+
+    /// Returns the a vec of &strs if possible
+    pub fn get_strs(&self, key: &str) -> Option<Vec<&str>> {
+        self.get(key)?
+            .as_vec()
+            .map(|v| {
+                     v.iter()
+                      .filter_map(Yaml::as_str)
+                      .collect()
+                 })
+    }
+
+    /// Returns the string in the position or an empty string
+    ///
+    /// # Panics
+    /// This panics if nothing is found.
+    /// You should have a default config for everything that you use.
+    pub fn get_to_string(&self, key: &str) -> String {
+        self.lookup_order().find_map(|layer| yaml::get_to_string(layer, key))
+            .unwrap_or_else(|| panic!("{}", format!("Config file {} in field {} does not contain a value",
+                             DEFAULT_LOCATION,
+                             key)))
+    }
+
+    /// Tries to get the config field as float
+    pub fn get_f64(&self, key: &str) -> Option<f64> {
+        self.lookup_order().find_map(|layer| yaml::get_f64(layer, key))
+        //.expect(&format!("Config file {} in field {} does not contain a value", DEFAULT_LOCATION, key))
+    }
+
+    /// Returns the boolean in the position or `false`
+    ///
+    /// # Panics
+    /// This panics if nothing is found.
+    /// You should have a default config for everything that you use.
+    pub fn get_bool(&self, key: &str) -> bool {
+        self.get(key)
+            .and_then(Yaml::as_bool)
+            .unwrap_or_else(|| panic!("{}", format!("Config file {} in field {} does not contain a boolean value",
+                             DEFAULT_LOCATION,
+                             key)))
+    }
+}
+
+
+#[test]
+fn simple_reading() {
+    if ::std::env::var("CI") == Ok(String::from("true")) {
+        return; // sorry about this
+    }
+
+    //assert!(ConfigReader::path_home().exists());
+    let config = ConfigReader::try_new().unwrap();
+
+    assert_eq!(config.get("list/colors").unwrap().as_bool().unwrap(),
+               config.get_bool("list/colors"));
+
+    assert!(config.get("dirs").is_some());
+    assert!(config.get("dirs/storage").is_some());
+    assert!(config.get("dirs/working").is_some());
+    assert!(config.get("dirs/storage").is_some());
+
+}
+
+#[test]
+fn storage_overrides_user() {
+    let dir = tempdir::TempDir::new("asciii-storage-config").unwrap();
+    std::fs::write(dir.path().join(DEFAULT_LOCATION), "currency: \"$\"\ndefaults:\n  tax: 0.07\n").unwrap();
+
+    let config = ConfigReader::from_content(Path::new("/nonexistent/.asciii.yml"),
+                                            "currency: \"£\"\nlist:\n  sort: date\n").unwrap();
+    assert_eq!(config.get_str("currency"), "£");
+
+    config.merge_storage_config(dir.path());
+    assert_eq!(config.get_str("currency"), "$");
+    assert_eq!(config.get_f64("defaults/tax"), Some(0.07));
+    assert_eq!(config.get_str("list/sort"), "date");
+}
@@ -0,0 +1,430 @@
+//! Every config key asciii knows about, with its type and what it does.
+//!
+//! Defaults are not repeated here, they are read from `DEFAULT_CONFIG`.
+//! Config files are checked against this schema when they are loaded,
+//! so a typo or a wrong type shows up right away instead of as a silent fallback to the default.
+//!
+//! `PROJECT_KEYS` does the same for project files, with `validation/strict_keys` or `asciii validate --strict`
+//! a `cilent:` is reported instead of silently missing.
+
+use std::fmt;
+
+use thiserror::Error;
+
+use yaml_rust::yaml::Hash;
+
+use crate::util::{self, yaml::{self, Yaml}};
+
+use super::DEFAULT_CONFIG;
+
+/// Type of a config value, `~` (unset) is fine for all of them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    /// `true` or `false`
+    Bool,
+    /// integer or float
+    Number,
+    /// any string
+    String,
+    /// list of strings
+    List,
+    /// mapping with keys of its own choosing
+    Map,
+}
+
+impl Kind {
+    fn matches(self, value: &Yaml) -> bool {
+        matches!((self, value),
+                 (_, Yaml::Null)
+                 | (Kind::Bool, Yaml::Boolean(_))
+                 | (Kind::Number, Yaml::Integer(_) | Yaml::Real(_))
+                 | (Kind::String, Yaml::String(_))
+                 | (Kind::List, Yaml::Array(_))
+                 | (Kind::Map, Yaml::Hash(_)))
+    }
+}
+
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match *self {
+            Kind::Bool => "bool",
+            Kind::Number => "number",
+            Kind::String => "string",
+            Kind::List => "list",
+            Kind::Map => "map",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Name of the yaml type of `value`, for error messages.
+fn yaml_type(value: &Yaml) -> &'static str {
+    match *value {
+        Yaml::Boolean(_) => "bool",
+        Yaml::Integer(_) | Yaml::Real(_) => "number",
+        Yaml::String(_) => "string",
+        Yaml::Array(_) => "list",
+        Yaml::Hash(_) => "map",
+        _ => "something else",
+    }
+}
+
+/// One config key.
+#[derive(Debug, Clone, Copy)]
+pub struct Key {
+    /// Path like `list/sort`.
+    pub path: &'static str,
+    /// Type of the value.
+    pub kind: Kind,
+    /// What the key does.
+    pub description: &'static str,
+}
+
+impl Key {
+    /// Default from `DEFAULT_CONFIG`, `None` if it is unset there.
+    pub fn default_value(&self) -> Option<String> {
+        let defaults = yaml::parse(DEFAULT_CONFIG).ok()?;
+        yaml::get(&defaults, self.path).and_then(to_string)
+    }
+}
+
+/// Config keys, in the order of `DEFAULT_CONFIG`.
+pub const KEYS: &[Key] = &[
+    Key { path: "user/name",                         kind: Kind::String, description: "your name, used as manager of new projects" },
+    Key { path: "user/editor",                       kind: Kind::String, description: "editor command, otherwise $EDITOR is used" },
+    Key { path: "locale",                            kind: Kind::String, description: "de or en for messages of asciii itself, defaults to $LANG" },
+    Key { path: "path",                              kind: Kind::String, description: "directory that contains the storage" },
+    Key { path: "output_path",                       kind: Kind::String, description: "where documents are put" },
+    Key { path: "output_dir_root",                   kind: Kind::String, description: "where relative output_dir of projects start, defaults to the storage" },
+    Key { path: "list/colors",                       kind: Kind::Bool,   description: "colored listings" },
+    Key { path: "list/ascii",                        kind: Kind::Bool,   description: "plain ascii instead of check marks and box drawing characters" },
+    Key { path: "list/verbose",                      kind: Kind::Bool,   description: "verbose listings by default" },
+    Key { path: "list/sort",                         kind: Kind::String, description: "field projects are sorted by" },
+    Key { path: "list/sort_key",                     kind: Kind::List,   description: "date, number and name in the order they dominate the index" },
+    Key { path: "list/gitstatus",                    kind: Kind::Bool,   description: "show the git status of project files" },
+    Key { path: "list/group",                        kind: Kind::Bool,   description: "separate sections for canceled and freshly archived projects" },
+    Key { path: "list/extra_details",                kind: Kind::List,   description: "computed fields added to every listing" },
+    Key { path: "list/row_styles",                   kind: Kind::List,   description: "rules that pick the style of a row, the first match wins" },
+    Key { path: "dirs/storage",                      kind: Kind::String, description: "name of the storage directory" },
+    Key { path: "dirs/working",                      kind: Kind::String, description: "directory of running projects" },
+    Key { path: "dirs/archive",                      kind: Kind::String, description: "directory of archived projects" },
+    Key { path: "dirs/templates",                    kind: Kind::String, description: "directory of templates" },
+    Key { path: "slugs/transliterate",               kind: Kind::Map,    description: "replacements in project names before they become directory names" },
+    Key { path: "slugs/max_length",                  kind: Kind::Number, description: "longest directory name of new projects, 0 for unlimited" },
+    Key { path: "slugs/lowercase",                   kind: Kind::Bool,   description: "lowercase directory names of new projects" },
+    Key { path: "template",                          kind: Kind::String, description: "project template used by `asciii new`" },
+    Key { path: "extensions/project_file",           kind: Kind::String, description: "extension of project files" },
+    Key { path: "extensions/project_template",       kind: Kind::String, description: "extension of project templates" },
+    Key { path: "extensions/output_file",            kind: Kind::String, description: "extension of filled latex templates" },
+    Key { path: "extensions/output_template",        kind: Kind::String, description: "extension of latex templates" },
+    Key { path: "extensions/mail_template",          kind: Kind::String, description: "extension of mail templates" },
+    Key { path: "document_export/convert_tool",      kind: Kind::String, description: "turns latex into documents" },
+    Key { path: "document_export/output_extension",  kind: Kind::String, description: "extension of latex documents" },
+    Key { path: "document_export/offer_file_name",   kind: Kind::String, description: "name of offer documents, with fields like {offer_number}" },
+    Key { path: "document_export/invoice_file_name", kind: Kind::String, description: "name of invoice documents, with fields like {invoice_number}" },
+    Key { path: "document_export/trash_extensions",  kind: Kind::List,   description: "files left by the convert tool that are removed" },
+    Key { path: "document_export/default_template",  kind: Kind::String, description: "document template used by `asciii make`" },
+    Key { path: "document_export/backend",           kind: Kind::String, description: "latex, typst or pandoc, otherwise the template that exists decides" },
+    Key { path: "document_export/typst_tool",        kind: Kind::String, description: "turns typst into documents" },
+    Key { path: "document_export/pandoc_tool",       kind: Kind::String, description: "turns markdown into documents" },
+    Key { path: "document_export/watch_debounce",    kind: Kind::Number, description: "milliseconds of quiet before `make --watch` rebuilds" },
+    Key { path: "mail/from",                         kind: Kind::String, description: "sender address, defaults to your git user.email" },
+    Key { path: "mail/smtp",                         kind: Kind::String, description: "smtp server, mails are sent with curl" },
+    Key { path: "mail/user",                         kind: Kind::String, description: "smtp login, the password is read from ASCIII_MAIL_PASSWORD" },
+    Key { path: "mail/template",                     kind: Kind::String, description: "mail template, `mail/body` is used if it does not exist" },
+    Key { path: "mail/subject",                      kind: Kind::String, description: "subject of mails" },
+    Key { path: "mail/body",                         kind: Kind::String, description: "body of mails without a template" },
+    Key { path: "paylink/provider",                  kind: Kind::String, description: "stripe or paypal, see `asciii paylink`" },
+    Key { path: "paylink/stripe/api",                kind: Kind::String, description: "Stripe API" },
+    Key { path: "paylink/paypal/api",                kind: Kind::String, description: "PayPal API" },
+    Key { path: "paylink/paypal/client_id",          kind: Kind::String, description: "PayPal client id" },
+    Key { path: "attachments/webdav/url",            kind: Kind::String, description: "WebDAV folder for remote attachments, see `asciii attach --remote`" },
+    Key { path: "attachments/webdav/user",           kind: Kind::String, description: "WebDAV user" },
+    Key { path: "http/timeout",                      kind: Kind::Number, description: "seconds per attempt of requests to the outside, 0 for none" },
+    Key { path: "http/connect_timeout",              kind: Kind::Number, description: "seconds to wait for a connection" },
+    Key { path: "http/retries",                      kind: Kind::Number, description: "attempts after the first one, see `util::http`" },
+    Key { path: "http/backoff",                      kind: Kind::Number, description: "milliseconds before the first retry, doubled for every further one" },
+    Key { path: "http/min_interval",                 kind: Kind::Number, description: "milliseconds between two requests" },
+    Key { path: "http/proxy",                        kind: Kind::String, description: "proxy for every request, otherwise taken from the environment" },
+    Key { path: "journal/file",                      kind: Kind::String, description: "journal in the storage root, see `asciii undo`" },
+    Key { path: "journal/limit",                     kind: Kind::Number, description: "actions that can be undone" },
+    Key { path: "lock/stale_hours",                  kind: Kind::Number, description: "hours after which project locks are taken over, 0 never" },
+    Key { path: "ledger/file",                       kind: Kind::String, description: "issued invoice numbers in the storage root" },
+    Key { path: "closing/file",                      kind: Kind::String, description: "closed years in the storage root, see `asciii close-year`" },
+    Key { path: "parallel/threads",                  kind: Kind::Number, description: "threads for opening projects, 0 means one per cpu" },
+    Key { path: "parallel/min_projects",             kind: Kind::Number, description: "fewer projects are opened one after another" },
+    Key { path: "duplicate/reset",                   kind: Kind::List,   description: "fields removed from copies made with `asciii duplicate`" },
+    Key { path: "defaults/tax",                      kind: Kind::Number, description: "tax of new projects" },
+    Key { path: "defaults/canceled",                 kind: Kind::Bool,   description: "whether new projects are canceled" },
+    Key { path: "defaults/salary",                   kind: Kind::Number, description: "hourly salary of new projects" },
+    Key { path: "defaults/offer_validity",           kind: Kind::Number, description: "days an offer stands after its date" },
+    Key { path: "defaults/lang",                     kind: Kind::String, description: "language of documents" },
+    Key { path: "defaults/timezone",                 kind: Kind::String, description: "time zone of event times" },
+    Key { path: "currency",                          kind: Kind::String, description: "currency symbol, or ISO code of a well known currency" },
+    Key { path: "currency_code",                     kind: Kind::String, description: "ISO 4217 code of the currency" },
+    Key { path: "currency_decimals",                 kind: Kind::Number, description: "digits after the comma of amounts" },
+    Key { path: "due/skip_weekends",                 kind: Kind::Bool,   description: "due dates never fall on a weekend" },
+    Key { path: "due/holidays/country",              kind: Kind::String, description: "public holidays due dates skip, DE or AT" },
+    Key { path: "due/holidays/state",                kind: Kind::String, description: "german state for its public holidays, e.g. SN" },
+    Key { path: "due/holidays/extra",                kind: Kind::List,   description: "more days off, \"24.12.\" or \"02.05.2025\"" },
+    Key { path: "exchange/fetch",                    kind: Kind::Bool,   description: "download exchange rates for projects in other currencies" },
+    Key { path: "exchange/url",                      kind: Kind::String, description: "ECB reference rates as XML" },
+    Key { path: "exchange/cache",                    kind: Kind::String, description: "where downloaded exchange rates are kept" },
+    Key { path: "computed_fields",                   kind: Kind::Map,    description: "custom fields, templates with `{path}` placeholders" },
+    Key { path: "validation/rules",                  kind: Kind::List,   description: "required or relaxed fields per stage, see `project::validate`" },
+    Key { path: "validation/require_acceptance",     kind: Kind::Bool,   description: "invoices need an accepted offer" },
+    Key { path: "validation/strict_keys",             kind: Kind::Bool,   description: "warn about unknown keys in project files" },
+    Key { path: "archive/require",                   kind: Kind::List,   description: "payed, employees_payed, invoice_sent or invoiced before a project can be archived" },
+    Key { path: "import/eml/event_date",             kind: Kind::String, description: "regex for the event date in booking mails" },
+    Key { path: "import/eml/event_name",             kind: Kind::String, description: "regex for the event name in booking mails, the subject otherwise" },
+    Key { path: "import/eml/client_name",            kind: Kind::String, description: "regex for the client name in booking mails, the sender otherwise" },
+    Key { path: "import/eml/client_email",           kind: Kind::String, description: "regex for the client email in booking mails, the sender otherwise" },
+    Key { path: "webhooks",                          kind: Kind::List,   description: "urls and events projects are posted to, see `webhooks`" },
+    Key { path: "gender_matches",                    kind: Kind::Map,    description: "client titles and the gender they imply" },
+    Key { path: "lang_addressing",                   kind: Kind::Map,    description: "salutation per language and gender" },
+];
+
+/// Keys of project files since format 2.0.0, see `unknown_project_keys()`.
+///
+/// The keys in maps are chosen by the user (products, employees), lists with keys below them hold mappings.
+pub const PROJECT_KEYS: &[Key] = &[
+    Key { path: "client/title",                      kind: Kind::String, description: "Herr, Frau, Mr, Ms..., implies the gender" },
+    Key { path: "client/first_name",                 kind: Kind::String, description: "first name of the client" },
+    Key { path: "client/last_name",                  kind: Kind::String, description: "last name of the client, or the company" },
+    Key { path: "client/email",                      kind: Kind::String, description: "email of the client" },
+    Key { path: "client/address",                    kind: Kind::String, description: "postal address, several lines" },
+    Key { path: "event/name",                        kind: Kind::String, description: "name of the event" },
+    Key { path: "event/location",                    kind: Kind::String, description: "where the event takes place" },
+    Key { path: "event/description",                 kind: Kind::String, description: "what happens" },
+    Key { path: "event/timezone",                    kind: Kind::String, description: "timezone of the dates, otherwise `defaults/timezone`" },
+    Key { path: "event/dates",                       kind: Kind::List,   description: "days of the event" },
+    Key { path: "event/dates/begin",                 kind: Kind::String, description: "first day" },
+    Key { path: "event/dates/end",                   kind: Kind::String, description: "last day, if more than one" },
+    Key { path: "event/dates/times",                 kind: Kind::List,   description: "hours of each day" },
+    Key { path: "event/dates/times/begin",           kind: Kind::String, description: "start time" },
+    Key { path: "event/dates/times/end",             kind: Kind::String, description: "end time" },
+    Key { path: "offer/date",                        kind: Kind::String, description: "date of the offer" },
+    Key { path: "offer/appendix",                    kind: Kind::Number, description: "version of the offer" },
+    Key { path: "offer/valid_until",                 kind: Kind::String, description: "until when the offer stands" },
+    Key { path: "offer/generated_date",              kind: Kind::String, description: "when the offer document was made" },
+    Key { path: "offer/sent_date",                   kind: Kind::String, description: "when the offer was sent" },
+    Key { path: "offer/accepted_date",               kind: Kind::String, description: "when the client accepted" },
+    Key { path: "offer/accepted_via",                kind: Kind::String, description: "email, phone or signed" },
+    Key { path: "offer/signed_copy",                 kind: Kind::String, description: "attached signed offer" },
+    Key { path: "invoice/number",                    kind: Kind::Number, description: "invoice number" },
+    Key { path: "invoice/official",                  kind: Kind::String, description: "official invoice number, if there is one" },
+    Key { path: "invoice/date",                      kind: Kind::String, description: "date of the invoice" },
+    Key { path: "invoice/payed_date",                kind: Kind::String, description: "when the invoice was payed" },
+    Key { path: "invoice/generated_date",            kind: Kind::String, description: "when the invoice document was made" },
+    Key { path: "invoice/sent_date",                 kind: Kind::String, description: "when the invoice was sent" },
+    Key { path: "invoice/paylink",                   kind: Kind::String, description: "payment link, see `asciii paylink`" },
+    Key { path: "invoice/paylink_id",                kind: Kind::String, description: "id of the payment link at the provider" },
+    Key { path: "cataloge",                          kind: Kind::Map,    description: "products that can be offered" },
+    Key { path: "products",                          kind: Kind::Map,    description: "amounts offered and sold" },
+    Key { path: "hours/salary",                      kind: Kind::Number, description: "hourly wage" },
+    Key { path: "hours/tax",                         kind: Kind::Number, description: "tax on the wages" },
+    Key { path: "hours/staff",                       kind: Kind::Number, description: "employees the event needs" },
+    Key { path: "hours/caterers",                    kind: Kind::Map,    description: "employees and their hours" },
+    Key { path: "hours/employees",                   kind: Kind::Map,    description: "employees and their hours, instead of caterers" },
+    Key { path: "hours/wages_date",                  kind: Kind::String, description: "when the wages were payed" },
+    Key { path: "expenses",                          kind: Kind::Map,    description: "money spent on the project" },
+    Key { path: "attachments",                       kind: Kind::List,   description: "remote attachments, see `asciii attach --remote`" },
+    Key { path: "attachments/name",                  kind: Kind::String, description: "file name" },
+    Key { path: "attachments/url",                   kind: Kind::String, description: "where it was uploaded" },
+    Key { path: "attachments/share",                 kind: Kind::String, description: "public link" },
+    Key { path: "attachments/size",                  kind: Kind::Number, description: "size in bytes" },
+    Key { path: "manager",                           kind: Kind::String, description: "who is responsible" },
+    Key { path: "canceled",                          kind: Kind::Bool,   description: "the event did not take place" },
+    Key { path: "tax",                               kind: Kind::Number, description: "tax on the products" },
+    Key { path: "currency",                          kind: Kind::String, description: "currency of the project, if not the configured one" },
+    Key { path: "follows",                           kind: Kind::String, description: "project this one is a follow-up of" },
+    Key { path: "output_dir",                        kind: Kind::String, description: "where documents of this project go" },
+    Key { path: "lang",                              kind: Kind::String, description: "language of the documents" },
+    Key { path: "messages",                          kind: Kind::Map,    description: "texts of the documents" },
+    Key { path: "legacy",                            kind: Kind::Map,    description: "what `asciii import legacy` could not map" },
+    Key { path: "meta/format",                       kind: Kind::String, description: "version of the project format" },
+    Key { path: "meta/invoicer_version",             kind: Kind::String, description: "version of asciii that created the project" },
+    Key { path: "meta/template",                     kind: Kind::String, description: "template the project was created from" },
+    Key { path: "format",                            kind: Kind::String, description: "version of the project format, before meta/format" },
+    Key { path: "created",                           kind: Kind::String, description: "day the project was created" },
+];
+
+/// Normalizes `user.name` to `user/name`.
+fn normalize(path: &str) -> String {
+    path.split(['/', '.']).filter(|s| !s.is_empty()).collect::<Vec<_>>().join("/")
+}
+
+/// Looks up a key, paths may use `.` or `/`.
+pub fn key(path: &str) -> Option<&'static Key> {
+    let path = normalize(path);
+    KEYS.iter().find(|k| k.path == path)
+}
+
+/// Something wrong in a config file.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ConfigProblem {
+    /// Not in the schema, most likely a typo.
+    #[error("{}", lformat!("unknown config key {:?}", _0))]
+    Unknown(String),
+
+    /// In the schema, but with the wrong type.
+    #[error("{}", lformat!("config key {:?} should be a {}, not a {}", key, expected, found))]
+    WrongType {
+        /// path of the key
+        key: String,
+        /// type from the schema
+        expected: Kind,
+        /// type in the config file
+        found: &'static str,
+    },
+}
+
+/// Checks `value` at `path` against `keys`.
+fn walk(keys: &[Key], value: &Yaml, path: &str, problems: &mut Vec<ConfigProblem>) {
+    let is_parent = path.is_empty() || keys.iter().any(|k| k.path.starts_with(&format!("{}/", path)));
+    if let Some(key) = keys.iter().find(|k| k.path == path) {
+        if !key.kind.matches(value) {
+            problems.push(ConfigProblem::WrongType { key: path.to_owned(), expected: key.kind, found: yaml_type(value) });
+        } else if let (Yaml::Array(items), true) = (value, is_parent) {
+            // a list of mappings, like `event/dates`
+            for item in items {
+                if let Yaml::Hash(hash) = item {
+                    walk_hash(keys, hash, path, problems);
+                }
+            }
+        }
+        return;
+    }
+
+    match (value, is_parent) {
+        (Yaml::Hash(hash), true) => walk_hash(keys, hash, path, problems),
+        (Yaml::Null, true) => {}
+        (_, true) => problems.push(ConfigProblem::WrongType { key: path.to_owned(), expected: Kind::Map, found: yaml_type(value) }),
+        (_, false) => problems.push(ConfigProblem::Unknown(path.to_owned())),
+    }
+}
+
+fn walk_hash(keys: &[Key], hash: &Hash, path: &str, problems: &mut Vec<ConfigProblem>) {
+    for (child, value) in hash {
+        let child = to_string(child).unwrap_or_default();
+        let child = if path.is_empty() { child } else { format!("{}/{}", path, child) };
+        walk(keys, value, &child, problems);
+    }
+}
+
+/// Checks a parsed config file against the schema.
+pub fn check(config: &Yaml) -> Vec<ConfigProblem> {
+    let mut problems = Vec::new();
+    walk(KEYS, config, "", &mut problems);
+    problems
+}
+
+/// Keys of a parsed project file that are not in `PROJECT_KEYS`, with the known key that was most likely meant.
+///
+/// Values are not checked, that is what `asciii validate` does anyway.
+pub fn unknown_project_keys(project: &Yaml) -> Vec<(String, Option<&'static str>)> {
+    let mut problems = Vec::new();
+    walk(PROJECT_KEYS, project, "", &mut problems);
+    problems.into_iter()
+            .filter_map(|problem| match problem {
+                ConfigProblem::Unknown(path) => Some(path),
+                ConfigProblem::WrongType { .. } => None,
+            })
+            .map(|path| {
+                let suggestion = suggest_project_key(&path);
+                (path, suggestion)
+            })
+            .collect()
+}
+
+/// The known key next to `path` that is spelled most like it.
+fn suggest_project_key(path: &str) -> Option<&'static str> {
+    let (parent, name) = path.rsplit_once('/').unwrap_or(("", path));
+    // paths of the keys next to `path`, cut out of `PROJECT_KEYS`
+    let siblings = PROJECT_KEYS.iter()
+        .filter_map(|key| {
+            let below = if parent.is_empty() { key.path } else { key.path.strip_prefix(parent)?.strip_prefix('/')? };
+            let sibling = below.split('/').next()?;
+            Some(&key.path[..key.path.len() - below.len() + sibling.len()])
+        })
+        .collect::<Vec<_>>();
+    let last = |path: &'static str| path.rsplit('/').next().unwrap_or(path);
+    let meant = util::closest_match(name, siblings.iter().map(|sibling| last(sibling)))?;
+    siblings.into_iter().find(|sibling| last(sibling) == meant)
+}
+
+/// Parses `value` as yaml and checks it fits `key`, returns the value as it should be written.
+pub fn check_value(path: &str, value: &str) -> Result<String, ConfigProblem> {
+    let key = key(path).ok_or_else(|| ConfigProblem::Unknown(path.to_owned()))?;
+    let parsed = yaml::parse(value).unwrap_or_else(|_| Yaml::String(value.to_owned()));
+    if key.kind.matches(&parsed) {
+        Ok(value.to_owned())
+    } else if key.kind == Kind::String {
+        // `asciii config set user/name 42` means the string
+        Ok(format!("{:?}", value))
+    } else {
+        Err(ConfigProblem::WrongType { key: key.path.to_owned(), expected: key.kind, found: yaml_type(&parsed) })
+    }
+}
+
+/// Value as shown by `asciii config`, lists and maps in flow style.
+pub fn to_string(value: &Yaml) -> Option<String> {
+    match value {
+        Yaml::Null | Yaml::BadValue => None,
+        Yaml::Array(items) => Some(format!("[{}]", items.iter().filter_map(to_string).collect::<Vec<_>>().join(", "))),
+        Yaml::Hash(hash) => Some(format!("{{{}}}", hash.iter()
+                                                      .filter_map(|(k, v)| Some(format!("{}: {}", to_string(k)?, to_string(v)?)))
+                                                      .collect::<Vec<_>>()
+                                                      .join(", "))),
+        Yaml::Real(s) | Yaml::String(s) => Some(s.to_owned()),
+        Yaml::Integer(i) => Some(i.to_string()),
+        Yaml::Boolean(b) => Some(b.to_string()),
+        Yaml::Alias(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_fit_the_schema() {
+        let defaults = yaml::parse(DEFAULT_CONFIG).unwrap();
+        assert_eq!(check(&defaults), Vec::new());
+        assert_eq!(key("list.sort").unwrap().default_value(), Some("index".into()));
+    }
+
+    #[test]
+    fn finds_typos_and_wrong_types() {
+        let config = yaml::parse("list:\n  colours: true\n  colors: yes please\ndefaults:\n  tax: 0.07\ngender_matches:\n  sir: male\n").unwrap();
+        assert_eq!(check(&config), vec![
+            ConfigProblem::Unknown("list/colours".into()),
+            ConfigProblem::WrongType { key: "list/colors".into(), expected: Kind::Bool, found: "string" },
+        ]);
+    }
+
+    #[test]
+    fn unknown_project_keys_are_suggested() {
+        for file in &["./templates/default.tyml", "../tests/test_projects/current.yml", "../tests/test_projects/tax.yml"] {
+            let project = yaml::open(std::path::Path::new(file)).unwrap();
+            assert_eq!(unknown_project_keys(&project), Vec::new(), "{}", file);
+        }
+
+        let project = yaml::parse("cilent:\n  first_name: Graf\nevent:\n  neme: Party\n  dates:\n  - begin: 01.03.2024\n    ende: 02.03.2024\nproducts:\n  Kaffee: { amount: 4 }\nhours:\n  caterers:\n    Ernie: 4\nvegetarian: true\n").unwrap();
+        assert_eq!(unknown_project_keys(&project), vec![
+            ("cilent".to_owned(), Some("client")),
+            ("event/neme".to_owned(), Some("event/name")),
+            ("event/dates/ende".to_owned(), Some("event/dates/end")),
+            ("vegetarian".to_owned(), None),
+        ]);
+    }
+
+    #[test]
+    fn values_to_set() {
+        assert_eq!(check_value("defaults/tax", "0.07"), Ok("0.07".into()));
+        assert!(check_value("defaults/tax", "much").is_err());
+        assert_eq!(check_value("user/name", "42"), Ok("\"42\"".into()));
+        assert_eq!(check_value("document_export/trash_extensions", "[aux, log]"), Ok("[aux, log]".into()));
+        assert!(check_value("list/colours", "true").is_err());
+    }
+}
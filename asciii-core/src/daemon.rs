@@ -0,0 +1,316 @@
+//! Long running JSON-RPC service for editor integration, see `asciii daemon`.
+//!
+//! Requests and responses are [JSON-RPC 2.0](https://www.jsonrpc.org/specification) objects,
+//! one per line. The storage is opened once and projects are cached between requests.
+//!
+//! | method     | params                          | result                                     |
+//! |------------|---------------------------------|--------------------------------------------|
+//! | `list`     | `{"dir": "working"}`            | `[{"name", "file", "date", "index"}]`      |
+//! | `open`     | `{"file": path}`                | the exported project                       |
+//! | `validate` | `{"file": path, "content"?: …}` | `[{"file", "field", "stage", "severity", "message", "line"}]` |
+//! | `complete` | `{"file"?: path, "prefix": …}`  | `[{"path", "kind", "value"}]`              |
+//! | `shutdown` |                                 | `null`, then the daemon stops              |
+//!
+//! `content` lets editors validate unsaved buffers, `dir` is `working`, `archive/<year>`, `year/<year>` or `all`.
+
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+
+use serde_json::{json, Value};
+
+use crate::error::{AsciiiError, SpecError};
+use crate::project::error::{Diagnostic, Severity};
+use crate::project::export::{Complete, ExportTarget};
+use crate::project::spec::{self, IsProject};
+use crate::project::Project;
+use crate::storage::{self, cache, Storable, Storage, StorageDir};
+use crate::util::yaml;
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const INTERNAL_ERROR: i64 = -32603;
+
+/// Error object of a JSON-RPC response.
+#[derive(Debug, PartialEq)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+}
+
+impl RpcError {
+    fn new<S: ToString>(code: i64, message: S) -> RpcError {
+        RpcError { code, message: message.to_string() }
+    }
+}
+
+impl From<AsciiiError> for RpcError {
+    fn from(error: AsciiiError) -> RpcError {
+        RpcError::new(INTERNAL_ERROR, error)
+    }
+}
+
+type RpcResult = Result<Value, RpcError>;
+
+/// Answers requests until `shutdown` or the end of input.
+pub struct Daemon {
+    storage: Option<Storage<Project>>,
+    running: bool,
+}
+
+impl Daemon {
+    pub fn new() -> Daemon {
+        cache::enable();
+        Daemon {
+            storage: storage::setup::<Project>()
+                .map_err(|e| log::warn!("daemon runs without storage: {}", e))
+                .ok(),
+            running: true,
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+
+    /// Reads requests line by line and writes one response line each, notifications are not answered.
+    pub fn serve<R: BufRead, W: Write>(&mut self, input: R, mut output: W) -> io::Result<()> {
+        for line in input.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            if let Some(response) = self.handle_line(&line) {
+                writeln!(output, "{}", response)?;
+                output.flush()?;
+            }
+            if !self.running {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Handles a single request, returns the serialized response.
+    pub fn handle_line(&mut self, line: &str) -> Option<String> {
+        let request: Value = match serde_json::from_str(line) {
+            Ok(request) => request,
+            Err(e) => return Some(response(Value::Null, Err(RpcError::new(PARSE_ERROR, e))).to_string())
+        };
+
+        let id = request.get("id").cloned();
+        let result = match request.get("method").and_then(Value::as_str) {
+            Some(method) => self.call(method, request.get("params").unwrap_or(&Value::Null)),
+            None => Err(RpcError::new(INVALID_REQUEST, "method missing"))
+        };
+
+        id.map(|id| response(id, result).to_string())
+    }
+
+    fn call(&mut self, method: &str, params: &Value) -> RpcResult {
+        log::debug!("daemon: {} {}", method, params);
+        match method {
+            "list"     => self.list(params),
+            "open"     => open(params),
+            "validate" => validate(params),
+            "complete" => complete(params),
+            "shutdown" => {
+                self.running = false;
+                Ok(Value::Null)
+            }
+            _ => Err(RpcError::new(METHOD_NOT_FOUND, format!("unknown method {:?}", method)))
+        }
+    }
+
+    fn list(&self, params: &Value) -> RpcResult {
+        let storage = self.storage.as_ref()
+            .ok_or_else(|| RpcError::new(INTERNAL_ERROR, "storage is not available"))?;
+        let dir = parse_dir(params.get("dir").and_then(Value::as_str).unwrap_or("working"))?;
+
+        let projects = storage.open_projects(dir)?;
+        Ok(projects.iter()
+                   .map(|p| json!({
+                       "name":  p.name().ok(),
+                       "file":  p.file(),
+                       "date":  p.event_date().ok().map(|d| d.format("%d.%m.%Y").to_string()),
+                       "index": p.index(),
+                   }))
+                   .collect())
+    }
+}
+
+impl Default for Daemon {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn response(id: Value, result: RpcResult) -> Value {
+    match result {
+        Ok(result) => json!({"jsonrpc": "2.0", "id": id, "result": result}),
+        Err(error) => json!({"jsonrpc": "2.0", "id": id, "error": {"code": error.code, "message": error.message}}),
+    }
+}
+
+fn parse_dir(dir: &str) -> Result<StorageDir, RpcError> {
+    let year = |y: &str| y.parse::<i32>().map_err(|_| RpcError::new(INVALID_PARAMS, format!("invalid year {:?}", y)));
+    match dir.split_once('/') {
+        None if dir == "working" => Ok(StorageDir::Working),
+        None if dir == "all"     => Ok(StorageDir::All),
+        Some(("archive", y))     => Ok(StorageDir::Archive(year(y)?)),
+        Some(("year", y))        => Ok(StorageDir::Year(year(y)?)),
+        _ => Err(RpcError::new(INVALID_PARAMS, format!("invalid dir {:?}", dir)))
+    }
+}
+
+fn file_param(params: &Value) -> Result<PathBuf, RpcError> {
+    params.get("file")
+          .and_then(Value::as_str)
+          .map(PathBuf::from)
+          .ok_or_else(|| RpcError::new(INVALID_PARAMS, "\"file\" missing"))
+}
+
+/// The buffer sent along or the file on disk.
+fn content_param(params: &Value, file: &Path) -> Result<String, RpcError> {
+    match params.get("content").and_then(Value::as_str) {
+        Some(content) => Ok(content.to_owned()),
+        None => std::fs::read_to_string(file).map_err(|e| RpcError::new(INVALID_PARAMS, format!("{}: {}", file.display(), e)))
+    }
+}
+
+fn open(params: &Value) -> RpcResult {
+    let file = file_param(params)?;
+    let project = Project::open_file(&file)?;
+    let exported: Complete = project.export();
+    serde_json::to_value(&exported).map_err(|e| RpcError::new(INTERNAL_ERROR, e))
+}
+
+/// Some checks only name the last part of a field, like `payed_date`, those are looked up by that key anywhere.
+fn line_of_field(content: &str, field: &str) -> Option<usize> {
+    yaml::line_of(content, field).or_else(|| {
+        let key = format!("{}:", field.rsplit(['/', '.']).next()?);
+        content.lines().position(|line| line.trim_start().starts_with(&key))
+    })
+}
+
+fn diagnostic_to_json(diagnostic: &Diagnostic, content: &str) -> Value {
+    let mut value = serde_json::to_value(diagnostic).unwrap_or(Value::Null);
+    value["line"] = json!(line_of_field(content, &diagnostic.field));
+    value
+}
+
+fn validate(params: &Value) -> RpcResult {
+    let file = file_param(params)?;
+    let content = content_param(params, &file)?;
+
+    if let Err(error) = yaml::parse(&content) {
+        let line = match error {
+            AsciiiError::Spec(SpecError::Yaml(ref e)) => Some(e.marker().line().saturating_sub(1)),
+            _ => None
+        };
+        let diagnostic = Diagnostic {
+            file,
+            field: String::new(),
+            stage: "file".into(),
+            severity: Severity::Error,
+            message: error.to_string(),
+        };
+        let mut value = serde_json::to_value(&diagnostic).unwrap_or(Value::Null);
+        value["line"] = json!(line);
+        return Ok(json!([value]));
+    }
+
+    let mut project = Project::from_file_content(&content)?;
+    project.set_file(&file);
+    Ok(project.diagnostics()
+              .iter()
+              .map(|d| diagnostic_to_json(d, &content))
+              .collect())
+}
+
+fn complete(params: &Value) -> RpcResult {
+    let prefix = params.get("prefix").and_then(Value::as_str).unwrap_or("").replace('.', "/");
+
+    let project = match params.get("file") {
+        Some(_) => {
+            let file = file_param(params)?;
+            let content = content_param(params, &file)?;
+            yaml::parse(&content).ok().map(|_| Project::from_file_content(&content)).transpose()?
+        }
+        None => None
+    };
+
+    let mut candidates = spec::SETTABLE_FIELDS.iter()
+        .filter(|(path, _)| path.starts_with(&prefix))
+        .map(|(path, kind)| json!({
+            "path":  path,
+            "kind":  format!("{:?}", kind),
+            "value": project.as_ref().and_then(|p| p.field(path)),
+        }))
+        .collect::<Vec<Value>>();
+
+    // fields the spec doesn't know about but which are already in the file
+    if let Some(leaves) = project.as_ref().and_then(|p| yaml::leaves(p.yaml())) {
+        for (path, value) in leaves {
+            if path.starts_with(&prefix) && spec::field_kind(&path).is_none() {
+                candidates.push(json!({"path": path, "kind": null, "value": value}));
+            }
+        }
+    }
+
+    Ok(Value::Array(candidates))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn daemon() -> Daemon {
+        Daemon { storage: None, running: true }
+    }
+
+    fn call(daemon: &mut Daemon, request: Value) -> Value {
+        serde_json::from_str(&daemon.handle_line(&request.to_string()).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn answers_requests() {
+        let mut daemon = daemon();
+        assert_eq!(call(&mut daemon, json!({"jsonrpc": "2.0", "id": 1, "method": "nope"}))["error"]["code"],
+                   json!(METHOD_NOT_FOUND));
+        assert_eq!(daemon.handle_line("{"), Some(response(Value::Null, Err(RpcError::new(PARSE_ERROR, "EOF while parsing an object at line 1 column 1"))).to_string()));
+        assert_eq!(daemon.handle_line(&json!({"jsonrpc": "2.0", "method": "list"}).to_string()), None);
+
+        assert_eq!(call(&mut daemon, json!({"jsonrpc": "2.0", "id": 2, "method": "shutdown"}))["result"], Value::Null);
+        assert!(!daemon.is_running());
+    }
+
+    #[test]
+    fn validates_buffers() {
+        let mut daemon = daemon();
+        let content = "invoice:\n  number: 41\n  payed_date: 32.12.2014\n";
+        let result = call(&mut daemon, json!({"jsonrpc": "2.0", "id": 1, "method": "validate",
+                                              "params": {"file": "a.yml", "content": content}}))["result"].clone();
+        let payed = result.as_array().unwrap().iter()
+                          .find(|d| d["severity"] == "error")
+                          .unwrap();
+        assert_eq!(payed["line"], json!(2));
+
+        let result = call(&mut daemon, json!({"jsonrpc": "2.0", "id": 1, "method": "validate",
+                                              "params": {"file": "a.yml", "content": "a: [b"}}))["result"].clone();
+        assert_eq!(result[0]["stage"], "file");
+    }
+
+    #[test]
+    fn completes_fields() {
+        let mut daemon = daemon();
+        let result = call(&mut daemon, json!({"jsonrpc": "2.0", "id": 1, "method": "complete",
+                                              "params": {"file": "a.yml", "prefix": "invoice.",
+                                                         "content": "invoice:\n  number: 41\n  extra: x\n"}}))["result"].clone();
+        let paths = result.as_array().unwrap().iter().map(|c| c["path"].as_str().unwrap()).collect::<Vec<_>>();
+        assert!(paths.contains(&"invoice/payed_date"));
+        assert!(paths.contains(&"invoice/extra"));
+        assert_eq!(result[0]["value"], "41");
+    }
+}
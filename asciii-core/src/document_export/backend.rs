@@ -0,0 +1,235 @@
+//! Renderers that turn filled templates into documents.
+//!
+//! Every backend has its own templates, `templates/<name>.<source extension>.hbs`.
+//! Unless a backend is picked with `asciii make --backend`, the template that exists decides,
+//! latex is tried first.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::error::{io_context, AsciiiError};
+use crate::storage;
+use crate::util;
+
+use super::error::ExportError;
+
+/// Everything that differs between renderers, the rest of the pipeline is shared.
+pub trait DocumentBackend: Sync {
+    /// Name used with `--backend`.
+    fn name(&self) -> &'static str;
+
+    /// Extension of the filled template, also part of the template file name.
+    fn source_extension(&self) -> &str;
+
+    /// Extension of the finished document.
+    fn document_extension(&self) -> &str;
+
+    /// Escapes values inserted into the template.
+    fn escape(&self, text: &str) -> String {
+        text.to_owned()
+    }
+
+    /// How templates refer to a file, e.g. the logo of `extras/branding.yml`.
+    fn asset_path(&self, path: &Path) -> String {
+        path.display().to_string()
+    }
+
+    /// Last touches to the filled template before it is written.
+    fn finish(&self, filled: String) -> String {
+        filled
+    }
+
+    /// Renders the written source file, returns where the document ended up.
+    fn render(&self, source: &Path) -> Result<PathBuf, AsciiiError>;
+
+    /// Files `render()` leaves in the working directory.
+    fn trash_extensions(&self) -> Vec<&str> {
+        Vec::new()
+    }
+
+    /// Extension of the templates made for this backend, e.g. `typ.hbs`.
+    fn template_extension(&self) -> String {
+        format!("{}.hbs", self.source_extension())
+    }
+
+    fn template_path(&self, template_name: &str) -> PathBuf {
+        templates_dir().join(format!("{}.{}", template_name, self.template_extension()))
+    }
+}
+
+fn templates_dir() -> PathBuf {
+    storage::get_storage_path().join(crate::CONFIG.get_str("dirs/templates"))
+}
+
+/// Runs `tool` (a command with arguments) on `args`, fails if it does not succeed.
+fn run(tool: &str, args: &[&Path]) -> Result<(), AsciiiError> {
+    let mut words = tool.split_whitespace();
+    let program = words.next().ok_or_else(|| ExportError::Failed(lformat!("no command given")))?;
+    log::info!("launching {:?} with {:?}", tool, args);
+    let status = Command::new(program)
+        .args(words)
+        .args(args)
+        .status()
+        .map_err(io_context(lformat!("can't run {:?}", program)))?;
+    if !status.success() {
+        return Err(ExportError::Failed(lformat!("{:?} did not exit successfully", tool)).into());
+    }
+    Ok(())
+}
+
+/// The original pipeline, `document_export/convert_tool` turns `.tex` into `.pdf`.
+pub struct Latex;
+
+impl DocumentBackend for Latex {
+    fn name(&self) -> &'static str { "latex" }
+
+    fn source_extension(&self) -> &str {
+        crate::CONFIG.get_str("extensions/output_file")
+    }
+
+    fn document_extension(&self) -> &str {
+        crate::CONFIG.get_str("document_export/output_extension")
+    }
+
+    fn escape(&self, text: &str) -> String {
+        text.replace('\n', r#"\newline "#)
+    }
+
+    /// `\includegraphics` wants forward slashes, even on windows.
+    fn asset_path(&self, path: &Path) -> String {
+        path.display().to_string().replace('\\', "/")
+    }
+
+    /// Templates write `<` and `>` where latex wants braces, handlebars would read those itself.
+    fn finish(&self, filled: String) -> String {
+        filled.replace('<', "{").replace('>', "}")
+    }
+
+    fn render(&self, source: &Path) -> Result<PathBuf, AsciiiError> {
+        util::pass_to_command(Some(crate::CONFIG.get_str("document_export/convert_tool")), &[source])?;
+
+        // pdflatex writes into the working directory
+        let local = util::to_local_file(source, self.document_extension());
+        let beside = source.with_extension(self.document_extension());
+        if local.exists() {
+            Ok(local)
+        } else if beside.exists() {
+            Ok(beside)
+        } else {
+            Err(ExportError::NoPdfCreated.into())
+        }
+    }
+
+    fn trash_extensions(&self) -> Vec<&str> {
+        crate::CONFIG.get_strs("document_export/trash_extensions").unwrap_or_default()
+    }
+
+    fn template_extension(&self) -> String {
+        crate::CONFIG.get_str("extensions/output_template").to_owned()
+    }
+}
+
+/// `typst compile` turns `.typ` into `.pdf`.
+pub struct Typst;
+
+impl DocumentBackend for Typst {
+    fn name(&self) -> &'static str { "typst" }
+
+    fn source_extension(&self) -> &str { "typ" }
+
+    fn document_extension(&self) -> &str { "pdf" }
+
+    fn escape(&self, text: &str) -> String {
+        let mut escaped = String::with_capacity(text.len());
+        for c in text.chars() {
+            match c {
+                '\n' => escaped.push_str(" \\\n"),
+                '\\' | '#' | '$' | '*' | '_' | '@' | '<' | '>' | '`' | '[' | ']' | '~' => {
+                    escaped.push('\\');
+                    escaped.push(c);
+                }
+                _ => escaped.push(c),
+            }
+        }
+        escaped
+    }
+
+    /// Absolute paths are relative to `--root`, which is `/` unless `document_export/typst_tool` says otherwise.
+    fn asset_path(&self, path: &Path) -> String {
+        path.display().to_string().replace('\\', "/")
+    }
+
+    fn render(&self, source: &Path) -> Result<PathBuf, AsciiiError> {
+        let document = source.with_extension(self.document_extension());
+        run(crate::CONFIG.get_str("document_export/typst_tool"), &[source, &document])?;
+        Ok(document)
+    }
+}
+
+/// Pandoc turns markdown templates into standalone `.html`.
+pub struct Pandoc;
+
+impl DocumentBackend for Pandoc {
+    fn name(&self) -> &'static str { "pandoc" }
+
+    fn source_extension(&self) -> &str { "md" }
+
+    fn document_extension(&self) -> &str { "html" }
+
+    fn render(&self, source: &Path) -> Result<PathBuf, AsciiiError> {
+        let document = source.with_extension(self.document_extension());
+        run(crate::CONFIG.get_str("document_export/pandoc_tool"), &[source, Path::new("-o"), &document])?;
+        Ok(document)
+    }
+}
+
+/// Every backend, in the order templates are looked for.
+pub static BACKENDS: &[&dyn DocumentBackend] = &[&Latex, &Typst, &Pandoc];
+
+pub fn by_name(name: &str) -> Result<&'static dyn DocumentBackend, AsciiiError> {
+    BACKENDS.iter()
+            .find(|backend| backend.name() == name)
+            .copied()
+            .ok_or_else(|| ExportError::UnknownBackend(name.to_owned()).into())
+}
+
+/// Picks the backend of a template and returns it along with the template file.
+///
+/// `backend` overrides `document_export/backend`, without either the first existing template wins.
+pub fn for_template(template_name: &str, backend: Option<&str>) -> Result<(&'static dyn DocumentBackend, PathBuf), AsciiiError> {
+    let backend = backend.or_else(|| crate::CONFIG.get_str_or("document_export/backend"));
+    let candidates = match backend {
+        Some(name) => vec![by_name(name)?],
+        None => BACKENDS.to_vec(),
+    };
+
+    let mut first_path = None;
+    for candidate in candidates {
+        let path = candidate.template_path(template_name);
+        log::debug!("{} template {:?} exists={}", candidate.name(), path, path.exists());
+        if path.exists() {
+            return Ok((candidate, path));
+        }
+        first_path.get_or_insert(path);
+    }
+    Err(ExportError::TemplateNotFoundAt(first_path.unwrap_or_else(|| templates_dir().join(template_name))).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escaping() {
+        assert_eq!(Latex.escape("a\nb"), "a\\newline b");
+        assert_eq!(Latex.finish("\\textbf<x>".into()), "\\textbf{x}");
+        assert_eq!(Typst.escape("#1 *bold* a_b"), "\\#1 \\*bold\\* a\\_b");
+        assert_eq!(Pandoc.escape("*as is*"), "*as is*");
+    }
+
+    #[test]
+    fn names() {
+        assert_eq!(by_name("typst").unwrap().source_extension(), "typ");
+        assert!(by_name("word").is_err());
+    }
+}
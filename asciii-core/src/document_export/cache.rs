@@ -0,0 +1,104 @@
+//! Remembers what documents were built from, so `asciii make` can skip unchanged projects.
+//!
+//! `.asciii-cache/documents` in the storage holds one line per project:
+//! a hash over project file, template and backend, the project file and the document built from it.
+//! A document is only rebuilt if that hash changed or the document is gone.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+use crate::error::AsciiiError;
+use crate::project::BillType;
+use crate::util;
+
+const DIR: &str = ".asciii-cache";
+const FILE: &str = "documents";
+
+#[derive(Debug, Default)]
+pub struct BuildCache {
+    file: PathBuf,
+    /// project file -> (hash, document)
+    entries: HashMap<PathBuf, (String, PathBuf)>,
+}
+
+/// Hash over everything a document is made of, `None` if one of the files can't be read.
+pub fn hash(project_file: &Path, template: &Path, backend: &str, bill_type: Option<BillType>) -> Option<String> {
+    let mut hasher = DefaultHasher::new();
+    fs::read(project_file).ok()?.hash(&mut hasher);
+    fs::read(template).ok()?.hash(&mut hasher);
+    backend.hash(&mut hasher);
+    bill_type.map(|b| b.to_string()).hash(&mut hasher);
+    Some(format!("{:016x}", hasher.finish()))
+}
+
+impl BuildCache {
+    /// Reads the cache of a storage, a missing or broken cache is just empty.
+    pub fn open(storage_root: &Path) -> BuildCache {
+        let file = storage_root.join(DIR).join(FILE);
+        let entries = fs::read_to_string(&file)
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.split('\t');
+                let (hash, project, document) = (fields.next()?, fields.next()?, fields.next()?);
+                Some((PathBuf::from(project), (hash.to_owned(), PathBuf::from(document))))
+            })
+            .collect();
+        BuildCache { file, entries }
+    }
+
+    /// The document of this project was built from exactly this and still exists.
+    pub fn is_fresh(&self, project_file: &Path, hash: &str) -> bool {
+        match self.entries.get(project_file) {
+            Some((cached, document)) => cached == hash && document.exists(),
+            None => false,
+        }
+    }
+
+    pub fn insert(&mut self, project_file: &Path, hash: String, document: &Path) {
+        self.entries.insert(project_file.to_owned(), (hash, document.to_owned()));
+    }
+
+    pub fn save(&self) -> Result<(), AsciiiError> {
+        if let Some(dir) = self.file.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut lines = self.entries.iter()
+            .map(|(project, (hash, document))| format!("{}\t{}\t{}\n", hash, project.display(), document.display()))
+            .collect::<Vec<_>>();
+        lines.sort();
+        util::atomic_write(&self.file, lines.concat())?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempdir::TempDir;
+
+    #[test]
+    fn fresh_until_changed() {
+        let dir = TempDir::new("asciii-build-cache").unwrap();
+        let (project, template, document) = (dir.path().join("p.yml"), dir.path().join("t.hbs"), dir.path().join("p.pdf"));
+        fs::write(&project, "event: a").unwrap();
+        fs::write(&template, "{{event}}").unwrap();
+        fs::write(&document, "%PDF").unwrap();
+
+        let before = hash(&project, &template, "latex", None).unwrap();
+        let mut cache = BuildCache::open(dir.path());
+        assert!(!cache.is_fresh(&project, &before));
+        cache.insert(&project, before.clone(), &document);
+        cache.save().unwrap();
+
+        let cache = BuildCache::open(dir.path());
+        assert!(cache.is_fresh(&project, &before));
+        fs::write(&template, "{{event}}!").unwrap();
+        let after = hash(&project, &template, "latex", None).unwrap();
+        assert!(!cache.is_fresh(&project, &after));
+        assert_ne!(hash(&project, &template, "typst", None), Some(after));
+    }
+}
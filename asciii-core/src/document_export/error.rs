@@ -0,0 +1,48 @@
+use thiserror::Error;
+
+use std::path::PathBuf;
+
+#[derive(Error, Debug)]
+pub enum ExportError {
+    #[error("No PDF Created")]
+    NoPdfCreated,
+
+    #[error("Nothing to do")]
+    NothingToDo,
+
+    #[error("Template not found at {:?}", _0)]
+    TemplateNotFoundAt(PathBuf),
+
+    #[error("Unknown document backend {:?}, try latex, typst or pandoc", _0)]
+    UnknownBackend(String),
+
+    #[error("{}", _0)]
+    Failed(String),
+
+    #[error(transparent)]
+    Template(Box<handlebars::TemplateError>),
+
+    #[error(transparent)]
+    TemplateFile(Box<handlebars::TemplateFileError>),
+
+    #[error(transparent)]
+    Render(#[from] handlebars::RenderError),
+
+    #[error(transparent)]
+    TemplateRender(Box<handlebars::TemplateRenderError>),
+}
+
+macro_rules! boxed_from {
+    ($($variant:ident: $error:ty),*) => {
+        $(impl From<$error> for ExportError {
+            fn from(error: $error) -> Self {
+                ExportError::$variant(Box::new(error))
+            }
+        })*
+    }
+}
+
+// these are large, boxing them keeps `Result`s small
+boxed_from!(Template: handlebars::TemplateError,
+            TemplateFile: handlebars::TemplateFileError,
+            TemplateRender: handlebars::TemplateRenderError);
@@ -0,0 +1,242 @@
+//! Composes emails carrying offers and invoices.
+//!
+//! Subject and body are handlebars templates filled with the same data as the document templates.
+//! A mail can be sent through smtp (using `curl`), saved as `.eml` draft or turned into a `mailto:` link.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::prelude::*;
+use handlebars::{no_escape, Handlebars};
+
+use crate::error::{io_context, AsciiiError, ExportError};
+use crate::project::export::{Complete, ExportTarget};
+use crate::project::spec::IsClient;
+use crate::project::{BillType, Exportable, Project};
+use crate::storage::Storable;
+use crate::util;
+use crate::util::http::Request;
+
+use super::DocAndStorage;
+use super::backend::Pandoc;
+
+/// An email with attachments.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mail {
+    pub from: String,
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+    pub attachments: Vec<PathBuf>,
+}
+
+/// Body template from `mail/template` in the templates dir, or `mail/body` if there is none.
+fn body_template() -> Result<String, AsciiiError> {
+    let path = crate::storage::get_storage_path()
+        .join(crate::CONFIG.get_str("dirs/templates"))
+        .join(crate::CONFIG.get_str("mail/template"))
+        .with_extension(crate::CONFIG.get_str("extensions/mail_template"));
+
+    if path.exists() {
+        log::debug!("mail body from {}", path.display());
+        Ok(fs::read_to_string(&path)?)
+    } else {
+        Ok(crate::CONFIG.get_str("mail/body").to_owned())
+    }
+}
+
+fn sender() -> Result<String, AsciiiError> {
+    crate::CONFIG.get_str_or("mail/from")
+        .filter(|from| !from.is_empty())
+        .map(ToOwned::to_owned)
+        .or_else(util::git_user_email)
+        .ok_or_else(|| ExportError::Failed(lformat!("No sender address, please set \"mail/from\"")).into())
+}
+
+impl Mail {
+    /// Composes the mail that sends the offer or invoice of a project to its client.
+    pub fn for_project(project: &Project, bill_type: BillType) -> Result<Mail, AsciiiError> {
+        let to = project.client().email()
+            .map_err(|_| ExportError::Failed(lformat!("{} has no client/email", project.short_desc())))?
+            .to_owned();
+
+        let document = project.output_file(bill_type)
+            .ok_or_else(|| ExportError::Failed(lformat!("{} has no {:?} number or date yet", project.short_desc(), bill_type)))?;
+        if !document.exists() {
+            return Err(ExportError::Failed(lformat!("{} does not exist, run `asciii make` first", document.display())).into());
+        }
+
+        let exported: Complete = project.export();
+        // plain text, the paths of branding assets stay as they are
+        let data = DocAndStorage::from(&exported, bill_type, &Pandoc);
+
+        let mut handlebars = Handlebars::new();
+        handlebars.register_escape_fn(no_escape);
+        let subject = handlebars.render_template(crate::CONFIG.get_str("mail/subject"), &data)?;
+        let body = handlebars.render_template(&body_template()?, &data)?;
+
+        Ok(Mail {
+            from: sender()?,
+            to,
+            subject: subject.trim().to_owned(),
+            body,
+            attachments: vec![document],
+        })
+    }
+
+    /// Renders the whole message as it is sent, attachments are base64 encoded.
+    pub fn to_eml(&self) -> Result<String, AsciiiError> {
+        let boundary = format!("asciii-{}", Utc::now().timestamp_nanos());
+        let mut eml = String::new();
+
+        eml += &format!("From: {}\r\n", address(&self.from)?);
+        eml += &format!("To: {}\r\n", address(&self.to)?);
+        eml += &format!("Subject: {}\r\n", encode_header(&self.subject));
+        eml += &format!("Date: {}\r\n", Local::now().to_rfc2822());
+        eml += "MIME-Version: 1.0\r\n";
+        eml += &format!("Content-Type: multipart/mixed; boundary=\"{}\"\r\n\r\n", boundary);
+
+        eml += &format!("--{}\r\n", boundary);
+        eml += "Content-Type: text/plain; charset=utf-8\r\n";
+        eml += "Content-Transfer-Encoding: base64\r\n\r\n";
+        eml += &wrap_base64(self.body.as_bytes());
+
+        for attachment in &self.attachments {
+            let name = attachment.file_name()
+                .and_then(|n| n.to_str())
+                .ok_or_else(|| ExportError::Failed(lformat!("{} is not a valid file name", attachment.display())))?;
+            let content = fs::read(attachment)
+                .map_err(io_context(lformat!("can't read {}", attachment.display())))?;
+
+            eml += &format!("--{}\r\n", boundary);
+            eml += &format!("Content-Type: {}; name=\"{}\"\r\n", mime_type(attachment), encode_header(name));
+            eml += &format!("Content-Disposition: attachment; filename=\"{}\"\r\n", encode_header(name));
+            eml += "Content-Transfer-Encoding: base64\r\n\r\n";
+            eml += &wrap_base64(&content);
+        }
+
+        eml += &format!("--{}--\r\n", boundary);
+        Ok(eml)
+    }
+
+    /// `mailto:` link with subject and body, mail clients don't accept attachments this way.
+    pub fn to_mailto(&self) -> String {
+        format!("mailto:{}?subject={}&body={}",
+                self.to,
+                util::percent_encode(&self.subject),
+                util::percent_encode(&self.body))
+    }
+
+    /// Saves the message as `.eml`, which most mail clients open as draft.
+    pub fn save_draft(&self, path: &Path) -> Result<(), AsciiiError> {
+        util::atomic_write(path, self.to_eml()?)?;
+        log::info!("{}", lformat!("wrote {}", path.display()));
+        Ok(())
+    }
+
+    /// Sends the message to the smtp server configured in `mail/smtp`.
+    ///
+    /// The password is read from `ASCIII_MAIL_PASSWORD` and given to curl on its stdin, the message in a temporary file.
+    pub fn send(&self) -> Result<(), AsciiiError> {
+        let server = crate::CONFIG.get_str_or("mail/smtp")
+            .filter(|s| !s.is_empty())
+            .ok_or_else(|| ExportError::Failed(lformat!("No smtp server, please set \"mail/smtp\"")))?;
+
+        let mut request = Request::new(lformat!("curl is needed to send mails"))
+            .args(["--ssl-reqd"])
+            .args(["--url", server])
+            .args(["--mail-from", &self.from])
+            .args(["--mail-rcpt", &self.to]);
+
+        if let Some(user) = crate::CONFIG.get_str_or("mail/user").filter(|u| !u.is_empty()) {
+            let password = std::env::var("ASCIII_MAIL_PASSWORD").unwrap_or_default();
+            request = request.basic_auth(user, &password);
+        }
+
+        log::debug!("sending mail to {} via {}", self.to, server);
+        // attachments make mails large, a mail that went out once is never sent again
+        let output = request.upload_file(self.to_eml()?.into_bytes())
+                            .timeout(None)
+                            .output()?;
+
+        if !output.status.success() {
+            return Err(ExportError::Failed(lformat!("sending mail to {} failed", self.to)).into());
+        }
+        Ok(())
+    }
+}
+
+fn mime_type(path: &Path) -> &'static str {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("pdf") => "application/pdf",
+        Some("txt") => "text/plain",
+        _ => "application/octet-stream",
+    }
+}
+
+/// An address as it goes into a header, a line break in it would start a header of its own.
+fn address(address: &str) -> Result<&str, AsciiiError> {
+    if address.contains(['\r', '\n']) {
+        return Err(ExportError::Failed(lformat!("{:?} is not a valid mail address", address)).into());
+    }
+    Ok(address)
+}
+
+/// Encodes headers that are not plain ascii as described in RFC 2047, line breaks included.
+fn encode_header(text: &str) -> String {
+    if text.is_ascii() && !text.contains(|c: char| c.is_ascii_control()) {
+        text.to_owned()
+    } else {
+        format!("=?utf-8?B?{}?=", base64::encode(text))
+    }
+}
+
+fn wrap_base64(content: &[u8]) -> String {
+    let encoded = base64::encode(content);
+    let mut wrapped = String::with_capacity(encoded.len() + encoded.len() / 38 + 2);
+    for line in encoded.as_bytes().chunks(76) {
+        wrapped.push_str(std::str::from_utf8(line).unwrap_or_default());
+        wrapped.push_str("\r\n");
+    }
+    wrapped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mail() -> Mail {
+        Mail {
+            from: "me@example.com".into(),
+            to: "you@example.com".into(),
+            subject: "Rechnung für Kaffee".into(),
+            body: "Hallo,\nanbei die Rechnung.".into(),
+            attachments: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn mailto_is_encoded() {
+        assert_eq!(mail().to_mailto(),
+                   "mailto:you@example.com?subject=Rechnung%20f%C3%BCr%20Kaffee&body=Hallo%2C%0Aanbei%20die%20Rechnung.");
+    }
+
+    #[test]
+    fn eml_headers() {
+        let eml = mail().to_eml().unwrap();
+        assert!(eml.contains("To: you@example.com\r\n"));
+        assert!(eml.contains(&format!("Subject: =?utf-8?B?{}?=\r\n", base64::encode("Rechnung für Kaffee"))));
+        assert!(eml.contains(&base64::encode("Hallo,\nanbei die Rechnung.")));
+    }
+
+    #[test]
+    fn no_headers_are_injected() {
+        let subject = "Rechnung Party\r\nBcc: everyone@example.com";
+        let eml = Mail { subject: subject.into(), ..mail() }.to_eml().unwrap();
+        assert!(eml.contains(&format!("Subject: =?utf-8?B?{}?=\r\n", base64::encode(subject))));
+        assert!(!eml.contains("Bcc:"));
+
+        let to = "you@example.com\nBcc: everyone@example.com";
+        assert!(Mail { to: to.into(), ..mail() }.to_eml().is_err());
+    }
+}
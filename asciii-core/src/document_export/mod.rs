@@ -0,0 +1,437 @@
+//! Fills export templates to create documents.
+//!
+//! Templates are filled with handlebars, rendering them is up to a [`DocumentBackend`](backend/trait.DocumentBackend.html).
+
+use std::{time,fs};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::ser::Serialize;
+
+use handlebars::{Handlebars, Helper, RenderContext, HelperDef, Context, Output, HelperResult};
+#[cfg(feature="rayon")] use rayon::prelude::*;
+
+use crate::actions::branding::{self, BankDetails};
+use crate::error::AsciiiError;
+use crate::util;
+use crate::project::{self, Project, Exportable};
+use crate::project::BillType::{self, Invoice, Offer};
+use crate::project::export::ExportTarget;
+use crate::storage::{self, Storable, StorageSelection};
+
+pub mod backend;
+pub mod cache;
+pub mod error;
+pub mod mail;
+pub mod watch;
+
+use self::backend::DocumentBackend;
+use self::cache::BuildCache;
+use self::error::*;
+
+#[cfg_attr(feature = "serialization", derive(Serialize))]
+struct DocAndStorage<'a, T: Serialize> {
+    document: &'a T,
+    storage: Option<storage::Paths>,
+    branding: Option<BrandingAssets>,
+    is_invoice: bool
+}
+
+impl<'a, T: 'a + Serialize> DocAndStorage<'a, T> {
+    fn from(document: &'a T, bill_type: BillType, backend: &'static dyn DocumentBackend) -> DocAndStorage<'a, T> {
+        let storage = storage::setup::<Project>().ok();
+        DocAndStorage {
+            document,
+            branding: storage.as_ref().and_then(|storage| BrandingAssets::of(storage, backend)),
+            storage: storage.map(|s| s.paths()),
+            is_invoice: bill_type == Invoice
+        }
+    }
+}
+
+/// `extras/branding.yml` with the files as the backend wants them.
+#[cfg_attr(feature = "serialization", derive(Serialize))]
+struct BrandingAssets {
+    logo: Option<String>,
+    signature: Option<String>,
+    footer: Option<String>,
+    bank: Option<BankDetails>,
+}
+
+impl BrandingAssets {
+    fn of(storage: &storage::Storage<Project>, backend: &'static dyn DocumentBackend) -> Option<BrandingAssets> {
+        let branding = branding::configured(storage)
+            .unwrap_or_else(|e| {
+                log::warn!("{}", lformat!("can't read {}: {}", branding::BRANDING_FILE, e));
+                None
+            })?;
+        let asset = |path: &Option<PathBuf>| path.as_ref().map(|path| backend.asset_path(&storage.extras_dir().join(path)));
+        Some(BrandingAssets {
+            logo: asset(&branding.logo),
+            signature: asset(&branding.signature),
+            footer: branding.footer,
+            bank: branding.bank,
+        })
+    }
+}
+
+#[derive(Clone, Copy)]
+struct IncHelper;
+
+impl HelperDef for IncHelper {
+    #[allow(clippy::extra_unused_lifetimes)]
+    fn call<'reg: 'rc, 'rc>(&self, h: &Helper<'_, '_>, _: &Handlebars, _: &Context, _: &mut RenderContext<'_>, out: &mut dyn Output) -> HelperResult {
+        let param = h.param(0).unwrap().value();
+        log::debug!("inc_helper({:?})", param);
+        out.write(&format!("{}", param.as_u64().expect("param can't be converted to u64") + 1))?;
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy)]
+struct CountHelper;
+
+impl HelperDef for CountHelper {
+    #[allow(clippy::extra_unused_lifetimes)]
+    fn call<'reg: 'rc, 'rc>(&self, h: &Helper<'_, '_>, _: &Handlebars, _: &Context, _: &mut RenderContext<'_>, out: &mut dyn Output) -> HelperResult {
+        let count = h.param(0).unwrap().value().as_array().map_or(0, Vec::len);
+        out.write(&format!("{}", count))?;
+        Ok(())
+    }
+}
+
+/// Takes a `T: Serialize` and a template path and does it's thing.
+///
+/// Values are escaped for the backend, which also gets the last word on the filled template.
+pub fn fill_template<E, P>(document: &E, bill_type: BillType, template_path: P, backend: &'static dyn DocumentBackend) -> Result<String, AsciiiError>
+    where E: Serialize, P:AsRef<Path>
+{
+    render(document, bill_type, template_path.as_ref(), backend, false)
+}
+
+/// Like `fill_template()`, but fails on fields the document does not have, see `asciii template check`.
+pub fn check_template<E: Serialize>(document: &E, bill_type: BillType, template_path: &Path, backend: &'static dyn DocumentBackend) -> Result<String, AsciiiError> {
+    render(document, bill_type, template_path, backend, true)
+}
+
+/// Like `fill_template()`, but takes the template itself instead of a path, e.g. where there is no filesystem.
+pub fn fill_template_content<E: Serialize>(document: &E, bill_type: BillType, template: &str, backend: &'static dyn DocumentBackend) -> Result<String, AsciiiError> {
+    let mut handlebars = registry(backend, false);
+    handlebars.register_template_string("document", template)?;
+    render_registered(&handlebars, document, bill_type, backend)
+}
+
+fn registry(backend: &'static dyn DocumentBackend, strict: bool) -> Handlebars {
+    let mut handlebars = Handlebars::new();
+
+    handlebars.register_escape_fn(move |data| backend.escape(data));
+    handlebars.set_strict_mode(strict);
+
+    handlebars.register_helper("inc",   Box::new(IncHelper));
+    // handlebars.register_helper("count", Box::new(count_helper));
+    handlebars
+}
+
+fn render<E: Serialize>(document: &E, bill_type: BillType, template_path: &Path, backend: &'static dyn DocumentBackend, strict: bool) -> Result<String, AsciiiError> {
+    let mut handlebars = registry(backend, strict);
+    handlebars.register_template_file("document", template_path)?;
+    render_registered(&handlebars, document, bill_type, backend)
+}
+
+fn render_registered<E: Serialize>(handlebars: &Handlebars, document: &E, bill_type: BillType, backend: &'static dyn DocumentBackend) -> Result<String, AsciiiError> {
+    let filled = {
+        let _timer = util::timing::start("rendering");
+        handlebars.render("document", &DocAndStorage::from(document, bill_type, backend))?
+    };
+    Ok(backend.finish(filled))
+}
+
+fn file_age(path: &Path) -> Result<time::Duration, AsciiiError> {
+    let metadata = fs::metadata(path)?;
+    let modified = metadata.modified()?;
+    Ok(modified.elapsed()?)
+}
+
+/// Creates the document sources within each projects directory, either for Invoice or Offer.
+#[cfg(feature="document_export")]
+#[allow(clippy::cognitive_complexity)] // sorry
+fn project_to_doc(project: &Project, config: &ExportConfig<'_>) -> Result<Option<PathBuf>, AsciiiError> {
+    log::trace!("exporting a document: {:#?}", config);
+
+    let &ExportConfig {
+        template_name,
+        backend,
+        bill_type,
+        output: output_path,
+        dry_run,
+        force,
+        pdf_only,
+        print_only,
+        ..
+    } = config;
+
+    // init_export_config()
+    let (backend, template_path) = backend::for_template(template_name, backend)?;
+    let output_ext    = backend.source_extension();
+    let convert_ext   = backend.document_extension();
+    let output_folder = project.output_folder().unwrap();
+    log::debug!("converting with {:?}", backend.name());
+    log::debug!("template {:?}", template_path);
+
+    // project_readiness(&project) {
+    let missing_for_offer = project.is_missing_for_offer();
+    let missing_for_invoice = project.is_missing_for_invoice();
+    let project_file = project.file();
+
+    let default_mode = if missing_for_invoice.is_empty() { Invoice } else { Offer };
+    let (dyn_bill_type, outfile_tex):
+        (Option<BillType>, Option<PathBuf>) =
+         match bill_type.unwrap_or(default_mode) // (bill_type, missing_for_offer[..], missing_for_invoice[..])
+    {
+        Offer if missing_for_offer.is_empty() =>
+            (Some(Offer), Some(project.dir().join(project.offer_file_name(output_ext)
+                                                  .expect("this should have been caught by missing_for_offer()")))),
+
+        Invoice if missing_for_invoice.is_empty() =>
+            (Some(Invoice), Some(project.dir().join(project.invoice_file_name(output_ext)
+                                                    .expect("this should have been caught by missing_for_invoice()")))),
+
+        Offer if !missing_for_offer.is_empty() && bill_type.is_some() => {
+            log::error!("cannot create an offer, check out:{}",missing_for_offer.join("|"));
+            (None,None)
+        },
+
+        Invoice if !missing_for_invoice.is_empty() && bill_type.is_some() => {
+            log::error!("cannot create an invoice, check out:{}",missing_for_invoice.join("|"));
+            (None,None)
+        }
+
+        _ => {
+            log::error!("Neither an Offer nor an Invoice can be created from this project\n please check out {}", missing_for_offer.join("|"));
+            (None,None)
+        }
+    };
+
+    // }
+
+    if let (Some(tex_file), Some(dyn_bill)) = (outfile_tex, dyn_bill_type) {
+        let exported_project: project::export::Complete = project.export();
+        let filled = fill_template(&exported_project, dyn_bill, &template_path, backend)?;
+
+        let pdffile = util::to_local_file(&tex_file, convert_ext);
+
+        let document_file = if let Some(output_path) = output_path {
+            if output_path.is_dir() { // if dir, use my name and place in there
+                log::trace!("output_path is dir");
+                output_path.join(&pdffile)
+            } else if output_path.parent().map(Path::exists).unwrap_or(false) {// if not dir, place at this path with this name
+                log::trace!("output_path is file");
+                output_path.to_owned()
+            } else {
+                println!("{}", lformat!("WARNING: Can't make sense of {}", output_path.display()));
+                output_folder.join(&pdffile)
+            }
+        } else {
+            output_folder.join(&pdffile)
+        };
+
+        log::debug!("document file will be {:?}", document_file);
+
+        let defy = force || pdf_only;
+
+        // ok, so apparently we can create a tex file, so lets do it
+        if !defy && document_file.exists() && file_age(&tex_file)? < file_age(&project_file)? {
+            // no wait, nothing has changed, so lets save ourselves the work
+            use std::ffi::OsStr;
+            log::info!("Nothing to do!\n{} is younger than {}\n\nuse --force if you don't agree\nuse --pdf to only render the pdf again",
+                  tex_file.file_name().and_then(OsStr::to_str).unwrap(),
+                  project_file.file_name().and_then(OsStr::to_str).unwrap()
+                  );
+            Ok(None)
+
+        } else if dry_run { // just testing what is possible
+            log::warn!("Dry run! This does not produce any output:\n * {}\n * {}", tex_file.display(), pdffile.display());
+            Ok(None)
+
+        } else if print_only { // for debugging or pipelining purposes
+            log::debug!("only printing");
+            println!("{}", filled);
+            Ok(None)
+
+
+        } else { // ok, we really have to work
+
+            let outfile_path = if pdf_only {
+                let (tex_age, project_age) = (file_age(&tex_file)?, file_age(&project_file)?);
+                log::info!("recreating the pdf");
+                log::debug!("{:?} -> {:?}", tex_file, document_file);
+                log::debug!("{:?} -> {:?}", tex_age, project_age);
+                if project_age < tex_age && !util::really(&lformat!("Project file is younger than pdf, continue anyway?")) {
+                    return Ok(None)
+                }
+                project.full_file_path(dyn_bill, output_ext)?
+            } else {
+                if dyn_bill == Invoice {
+                    if let Some((_, message)) = project.unaccepted_offer() {
+                        log::warn!("{}", message);
+                    }
+                }
+                // stamped before writing, so the document is still younger than the project file
+                let generated_field = match dyn_bill { Offer => "offer/generated_date", Invoice => "invoice/generated_date" };
+                let today = chrono::Local::today().format("%d.%m.%Y").to_string();
+                if let Err(e) = project.set_field(generated_field, &today) {
+                    log::warn!("{}", lformat!("can't set {}: {}", generated_field, e));
+                }
+
+                let outfile_path = project.write_to_file(&filled, dyn_bill, output_ext)?;
+                log::debug!("{} vs\n        {}", tex_file.display(), outfile_path.display());
+                outfile_path
+            };
+            let rendered = {
+                let _timer = util::timing::start("rendering");
+                backend.render(&outfile_path)?
+            };
+
+            // clean up expected log and aux files etc
+            for trash_ext in backend.trash_extensions() {
+                let trash_file = util::to_local_file(&tex_file, trash_ext);
+                if  trash_file.exists() {
+                    fs::remove_file(&trash_file)?;
+                    log::debug!("just deleted: {}", trash_file.display())
+                } else {
+                    log::debug!("I expected there to be a {}, but there wasn't any ?", trash_file.display())
+                }
+            }
+
+            // now we move the created document, a project's own output_dir may not be there yet
+            log::debug!("now there is be a {:?} -> {:?}", rendered, document_file);
+            if let Some(folder) = document_file.parent() {
+                fs::create_dir_all(folder)?;
+            }
+            fs::rename(&rendered, &document_file)?;
+            Ok(Some(document_file))
+        }
+
+    } else {
+        Err(ExportError::NoPdfCreated.into())
+    }
+}
+
+#[derive(Debug)]
+pub struct ExportConfig<'a> {
+    pub select: StorageSelection,
+    pub template_name: &'a str,
+    /// see `backend::for_template()`
+    pub backend: Option<&'a str>,
+    pub bill_type: Option<BillType>,
+    pub output: Option<&'a Path>,
+    pub dry_run: bool,
+    pub pdf_only: bool,
+    pub force: bool,
+    pub print_only: bool,
+    pub open: bool,
+}
+
+impl<'a> Default for ExportConfig<'a> {
+    fn default() -> Self {
+        Self {
+            select: StorageSelection::default(),
+            template_name: crate::CONFIG.get_str("document_export/default_template"),
+            backend: None,
+            bill_type: None,
+            output: None,
+            dry_run: false,
+            pdf_only: false,
+            force: false,
+            print_only: false,
+            open: true
+        }
+    }
+}
+
+/// What became of a single project in `projects_to_doc()`.
+#[derive(Debug)]
+pub enum Built {
+    Rebuilt(PathBuf),
+    Skipped,
+    Failed(AsciiiError),
+}
+
+/// Creates the documents of all selected projects, in parallel with the `par_open` feature.
+///
+/// Projects whose file, template and backend are unchanged since the last build are skipped, see `cache`.
+/// A failing project does not stop the others, a summary is printed at the end.
+#[cfg(feature="document_export")]
+pub fn projects_to_doc(config: &ExportConfig<'_>) -> Result<(), AsciiiError> {
+    let storage = storage::setup::<Project>()?;
+    let projects = storage.open_projects(&config.select)?;
+    let (backend, template) = backend::for_template(config.template_name, config.backend)?;
+
+    let use_cache = !(config.force || config.pdf_only || config.dry_run || config.print_only);
+    let cache = Mutex::new(BuildCache::open(storage.root_dir()));
+    // with the cache in use it decides what is outdated, not the age of the files
+    let build_config = ExportConfig { force: config.force || use_cache, select: config.select.clone(), ..*config };
+
+    let build = |project: &Project| -> Built {
+        let file = project.file();
+        let hash = || cache::hash(&file, &template, backend.name(), config.bill_type).filter(|_| use_cache);
+        if let Some(hash) = hash() {
+            if cache.lock().unwrap().is_fresh(&file, &hash) {
+                log::debug!("{} is up to date", file.display());
+                return Built::Skipped;
+            }
+        }
+
+        match project_to_doc(project, &build_config) {
+            Ok(Some(document)) => {
+                // hashed afterwards, building stamps the project file
+                if let Some(hash) = hash() {
+                    cache.lock().unwrap().insert(&file, hash, &document);
+                }
+                Built::Rebuilt(document)
+            }
+            Ok(None) => Built::Skipped,
+            Err(error) => Built::Failed(error),
+        }
+    };
+
+    #[cfg(feature="rayon")]
+    let results = if config.pdf_only || config.print_only {
+        // these may ask questions or print whole documents, better one after the other
+        projects.iter().map(build).collect::<Vec<Built>>()
+    } else {
+        projects.par_iter().map(build).collect()
+    };
+    #[cfg(not(feature="rayon"))]
+    let results = projects.iter().map(build).collect::<Vec<Built>>();
+
+    if use_cache {
+        if let Err(e) = cache.into_inner().unwrap().save() {
+            log::warn!("{}", lformat!("can't save the build cache: {}", e));
+        }
+    }
+
+    let (mut rebuilt, mut skipped, mut failed) = (0, 0, 0);
+    for (project, result) in projects.iter().zip(&results) {
+        match result {
+            Built::Rebuilt(document) => {
+                rebuilt += 1;
+                if config.open {
+                    util::open_file(document)?;
+                }
+            }
+            Built::Skipped => skipped += 1,
+            Built::Failed(error) => {
+                failed += 1;
+                log::error!("{}: {}", project.short_desc(), error);
+            }
+        }
+    }
+    println!("{}", lformat!("{} rebuilt, {} skipped, {} failed", rebuilt, skipped, failed));
+
+    if failed > 0 {
+        return Err(ExportError::Failed(lformat!("{} of {} documents failed", failed, results.len())).into());
+    }
+    Ok(())
+}
+
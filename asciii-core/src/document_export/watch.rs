@@ -0,0 +1,132 @@
+//! Rebuilds documents whenever a project file or the template changes, see `asciii make --watch`.
+//!
+//! Files are polled instead of subscribing to filesystem events.
+//! A change is only picked up once the files stayed untouched for `document_export/watch_debounce` milliseconds,
+//! so editors that save in several steps trigger a single build.
+//! Failing builds are reported and the watch goes on.
+
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use chrono::Local;
+
+use crate::error::{AsciiiError, ExportError};
+use crate::project::Project;
+use crate::util;
+use crate::storage::{self, Storable};
+
+use super::{backend, project_to_doc, ExportConfig};
+
+/// How often files are checked for changes.
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+fn modified(paths: &[PathBuf]) -> Vec<Option<SystemTime>> {
+    paths.iter()
+         .map(|path| path.metadata().and_then(|m| m.modified()).ok())
+         .collect()
+}
+
+/// Blocks until one of `paths` changed and then stayed untouched for `debounce`.
+fn wait_for_change(paths: &[PathBuf], debounce: Duration) {
+    let before = modified(paths);
+    while modified(paths) == before {
+        thread::sleep(POLL_INTERVAL);
+    }
+
+    let mut last = modified(paths);
+    loop {
+        thread::sleep(debounce);
+        let now = modified(paths);
+        if now == last {
+            return;
+        }
+        last = now;
+    }
+}
+
+/// Shows a failed build prominently, so it is not missed between the successful ones.
+fn print_error(file: &Path, error: &AsciiiError) {
+    let rule = "━".repeat(60);
+    eprintln!("{}", rule);
+    eprintln!("{} {}", lformat!("build failed:"), file.display());
+    let mut cause: Option<&dyn std::error::Error> = Some(error);
+    while let Some(error) = cause {
+        eprintln!("  {}", error);
+        cause = error.source();
+    }
+    eprintln!("{}", rule);
+}
+
+fn build(file: &Path, config: &ExportConfig<'_>) -> Option<PathBuf> {
+    let time = Local::now().format("%H:%M:%S");
+    match Project::open_file(file).and_then(|project| project_to_doc(&project, config)) {
+        Ok(Some(document)) => {
+            println!("{} {}", time, lformat!("created {}", document.display()));
+            Some(document)
+        }
+        Ok(None) => {
+            println!("{} {}", time, lformat!("nothing created from {}", file.display()));
+            None
+        }
+        Err(error) => {
+            print_error(file, &error);
+            None
+        }
+    }
+}
+
+/// Builds the selected projects once and again on every change, until interrupted.
+///
+/// Documents are always rebuilt, a changed template does not make the project file any younger.
+/// With `open` the documents are only opened after the first build, viewers usually reload them by themselves.
+pub fn watch(config: &ExportConfig<'_>) -> Result<(), AsciiiError> {
+    let storage = storage::setup::<Project>()?;
+    let files = storage.open_projects(&config.select)?
+                       .iter()
+                       .map(Storable::file)
+                       .collect::<Vec<PathBuf>>();
+    if files.is_empty() {
+        return Err(ExportError::Failed(lformat!("Nothing to watch")).into());
+    }
+
+    let mut paths = files.clone();
+    paths.push(backend::for_template(config.template_name, config.backend)?.1);
+    let debounce = Duration::from_millis(crate::CONFIG.get_f64("document_export/watch_debounce").unwrap_or(300.0) as u64);
+
+    let mut open = config.open;
+    let config = ExportConfig { force: true, open: false, select: config.select.clone(), ..*config };
+    println!("{}", lformat!("watching {} files, stop with Ctrl-C", paths.len()));
+    loop {
+        for file in &files {
+            if let (Some(document), true) = (build(file, &config), open) {
+                util::open_file(&document)?;
+            }
+        }
+        open = false;
+        wait_for_change(&paths, debounce);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempdir::TempDir;
+
+    #[test]
+    fn notices_changes() {
+        let dir = TempDir::new("asciii-watch").unwrap();
+        let file = dir.path().join("project.yml");
+        fs::write(&file, "a: 1").unwrap();
+
+        let paths = vec![file.clone(), dir.path().join("missing.tyml")];
+        let writer = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(50));
+            fs::write(&file, "a: 2").unwrap();
+        });
+        wait_for_change(&paths, Duration::from_millis(50));
+        writer.join().unwrap();
+        assert_eq!(fs::read_to_string(&paths[0]).unwrap(), "a: 2");
+    }
+}
@@ -0,0 +1,207 @@
+//! The error type of all public functions.
+//!
+//! Every fallible function of this crate returns an [`AsciiiError`],
+//! so callers can `match` on what went wrong instead of comparing messages.
+
+use std::{fmt, io};
+
+use thiserror::Error;
+use yaml_rust::{EmitError, ScanError};
+
+pub use crate::actions::error::ActionError;
+pub use crate::project::error::ProjectError;
+pub use crate::project::product::ProductError;
+pub use crate::project::FieldError;
+pub use crate::config::schema::ConfigProblem;
+pub use crate::storage::error::StorageError;
+pub use crate::storage::journal::JournalError;
+pub use crate::storage::ledger::LedgerError;
+pub use crate::storage::lock::LockError;
+pub use crate::storage::closing::ClosingError;
+#[cfg(feature="document_export")]
+pub use crate::document_export::error::ExportError;
+
+#[cfg(feature="git_statuses")]
+pub use git2::Error as GitError;
+#[cfg(not(feature="git_statuses"))]
+pub use crate::storage::repo::GitError;
+
+/// Shorthand for results of this crate.
+pub type Result<T, E = AsciiiError> = std::result::Result<T, E>;
+
+#[derive(Error, Debug)]
+pub enum AsciiiError {
+    /// The storage is missing, misconfigured or a project or template can't be found.
+    #[error(transparent)]
+    Storage(#[from] StorageError),
+
+    /// A project file can't be parsed or does not make sense.
+    #[error(transparent)]
+    Spec(#[from] SpecError),
+
+    /// Git failed.
+    #[error(transparent)]
+    Git(#[from] GitError),
+
+    /// A document can't be created or sent.
+    #[cfg(feature="document_export")]
+    #[error(transparent)]
+    Document(#[from] ExportError),
+
+    /// A configuration file is broken or can't be changed.
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+
+    /// An action on several projects failed or found something.
+    #[error(transparent)]
+    Action(#[from] ActionError),
+
+    /// Reading or writing a file failed.
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+/// Everything that can be wrong with a project file.
+#[derive(Error, Debug)]
+pub enum SpecError {
+    #[error(transparent)]
+    Project(#[from] ProjectError),
+
+    #[error(transparent)]
+    Product(#[from] ProductError),
+
+    #[error(transparent)]
+    Field(#[from] FieldError),
+
+    #[error(transparent)]
+    Yaml(#[from] ScanError),
+
+    #[error(transparent)]
+    YamlEmit(#[from] EmitError),
+
+    #[cfg(feature="serialization")]
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+
+    #[cfg(any(feature="serialization", feature="deserialization"))]
+    #[error(transparent)]
+    Deserialize(#[from] serde_yaml::Error),
+}
+
+/// Problems with `~/.asciii.yml` and `.asciii.yml`.
+#[derive(Error, Debug)]
+pub enum ConfigError {
+    #[error("can't set {:?} in {}", _0, _1.display())]
+    CantSet(String, std::path::PathBuf),
+
+    #[error(transparent)]
+    Invalid(#[from] ConfigProblem),
+}
+
+macro_rules! spec_error_from {
+    ($($error:ty),*) => {
+        $(impl From<$error> for AsciiiError {
+            fn from(error: $error) -> Self {
+                AsciiiError::Spec(error.into())
+            }
+        })*
+    }
+}
+
+spec_error_from!(ProjectError, ProductError, FieldError, ScanError, EmitError);
+#[cfg(feature="serialization")] spec_error_from!(serde_json::Error);
+#[cfg(any(feature="serialization", feature="deserialization"))] spec_error_from!(serde_yaml::Error);
+
+macro_rules! action_error_from {
+    ($($error:ty),*) => {
+        $(impl From<$error> for AsciiiError {
+            fn from(error: $error) -> Self {
+                AsciiiError::Action(error.into())
+            }
+        })*
+    }
+}
+
+action_error_from!(csv::Error, std::string::FromUtf8Error, std::time::SystemTimeError);
+#[cfg(feature="meta")] action_error_from!(toml::de::Error);
+
+#[cfg(feature="document_export")]
+macro_rules! document_error_from {
+    ($($error:ty),*) => {
+        $(impl From<$error> for AsciiiError {
+            fn from(error: $error) -> Self {
+                AsciiiError::Document(error.into())
+            }
+        })*
+    }
+}
+
+#[cfg(feature="document_export")]
+document_error_from!(handlebars::TemplateError, handlebars::TemplateFileError,
+                     handlebars::RenderError, handlebars::TemplateRenderError);
+
+impl From<ConfigProblem> for AsciiiError {
+    fn from(error: ConfigProblem) -> Self {
+        AsciiiError::Config(error.into())
+    }
+}
+
+/// Puts `what` in front of an io error, so it is clear which file or program failed.
+pub(crate) fn io_context(what: String) -> impl FnOnce(io::Error) -> io::Error {
+    move |error| io::Error::new(error.kind(), format!("{}: {}", what, error))
+}
+
+impl<W> From<csv::IntoInnerError<W>> for AsciiiError {
+    fn from(error: csv::IntoInnerError<W>) -> Self {
+        AsciiiError::Io(error.into_error())
+    }
+}
+
+/// Only happens when writing into a `String` fails.
+impl From<fmt::Error> for AsciiiError {
+    fn from(error: fmt::Error) -> Self {
+        AsciiiError::Io(io::Error::other(error))
+    }
+}
+
+impl From<JournalError> for AsciiiError {
+    fn from(error: JournalError) -> Self {
+        AsciiiError::Storage(error.into())
+    }
+}
+
+impl From<LedgerError> for AsciiiError {
+    fn from(error: LedgerError) -> Self {
+        AsciiiError::Storage(error.into())
+    }
+}
+
+impl From<LockError> for AsciiiError {
+    fn from(error: LockError) -> Self {
+        AsciiiError::Storage(error.into())
+    }
+}
+
+impl From<ClosingError> for AsciiiError {
+    fn from(error: ClosingError) -> Self {
+        AsciiiError::Storage(error.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project::Project;
+
+    #[test]
+    fn broken_yaml_is_a_spec_error() {
+        let error = Project::from_file_content("client: [").unwrap_err();
+        assert!(matches!(error, AsciiiError::Spec(SpecError::Yaml(_))));
+    }
+
+    #[test]
+    fn journal_errors_are_storage_errors() {
+        let error = AsciiiError::from(JournalError::NothingToUndo);
+        assert!(matches!(error, AsciiiError::Storage(StorageError::Journal(JournalError::NothingToUndo))));
+    }
+}
@@ -0,0 +1,203 @@
+//! Euro reference rates of the European Central Bank, for projects billed in another currency.
+//!
+//! A project names its currency by ISO code, e.g. `currency: USD`, without it the project is billed in euro.
+//! Reports need euro amounts, so those projects are converted with the rate of their invoice date,
+//! or the last one before it (there are no rates on weekends and holidays).
+//!
+//! The rates are downloaded (using `curl`) from `exchange/url` once a day and kept in `exchange/cache`.
+//! With `exchange/fetch: false` only the cache is read, e.g. a file downloaded by hand.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use bill::Currency;
+use chrono::NaiveDate;
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::error::{io_context, ActionError, AsciiiError};
+use crate::util;
+use crate::util::http::Request;
+
+/// The currency ECB rates are quoted against.
+pub const HOME: &str = "EUR";
+
+/// Rates in the cache are refreshed after this long.
+const MAX_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+lazy_static! {
+    static ref DAY: Regex = Regex::new(r#"^Cube\s+time=["'](\d{4}-\d{2}-\d{2})["']"#).unwrap();
+    static ref RATE: Regex = Regex::new(r#"^Cube\s+currency=["']([A-Z]{3})["']\s+rate=["']([0-9.]+)["']"#).unwrap();
+}
+
+/// Units of each currency one euro was worth, per day.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Rates {
+    days: BTreeMap<NaiveDate, HashMap<String, f64>>,
+}
+
+impl Rates {
+    /// Reads the XML the ECB publishes its daily or historic rates in.
+    pub fn parse(xml: &str) -> Result<Rates, AsciiiError> {
+        let mut days = BTreeMap::new();
+        let mut day: Option<NaiveDate> = None;
+        for tag in xml.split('<') {
+            if let Some(caps) = DAY.captures(tag) {
+                day = NaiveDate::parse_from_str(&caps[1], "%Y-%m-%d").ok();
+            } else if let (Some(caps), Some(day)) = (RATE.captures(tag), day) {
+                if let Ok(rate) = caps[2].parse::<f64>() {
+                    days.entry(day).or_insert_with(HashMap::new).insert(caps[1].to_owned(), rate);
+                }
+            }
+        }
+        if days.is_empty() {
+            return Err(ActionError::Failed(lformat!("no exchange rates found")).into());
+        }
+        Ok(Rates { days })
+    }
+
+    /// The most recent day with rates.
+    pub fn latest(&self) -> Option<NaiveDate> {
+        self.days.keys().next_back().copied()
+    }
+
+    /// Units of `currency` one euro was worth on `date`, or on the last day before it with rates.
+    pub fn rate(&self, currency: &str, date: NaiveDate) -> Option<f64> {
+        if currency.eq_ignore_ascii_case(HOME) {
+            return Some(1.0);
+        }
+        let currency = currency.to_uppercase();
+        self.days.range(..=date)
+            .rev()
+            .find_map(|(_, rates)| rates.get(&currency).copied())
+    }
+
+    /// `amount` in `currency` converted to euro, by the rate of `date`.
+    pub fn to_home(&self, amount: Currency, currency: &str, date: NaiveDate) -> Result<Currency, AsciiiError> {
+        let rate = self.rate(currency, date)
+            .ok_or_else(|| ActionError::Failed(lformat!("no exchange rate for {} on {}", currency, date)))?;
+        Ok(util::to_currency(amount.as_float() / rate))
+    }
+
+    /// Rates from the cache, downloaded first if it is older than a day.
+    ///
+    /// If downloading fails an outdated cache is still used.
+    pub fn cached() -> Result<Rates, AsciiiError> {
+        let cache = cache_file();
+        let fresh = fs::metadata(&cache)
+            .and_then(|meta| meta.modified())
+            .ok()
+            .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+            .is_some_and(|age| age < MAX_AGE);
+
+        if !fresh && crate::CONFIG.get_bool("exchange/fetch") {
+            let url = crate::CONFIG.get_str("exchange/url");
+            match download(url, &cache) {
+                Ok(()) => log::debug!("downloaded exchange rates from {}", url),
+                Err(e) if cache.exists() => log::warn!("{}", lformat!("using outdated exchange rates, {}", e)),
+                Err(e) => return Err(e),
+            }
+        }
+
+        let xml = fs::read_to_string(&cache)
+            .map_err(io_context(lformat!("can't read exchange rates from {}", cache.display())))?;
+        Rates::parse(&xml)
+    }
+}
+
+/// Where the rates are kept, `exchange/cache`.
+pub fn cache_file() -> PathBuf {
+    util::paths::expand_tilde(Path::new(crate::CONFIG.get_str("exchange/cache")))
+}
+
+fn download(url: &str, target: &Path) -> Result<(), AsciiiError> {
+    if let Some(dir) = target.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let output = Request::new(lformat!("curl is needed to download exchange rates"))
+        .args(["--fail", "--location", url])
+        .idempotent()
+        .output()?;
+    if !output.status.success() {
+        return Err(ActionError::Failed(lformat!("downloading exchange rates from {} failed: {}",
+                                                url, String::from_utf8_lossy(&output.stderr).trim())).into());
+    }
+    // only keep what can be read
+    Rates::parse(&String::from_utf8_lossy(&output.stdout))?;
+    util::atomic_write(target, &output.stdout)?;
+    Ok(())
+}
+
+/// Loads the rates once they are needed, so projects in euro never touch the network.
+#[derive(Debug, Default)]
+pub struct Exchange {
+    rates: Option<Rates>,
+}
+
+impl Exchange {
+    pub fn new() -> Exchange {
+        Exchange::default()
+    }
+
+    /// Uses `rates` instead of `Rates::cached()`.
+    pub fn with_rates(rates: Rates) -> Exchange {
+        Exchange { rates: Some(rates) }
+    }
+
+    /// `amount` in euro, `currency` is the project's currency if it has one.
+    pub fn to_home(&mut self, amount: Currency, currency: Option<&str>, date: NaiveDate) -> Result<Currency, AsciiiError> {
+        match currency {
+            Some(currency) if !currency.eq_ignore_ascii_case(HOME) => {
+                if self.rates.is_none() {
+                    self.rates = Some(Rates::cached()?);
+                }
+                self.rates.as_ref().unwrap().to_home(amount, currency, date)
+            }
+            _ => Ok(amount),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<gesmes:Envelope xmlns:gesmes="http://www.gesmes.org/xml/2002-08-01" xmlns="http://www.ecb.int/vocabulary/2002-08-01/eurofxref">
+  <Cube>
+    <Cube time='2021-05-04'>
+      <Cube currency='USD' rate='1.2000'/>
+      <Cube currency='CHF' rate='1.1000'/>
+    </Cube>
+    <Cube time='2021-04-30'>
+      <Cube currency='USD' rate='1.2500'/>
+    </Cube>
+  </Cube>
+</gesmes:Envelope>"#;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2021, 5, day).unwrap()
+    }
+
+    #[test]
+    fn rates_of_the_last_day_before() {
+        let rates = Rates::parse(XML).unwrap();
+        assert_eq!(rates.latest(), Some(date(4)));
+        assert_eq!(rates.rate("USD", date(4)), Some(1.2));
+        assert_eq!(rates.rate("usd", date(3)), Some(1.25));
+        assert_eq!(rates.rate("CHF", date(3)), None);
+        assert_eq!(rates.rate("EUR", date(1)), Some(1.0));
+        assert!(Rates::parse("<html/>").is_err());
+    }
+
+    #[test]
+    fn converts_to_euro() {
+        let mut exchange = Exchange::with_rates(Rates::parse(XML).unwrap());
+        let dollars = util::to_currency(120.0);
+        assert_eq!(exchange.to_home(dollars, Some("USD"), date(5)).unwrap(), util::to_currency(100.0));
+        assert_eq!(exchange.to_home(dollars, None, date(5)).unwrap(), dollars);
+        assert!(exchange.to_home(dollars, Some("GBP"), date(5)).is_err());
+    }
+}
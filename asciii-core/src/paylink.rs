@@ -0,0 +1,347 @@
+//! Payment links for invoices, see `asciii paylink`.
+//!
+//! A link is created with the provider in `paylink/provider` (`stripe` or `paypal`) over the invoice total
+//! and kept in the project as `invoice/paylink`, next to `invoice/paylink_id` (`stripe:plink_…` or `paypal:<order>`).
+//! Documents and exports find it as `invoice.paylink`.
+//! Polling asks the provider whether the link was payed, PayPal orders that were approved are captured then.
+//!
+//! Requests go through `curl`, see `util::http`. The Stripe secret key is read from `ASCIII_STRIPE_KEY`,
+//! the PayPal secret from `ASCIII_PAYPAL_SECRET` with `paylink/paypal/client_id`.
+//! Neither they nor the PayPal token go on curl's command line, they are handed over as `Secret`s.
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use serde_json::{json, Value};
+
+use crate::error::{ActionError, AsciiiError};
+use crate::project::spec::{Invoicable, IsProject, Redeemable};
+use crate::project::Project;
+use crate::storage::Storable;
+use crate::util::http::{Request, Secret};
+
+/// Who handles the payment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Provider {
+    Stripe,
+    PayPal,
+}
+
+impl Provider {
+    pub const ALL: [Provider; 2] = [Provider::Stripe, Provider::PayPal];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Provider::Stripe => "stripe",
+            Provider::PayPal => "paypal",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Result<Provider, AsciiiError> {
+        Provider::ALL.iter()
+            .copied()
+            .find(|provider| provider.name().eq_ignore_ascii_case(name.trim()))
+            .ok_or_else(|| ActionError::Failed(lformat!("unknown payment provider {:?}", name)).into())
+    }
+
+    /// `paylink/provider`
+    pub fn configured() -> Result<Provider, AsciiiError> {
+        Provider::from_name(crate::CONFIG.get_str_or("paylink/provider").unwrap_or("stripe"))
+    }
+}
+
+/// A created payment link.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Link {
+    pub provider: Provider,
+    /// the provider's id, of the payment link or the order
+    pub id: String,
+    pub url: String,
+}
+
+impl Link {
+    /// Value of `invoice/paylink_id`.
+    pub fn field_id(&self) -> String {
+        format!("{}:{}", self.provider.name(), self.id)
+    }
+
+    /// The link stored in `project`, if it has one.
+    pub fn of(project: &Project) -> Option<Link> {
+        let id = project.field("invoice/paylink_id")?;
+        let (provider, id) = id.split_once(':')?;
+        Some(Link {
+            provider: Provider::from_name(provider).ok()?,
+            id: id.to_owned(),
+            url: project.invoice().paylink().ok()?.to_owned(),
+        })
+    }
+}
+
+/// What is asked for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Charge {
+    /// in cents, tax included
+    pub amount: i64,
+    /// ISO code, upper case
+    pub currency: String,
+    pub description: String,
+    /// official or long invoice number, sent along as reference
+    pub reference: String,
+}
+
+impl Charge {
+    /// The invoice total of `project`.
+    pub fn for_project(project: &Project) -> Result<Charge, AsciiiError> {
+        let invoice = project.invoice();
+        let reference = invoice.official().ok()
+            .or_else(|| invoice.number_long_str())
+            .or_else(|| invoice.number_str())
+            .ok_or_else(|| ActionError::Failed(lformat!("{} has no invoice number yet", project.short_desc())))?;
+        let (_, invoice) = project.bills()?;
+        // `net_total()` is what the bill crate calls the sum with tax
+        let amount = invoice.net_total().value();
+        if amount <= 0 {
+            return Err(ActionError::Failed(lformat!("there is nothing to pay in {}", project.short_desc())).into());
+        }
+        Ok(Charge {
+            amount,
+            currency: project.currency_unit().code,
+            description: format!("{} {}", reference, project.name().unwrap_or("")).trim().to_owned(),
+            reference,
+        })
+    }
+
+    /// `12.30`, as PayPal wants it
+    fn decimal(&self) -> String {
+        format!("{}.{:02}", self.amount / 100, self.amount % 100)
+    }
+}
+
+/// Whether a link was payed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Open,
+    Payed(NaiveDate),
+}
+
+/// Runs `curl` with `auth` and `args` and reads the answer as JSON, errors of the provider become `ActionError`s.
+///
+/// This one changes something at the provider, it is only repeated if it never got there.
+fn request(auth: &Secret, args: &[&str]) -> Result<Value, AsciiiError> {
+    answer(args, Request::new(lformat!("curl is needed for payment links")).secret(auth.clone()).args(args))
+}
+
+/// Like `request()` for requests that only ask, they are repeated after timeouts and server errors, too.
+fn query(auth: &Secret, args: &[&str]) -> Result<Value, AsciiiError> {
+    answer(args, Request::new(lformat!("curl is needed for payment links")).secret(auth.clone()).args(args).idempotent())
+}
+
+fn answer(args: &[&str], request: Request) -> Result<Value, AsciiiError> {
+    log::debug!("paylink: curl {}", args.join(" "));
+    let output = request.output()?;
+    if !output.status.success() {
+        return Err(ActionError::Failed(String::from_utf8_lossy(&output.stderr).trim().to_owned()).into());
+    }
+    let value: Value = serde_json::from_slice(&output.stdout)?;
+    match error_message(&value) {
+        Some(message) => Err(ActionError::Failed(lformat!("payment provider says: {}", message)).into()),
+        None => Ok(value),
+    }
+}
+
+/// The error in an answer of Stripe or PayPal.
+fn error_message(value: &Value) -> Option<String> {
+    value["error"]["message"].as_str()
+        .or_else(|| value["error_description"].as_str())
+        .or_else(|| value["message"].as_str().filter(|_| value["name"].is_string()))
+        .map(ToOwned::to_owned)
+}
+
+fn config_str(key: &str, default: &str) -> String {
+    crate::CONFIG.get_str_or(key)
+        .filter(|value| !value.is_empty())
+        .unwrap_or(default)
+        .trim_end_matches('/')
+        .to_owned()
+}
+
+fn secret(var: &str) -> Result<String, AsciiiError> {
+    std::env::var(var)
+        .ok()
+        .filter(|secret| !secret.is_empty())
+        .ok_or_else(|| ActionError::Failed(lformat!("please set {}", var)).into())
+}
+
+fn stripe_auth() -> Result<Secret, AsciiiError> {
+    Ok(Secret::basic_auth(&secret("ASCIII_STRIPE_KEY")?, ""))
+}
+
+fn stripe_api() -> String {
+    config_str("paylink/stripe/api", "https://api.stripe.com")
+}
+
+fn paypal_api() -> String {
+    config_str("paylink/paypal/api", "https://api-m.paypal.com")
+}
+
+fn paypal_token() -> Result<Secret, AsciiiError> {
+    let auth = Secret::basic_auth(&config_str("paylink/paypal/client_id", ""), &secret("ASCIII_PAYPAL_SECRET")?);
+    let answer = query(&auth, &["--data", "grant_type=client_credentials",
+                                &format!("{}/v1/oauth2/token", paypal_api())])?;
+    answer["access_token"].as_str()
+        .map(|token| Secret::Header(format!("Authorization: Bearer {}", token)))
+        .ok_or_else(|| ActionError::Failed(lformat!("PayPal did not hand out a token")).into())
+}
+
+/// The `id` and `url` of a created Stripe payment link.
+fn stripe_link(answer: &Value) -> Option<Link> {
+    Some(Link {
+        provider: Provider::Stripe,
+        id: answer["id"].as_str()?.to_owned(),
+        url: answer["url"].as_str()?.to_owned(),
+    })
+}
+
+/// The day the first paid checkout session of a Stripe payment link was created.
+fn stripe_payed(sessions: &Value) -> Option<NaiveDate> {
+    sessions["data"].as_array()?
+        .iter()
+        .filter(|session| session["payment_status"] == "paid")
+        .filter_map(|session| NaiveDateTime::from_timestamp_opt(session["created"].as_i64()?, 0))
+        .map(|created| created.date())
+        .min()
+}
+
+/// The order body PayPal expects.
+fn paypal_order(charge: &Charge) -> Value {
+    json!({
+        "intent": "CAPTURE",
+        "purchase_units": [{
+            "reference_id": charge.reference,
+            "invoice_id": charge.reference,
+            "description": charge.description,
+            "amount": { "currency_code": charge.currency, "value": charge.decimal() },
+        }],
+    })
+}
+
+/// The `id` and approval url of a created PayPal order.
+fn paypal_link(order: &Value) -> Option<Link> {
+    let url = order["links"].as_array()?
+        .iter()
+        .find(|link| link["rel"] == "approve" || link["rel"] == "payer-action")?["href"]
+        .as_str()?;
+    Some(Link { provider: Provider::PayPal, id: order["id"].as_str()?.to_owned(), url: url.to_owned() })
+}
+
+/// The day a completed PayPal order was captured.
+fn paypal_payed(order: &Value) -> Option<NaiveDate> {
+    if order["status"] != "COMPLETED" {
+        return None;
+    }
+    let capture = &order["purchase_units"][0]["payments"]["captures"][0];
+    let time = capture["create_time"].as_str().or_else(|| order["update_time"].as_str())?;
+    DateTime::parse_from_rfc3339(time).ok().map(|time| time.with_timezone(&Utc).naive_utc().date())
+}
+
+/// Creates a payment link over `charge`.
+pub fn create(provider: Provider, charge: &Charge) -> Result<Link, AsciiiError> {
+    let broken = || ActionError::Failed(lformat!("{} answered with something unexpected", provider.name()));
+    match provider {
+        Provider::Stripe => {
+            let auth = stripe_auth()?;
+            let price = request(&auth, &["--data-urlencode", &format!("currency={}", charge.currency.to_lowercase()),
+                                         "--data-urlencode", &format!("unit_amount={}", charge.amount),
+                                         "--data-urlencode", &format!("product_data[name]={}", charge.description),
+                                         &format!("{}/v1/prices", stripe_api())])?;
+            let price = price["id"].as_str().ok_or_else(broken)?;
+            let link = request(&auth, &["--data-urlencode", &format!("line_items[0][price]={}", price),
+                                        "--data-urlencode", "line_items[0][quantity]=1",
+                                        "--data-urlencode", &format!("metadata[invoice]={}", charge.reference),
+                                        &format!("{}/v1/payment_links", stripe_api())])?;
+            stripe_link(&link).ok_or_else(|| broken().into())
+        }
+        Provider::PayPal => {
+            let token = paypal_token()?;
+            let order = request(&token, &["--header", "Content-Type: application/json",
+                                          "--data", &paypal_order(charge).to_string(),
+                                          &format!("{}/v2/checkout/orders", paypal_api())])?;
+            paypal_link(&order).ok_or_else(|| broken().into())
+        }
+    }
+}
+
+/// Asks the provider whether `link` was payed.
+pub fn status(link: &Link) -> Result<Status, AsciiiError> {
+    let payed = match link.provider {
+        Provider::Stripe => {
+            let sessions = query(&stripe_auth()?, &["--get",
+                                                    "--data-urlencode", &format!("payment_link={}", link.id),
+                                                    &format!("{}/v1/checkout/sessions", stripe_api())])?;
+            stripe_payed(&sessions)
+        }
+        Provider::PayPal => {
+            let token = paypal_token()?;
+            let url = format!("{}/v2/checkout/orders/{}", paypal_api(), link.id);
+            let mut order = query(&token, &[&url])?;
+            if order["status"] == "APPROVED" {
+                log::info!("{}", lformat!("capturing PayPal order {}", link.id));
+                order = request(&token, &["--header", "Content-Type: application/json",
+                                          "--data", "{}",
+                                          &format!("{}/capture", url)])?;
+            }
+            paypal_payed(&order)
+        }
+    };
+    Ok(payed.map_or(Status::Open, Status::Payed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn charges_are_the_invoice_total() {
+        let project = Project::from_file_content("event:\n  name: Party\ntax: 0.19\nhours:\n  salary: 8.0\ninvoice:\n  number: 7\n  date: 12.05.2024\n\
+                                                  products:\n  Kaffee: { amount: 2, price: 10 }\n").unwrap();
+        let charge = Charge::for_project(&project).unwrap();
+        assert_eq!(charge.amount, 2380);
+        assert_eq!(charge.currency, "EUR");
+        assert_eq!(charge.decimal(), "23.80");
+        assert!(charge.description.ends_with("Party"));
+
+        let order = paypal_order(&charge);
+        assert_eq!(order["purchase_units"][0]["amount"]["value"], "23.80");
+
+        let dollars = Project::from_file_content("event:\n  name: Party\ncurrency: usd\nhours:\n  salary: 8.0\ninvoice:\n  number: 8\n  date: 12.05.2024\n\
+                                                  products:\n  Kaffee: { amount: 2, price: 10 }\n").unwrap();
+        assert_eq!(Charge::for_project(&dollars).unwrap().currency, "USD");
+        assert_eq!(dollars.currency_unit().symbol, "$");
+
+        let unbilled = Project::from_file_content("event:\n  name: Party\n").unwrap();
+        assert!(Charge::for_project(&unbilled).is_err());
+    }
+
+    #[test]
+    fn answers_are_read() {
+        let link = stripe_link(&json!({"id": "plink_1", "url": "https://buy.stripe.com/test_1"})).unwrap();
+        assert_eq!(link.field_id(), "stripe:plink_1");
+
+        let sessions = json!({"data": [{"payment_status": "unpaid", "created": 1715000000},
+                                       {"payment_status": "paid", "created": 1715515200}]});
+        assert_eq!(stripe_payed(&sessions), NaiveDate::from_ymd_opt(2024, 5, 12));
+        assert_eq!(stripe_payed(&json!({"data": []})), None);
+
+        let order = json!({"id": "5O1", "status": "CREATED",
+                           "links": [{"rel": "self", "href": "https://api/5O1"},
+                                     {"rel": "approve", "href": "https://www.paypal.com/checkoutnow?token=5O1"}]});
+        assert_eq!(paypal_link(&order).unwrap().url, "https://www.paypal.com/checkoutnow?token=5O1");
+        assert_eq!(paypal_payed(&order), None);
+
+        let captured = json!({"status": "COMPLETED",
+                              "purchase_units": [{"payments": {"captures": [{"create_time": "2024-05-13T09:00:00Z"}]}}]});
+        assert_eq!(paypal_payed(&captured), NaiveDate::from_ymd_opt(2024, 5, 13));
+
+        assert_eq!(error_message(&json!({"error": {"message": "No such price"}})).as_deref(), Some("No such price"));
+        assert_eq!(error_message(&json!({"name": "INVALID_REQUEST", "message": "broken"})).as_deref(), Some("broken"));
+        assert_eq!(error_message(&order), None);
+    }
+}
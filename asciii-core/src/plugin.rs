@@ -0,0 +1,184 @@
+//! External subcommands, like `git` and `cargo` have them.
+//!
+//! `asciii foo party -- --verbose` runs the executable `asciii-foo` from the `PATH` with the arguments `--verbose`.
+//! The projects selected by the search terms before `--` (the whole working directory if there are none)
+//! are written to its stdin as one JSON object, an `Invocation`.
+//!
+//! Plugins written in Rust can read it with `Invocation::from_stdin()`:
+//!
+//! ```no_run
+//! use asciii_core::plugin::Invocation;
+//!
+//! let invocation = Invocation::from_stdin().unwrap();
+//! for selected in &invocation.projects {
+//!     println!("{} {:?}", selected.file.display(), selected.project.invoice.gross_total);
+//! }
+//! ```
+
+use std::env;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus, Stdio};
+
+use crate::error::AsciiiError;
+use crate::project::export::{Complete, ExportTarget};
+use crate::project::Project;
+use crate::storage::Storable;
+
+/// Version of the JSON handed to plugins, increased on incompatible changes.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// Prefix of plugin executables.
+pub const PREFIX: &str = "asciii-";
+
+/// What a plugin gets on stdin.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Invocation {
+    pub version: u32,
+    /// the subcommand, `foo` for `asciii-foo`
+    pub command: String,
+    /// search terms the projects were selected by
+    pub search_terms: Vec<String>,
+    /// root of the storage, if there is one
+    pub storage: Option<PathBuf>,
+    pub projects: Vec<Selected>,
+}
+
+/// One selected project.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Selected {
+    /// the project file
+    pub file: PathBuf,
+    /// everything about the project, like `asciii show --json`
+    pub project: Complete,
+}
+
+impl Selected {
+    pub fn new(project: &Project) -> Selected {
+        Selected {
+            file: project.file(),
+            project: project.export(),
+        }
+    }
+}
+
+impl Invocation {
+    pub fn new(command: &str, search_terms: &[String], storage: Option<&Path>, projects: &[Project]) -> Invocation {
+        Invocation {
+            version: PROTOCOL_VERSION,
+            command: command.to_owned(),
+            search_terms: search_terms.to_vec(),
+            storage: storage.map(ToOwned::to_owned),
+            projects: projects.iter().map(Selected::new).collect(),
+        }
+    }
+
+    /// Reads what asciii hands to a plugin.
+    pub fn from_reader<R: Read>(reader: R) -> Result<Invocation, AsciiiError> {
+        Ok(serde_json::from_reader(reader)?)
+    }
+
+    /// Reads what asciii hands to a plugin from stdin, this is what plugins call first.
+    pub fn from_stdin() -> Result<Invocation, AsciiiError> {
+        Invocation::from_reader(io::stdin().lock())
+    }
+}
+
+fn executable_name(command: &str) -> String {
+    format!("{}{}{}", PREFIX, command, env::consts::EXE_SUFFIX)
+}
+
+/// Looks for the plugin `command` in `dirs`.
+pub fn find_in<I: IntoIterator<Item = PathBuf>>(command: &str, dirs: I) -> Option<PathBuf> {
+    let name = executable_name(command);
+    dirs.into_iter()
+        .map(|dir| dir.join(&name))
+        .find(|path| path.is_file())
+}
+
+/// Looks for the plugin `command` on the `PATH`.
+pub fn find(command: &str) -> Option<PathBuf> {
+    find_in(command, path_dirs())
+}
+
+/// Names of all plugins on the `PATH`, sorted and without duplicates.
+pub fn list() -> Vec<String> {
+    let mut names = path_dirs()
+        .filter_map(|dir| dir.read_dir().ok())
+        .flat_map(|entries| entries.filter_map(Result::ok))
+        .filter(|entry| entry.path().is_file())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter_map(|name| name.strip_prefix(PREFIX)
+                               .map(|command| command.trim_end_matches(env::consts::EXE_SUFFIX).to_owned()))
+        .filter(|command| !command.is_empty())
+        .collect::<Vec<_>>();
+    names.sort();
+    names.dedup();
+    names
+}
+
+fn path_dirs() -> impl Iterator<Item = PathBuf> {
+    env::split_paths(&env::var_os("PATH").unwrap_or_default()).collect::<Vec<_>>().into_iter()
+}
+
+/// Runs the plugin at `executable` with `args` and writes `invocation` to its stdin.
+///
+/// Waits for the plugin to finish, its stdout and stderr are those of asciii.
+pub fn run(executable: &Path, args: &[String], invocation: &Invocation) -> Result<ExitStatus, AsciiiError> {
+    log::debug!("running plugin {} {:?}", executable.display(), args);
+    let mut child = Command::new(executable)
+        .args(args)
+        .stdin(Stdio::piped())
+        .spawn()?;
+
+    let input = serde_json::to_vec(invocation)?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // plugins that don't care about the selection may exit without reading it
+        match stdin.write_all(&input) {
+            Err(ref e) if e.kind() == io::ErrorKind::BrokenPipe => {},
+            result => result?,
+        }
+    }
+    Ok(child.wait()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempdir::TempDir;
+
+    #[test]
+    fn invocations_round_trip() {
+        let project = Project::open("../tests/test_projects/current.yml").unwrap();
+        let invocation = Invocation::new("foo", &["party".into()], None, &[project]);
+        let json = serde_json::to_string(&invocation).unwrap();
+        let parsed = Invocation::from_reader(json.as_bytes()).unwrap();
+        assert_eq!(parsed.version, PROTOCOL_VERSION);
+        assert_eq!(parsed.search_terms, vec!["party".to_owned()]);
+        assert_eq!(parsed.projects[0].project.client.last_name.as_deref(), Some("Zahl"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn runs_plugins_with_the_selection_on_stdin() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = TempDir::new("asciii-plugin").unwrap();
+        let output = dir.path().join("output");
+        let plugin = dir.path().join("asciii-foo");
+        fs::write(&plugin, format!("#!/bin/sh\necho \"$1\" > {0}\ncat >> {0}\n", output.display())).unwrap();
+        fs::set_permissions(&plugin, fs::Permissions::from_mode(0o755)).unwrap();
+
+        assert_eq!(find_in("foo", vec![dir.path().to_owned()]), Some(plugin.clone()));
+        assert_eq!(find_in("bar", vec![dir.path().to_owned()]), None);
+
+        let invocation = Invocation::new("foo", &[], Some(dir.path()), &[]);
+        assert!(run(&plugin, &["--verbose".into()], &invocation).unwrap().success());
+
+        let written = fs::read_to_string(&output).unwrap();
+        let (arg, json) = written.split_once('\n').unwrap();
+        assert_eq!(arg, "--verbose");
+        assert_eq!(Invocation::from_reader(json.as_bytes()).unwrap().storage.as_deref(), Some(dir.path()));
+    }
+}
@@ -1,4 +1,9 @@
 
+use std::sync::{Arc, RwLock};
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
 use crate::storage::{self, Storable};
 use crate::util;
 
@@ -124,3 +129,107 @@ impl ComputedField {
         }
     }
 }
+
+/// Computes a custom field, see `register()`.
+pub type FieldFn = dyn Fn(&Project) -> Option<String> + Send + Sync;
+
+lazy_static! {
+    static ref REGISTERED: RwLock<Vec<(String, Arc<FieldFn>)>> = RwLock::new(Vec::new());
+    static ref PLACEHOLDER: Regex = Regex::new(r"\{([^{}]+)\}").unwrap();
+}
+
+/// Adds a computed field, which is then available to `Project::field()`,
+/// and thereby to `--details`, `--sort` and the exports.
+///
+/// A field registered under the same name before is replaced.
+/// Built-in fields can't be replaced, they are looked up first.
+pub fn register<F>(name: &str, compute: F)
+    where F: Fn(&Project) -> Option<String> + Send + Sync + 'static
+{
+    let mut registered = REGISTERED.write().unwrap();
+    registered.retain(|(known, _)| known != name);
+    registered.push((name.to_owned(), Arc::new(compute)));
+}
+
+/// Removes a field added with `register()`, returns whether there was one.
+pub fn unregister(name: &str) -> bool {
+    let mut registered = REGISTERED.write().unwrap();
+    let before = registered.len();
+    registered.retain(|(known, _)| known != name);
+    registered.len() != before
+}
+
+/// Names of the registered fields followed by those in `computed_fields` of the config.
+pub fn custom_field_names() -> Vec<String> {
+    let mut names = REGISTERED.read().unwrap()
+        .iter()
+        .map(|(name, _)| name.clone())
+        .collect::<Vec<_>>();
+    for name in configured_fields().into_iter().map(|(name, _)| name) {
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    names
+}
+
+/// Value of a field added with `register()` or configured in `computed_fields`.
+///
+/// Returns `None` if there is no such field.
+pub fn get_custom(name: &str, project: &Project) -> Option<String> {
+    registered(name)
+        .and_then(|compute| compute(project))
+        .or_else(|| configured(name).and_then(|template| fill_configured(&template, project)))
+}
+
+fn registered(name: &str) -> Option<Arc<FieldFn>> {
+    REGISTERED.read().unwrap()
+        .iter()
+        .find(|(known, _)| known == name)
+        .map(|(_, compute)| Arc::clone(compute))
+}
+
+fn configured_fields() -> Vec<(String, String)> {
+    crate::CONFIG.get("computed_fields")
+        .and_then(|fields| fields.as_hash())
+        .map(|fields| fields.iter()
+             .filter_map(|(name, template)| Some((name.as_str()?.to_owned(), template.as_str()?.to_owned())))
+             .collect())
+        .unwrap_or_default()
+}
+
+fn configured(name: &str) -> Option<String> {
+    configured_fields().into_iter()
+        .find(|(known, _)| known == name)
+        .map(|(_, template)| template)
+}
+
+/// Replaces every `{path}` in `template` with `Project::field(path)`.
+///
+/// Configured fields can't refer to each other, so there are no cycles.
+/// If any of the fields is missing, so is the result.
+fn fill_configured(template: &str, project: &Project) -> Option<String> {
+    fill_placeholders(template, |path| {
+        if configured(path).is_some() && registered(path).is_none() {
+            None
+        } else {
+            project.field(path)
+        }
+    })
+}
+
+/// Replaces every `{name}` in `template` with `value(name)`, nothing if any of them is missing.
+pub(crate) fn fill_placeholders<F>(template: &str, value: F) -> Option<String>
+    where F: Fn(&str) -> Option<String>
+{
+    let mut filled = String::with_capacity(template.len());
+    let mut last = 0;
+    for placeholder in PLACEHOLDER.captures_iter(template) {
+        let whole = placeholder.get(0)?;
+        filled.push_str(&template[last..whole.start()]);
+        filled.push_str(&value(&placeholder[1])?);
+        last = whole.end();
+    }
+    filled.push_str(&template[last..]);
+    Some(filled)
+}
@@ -0,0 +1,170 @@
+#![allow(missing_docs)]
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use thiserror::Error;
+
+use super::yaml_provider::error::{FieldResult, FieldError};
+
+#[derive(Error, Debug)]
+pub enum ProjectError {
+
+    #[error("This feature is not enabled in this build")]
+    FeatureDeactivated,
+
+    #[error("Cannot determine target file name")]
+    CantDetermineTargetFile,
+
+    #[error("{:?} is not a field that can be set", _0)]
+    UnknownField(String),
+
+    #[error("Invalid value for {:?}: {}", _0, _1)]
+    InvalidValue(String, String),
+
+    #[error("Cannot set {:?} in this project file", _0)]
+    CantSetField(String),
+
+    #[error("Cannot merge the patch into this project file")]
+    CantMerge,
+
+    /// the first section whose data would change
+    #[error("Formatting would change {:?} in this project file, it is left as it is", _0)]
+    CantFormat(String),
+
+    #[error("Variable {:?} of the template is broken: {}", _0, _1)]
+    TemplateVariable(String, String),
+}
+
+
+/// Result of validating part of a project.
+///
+/// We have to differentiate between incomplete data (missing values) and wrong data (invalid values).
+/// Wrong data should always be a hard error - there is no reason to have invalid values in project files.
+///
+/// Missing data is not an error, it simply means that some information is not available yet.
+/// An example is the field for the payment date: this field is missing until the invoice has been paid,
+/// since the date is unknown until that point.
+#[derive(Eq, PartialEq, Debug, Default)]
+pub struct ValidationResult {
+    /// hard error messages (invalid data)
+    pub validation_errors: Vec<String>,
+
+    /// soft error messages (incomplete data)
+    pub missing_fields: Vec<String>,
+
+    /// names and messages of invalid fields, the same as `validation_errors`
+    pub invalid_fields: Vec<(String, String)>,
+}
+
+impl ValidationResult {
+    pub fn new() -> Self {
+        ValidationResult {
+            validation_errors: Vec::new(),
+            missing_fields: Vec::new(),
+            invalid_fields: Vec::new(),
+        }
+    }
+
+    pub fn is_ok(&self) -> bool {
+        self.validation_errors.is_empty() && self.missing_fields.is_empty()
+    }
+
+    fn invalid(&mut self, name: &str, msg: String) {
+        self.validation_errors.push(lformat!("{:?} is invalid: {}", name, msg));
+        self.invalid_fields.push((name.to_string(), msg));
+    }
+
+    pub fn validate_field<T>(&mut self, name: &str, val: FieldResult<T>) {
+        if let Err(FieldError::Invalid(msg)) = val {
+            self.invalid(name, msg);
+        }
+    }
+
+    pub fn require_option<T>(&mut self, name: &str, val: Option<T>) {
+        if val.is_none() {
+            self.missing_fields.push(name.to_string())
+        }
+    }
+
+    pub fn require_field<T>(&mut self, name: &str, val: FieldResult<T>) {
+        if val.is_err() {
+            self.missing_fields.push(name.to_string())
+        }
+
+        if let Err(FieldError::Invalid(msg)) = val {
+            self.invalid(name, msg);
+        }
+    }
+
+    pub fn and(mut self, next: ValidationResult) -> ValidationResult {
+        self.missing_fields.extend(next.missing_fields);
+        self.validation_errors.extend(next.validation_errors);
+        self.invalid_fields.extend(next.invalid_fields);
+        self
+    }
+
+    /// Invalid fields become errors, missing fields are reported with `missing` severity.
+    pub fn diagnostics(&self, file: &Path, stage: &str, missing: Severity) -> Vec<Diagnostic> {
+        let invalid = self.invalid_fields.iter()
+            .map(|(field, msg)| Diagnostic {
+                file: file.to_owned(),
+                field: field.clone(),
+                stage: stage.to_owned(),
+                severity: Severity::Error,
+                message: msg.clone(),
+            });
+        let missing = self.missing_fields.iter()
+            .map(|field| Diagnostic {
+                file: file.to_owned(),
+                field: field.clone(),
+                stage: stage.to_owned(),
+                severity: missing,
+                message: lformat!("missing for {}", stage),
+            });
+        invalid.chain(missing).collect()
+    }
+}
+
+/// How bad a `Diagnostic` is.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
+#[cfg_attr(feature = "serialization", derive(Serialize))]
+#[cfg_attr(feature = "serialization", serde(rename_all = "lowercase"))]
+pub enum Severity {
+    /// invalid data
+    Error,
+    /// data needed for the next document is missing
+    Warning,
+    /// data needed for archiving is missing
+    Info,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Severity::Error   => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Info    => write!(f, "info"),
+        }
+    }
+}
+
+/// A single finding of `asciii validate`, meant to be read by editors and CI.
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize))]
+pub struct Diagnostic {
+    pub file: PathBuf,
+    pub field: String,
+    /// offer, invoice, archive or file
+    pub stage: String,
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}: {}: {}", self.file.display(), self.severity, self.field, self.message)
+    }
+
+}
+
@@ -0,0 +1,406 @@
+//! Flat, owned views of a project, as handed to templates and printed by `--json`.
+//!
+//! With the `serialization` feature these can be parsed back from asciii's JSON output.
+
+use std::collections::BTreeMap;
+
+use bill::{Bill, ItemList, Tax};
+use crate::util::currency::CurrencyUnit;
+
+use crate::storage::storable::Storable;
+use crate::project::Project;
+use super::spec::*;
+use super::computed_field::{self, ComputedField};
+use super::validate;
+
+pub trait ExportTarget<T> {
+    fn export(&self) -> T;
+}
+
+fn opt_str(opt: Option<&str>) -> Option<String> {
+    opt.map(ToOwned::to_owned)
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct Client {
+    pub title: Option<String>,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub full_name: Option<String>,
+    pub address: Option<String>,
+    pub email: Option<String>,
+    pub addressing: Option<String>,
+}
+
+impl ExportTarget<Client> for Project {
+    fn export(&self) -> Client {
+        Client {
+            full_name: self.client().full_name(),
+            addressing: self.client().addressing(),
+            email: opt_str(self.client().email().ok()),
+            last_name: opt_str(self.client().last_name().ok()),
+            first_name: opt_str(self.client().first_name().ok()),
+            title: opt_str(self.client().title().ok()),
+            address: opt_str(self.client().address().ok()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct Event {
+    pub name: Option<String>,
+    pub date: Option<String>,
+    pub manager: Option<String>,
+    /// zone of `times`, missing for floating times
+    #[cfg_attr(feature = "serialization", serde(default))]
+    pub timezone: Option<String>,
+    /// begin and end of every slot, ISO 8601 with the offset if it is known
+    #[cfg_attr(feature = "serialization", serde(default))]
+    pub times: Vec<(String, String)>,
+}
+
+use chrono::prelude::*;
+fn dmy(date: Option<Date<Utc>>) -> Option<String> {
+    date.map(|d| d.format("%d.%m.%Y").to_string())
+}
+
+impl ExportTarget<Event> for Project {
+    fn export(&self) -> Event {
+        Event {
+            name: IsProject::name(self).ok().map(ToOwned::to_owned),
+            date: dmy(self.event_date().ok()),
+            manager: self.responsible().ok().map(ToOwned::to_owned),
+            timezone: self.timezone().ok()
+                          .filter(|zone| *zone != EventZone::Floating)
+                          .map(|zone| zone.to_string()),
+            times: self.events().unwrap_or_default()
+                       .iter()
+                       .flat_map(|event| event.slots()
+                                 .into_iter()
+                                 .map(move |(begin, end)| (event.zone.format(begin), event.zone.format(end))))
+                       .collect(),
+        }
+    }
+}
+
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct Service {
+    pub time: Option<f64>,
+    pub tax: Option<f64>,
+    pub salary: Option<String>,
+    pub gross_total: Option<String>,
+    pub net_total: Option<String>,
+    pub employees: Option<Vec<Employee>>,
+}
+
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+/// TODO: move this type to spec
+pub struct Employee {
+    pub name: String,
+    pub salary: String,
+    pub time: f64,
+    pub wage: String,
+}
+
+fn export_employee(e: &crate::project::spec::Employee) -> Employee {
+    Employee {
+        name: e.name.clone(),
+        time: e.time,
+        salary:  e.salary.postfix().to_string(),
+        wage:  e.wage.postfix().to_string(),
+    }
+}
+
+impl ExportTarget<Service> for Project {
+    fn export(&self) -> Service {
+        Service {
+            time:         self.hours().total_time(),
+            tax:          self.hours().tax().ok().map(|t|t.value()),
+            salary:       self.hours().salary().ok()
+                                      .map(|s| s.postfix().to_string()),
+            gross_total:  self.hours().gross_wages()
+                                      .map(|s| s.postfix().to_string()),
+            net_total:    self.hours().net_wages()
+                                      .map(|s| s.postfix().to_string()),
+            employees:    self.hours().employees().ok()
+                                      .map(|employees|
+                                           employees.iter()
+                                                .map(export_employee)
+                                                .collect()
+                                      )
+        }
+    }
+}
+
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct Sum {
+    pub gross_sum: String,
+    pub has_tax: bool,
+    pub tax_sum: String,
+    pub tax_value: f64,
+}
+
+use super::product::Product;
+fn sums_from_bill(bill: &Bill<Product<'_>>, unit: &CurrencyUnit) -> Vec<Sum> {
+    bill.iter()
+        .map(|(tax, list)| Sum::from_itemlist(*tax, list, unit))
+        .rev()
+        .collect::<Vec<_>>()
+}
+
+impl Sum {
+    pub fn from_itemlist(tax: Tax, list: &ItemList<Product<'_>>, unit: &CurrencyUnit) -> Sum {
+        let gross_sum = list.gross_sum();
+        let tax_sum = list.tax_sum();
+        Sum {
+            tax_value: (tax.into_inner() * 100.0),
+            gross_sum: unit.format(&gross_sum),
+            tax_sum: unit.format(&tax_sum),
+            has_tax: (tax.into_inner() > 0f64),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct Offer {
+    // appendix: Option<i64>,
+    pub date: Option<String>,
+    pub number: Option<String>,
+    /// last day the offer stands
+    #[cfg_attr(feature = "serialization", serde(default))]
+    pub valid_until: Option<String>,
+    /// when and how the client accepted it, and the signed copy
+    #[cfg_attr(feature = "serialization", serde(default))]
+    pub accepted_date: Option<String>,
+    #[cfg_attr(feature = "serialization", serde(default))]
+    pub accepted_via: Option<String>,
+    #[cfg_attr(feature = "serialization", serde(default))]
+    pub signed_copy: Option<String>,
+    pub sums: Vec<Sum>,
+    pub net_total: String,
+    pub gross_total: String,
+}
+
+
+impl ExportTarget<Offer> for Project {
+    fn export(&self) -> Offer {
+        let (offer, _) = self.bills().unwrap();
+        let unit = self.currency_unit();
+        Offer {
+            // appendix: self.offer().appendix(),
+            date: dmy(self.offer().date().ok()),
+            number: self.offer().number().ok(),
+            valid_until: dmy(self.offer().valid_until().ok()),
+            accepted_date: dmy(self.offer().accepted_date().ok()),
+            accepted_via: self.offer().accepted_via().ok().map(ToOwned::to_owned),
+            signed_copy: self.offer().signed_copy().ok().map(ToOwned::to_owned),
+            sums: sums_from_bill(&offer, &unit),
+            net_total: unit.format(&offer.net_total()),
+            gross_total: unit.format(&offer.gross_total()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct Invoice {
+    pub date: Option<String>,
+    pub number: Option<String>,
+    pub number_long: Option<String>,
+    pub official: Option<String>,
+    pub sums: Vec<Sum>,
+    pub net_total: String,
+    pub gross_total: String,
+    #[cfg_attr(feature = "serialization", serde(default))]
+    pub paylink: Option<String>,
+}
+
+
+impl ExportTarget<Invoice> for Project {
+    fn export(&self) -> Invoice {
+        let (_, invoice) = self.bills().unwrap();
+        let unit = self.currency_unit();
+
+        Invoice {
+            date: dmy(self.invoice().date().ok()),
+            number: self.invoice().number_str(),
+            number_long: self.invoice().number_long_str(),
+            official: self.invoice().official().ok(),
+            sums: sums_from_bill(&invoice, &unit),
+            net_total: unit.format(&invoice.net_total()),
+            gross_total: unit.format(&invoice.gross_total()),
+            paylink: self.invoice().paylink().ok().map(ToOwned::to_owned),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct ExportProduct {
+    pub name: String,
+    pub price: String,
+    pub unit: String,
+    pub amount: f64,
+    pub cost: String,
+    pub tax: f64,
+    /// `catalog` or `client`, see `PriceSource`
+    #[cfg_attr(feature = "serialization", serde(default))]
+    pub price_source: String,
+}
+
+fn bill_products(bill: &Bill<Product<'_>>, unit: &CurrencyUnit) -> Vec<ExportProduct> {
+    bill.as_items_with_tax()
+        .into_iter()
+        .map(|(tax, item)| {
+        ExportProduct {
+            name: item.product.name.to_string(),
+            price: unit.format(&item.product.price),
+            unit: item.product.unit.unwrap_or("").to_string(),
+            amount: item.amount,
+            cost: unit.format(&item.gross()),
+            tax: tax.value(),
+            price_source: item.product.source.name().to_owned(),
+        }
+    })
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct Bills {
+    pub offer: Vec<ExportProduct>,
+    pub invoice: Vec<ExportProduct>,
+}
+
+
+impl ExportTarget<Bills> for Project {
+    fn export(&self) -> Bills {
+        let (offer, invoice) = self.bills().unwrap();
+        let unit = self.currency_unit();
+
+        Bills {
+            offer: bill_products(&offer, &unit),
+            invoice: bill_products(&invoice, &unit),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct Complete {
+    pub client: Client,
+    pub event: Event,
+    pub service: Service,
+    pub offer: Offer,
+    pub invoice: Invoice,
+    pub bills: Bills,
+    pub checks: Checks,
+    pub errors: Errors,
+    pub extras: Extras,
+    /// code and symbol of the amounts, e.g. for e-invoices
+    #[cfg_attr(feature = "serialization", serde(default))]
+    pub currency: CurrencyUnit,
+}
+
+
+impl ExportTarget<Complete> for Project {
+    fn export(&self) -> Complete {
+        Complete {
+            client: self.export(),
+            event: self.export(),
+            service: self.export(),
+            offer: self.export(),
+            invoice: self.export(),
+            bills: self.export(),
+            checks: self.export(),
+            errors: self.export(),
+            extras: self.export(),
+            currency: self.currency_unit(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct Checks {
+    pub missing_for_offer: bool,
+    pub missing_for_invoice: bool,
+    pub ready_for_archive: bool,
+    pub payed_by_customer: bool,
+    pub payed_employees: bool,
+    pub canceled: bool,
+    /// the offer was accepted, as `validation/require_acceptance` wants it
+    #[cfg_attr(feature = "serialization", serde(default))]
+    pub offer_accepted: bool,
+}
+
+impl ExportTarget<Checks> for Project {
+    fn export(&self) -> Checks {
+        Checks {
+            missing_for_offer: self.is_missing_for_offer().is_empty(),
+            missing_for_invoice: self.is_missing_for_invoice().is_empty(),
+            ready_for_archive: self.is_ready_for_archive().is_empty(),
+            payed_by_customer: self.is_payed(),
+            payed_employees: self.hours().employees_payed(),
+            canceled: self.canceled(),
+            offer_accepted: validate::acceptance(self).is_ok(),
+            // errors: self.is_missing_for_offer().err().map(|list| list.errors)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct Errors {
+    pub missing_for_offer:   Vec<String>,
+    pub missing_for_invoice: Vec<String>,
+    pub ready_for_archive: Vec<String>,
+}
+
+
+impl ExportTarget<Errors> for Project {
+    fn export(&self) -> Errors {
+        Errors {
+            missing_for_offer:   self.is_missing_for_offer(),
+            missing_for_invoice: self.is_missing_for_invoice(),
+            ready_for_archive: self.is_ready_for_archive(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct Extras {
+    pub dir: Option<String>,
+    pub age: Option<i64>,
+    pub our_bad: Option<i64>,
+    pub their_bad: Option<i64>,
+    pub sort_index: Option<String>,
+    /// custom computed fields, see `computed_field::register()`
+    #[cfg_attr(feature = "serialization", serde(default))]
+    pub custom: BTreeMap<String, String>,
+}
+
+impl ExportTarget<Extras> for Project {
+    fn export(&self) -> Extras {
+        Extras {
+            dir: ComputedField::Dir.get(self),
+            age: self.age(),
+            our_bad: self.our_bad().map(|d| d.num_days()),
+            their_bad: self.their_bad().map(|d| d.num_days()),
+            sort_index: self.index(),
+            custom: computed_field::custom_field_names().into_iter()
+                .filter_map(|name| Some((name.clone(), self.field(&name)?)))
+                .collect(),
+        }
+    }
+}
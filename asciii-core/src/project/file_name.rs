@@ -0,0 +1,89 @@
+//! Names of the generated documents, see `document_export/offer_file_name` and `document_export/invoice_file_name`.
+//!
+//! The templates name fields in braces, e.g. `{invoice_number} {name} {date}`:
+//! `offer_number`, `invoice_number`, `name` (as a slug), `client` and `date`,
+//! the date of the offer or the invoice as `2024-03-10`. Anything else is looked up by `Project::field()`.
+//! The extension is appended, a `.pdf` at the end of the template is left out.
+//! Without one of the fields there is no name, like an invoice without a number.
+
+use crate::storage::SlugStrategy;
+
+use super::{BillType, Project};
+use super::computed_field::fill_placeholders;
+use super::spec::*;
+
+/// Used if `document_export/offer_file_name` is empty.
+pub const OFFER_TEMPLATE: &str = "{offer_number} {name}";
+
+/// Used if `document_export/invoice_file_name` is empty.
+pub const INVOICE_TEMPLATE: &str = "{invoice_number} {name} {date}";
+
+/// The configured template for `bill_type`.
+pub fn template(bill_type: BillType) -> &'static str {
+    let (key, default) = match bill_type {
+        BillType::Offer   => ("document_export/offer_file_name", OFFER_TEMPLATE),
+        BillType::Invoice => ("document_export/invoice_file_name", INVOICE_TEMPLATE),
+    };
+    match crate::CONFIG.get_str(key).trim() {
+        "" => default,
+        template => template,
+    }
+}
+
+/// The value of one field of a template.
+fn value(project: &Project, bill_type: BillType, field: &str) -> Option<String> {
+    let slugs = SlugStrategy::with_config(&crate::CONFIG);
+    let value = match field {
+        "offer_number"   => project.offer().number().ok()?,
+        "invoice_number" => project.invoice().number_str()?,
+        "name"           => slugs.slugify(project.name().ok()?),
+        "client"         => slugs.slugify(&project.client().full_name()?),
+        "date"           => match bill_type {
+            BillType::Offer   => project.offer().date().ok()?,
+            BillType::Invoice => project.invoice().date().ok()?,
+        }.format("%Y-%m-%d").to_string(),
+        _ => project.field(field)?,
+    };
+    // a value is never a directory
+    Some(value.replace(['/', '\\'], "-"))
+}
+
+/// Fills `template` for the `bill_type` document of `project` and appends `extension`.
+pub fn fill(template: &str, project: &Project, bill_type: BillType, extension: &str) -> Option<String> {
+    let document_extension = crate::CONFIG.get_str("document_export/output_extension");
+    let template = template.trim()
+                           .strip_suffix(&format!(".{}", document_extension))
+                           .unwrap_or_else(|| template.trim());
+    let name = fill_placeholders(template, |field| value(project, bill_type, field))?;
+    Some(format!("{}.{}", name, extension))
+}
+
+/// File name of the `bill_type` document of `project`, as configured.
+pub fn file_name(project: &Project, bill_type: BillType, extension: &str) -> Option<String> {
+    fill(template(bill_type), project, bill_type, extension)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROJECT: &str = "event:\n  name: Sommer Fest\n  dates:\n  - begin: 01.07.2024\n\
+                           client:\n  first_name: Graf\n  last_name: Zahl\n\
+                           offer:\n  date: 10.06.2024\n  appendix: 1\n\
+                           invoice:\n  number: 42\n  date: 05.07.2024\n\
+                           manager: Ernie\nhours:\n  salary: 8.0\n";
+
+    #[test]
+    fn templated_file_names() {
+        let project = Project::from_file_content(PROJECT).unwrap();
+        assert_eq!(fill(INVOICE_TEMPLATE, &project, BillType::Invoice, "pdf").unwrap(), "R042 sommer-fest 2024-07-05.pdf");
+        assert_eq!(fill("{invoice_number} {name} {date}.pdf", &project, BillType::Invoice, "tex").unwrap(), "R042 sommer-fest 2024-07-05.tex");
+        assert_eq!(fill("{date} {client} {manager}", &project, BillType::Offer, "pdf").unwrap(), "2024-06-10 graf-zahl Ernie.pdf");
+        assert_eq!(fill("{offer_number}", &project, BillType::Offer, "pdf"), project.offer().number().ok().map(|n| format!("{}.pdf", n)));
+
+        // no number, no name
+        let offer_only = Project::from_file_content(&PROJECT.replace("  number: 42\n", "")).unwrap();
+        assert_eq!(fill(INVOICE_TEMPLATE, &offer_only, BillType::Invoice, "pdf"), None);
+        assert_eq!(fill("{nothing}", &project, BillType::Offer, "pdf"), None);
+    }
+}
@@ -0,0 +1,153 @@
+//! Normalizes the layout of project files, see `asciii fmt`.
+//!
+//! Indentation becomes two spaces per level, the top level sections are put into the order of the
+//! default template and dates are written as `dd.mm.yyyy`. Comments, anchors and the order inside
+//! sections stay as they are. The result is parsed again and has to contain the same data, otherwise
+//! the file is not touched.
+
+use chrono::NaiveDate;
+use yaml_rust::Yaml;
+use yaml_rust::yaml::Hash as YamlHash;
+
+use crate::error::AsciiiError;
+use crate::util::yaml;
+use super::error::ProjectError;
+
+/// Top level sections in the order of the default template.
+pub const SECTION_ORDER: &[&str] = &[
+    "client", "event", "offer", "invoice", "cataloge", "products", "hours",
+    "manager", "canceled", "tax", "lang", "output_dir", "messages", "meta", "created",
+];
+
+/// Keys whose values are dates, wherever they are.
+const DATE_KEYS: &[&str] = &[
+    "date", "begin", "end", "created", "payed_date", "wages_date",
+    "sent_date", "generated_date", "accepted_date", "invoice_date", "valid_until",
+];
+
+/// `2024-03-10` and `10.3.2024` as `10.03.2024`, anything else is not a date this touches.
+fn normalize_date(value: &str) -> Option<String> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d").ok()
+        .or_else(|| {
+            let parts = value.split('.').collect::<Vec<_>>();
+            if parts.len() != 3 || parts[2].len() != 4 || parts.iter().any(|p| p.is_empty() || !p.chars().all(|c| c.is_ascii_digit())) {
+                return None;
+            }
+            NaiveDate::from_ymd_opt(parts[2].parse().ok()?, parts[1].parse().ok()?, parts[0].parse().ok()?)
+        })
+        .map(|date| date.format("%d.%m.%Y").to_string())
+        .filter(|normalized| normalized != value)
+}
+
+/// Rewrites the date in `key: date # comment` or `- key: date`, if it needs to.
+fn normalize_date_line(line: &str) -> Option<String> {
+    let trimmed = line.trim_start();
+    let item = trimmed.strip_prefix("- ").unwrap_or(trimmed).trim_start();
+    let colon = item.find(':')?;
+    if !DATE_KEYS.contains(&item[..colon].trim_end()) {
+        return None;
+    }
+
+    let rest = &item[colon + 1..];
+    let value = rest.split(" #").next().unwrap_or_default().trim();
+    let unquoted = value.trim_matches(|c| c == '"' || c == '\'');
+    let normalized = normalize_date(unquoted)?;
+
+    let start = line.len() - rest.len();
+    Some(format!("{}{}", &line[..start], rest.replacen(unquoted, &normalized, 1)))
+}
+
+fn normalize_dates(content: &str) -> String {
+    let mut formatted = content.lines()
+                               .map(|line| normalize_date_line(line).unwrap_or_else(|| line.to_owned()))
+                               .collect::<Vec<_>>()
+                               .join("\n");
+    if content.ends_with('\n') {
+        formatted.push('\n');
+    }
+    formatted
+}
+
+/// The data of a document, with mappings sorted and dates normalized, so only the layout may differ.
+fn canonical(doc: &Yaml, key: Option<&str>) -> Yaml {
+    match *doc {
+        Yaml::Hash(ref hash) => {
+            let mut entries = hash.iter()
+                                  .map(|(k, v)| (canonical(k, None), canonical(v, k.as_str())))
+                                  .collect::<Vec<_>>();
+            entries.sort();
+            Yaml::Hash(entries.into_iter().collect::<YamlHash>())
+        }
+        Yaml::Array(ref items) => Yaml::Array(items.iter().map(|item| canonical(item, key)).collect()),
+        Yaml::String(ref value) if key.is_some_and(|key| DATE_KEYS.contains(&key)) => {
+            Yaml::String(normalize_date(value).unwrap_or_else(|| value.to_owned()))
+        }
+        ref other => other.clone(),
+    }
+}
+
+/// The first top level section whose data differs.
+fn first_difference(before: &Yaml, after: &Yaml) -> String {
+    match (before, after) {
+        (Yaml::Hash(before), Yaml::Hash(after)) => {
+            before.iter()
+                  .find(|(key, value)| after.get(key) != Some(value))
+                  .or_else(|| after.iter().find(|(key, _)| !before.contains_key(key)))
+                  .and_then(|(key, _)| key.as_str().map(ToOwned::to_owned))
+                  .unwrap_or_else(|| String::from("document"))
+        }
+        _ => String::from("document"),
+    }
+}
+
+/// `content` formatted like `asciii fmt` does, fails if that would change any data.
+pub fn format(content: &str) -> Result<String, AsciiiError> {
+    let formatted = yaml::reindent_text(content, 2);
+    let formatted = yaml::reorder_in_text(&formatted, SECTION_ORDER);
+    let formatted = normalize_dates(&formatted);
+
+    let before = canonical(&yaml::parse(content)?, None);
+    let after = match yaml::parse(&formatted) {
+        Ok(after) => canonical(&after, None),
+        Err(_) => return Err(ProjectError::CantFormat(String::from("document")).into()),
+    };
+    if before != after {
+        return Err(ProjectError::CantFormat(first_difference(&before, &after)).into());
+    }
+    Ok(formatted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_layout() {
+        let content = "--- # asciii document\n\
+                       invoice:\n    number: 4\n    date: 2024-03-10 # invoice date\n\n\
+                       # who it is for\n\
+                       client:\n    first_name: Graf   \n    address: |\n        Straße 1\n          Hinterhaus\n\n\
+                       event:\n    dates:\n    -   begin: 3.7.2024\n        end: \"4.7.2024\"\n    - begin: 05.07.2024\n\
+                       unknown: 1\n\
+                       ...\n";
+        let formatted = format(content).unwrap();
+        assert_eq!(formatted,
+                   "--- # asciii document\n\
+                    # who it is for\n\
+                    client:\n  first_name: Graf\n  address: |\n    Straße 1\n      Hinterhaus\n\n\
+                    event:\n  dates:\n  - begin: 03.07.2024\n    end: \"04.07.2024\"\n  - begin: 05.07.2024\n\
+                    unknown: 1\n\
+                    invoice:\n  number: 4\n  date: 10.03.2024 # invoice date\n\n\
+                    ...\n");
+        assert_eq!(format(&formatted).unwrap(), formatted);
+    }
+
+    #[test]
+    fn keeps_anchors_and_comments() {
+        let content = "cataloge:\n   product: &kaffee { name: Kaffee, price: 2.5, unit: 1l }\n\
+                       products:\n   *kaffee:\n      amount: 4 # 4 liters\n      #sold: 1\n";
+        assert_eq!(format(content).unwrap(),
+                   "cataloge:\n  product: &kaffee { name: Kaffee, price: 2.5, unit: 1l }\n\
+                    products:\n  *kaffee:\n    amount: 4 # 4 liters\n    #sold: 1\n");
+    }
+}
@@ -1,4 +1,4 @@
-use anyhow::Error;
+use crate::error::AsciiiError;
 use ordered_float::OrderedFloat;
 use num_traits::Float;
 
@@ -120,7 +120,7 @@ pub struct Meta {
     format: String,
 }
 
-pub fn from_str(content: &str) -> Result<Project, Error> {
+pub fn from_str(content: &str) -> Result<Project, AsciiiError> {
     Ok(serde_yaml::from_str(content)?)
 }
 
@@ -2,28 +2,32 @@
 //!
 //! This module implements all functionality of a project.
 
-use std::fs::{self, File};
-use std::io::prelude::*;
+use std::fs;
 use std::ffi::OsStr;
 use std::fmt;
 use std::path::{Path, PathBuf};
 use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
 
 use chrono::prelude::*;
 use chrono::Duration;
 use yaml_rust::Yaml;
 use maplit::hashmap;
 use tempdir::TempDir;
-use anyhow::{bail, Error};
 
-use bill::BillItem;
+use bill::{BillItem, Currency, Tax};
 use icalendar::{Calendar, CalendarDateTime, Component, Todo};
 use semver::Version;
 
-use crate::util::{yaml, get_valid_path};
-use crate::storage::{Storable, list_path_content};
+use crate::error::AsciiiError;
+use crate::util::{self, yaml, get_valid_path, to_currency};
+use crate::util::holidays::BusinessDays;
+use crate::util::currency::CurrencyUnit;
+use crate::storage::{Storable, SlugStrategy, SortKey, SortOrder, list_path_content};
+use crate::storage::archive_index::IndexEntry;
 use crate::storage::StorableAndTempDir;
 use crate::storage::StorageError;
+use crate::storage::clients::{ClientDatabase, KnownClient};
 use crate::storage::repo::GitStatus;
 use crate::templater::{Templater, IsKeyword};
 
@@ -33,7 +37,10 @@ mod spec_yaml;
 mod yaml_provider;
 
 pub mod error;
-mod computed_field;
+pub mod computed_field;
+pub mod validate;
+pub mod format;
+pub mod file_name;
 
 #[cfg(test)]
 mod tests;
@@ -46,70 +53,141 @@ use self::spec::{IsProject, IsClient};
 use self::spec::{Offerable, Invoicable, Redeemable, Validatable, HasEmployees};
 use self::yaml_provider::*;
 
-use self::error::ProjectError;
-use self::product::{Product, ProductError};
+use self::error::{ProjectError, Diagnostic, Severity};
+use self::product::{PriceSource, Product, ProductError};
 
 pub use self::computed_field::ComputedField;
+pub use self::yaml_provider::error::FieldError;
+
+/// Top level sections that are only parsed when something asks for them.
+///
+/// Listing projects needs none of these, but they make up most of a project file.
+const LAZY_SECTIONS: &[&str] = &["cataloge", "products", "hours"];
 
 /// Represents a Project.
 ///
 /// A project is storable, contains products, and you can create an offer or invoice from it.
 /// The main implementation is done in [`spec`](spec/index.html).
+///
+/// The file is parsed lazily: fields outside of `LAZY_SECTIONS` only parse the rest of the document,
+/// anything else parses all of it, once. The items of the bills are read once, when first asked for.
+/// The content never changes after opening, `write_content()` only writes the file,
+/// so none of this has to be invalidated; a project opened again starts out empty.
 #[derive(Clone)]
 pub struct Project {
     file_path: PathBuf,
     git_status: Option<GitStatus>,
     file_content: String,
-    yaml: Yaml
+    /// the whole document
+    yaml: OnceLock<Yaml>,
+    /// the document without `LAZY_SECTIONS`
+    head: OnceLock<Yaml>,
+    /// what `bills()` is made of
+    bill_items: OnceLock<Vec<BillLine>>,
+    /// price books, handed out by the storage
+    clients: Option<Arc<ClientDatabase>>,
+    /// `output_dir`, resolved by the storage
+    output_dir: Option<PathBuf>,
+}
+
+/// One line of the offer and the invoice, read from `hours` or `products` by `bills()`.
+#[derive(Clone, Debug)]
+struct BillLine {
+    name: String,
+    unit: Option<String>,
+    tax: Tax,
+    price: Currency,
+    source: PriceSource,
+    offered: f64,
+    sold: f64,
+}
+
+impl BillLine {
+    fn product(&self) -> Product<'_> {
+        Product { name: &self.name, unit: self.unit.as_deref(), tax: self.tax, price: self.price, source: self.source }
+    }
 }
 
 impl Project {
     /// Access to inner data
-    pub fn yaml(&self) -> &Yaml{ &self.yaml }
+    pub fn yaml(&self) -> &Yaml {
+        self.yaml.get_or_init(|| {
+            let _timer = util::timing::start("parsing");
+            yaml::parse(&self.file_content).unwrap_or_else(|e|{
+                log::error!("syntax error in {}\n  {}", self.file_path.display(), e);
+                Yaml::Null
+            })
+        })
+    }
+
+    /// The document without `LAZY_SECTIONS`, or all of it if that was already parsed.
+    fn head(&self) -> &Yaml {
+        if let Some(yaml) = self.yaml.get() {
+            return yaml;
+        }
+        self.head.get_or_init(|| self.parse_head().unwrap_or_else(|_| self.yaml().clone()))
+    }
+
+    /// Parses the document without `LAZY_SECTIONS`, fails only if the whole document is broken.
+    fn parse_head(&self) -> Result<Yaml, AsciiiError> {
+        let _timer = util::timing::start("parsing");
+        let head = LAZY_SECTIONS.iter()
+                                .fold(self.file_content.clone(), |content, section| {
+                                    yaml::remove_in_text(&content, section).unwrap_or(content)
+                                });
+        // an alias into one of the lazy sections, unusual but valid
+        yaml::parse(&head).or_else(|_| yaml::parse(&self.file_content))
+    }
+
+    fn new(file_path: PathBuf, file_content: String) -> Project {
+        Project {
+            file_path,
+            git_status: None,
+            file_content,
+            yaml: OnceLock::new(),
+            head: OnceLock::new(),
+            bill_items: OnceLock::new(),
+            clients: None,
+            output_dir: None,
+        }
+    }
 
     /// Opens a project from file path;
-    pub fn open<S: AsRef<OsStr> + std::fmt::Debug + ?Sized>(pathish: &S) -> Result<Project, Error> {
+    ///
+    /// Only the head is parsed yet, but fields that are there and invalid are reported right away.
+    /// Syntax errors fail right away, unless they are inside one of the `LAZY_SECTIONS`.
+    pub fn open<S: AsRef<OsStr> + std::fmt::Debug + ?Sized>(pathish: &S) -> Result<Project, AsciiiError> {
         log::trace!("Project::open({:?});", pathish);
         let file_path = Path::new(&pathish);
-        let file_content = fs::read_to_string(&file_path)?;
-        let project = Project {
-            file_path: file_path.to_owned(),
-            git_status: None,
-            yaml: yaml::parse(&file_content).unwrap_or_else(|e|{
-                log::error!("syntax error in {}\n  {}", file_path.display(), e);
-                Yaml::Null
-            }),
-            file_content,
-        };
+        let file_content = fs::read_to_string(file_path)?;
+        let project = Project::new(file_path.to_owned(), file_content);
+        let _ = project.head.set(project.parse_head()?);
 
+        // `hours` is checked by `asciii validate`, checking it here would parse everything
         let validation = project.validate()
             .and(project.client().validate())
             .and(project.invoice().validate())
             .and(project.offer().validate())
-            .and(project.hours().validate())
             .and(<dyn Redeemable>::validate(&project));
 
+        // on stderr, stdout may be `asciii show --json`
         if !validation.validation_errors.is_empty() {
             let name = project.short_desc();
-            log::warn!("project {:?}:", name);
-            for err in validation.validation_errors {
-                println!(" * {}", err);
-            }
-
+            log::warn!("project {:?}:\n * {}", name, validation.validation_errors.join("\n * "));
         }
         Ok(project)
     }
 
     /// import from yaml file
     #[cfg(feature="deserialization")]
-    pub fn parse_yaml(&self) -> Result<import::Project, Error> {
+    pub fn parse_yaml(&self) -> Result<import::Project, AsciiiError> {
         import::from_str(&self.file_content)
     }
 
     /// (feature deactivated) import from yaml file
     #[cfg(not(feature="deserialization"))]
-    pub fn parse_yaml(&self) -> Result<(), Error> {
-        bail!(error::ProjectError::FeatureDeactivated)
+    pub fn parse_yaml(&self) -> Result<(), AsciiiError> {
+        Err(ProjectError::FeatureDeactivated.into())
     }
 
     pub fn dump_yaml(&self) -> String {
@@ -126,32 +204,45 @@ impl Project {
 
     #[cfg(feature="serialization")]
     /// export to JSON
-    pub fn to_json(&self) -> Result<String, Error> {
+    pub fn to_json(&self) -> Result<String, AsciiiError> {
         let complete: Complete = self.export();
         Ok(serde_json::to_string(&complete)?)
     }
 
     #[cfg(not(feature="serialization"))]
     /// feature deactivateda) export to JSON
-    pub fn to_json(&self) -> Result<String, Error> {
-        bail!(error::ProjectError::FeatureDeactivated)
+    pub fn to_json(&self) -> Result<String, AsciiiError> {
+        Err(ProjectError::FeatureDeactivated.into())
+    }
+
+    #[cfg(feature="serialization")]
+    /// export to YAML, the same data as `to_json()`
+    pub fn to_yaml(&self) -> Result<String, AsciiiError> {
+        let complete: Complete = self.export();
+        Ok(serde_yaml::to_string(&complete)?)
+    }
+
+    #[cfg(not(feature="serialization"))]
+    /// (feature deactivated) export to YAML
+    pub fn to_yaml(&self) -> Result<String, AsciiiError> {
+        Err(ProjectError::FeatureDeactivated.into())
     }
 
     /// Used mostly for testing purposes
-    pub fn from_file_content(content: &str) -> Result<Project, Error> {
-        Ok(Project{
-            file_path: PathBuf::new(),
-            git_status: None,
-            yaml: yaml::parse(content).unwrap(),
-            file_content: String::from(content),
-        })
+    pub fn from_file_content(content: &str) -> Result<Project, AsciiiError> {
+        let yaml = yaml::parse(content)?;
+        let project = Project::new(PathBuf::new(), String::from(content));
+        let _ = project.yaml.set(yaml);
+        Ok(project)
     }
 
     /// wrapper around `yaml::get()` with replacement
+    ///
+    /// Built-in computed fields come first, then custom ones (see `computed_field::register()`).
     pub fn field(&self, path:&str) -> Option<String> {
-        ComputedField::from(path).get(self).or_else(|| 
-            yaml::get_to_string(self.yaml(),path)
-        )
+        ComputedField::from(path).get(self)
+            .or_else(|| computed_field::get_custom(path, self))
+            .or_else(|| yaml::get_to_string(self.data_for(path),path))
     }
 
     /// Returns the struct `Client`, which abstracts away client specific stuff.
@@ -174,16 +265,49 @@ impl Project {
         Hours { inner: self }
     }
 
+    /// The entry of this project's client in the client database, if the storage has one.
+    pub fn known_client(&self) -> Option<&KnownClient> {
+        let client = self.client();
+        let full_name = client.full_name();
+        let names = full_name.iter().map(String::as_str)
+                             .chain(client.last_name().ok())
+                             .collect::<Vec<_>>();
+        self.clients.as_ref()?.find(&names, client.email().ok())
+    }
+
+    /// ISO code of the currency the project is billed in, `None` if it is billed in euro, see `exchange`.
+    pub fn billing_currency(&self) -> Option<&str> {
+        yaml::get_str(self.yaml(), "currency")
+            .map(str::trim)
+            .filter(|code| !code.is_empty() && !code.eq_ignore_ascii_case(crate::exchange::HOME))
+    }
+
+    /// Currency of the amounts in the project, its own `currency` or the configured one.
+    pub fn currency_unit(&self) -> CurrencyUnit {
+        let configured = CurrencyUnit::configured();
+        match yaml::get_str(self.yaml(), "currency").map(str::trim) {
+            Some(code) if !code.is_empty() && !code.eq_ignore_ascii_case(&configured.code) => CurrencyUnit::new(code, Some(code), None),
+            _ => configured
+        }
+    }
+
+    /// Sum of `expenses`, a mapping of descriptions to amounts that were spent on the project.
+    pub fn expenses(&self) -> Currency {
+        yaml::get_hash(self.yaml(), "expenses")
+            .map(|expenses| expenses.values()
+                                    .filter_map(|v| v.as_f64().or_else(|| v.as_i64().map(|i| i as f64)))
+                                    .sum())
+            .map(to_currency)
+            .unwrap_or_else(|| to_currency(0.0))
+    }
+
     /// Ready to produce offer.
     ///
-    /// Ready to send an **offer** to the client.
+    /// Ready to send an **offer** to the client, checked by `validate::validator()`.
     ///
     /// Returns list of missing fields, empty vector if ready.
     pub fn is_missing_for_offer(&self) -> Vec<String> {
-        self.offer().validate()
-            .and(self.client().validate())
-            .and(self.validate())
-            .missing_fields
+        validate::validator().offer(self).missing_fields
     }
 
     /// Valid to produce invoice
@@ -193,26 +317,120 @@ impl Project {
     /// Returns list of missing fields, empty vector if ready.
     pub fn is_missing_for_invoice(&self) -> Vec<String>{
         let mut missing = self.is_missing_for_offer();
-        missing.extend(self.invoice().validate().missing_fields);
+        missing.extend(validate::validator().invoice(self).missing_fields);
         missing
     }
 
     /// Completely done and in the past.
     ///
-    /// Ready to be **h:
+    /// Ready to be **archived**, as far as `archive/require` is concerned.
     ///
     /// Returns list of missing fields, empty vector if ready.
     pub fn is_ready_for_archive(&self) -> Vec<String> {
-        if self.canceled(){
-            Vec::new()
+        validate::validator().archive(self).missing_fields
+    }
+
+    /// Runs every spec check and returns structured findings.
+    ///
+    /// Invalid values are errors, fields missing for offer or invoice are warnings
+    /// and fields missing for the archive are only informational.
+    pub fn diagnostics(&self) -> Vec<Diagnostic> {
+        let file = self.file();
+        let validator = validate::validator();
+        let mut diagnostics = validator.offer(self).diagnostics(&file, "offer", Severity::Warning);
+        diagnostics.extend(validator.invoice(self).diagnostics(&file, "invoice", Severity::Warning));
+        diagnostics.extend(validator.archive(self).diagnostics(&file, "archive", Severity::Info));
+
+        if self.invoice().generated_date().is_ok() {
+            if let Some((field, message)) = self.unaccepted_offer() {
+                diagnostics.push(Diagnostic {
+                    file: file.clone(),
+                    field: field.into(),
+                    stage: "invoice".into(),
+                    severity: Severity::Warning,
+                    message,
+                });
+            }
+        }
+
+        if let Err(error) = self.bills() {
+            diagnostics.push(Diagnostic {
+                file,
+                field: "products".into(),
+                stage: "offer".into(),
+                severity: Severity::Error,
+                message: error.to_string(),
+            });
+        }
+
+        if crate::CONFIG.get_bool("validation/strict_keys") {
+            diagnostics.extend(self.unknown_keys());
+        }
+
+        diagnostics
+    }
+
+    /// Keys `config::schema::PROJECT_KEYS` does not know, e.g. a `cilent:`, see `validation/strict_keys`.
+    ///
+    /// Files older than format 2.0.0 have keys of their own, they are left to `asciii import legacy`.
+    pub fn unknown_keys(&self) -> Vec<Diagnostic> {
+        if !self.format().is_ok_and(|format| format.major >= 2) {
+            return Vec::new();
+        }
+        crate::config::schema::unknown_project_keys(self.yaml())
+            .into_iter()
+            .map(|(key, meant)| Diagnostic {
+                file: self.file(),
+                field: key,
+                stage: "file".into(),
+                severity: Severity::Warning,
+                message: match meant {
+                    Some(meant) => lformat!("unknown key, did you mean {:?}?", meant),
+                    None => lformat!("unknown key"),
+                },
+            })
+            .collect()
+    }
+
+    /// Invoicing an offer that was never sent or accepted is most likely a mistake.
+    ///
+    /// Returns the missing field and why it matters.
+    pub fn unaccepted_offer(&self) -> Option<(&'static str, String)> {
+        if self.offer().sent_date().is_err() {
+            Some(("offer/sent_date", lformat!("invoice generated from an offer that was never sent")))
+        } else if self.offer().accepted_date().is_err() {
+            Some(("offer/accepted_date", lformat!("invoice generated from an offer that was not accepted")))
         } else {
-            <dyn Redeemable>::validate(self)
-                .and(self.hours().validate())
-                .missing_fields
+            None
         }
     }
 
-    pub fn to_csv(&self, bill_type: BillType) -> Result<String, Error>{
+    /// The offer was neither accepted nor invoiced and the project is not canceled, see `asciii offers`.
+    pub fn has_open_offer(&self) -> bool {
+        !self.canceled() && self.offer().accepted_date().is_err() && self.invoice().number().is_err()
+    }
+
+    /// Last day of an open offer that ran out, see `asciii offers --expired`.
+    pub fn expired_offer(&self, today: Date<Utc>) -> Option<Date<Utc>> {
+        if !self.has_open_offer() {
+            return None;
+        }
+        self.offer().valid_until().ok().filter(|valid_until| *valid_until < today)
+    }
+
+    /// Furthest step the documents of this project have reached and when, see `asciii sent`.
+    pub fn document_state(&self) -> Option<(String, Date<Utc>)> {
+        let (offer, invoice) = (self.offer(), self.invoice());
+        [(lformat!("invoice sent"),      invoice.sent_date()),
+         (lformat!("invoice generated"), invoice.generated_date()),
+         (lformat!("offer accepted"),    offer.accepted_date()),
+         (lformat!("offer sent"),        offer.sent_date()),
+         (lformat!("offer generated"),   offer.generated_date())]
+            .into_iter()
+            .find_map(|(state, date)| date.ok().map(|date| (state, date)))
+    }
+
+    pub fn to_csv(&self, bill_type: BillType) -> Result<String, AsciiiError>{
         use std::fmt::Write;
         let (offer, invoice) = self.bills()?;
         let bill = match bill_type{ BillType::Offer => offer, BillType::Invoice => invoice };
@@ -245,7 +463,7 @@ impl Project {
     }
 
     /// Fill certain field
-    pub fn replace_field(&self, field:&str, value:&str) -> Result<(), Error> {
+    pub fn replace_field(&self, field:&str, value:&str) -> Result<(), AsciiiError> {
         // fills the template
         let filled = Templater::new(&self.file_content)
             .fill_in_field(field,value)
@@ -254,20 +472,120 @@ impl Project {
 
         match yaml::parse(&filled){
             Ok(_) => {
-                let mut file = File::create(self.file())?;
-                file.write_all(filled.as_bytes())?;
-                file.sync_all()?;
+                util::atomic_write(self.file(), &filled)?;
                 Ok(())
             },
             Err(e) => {
                 log::error!("The resulting document is no valid yaml. SORRY!\n{}\n\n{}",
                        filled.lines().enumerate().map(|(n,l)| format!("{:>3}. {}\n",n,l)).collect::<String>(), //line numbers :D
                        e);
-                bail!(e)
+                Err(e)
             }
         }
     }
 
+    /// Sets a field of the project file, see `spec::SETTABLE_FIELDS`.
+    ///
+    /// The value is checked against the kind of the field,
+    /// everything else in the file (comments, anchors, formatting) stays as it is.
+    /// Returns `false` if the file already contained this value.
+    pub fn set_field(&self, path:&str, value:&str) -> Result<bool, AsciiiError> {
+        let changed = self.content_with_fields(&[(path, value)])?;
+        if changed == self.file_content {
+            return Ok(false);
+        }
+        self.write_content(&changed)?;
+        Ok(true)
+    }
+
+    /// Content of the project file after setting all `fields`, nothing is written.
+    pub fn content_with_fields(&self, fields:&[(&str, &str)]) -> Result<String, AsciiiError> {
+        Self::set_fields_in(self.file_content.clone(), fields)
+    }
+
+    /// Content of the project file after merging the yaml document `patch` into it, see `yaml::merge_in_text()`.
+    ///
+    /// Settable fields that change are checked against their kind, like in `set_field()`.
+    pub fn content_with_patch(&self, patch:&str) -> Result<String, AsciiiError> {
+        let content = yaml::merge_in_text(&self.file_content, patch).ok_or(ProjectError::CantMerge)?;
+        let merged = yaml::parse(&content)?;
+        for path in spec::changed_fields(self.yaml(), &merged) {
+            if let (Some(kind), Some(value)) = (spec::field_kind(path), yaml::get_to_string(&merged, path)) {
+                kind.to_yaml(&value).map_err(|e| match e {
+                    FieldError::Invalid(msg) => ProjectError::InvalidValue(path.to_owned(), msg),
+                    e => ProjectError::InvalidValue(path.to_owned(), e.to_string()),
+                })?;
+            }
+        }
+        Ok(content)
+    }
+
+    fn set_fields_in(mut content:String, fields:&[(&str, &str)]) -> Result<String, AsciiiError> {
+        for &(path, value) in fields {
+            let kind = spec::field_kind(path).ok_or_else(|| ProjectError::UnknownField(path.to_owned()))?;
+            let scalar = kind.to_yaml(value).map_err(|e| match e {
+                FieldError::Invalid(msg) => ProjectError::InvalidValue(path.to_owned(), msg),
+                e => ProjectError::InvalidValue(path.to_owned(), e.to_string()),
+            })?;
+            content = yaml::set_in_text(&content, path, &scalar)
+                .ok_or_else(|| ProjectError::CantSetField(path.to_owned()))?;
+
+            // make sure the result still reads like a project
+            if yaml::get(&yaml::parse(&content)?, path).is_none() {
+                return Err(ProjectError::CantSetField(path.to_owned()).into());
+            }
+        }
+        Ok(content)
+    }
+
+    /// Content of the project file with a normalized layout, see `format::format()`.
+    pub fn formatted_content(&self) -> Result<String, AsciiiError> {
+        format::format(&self.file_content)
+    }
+
+    /// Content for a copy of this project named `new_name`, see `asciii duplicate`.
+    ///
+    /// Everything listed in `duplicate/reset` is removed and the copy is created today,
+    /// it `follows()` this project.
+    pub fn duplicate_content(&self, new_name:&str) -> Result<String, AsciiiError> {
+        let mut content = self.file_content.clone();
+        for field in crate::CONFIG.get_strs("duplicate/reset").unwrap_or_default() {
+            if let Some(reset) = yaml::remove_in_text(&content, field) {
+                log::trace!("duplicate: reset {:?}", field);
+                content = reset;
+            }
+        }
+
+        let created = Utc::today().format("%d.%m.%Y").to_string();
+        content = yaml::set_in_text(&content, "created", &created)
+            .ok_or_else(|| ProjectError::CantSetField("created".into()))?;
+        match self.file().file_stem().and_then(OsStr::to_str) {
+            Some(ident) => Self::set_fields_in(content, &[("event/name", new_name), ("follows", ident)]),
+            None => Self::set_fields_in(content, &[("event/name", new_name)])
+        }
+    }
+
+    /// The project this one is a follow-up of, by the name of its file or its invoice number, see `asciii export --graphviz`.
+    pub fn follows(&self) -> Option<&str> {
+        yaml::get_str(self.yaml(), "follows").map(str::trim).filter(|follows| !follows.is_empty())
+    }
+
+    /// When the project was created, if its template or `asciii duplicate` wrote down `created`.
+    pub fn created_date(&self) -> Option<Date<Utc>> {
+        self.get_dmy("created").ok()
+    }
+
+    /// Overwrites the project file.
+    pub fn write_content(&self, content:&str) -> Result<(), AsciiiError> {
+        util::atomic_write(self.file(), content)?;
+        Ok(())
+    }
+
+    /// Original content of the project file.
+    pub fn file_content(&self) -> &str {
+        &self.file_content
+    }
+
     /// Time between event and creation of invoice
     pub fn our_bad(&self) -> Option<Duration> {
         let event   = self.event_date().ok()?;
@@ -310,8 +628,8 @@ impl Project {
                 (None,          None,          _) if today < event => { /* no need to worry yet */ },
 
                 // they haven't payed us yet
-                (Some(invoice), None,          _) if days_since(invoice) >= 14 => { cal.push(self.task_follow_up(invoice)); },
-                (Some(invoice), None,          _) if days_since(invoice) < 14 => { /* they have 14 days before we complain */ },
+                (Some(invoice), None,          _) if today >= Self::due_date(invoice, 14) => { cal.push(self.task_follow_up(invoice)); },
+                (Some(_),       None,          _) => { /* they have 14 days before we complain */ },
 
                 // we need to pay the employees
                 (Some(_),       Some(payed),   None) => { cal.push(self.task_pay_employees(payed)); },
@@ -325,9 +643,20 @@ impl Project {
         cal
     }
 
+    /// `days` after `start`, on the next business day, see `util::holidays`.
+    fn due_date(start: Date<Utc>, days: i64) -> Date<Utc> {
+        let due = BusinessDays::configured().due_date(start.naive_utc(), days);
+        Date::from_utc(due, Utc)
+    }
+
+    /// Day the invoice has to be payed by, two weeks after it was issued on a business day.
+    pub fn payment_due(&self) -> Option<Date<Utc>> {
+        self.invoice().date().ok().map(|invoice| Self::due_date(invoice, 14))
+    }
+
     fn task_issue_invoice(event_date: Date<Utc>) -> Todo {
         Todo::new().summary(&lformat!("Create an Invoice"))
-                   .due(CalendarDateTime::from((event_date + Duration::days(14)).and_hms(11, 10, 0)))
+                   .due(CalendarDateTime::from(Self::due_date(event_date, 14).and_hms(11, 10, 0)))
                    .priority(6)
                    .done()
     }
@@ -338,7 +667,7 @@ impl Project {
             .description( &lformat!("Pay {}\nYou have had the money for {} days!",
                                    self.hours().employees_string().unwrap_or_default(),
                                    days_since_payed))
-            .due(CalendarDateTime::from((payed_date + Duration::days(14)).and_hms(11, 10, 0)))
+            .due(CalendarDateTime::from(Self::due_date(payed_date, 14).and_hms(11, 10, 0)))
             .done()
     }
 
@@ -375,7 +704,7 @@ impl Project {
                        .done()
     }
 
-    fn item_from_desc_and_value<'y>(&self, desc: &'y Yaml, values: &'y Yaml) -> Result<(BillItem<Product<'y>>,BillItem<Product<'y>>), Error> {
+    fn item_from_desc_and_value<'y>(&self, desc: &'y Yaml, values: &'y Yaml) -> Result<(BillItem<Product<'y>>,BillItem<Product<'y>>), AsciiiError> {
         let get_f64 = |yaml, path|
             self.get_direct(yaml,path)
                 .and_then(|y| y.as_f64()
@@ -396,10 +725,10 @@ impl Project {
         let sold = if let Some(returned) = get_f64(values, "returned") {
             // if "returned", there must be no "sold"
             if sold.is_some() {
-                bail!(ProductError::AmbiguousAmounts(product.name.to_owned()));
+                return Err(ProductError::AmbiguousAmounts(product.name.to_owned()).into());
             }
             if returned > offered {
-                bail!(ProductError::TooMuchReturned(product.name.to_owned()));
+                return Err(ProductError::TooMuchReturned(product.name.to_owned()).into());
             }
             offered - returned
         } else if let Some(sold) = sold {
@@ -432,10 +761,10 @@ pub trait Exportable {
     /// Where to export to
     fn export_dir(&self)  -> PathBuf;
 
-    /// Filename of the offer output file.
+    /// Filename of the offer output file, see `file_name`.
     fn offer_file_name(&self, extension: &str) -> Option<String>;
 
-    /// Filename of the invoice output file. **Careful!** uses today's date.
+    /// Filename of the invoice output file, see `file_name`.
     fn invoice_file_name(&self, extension: &str) -> Option<String>;
 
     fn output_file_exists(&self, bill_type: BillType) -> bool {
@@ -452,8 +781,13 @@ pub trait Exportable {
         }
     }
 
+    /// Where documents go, `output_path` unless the project has its own `output_dir`
+    fn output_folder(&self) -> Option<PathBuf> {
+        get_valid_path(crate::CONFIG.get_str("output_path"))
+    }
+
     fn offer_file(&self) -> Option<PathBuf> {
-        let output_folder = get_valid_path(crate::CONFIG.get_str("output_path"));
+        let output_folder = self.output_folder();
         let convert_ext  = crate::CONFIG.get_str("document_export/output_extension");
         match (output_folder, self.offer_file_name(convert_ext)) {
             (Some(folder), Some(name)) => folder.join(&name).into(),
@@ -462,7 +796,7 @@ pub trait Exportable {
     }
 
     fn invoice_file(&self) -> Option<PathBuf>{
-        let output_folder = get_valid_path(crate::CONFIG.get_str("output_path"));
+        let output_folder = self.output_folder();
         let convert_ext  = crate::CONFIG.get_str("document_export/output_extension");
         match (output_folder, self.invoice_file_name(convert_ext)) {
             (Some(folder), Some(name)) => folder.join(&name).into(),
@@ -478,51 +812,49 @@ pub trait Exportable {
         self.invoice_file().map_or(false, |f|f.exists())
     }
 
-    fn write_to_path<P:AsRef<OsStr> + fmt::Debug>(content: &str, target: &P) -> Result<(), Error> {
+    fn write_to_path<P:AsRef<OsStr> + fmt::Debug>(content: &str, target: &P) -> Result<(), AsciiiError> {
         log::trace!("writing content ({}bytes) to {:?}", content.len(), target);
-        let mut file = File::create(Path::new(target))?;
-        file.write_all(content.as_bytes())?;
-        file.sync_all()?;
+        util::atomic_write(Path::new(target), content)?;
         Ok(())
     }
 
-    fn full_file_path(&self, bill_type: BillType, ext: &str) -> Result<PathBuf, Error> {
+    fn full_file_path(&self, bill_type: BillType, ext: &str) -> Result<PathBuf, AsciiiError> {
         match bill_type {
             BillType::Offer   => self.full_offer_file_path(ext),
             BillType::Invoice => self.full_invoice_file_path(ext)
         }
     }
 
-    fn full_offer_file_path(&self, ext: &str) -> Result<PathBuf, Error> {
+    fn full_offer_file_path(&self, ext: &str) -> Result<PathBuf, AsciiiError> {
         if let Some(target) = self.offer_file_name(ext) {
             Ok(self.export_dir().join(&target))
         } else {
-            bail!(ProjectError::CantDetermineTargetFile)
+            Err(ProjectError::CantDetermineTargetFile.into())
         }
     }
 
-    fn full_invoice_file_path(&self, ext: &str) -> Result<PathBuf, Error> {
+    fn full_invoice_file_path(&self, ext: &str) -> Result<PathBuf, AsciiiError> {
         if let Some(target) = self.invoice_file_name(ext) {
             Ok(self.export_dir().join(&target))
         } else {
-            bail!(ProjectError::CantDetermineTargetFile)
+            Err(ProjectError::CantDetermineTargetFile.into())
         }
     }
 
-    fn write_to_file(&self, content: &str, bill_type: BillType, ext: &str) -> Result<PathBuf, Error> {
+    fn write_to_file(&self, content: &str, bill_type: BillType, ext: &str) -> Result<PathBuf, AsciiiError> {
         match bill_type{
             BillType::Offer   => self.write_to_offer_file(content, ext),
             BillType::Invoice => self.write_to_invoice_file(content, ext)
         }
     }
 
-    fn write_to_offer_file(&self, content: &str, ext: &str) -> Result<PathBuf, Error> {
+    fn write_to_offer_file(&self, content: &str, ext: &str) -> Result<PathBuf, AsciiiError> {
         let full_path = self.full_offer_file_path(ext)?;
         Self::write_to_path(content, &full_path)?;
         Ok(full_path)
     }
 
-    fn write_to_invoice_file(&self, content: &str, ext: &str) -> Result<PathBuf, Error> {
+    fn write_to_invoice_file(&self, content: &str, ext: &str) -> Result<PathBuf, AsciiiError> {
         let full_path = self.full_invoice_file_path(ext)?;
         Self::write_to_path(content, &full_path)?;
         Ok(full_path)
@@ -532,18 +864,16 @@ pub trait Exportable {
 impl Exportable for Project {
     fn export_dir(&self)  -> PathBuf { Storable::dir(self) }
 
+    fn output_folder(&self) -> Option<PathBuf> {
+        self.output_dir.clone().or_else(|| get_valid_path(crate::CONFIG.get_str("output_path")))
+    }
+
     fn offer_file_name(&self, extension: &str) -> Option<String>{
-        let num = self.offer().number().ok()?;
-        let name = slug::slugify(IsProject::name(self).ok()?);
-        Some(format!("{} {}.{}", num, name, extension))
+        file_name::file_name(self, BillType::Offer, extension)
     }
 
     fn invoice_file_name(&self, extension: &str) -> Option<String>{
-        let num = self.invoice().number_str()?;
-        let name = slug::slugify(self.name().ok()?);
-        //let date = Local::today().format("%Y-%m-%d").to_string();
-        let date = self.invoice().date().ok()?.format("%Y-%m-%d").to_string();
-        Some(format!("{} {} {}.{}",num,name,date,extension))
+        file_name::file_name(self, BillType::Invoice, extension)
     }
 
 }
@@ -553,7 +883,7 @@ impl Storable for Project {
         crate::CONFIG.get_to_string("extensions.project_file")
     }
 
-    fn from_template(project_name: &str, template:&Path, fill: &HashMap<&str, String>) -> Result<StorableAndTempDir<Self>, Error> {
+    fn from_template(project_name: &str, template:&Path, fill: &HashMap<&str, String>) -> Result<StorableAndTempDir<Self>, AsciiiError> {
         let template_name = template.file_stem().unwrap().to_str().unwrap();
 
         let event_date = (Utc::today() + Duration::days(14)).format("%d.%m.%Y").to_string();
@@ -584,12 +914,10 @@ impl Storable for Project {
 
         // generates a temp file
         let temp_dir  = TempDir::new(project_name).unwrap();
-        let temp_file = temp_dir.path().join(slug::slugify(project_name) + "." + &Self::file_extension());
+        let temp_file = temp_dir.path().join(SlugStrategy::with_config(&crate::CONFIG).slugify(project_name) + "." + &Self::file_extension());
 
         // write into a file
-        let mut file = File::create(&temp_file)?;
-        file.write_all(file_content.as_bytes())?;
-        file.sync_all()?;
+        util::atomic_write(&temp_file, &file_content)?;
 
         let yaml = match yaml::parse(&file_content){
             Ok(y) => y,
@@ -597,17 +925,13 @@ impl Storable for Project {
                 log::error!("The created document is no valid yaml. SORRY!\n{}\n\n{}",
                        file_content.lines().enumerate().map(|(n,l)| format!("{:>3}. {}\n",n,l)).collect::<String>(), //line numbers :D
                        e);
-                bail!(e)
+                return Err(e)
             }
         };
 
         // project now lives in the temp_file
-        let project = Project {
-            file_path: temp_file,
-            git_status: None,
-            file_content,
-            yaml
-        };
+        let project = Project::new(temp_file, file_content);
+        let _ = project.yaml.set(yaml);
 
         Ok(StorableAndTempDir {
             storable: project,
@@ -619,17 +943,28 @@ impl Storable for Project {
         self.invoice().number_str()
     }
 
-    fn index(&self) -> Option<String>{
-        let prefix = self.invoice().number_long_str().unwrap_or_else(||String::from("zzzz"));
-        match (self.invoice().date().ok(), self.modified_date()) {
-            (Some(date), _) |
-            (None, Some(date)) => {
-                Some(format!("{0}{1}", prefix, date.format("%Y%m%d")))
-            },
-            (None, None) => None,
+    fn index_entry(&self) -> IndexEntry {
+        IndexEntry {
+            ident: self.ident(),
+            name: self.short_desc(),
+            client: self.client().full_name(),
+            invoice: self.invoice().number_str(),
+            sum: self.sum_sold().ok().map(|sum| sum.as_float()),
         }
     }
 
+    fn sort_key(&self) -> SortKey {
+        SortKey {
+            date: self.invoice().date().ok().or_else(|| self.modified_date()),
+            number: self.invoice().number().ok(),
+            name: self.short_desc(),
+        }
+    }
+
+    fn index(&self) -> Option<String>{
+        Some(self.sort_key().to_index(&SortOrder::with_config(&crate::CONFIG)))
+    }
+
     fn short_desc(&self) -> String {
         self.name().ok()
             .map(ToOwned::to_owned)
@@ -655,6 +990,18 @@ impl Storable for Project {
         self.git_status = Some(status);
     }
 
+    fn set_client_database(&mut self, clients: Arc<ClientDatabase>){
+        self.clients = Some(clients);
+    }
+
+    fn output_dir_setting(&self) -> Option<String>{
+        self.get_str("output_dir").ok().filter(|dir| !dir.trim().is_empty()).map(ToOwned::to_owned)
+    }
+
+    fn set_output_dir(&mut self, dir: PathBuf){
+        self.output_dir = Some(dir);
+    }
+
     /// Ask a project for its gitstatus
     #[cfg(feature="git_statuses")]
     fn get_git_status(&self) -> GitStatus{
@@ -665,7 +1012,7 @@ impl Storable for Project {
     }
 
     /// Opens a yaml and parses it.
-    fn open_folder(folder_path: &Path) -> Result<Project, Error>{
+    fn open_folder(folder_path: &Path) -> Result<Project, AsciiiError>{
         let project_file_extension = crate::CONFIG.get_to_string("extensions.project_file");
         let file_path = list_path_content(folder_path)?.iter()
             .find(|f|f.extension().unwrap_or_else(||OsStr::new("")) == project_file_extension.as_str())
@@ -674,8 +1021,8 @@ impl Storable for Project {
         Self::open_file(&file_path)
     }
 
-    fn open_file(file_path:&Path) -> Result<Project, Error> {
-        Project::open(file_path)
+    fn open_file(file_path:&Path) -> Result<Project, AsciiiError> {
+        crate::storage::cache::file(file_path, Project::open)
     }
 
     /// Checks against a certain key-val pair.
@@ -736,7 +1083,7 @@ impl<'a> From<&'a Project> for Debug {
         Debug {
             file_path:  project.file_path.clone(),
             git_status: project.git_status.clone(),
-            yaml:       project.yaml.clone()
+            yaml:       project.yaml().clone()
         }
     }
 }
@@ -21,7 +21,28 @@ pub struct Product<'a> {
     pub name: &'a str,
     pub unit: Option<&'a str>,
     pub tax: Tax,
-    pub price: Currency
+    pub price: Currency,
+    pub source: PriceSource,
+}
+
+/// Where the price of a `Product` comes from.
+#[derive(Copy,Clone,Debug,PartialEq,Eq)]
+#[cfg_attr(feature = "serialization", derive(Serialize))]
+#[cfg_attr(feature = "serialization", serde(rename_all = "lowercase"))]
+pub enum PriceSource {
+    /// the project file: its `cataloge`, the product itself or the salary in `hours`
+    Catalog,
+    /// the price book of the client, see `storage::clients`
+    Client,
+}
+
+impl PriceSource {
+    pub fn name(self) -> &'static str {
+        match self {
+            PriceSource::Catalog => "catalog",
+            PriceSource::Client => "client",
+        }
+    }
 }
 
 #[derive(Error, Debug)]
@@ -59,7 +80,7 @@ impl<'a> Product<'a> {
             .map(to_currency)
             .ok_or_else(||ProductError::InvalidPrice(name.to_string()))?;
 
-        Ok(Product { name, unit, tax, price })
+        Ok(Product { name, unit, tax, price, source: PriceSource::Catalog })
     }
 
     fn from_new_format<'y>(desc: &'y yaml::Yaml, values: &'y yaml::Yaml, local_tax: Option<Tax>) -> Result<Product<'y>, ProductError> {
@@ -77,7 +98,7 @@ impl<'a> Product<'a> {
                 .map(to_currency)?;
         let unit = yaml::get_str(desc, "unit");
 
-        Ok(Product { name, unit, tax, price })
+        Ok(Product { name, unit, tax, price, source: PriceSource::Catalog })
     }
 
     pub fn from_desc_and_value<'y>(desc: &'y yaml::Yaml, values: &'y yaml::Yaml, local_tax: Option<Tax>) -> Result<Product<'y>, ProductError> {
@@ -0,0 +1,491 @@
+//! Implements the ascii invoicer project file specification.
+//!
+//! This does all of the heavy lifting.
+//! The implementation is separated into sub-modules which take care of separate objectives.
+//! Most of the functions in these modules take the `yaml` data directly as reference.
+//! Each module contains a `validate()` function which ought to be kept up to date.
+
+use std::fmt;
+
+use bill::{Bill, Currency, Tax};
+use chrono::{Date, Duration, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use icalendar::Calendar;
+use semver::Version;
+use yaml_rust::Yaml;
+
+use crate::error::AsciiiError;
+use crate::storage::Storable;
+use super::error::ValidationResult;
+use super::product::{PriceSource, Product};
+use super::yaml_provider::{FieldResult, FieldError};
+
+
+/// Every other trait in this module ought to be `Validatable`
+///
+pub trait Validatable {
+    /// Validates the spec, checking for missing fields and invalid values
+    fn validate(&self) -> ValidationResult;
+}
+
+pub trait ValidatableExt {
+    /// Returns fields that have invalid data or are missing
+    fn missing_fields(&self) -> Vec<String>;
+}
+
+impl<T: Validatable> ValidatableExt for T {
+    fn missing_fields(&self) -> Vec<String> {
+        self.validate().missing_fields
+    }
+}
+
+/// Stage 0: the Project itself
+///
+/// Provide the basics every Project should have.
+pub trait IsProject {
+
+    /// Project Name
+    fn name(&self) -> FieldResult<&str>;
+
+    /// When was the event
+    fn event_date(&self) -> FieldResult<Date<Utc>>;
+
+    /// Version of project format
+    fn format(&self) -> FieldResult<Version>;
+
+    /// Did the event actually occur
+    fn canceled(&self) -> bool;
+
+    /// Who organized the event
+    fn responsible(&self) -> FieldResult<&str>;
+
+    /// Long description of the project
+    fn long_desc(&self) -> String;
+}
+
+/// Extended functionality for projects
+pub trait IsProjectExt {
+    /// Number of days since creation of the project
+    fn age(&self) -> Option<i64>;
+}
+
+impl<T> IsProjectExt for T where T: Storable {
+    fn age(&self) -> Option<i64> {
+        self.modified_date()
+            .map(|date|
+                 (Utc::today().signed_duration_since(date))
+                              .num_days()
+                )
+    }
+}
+
+/// Stage 1: requirements for an offer
+pub trait Offerable {
+    /// Raised if the offer number is reused
+    fn appendix(&self) -> FieldResult<i64>;
+
+    /// When was the offer created
+    fn date(&self) -> FieldResult<Date<Utc>>;
+
+    /// ID of an the offer
+    fn number(&self) -> FieldResult<String>;
+
+    /// When was the offer document last generated
+    fn generated_date(&self) -> FieldResult<Date<Utc>>;
+
+    /// When was the offer sent to the client
+    fn sent_date(&self) -> FieldResult<Date<Utc>>;
+
+    /// When did the client accept the offer
+    fn accepted_date(&self) -> FieldResult<Date<Utc>>;
+
+    /// How the client accepted the offer, one of `ACCEPTANCE_METHODS`
+    fn accepted_via(&self) -> FieldResult<&str>;
+
+    /// The signed offer, attached to the project, see `asciii accept --document`
+    fn signed_copy(&self) -> FieldResult<&str>;
+
+    /// Last day the offer stands, `offer/valid_until` or `defaults/offer_validity` days after its date
+    fn valid_until(&self) -> FieldResult<Date<Utc>>;
+}
+
+/// Everything about the client
+///
+/// This is a [client](../struct.Project.html#method.client)
+pub trait IsClient {
+    ///Returns the content of `/client/email`
+    fn email(&self) -> FieldResult<&str>;
+
+    ///Returns the content of `/client/address`
+    fn address(&self) -> FieldResult<&str>;
+
+    ///Returns the content of `/client/title`
+    fn title(&self) -> FieldResult<&str>;
+
+    ///Returns the first word of `client/title`
+    fn salute(&self) -> FieldResult<&str>;
+
+    ///Returns the content of `/client/first_name`
+    fn first_name(&self) -> FieldResult<&str>;
+
+    ///Returns the content of `/client/last_name`
+    fn last_name(&self) -> FieldResult<&str>;
+
+    /// Combines `first_name` and `last_name`.
+    fn full_name(&self) -> Option<String>;
+
+    /// Produces a standard salutation field.
+    fn addressing(&self) -> Option<String>;
+}
+
+/// Stage 2: requirements for an invoice
+pub trait Invoicable {
+    /// plain access to `invoice/number`
+    fn number(&self) -> FieldResult<i64>;
+
+    /// When was the invoice created
+    fn date(&self) -> FieldResult<Date<Utc>>;
+
+    /// invoice number as a string
+    fn number_str(&self) -> Option<String>;
+
+    /// invoice number as a long string
+    fn number_long_str(&self) -> Option<String>;
+
+    /// An official identifier
+    fn official(&self) -> FieldResult<String>;
+
+    /// When was the invoice document last generated
+    fn generated_date(&self) -> FieldResult<Date<Utc>>;
+
+    /// When was the invoice sent to the client
+    fn sent_date(&self) -> FieldResult<Date<Utc>>;
+
+    /// Where the client can pay online, see `paylink`
+    fn paylink(&self) -> FieldResult<&str>;
+}
+
+/// Represents an Employee
+pub struct Employee {
+    /// Name of the Employee
+    pub name: String,
+
+    /// Amount of Currency the employees receives per hour
+    pub salary: Currency,
+
+    /// Number of hours the employee worked on this project
+    pub time: f64,
+
+    /// Salary times hours
+    pub wage: Currency,
+}
+
+/// Something that has employees
+pub trait HasEmployees {
+    /// When were the wages payed
+    fn wages_date(&self) -> FieldResult<Date<Utc>>;
+
+    /// Salary
+    fn salary(&self) -> FieldResult<Currency>;
+
+    /// Tax
+    fn tax(&self) -> FieldResult<Tax>;
+
+    /// Sum of wages after tax
+    fn net_wages(&self) -> Option<Currency> ;
+
+    /// Sum of wages before tax
+    fn gross_wages(&self) -> Option<Currency> ;
+
+
+    /// Full number of service hours
+    ///
+    /// `TODO` test this against old format
+    fn total_time(&self) -> Option<f64>;
+
+    /// Returns a product from Service
+    fn to_product(&self) -> Option<Product<'_>> {
+        self.salary().ok().map(|s| {
+            Product {
+                name: "Service",
+                unit: Some("h"),
+                tax: self.tax().ok().unwrap_or_else(|| Tax::new(0.0)),
+                price: s,
+                source: PriceSource::Catalog,
+            }
+        })
+    }
+
+    /// Nicely formatted list of employees with their respective service hours
+    fn employees_string(&self) -> Option<String>;
+
+    /// How many employees the event needs, `hours/staff`
+    fn staff(&self) -> FieldResult<u32>;
+
+    /// Names of everyone on the event, also those without hours yet
+    fn assigned(&self) -> Vec<String>;
+
+    /// List of employees and their respective service hours
+    fn employees(&self) -> FieldResult<Vec<Employee>>;
+
+    /// Check if the employees have been payed
+    fn employees_payed(&self) -> bool;
+
+    /// Sum of wages for the project
+    fn wages(&self) -> Option<Currency>;
+}
+
+
+/// Stage 3: when an `IsProject` is redeem and can be archived
+pub trait Redeemable: IsProject {
+
+    /// When was the project payed
+    fn payed_date(&self) -> FieldResult<Date<Utc>>;
+
+    /// If was the project payed
+    fn is_payed(&self) -> bool;
+
+    /// Returns a bill for the offer and one for the invoice.
+    fn bills(&self) -> Result<(Bill<Product<'_>>, Bill<Product<'_>>), AsciiiError>;
+
+    /// When what is the MWsT of the project.
+    fn tax(&self) -> FieldResult<Tax>;
+
+    /// Sum of sold products
+    fn sum_sold(&self) -> Result<Currency, AsciiiError> {
+        let (_,invoice) = self.bills()?;
+        Ok(invoice.net_total())
+    }
+
+}
+
+/// Holds the time of the beginning and end of an event
+#[derive(Debug)]
+pub struct EventTime {
+    /// Start of the event
+    pub start: NaiveTime,
+
+    /// End of the event
+    pub end:   NaiveTime
+}
+
+/// Time zone the times of an event are given in, `event/timezone` or `defaults/timezone`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EventZone {
+    /// No zone given, the times are local to whoever reads them.
+    Floating,
+    /// A name like `Europe/Berlin`, calendar programs know its offsets.
+    Named(String),
+    /// An offset like `+02:00`, `UTC` is `+00:00`.
+    Fixed(FixedOffset),
+}
+
+impl EventZone {
+    /// Reads `Europe/Berlin`, `+02:00`, `-0530` or `UTC`, an empty string is `Floating`.
+    pub fn parse(zone: &str) -> Option<EventZone> {
+        let zone = zone.trim();
+        if zone.is_empty() {
+            return Some(EventZone::Floating);
+        }
+        if zone.eq_ignore_ascii_case("utc") || zone == "Z" {
+            return FixedOffset::east_opt(0).map(EventZone::Fixed);
+        }
+        if let Some(sign) = zone.strip_prefix(['+', '-']).map(|_| if zone.starts_with('-') { -1 } else { 1 }) {
+            let digits = zone[1..].replace(':', "");
+            if digits.len() != 4 || !digits.bytes().all(|b| b.is_ascii_digit()) {
+                return None;
+            }
+            let (hours, minutes) = (digits[..2].parse::<i32>().ok()?, digits[2..].parse::<i32>().ok()?);
+            return FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60)).map(EventZone::Fixed);
+        }
+        if zone.contains('/') && !zone.contains(char::is_whitespace) {
+            return Some(EventZone::Named(zone.to_owned()));
+        }
+        None
+    }
+
+    /// `dt` in ISO 8601, with an offset if it is known.
+    pub fn format(&self, dt: NaiveDateTime) -> String {
+        match self {
+            EventZone::Fixed(offset) => offset.from_local_datetime(&dt).unwrap().to_rfc3339(),
+            _ => dt.format("%Y-%m-%dT%H:%M:%S").to_string(),
+        }
+    }
+}
+
+impl fmt::Display for EventZone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EventZone::Floating => Ok(()),
+            EventZone::Named(name) => f.write_str(name),
+            EventZone::Fixed(offset) => write!(f, "{}", offset),
+        }
+    }
+}
+
+/// Describes either the coarse begin and end date of the event
+/// or holds a list of distinct `EventTime`s
+#[derive(Debug)]
+pub struct Event {
+    /// Begin of the event, as a calendar date where it takes place
+    pub begin: NaiveDate,
+
+    /// End of the event
+    pub end: Option<NaiveDate>,
+
+    /// Set of of times
+    pub times: Vec<EventTime>,
+
+    /// Zone of the `times`
+    pub zone: EventZone,
+}
+
+impl Event {
+    /// Start and end of every time on `begin`, an end before the start is on the next day.
+    pub fn slots(&self) -> Vec<(NaiveDateTime, NaiveDateTime)> {
+        self.times.iter()
+            .map(|time| {
+                let start = self.begin.and_time(time.start);
+                let mut end = self.begin.and_time(time.end);
+                if end < start {
+                    end += Duration::days(1);
+                }
+                (start, end)
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(end) = self.end { write!(f, "start: {}\nend:  {}", self.begin, end) }
+        else { write!(f, "start: {}", self.begin) }?;
+        if self.zone == EventZone::Floating { writeln!(f) } else { writeln!(f, " ({})", self.zone) }?;
+        for time in &self.times {
+            if time.start == time.end { writeln!(f, " * {}", time.start) }
+            else { writeln!(f, " * {} - {}", time.start, time.end) } ?
+        }
+        Ok(())
+    }
+}
+
+/// Something that has events
+pub trait HasEvents {
+    /// Produces an iCal calendar from this project.
+    fn to_ical(&self) -> Calendar;
+
+    /// Produces a list of `DateRange`s for the event.
+    fn events(&self) -> Option<Vec<Event>>;
+
+    /// Returns a list of `Event`s
+    fn times(&self,yaml: &Yaml) -> Option<Vec<EventTime>>;
+
+    /// Returns the location of the event
+    fn location(&self) -> FieldResult<&str>;
+
+    /// Zone the event times are given in, `Floating` if there is none.
+    fn timezone(&self) -> FieldResult<EventZone>;
+
+}
+
+/// Kind of value a field holds
+///
+/// Used to check values before they are written into a project file.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum FieldKind {
+    Text,
+    Integer,
+    Decimal,
+    Bool,
+    /// `dd.mm.yyyy`
+    Date,
+}
+
+impl FieldKind {
+    /// Checks `value` and turns it into a yaml scalar.
+    pub fn to_yaml(self, value: &str) -> FieldResult<String> {
+        let value = value.trim();
+        let valid = match self {
+            FieldKind::Text    => return Ok(quote_text(value)),
+            FieldKind::Integer => value.parse::<i64>().is_ok(),
+            FieldKind::Decimal => value.parse::<f64>().is_ok(),
+            FieldKind::Bool    => value == "true" || value == "false",
+            FieldKind::Date    => crate::util::yaml::parse_dmy_date(value).is_some(),
+        };
+
+        if valid {
+            Ok(value.to_owned())
+        } else {
+            Err(FieldError::Invalid(lformat!("{:?} is not a valid {:?}", value, self)))
+        }
+    }
+}
+
+/// Quotes text that yaml would otherwise read as something else.
+fn quote_text(value: &str) -> String {
+    if value.contains('\n') {
+        return value.to_owned();
+    }
+    match yaml_rust::YamlLoader::load_from_str(value).ok().as_ref().and_then(|docs| docs.first()) {
+        Some(Yaml::String(parsed)) if parsed == value && !value.contains(" #") => value.to_owned(),
+        _ => format!("{:?}", value)
+    }
+}
+
+/// Fields that can be changed with `asciii set`, other paths are refused.
+pub const SETTABLE_FIELDS: &[(&str, FieldKind)] = &[
+    ("format",                  FieldKind::Text),
+    ("manager",                 FieldKind::Text),
+    ("canceled",                FieldKind::Bool),
+    ("tax",                     FieldKind::Decimal),
+    ("currency",                FieldKind::Text),
+    ("follows",                 FieldKind::Text),
+    ("output_dir",              FieldKind::Text),
+    ("client/title",            FieldKind::Text),
+    ("client/first_name",       FieldKind::Text),
+    ("client/last_name",        FieldKind::Text),
+    ("client/email",            FieldKind::Text),
+    ("client/address",          FieldKind::Text),
+    ("event/name",              FieldKind::Text),
+    ("event/location",          FieldKind::Text),
+    ("event/description",       FieldKind::Text),
+    ("event/timezone",          FieldKind::Text),
+    ("offer/date",              FieldKind::Date),
+    ("offer/appendix",          FieldKind::Integer),
+    ("offer/generated_date",    FieldKind::Date),
+    ("offer/sent_date",         FieldKind::Date),
+    ("offer/accepted_date",     FieldKind::Date),
+    ("offer/valid_until",       FieldKind::Date),
+    ("offer/accepted_via",      FieldKind::Text),
+    ("offer/signed_copy",       FieldKind::Text),
+    ("invoice/number",          FieldKind::Integer),
+    ("invoice/date",            FieldKind::Date),
+    ("invoice/payed_date",      FieldKind::Date),
+    ("invoice/official",        FieldKind::Text),
+    ("invoice/generated_date",  FieldKind::Date),
+    ("invoice/sent_date",       FieldKind::Date),
+    ("invoice/paylink",         FieldKind::Text),
+    ("invoice/paylink_id",      FieldKind::Text),
+    ("hours/salary",            FieldKind::Decimal),
+    ("hours/tax",               FieldKind::Decimal),
+    ("hours/wages_date",        FieldKind::Date),
+    ("hours/staff",             FieldKind::Integer),
+];
+
+/// Settable fields whose value differs between two versions of a project file.
+pub fn changed_fields(before: &Yaml, after: &Yaml) -> Vec<&'static str> {
+    SETTABLE_FIELDS.iter()
+                   .map(|&(field, _)| field)
+                   .filter(|field| crate::util::yaml::get(before, field) != crate::util::yaml::get(after, field))
+                   .collect()
+}
+
+/// Ways a client can accept an offer, `offer/accepted_via`.
+pub const ACCEPTANCE_METHODS: &[&str] = &["signed", "email", "phone", "verbal"];
+
+/// Looks up the kind of a settable field, accepts `.` and `/` as separators.
+pub fn field_kind(path: &str) -> Option<FieldKind> {
+    let path = path.trim_matches('/').replace('.', "/");
+    SETTABLE_FIELDS.iter()
+                   .find(|(field, _)| *field == path)
+                   .map(|&(_, kind)| kind)
+}
@@ -1,15 +1,16 @@
 use std::str::FromStr;
 
 use bill::{Bill, Currency, Tax};
+use chrono::{NaiveDateTime, TimeZone};
 use icalendar::Event as CalEvent;
-use icalendar::{Component, Calendar};
-use anyhow::bail;
+use icalendar::{CalendarDateTime, Component, Calendar, Property};
 use yaml_rust::Yaml;
 
+use crate::error::AsciiiError;
 use super::*;
 use super::spec::*;
 use super::error::ValidationResult;
-use super::product::ProductError;
+use super::product::{PriceSource, ProductError};
 use super::yaml_provider::error::FieldResultExt;
 use crate::util::{self, to_currency};
 use crate::util::yaml::parse_dmy_date;
@@ -18,6 +19,15 @@ impl YamlProvider for Project {
     fn data(&self) -> &Yaml {
         self.yaml()
     }
+
+    fn data_for(&self, path: &str) -> &Yaml {
+        let section = path.split(['/', '.']).find(|s| !s.is_empty()).unwrap_or_default();
+        if LAZY_SECTIONS.contains(&section) {
+            self.yaml()
+        } else {
+            self.head()
+        }
+    }
 }
 
 impl IsProject for Project {
@@ -82,17 +92,17 @@ impl HasEvents for Project {
                     }
 
                     if let Some(end) = event.end {
-                        cal_event.start_date(event.begin);
-                        cal_event.end_date(end);
+                        cal_event.start_date(Date::<Utc>::from_utc(event.begin, Utc));
+                        cal_event.end_date(Date::<Utc>::from_utc(end, Utc));
                     } else {
-                        cal_event.all_day(event.begin);
+                        cal_event.all_day(Date::<Utc>::from_utc(event.begin, Utc));
                     }
 
                     cal_event.summary(self.name().unwrap_or("unnamed"));
                     calendar.push(cal_event);
 
                 } else {
-                    for time in &event.times {
+                    for (start, end) in event.slots() {
 
                         let mut cal_event = CalEvent::new();
                         cal_event.description(&self.long_desc());
@@ -100,15 +110,8 @@ impl HasEvents for Project {
                             cal_event.location(location);
                         }
 
-                        if let Some(end) = event.begin.and_time(time.end) {
-                            cal_event.ends(end);
-                        }
-
-                        if let Some(start) = event.begin.and_time(time.start) {
-                            cal_event.starts(start);
-                        }
-
-                        //cal_event.start_date(event.begin);
+                        cal_event.append_property(ical_time("DTSTART", start, &event.zone));
+                        cal_event.append_property(ical_time("DTEND", end, &event.zone));
 
                         cal_event.summary(self.name().unwrap_or("unnamed"));
                         calendar.push(cal_event);
@@ -136,9 +139,10 @@ impl HasEvents for Project {
                           .and_then(parse_dmy_date);
 
             Some(spec::Event {
-                     begin,
-                     end,
+                     begin: begin.naive_utc(),
+                     end: end.map(|end| end.naive_utc()),
                      times: self.times(h).unwrap_or_default(),
+                     zone: self.timezone().unwrap_or(EventZone::Floating),
                  })
         })
              .collect()
@@ -174,19 +178,40 @@ impl HasEvents for Project {
     fn location(&self) -> FieldResult<&str> {
         self.get_str("event.location")
     }
+
+    fn timezone(&self) -> FieldResult<EventZone> {
+        let zone = self.get_str("event.timezone")
+            .if_missing_try(|| Ok(crate::CONFIG.get_str_or("defaults/timezone").unwrap_or("")))?;
+        EventZone::parse(zone).ok_or_else(|| FieldError::invalid(&lformat!("unknown time zone {:?}", zone)))
+    }
+}
+
+/// `DTSTART` or `DTEND` at the local time `dt` of `zone`.
+fn ical_time(key: &str, dt: NaiveDateTime, zone: &EventZone) -> Property {
+    match zone {
+        EventZone::Floating => Property::new(key, &CalendarDateTime::from(dt).to_string()),
+        EventZone::Named(name) => Property::new(key, &dt.format("%Y%m%dT%H%M%S").to_string())
+            .add_parameter("TZID", name)
+            .done(),
+        EventZone::Fixed(offset) => {
+            let utc = offset.from_local_datetime(&dt).unwrap().with_timezone(&Utc);
+            Property::new(key, &CalendarDateTime::from(utc).to_string())
+        }
+    }
 }
 
 /// Returns a product from Service
-fn service_to_product<'a, T: HasEmployees>(s: &T) -> Result<Product<'a>, Error> {
+fn service_to_product<'a, T: HasEmployees>(s: &T) -> Result<Product<'a>, AsciiiError> {
     if let Ok(salary) = s.salary() {
         Ok(Product {
                  name: "Service",
                  unit: Some("h"),
                  tax: s.tax().ok().unwrap_or_else(|| Tax::new(0.0)),
                  price: salary,
+                 source: PriceSource::Catalog,
              })
     } else {
-        bail!(ProductError::InvalidServerSection)
+        Err(ProductError::InvalidServerSection.into())
     }
 }
 
@@ -205,17 +230,50 @@ impl Redeemable for Project {
         self.get_f64("tax").map(Tax::new)
     }
 
-    fn bills(&self) -> Result<(Bill<Product<'_>>, Bill<Product<'_>>), Error> {
+    fn bills(&self) -> Result<(Bill<Product<'_>>, Bill<Product<'_>>), AsciiiError> {
+        let lines = match self.bill_items.get() {
+            Some(lines) => lines,
+            None => {
+                let lines = self.read_bill_lines()?;
+                self.bill_items.get_or_init(|| lines)
+            }
+        };
+
         let mut offer: Bill<Product<'_>> = Bill::new();
         let mut invoice: Bill<Product<'_>> = Bill::new();
+        for line in lines {
+            if line.offered.is_normal() {
+                offer.add_item(line.offered, line.product());
+            }
+            if line.sold.is_normal() {
+                invoice.add_item(line.sold, line.product());
+            }
+        }
+        Ok((offer, invoice))
+    }
+}
+
+impl Project {
+    /// The service and the products, as offered and as sold.
+    fn read_bill_lines(&self) -> Result<Vec<BillLine>, AsciiiError> {
+        let _timer = util::timing::start("bills");
+        let mut lines = Vec::new();
+        let line = |item: &BillItem<Product<'_>>, sold: f64| BillLine {
+            name: item.product.name.to_owned(),
+            unit: item.product.unit.map(ToOwned::to_owned),
+            tax: item.product.tax,
+            price: item.product.price,
+            source: item.product.source,
+            offered: item.amount,
+            sold,
+        };
 
         let service = service_to_product(&self.hours())?;
        //  .("cannot create product from employees, salary or tax missing");
 
         if let Some(total) = self.hours().total_time() {
             if total.is_normal() {
-                offer.add_item(total, service);
-                invoice.add_item(total, service);
+                lines.push(line(&BillItem { amount: total, product: service }, total));
             }
         }
 
@@ -225,17 +283,19 @@ impl Redeemable for Project {
 
         // let document_tax =  // TODO: activate this once the tax no longer 19%
 
+        // negotiated rates beat the cataloge
+        let known_client = self.known_client();
         for (desc, values) in raw_products {
             let (offer_item, invoice_item) = self.item_from_desc_and_value(desc, values)?;
-            if offer_item.amount.is_normal() {
-                offer.add(offer_item);
-            }
-            if invoice_item.amount.is_normal() {
-                invoice.add(invoice_item);
+            let mut line = line(&offer_item, invoice_item.amount);
+            if let Some(price) = known_client.and_then(|client| client.price_of(&line.name)) {
+                line.price = price;
+                line.source = PriceSource::Client;
             }
+            lines.push(line);
         }
 
-        Ok((offer, invoice))
+        Ok(lines)
     }
 }
 
@@ -247,6 +307,7 @@ impl Validatable for Project {
         validation.require_field("date", self.event_date());
         validation.require_field("manager", self.responsible());
         validation.require_field("format", self.format());
+        validation.validate_field("timezone", self.timezone());
 
         validation
     }
@@ -273,6 +334,10 @@ impl<'a> YamlProvider for Client<'a> {
     fn data(&self) -> &Yaml {
         self.inner.data()
     }
+
+    fn data_for(&self, path: &str) -> &Yaml {
+        self.inner.data_for(path)
+    }
 }
 
 impl<'a> IsClient for Client<'a> {
@@ -360,6 +425,10 @@ impl<'a> YamlProvider for Offer<'a> {
     fn data(&self) -> &Yaml {
         self.inner.data()
     }
+
+    fn data_for(&self, path: &str) -> &Yaml {
+        self.inner.data_for(path)
+    }
 }
 
 impl<'a> Offerable for Offer<'a> {
@@ -381,6 +450,42 @@ impl<'a> Offerable for Offer<'a> {
         // old spec
         .if_missing_try(|| self.get_str("manumber").map(ToString::to_string))
     }
+
+    fn generated_date(&self) -> FieldResult<Date<Utc>> {
+        self.get_dmy("offer.generated_date")
+    }
+
+    fn sent_date(&self) -> FieldResult<Date<Utc>> {
+        self.get_dmy("offer.sent_date")
+    }
+
+    fn accepted_date(&self) -> FieldResult<Date<Utc>> {
+        self.get_dmy("offer.accepted_date")
+    }
+
+    fn accepted_via(&self) -> FieldResult<&str> {
+        self.get_str("offer.accepted_via")
+            .and_then(|method| if ACCEPTANCE_METHODS.contains(&method) {
+                Ok(method)
+            } else {
+                Err(FieldError::Invalid(lformat!("{:?} is none of {}", method, ACCEPTANCE_METHODS.join(", "))))
+            })
+    }
+
+    fn signed_copy(&self) -> FieldResult<&str> {
+        self.get_str("offer.signed_copy")
+    }
+
+    fn valid_until(&self) -> FieldResult<Date<Utc>> {
+        self.get_dmy("offer.valid_until")
+            .if_missing_try(|| {
+                let days = crate::CONFIG.get("defaults/offer_validity")
+                                        .and_then(Yaml::as_i64)
+                                        .filter(|days| *days > 0)
+                                        .ok_or(FieldError::Missing)?;
+                Offerable::date(self).map(|date| date + Duration::days(days))
+            })
+    }
 }
 
 impl<'a> Validatable for Offer<'a> {
@@ -390,6 +495,8 @@ impl<'a> Validatable for Offer<'a> {
         validation.require_field("offer.date", self.date());
         validation.require_field("manager", self.inner.responsible());
         validation.require_field("appendix", self.appendix());
+        validation.validate_field("offer.valid_until", self.get_dmy("offer.valid_until"));
+        validation.validate_field("offer.accepted_via", self.accepted_via());
 
         validation
     }
@@ -399,6 +506,10 @@ impl<'a> YamlProvider for Invoice<'a> {
     fn data(&self) -> &Yaml {
         self.inner.data()
     }
+
+    fn data_for(&self, path: &str) -> &Yaml {
+        self.inner.data_for(path)
+    }
 }
 
 impl<'a> Invoicable for Invoice<'a> {
@@ -427,6 +538,18 @@ impl<'a> Invoicable for Invoice<'a> {
     fn official(&self) -> FieldResult<String> {
         self.get_str("invoice.official").map(ToOwned::to_owned)
     }
+
+    fn generated_date(&self) -> FieldResult<Date<Utc>> {
+        self.get_dmy("invoice.generated_date")
+    }
+
+    fn sent_date(&self) -> FieldResult<Date<Utc>> {
+        self.get_dmy("invoice.sent_date")
+    }
+
+    fn paylink(&self) -> FieldResult<&str> {
+        self.get_str("invoice.paylink")
+    }
 }
 
 impl<'a> Validatable for Invoice<'a> {
@@ -444,6 +567,10 @@ impl<'a> YamlProvider for Hours<'a> {
     fn data(&self) -> &Yaml {
         self.inner.data()
     }
+
+    fn data_for(&self, path: &str) -> &Yaml {
+        self.inner.data_for(path)
+    }
 }
 
 impl<'a> HasEmployees for Hours<'a> {
@@ -525,6 +652,25 @@ impl<'a> HasEmployees for Hours<'a> {
             .collect::<FieldResult<Vec<Employee>>>()
     }
 
+    fn staff(&self) -> FieldResult<u32> {
+        self.get_int("hours.staff").map(|staff| staff.max(0) as u32)
+    }
+
+    fn assigned(&self) -> Vec<String> {
+        let employees = self.get_hash("hours.caterers")
+                            .or_else(|_| self.get_hash("hours.employees"));
+        employees.map(|employees| {
+                     employees.keys()
+                              .filter_map(Yaml::as_str)
+                              .map(str::trim)
+                              // placeholder of the default template
+                              .filter(|name| !name.is_empty() && !name.eq_ignore_ascii_case("unknown"))
+                              .map(ToOwned::to_owned)
+                              .collect()
+                 })
+                 .unwrap_or_default()
+    }
+
     fn employees_payed(&self) -> bool {
         self.employees().is_err() || self.wages_date().is_ok()
     }
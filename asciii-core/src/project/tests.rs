@@ -0,0 +1,643 @@
+#![allow(unused_imports, dead_code)]
+use std::path::Path;
+use crate::project::spec::*;
+use crate::project::Project;
+use crate::storage::Storable;
+
+fn parse_project(yaml: &str) -> Project{
+  Project::from_file_content(yaml).unwrap()
+}
+
+#[test]
+fn compare_basics(){
+    println!("{:?}", ::std::env::current_dir());
+    let new_project = Project::open_file(Path::new("../tests/test_projects/current.yml")).unwrap();
+    let old_project = Project::open_file(Path::new("../tests/test_projects/old.yml")).unwrap();
+    //let config = &::CONFIG;
+
+    assert_eq!(old_project.name(),
+                new_project.name());
+
+    assert_eq!(old_project.offer().number(),
+                new_project.offer().number());
+
+    //assert_eq!(old_project.offer().date(), // old format had no offer date
+    //           new_project.offer().date());
+
+    assert_eq!(old_project.invoice().number_str(),
+                new_project.invoice().number_str());
+
+    assert_eq!(old_project.invoice().date(),
+                new_project.invoice().date());
+
+    assert_eq!(old_project.payed_date(),
+                new_project.payed_date());
+
+    assert_eq!(old_project.client().title(),
+                new_project.client().title());
+
+    assert_eq!(old_project.client().last_name(),
+                new_project.client().last_name());
+
+    assert_eq!(old_project.client().addressing(),
+                new_project.client().addressing());
+
+    assert_eq!(old_project.client().address(),
+                new_project.client().address());
+}
+
+
+
+pub mod client {
+  use super::*;
+
+  #[test]
+  fn validate_stage1() {
+      let doc = r#"
+      client:
+        title:      Herr # Frau, Professor, Professorin
+        first_name: Graf
+        last_name:  Zahl
+        email: this.man@example.com
+        address: |
+          Graf Zahl
+          Nummernhöllenstraße 666
+          01234 Countilvania
+      "#;
+
+      assert_eq!(parse_project(doc).client().validate().missing_fields, Vec::<String>::new());
+  }
+
+  #[test]
+  fn missing_address() {
+      let doc = r#"
+      client:
+        title:      Herr # Frau, Professor, Professorin
+        first_name: Graf
+        last_name:  Zahl
+        email: this.man@example.com
+      "#;
+      assert_eq!(
+          parse_project(doc).client().missing_fields(),
+          vec!["client/address".to_string()]
+      );
+  }
+
+  #[test]
+  fn missing_title() {
+      let doc = r#"
+      client:
+        first_name: Graf
+        last_name:  Zahl
+        email: this.man@example.com
+        address: |
+          Graf Zahl
+          Nummernhöllenstraße 666
+          01234 Countilvania
+      "#;
+      assert_eq!(
+        parse_project(doc).client().missing_fields(),
+        vec![
+          "client/title".to_string(),
+          "client_addressing".to_string(),
+        ]
+      );
+  }
+
+  #[test]
+  fn missing_last_name() {
+      let doc = r#"
+      client:
+        title:      Herr # Frau, Professor, Professorin
+        first_name: Graf
+        email: this.man@example.com
+        address: |
+          Graf Zahl
+          Nummernhöllenstraße 666
+          01234 Countilvania
+      "#;
+      assert_eq!(
+        parse_project(doc).client().missing_fields(),
+        vec![
+          "client/last_name",
+          "client_addressing"
+        ]
+      );
+  }
+
+}
+
+
+
+pub mod offer {
+  use super::*;
+
+  #[test]
+  fn validate_stage2() {
+      let doc = r#"
+      offer:
+        date: 07.11.2014
+        appendix: 1
+      manager: somebody
+      "#;
+
+      let errors = parse_project(doc).offer().missing_fields();
+      assert_eq!(errors, Vec::<String>::new());
+  }
+
+}
+
+
+pub mod invoice {
+  use super::*;
+
+  #[test]
+  fn validate_stage3() {
+      let doc = r#"
+      invoice:
+        number: 41
+        date: 06.12.2014
+        payed_date: 08.12.2014
+      "#;
+
+      let errors = parse_project(doc).invoice().missing_fields();
+      assert_eq!(errors, Vec::<String>::new());
+  }
+
+
+  #[test]
+  fn validate_stage3_missing_date() {
+      let doc = r#"
+        invoice:
+          number: 41
+          payed_date: 08.12.2014
+        "#;
+
+      let errors = parse_project(doc).invoice().missing_fields();
+      assert_eq!(
+          errors,
+          vec!["invoice.date"]
+      );
+  }
+  #[test]
+  fn diagnostics() {
+      use crate::project::error::Severity;
+      let doc = r#"
+        invoice:
+          number: 41
+          payed_date: 32.12.2014
+        "#;
+
+      let diagnostics = parse_project(doc).diagnostics();
+      let invoice = diagnostics.iter()
+                               .filter(|d| d.stage == "invoice")
+                               .map(|d| (d.field.as_str(), d.severity))
+                               .collect::<Vec<_>>();
+      assert_eq!(invoice, vec![("invoice.date", Severity::Warning)]);
+      assert!(diagnostics.iter().any(|d| d.stage == "archive" && d.severity == Severity::Error));
+  }
+
+  #[test]
+  fn unaccepted_offer() {
+      let doc = "offer:\n  sent_date: 01.11.2014\ninvoice:\n  generated_date: 03.12.2014\n";
+      let project = parse_project(doc);
+      assert_eq!(project.document_state().map(|(_, date)| date.format("%d.%m.%Y").to_string()), Some("03.12.2014".into()));
+      assert!(project.diagnostics().iter().any(|d| d.field == "offer/accepted_date"));
+
+      let accepted = parse_project(&project.content_with_fields(&[("offer/accepted_date", "02.11.2014")]).unwrap());
+      assert!(accepted.unaccepted_offer().is_none());
+      assert!(!accepted.diagnostics().iter().any(|d| d.field.starts_with("offer/")));
+  }
+
+  #[test]
+  fn offer_validity() {
+      use chrono::{TimeZone, Utc};
+      let project = parse_project("offer:\n  date: 01.11.2014\n");
+      assert_eq!(project.offer().valid_until().ok(), Some(Utc.ymd(2014, 12, 1)));
+      assert_eq!(project.expired_offer(Utc.ymd(2014, 12, 1)), None);
+      assert_eq!(project.expired_offer(Utc.ymd(2014, 12, 2)), Some(Utc.ymd(2014, 12, 1)));
+
+      let explicit = parse_project("offer:\n  date: 01.11.2014\n  valid_until: 15.11.2014\n");
+      assert_eq!(explicit.expired_offer(Utc.ymd(2014, 11, 20)), Some(Utc.ymd(2014, 11, 15)));
+
+      let accepted = parse_project("offer:\n  date: 01.11.2014\n  accepted_date: 10.11.2014\n");
+      assert!(!accepted.has_open_offer());
+      assert_eq!(accepted.expired_offer(Utc.ymd(2015, 1, 1)), None);
+  }
+
+  #[test]
+  fn offer_acceptance() {
+      use crate::project::validate::acceptance;
+      assert_eq!(acceptance(&parse_project("offer:\n  date: 01.11.2014\n")).missing_fields, vec!["offer.accepted_date"]);
+      assert!(acceptance(&parse_project("offer:\n  accepted_date: 10.11.2014\n  accepted_via: email\n")).is_ok());
+
+      let signed = parse_project("offer:\n  accepted_date: 10.11.2014\n  accepted_via: signed\n");
+      assert_eq!(acceptance(&signed).missing_fields, vec!["offer.signed_copy"]);
+      let not_attached = parse_project("offer:\n  accepted_date: 10.11.2014\n  signed_copy: nowhere.pdf\n");
+      assert_eq!(acceptance(&not_attached).validation_errors.len(), 1);
+
+      let unknown = parse_project("offer:\n  accepted_date: 10.11.2014\n  accepted_via: telepathy\n");
+      assert!(unknown.offer().accepted_via().is_err());
+      assert!(unknown.offer().validate().validation_errors.iter().any(|error| error.contains("telepathy")));
+  }
+}
+
+#[cfg(feature = "serialization")]
+pub mod export {
+  use super::*;
+  use crate::project::export::{Complete, ExportTarget};
+
+  #[test]
+  fn complete_round_trips_through_json() {
+      let project = Project::open_file(Path::new("../tests/test_projects/current.yml")).unwrap();
+      let exported: Complete = project.export();
+      let json = serde_json::to_string(&exported).unwrap();
+      let parsed: Complete = serde_json::from_str(&json).unwrap();
+      assert_eq!(parsed, exported);
+      assert_eq!(parsed.client.last_name, exported.client.last_name);
+  }
+
+  #[test]
+  fn yaml_is_the_same_as_json() {
+      let project = Project::open_file(Path::new("../tests/test_projects/current.yml")).unwrap();
+      let from_yaml: Complete = serde_yaml::from_str(&project.to_yaml().unwrap()).unwrap();
+      let from_json: Complete = serde_json::from_str(&project.to_json().unwrap()).unwrap();
+      assert_eq!(from_yaml, from_json);
+  }
+
+  #[test]
+  fn client_rates_beat_the_cataloge() {
+      use std::sync::Arc;
+      use crate::project::export::Bills;
+      use crate::storage::clients::ClientDatabase;
+
+      let doc = "client:\n  first_name: Graf\n  last_name: Zahl\ncataloge:\n  product: &tee { name: Tee, price: 1.5, unit: 1l }\n\
+                 products:\n  *tee:\n    amount: 4\n  Kaffee: { amount: 1, price: 2.0 }\nhours:\n  salary: 8.0\n";
+      let clients = ClientDatabase::parse("- name: graf zahl\n  prices:\n    Tee: 1.0\n").unwrap();
+      let mut project = Project::new(Default::default(), doc.into());
+      project.set_client_database(Arc::new(clients));
+      assert_eq!(project.known_client().map(|client| client.name.as_str()), Some("graf zahl"));
+
+      let (offer, _) = project.bills().unwrap();
+      assert_eq!(offer.gross_total(), crate::util::to_currency(4.0 * 1.0 + 2.0));
+      let bills: Bills = project.export();
+      let sources = bills.offer.iter().map(|p| (p.name.as_str(), p.price_source.as_str())).collect::<Vec<_>>();
+      assert!(sources.contains(&("Tee", "client")), "{:?}", sources);
+      assert!(sources.contains(&("Kaffee", "catalog")), "{:?}", sources);
+
+      // without the database it is the cataloge again
+      let unknown = Project::new(Default::default(), doc.into());
+      let (offer, _) = unknown.bills().unwrap();
+      assert_eq!(offer.gross_total(), crate::util::to_currency(4.0 * 1.5 + 2.0));
+  }
+}
+
+pub mod fields {
+  use super::*;
+
+  #[test]
+  fn settable_fields() {
+      assert_eq!(field_kind("invoice/payed_date"), Some(FieldKind::Date));
+      assert_eq!(field_kind("invoice.number"), Some(FieldKind::Integer));
+      assert_eq!(field_kind("invoice/bogus"), None);
+  }
+
+  #[test]
+  fn check_values() {
+      assert_eq!(FieldKind::Date.to_yaml("08.12.2014"), Ok("08.12.2014".into()));
+      assert!(FieldKind::Date.to_yaml("32.12.2014").is_err());
+      assert!(FieldKind::Integer.to_yaml("4.1").is_err());
+      assert!(FieldKind::Bool.to_yaml("yes").is_err());
+      assert_eq!(FieldKind::Text.to_yaml("Graf Zahl"), Ok("Graf Zahl".into()));
+      assert_eq!(FieldKind::Text.to_yaml("42"), Ok("\"42\"".into()));
+      assert_eq!(FieldKind::Text.to_yaml("a: b"), Ok("\"a: b\"".into()));
+  }
+
+  #[test]
+  fn patches_are_merged() {
+      let doc = "event:\n  name: Party\nproducts:\n  Tee:\n    amount: 2\n    price: 1.5\nhours:\n  salary: 8.0\n  caterers:\n    Max: 2\ntax: 0.19\n";
+      let patch = "products:\n  Kaffee:\n    amount: 3\n    price: 2.0\nhours:\n  caterers:\n    Ida: 3\ntax: 0.07\n";
+      let patched = parse_project(&parse_project(doc).content_with_patch(patch).unwrap());
+      assert_eq!(patched.field("products/Tee/amount"), Some("2".into()));
+      assert_eq!(patched.field("products/Kaffee/amount"), Some("3".into()));
+      assert_eq!(patched.field("hours/caterers/Max"), Some("2".into()));
+      assert_eq!(patched.field("hours/caterers/Ida"), Some("3".into()));
+      assert_eq!(patched.field("tax"), Some("0.07".into()));
+
+      assert!(parse_project(doc).content_with_patch("invoice:\n  date: tomorrow\n").is_err());
+  }
+
+  #[test]
+  fn duplicate_resets_fields() {
+      let doc = "created: 03.11.2014\nevent:\n  name: Party\noffer:\n  date: 07.11.2014\n  appendix: 1\ninvoice:\n  number: 41\n  payed_date: 08.12.2014\n";
+      let copy = parse_project(&parse_project(doc).duplicate_content("Party Harder").unwrap());
+      assert_eq!(copy.field("event/name"), Some("Party Harder".into()));
+      assert_eq!(copy.field("offer/appendix"), Some("1".into()));
+      assert_eq!(copy.field("offer/date"), None);
+      assert_eq!(copy.field("invoice/number"), None);
+      assert_eq!(copy.field("invoice/payed_date"), None);
+      assert_ne!(copy.field("created"), Some("03.11.2014".into()));
+  }
+
+  #[test]
+  fn parses_lazily() {
+      let doc = "event:\n  name: Party\ncataloge:\n  product: &tee { name: Tee, price: 1.5, unit: 1l }\nproducts:\n  *tee:\n    amount: 2\nhours:\n  salary: 8.0\n  caterers:\nmanager: Max\n";
+      let project = Project::new(Default::default(), doc.into());
+      assert_eq!(project.name(), Ok("Party"));
+      assert_eq!(project.field("manager"), Some("Max".into()));
+      assert!(project.yaml.get().is_none());
+
+      assert_eq!(project.field("products/*tee/amount"), None);
+      assert!(project.yaml.get().is_some());
+      assert!(project.sum_sold().is_ok());
+  }
+
+  #[test]
+  fn bills_are_read_once() {
+      let doc = "event:\n  name: Party\ntax: 0.19\ncataloge:\n  product: &tee { name: Tee, price: 1.5, unit: 1l }\nproducts:\n  *tee:\n    amount: 4\n    sold: 2\n  Kaffee: { amount: 1, price: 2.0 }\nhours:\n  salary: 8.0\n  caterers:\n    Ernie: 2\n";
+      let project = Project::new(Default::default(), doc.into());
+      assert!(project.bill_items.get().is_none());
+
+      let (offer, invoice) = project.bills().unwrap();
+      assert_eq!(project.bill_items.get().map(Vec::len), Some(3));
+      assert_eq!(offer.gross_total(), crate::util::to_currency(2.0 * 8.0 + 4.0 * 1.5 + 2.0));
+      assert_eq!(invoice.gross_total(), crate::util::to_currency(2.0 * 8.0 + 2.0 * 1.5 + 2.0));
+
+      let (offer_again, invoice_again) = project.bills().unwrap();
+      assert_eq!(offer_again.net_total(), offer.net_total());
+      assert_eq!(invoice_again.gross_total(), invoice.gross_total());
+      assert_eq!(invoice.as_items_with_tax().iter().map(|(_, item)| item.product.unit).collect::<Vec<_>>(),
+                 invoice_again.as_items_with_tax().iter().map(|(_, item)| item.product.unit).collect::<Vec<_>>());
+  }
+
+  #[test]
+  fn registered_computed_fields() {
+      use crate::project::computed_field;
+      use crate::project::export::{Extras, ExportTarget};
+
+      let project = parse_project("event:\n  name: Party\nclient:\n  last_name: Zahl\n");
+      computed_field::register("shout", |p| p.field("event/name").map(|name| name.to_uppercase()));
+      computed_field::register("Name", |_| Some("not built-in".into()));
+      assert_eq!(project.field("shout"), Some("PARTY".into()));
+      assert_eq!(project.field("Name"), Some("Party".into()));
+      assert!(computed_field::custom_field_names().contains(&"shout".to_owned()));
+
+      let extras: Extras = project.export();
+      assert_eq!(extras.custom.get("shout").map(String::as_str), Some("PARTY"));
+
+      assert!(computed_field::unregister("shout"));
+      assert!(computed_field::unregister("Name"));
+      assert!(!computed_field::unregister("shout"));
+      assert_eq!(project.field("shout"), None);
+  }
+
+  #[test]
+  fn payment_due_two_weeks_after_invoice() {
+      use chrono::prelude::*;
+
+      let project = parse_project("invoice:\n  number: 1\n  date: 15.03.2024\n");
+      // no days off are configured by default, not even Good Friday
+      assert_eq!(project.payment_due(), Some(Utc.ymd(2024, 3, 29)));
+      assert_eq!(parse_project("event:\n  name: Party\n").payment_due(), None);
+  }
+
+  #[test]
+  fn event_times_keep_their_zone() {
+      let party = |zone: &str| parse_project(&format!(
+          "event:\n  name: Party\n  timezone: \"{}\"\n  dates:\n  - begin: 14.06.2024\n    times:\n    - begin: \"22:00\"\n      end: \"02:00\"\n",
+          zone));
+
+      assert_eq!(EventZone::parse("Europe/Berlin"), Some(EventZone::Named("Europe/Berlin".into())));
+      assert_eq!(EventZone::parse("+0530").unwrap().to_string(), "+05:30");
+      assert_eq!(EventZone::parse("berlin"), None);
+
+      let berlin = party("+02:00");
+      let events = berlin.events().unwrap();
+      let (start, end) = events[0].slots()[0];
+      assert_eq!(events[0].zone.format(start), "2024-06-14T22:00:00+02:00");
+      assert_eq!(events[0].zone.format(end), "2024-06-15T02:00:00+02:00");
+
+      // fixed offsets end up in UTC, named zones are left to the calendar
+      let ical = berlin.to_ical().to_string();
+      assert!(ical.contains("DTSTART:20240614T200000Z"), "{}", ical);
+      assert!(ical.contains("DTEND:20240615T000000Z"), "{}", ical);
+      let ical = party("Europe/Berlin").to_ical().to_string();
+      assert!(ical.contains("DTSTART;TZID=Europe/Berlin:20240614T220000"), "{}", ical);
+
+      assert!(party("somewhere").timezone().is_err());
+  }
+}
+
+/*
+
+mod product {
+  use super::*;
+
+  static PRODUCT_TEST_DOC_VALID: &'static str = r#"
+  ---
+  cataloge:
+    product: &coffee    { name: Kaffee, price: 2.5, unit: 1l  }
+    product: &tea       { name: Tee,    price: 2.5, unit: 1l  }
+    product: &water     { name: Wasser, price: 2.5, unit: 1l  }
+
+  products:
+    *coffee: { amount: 5 }
+    *tea: { amount: 6, sold: 2 }
+    *water:
+      amount: 6
+      returned: 4
+  ...
+  "#;
+
+
+
+
+
+  //  #[test]
+  //  fn validate_products() {
+  //      let doc = yaml::parse(PRODUCT_TEST_DOC_VALID).unwrap();
+  //
+  //      println!("{:#?}", doc);
+  //      let products = spec::products::invoice_items(&doc).unwrap();
+  //      println!("Products {:#?}", products);
+  //      assert_eq!(products[0].product.name, "Kaffee");
+  //      assert_eq!(products[0].amount_offered, 5f64);
+  //      assert_eq!(products[0].amount_sold, 5f64);
+  //      assert_eq!(products[0].cost_sold_before_tax(),
+  //                Currency(Some('€'), 12_50));
+  //      assert_eq!(products[0].cost_sold_after_tax(),
+  //                Currency(Some('€'), 14_88));
+  //
+  //      assert_eq!(products[1].product.name, "Tee");
+  //      assert_eq!(products[1].amount_offered, 6f64);
+  //      assert_eq!(products[1].amount_sold, 2f64);
+  //
+  //      assert_eq!(products[2].product.name, "Wasser");
+  //      assert_eq!(products[2].amount_offered, 6f64);
+  //      assert_eq!(products[2].amount_sold, 2f64);
+  //  }
+
+
+
+
+
+
+
+
+  static PRODUCT_CATALOG: &'static str = r#"
+  cataloge:
+    product: &apfelsaft    { name: Apfelsaft,       price: 1.64,  unit: 1l  }
+    product: &orangensaft  { name: Orangensaft,     price: 1.86,  unit: 1l  }
+    product: &broetchen    { name: halbe Brötchen,  price: 1.16,  unit: stk }
+    product: &gluehwein    { name: Glühwein,        price: 1.60,  unit: 1l  }
+    product: &kaffee       { name: Kaffee,          price: 2.5,   unit: 1l  }
+    product: &tee          { name: Tee,             price: 2.5,   unit: 1l  }
+    product: &kekse        { name: Kekse,           price: 3.40,  unit: 400g}
+    product: &kinderpunsch { name: Punsch,          price: 1.60,  unit: 1l  }
+    product: &salzgebaeck  { name: Salzgebäck,      price: 3.50,  unit: 400g}
+    product: &wasser_gross { name: Mineralwasser,   price: 0.61,  unit: 1l  }
+  "#;
+
+  static PRODUCT_TEST_SUM_UP1: &'static str = r#"
+  products:
+    *kaffee: { amount: 20 }
+    *wasser_gross: { amount: 100 }
+    *apfelsaft: { amount: 15 }
+    *orangensaft: { amount: 15 }
+    *kekse: { amount: 5 }
+  "#;
+
+
+  static PRODUCT_TEST_SUM_UP2: &'static str = r#"
+  products:
+    *kinderpunsch: { amount: 10 } # 10 * 1.60
+    *gluehwein: { amount: 15 }    # 15 * 1.60
+    *kekse: { amount: 3 }         #  3 * 3.40
+    *salzgebaeck: { amount: 3 }   #  3 * 3.50
+  "#;
+
+
+  static PRODUCT_TEST_SUM_UP3: &'static str = r#"
+  products:
+    *wasser_gross: { amount:  8 } # 0.61 *  8 =  4.88
+    *apfelsaft:    { amount:  4 } # 1.64 *  4 =  6.56
+    *orangensaft:  { amount:  4 } # 1.86 *  4 =  7.44
+    *broetchen:    { amount: 40 } # 1.16 * 40 = 46.40
+                                            # 65.28
+  "#;
+
+  static PRODUCT_TEST_SUM_UP4: &'static str = r#"
+
+  products:
+    *kaffee: { amount: 5 }        # 5 * 2.50 = 12.50
+    *tee: { amount: 1 }           # 1 * 2.50 =  2.50
+    *wasser_gross: { amount: 4 }  # 4 * 0.61 =  2.44
+    *orangensaft: { amount: 2 }   # 2 * 1.86 =  3.72
+    *apfelsaft: { amount: 2 }     # 2 * 1.64 =  3.28
+    *kekse: { amount: 1 }         # 1 * 3.40 =  3.40
+                                  #            27.84 aber 27.09
+  "#;
+
+  //  fn compare_sum(doc_string: &str, want: i64) {
+  //      let doc_string_plus_catalogue = [PRODUCT_CATALOG, doc_string].join("\n");
+  //
+  //      let doc = yaml::parse(&doc_string_plus_catalogue).unwrap();
+  //      let products = spec::products::invoice_items(&doc).unwrap();
+  //      let sum_offered = spec::products::sum_offered(&products);
+  //      let sum_sold = spec::products::sum_sold(&products);
+  //
+  //      // assert_eq!(sum_sold.1, 334_79);
+  //      assert_eq!(sum_sold.1, want);
+  //  }
+
+  //  #[test]
+  //  fn check_prices() {
+  //      let doc = yaml::parse(&[PRODUCT_CATALOG, PRODUCT_TEST_SUM_UP1].join("\n")).unwrap();
+  //      let products = spec::products::invoice_items(&doc).unwrap();
+  //      assert_eq!(products[0].product.name, "Apfelsaft");
+  //      assert_eq!(products[0].product.price.1, 164);
+  //      assert_eq!(products[1].product.name, "Kaffee");
+  //      assert_eq!(products[2].product.name, "Kekse");
+  //
+  //      let odd_prices = r#"products: { *broetchen:    { amount: 40 } } "#;
+  //
+  //      let doc = yaml::parse(&[PRODUCT_CATALOG, odd_prices].join("\n")).unwrap();
+  //      let products = spec::products::invoice_items(&doc).unwrap();
+  //      assert_eq!(products[0].product.name, "halbe Brötchen");
+  //      assert_eq!(products[0].product.price.1, 116);
+  //  }
+  //
+  //   #[test]
+  //   fn sum_up_products1() {
+  //       compare_sum(PRODUCT_TEST_SUM_UP1, 180_50);
+  //   }
+  //   #[test]
+  //   fn sum_up_products2() {
+  //       compare_sum(PRODUCT_TEST_SUM_UP2, 60_70);
+  //   }
+  //   #[test]
+  //   fn sum_up_products3() {
+  //       compare_sum(PRODUCT_TEST_SUM_UP3, 65_28);
+  //   }
+    // #[test] fn sum_up_products4(){ compare_sum(PRODUCT_TEST_SUM_UP4, 27_09); }
+
+  static PRODUCT_TEST_DOC_INVALID1: &'static str = r#"
+  --- # sold and returend
+  cataloge:
+    product: &tea       { name: Tee,    price: 2.5, unit: 1l  }
+  products:
+    *tea: { amount: 6, sold: 2, returned: 4 }
+  ...
+  "#;
+
+  static PRODUCT_TEST_DOC_INVALID2: &'static str = r#"
+  --- # returning too much
+  cataloge:
+    product: &tea { name: Tee, price: 2.5, unit: 1l }
+  products:
+    *tea: { amount: 6, returned: 7 }
+  ...
+  "#;
+
+  static PRODUCT_TEST_DOC_INVALID3: &'static str = r#"
+  --- # returning too much
+  cataloge:
+    product: &tea { name: Tee, price: 2.5, unit: 1l }
+  products:
+    *tea: { returned: 7 }
+  ...
+  "#;
+
+//  #[test]
+//  fn validate_invalid_products() {
+//      println!("canary");
+//      let invalid1 = yaml::parse(PRODUCT_TEST_DOC_INVALID1).unwrap();
+//      let invalid2 = yaml::parse(PRODUCT_TEST_DOC_INVALID2).unwrap();
+//      let invalid3 = yaml::parse(PRODUCT_TEST_DOC_INVALID3).unwrap();
+//      assert_eq!(spec::products::invoice_items(&invalid1).unwrap_err(),
+//                ProductError::AmbiguousAmounts("Tee".to_owned()));
+//      assert_eq!(spec::products::invoice_items(&invalid2).unwrap_err(),
+//                ProductError::TooMuchReturned("Tee".to_owned()));
+//      assert_eq!(spec::products::invoice_items(&invalid3).unwrap_err(),
+//                ProductError::MissingAmount("Tee".to_owned()));
+//  }
+
+
+//   #[test]
+//   fn rounding() {
+//       static PRODUCT_TEST_DOC_INVALID3: &'static str = r#"
+//   products:
+//     gutschein5: { amount: 8, sold: 8, price: 4.20 }
+//     gutschein7: { amount: 1, sold: 1, price: 5.90 }
+//   "#;
+// 
+//       let doc = yaml::parse(PRODUCT_TEST_DOC_INVALID3).unwrap();
+//       let products = spec::products::invoice_items(&doc).unwrap();
+//       let sum_offered = spec::products::sum_offered(&products);
+//       let sum_sold = spec::products::sum_sold(&products);
+//       assert_eq!(sum_sold * 1.19, Currency(Some('€'), 4701))
+//   }
+
+}
+
+*/
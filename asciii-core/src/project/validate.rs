@@ -0,0 +1,367 @@
+//! Checks whether a project is ready for an offer, an invoice or the archive.
+//!
+//! The active checks are a `Validate` implementation, by default `Configured`:
+//! the built-in checks plus the rules under `validation/rules` in the config, e.g.
+//!
+//! ```yaml
+//! validation:
+//!   rules:
+//!     - stage: invoice
+//!       require: [client/purchase_order]
+//!       when: { client/last_name: Zahl }
+//!     - stage: offer
+//!       relax: [client/address]
+//! ```
+//!
+//! With `validation/require_acceptance` an invoice also needs the offer to be accepted, see `acceptance()`.
+//!
+//! What a project needs before it can be archived is listed under `archive/require`,
+//! so a storage config can make that stricter or looser than the default of `[payed, employees_payed]`:
+//!
+//! ```yaml
+//! archive:
+//!   require: [payed, employees_payed, invoice_sent]
+//! ```
+//!
+//! Library users can replace them with `set_validator()`.
+
+use std::sync::{Arc, RwLock};
+
+use lazy_static::lazy_static;
+use yaml_rust::Yaml;
+
+use crate::attachments::RemoteAttachment;
+use crate::storage::Storable;
+use super::{FieldError, Project};
+use super::error::ValidationResult;
+use super::spec::*;
+
+/// Readiness checks, one method per stage.
+///
+/// Every method defaults to the built-in checks, so implementations only override what they change.
+pub trait Validate: Send + Sync {
+    /// Missing or invalid fields that keep an offer from being sent.
+    fn offer(&self, project: &Project) -> ValidationResult {
+        builtin_offer(project)
+    }
+
+    /// Missing or invalid fields that keep an invoice from being sent, on top of `offer()`.
+    fn invoice(&self, project: &Project) -> ValidationResult {
+        builtin_invoice(project)
+    }
+
+    /// Missing or invalid fields that keep the project from being archived.
+    fn archive(&self, project: &Project) -> ValidationResult {
+        builtin_archive(project)
+    }
+}
+
+/// The checks for offers, as asciii has always done them.
+pub fn builtin_offer(project: &Project) -> ValidationResult {
+    project.offer().validate()
+        .and(project.client().validate())
+        .and(project.validate())
+}
+
+/// The checks for invoices, as asciii has always done them.
+pub fn builtin_invoice(project: &Project) -> ValidationResult {
+    project.invoice().validate()
+}
+
+/// The offer was accepted, if it was signed the signed copy is attached, see `validation/require_acceptance`.
+pub fn acceptance(project: &Project) -> ValidationResult {
+    let offer = project.offer();
+    let mut validation = ValidationResult::new();
+    validation.require_field("offer.accepted_date", offer.accepted_date());
+    if offer.accepted_via() == Ok("signed") {
+        validation.require_field("offer.signed_copy", offer.signed_copy());
+    }
+    if let Ok(copy) = offer.signed_copy() {
+        let attached = project.dir().join(copy).is_file()
+            || RemoteAttachment::of(project).iter().any(|attachment| attachment.name == copy);
+        if !attached {
+            validation.validate_field::<()>("offer.signed_copy", Err(FieldError::Invalid(lformat!("{} is not attached", copy))));
+        }
+    }
+    validation
+}
+
+/// The checks for archiving, canceled projects can always be archived.
+pub fn builtin_archive(project: &Project) -> ValidationResult {
+    if project.canceled() {
+        ValidationResult::new()
+    } else {
+        <dyn Redeemable>::validate(project)
+            .and(project.hours().validate())
+    }
+}
+
+/// One condition of `archive/require`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveRequirement {
+    /// the invoice is payed
+    Payed,
+    /// caterers are valid and their wages payed
+    EmployeesPayed,
+    /// the invoice went out
+    InvoiceSent,
+    /// everything an invoice needs is there
+    Invoiced,
+}
+
+impl ArchiveRequirement {
+    pub const ALL: [ArchiveRequirement; 4] = [ArchiveRequirement::Payed,
+                                              ArchiveRequirement::EmployeesPayed,
+                                              ArchiveRequirement::InvoiceSent,
+                                              ArchiveRequirement::Invoiced];
+
+    pub fn parse(name: &str) -> Option<ArchiveRequirement> {
+        ArchiveRequirement::ALL.iter().copied().find(|requirement| requirement.name() == name)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ArchiveRequirement::Payed => "payed",
+            ArchiveRequirement::EmployeesPayed => "employees_payed",
+            ArchiveRequirement::InvoiceSent => "invoice_sent",
+            ArchiveRequirement::Invoiced => "invoiced",
+        }
+    }
+
+    /// What the built-in checks require.
+    pub fn builtin() -> Vec<ArchiveRequirement> {
+        vec![ArchiveRequirement::Payed, ArchiveRequirement::EmployeesPayed]
+    }
+
+    /// From `archive/require`, unknown names are skipped with a warning.
+    pub fn configured() -> Vec<ArchiveRequirement> {
+        crate::CONFIG.get("archive/require")
+            .and_then(Yaml::as_vec)
+            .map(|names| names.iter()
+                 .filter_map(|name| name.as_str().and_then(ArchiveRequirement::parse)
+                             .or_else(|| { log::warn!("ignoring unknown archive requirement {:?}", name); None }))
+                 .collect())
+            .unwrap_or_else(ArchiveRequirement::builtin)
+    }
+
+    fn check(self, project: &Project) -> ValidationResult {
+        match self {
+            ArchiveRequirement::Payed => <dyn Redeemable>::validate(project),
+            ArchiveRequirement::EmployeesPayed => project.hours().validate(),
+            ArchiveRequirement::InvoiceSent => {
+                let mut validation = ValidationResult::new();
+                validation.require_field("invoice.sent_date", project.invoice().sent_date());
+                validation
+            }
+            ArchiveRequirement::Invoiced => builtin_invoice(project),
+        }
+    }
+}
+
+/// The checks for archiving with other `requirements`, canceled projects can always be archived.
+pub fn archive_requiring(project: &Project, requirements: &[ArchiveRequirement]) -> ValidationResult {
+    if project.canceled() {
+        return ValidationResult::new();
+    }
+    requirements.iter()
+                .fold(ValidationResult::new(), |result, requirement| result.and(requirement.check(project)))
+}
+
+/// Only the built-in checks.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct BuiltIn;
+
+impl Validate for BuiltIn {}
+
+/// The built-in checks, adjusted by `archive/require` and the rules in `validation/rules`.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Configured;
+
+impl Validate for Configured {
+    fn offer(&self, project: &Project) -> ValidationResult {
+        Rule::apply_configured(Stage::Offer, project, builtin_offer(project))
+    }
+
+    fn invoice(&self, project: &Project) -> ValidationResult {
+        let mut result = builtin_invoice(project);
+        if crate::CONFIG.get_bool("validation/require_acceptance") {
+            result = result.and(acceptance(project));
+        }
+        Rule::apply_configured(Stage::Invoice, project, result)
+    }
+
+    fn archive(&self, project: &Project) -> ValidationResult {
+        let requirements = ArchiveRequirement::configured();
+        Rule::apply_configured(Stage::Archive, project, archive_requiring(project, &requirements))
+    }
+}
+
+lazy_static! {
+    static ref VALIDATOR: RwLock<Arc<dyn Validate>> = RwLock::new(Arc::new(Configured));
+}
+
+/// Replaces the checks used by `Project::is_missing_for_offer()` and friends.
+pub fn set_validator<V: Validate + 'static>(validator: V) {
+    *VALIDATOR.write().unwrap() = Arc::new(validator);
+}
+
+/// The checks currently in use.
+pub fn validator() -> Arc<dyn Validate> {
+    Arc::clone(&VALIDATOR.read().unwrap())
+}
+
+/// What a project is checked for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    Offer,
+    Invoice,
+    Archive,
+}
+
+impl Stage {
+    fn from_str(stage: &str) -> Option<Stage> {
+        match stage {
+            "offer" => Some(Stage::Offer),
+            "invoice" => Some(Stage::Invoice),
+            "archive" => Some(Stage::Archive),
+            _ => None,
+        }
+    }
+}
+
+/// One entry of `validation/rules`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rule {
+    pub stage: Stage,
+    /// fields that must be present, by `Project::field()` path
+    pub require: Vec<String>,
+    /// fields that are no longer reported as missing
+    pub relax: Vec<String>,
+    /// the rule only applies to projects whose fields have these values
+    pub when: Vec<(String, String)>,
+}
+
+impl Rule {
+    /// Reads a rule, `None` if it has no valid `stage`.
+    pub fn from_yaml(yaml: &Yaml) -> Option<Rule> {
+        let strings = |key: &str| yaml[key].as_vec()
+            .map(|list| list.iter().filter_map(Yaml::as_str).map(ToOwned::to_owned).collect())
+            .unwrap_or_default();
+        Some(Rule {
+            stage: yaml["stage"].as_str().and_then(Stage::from_str)?,
+            require: strings("require"),
+            relax: strings("relax"),
+            when: yaml["when"].as_hash()
+                .map(|when| when.iter()
+                     .filter_map(|(field, value)| Some((field.as_str()?.to_owned(), scalar_to_string(value)?)))
+                     .collect())
+                .unwrap_or_default(),
+        })
+    }
+
+    /// Rules from `validation/rules`, broken ones are skipped with a warning.
+    pub fn configured() -> Vec<Rule> {
+        crate::CONFIG.get("validation/rules")
+            .and_then(Yaml::as_vec)
+            .map(|rules| rules.iter()
+                 .filter_map(|rule| Rule::from_yaml(rule)
+                             .or_else(|| { log::warn!("ignoring validation rule without a valid stage: {:?}", rule); None }))
+                 .collect())
+            .unwrap_or_default()
+    }
+
+    fn applies_to(&self, project: &Project) -> bool {
+        self.when.iter().all(|(field, value)| project.field(field).as_ref() == Some(value))
+    }
+
+    /// Adjusts `result` of `stage` by this rule.
+    pub fn apply(&self, stage: Stage, project: &Project, mut result: ValidationResult) -> ValidationResult {
+        if self.stage != stage || !self.applies_to(project) {
+            return result;
+        }
+        result.missing_fields.retain(|field| !self.relax.contains(field));
+        for field in &self.require {
+            if project.field(field).is_none() && !result.missing_fields.contains(field) {
+                result.missing_fields.push(field.clone());
+            }
+        }
+        result
+    }
+
+    fn apply_configured(stage: Stage, project: &Project, result: ValidationResult) -> ValidationResult {
+        Rule::configured().iter()
+            .fold(result, |result, rule| rule.apply(stage, project, result))
+    }
+}
+
+fn scalar_to_string(yaml: &Yaml) -> Option<String> {
+    match *yaml {
+        Yaml::String(ref s) | Yaml::Real(ref s) => Some(s.clone()),
+        Yaml::Integer(i) => Some(i.to_string()),
+        Yaml::Boolean(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::yaml;
+
+    const DOC: &str = "client:\n  last_name: Zahl\nevent:\n  name: Party\n";
+
+    fn rule(yaml: &str) -> Rule {
+        Rule::from_yaml(&yaml::parse(yaml).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn rules_require_and_relax() {
+        let project = Project::from_file_content(DOC).unwrap();
+        let builtin = builtin_offer(&project);
+        assert!(builtin.missing_fields.contains(&"client/address".to_owned()));
+
+        let relaxed = rule("stage: offer\nrelax: [client/address]").apply(Stage::Offer, &project, builtin_offer(&project));
+        assert!(!relaxed.missing_fields.contains(&"client/address".to_owned()));
+
+        let required = rule("stage: invoice\nrequire: [client/purchase_order]\nwhen: { client/last_name: Zahl }");
+        let result = required.apply(Stage::Invoice, &project, ValidationResult::new());
+        assert_eq!(result.missing_fields, vec!["client/purchase_order".to_owned()]);
+        assert!(required.apply(Stage::Offer, &project, ValidationResult::new()).is_ok());
+
+        let other_client = rule("stage: invoice\nrequire: [client/purchase_order]\nwhen: { client/last_name: Graf }");
+        assert!(other_client.apply(Stage::Invoice, &project, ValidationResult::new()).is_ok());
+
+        assert_eq!(Rule::from_yaml(&yaml::parse("stage: never").unwrap()), None);
+    }
+
+    #[test]
+    fn archive_requirements() {
+        let project = Project::from_file_content(DOC).unwrap();
+        assert_eq!(archive_requiring(&project, &ArchiveRequirement::builtin()), builtin_archive(&project));
+        assert!(archive_requiring(&project, &[]).is_ok());
+
+        let sent = archive_requiring(&project, &[ArchiveRequirement::InvoiceSent]);
+        assert_eq!(sent.missing_fields, vec!["invoice.sent_date".to_owned()]);
+
+        let payed = Project::from_file_content("event:\n  name: Party\ninvoice:\n  payed_date: 01.02.2024\n  sent_date: 01.01.2024\n").unwrap();
+        assert!(archive_requiring(&payed, &[ArchiveRequirement::Payed, ArchiveRequirement::InvoiceSent]).is_ok());
+
+        assert_eq!(ArchiveRequirement::parse("employees_payed"), Some(ArchiveRequirement::EmployeesPayed));
+        assert_eq!(ArchiveRequirement::parse("signed"), None);
+    }
+
+    #[test]
+    fn custom_validators_keep_the_defaults() {
+        struct NoArchive;
+        impl Validate for NoArchive {
+            fn archive(&self, _: &Project) -> ValidationResult {
+                ValidationResult::new()
+            }
+        }
+
+        let project = Project::from_file_content(DOC).unwrap();
+        assert!(NoArchive.archive(&project).is_ok());
+        assert!(!BuiltIn.archive(&project).is_ok());
+        assert_eq!(NoArchive.offer(&project), BuiltIn.offer(&project));
+    }
+}
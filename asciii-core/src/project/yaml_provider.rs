@@ -54,13 +54,18 @@ pub trait YamlProvider {
     //fn data(&self) -> impl PathAccessible {
     fn data(&self) -> &Yaml;
 
+    /// The data that holds `path`, implement this to only parse what is asked for.
+    fn data_for(&self, _path: &str) -> &Yaml {
+        self.data()
+    }
+
     /// Wrapper around `get_path()`.
     ///
     /// Splits path string
     /// and replaces `Yaml::Null` and `Yaml::BadValue`.
     fn get<'a>(&'a self, paths: &str) -> Option<&'a Yaml> {
         paths.split('|').filter_map(|path|
-            self.get_direct(self.data(), path)
+            self.get_direct(self.data_for(path), path)
         ).next()
     }
 
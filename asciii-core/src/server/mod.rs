@@ -0,0 +1,81 @@
+#![cfg(feature = "server")]
+#![allow(clippy::new_without_default)]
+
+use linked_hash_map::LinkedHashMap;
+use itertools::Itertools;
+
+use crate::project::Project;
+use crate::storage::{self, ProjectList, Storage, StorageDir, Storable};
+
+pub struct ProjectLoader {
+    pub storage: Storage<Project>,
+    pub state: State,
+}
+
+
+
+pub struct State {
+    pub all: ProjectList<Project>,
+    pub working: LinkedHashMap<String, Project>,
+    pub mapped:  LinkedHashMap<String, Project>,
+    pub years: Vec<i32>
+}
+
+fn reinitialize(storage: &Storage<Project>) -> State {
+    let all = storage.open_projects(StorageDir::All).unwrap();
+    let working = storage.open_projects(StorageDir::Working).unwrap();
+    State::new(all, working)
+}
+
+impl State {
+    fn new(all: ProjectList<Project>, working: ProjectList<Project>) -> State {
+        let working = working
+            .into_iter()
+            .map(|p| (Storable::ident(&p), p))
+            .collect();
+
+        let mapped = all.iter()
+            .cloned()
+            .map(|p| (format!("{}-{}",
+                              Storable::year(&p).unwrap(),
+                              Storable::ident(&p)),
+                              p))
+            .collect();
+
+        let years = all.iter()
+                       .filter_map(Project::year)
+                       .unique()
+                       .collect::<Vec<_>>();
+
+        State {all, working, mapped, years}
+    }
+}
+
+impl ProjectLoader {
+
+    pub fn new() -> Self {
+        let storage = storage::setup().unwrap();
+        let state = reinitialize(&storage);
+
+        Self {
+            storage,
+            state
+        }
+    }
+
+    pub fn update(&mut self) {
+        log::debug!("updating projects");
+        self.state = reinitialize(&self.storage);
+    }
+
+    /// Reads all projects without blocking the executor, see `storage::nonblocking`.
+    ///
+    /// Uses a storage of its own, so nothing has to be locked while the archive is scanned.
+    #[cfg(feature = "async")]
+    pub async fn load() -> Result<State, crate::AsciiiError> {
+        let storage = storage::setup::<Project>()?;
+        let all = storage.open_projects_dir_async(StorageDir::All).await?;
+        let working = storage.open_projects_dir_async(StorageDir::Working).await?;
+        Ok(State::new(all, working))
+    }
+}
@@ -0,0 +1,124 @@
+//! `.index.yml` in every year of the archive.
+//!
+//! Lists what was archived in that year, so tools (or a quick `grep`) don't have to open every project:
+//!
+//! ```yaml
+//! - ident: R042_sommerfest
+//!   name: Sommerfest
+//!   client: Erika Mustermann
+//!   invoice: R042
+//!   sum: 1234.50
+//! ```
+//!
+//! It is rewritten whenever something is archived into the year or taken out of it,
+//! `asciii archive --reindex <year>` writes it for archives that are older than that.
+//! It is hidden, so nothing that looks for project files takes it for one.
+//! Older versions wrote `index.yml`, such a file is ignored just the same and can be deleted.
+
+use std::path::{Path, PathBuf};
+
+use yaml_rust::yaml::{Hash, Yaml};
+use yaml_rust::YamlEmitter;
+
+use crate::error::AsciiiError;
+use crate::util::{self, yaml};
+
+pub const INDEX_FILE: &str = ".index.yml";
+/// Name of the index before it was hidden.
+const LEGACY_INDEX_FILE: &str = "index.yml";
+
+/// One archived project.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IndexEntry {
+    /// name of the folder
+    pub ident: String,
+    pub name: String,
+    pub client: Option<String>,
+    /// invoice number
+    pub invoice: Option<String>,
+    pub sum: Option<f64>,
+}
+
+impl IndexEntry {
+    fn to_yaml(&self) -> Yaml {
+        let mut hash = Hash::new();
+        hash.insert(Yaml::String("ident".into()), Yaml::String(self.ident.clone()));
+        hash.insert(Yaml::String("name".into()), Yaml::String(self.name.clone()));
+        if let Some(ref client) = self.client {
+            hash.insert(Yaml::String("client".into()), Yaml::String(client.clone()));
+        }
+        if let Some(ref invoice) = self.invoice {
+            hash.insert(Yaml::String("invoice".into()), Yaml::String(invoice.clone()));
+        }
+        if let Some(sum) = self.sum {
+            hash.insert(Yaml::String("sum".into()), Yaml::Real(format!("{:.2}", sum)));
+        }
+        Yaml::Hash(hash)
+    }
+
+    fn from_yaml(yaml: &Yaml) -> Option<IndexEntry> {
+        Some(IndexEntry {
+            ident: yaml::get_to_string(yaml, "ident")?,
+            name: yaml::get_to_string(yaml, "name").unwrap_or_default(),
+            client: yaml::get_to_string(yaml, "client"),
+            invoice: yaml::get_to_string(yaml, "invoice"),
+            sum: yaml::get_f64(yaml, "sum"),
+        })
+    }
+}
+
+/// Path of the index of the archive `year_dir`.
+pub fn path(year_dir: &Path) -> PathBuf {
+    year_dir.join(INDEX_FILE)
+}
+
+/// Whether `path` is an index rather than a project folder.
+pub fn is_index(path: &Path) -> bool {
+    path.file_name().is_some_and(|name| name == INDEX_FILE || name == LEGACY_INDEX_FILE)
+}
+
+/// Reads the index of the archive `year_dir`, empty if there is none.
+pub fn read(year_dir: &Path) -> Result<Vec<IndexEntry>, AsciiiError> {
+    let file = path(year_dir);
+    if !file.exists() {
+        return Ok(Vec::new());
+    }
+    Ok(yaml::open(&file)?.as_vec()
+                         .map(|entries| entries.iter().filter_map(IndexEntry::from_yaml).collect())
+                         .unwrap_or_default())
+}
+
+/// Writes `entries` as the index of the archive `year_dir`, ordered by `ident`.
+pub fn write(year_dir: &Path, mut entries: Vec<IndexEntry>) -> Result<PathBuf, AsciiiError> {
+    entries.sort_by(|a, b| a.ident.cmp(&b.ident));
+    let mut out = String::new();
+    YamlEmitter::new(&mut out).dump(&Yaml::Array(entries.iter().map(IndexEntry::to_yaml).collect()))?;
+    out.push('\n');
+    let file = path(year_dir);
+    util::atomic_write(&file, out)?;
+    Ok(file)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempdir::TempDir;
+
+    #[test]
+    fn index_round_trip() {
+        let dir = TempDir::new("asciii-archive-index").unwrap();
+        assert!(read(dir.path()).unwrap().is_empty());
+
+        let entries = vec![IndexEntry { ident: "R042_sommerfest".into(), name: "Sommerfest".into(),
+                                        client: Some("Erika Mustermann".into()), invoice: Some("R042".into()), sum: Some(1234.5) },
+                           IndexEntry { ident: "R007_hochzeit".into(), name: "Hochzeit".into(), ..IndexEntry::default() }];
+        let file = write(dir.path(), entries.clone()).unwrap();
+        assert!(is_index(&file));
+        assert!(is_index(&dir.path().join("index.yml")));
+        assert!(!is_index(&dir.path().join("R042_sommerfest")));
+
+        let read = read(dir.path()).unwrap();
+        assert_eq!(read, vec![entries[1].clone(), entries[0].clone()]);
+        assert!(std::fs::read_to_string(file).unwrap().contains("sum: 1234.50"));
+    }
+}
@@ -0,0 +1,152 @@
+//! Sets up a `Storage` step by step, see `Storage::builder()`.
+
+use std::marker::PhantomData;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::OnceLock;
+
+use crate::config::ConfigReader;
+use crate::error::AsciiiError;
+
+use super::repo::Repository;
+use super::{dir_names, storage_path, Storable, Storage, StorageError};
+
+/// Configures a `Storage`.
+///
+/// Everything that is not set explicitly is taken from the config,
+/// just like `Storage::setup()` does.
+///
+/// ```no_run
+/// # use asciii_core::storage::Storage;
+/// # use asciii_core::project::Project;
+/// let storage = Storage::<Project>::builder()
+///     .root("/home/hendrik/caterings")
+///     .git(true)
+///     .read_only(true)
+///     .build()
+///     .unwrap();
+/// ```
+pub struct StorageBuilder<L: Storable> {
+    root: Option<PathBuf>,
+    working: Option<String>,
+    archive: Option<String>,
+    templates: Option<String>,
+    git: bool,
+    read_only: bool,
+    config: Arc<ConfigReader>,
+    project_type: PhantomData<L>,
+}
+
+impl<L: Storable> Default for StorageBuilder<L> {
+    fn default() -> Self {
+        StorageBuilder {
+            root: None,
+            working: None,
+            archive: None,
+            templates: None,
+            git: false,
+            read_only: false,
+            config: Arc::clone(&crate::CONFIG),
+            project_type: PhantomData,
+        }
+    }
+}
+
+impl<L: Storable> StorageBuilder<L> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Root of the storage, defaults to `dirs/storage` of the config.
+    pub fn root<P: Into<PathBuf>>(mut self, root: P) -> Self {
+        self.root = Some(root.into());
+        self
+    }
+
+    /// Name of the working directory, defaults to `dirs/working` of the config.
+    pub fn working_dir(mut self, name: &str) -> Self {
+        self.working = Some(name.to_owned());
+        self
+    }
+
+    /// Name of the archive directory, defaults to `dirs/archive` of the config.
+    pub fn archive_dir(mut self, name: &str) -> Self {
+        self.archive = Some(name.to_owned());
+        self
+    }
+
+    /// Name of the templates directory, defaults to `dirs/templates` of the config.
+    pub fn templates_dir(mut self, name: &str) -> Self {
+        self.templates = Some(name.to_owned());
+        self
+    }
+
+    /// Turns on git, the root has to be a repository then.
+    pub fn git(mut self, git: bool) -> Self {
+        self.git = git;
+        self
+    }
+
+    /// Everything that would change the storage fails with `StorageError::ReadOnly`.
+    pub fn read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Settings of the storage, `crate::CONFIG` unless set.
+    pub fn config(mut self, config: Arc<ConfigReader>) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Sets up the storage, without looking whether the directories exist, see `Storage::health_check()`.
+    ///
+    /// If no root was given the `.asciii.yml` of the configured storage is merged into the config.
+    pub fn build(self) -> Result<Storage<L>, AsciiiError> {
+        let root = match self.root {
+            Some(root) => root,
+            None => {
+                let root = storage_path(&self.config);
+                self.config.merge_storage_config(&root);
+                root
+            }
+        };
+        log::trace!("initializing storage, root: {}", root.display());
+        if !root.is_absolute() {
+            return Err(StorageError::StoragePathNotAbsolute.into());
+        }
+
+        let (working, archive, templates) = match (self.working, self.archive, self.templates) {
+            (Some(working), Some(archive), Some(templates)) => (working, archive, templates),
+            (working, archive, templates) => {
+                let defaults = dir_names(&self.config)?;
+                (working.unwrap_or_else(|| defaults.0.to_owned()),
+                 archive.unwrap_or_else(|| defaults.1.to_owned()),
+                 templates.unwrap_or_else(|| defaults.2.to_owned()))
+            }
+        };
+
+        let repository = if self.git {
+            log::trace!("initializing storage, with git");
+            Some(Repository::try_new(&root)?)
+        } else {
+            None
+        };
+
+        Ok(Storage {
+            working:   root.join(working),
+            archive:   root.join(archive),
+            templates: root.join(templates),
+            extras:    root.join("extras"),
+            root,
+            project_type: PhantomData,
+            repository,
+            read_only: self.read_only,
+            config: self.config,
+            hooks: Default::default(),
+            clients: OnceLock::new(),
+            #[cfg(feature="rayon")]
+            thread_pool: OnceLock::new(),
+        })
+    }
+}
@@ -0,0 +1,182 @@
+//! Keeps opened files and git statuses around between commands.
+//!
+//! The cache is off unless `enable()` is called, which `asciii shell` and the daemon do.
+//! Files are cached by path and are reopened once their modification time or size changes,
+//! git statuses are kept until `clear_statuses()` is called.
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use lazy_static::lazy_static;
+
+use crate::error::AsciiiError;
+use super::repo::GitStatus;
+
+/// Identifies the version of a file on disk.
+type Stamp = (SystemTime, u64);
+
+/// A cached value together with the version of the file it came from.
+type Entry = (Stamp, Box<dyn Any + Send>);
+
+type Statuses = HashMap<PathBuf, GitStatus>;
+
+/// Opened files and git statuses, `GLOBAL` is the one of the process.
+///
+/// Tests use their own, so they don't switch caching on for each other.
+pub struct Cache {
+    enabled: AtomicBool,
+    files: Mutex<HashMap<PathBuf, Entry>>,
+    statuses: Mutex<HashMap<PathBuf, Statuses>>,
+}
+
+lazy_static! {
+    static ref GLOBAL: Cache = Cache::new();
+}
+
+/// Turns caching on for the rest of the process.
+pub fn enable() {
+    GLOBAL.enable();
+}
+
+pub fn is_enabled() -> bool {
+    GLOBAL.is_enabled()
+}
+
+/// Forgets everything.
+pub fn clear() {
+    GLOBAL.clear();
+}
+
+/// Forgets the git statuses, call this after the repository may have changed.
+pub fn clear_statuses() {
+    GLOBAL.clear_statuses();
+}
+
+/// Returns a copy of what `open` returned for this path before, as long as the file didn't change.
+pub fn file<T, F>(path: &Path, open: F) -> Result<T, AsciiiError>
+    where T: Clone + Send + 'static,
+          F: FnOnce(&Path) -> Result<T, AsciiiError>
+{
+    GLOBAL.file(path, open)
+}
+
+/// Returns the git statuses of the repository at `root`, computing them only once.
+pub fn statuses<F, E>(root: &Path, compute: F) -> Result<Statuses, E>
+    where F: FnOnce() -> Result<Statuses, E>
+{
+    GLOBAL.statuses(root, compute)
+}
+
+fn stamp(path: &Path) -> Option<Stamp> {
+    let meta = path.metadata().ok()?;
+    Some((meta.modified().ok()?, meta.len()))
+}
+
+impl Cache {
+    /// Empty and off.
+    pub fn new() -> Cache {
+        Cache {
+            enabled: AtomicBool::new(false),
+            files: Mutex::new(HashMap::new()),
+            statuses: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn enable(&self) {
+        self.enabled.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::SeqCst)
+    }
+
+    pub fn clear(&self) {
+        self.files.lock().unwrap().clear();
+        self.clear_statuses();
+    }
+
+    pub fn clear_statuses(&self) {
+        self.statuses.lock().unwrap().clear();
+    }
+
+    /// See `file()`.
+    pub fn file<T, F>(&self, path: &Path, open: F) -> Result<T, AsciiiError>
+        where T: Clone + Send + 'static,
+              F: FnOnce(&Path) -> Result<T, AsciiiError>
+    {
+        let stamp = match stamp(path) {
+            Some(stamp) if self.is_enabled() => stamp,
+            _ => return open(path)
+        };
+
+        if let Some((cached_stamp, value)) = self.files.lock().unwrap().get(path) {
+            if *cached_stamp == stamp {
+                if let Some(value) = value.downcast_ref::<T>() {
+                    log::trace!("cache hit {}", path.display());
+                    return Ok(value.clone());
+                }
+            }
+        }
+
+        let value = open(path)?;
+        self.files.lock().unwrap().insert(path.to_owned(), (stamp, Box::new(value.clone())));
+        Ok(value)
+    }
+
+    /// See `statuses()`.
+    pub fn statuses<F, E>(&self, root: &Path, compute: F) -> Result<Statuses, E>
+        where F: FnOnce() -> Result<Statuses, E>
+    {
+        if !self.is_enabled() {
+            return compute();
+        }
+
+        if let Some(statuses) = self.statuses.lock().unwrap().get(root) {
+            log::trace!("cached git statuses for {}", root.display());
+            return Ok(statuses.clone());
+        }
+
+        let statuses = compute()?;
+        self.statuses.lock().unwrap().insert(root.to_owned(), statuses.clone());
+        Ok(statuses)
+    }
+}
+
+impl Default for Cache {
+    fn default() -> Cache {
+        Cache::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use std::fs;
+    use tempdir::TempDir;
+
+    #[test]
+    fn reopens_changed_files() {
+        let cache = Cache::new();
+        cache.enable();
+        let dir = TempDir::new("asciii-cache").unwrap();
+        let path = dir.path().join("file.yml");
+        fs::write(&path, "a").unwrap();
+
+        let opened = Cell::new(0);
+        let open = |p: &Path| { opened.set(opened.get() + 1); Ok(fs::read_to_string(p)?) };
+
+        assert_eq!(cache.file(&path, open).unwrap(), "a");
+        assert_eq!(cache.file(&path, open).unwrap(), "a");
+        assert_eq!(opened.get(), 1);
+
+        fs::write(&path, "ab").unwrap();
+        assert_eq!(cache.file(&path, open).unwrap(), "ab");
+        assert_eq!(opened.get(), 2);
+        assert!(!is_enabled(), "the cache of the process stays off");
+    }
+}
@@ -0,0 +1,131 @@
+//! The client database, `extras/clients.yml` of the storage: clients and the rates negotiated with them.
+//!
+//! ```yaml
+//! - name: Graf Zahl
+//!   email: graf@zahl.de
+//!   prices: # price book, overrides the cataloge of every project for this client
+//!     Kaffee: 2.0
+//!     Tee: 1.2
+//! ```
+//!
+//! A project belongs to a client if the emails match, or, without an email on either side,
+//! if the name is the client's full or last name. Names are compared ignoring case and surrounding whitespace.
+
+use std::fs;
+use std::path::Path;
+
+use bill::Currency;
+use yaml_rust::Yaml;
+
+use crate::error::{ActionError, AsciiiError};
+use crate::util::{self, yaml};
+
+pub const CLIENTS_FILE: &str = "clients.yml";
+
+fn same(a: &str, b: &str) -> bool {
+    a.trim().to_lowercase() == b.trim().to_lowercase()
+}
+
+/// One entry of the client database.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KnownClient {
+    pub name: String,
+    pub email: Option<String>,
+    /// negotiated price per product name
+    pub prices: Vec<(String, Currency)>,
+}
+
+impl KnownClient {
+    /// Negotiated price of `product`, if there is one.
+    pub fn price_of(&self, product: &str) -> Option<Currency> {
+        self.prices.iter()
+                   .find(|(name, _)| same(name, product))
+                   .map(|(_, price)| *price)
+    }
+
+    /// Whether a project with this client `name` and `email` is for this client.
+    pub fn is(&self, names: &[&str], email: Option<&str>) -> bool {
+        match (self.email.as_deref(), email) {
+            (Some(known), Some(email)) => same(known, email),
+            _ => names.iter().any(|name| same(&self.name, name)),
+        }
+    }
+}
+
+/// All entries of `extras/clients.yml`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ClientDatabase {
+    pub clients: Vec<KnownClient>,
+}
+
+impl ClientDatabase {
+    /// Reads the content of a `clients.yml`.
+    pub fn parse(content: &str) -> Result<ClientDatabase, AsciiiError> {
+        let broken = |what: String| -> AsciiiError { ActionError::Failed(what).into() };
+        let entries = match yaml::parse(content)? {
+            Yaml::Array(entries) => entries,
+            // nothing but comments
+            _ if content.lines().all(|line| line.trim().is_empty() || line.trim_start().starts_with('#')) => Vec::new(),
+            _ => return Err(broken(lformat!("{} must be a list of clients", CLIENTS_FILE)))
+        };
+
+        let mut clients = Vec::new();
+        for entry in &entries {
+            let name = yaml::get_str(entry, "name")
+                            .ok_or_else(|| broken(lformat!("client without a name in {}", CLIENTS_FILE)))?;
+            let mut prices = Vec::new();
+            if let Some(book) = yaml::get_hash(entry, "prices") {
+                for (product, price) in book {
+                    let product = product.as_str().unwrap_or_default();
+                    let price = price.as_f64().or_else(|| price.as_i64().map(|p| p as f64))
+                                     .ok_or_else(|| broken(lformat!("price of {:?} for {} is not a number", product, name)))?;
+                    prices.push((product.to_owned(), util::to_currency(price)));
+                }
+            }
+            clients.push(KnownClient {
+                name: name.trim().to_owned(),
+                email: yaml::get_str(entry, "email").map(|e| e.trim().to_owned()),
+                prices,
+            });
+        }
+        Ok(ClientDatabase { clients })
+    }
+
+    /// Reads `path`, an empty database if there is no such file.
+    pub fn open(path: &Path) -> Result<ClientDatabase, AsciiiError> {
+        if !path.exists() {
+            return Ok(ClientDatabase::default());
+        }
+        ClientDatabase::parse(&fs::read_to_string(path)?)
+    }
+
+    /// The client a project with these `names` and `email` belongs to.
+    pub fn find(&self, names: &[&str], email: Option<&str>) -> Option<&KnownClient> {
+        self.clients.iter().find(|client| client.is(names, email))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn price_books() {
+        let database = ClientDatabase::parse("# negotiated rates\n\
+                                              - name: Graf Zahl\n  email: graf@zahl.de\n  prices:\n    Kaffee: 2.0\n    Tee: 1\n\
+                                              - name: Ernie\n").unwrap();
+        assert_eq!(database.clients.len(), 2);
+
+        let graf = database.find(&["Someone else"], Some(" GRAF@zahl.de")).unwrap();
+        assert_eq!(graf.price_of("kaffee"), Some(util::to_currency(2.0)));
+        assert_eq!(graf.price_of("Tee"), Some(util::to_currency(1.0)));
+        assert_eq!(graf.price_of("Kakao"), None);
+        // an email decides
+        assert!(database.find(&["Graf Zahl"], Some("zahl@graf.de")).is_none());
+        assert_eq!(database.find(&["ernie"], Some("ernie@sesam.de")).unwrap().name, "Ernie");
+
+        assert_eq!(ClientDatabase::parse("# nobody yet\n").unwrap(), ClientDatabase::default());
+        assert!(ClientDatabase::parse("- name: Bert\n  prices: { Kaffee: viel }\n").is_err());
+        assert!(ClientDatabase::parse("- email: bert@sesam.de\n").is_err());
+    }
+}
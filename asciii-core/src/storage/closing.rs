@@ -0,0 +1,155 @@
+//! Years that were closed, see `asciii close-year`.
+//!
+//! Closing a year records it in `closing/file` in the storage root.
+//! The archive of a closed year is read-only: nothing is archived into it or taken out of it,
+//! and its projects can't be changed with `asciii set` or `asciii apply`.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::prelude::*;
+use thiserror::Error;
+use yaml_rust::yaml::{Hash, Yaml};
+use yaml_rust::YamlEmitter;
+
+use crate::config::ConfigReader;
+use crate::error::AsciiiError;
+use crate::util::{self, yaml};
+
+#[derive(Error, Debug)]
+pub enum ClosingError {
+    #[error("{} is closed, its archive can't be changed anymore.", _0)]
+    Closed(i32),
+
+    #[error("{} was already closed on {}.", _0, _1.format("%d.%m.%Y"))]
+    AlreadyClosed(i32, NaiveDate),
+
+    #[error("The list of closed years {} is broken.", _0.display())]
+    Broken(PathBuf),
+}
+
+/// One closed year.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Closed {
+    pub year: i32,
+    /// when the year was closed
+    pub date: NaiveDate,
+    /// git tag of the closing, if there is a repository
+    pub tag: Option<String>,
+}
+
+impl Closed {
+    fn to_yaml(&self) -> Yaml {
+        let mut hash = Hash::new();
+        hash.insert(Yaml::String("year".into()), Yaml::Integer(i64::from(self.year)));
+        hash.insert(Yaml::String("date".into()), Yaml::String(self.date.format("%d.%m.%Y").to_string()));
+        if let Some(ref tag) = self.tag {
+            hash.insert(Yaml::String("tag".into()), Yaml::String(tag.clone()));
+        }
+        Yaml::Hash(hash)
+    }
+
+    fn from_yaml(yaml: &Yaml) -> Option<Closed> {
+        Some(Closed {
+            year: yaml::get_int(yaml, "year")? as i32,
+            date: yaml::get_str(yaml, "date").and_then(yaml::parse_dmy_date)?.naive_utc(),
+            tag: yaml::get_string(yaml, "tag"),
+        })
+    }
+}
+
+/// The closed years of a storage.
+pub struct ClosedYears {
+    file: PathBuf,
+}
+
+impl ClosedYears {
+    /// Closed years of the storage in `root`, the file is created when the first year is closed.
+    pub fn new(root: &Path) -> ClosedYears {
+        ClosedYears { file: root.join(".asciii_closed.yml") }
+    }
+
+    /// Like `new()`, with `closing/file` from `config`.
+    pub fn with_config(root: &Path, config: &ConfigReader) -> ClosedYears {
+        match config.get_str_or("closing/file") {
+            Some(file) => ClosedYears { file: root.join(file) },
+            None => ClosedYears::new(root),
+        }
+    }
+
+    pub fn file(&self) -> &Path {
+        &self.file
+    }
+
+    /// All closed years, in the order they were closed.
+    pub fn closed(&self) -> Result<Vec<Closed>, AsciiiError> {
+        if !self.file.exists() {
+            return Ok(Vec::new());
+        }
+        let yaml = yaml::open(&self.file)?;
+        match yaml.as_vec() {
+            Some(entries) => entries.iter()
+                                    .map(Closed::from_yaml)
+                                    .collect::<Option<Vec<Closed>>>()
+                                    .ok_or_else(|| ClosingError::Broken(self.file.clone()).into()),
+            None if yaml.is_null() => Ok(Vec::new()),
+            None => Err(ClosingError::Broken(self.file.clone()).into())
+        }
+    }
+
+    pub fn is_closed(&self, year: i32) -> Result<bool, AsciiiError> {
+        Ok(self.closed()?.iter().any(|closed| closed.year == year))
+    }
+
+    /// Fails with `ClosingError::Closed` if `year` is closed.
+    pub fn ensure_open(&self, year: i32) -> Result<(), AsciiiError> {
+        if self.is_closed(year)? {
+            return Err(ClosingError::Closed(year).into());
+        }
+        Ok(())
+    }
+
+    /// Records `year` as closed today, a year is only closed once.
+    pub fn close(&self, year: i32, tag: Option<&str>) -> Result<Closed, AsciiiError> {
+        let mut entries = self.closed()?;
+        if let Some(closed) = entries.iter().find(|closed| closed.year == year) {
+            return Err(ClosingError::AlreadyClosed(year, closed.date).into());
+        }
+
+        let closed = Closed { year, date: Local::today().naive_local(), tag: tag.map(ToOwned::to_owned) };
+        entries.push(closed.clone());
+
+        let mut out = String::new();
+        YamlEmitter::new(&mut out).dump(&Yaml::Array(entries.iter().map(Closed::to_yaml).collect()))?;
+        out.push('\n');
+        if let Some(dir) = self.file.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        util::atomic_write(&self.file, out)?;
+        Ok(closed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempdir::TempDir;
+
+    #[test]
+    fn closing_years() {
+        let dir = TempDir::new("asciii-closing").unwrap();
+        let years = ClosedYears::new(dir.path());
+        assert!(!years.is_closed(2023).unwrap());
+        assert!(years.ensure_open(2023).is_ok());
+
+        years.close(2023, Some("closing-2023")).unwrap();
+        years.close(2024, None).unwrap();
+        assert!(years.is_closed(2023).unwrap());
+        assert!(years.ensure_open(2023).is_err());
+        assert!(years.ensure_open(2025).is_ok());
+        assert_eq!(years.closed().unwrap()[0].tag.as_deref(), Some("closing-2023"));
+        assert_eq!(years.closed().unwrap()[1].tag, None);
+
+        assert!(years.close(2024, None).is_err());
+    }
+}
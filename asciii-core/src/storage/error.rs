@@ -4,12 +4,10 @@ use thiserror::Error;
 
 use std::path::PathBuf;
 
-#[cfg(not(feature = "git_statuses"))]
-mod git2 {
-    pub use super::super::repo::GitError as Error;
+fn did_you_mean(suggestion: &Option<String>) -> String {
+    suggestion.as_ref().map(|name| format!(" Did you mean `{}`?", name)).unwrap_or_default()
 }
 
-
 #[derive(Error, Debug)]
 pub enum StorageError {
     #[error("The directory you passed cannot be used in this context. You perhaps passed `Templates` instead of `Archive` or `Working`")]
@@ -45,6 +43,10 @@ pub enum StorageError {
     #[error("The described template file does not exist.")]
     TemplateNotFound,
 
+    /// name, available templates, closest match
+    #[error("There is no template {:?}.{} Available templates: {}.", _0, did_you_mean(_2), _1.join(", "))]
+    UnknownTemplate(String, Vec<String>, Option<String>),
+
     #[error("Calling `git` failed")]
     GitProcessFailed,
 
@@ -54,4 +56,19 @@ pub enum StorageError {
     #[error("Nothing found for {:?}", _0)]
     NothingFound(Vec<String>),
 
+    #[error("The storage is read-only.")]
+    ReadOnly,
+
+    #[error(transparent)]
+    Journal(#[from] super::journal::JournalError),
+
+    #[error(transparent)]
+    Ledger(#[from] super::ledger::LedgerError),
+
+    #[error(transparent)]
+    Lock(#[from] super::lock::LockError),
+
+    #[error(transparent)]
+    Closing(#[from] super::closing::ClosingError),
+
 }
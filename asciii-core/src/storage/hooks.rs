@@ -0,0 +1,104 @@
+//! Callbacks for programs that embed asciii, e.g. to drive a progress bar or refresh a view.
+//!
+//! Register them on a `Storage` with `on_project_opened()`, `on_progress()`, `on_created()`, `on_field_changed()`,
+//! `on_archived()` and `on_unarchived()`.
+//! Projects are opened in parallel, so callbacks may be called from several threads at once.
+
+use std::fmt;
+use std::path::Path;
+
+type PathCallback = Box<dyn Fn(&Path) + Send + Sync>;
+type MoveCallback = Box<dyn Fn(&Path, &Path) + Send + Sync>;
+type FieldCallback = Box<dyn Fn(&Path, &str) + Send + Sync>;
+type ProgressCallback = Box<dyn Fn(Progress) + Send + Sync>;
+
+/// How far opening a list of projects got.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    /// projects opened so far, including broken ones
+    pub done: usize,
+    pub total: usize,
+}
+
+#[derive(Default)]
+pub struct Hooks {
+    project_opened: Vec<PathCallback>,
+    progress: Vec<ProgressCallback>,
+    created: Vec<PathCallback>,
+    field_changed: Vec<FieldCallback>,
+    archived: Vec<MoveCallback>,
+    unarchived: Vec<MoveCallback>,
+}
+
+impl Hooks {
+    pub fn add_project_opened(&mut self, callback: PathCallback) {
+        self.project_opened.push(callback);
+    }
+
+    pub fn add_progress(&mut self, callback: ProgressCallback) {
+        self.progress.push(callback);
+    }
+
+    pub fn add_created(&mut self, callback: PathCallback) {
+        self.created.push(callback);
+    }
+
+    pub fn add_field_changed(&mut self, callback: FieldCallback) {
+        self.field_changed.push(callback);
+    }
+
+    pub fn add_archived(&mut self, callback: MoveCallback) {
+        self.archived.push(callback);
+    }
+
+    pub fn add_unarchived(&mut self, callback: MoveCallback) {
+        self.unarchived.push(callback);
+    }
+
+    /// Whether anybody listens for `progress()`, so counting can be skipped otherwise.
+    pub fn wants_progress(&self) -> bool {
+        !self.progress.is_empty()
+    }
+
+    /// A project file or folder was opened successfully.
+    pub fn project_opened(&self, path: &Path) {
+        self.project_opened.iter().for_each(|f| f(path));
+    }
+
+    pub fn progress(&self, done: usize, total: usize) {
+        self.progress.iter().for_each(|f| f(Progress { done, total }));
+    }
+
+    /// A new project was written to `file`.
+    pub fn created(&self, file: &Path) {
+        self.created.iter().for_each(|f| f(file));
+    }
+
+    /// `field` of the project in `file` was changed.
+    pub fn field_changed(&self, file: &Path, field: &str) {
+        self.field_changed.iter().for_each(|f| f(file, field));
+    }
+
+    /// A project folder was moved from `from` into the archive at `to`.
+    pub fn archived(&self, from: &Path, to: &Path) {
+        self.archived.iter().for_each(|f| f(from, to));
+    }
+
+    /// A project folder was moved from the archive at `from` back to `to`.
+    pub fn unarchived(&self, from: &Path, to: &Path) {
+        self.unarchived.iter().for_each(|f| f(from, to));
+    }
+}
+
+impl fmt::Debug for Hooks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Hooks")
+            .field("project_opened", &self.project_opened.len())
+            .field("progress", &self.progress.len())
+            .field("created", &self.created.len())
+            .field("field_changed", &self.field_changed.len())
+            .field("archived", &self.archived.len())
+            .field("unarchived", &self.unarchived.len())
+            .finish()
+    }
+}
@@ -0,0 +1,358 @@
+//! Records every change asciii makes to the storage, so it can be undone.
+//!
+//! Each mutating action (creating, setting, archiving, deleting projects…) appends an `Entry` to the
+//! journal file in the storage root (`journal/file`).
+//! An entry keeps the content of written files before and after the action and the paths of moved directories.
+//! This also covers changes that were never committed to git.
+//!
+//! `undo()` reverts the latest entry and `redo()` applies it again, until a new action is recorded.
+//! Nothing is reverted if the files changed since, generated documents of deleted projects are lost.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::prelude::*;
+use thiserror::Error;
+use yaml_rust::yaml::{Hash, Yaml};
+use yaml_rust::YamlEmitter;
+
+use crate::error::AsciiiError;
+use crate::config::ConfigReader;
+use crate::util::{self, yaml};
+
+#[derive(Error, Debug)]
+pub enum JournalError {
+    #[error("Nothing to undo.")]
+    NothingToUndo,
+
+    #[error("Nothing to redo.")]
+    NothingToRedo,
+
+    #[error("{} changed since {:?}, refusing to touch it.", _0.display(), _1)]
+    ChangedSince(PathBuf, String),
+
+    #[error("The journal {} is broken.", _0.display())]
+    Broken(PathBuf),
+}
+
+/// A single change to the storage, paths are relative to the storage root.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change {
+    /// A file was written, `None` means it did not exist.
+    Write { path: PathBuf, before: Option<String>, after: Option<String> },
+    /// A directory was moved.
+    Move { from: PathBuf, to: PathBuf },
+}
+
+impl Change {
+    /// Swaps before and after.
+    fn reversed(&self) -> Change {
+        match *self {
+            Change::Write { ref path, ref before, ref after } => Change::Write { path: path.clone(), before: after.clone(), after: before.clone() },
+            Change::Move { ref from, ref to } => Change::Move { from: to.clone(), to: from.clone() },
+        }
+    }
+
+    /// Paths that are touched by this change.
+    pub fn paths(&self) -> Vec<&Path> {
+        match *self {
+            Change::Write { ref path, .. } => vec![path],
+            Change::Move { ref from, ref to } => vec![from, to],
+        }
+    }
+
+    /// Makes sure the storage still looks like right after this change.
+    fn check(&self, root: &Path, action: &str) -> Result<(), AsciiiError> {
+        let unchanged = match *self {
+            Change::Write { ref path, ref after, .. } => fs::read_to_string(root.join(path)).ok() == *after,
+            Change::Move { ref from, ref to } => root.join(to).exists() && !root.join(from).exists(),
+        };
+        if !unchanged {
+            return Err(JournalError::ChangedSince(self.paths()[0].to_owned(), action.to_owned()).into());
+        }
+        Ok(())
+    }
+
+    fn apply(&self, root: &Path) -> Result<(), AsciiiError> {
+        match *self {
+            Change::Write { ref path, after: Some(ref content), .. } => {
+                let path = root.join(path);
+                if let Some(dir) = path.parent() {
+                    fs::create_dir_all(dir)?;
+                }
+                util::atomic_write(&path, content)?;
+            }
+            Change::Write { ref path, after: None, .. } => {
+                let path = root.join(path);
+                if path.exists() {
+                    fs::remove_file(&path)?;
+                }
+                // project dirs are removed together with their file
+                if let Some(dir) = path.parent() {
+                    let _ = fs::remove_dir(dir);
+                }
+            }
+            Change::Move { ref from, ref to } => {
+                if let Some(dir) = root.join(to).parent() {
+                    fs::create_dir_all(dir)?;
+                }
+                fs::rename(root.join(from), root.join(to))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn to_yaml(&self) -> Yaml {
+        let string = |s: &str| Yaml::String(s.to_owned());
+        let path = |p: &Path| string(&p.to_string_lossy());
+        let content = |c: &Option<String>| c.as_ref().map_or(Yaml::Null, |c| string(c));
+
+        let mut hash = Hash::new();
+        match *self {
+            Change::Write { path: ref p, ref before, ref after } => {
+                hash.insert(string("write"), path(p));
+                hash.insert(string("before"), content(before));
+                hash.insert(string("after"), content(after));
+            }
+            Change::Move { ref from, ref to } => {
+                hash.insert(string("move"), path(from));
+                hash.insert(string("to"), path(to));
+            }
+        }
+        Yaml::Hash(hash)
+    }
+
+    fn from_yaml(yaml: &Yaml) -> Option<Change> {
+        if let Some(path) = yaml::get_str(yaml, "write") {
+            Some(Change::Write {
+                path: PathBuf::from(path),
+                before: yaml::get_string(yaml, "before"),
+                after: yaml::get_string(yaml, "after"),
+            })
+        } else {
+            Some(Change::Move {
+                from: PathBuf::from(yaml::get_str(yaml, "move")?),
+                to: PathBuf::from(yaml::get_str(yaml, "to")?),
+            })
+        }
+    }
+}
+
+/// One recorded action.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry {
+    pub date: DateTime<Local>,
+    /// what was done, e.g. `set invoice/payed_date to "08.12.2014" in R036 Party`
+    pub action: String,
+    pub changes: Vec<Change>,
+    /// reverted by `undo()`, may be redone
+    pub undone: bool,
+}
+
+impl Entry {
+    fn to_yaml(&self) -> Yaml {
+        let mut hash = Hash::new();
+        hash.insert(Yaml::String("date".into()), Yaml::String(self.date.to_rfc3339()));
+        hash.insert(Yaml::String("action".into()), Yaml::String(self.action.clone()));
+        hash.insert(Yaml::String("undone".into()), Yaml::Boolean(self.undone));
+        hash.insert(Yaml::String("changes".into()), Yaml::Array(self.changes.iter().map(Change::to_yaml).collect()));
+        Yaml::Hash(hash)
+    }
+
+    fn from_yaml(yaml: &Yaml) -> Option<Entry> {
+        Some(Entry {
+            date: DateTime::parse_from_rfc3339(yaml::get_str(yaml, "date")?).ok()?.with_timezone(&Local),
+            action: yaml::get_string(yaml, "action")?,
+            changes: yaml::get(yaml, "changes")?
+                .as_vec()?
+                .iter()
+                .map(Change::from_yaml)
+                .collect::<Option<Vec<Change>>>()?,
+            undone: yaml::get_bool(yaml, "undone").unwrap_or(false),
+        })
+    }
+}
+
+/// The journal of a storage.
+pub struct Journal {
+    root: PathBuf,
+    file: PathBuf,
+    /// entries kept, older ones are dropped
+    limit: usize,
+}
+
+impl Journal {
+    /// Journal of the storage in `root`, the file is created on the first `record()`.
+    pub fn new(root: &Path) -> Journal {
+        Journal {
+            root: root.to_owned(),
+            file: root.join(".asciii_journal.yml"),
+            limit: 100,
+        }
+    }
+
+    /// Like `new()`, with `journal/file` and `journal/limit` from `config`.
+    pub fn with_config(root: &Path, config: &ConfigReader) -> Journal {
+        let journal = Journal::new(root);
+        Journal {
+            file: config.get_str_or("journal/file").map_or(journal.file, |file| root.join(file)),
+            limit: config.get("journal/limit")
+                         .and_then(Yaml::as_i64)
+                         .map_or(journal.limit, |l| l.max(1) as usize),
+            .. journal
+        }
+    }
+
+    /// Path relative to the storage root, absolute if it lies outside.
+    pub fn relative(&self, path: &Path) -> PathBuf {
+        path.strip_prefix(&self.root).unwrap_or(path).to_owned()
+    }
+
+    /// Describes writing `path`, which contained `before`, reads the new content from disk.
+    pub fn write(&self, path: &Path, before: Option<&str>) -> Change {
+        Change::Write {
+            path: self.relative(path),
+            before: before.map(ToOwned::to_owned),
+            after: fs::read_to_string(path).ok(),
+        }
+    }
+
+    /// Describes moving the directory `from` to `to`.
+    pub fn moved(&self, from: &Path, to: &Path) -> Change {
+        Change::Move { from: self.relative(from), to: self.relative(to) }
+    }
+
+    /// All entries, oldest first.
+    pub fn entries(&self) -> Result<Vec<Entry>, AsciiiError> {
+        if !self.file.exists() {
+            return Ok(Vec::new());
+        }
+        let yaml = yaml::open(&self.file)?;
+        match yaml.as_vec() {
+            Some(entries) => entries.iter()
+                                    .map(Entry::from_yaml)
+                                    .collect::<Option<Vec<Entry>>>()
+                                    .ok_or_else(|| JournalError::Broken(self.file.clone()).into()),
+            None if yaml.is_null() => Ok(Vec::new()),
+            None => Err(JournalError::Broken(self.file.clone()).into())
+        }
+    }
+
+    fn save(&self, entries: &[Entry]) -> Result<(), AsciiiError> {
+        let entries = &entries[entries.len().saturating_sub(self.limit)..];
+
+        let mut out = String::new();
+        YamlEmitter::new(&mut out).dump(&Yaml::Array(entries.iter().map(Entry::to_yaml).collect()))?;
+        out.push('\n');
+        util::atomic_write(&self.file, out)?;
+        Ok(())
+    }
+
+    /// Appends an action, entries that were undone can't be redone afterwards.
+    pub fn record(&self, action: &str, changes: Vec<Change>) -> Result<(), AsciiiError> {
+        if changes.is_empty() {
+            return Ok(());
+        }
+        log::debug!("journal: {}", action);
+        let mut entries = self.entries()?;
+        entries.retain(|e| !e.undone);
+        entries.push(Entry { date: Local::now(), action: action.to_owned(), changes, undone: false });
+        self.save(&entries)
+    }
+
+    /// Reverts the latest action and returns it.
+    pub fn undo(&self) -> Result<Entry, AsciiiError> {
+        let mut entries = self.entries()?;
+        let entry = entries.iter_mut()
+                           .rev()
+                           .find(|e| !e.undone)
+                           .ok_or(JournalError::NothingToUndo)?;
+
+        for change in &entry.changes {
+            change.check(&self.root, &entry.action)?;
+        }
+        for change in entry.changes.iter().rev() {
+            change.reversed().apply(&self.root)?;
+        }
+        entry.undone = true;
+        let entry = entry.clone();
+        self.save(&entries)?;
+        Ok(entry)
+    }
+
+    /// Applies the latest undone action again and returns it.
+    pub fn redo(&self) -> Result<Entry, AsciiiError> {
+        let mut entries = self.entries()?;
+        let entry = entries.iter_mut()
+                           .find(|e| e.undone)
+                           .ok_or(JournalError::NothingToRedo)?;
+
+        for change in &entry.changes {
+            change.reversed().check(&self.root, &entry.action)?;
+        }
+        for change in &entry.changes {
+            change.apply(&self.root)?;
+        }
+        entry.undone = false;
+        let entry = entry.clone();
+        self.save(&entries)?;
+        Ok(entry)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempdir::TempDir;
+
+    #[test]
+    fn undo_and_redo() {
+        let dir = TempDir::new("asciii-journal").unwrap();
+        let journal = Journal::new(dir.path());
+        let file = dir.path().join("working/party/party.yml");
+
+        fs::create_dir_all(file.parent().unwrap()).unwrap();
+        fs::write(&file, "a: 1\n").unwrap();
+        journal.record("create party", vec![journal.write(&file, None)]).unwrap();
+
+        fs::write(&file, "a: 2\n").unwrap();
+        journal.record("set a", vec![journal.write(&file, Some("a: 1\n"))]).unwrap();
+
+        let archived = dir.path().join("archive/2020/party");
+        fs::create_dir_all(archived.parent().unwrap()).unwrap();
+        fs::rename(file.parent().unwrap(), &archived).unwrap();
+        journal.record("archive party", vec![journal.moved(file.parent().unwrap(), &archived)]).unwrap();
+
+        assert_eq!(journal.undo().unwrap().action, "archive party");
+        assert_eq!(journal.undo().unwrap().action, "set a");
+        assert_eq!(fs::read_to_string(&file).unwrap(), "a: 1\n");
+
+        assert_eq!(journal.redo().unwrap().action, "set a");
+        assert_eq!(fs::read_to_string(&file).unwrap(), "a: 2\n");
+
+        // a new action drops what was undone
+        journal.record("touch", vec![journal.write(&dir.path().join("other"), None)]).unwrap();
+        assert!(journal.redo().is_err());
+
+        assert_eq!(journal.entries().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn refuse_changed_files() {
+        let dir = TempDir::new("asciii-journal").unwrap();
+        let journal = Journal::new(dir.path());
+        let file = dir.path().join("party.yml");
+
+        fs::write(&file, "a: 2\n").unwrap();
+        journal.record("set a", vec![journal.write(&file, Some("a: 1\n"))]).unwrap();
+        fs::write(&file, "a: 3\n").unwrap();
+
+        assert!(journal.undo().is_err());
+        assert_eq!(fs::read_to_string(&file).unwrap(), "a: 3\n");
+
+        fs::remove_file(&file).unwrap();
+        journal.record("delete party", vec![Change::Write { path: "party.yml".into(), before: Some("a: 3\n".into()), after: None }]).unwrap();
+        journal.undo().unwrap();
+        assert_eq!(fs::read_to_string(&file).unwrap(), "a: 3\n");
+    }
+}
@@ -0,0 +1,162 @@
+//! Every invoice number that was ever issued, so numbering can be shown to be gapless.
+//!
+//! `asciii invoice` takes the next number of the current year from the ledger (`ledger/file` in the storage root)
+//! and records it there together with the project it went to.
+//! Numbers stay in the ledger even if their project is deleted later, `asciii report numbers` points those out.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::prelude::*;
+use thiserror::Error;
+use yaml_rust::yaml::{Hash, Yaml};
+use yaml_rust::YamlEmitter;
+
+use crate::config::ConfigReader;
+use crate::error::AsciiiError;
+use crate::util::{self, yaml};
+
+#[derive(Error, Debug)]
+pub enum LedgerError {
+    #[error("Invoice number {} of {} was already issued to {:?}.", _1, _0, _2)]
+    AlreadyIssued(i32, u32, String),
+
+    #[error("The ledger {} is broken.", _0.display())]
+    Broken(PathBuf),
+}
+
+/// One issued invoice number.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Issued {
+    pub year: i32,
+    pub number: u32,
+    /// name of the project at the time
+    pub project: String,
+    /// when the number was issued
+    pub date: NaiveDate,
+}
+
+impl Issued {
+    fn to_yaml(&self) -> Yaml {
+        let mut hash = Hash::new();
+        hash.insert(Yaml::String("year".into()), Yaml::Integer(i64::from(self.year)));
+        hash.insert(Yaml::String("number".into()), Yaml::Integer(i64::from(self.number)));
+        hash.insert(Yaml::String("project".into()), Yaml::String(self.project.clone()));
+        hash.insert(Yaml::String("date".into()), Yaml::String(self.date.format("%d.%m.%Y").to_string()));
+        Yaml::Hash(hash)
+    }
+
+    fn from_yaml(yaml: &Yaml) -> Option<Issued> {
+        Some(Issued {
+            year: yaml::get_int(yaml, "year")? as i32,
+            number: yaml::get_int(yaml, "number")? as u32,
+            project: yaml::get_string(yaml, "project").unwrap_or_default(),
+            date: yaml::get_str(yaml, "date").and_then(yaml::parse_dmy_date)?.naive_utc(),
+        })
+    }
+}
+
+/// The ledger of a storage.
+pub struct Ledger {
+    file: PathBuf,
+}
+
+impl Ledger {
+    /// Ledger of the storage in `root`, the file is created with the first issued number.
+    pub fn new(root: &Path) -> Ledger {
+        Ledger { file: root.join(".asciii_ledger.yml") }
+    }
+
+    /// Like `new()`, with `ledger/file` from `config`.
+    pub fn with_config(root: &Path, config: &ConfigReader) -> Ledger {
+        match config.get_str_or("ledger/file") {
+            Some(file) => Ledger { file: root.join(file) },
+            None => Ledger::new(root),
+        }
+    }
+
+    pub fn file(&self) -> &Path {
+        &self.file
+    }
+
+    /// All issued numbers, in the order they were issued.
+    pub fn issued(&self) -> Result<Vec<Issued>, AsciiiError> {
+        if !self.file.exists() {
+            return Ok(Vec::new());
+        }
+        let yaml = yaml::open(&self.file)?;
+        match yaml.as_vec() {
+            Some(entries) => entries.iter()
+                                    .map(Issued::from_yaml)
+                                    .collect::<Option<Vec<Issued>>>()
+                                    .ok_or_else(|| LedgerError::Broken(self.file.clone()).into()),
+            None if yaml.is_null() => Ok(Vec::new()),
+            None => Err(LedgerError::Broken(self.file.clone()).into())
+        }
+    }
+
+    /// Numbers issued in `year`, ascending.
+    pub fn numbers(&self, year: i32) -> Result<Vec<u32>, AsciiiError> {
+        let mut numbers = self.issued()?
+                              .into_iter()
+                              .filter(|issued| issued.year == year)
+                              .map(|issued| issued.number)
+                              .collect::<Vec<_>>();
+        numbers.sort_unstable();
+        Ok(numbers)
+    }
+
+    /// The number after the highest one of `year`, in the ledger or in `known`.
+    ///
+    /// `known` are the numbers projects already carry, they count for years from before the ledger.
+    pub fn next_number(&self, year: i32, known: impl IntoIterator<Item = u32>) -> Result<u32, AsciiiError> {
+        let highest = self.numbers(year)?
+                          .into_iter()
+                          .chain(known)
+                          .max()
+                          .unwrap_or(0);
+        Ok(highest + 1)
+    }
+
+    /// Records `number` of `year` for `project`, a number is only ever issued once.
+    pub fn issue(&self, year: i32, number: u32, project: &str) -> Result<Issued, AsciiiError> {
+        let mut entries = self.issued()?;
+        if let Some(issued) = entries.iter().find(|issued| issued.year == year && issued.number == number) {
+            return Err(LedgerError::AlreadyIssued(year, number, issued.project.clone()).into());
+        }
+
+        let issued = Issued { year, number, project: project.to_owned(), date: Local::today().naive_local() };
+        entries.push(issued.clone());
+
+        let mut out = String::new();
+        YamlEmitter::new(&mut out).dump(&Yaml::Array(entries.iter().map(Issued::to_yaml).collect()))?;
+        out.push('\n');
+        if let Some(dir) = self.file.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        util::atomic_write(&self.file, out)?;
+        Ok(issued)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempdir::TempDir;
+
+    #[test]
+    fn numbers_are_issued_once() {
+        let dir = TempDir::new("asciii-ledger").unwrap();
+        let ledger = Ledger::new(dir.path());
+        assert_eq!(ledger.next_number(2024, vec![3, 1]).unwrap(), 4);
+
+        ledger.issue(2024, 4, "Party").unwrap();
+        ledger.issue(2025, 1, "Hochzeit").unwrap();
+        assert_eq!(ledger.next_number(2024, None).unwrap(), 5);
+        assert_eq!(ledger.next_number(2025, vec![]).unwrap(), 2);
+        assert_eq!(ledger.numbers(2024).unwrap(), vec![4]);
+
+        assert!(ledger.issue(2024, 4, "Feier").is_err());
+        assert_eq!(ledger.issued().unwrap()[1].project, "Hochzeit");
+    }
+}
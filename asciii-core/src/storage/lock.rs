@@ -0,0 +1,254 @@
+//! Advisory locks on project directories, so two people don't edit the same project at once.
+//!
+//! `asciii edit` and `asciii set` put a `.asciii.lock` into the project directory while they work on it,
+//! naming who holds it, on which host and since when. Storages shared through a sync service carry the lock
+//! to everyone else, who then get told who is editing instead of overwriting each other.
+//! Locks whose process is gone (same host) or that are older than `lock/stale_hours` are taken over.
+//!
+//! The lock is written completely before it appears (a hard link to a temporary file), so it is never seen half written.
+//! A lock that can't be read anyway, e.g. while a sync service is still writing it, is only taken over
+//! once it is older than a few seconds.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time;
+
+use chrono::prelude::*;
+use chrono::Duration;
+use thiserror::Error;
+use yaml_rust::Yaml;
+
+use crate::config::ConfigReader;
+use crate::error::AsciiiError;
+use crate::util::yaml;
+
+/// Name of the lock file inside a project directory.
+pub const LOCK_FILE: &str = ".asciii.lock";
+
+/// How long an unreadable lock is waited for before it is taken over.
+const UNREADABLE_GRACE: time::Duration = time::Duration::from_secs(5);
+
+/// Tells apart the temporary files of locks acquired at the same time.
+static ATTEMPT: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Error, Debug)]
+pub enum LockError {
+    /// project, holder, lock file
+    #[error("{} is locked by {} on {} since {}, remove {} if nobody is editing it.", _0, _1.user, _1.host, _1.since_str(), _2.display())]
+    Locked(String, Holder, PathBuf),
+}
+
+/// Whoever holds a lock.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Holder {
+    pub user: String,
+    pub host: String,
+    pub pid: u32,
+    pub since: DateTime<FixedOffset>,
+}
+
+impl Holder {
+    /// This process.
+    fn current(user: &str) -> Holder {
+        let now = Local::now();
+        Holder {
+            user: user.to_owned(),
+            host: hostname(),
+            pid: process::id(),
+            since: now.with_timezone(now.offset()),
+        }
+    }
+
+    fn from_yaml(doc: &Yaml) -> Option<Holder> {
+        Some(Holder {
+            user: yaml::get_string(doc, "user")?,
+            host: yaml::get_string(doc, "host")?,
+            pid: yaml::get_int(doc, "pid")? as u32,
+            since: DateTime::parse_from_rfc3339(yaml::get_str(doc, "since")?).ok()?,
+        })
+    }
+
+    fn to_yaml_string(&self) -> String {
+        format!("user: {:?}\nhost: {:?}\npid: {}\nsince: {:?}\n", self.user, self.host, self.pid, self.since.to_rfc3339())
+    }
+
+    /// Time of day if it was today, with the date otherwise.
+    pub fn since_str(&self) -> String {
+        if self.since.naive_local().date() == Local::now().naive_local().date() {
+            self.since.format("%H:%M").to_string()
+        } else {
+            self.since.format("%d.%m.%Y %H:%M").to_string()
+        }
+    }
+
+    /// The holder is gone or has held the lock for longer than `stale_after`.
+    fn is_stale(&self, stale_after: Option<Duration>) -> bool {
+        let expired = stale_after.is_some_and(|limit| Local::now().signed_duration_since(self.since) > limit);
+        expired || (self.host == hostname() && !process_runs(self.pid))
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn process_runs(pid: u32) -> bool {
+    Path::new("/proc").join(pid.to_string()).exists()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn process_runs(_pid: u32) -> bool {
+    true
+}
+
+/// Name of this machine, `"unknown"` if it can't be found out.
+pub fn hostname() -> String {
+    std::env::var("HOSTNAME").ok()
+        .or_else(|| fs::read_to_string("/etc/hostname").ok())
+        .or_else(|| process::Command::new("hostname").output().ok()
+                                                      .and_then(|out| String::from_utf8(out.stdout).ok()))
+        .map(|name| name.trim().to_owned())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| String::from("unknown"))
+}
+
+/// Holds the lock of a project directory until it is dropped.
+#[derive(Debug)]
+pub struct ProjectLock {
+    file: PathBuf,
+    holder: Holder,
+}
+
+impl ProjectLock {
+    /// Locks `dir` for `user`, locks older than `stale_after` are taken over.
+    pub fn acquire(dir: &Path, user: &str, stale_after: Option<Duration>) -> Result<ProjectLock, AsciiiError> {
+        let file = dir.join(LOCK_FILE);
+        let holder = Holder::current(user);
+        loop {
+            match ProjectLock::create(&file, &holder) {
+                Ok(()) => return Ok(ProjectLock { file, holder }),
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+                    match ProjectLock::holder(dir) {
+                        Some(other) if !other.is_stale(stale_after) => {
+                            let project = dir.file_name().map_or_else(|| dir.display().to_string(), |name| name.to_string_lossy().into_owned());
+                            return Err(LockError::Locked(project, other, file).into());
+                        }
+                        Some(other) => log::warn!("taking over the stale lock of {} on {} since {}", other.user, other.host, other.since_str()),
+                        None if age(&file).is_some_and(|age| age < UNREADABLE_GRACE) => {
+                            thread::sleep(time::Duration::from_millis(100));
+                            continue;
+                        }
+                        None => log::warn!("taking over the unreadable lock {}", file.display()),
+                    }
+                    match fs::remove_file(&file) {
+                        Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e.into()),
+                        _ => continue,
+                    }
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+
+    /// Puts a complete lock of `holder` into place, fails with `AlreadyExists` if there is one.
+    fn create(file: &Path, holder: &Holder) -> io::Result<()> {
+        let temp = file.with_file_name(format!("{}.{}.{}", LOCK_FILE, process::id(), ATTEMPT.fetch_add(1, Ordering::Relaxed)));
+        fs::write(&temp, holder.to_yaml_string())?;
+        let linked = fs::hard_link(&temp, file);
+        fs::remove_file(&temp)?;
+        linked
+    }
+
+    /// Like `acquire()`, with the user from `user/name` and `lock/stale_hours` from `config`.
+    pub fn with_config(dir: &Path, config: &ConfigReader) -> Result<ProjectLock, AsciiiError> {
+        let user = config.get_str_or("user/name")
+                         .filter(|name| !name.is_empty())
+                         .map(ToOwned::to_owned)
+                         .or_else(|| std::env::var("USER").ok())
+                         .unwrap_or_else(|| String::from("unknown"));
+        let stale_after = config.get("lock/stale_hours")
+                                .and_then(Yaml::as_i64)
+                                .filter(|hours| *hours > 0)
+                                .map(Duration::hours);
+        ProjectLock::acquire(dir, &user, stale_after)
+    }
+
+    /// Who holds the lock on `dir`, if anyone.
+    pub fn holder(dir: &Path) -> Option<Holder> {
+        let content = fs::read_to_string(dir.join(LOCK_FILE)).ok()?;
+        Holder::from_yaml(&yaml::parse(&content).ok()?)
+    }
+
+    pub fn file(&self) -> &Path {
+        &self.file
+    }
+}
+
+/// How long ago `file` was last written.
+fn age(file: &Path) -> Option<time::Duration> {
+    fs::metadata(file).and_then(|meta| meta.modified()).ok()?.elapsed().ok()
+}
+
+impl Drop for ProjectLock {
+    fn drop(&mut self) {
+        // someone may have taken it over in the meantime
+        if ProjectLock::holder(self.file.parent().unwrap_or_else(|| Path::new("."))).as_ref() == Some(&self.holder) {
+            if let Err(e) = fs::remove_file(&self.file) {
+                log::warn!("can't remove the lock {}: {}", self.file.display(), e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempdir::TempDir;
+
+    #[test]
+    fn one_holder_at_a_time() {
+        let dir = TempDir::new("asciii-lock").unwrap();
+        let lock = ProjectLock::acquire(dir.path(), "alice", None).unwrap();
+        assert_eq!(ProjectLock::holder(dir.path()).unwrap().user, "alice");
+
+        let error = ProjectLock::acquire(dir.path(), "bob", None).unwrap_err();
+        assert!(error.to_string().contains("locked by alice"), "{}", error);
+
+        drop(lock);
+        assert!(!dir.path().join(LOCK_FILE).exists());
+        let _lock = ProjectLock::acquire(dir.path(), "bob", None).unwrap();
+    }
+
+    #[test]
+    fn stale_locks_are_taken_over() {
+        let dir = TempDir::new("asciii-lock").unwrap();
+        let old = Holder {
+            since: DateTime::parse_from_rfc3339("2020-01-01T10:32:00+01:00").unwrap(),
+            host: String::from("elsewhere"),
+            .. Holder::current("alice")
+        };
+        fs::write(dir.path().join(LOCK_FILE), old.to_yaml_string()).unwrap();
+        assert_eq!(ProjectLock::holder(dir.path()), Some(old));
+
+        assert!(ProjectLock::acquire(dir.path(), "bob", None).is_err());
+        let lock = ProjectLock::acquire(dir.path(), "bob", Some(Duration::hours(12))).unwrap();
+        assert_eq!(ProjectLock::holder(dir.path()).unwrap().user, "bob");
+        drop(lock);
+    }
+
+    #[test]
+    fn unreadable_locks_get_a_grace_period() {
+        let dir = TempDir::new("asciii-lock").unwrap();
+        let file = dir.path().join(LOCK_FILE);
+        fs::write(&file, "user: \"ali").unwrap();
+        assert_eq!(ProjectLock::holder(dir.path()), None);
+
+        let start = time::Instant::now();
+        let lock = ProjectLock::acquire(dir.path(), "bob", None).unwrap();
+        assert!(start.elapsed() >= time::Duration::from_secs(4));
+        assert_eq!(ProjectLock::holder(dir.path()).unwrap().user, "bob");
+        drop(lock);
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 0, "no temporary files are left behind");
+    }
+}
@@ -0,0 +1,126 @@
+//! A storage that only lives in memory, for places without a filesystem like the browser.
+//!
+//! Files are kept under the same relative paths they would have on disk,
+//! e.g. `working/party/party.yml` or `archive/2015/R036_party/party.yml`.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::error::AsciiiError;
+use crate::project::Project;
+
+use super::{ProjectList, Storable, StorageDir, StorageError, Year};
+
+/// Project files by path, see `Storage` for the layout.
+#[derive(Debug, Clone)]
+pub struct MemoryStorage {
+    files: BTreeMap<PathBuf, String>,
+    working: PathBuf,
+    archive: PathBuf,
+    extension: String,
+}
+
+impl Default for MemoryStorage {
+    fn default() -> Self {
+        MemoryStorage::with_dir_names("working", "archive")
+    }
+}
+
+impl MemoryStorage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Uses other names than `working` and `archive`, like `dirs/working` and `dirs/archive` would.
+    pub fn with_dir_names(working: &str, archive: &str) -> Self {
+        MemoryStorage {
+            files: BTreeMap::new(),
+            working: PathBuf::from(working),
+            archive: PathBuf::from(archive),
+            extension: crate::CONFIG.get_to_string("extensions.project_file"),
+        }
+    }
+
+    /// Adds or replaces a file.
+    pub fn insert<P: Into<PathBuf>>(&mut self, path: P, content: String) {
+        self.files.insert(path.into(), content);
+    }
+
+    pub fn remove(&mut self, path: &Path) -> Option<String> {
+        self.files.remove(path)
+    }
+
+    pub fn read(&self, path: &Path) -> Option<&str> {
+        self.files.get(path).map(String::as_str)
+    }
+
+    /// All files, in order of their paths.
+    pub fn files(&self) -> impl Iterator<Item = &Path> {
+        self.files.keys().map(PathBuf::as_path)
+    }
+
+    /// Archived years, in ascending order.
+    pub fn list_years(&self) -> Vec<Year> {
+        let mut years = self.files()
+            .filter_map(|path| path.strip_prefix(&self.archive).ok())
+            .filter_map(|rest| rest.components().next())
+            .filter_map(|year| year.as_os_str().to_str()?.parse::<Year>().ok())
+            .collect::<Vec<_>>();
+        years.sort_unstable();
+        years.dedup();
+        years
+    }
+
+    /// Project files of `directory`, each one in its own folder.
+    pub fn list_project_files(&self, directory: StorageDir) -> Result<Vec<&Path>, AsciiiError> {
+        let parents = match directory {
+            StorageDir::Working => vec![self.working.clone()],
+            StorageDir::Archive(year) => vec![self.archive.join(year.to_string())],
+            StorageDir::Year(year) => vec![self.archive.join(year.to_string()), self.working.clone()],
+            StorageDir::All => {
+                let mut parents = self.list_years().into_iter()
+                    .map(|year| self.archive.join(year.to_string()))
+                    .collect::<Vec<_>>();
+                parents.push(self.working.clone());
+                parents
+            },
+            _ => return Err(StorageError::BadChoice.into())
+        };
+
+        Ok(parents.iter()
+            .flat_map(|parent| self.files()
+                      .filter(move |path| path.parent().and_then(Path::parent) == Some(parent.as_path())))
+            .filter(|path| path.extension().and_then(|ext| ext.to_str()) == Some(self.extension.as_str()))
+            .collect())
+    }
+
+    /// Like `Storage::open_projects()`, projects that can't be parsed are left out.
+    pub fn open_projects(&self, directory: StorageDir) -> Result<ProjectList<Project>, AsciiiError> {
+        let projects = self.list_project_files(directory)?
+            .into_iter()
+            .filter_map(|path| self.open_project(path).map_err(|e| log::warn!("{}: {}", path.display(), e)).ok())
+            .collect();
+        let mut projects = ProjectList { projects };
+        if let StorageDir::Year(year) = directory {
+            projects.filter_by_key_val("Year", year.to_string().as_ref());
+        }
+        Ok(projects)
+    }
+
+    /// Opens the project file at `path`.
+    pub fn open_project(&self, path: &Path) -> Result<Project, AsciiiError> {
+        let content = self.read(path).ok_or(StorageError::ProjectDoesNotExist)?;
+        let mut project = Project::from_file_content(content)?;
+        project.set_file(path);
+        Ok(project)
+    }
+
+    /// Finds a project of `directory` by its folder name, like `Storage::search_projects()`.
+    pub fn find_project(&self, directory: StorageDir, name: &str) -> Result<Project, AsciiiError> {
+        let path = self.list_project_files(directory)?
+            .into_iter()
+            .find(|path| path.parent().and_then(Path::file_name).and_then(|dir| dir.to_str()) == Some(name))
+            .ok_or_else(|| StorageError::NothingFound(vec![name.to_owned()]))?;
+        self.open_project(path)
+    }
+}
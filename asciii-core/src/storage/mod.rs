@@ -0,0 +1,1582 @@
+//! Manages file structure of templates, working directory and archives.
+//!
+//! This module takes care of project file management.
+//!
+//! Your ordinary file structure would look something like this:
+//!
+//! ```bash
+//! # root dir
+//! ├── working
+//! │   └── Project1
+//! │       └── Project1.yml
+//! ├── archive
+//! │   ├── 2013
+//! │   └── 2014
+//! │       └── R036_Project3
+//! │           ├── Project3.yml
+//! │           └── R036 Project3 2014-10-08.tex
+//! ...
+//! ```
+//!
+
+#[cfg(feature="rayon")] use rayon::prelude::*;
+
+use std::fs;
+use std::env;
+use std::path::{Path, PathBuf};
+use std::marker::PhantomData;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::OnceLock;
+
+use crate::error::AsciiiError;
+use crate::config::ConfigReader;
+
+/// Year = `i32`
+pub type Year =  i32;
+
+/// Templates that come with asciii, named for the default settings, see `Storage::materialize_default_templates()`.
+pub const DEFAULT_TEMPLATES: &[(&str, &str)] = &[
+    ("default.tyml",   include_str!("../../templates/default.tyml")),
+    ("export.tex.hbs", include_str!("../../templates/export.tex.hbs")),
+];
+
+#[cfg(test)] mod tests;
+#[cfg(test)] mod realworld;
+
+mod project_list;
+pub use self::project_list::{ProjectList, ProjectsByYear, Projects};
+pub mod repo;
+pub mod cache;
+pub mod journal;
+use self::journal::{Change, Journal};
+pub mod ledger;
+use self::ledger::Ledger;
+pub mod lock;
+pub mod closing;
+use self::closing::ClosedYears;
+pub mod archive_index;
+pub mod clients;
+use self::clients::{ClientDatabase, CLIENTS_FILE};
+use self::lock::ProjectLock;
+pub mod transaction;
+pub use self::transaction::Transaction;
+mod builder;
+pub use self::builder::StorageBuilder;
+pub mod hooks;
+pub use self::hooks::{Hooks, Progress};
+pub mod memory;
+pub use self::memory::MemoryStorage;
+#[cfg(feature = "async")] mod nonblocking;
+pub mod error;
+pub use self::error::StorageError;
+pub mod storable;
+pub use self::storable::*;
+pub mod sort;
+pub use self::sort::{SortComponent, SortKey, SortOrder};
+
+
+// TODO: rely more on IoError, it has most of what you need
+/// Manages project file storage.
+///
+/// This includes:
+///
+/// * keeping current projects in a working directory
+/// * listing project folders and files
+/// * listing templates
+/// * archiving and unarchiving projects
+/// * git interaction
+pub struct Storage<L:Storable> {
+    /// Root of the entire Structure.
+    root:  PathBuf,
+
+    /// Place for project directories.
+    working:  PathBuf,
+
+    /// Place for archive directories (*e.g. `2015/`*) each containing project directories.
+    archive:  PathBuf,
+
+    /// Place for template files.
+    templates: PathBuf,
+
+    /// Place for extra files.
+    extras: PathBuf,
+
+    project_type: PhantomData<L>,
+
+    repository: Option<Repository>,
+
+    /// Refuses to change anything, see `StorageBuilder::read_only()`.
+    read_only: bool,
+
+    /// Settings this storage was set up with, `crate::CONFIG` unless told otherwise.
+    config: Arc<ConfigReader>,
+
+    /// Callbacks of embedders, see `on_project_opened()` and friends.
+    hooks: Hooks,
+
+    /// `extras/clients.yml`, read once when the first project is opened.
+    clients: OnceLock<Arc<ClientDatabase>>,
+
+    /// Opens projects, see `open_paths()`.
+    #[cfg(feature="rayon")]
+    thread_pool: OnceLock<Option<rayon::ThreadPool>>,
+}
+
+/// Used to identify what directory you are talking about.
+#[derive(Debug,Clone,Copy)]
+pub enum StorageDir {
+    /// Describes exclusively the working directory.
+    Working,
+    /// Describes exclusively one year's archive.
+    Archive(Year),
+
+    /// Describes archive of year and working directory,
+    /// if this year is still current.
+    Year(Year),
+
+    /// Parent of `Working`, `Archive` and `Templates`.
+    Root,
+
+    /// Place to store templates.
+    Templates,
+
+    /// Place to store extra.
+    Extras,
+    /// `Archive` and `Working` directory, not `Templates`.
+    All
+}
+
+/// A description from which we can open Storables
+#[derive(Debug, Clone)]
+pub enum StorageSelection {
+    DirAndSearch(StorageDir, Vec<String>),
+    Dir(StorageDir),
+    Paths(Vec<PathBuf>),
+    Uninitialized
+}
+
+impl<'a> From<&'a StorageSelection> for StorageSelection {
+    fn from(val: &'a StorageSelection) -> Self {
+        val.clone()
+    }
+}
+
+
+impl From<StorageDir> for StorageSelection {
+    fn from(val: StorageDir) -> Self {
+        StorageSelection::Dir(val)
+    }
+}
+
+
+impl Default for StorageSelection {
+    fn default() -> Self {
+        StorageSelection::DirAndSearch(StorageDir::Working, Vec::new())
+    }
+}
+
+/// A project file or folder that could not be opened, see `Storage::open_projects_with_failures()`.
+#[derive(Debug)]
+pub struct OpenFailure {
+    pub path: PathBuf,
+    pub error: AsciiiError,
+}
+
+impl fmt::Display for OpenFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path.display(), self.error)
+    }
+}
+
+/// Search terms like `N3` select the third project by index.
+fn is_index_term(term: &str) -> bool {
+    term.strip_prefix('N').is_some_and(|n| n.parse::<usize>().is_ok())
+}
+
+fn is_dot_file(path: &Path) -> bool {
+    path
+        .file_name()
+        .and_then(std::ffi::OsStr::to_str)
+        .and_then(|s|s.chars().next())
+        .map(|c| c == '.')
+        .unwrap_or(false)
+}
+
+#[cfg_attr(feature = "serialization", derive(Serialize))]
+#[derive(Debug)]
+pub struct Paths {
+    pub storage:   PathBuf,
+    pub working:   PathBuf,
+    pub archive:   PathBuf,
+    pub templates: PathBuf
+}
+
+/// Basically `ls`, returns a list of paths.
+pub fn list_path_content(path:&Path) -> Result<Vec<PathBuf>, AsciiiError> {
+    let _timer = util::timing::start("storage scan");
+    if !path.exists() {
+        log::error!("Path does not exist: {}", path.display());
+    }
+
+    Ok(fs::read_dir(path)?
+        .filter_map(Result::ok)
+        .filter(|entry| !is_dot_file(&entry.path()))
+        .map(|entry| entry.path())
+        .collect::<Vec<PathBuf>>())
+}
+
+/// Interprets storage path from config.
+///
+/// Even if it starts with `~` or is a relative path.
+/// This is by far the most important function of all utility functions.
+pub fn get_storage_path() -> PathBuf {
+    storage_path(&crate::CONFIG)
+}
+
+/// Like `get_storage_path()`, with settings from `config`.
+pub fn storage_path(config: &ConfigReader) -> PathBuf
+{
+    let storage_path = PathBuf::from(config.var_get_str("path"))
+            .join(config.var_get_str("dirs/storage"));
+
+    util::paths::absolute(&storage_path)
+}
+
+
+/// Sets up an instance of `Storage`.
+pub fn setup<L:Storable>() -> Result<Storage<L>, AsciiiError> {
+    log::trace!("storage::setup()");
+    Storage::setup(Arc::clone(&crate::CONFIG))
+}
+
+/// Sets up an instance of `Storage`, with git turned on.
+pub fn setup_with_git<L:Storable>() -> Result<Storage<L>, AsciiiError> {
+    log::trace!("storage::setup_with_git()");
+    Storage::setup_with_git(Arc::clone(&crate::CONFIG))
+}
+
+/// Something `Storage::repair()` did.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Repair {
+    /// a missing directory was created
+    Created(PathBuf),
+    /// the owner got read and write access back
+    Permissions(PathBuf),
+    /// a default template was written
+    Template(PathBuf),
+}
+
+/// Gives the owner read and write access to `path` and everything below, except `.git`.
+#[cfg(unix)]
+fn repair_permissions(path: &Path, repairs: &mut Vec<Repair>) -> Result<(), AsciiiError> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let meta = fs::symlink_metadata(path)?;
+    if meta.file_type().is_symlink() {
+        return Ok(());
+    }
+    let needed = if meta.is_dir() { 0o700 } else { 0o600 };
+    let mode = meta.permissions().mode();
+    if mode & needed != needed {
+        fs::set_permissions(path, fs::Permissions::from_mode(mode | needed))?;
+        repairs.push(Repair::Permissions(path.to_owned()));
+    }
+
+    if meta.is_dir() {
+        for entry in fs::read_dir(path)? {
+            let entry = entry?.path();
+            if entry.file_name() != Some(OsStr::new(".git")) {
+                repair_permissions(&entry, repairs)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Working, archive and templates directory names from `config`.
+fn dir_names(config: &ConfigReader) -> Result<(&str, &str, &str), AsciiiError> {
+    let working   = config.get_str_or("dirs/working")  .ok_or_else(||StorageError::FaultyConfig("dirs/working".into()))?;
+    let archive   = config.get_str_or("dirs/archive")  .ok_or_else(||StorageError::FaultyConfig("dirs/archive".into()))?;
+    let templates = config.get_str_or("dirs/templates").ok_or_else(||StorageError::FaultyConfig("dirs/templates".into()))?;
+    Ok((working, archive, templates))
+}
+
+
+
+use self::repo::Repository;
+use crate::util;
+use crate::templater::{self, Variable};
+
+use std::fmt;
+use std::ffi::OsStr;
+use std::ops::DerefMut;
+use std::collections::HashMap;
+use linked_hash_map::LinkedHashMap;
+
+/// How project names become directory and file names, see `slugs` in the config.
+///
+/// The default is what asciii always did: lowercase ascii, no table, no limit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlugStrategy {
+    /// replaced before anything else, e.g. `ä` by `ae`
+    pub transliterate: Vec<(String, String)>,
+    /// in characters, `0` for unlimited
+    pub max_length: usize,
+    pub lowercase: bool,
+}
+
+impl Default for SlugStrategy {
+    fn default() -> SlugStrategy {
+        SlugStrategy { transliterate: Vec::new(), max_length: 0, lowercase: true }
+    }
+}
+
+impl SlugStrategy {
+    /// From `slugs/transliterate`, `slugs/max_length` and `slugs/lowercase`.
+    pub fn with_config(config: &ConfigReader) -> SlugStrategy {
+        let default = SlugStrategy::default();
+        SlugStrategy {
+            transliterate: config.get("slugs/transliterate")
+                                 .and_then(yaml_rust::Yaml::as_hash)
+                                 .map(|table| table.iter()
+                                                   .filter_map(|(from, to)| Some((from.as_str()?.to_owned(), to.as_str().unwrap_or_default().to_owned())))
+                                                   .collect())
+                                 .unwrap_or(default.transliterate),
+            max_length: config.get("slugs/max_length")
+                              .and_then(yaml_rust::Yaml::as_i64)
+                              .map_or(default.max_length, |max| max.max(0) as usize),
+            lowercase: config.get("slugs/lowercase")
+                             .and_then(yaml_rust::Yaml::as_bool)
+                             .unwrap_or(default.lowercase),
+        }
+    }
+
+    /// Ascii letters and digits, everything else becomes a single `-`.
+    pub fn slugify(&self, name: &str) -> String {
+        let name = self.transliterate.iter()
+                       .fold(name.to_owned(), |name, (from, to)| name.replace(from.as_str(), to));
+
+        let mut slug = String::with_capacity(name.len());
+        for c in deunicode::deunicode(&name).chars() {
+            if c.is_ascii_alphanumeric() {
+                slug.push(if self.lowercase { c.to_ascii_lowercase() } else { c });
+            } else if !slug.is_empty() && !slug.ends_with('-') {
+                slug.push('-');
+            }
+        }
+        if self.max_length > 0 {
+            slug.truncate(self.max_length);
+        }
+        slug.trim_end_matches('-').to_owned()
+    }
+}
+
+impl<L:Storable> Storage<L> {
+
+    /// Configures a storage step by step, see `StorageBuilder`.
+    pub fn builder() -> StorageBuilder<L> {
+        StorageBuilder::new()
+    }
+
+    /// How names of new projects become directory names.
+    /// The order of listings and `N` search terms, see `list/sort_key`.
+    pub fn sort_order(&self) -> SortOrder {
+        SortOrder::with_config(&self.config)
+    }
+
+    pub fn slug_strategy(&self) -> SlugStrategy {
+        SlugStrategy::with_config(&self.config)
+    }
+
+    fn slugify(&self, name: &str) -> String {
+        self.slug_strategy().slugify(name)
+    }
+
+    /// Directory names `name` may have, older projects may still be named the way asciii always did.
+    fn slug_candidates(&self, name: &str) -> Vec<String> {
+        let mut candidates = vec![self.slugify(name)];
+        let legacy = SlugStrategy::default().slugify(name);
+        if !candidates.contains(&legacy) {
+            candidates.push(legacy);
+        }
+        candidates
+    }
+
+    /// Inits storage, does not check existence, yet. TODO
+    #[deprecated(note = "use `Storage::builder()`")]
+    pub fn try_new<P: AsRef<Path>>(root:P, working:&str, archive:&str, template:&str) -> Result<Self, AsciiiError> {
+        StorageBuilder::new()
+            .root(root.as_ref())
+            .working_dir(working)
+            .archive_dir(archive)
+            .templates_dir(template)
+            .build()
+    }
+
+    /// Inits storage with git capabilities.
+    #[deprecated(note = "use `Storage::builder()` with `git(true)`")]
+    pub fn try_new_with_git<P: AsRef<Path>>(root:P, working:&str, archive:&str, template:&str) -> Result<Self, AsciiiError> {
+        StorageBuilder::new()
+            .root(root.as_ref())
+            .working_dir(working)
+            .archive_dir(archive)
+            .templates_dir(template)
+            .git(true)
+            .build()
+    }
+
+    /// Sets up the storage `config` points to, its `.asciii.yml` is merged into `config`.
+    pub fn setup(config: Arc<ConfigReader>) -> Result<Self, AsciiiError> {
+        let mut storage = StorageBuilder::new().config(config).build()?;
+        storage.health_check()?;
+        #[cfg(feature="serialization")] crate::webhooks::install(&mut storage);
+        Ok(storage)
+    }
+
+    /// Like `setup()`, with git turned on unless `ASCIII_NO_GIT` is set.
+    pub fn setup_with_git(config: Arc<ConfigReader>) -> Result<Self, AsciiiError> {
+        let mut storage = StorageBuilder::new()
+            .config(config)
+            .git(env::var("ASCIII_NO_GIT").is_err())
+            .build()?;
+        storage.health_check()?;
+        #[cfg(feature="serialization")] crate::webhooks::install(&mut storage);
+        Ok(storage)
+    }
+
+    /// Uses `config` instead of `crate::CONFIG` from now on.
+    pub fn with_config(self, config: Arc<ConfigReader>) -> Self {
+        Storage { config, .. self }
+    }
+
+    /// Settings of this storage.
+    pub fn config(&self) -> &ConfigReader {
+        &self.config
+    }
+
+    /// Whether changes are refused, see `StorageBuilder::read_only()`.
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// Calls `callback` with the path of every project that was opened.
+    pub fn on_project_opened<F>(&mut self, callback: F) -> &mut Self
+        where F: Fn(&Path) + Send + Sync + 'static
+    {
+        self.hooks.add_project_opened(Box::new(callback));
+        self
+    }
+
+    /// Calls `callback` after each project that was tried to open, whether that worked or not.
+    pub fn on_progress<F>(&mut self, callback: F) -> &mut Self
+        where F: Fn(Progress) + Send + Sync + 'static
+    {
+        self.hooks.add_progress(Box::new(callback));
+        self
+    }
+
+    /// Calls `callback` with the file of every project created in this storage.
+    pub fn on_created<F>(&mut self, callback: F) -> &mut Self
+        where F: Fn(&Path) + Send + Sync + 'static
+    {
+        self.hooks.add_created(Box::new(callback));
+        self
+    }
+
+    /// Calls `callback` with the file and field path whenever `field_changed()` is told about a change.
+    pub fn on_field_changed<F>(&mut self, callback: F) -> &mut Self
+        where F: Fn(&Path, &str) + Send + Sync + 'static
+    {
+        self.hooks.add_field_changed(Box::new(callback));
+        self
+    }
+
+    /// Tells the `on_field_changed()` callbacks that `field` of the project in `file` was changed.
+    ///
+    /// Fields are changed on `Project`s directly, so whoever writes them calls this afterwards.
+    pub fn field_changed(&self, file: &Path, field: &str) {
+        self.hooks.field_changed(file, field);
+    }
+
+    /// Calls `callback` with the old and new directory of every archived project, once all of them were moved.
+    pub fn on_archived<F>(&mut self, callback: F) -> &mut Self
+        where F: Fn(&Path, &Path) + Send + Sync + 'static
+    {
+        self.hooks.add_archived(Box::new(callback));
+        self
+    }
+
+    /// Calls `callback` with the old and new directory of every unarchived project, once all of them were moved.
+    pub fn on_unarchived<F>(&mut self, callback: F) -> &mut Self
+        where F: Fn(&Path, &Path) + Send + Sync + 'static
+    {
+        self.hooks.add_unarchived(Box::new(callback));
+        self
+    }
+
+    fn ensure_writable(&self) -> Result<(), AsciiiError> {
+        if self.read_only {
+            return Err(StorageError::ReadOnly.into());
+        }
+        Ok(())
+    }
+
+    /// Checks whether the folder structure is as it's supposed to be.
+    pub fn health_check(&self) -> Result<(), AsciiiError> {
+        let r = self.root_dir();
+        let w = self.working_dir();
+        let a = self.archive_dir();
+        let t = self.templates_dir();
+
+        if r.exists() && w.exists() && a.exists() && t.exists() {
+            Ok(())
+        } else {
+            for f in &[r,w,a,t]{
+                if !f.exists() { log::warn!("{} does not exist", f.display())}
+            }
+            Err(StorageError::InvalidDirStructure.into())
+        }
+    }
+
+    /// Repairs what `health_check()` would complain about, see `asciii doctor --fix`.
+    ///
+    /// Missing directories, `extras` included, are created, the owner gets read and write access
+    /// to everything in the storage back and the default templates are written if there are none.
+    pub fn repair(&self) -> Result<Vec<Repair>, AsciiiError> {
+        log::trace!("repairing storage");
+        self.ensure_writable()?;
+        if !self.root_dir().is_absolute() {
+            return Err(StorageError::StoragePathNotAbsolute.into());
+        }
+
+        let mut repairs = Vec::new();
+        for dir in &[self.root_dir(), self.working_dir(), self.archive_dir(), self.templates_dir(), self.extras_dir()] {
+            if !dir.exists() {
+                fs::create_dir_all(dir)?;
+                repairs.push(Repair::Created(dir.to_path_buf()));
+            }
+        }
+
+        #[cfg(unix)] repair_permissions(self.root_dir(), &mut repairs)?;
+
+        if self.list_template_files().is_err() {
+            repairs.extend(self.materialize_default_templates()?.into_iter().map(Repair::Template));
+        }
+        Ok(repairs)
+    }
+
+    /// Getter for Storage::storage.
+    pub fn root_dir(&self) -> &Path {
+        self.root.as_ref()
+    }
+
+    /// Getter for Storage::working.
+    pub fn working_dir(&self) -> &Path {
+        self.working.as_ref()
+    }
+
+    /// Getter for Storage::archive.
+    pub fn archive_dir(&self) -> &Path {
+        self.archive.as_ref()
+    }
+
+    /// Getter for Storage::templates.
+    pub fn templates_dir(&self) -> &Path {
+        self.templates.as_ref()
+    }
+
+    /// Getter for Storage::extras.
+    pub fn extras_dir(&self) -> &Path {
+        self.extras.as_ref()
+    }
+
+    /// Where the documents of `project` go, if it has an `output_dir` of its own, otherwise `output_path` applies.
+    ///
+    /// `~` is expanded, a relative `output_dir` starts at `output_dir_root` or, if that is empty, the storage.
+    pub fn output_dir(&self, project: &L) -> Option<PathBuf> {
+        let dir = util::paths::expand_tilde(Path::new(project.output_dir_setting()?.trim()));
+        if dir.is_absolute() {
+            return Some(dir);
+        }
+        let root = match self.config.get_str("output_dir_root") {
+            "" => self.root_dir().to_owned(),
+            root => util::paths::absolute(Path::new(root)),
+        };
+        Some(root.join(dir))
+    }
+
+    /// Getter for Storage::templates.
+    pub fn repository(&self) -> Option<&Repository> {
+        self.repository.as_ref()
+    }
+
+    /// Getter for Storage::templates, returns `Result`.
+    pub fn get_repository(&self) -> Result<&Repository, AsciiiError> {
+        self.repository.as_ref().ok_or_else(|| StorageError::RepoUninitialized.into())
+    }
+
+    /// Journal of this storage, see `asciii undo`.
+    pub fn journal(&self) -> Journal {
+        Journal::with_config(self.root_dir(), &self.config)
+    }
+
+    /// Ledger of issued invoice numbers, see `asciii report numbers`.
+    pub fn ledger(&self) -> Ledger {
+        Ledger::with_config(self.root_dir(), &self.config)
+    }
+
+    /// Years closed by `asciii close-year`, their archives are read-only.
+    pub fn closed_years(&self) -> ClosedYears {
+        ClosedYears::with_config(self.root_dir(), &self.config)
+    }
+
+    /// Year of the archive `dir` is in, `None` if it is not archived.
+    fn archive_year_of(&self, dir: &Path) -> Option<Year> {
+        dir.strip_prefix(self.archive_dir()).ok()?
+           .components().next()?
+           .as_os_str().to_str()?
+           .parse().ok()
+    }
+
+    /// Fails if `project` is archived in a closed year.
+    pub fn ensure_open(&self, project: &L) -> Result<(), AsciiiError> {
+        match self.archive_year_of(&project.dir()) {
+            Some(year) => self.closed_years().ensure_open(year),
+            None => Ok(())
+        }
+    }
+
+    /// Locks the directory of `project` until the lock is dropped, see `asciii edit`.
+    pub fn lock(&self, project: &L) -> Result<ProjectLock, AsciiiError> {
+        ProjectLock::with_config(&project.dir(), &self.config)
+    }
+
+    /// Records an action in the journal, failing to do so is only a warning.
+    pub fn record(&self, action: &str, changes: Vec<Change>) {
+        if let Err(e) = self.journal().record(action, changes) {
+            log::warn!("can't record {:?} in the journal: {}", action, e);
+        }
+    }
+
+    /// Returns a struct containing all configured paths of this `Storage`.
+    pub fn paths(&self) -> Paths {
+        Paths {
+           storage: self.root_dir().into(),
+           working: self.working_dir().into(),
+           archive: self.archive_dir().into(),
+           templates: self.templates_dir().into(),
+        }
+    }
+
+    /// Creates the basic dir structure inside the storage directory.
+    ///
+    ///<pre>
+    ///└── root
+    ///    ├── archive
+    ///    ├── templates
+    ///    └── working
+    ///</pre>
+    /// If the directories already exist as expected, that's fine
+    /// TODO: ought to fail when storage_dir already contains directories that do not correspond
+    /// with the names given in this setup.
+    pub fn create_dirs(&self) -> Result<(), AsciiiError> {
+        log::trace!("creating storage directories");
+        self.ensure_writable()?;
+        if !self.root_dir().is_absolute() {
+            return Err(StorageError::StoragePathNotAbsolute.into());
+        }
+
+        if !self.root_dir().exists()  { fs::create_dir(&self.root_dir())?;  }
+        if !self.working_dir().exists()  { fs::create_dir(&self.working_dir())?;  }
+        if !self.archive_dir().exists()  { fs::create_dir(&self.archive_dir())?;  }
+        if !self.templates_dir().exists() { fs::create_dir(&self.templates_dir())?; }
+
+        Ok(())
+    }
+
+    /// Writes `DEFAULT_TEMPLATES` into the `templates_dir()`, files that already exist are kept.
+    ///
+    /// Returns the files that were written.
+    pub fn materialize_default_templates(&self) -> Result<Vec<PathBuf>, AsciiiError> {
+        log::trace!("writing default templates");
+        self.ensure_writable()?;
+        let mut written = Vec::new();
+        for (name, content) in DEFAULT_TEMPLATES {
+            let path = self.templates_dir().join(name);
+            if !path.exists() {
+                util::atomic_write(&path, content)?;
+                written.push(path);
+            }
+        }
+        Ok(written)
+    }
+
+    /// Creates an archive for a certain year.
+    /// This is a subdirectory under the archive directory.
+    ///<pre>
+    ///└── root
+    ///    ├── archive
+    ///        ├── 2001
+    ///    ...
+    ///</pre>
+    pub fn create_archive(&self, year:Year) -> Result<PathBuf, AsciiiError> {
+        log::trace!("creating archive directory: {}", year);
+        self.ensure_writable()?;
+        assert!(self.archive_dir().exists());
+        let archive = &self.archive_dir().join(year.to_string());
+
+        if self.archive_dir().exists() && !archive.exists() {
+            fs::create_dir(archive)?;
+        }
+        Ok(archive.to_owned())
+    }
+
+    /// Produces a list of files in the `extras_dir()`
+    pub fn list_extra_files(&self) -> Result<Vec<PathBuf>, AsciiiError> {
+        log::trace!("listing extra files");
+        list_path_content(self.extras_dir())
+    }
+
+    /// Returns the Path to the extra file by the given name, maybe.
+    pub fn get_extra_file(&self, name: &str) -> Result<PathBuf, AsciiiError> {
+        let full_path = self.extras_dir().join(name);
+        log::trace!("opening {:?}", full_path);
+
+        Ok(full_path)
+    }
+
+    /// Produces a list of files in the `template_dir()`
+    pub fn list_template_files(&self) -> Result<Vec<PathBuf>, AsciiiError> {
+        let template_file_extension = self.config.get_str("extensions/project_template");
+        log::trace!("listing template files (.{})", template_file_extension);
+        let template_files =
+        list_path_content(self.templates_dir())?
+            .into_iter()
+            .filter(|p|p.extension()
+                        .unwrap_or_else(|| OsStr::new("")) == OsStr::new(template_file_extension)
+                        )
+            .collect::<Vec<PathBuf>>();
+        if template_files.is_empty() {
+            return Err(StorageError::TemplateNotFound.into());
+        }
+        Ok(template_files)
+    }
+
+    /// Produces a list of names of all template filses in the `templates_dir()`
+    pub fn list_template_names(&self) -> Result<Vec<String>, AsciiiError> {
+        log::trace!("listing template names");
+        let template_names = self.list_template_files()?.iter()
+            .filter_map(|p|p.file_stem())
+            .filter_map(OsStr::to_str)
+            .map(ToOwned::to_owned)
+            .collect();
+        Ok(template_names)
+    }
+
+    /// Returns the Path to the template file by the given name, maybe.
+    ///
+    /// Fails with the available names and the closest one if there is no such template.
+    pub fn get_template_file(&self, name:&str) -> Result<PathBuf, AsciiiError> {
+        let files = self.list_template_files()?;
+        if let Some(file) = files.iter().find(|f|f.file_stem().unwrap_or_else(||OsStr::new("")) == name) {
+            return Ok(file.to_owned());
+        }
+
+        let mut available = files.iter()
+                                 .filter_map(|f| f.file_stem().and_then(OsStr::to_str))
+                                 .map(ToOwned::to_owned)
+                                 .collect::<Vec<String>>();
+        available.sort();
+        let suggestion = util::closest_match(name, available.iter().map(String::as_str)).map(ToOwned::to_owned);
+        Err(StorageError::UnknownTemplate(name.to_owned(), available, suggestion).into())
+    }
+
+    /// Produces a list of paths to all archives in the `archive_dir`.
+    /// An archive itself is a folder that contains project dirs,
+    /// therefore it essentially has the same structure as the `working_dir`,
+    /// with the difference, that the project folders may be prefixed with the projects index, e.g.
+    /// an invoice number etc.
+    pub fn list_archives(&self) -> Result<Vec<PathBuf>, AsciiiError> {
+        log::trace!("listing archives files");
+        list_path_content(self.archive_dir())
+    }
+
+    /// Produces a list of years for which there is an archive.
+    pub fn list_years(&self) -> Result<Vec<Year>, AsciiiError> {
+        log::trace!("listing years");
+        let mut years : Vec<Year> =
+            self.list_archives()?
+            .iter()
+            .filter_map(|p| p.file_stem())
+            .filter_map(OsStr::to_str)
+            .filter_map(|year_str| year_str.parse::<Year>().ok())
+            .collect();
+        years.sort_unstable();
+        Ok(years)
+    }
+
+    /// The variables declared in the front-matter of a template, see `templater::variables()`.
+    pub fn template_variables(&self, template_name: &str) -> Result<Vec<Variable>, AsciiiError> {
+        templater::variables(&fs::read_to_string(self.get_template_file(template_name)?)?)
+    }
+
+    /// Takes a template file and stores it in the working directory,
+    /// in a new project directory according to it's name.
+    ///
+    /// `fill_data` is checked against the variables the template declares, missing ones get their defaults.
+    pub fn create_project(&self, project_name: &str, template_name: &str, fill_data: &HashMap<&str, String>) -> Result<L, AsciiiError> {
+        log::debug!("creating a project\n name: {name}\n template: {tmpl}",
+               name = project_name,
+               tmpl = template_name
+               );
+        self.ensure_writable()?;
+        let template_path = self.get_template_file(template_name)?;
+        let variables = templater::variables(&fs::read_to_string(&template_path)?)?;
+        let fill_data = templater::fill_variables(&variables, fill_data)?;
+        let (project_dir, target_file) = self.new_project_paths(project_name)?;
+
+        log::trace!("creating project using concrete Project implementation of from_template");
+        let mut project = L::from_template(project_name, &template_path, &fill_data)?;
+
+        // TODO: Hand of creation entirely to Storable implementation
+        //      Storage it self should only concern itself with Project folders!
+        fs::create_dir(&project_dir)?;
+        util::atomic_write(&target_file, fs::read(project.file())?)?;
+        log::trace!("copied project file successfully");
+        project.set_file(&target_file);
+        self.record(&format!("create {}", project_name), vec![self.journal().write(&target_file, None)]);
+        self.hooks.created(&target_file);
+
+        Ok(project.storable)
+    }
+
+    /// Creates a new project in the working directory from the content of a project file.
+    pub fn create_project_from_content(&self, project_name: &str, content: &str) -> Result<L, AsciiiError> {
+        log::debug!("creating a project from content\n name: {}", project_name);
+        self.ensure_writable()?;
+        let (project_dir, target_file) = self.new_project_paths(project_name)?;
+
+        fs::create_dir(&project_dir)?;
+        util::atomic_write(&target_file, content)?;
+        self.record(&format!("create {}", project_name), vec![self.journal().write(&target_file, None)]);
+        self.hooks.created(&target_file);
+        L::open_file(&target_file)
+    }
+
+    /// Directory and file of a new project in the working directory, neither exists yet.
+    pub fn new_project_paths(&self, project_name: &str) -> Result<(PathBuf, PathBuf), AsciiiError> {
+        if !self.working_dir().exists(){
+            log::error!("working directory does not exist");
+            return Err(StorageError::NoWorkingDir.into())
+        };
+        let slugged_name = self.slugify(project_name);
+        let project_dir  = self.working_dir().join(&slugged_name);
+        if project_dir.exists() {
+            log::error!("project directory already exists");
+            return Err(StorageError::ProjectDirExists.into());
+        }
+
+        log::trace!("created project will be called {:?}", slugged_name);
+
+        let target_file  = project_dir
+            .join(&(slugged_name + "." + &L::file_extension()));
+        Ok((project_dir, target_file))
+    }
+
+    /// Moves a project folder from `/working` dir to `/archive/$year`.
+    ///
+    /// Returns path to new storage dir in archive.
+    #[cfg(test)]
+    pub fn archive_project_by_name(&self, name:&str, year:Year, prefix:Option<String>) -> Result<PathBuf, AsciiiError> {
+        log::info!("archiving project by name {:?} into archive for {}", name, year);
+        log::trace!("prefix {:?}", prefix);
+
+        let slugged_name = self.slugify(name);
+        let name_in_archive = match prefix{
+            Some(prefix) => format!("{}_{}", prefix, slugged_name),
+                    None => slugged_name
+        };
+
+        let archive = self.create_archive(year)?;
+        let project_folder = self.get_project_dir(name, StorageDir::Working)?;
+        let target = archive.join(&name_in_archive);
+        log::trace!(" moving file into {:?}", target);
+
+        fs::rename(&project_folder, &target)?;
+
+        Ok(target)
+    }
+
+    /// Moves a project folder from `/working` dir to `/archive/$year`.
+    /// Also adds the project.prefix() to the folder name.
+    ///<pre>
+    ///└── root
+    ///    ├── archive
+    ///        ├── 2001
+    ///            ├── R0815_Birthday_party
+    ///    ...
+    ///</pre>
+    // TODO: write extra tests
+    // TODO: make year optional and default to project.year()
+    pub fn archive_project(&self, project:&L, year:Year) -> Result<Vec<PathBuf>, AsciiiError> {
+        let mut transaction = Transaction::new();
+        self.archive_project_in(project, year, &mut transaction)?;
+        self.finish(transaction, &format!("archive {}", project.short_desc()), Hooks::archived)
+    }
+
+    /// Moves several projects like `archive_project()`, either all of them or none.
+    ///
+    /// Returns list of old and new paths.
+    pub fn archive_projects<'p, I>(&self, projects:I) -> Result<Vec<PathBuf>, AsciiiError>
+        where I: IntoIterator<Item=(&'p L, Year)>, L: 'p
+    {
+        let mut transaction = Transaction::new();
+        let mut names = Vec::new();
+        for (project, year) in projects {
+            log::info!("archiving {} ({})",  project.ident(), year);
+            self.archive_project_in(project, year, &mut transaction)?;
+            names.push(project.short_desc());
+        }
+        self.finish(transaction, &format!("archive {}", names.join(", ")), Hooks::archived)
+    }
+
+    fn archive_project_in(&self, project:&L, year:Year, transaction:&mut Transaction) -> Result<PathBuf, AsciiiError> {
+        log::debug!("trying archiving {:?} into {:?}", project.short_desc(), year);
+
+        self.closed_years().ensure_open(year)?;
+        let archive = self.create_archive(year)?;
+        let target = archive.join(Self::name_in_archive(project, project.ident()));
+        if target.exists() {
+            return Err(StorageError::ProjectFileExists.into());
+        }
+
+        transaction.rename(&project.dir(), &target)?;
+        log::info!("successfully archived {:?} to {:?}", project.short_desc() ,target);
+        Ok(target)
+    }
+
+    /// Name of the folder of `project` in the archive, `name` prefixed if it has a `prefix()`.
+    fn name_in_archive(project:&L, name:String) -> String {
+        match project.prefix(){
+            Some(prefix) => format!("{}_{}", prefix, name),
+            None =>  name
+        }
+    }
+
+    /// Renames the folders in the archive of `year` that are not named like `archive_project()` would name them,
+    /// e.g. those archived before their project had an invoice number.
+    ///
+    /// Folders whose new name is already taken are left alone.
+    /// Returns old and new path of every renamed folder.
+    pub fn fix_archive_prefixes(&self, year:Year) -> Result<Vec<(PathBuf, PathBuf)>, AsciiiError> {
+        self.ensure_writable()?;
+        self.closed_years().ensure_open(year)?;
+        if !self.list_years()?.contains(&year) {
+            return Ok(Vec::new());
+        }
+        let archive = self.archive_dir().join(year.to_string());
+
+        let mut transaction = Transaction::new();
+        for project in self.open_projects(StorageDir::Archive(year))?.iter() {
+            // the folder may have been renamed, the file is still named after the project
+            let name = match project.file().file_stem().and_then(OsStr::to_str) {
+                Some(name) => name.to_owned(),
+                None => continue
+            };
+            let dir = project.dir();
+            let target = archive.join(Self::name_in_archive(project, name));
+            if dir == target {
+                continue;
+            }
+            if target.exists() {
+                log::warn!("{}", lformat!("can't rename {} to {}, it already exists", dir.display(), target.display()));
+                continue;
+            }
+            transaction.rename(&dir, &target)?;
+        }
+
+        let moves = transaction.moves().to_vec();
+        if !moves.is_empty() {
+            self.finish(transaction, &format!("fix prefixes in {}", year), |_, _, _| ())?;
+        }
+        Ok(moves)
+    }
+
+    /// Rewrites the `.index.yml` of the archive of `year` from its projects, see `archive_index`.
+    pub fn update_archive_index(&self, year:Year) -> Result<PathBuf, AsciiiError> {
+        self.ensure_writable()?;
+        let entries = self.open_projects(StorageDir::Archive(year))?
+                          .iter()
+                          .map(Storable::index_entry)
+                          .collect();
+        archive_index::write(&self.archive_dir().join(year.to_string()), entries)
+    }
+
+    /// Keeps what `transaction` moved, records it as one action in the journal, adds it to git
+    /// and tells `notify` about every move.
+    ///
+    /// Returns list of old and new paths.
+    fn finish(&self, transaction:Transaction, action:&str, notify:fn(&Hooks, &Path, &Path)) -> Result<Vec<PathBuf>, AsciiiError> {
+        let mut changes = transaction.changes(&self.journal());
+        let moved_files = transaction.paths();
+        let moves = transaction.commit();
+
+        let mut years = moved_files.iter().filter_map(|path| self.archive_year_of(path)).collect::<Vec<_>>();
+        years.sort_unstable();
+        years.dedup();
+        let mut indexes = Vec::new();
+        for year in years {
+            let index = archive_index::path(&self.archive_dir().join(year.to_string()));
+            let before = fs::read_to_string(&index).ok();
+            match self.update_archive_index(year) {
+                Ok(index) => {
+                    changes.push(self.journal().write(&index, before.as_deref()));
+                    indexes.push(index);
+                },
+                Err(e) => log::warn!("{}", lformat!("can't update the index of {}: {}", year, e))
+            }
+        }
+        self.record(action, changes);
+
+        if let Some(repo) = self.repository() {
+            repo.add(&moved_files);
+            if !indexes.is_empty() {
+                repo.add(&indexes);
+            }
+        }
+        for (from, to) in &moves {
+            notify(&self.hooks, from, to);
+        }
+        Ok(moved_files)
+    }
+
+
+    /// Moves projects found through `search_terms` from the `Working` directory to the `Archive`/`year` directory.
+    ///
+    /// Nothing is moved if one of them can't be.
+    /// Returns list of old and new paths.
+    pub fn archive_projects_if<F>(&self, search_terms:&[&str], manual_year:Option<i32>, confirm:F) -> Result<Vec<PathBuf>, AsciiiError>
+        where F: Fn()->bool
+    {
+        let projects = self.search_projects_any(StorageDir::Working, search_terms)?;
+        let force = confirm();
+
+        if projects.is_empty() {
+            return Err(StorageError:: ProjectDoesNotExist.into());
+        }
+
+        let mut ready = Vec::new();
+        for project in projects.iter() {
+            if force {log::warn!("you are using --force")};
+            if project.is_ready_for_archive() || force {
+                log::info!("project {:?} is ready to be archived", project.short_desc());
+                let year = manual_year.or_else(|| project.year()).unwrap();
+                ready.push((project, year));
+            }
+            else {
+                log::warn!("project {:?} is not ready to be archived", project.short_desc());
+            }
+        };
+
+        self.archive_projects(ready)
+    }
+
+    pub fn delete_project_if<F>(&self, project:&L, confirmed:F) -> Result<(), AsciiiError>
+        where F: Fn() -> bool
+    {
+        log::debug!("deleting {}", project.dir().display());
+        self.ensure_writable()?;
+        let content = fs::read_to_string(project.file()).ok();
+        project.delete_project_dir_if(confirmed)?;
+        if !project.dir().exists() {
+            let deleted = Change::Write { path: self.journal().relative(&project.file()), before: content, after: None };
+            self.record(&format!("delete {}", project.short_desc()), vec![deleted]);
+        }
+        if let Some(ref repo) = self.repository {
+            if !repo.add(&[project.dir()]).success() {
+                log::debug!("adding {} to git", project.dir().display());
+                return Err(StorageError::GitProcessFailed.into());
+            }
+        }
+        Ok(())
+    }
+
+
+    /// Moves projects found through `search_terms` from the `year` back to the `Working` directory.
+    ///
+    /// Returns list of old and new paths.
+    pub fn unarchive_projects(&self, year:i32, search_terms:&[&str]) -> Result<Vec<PathBuf>, AsciiiError> {
+        let projects = self.search_projects_any(StorageDir::Archive(year), search_terms)?;
+
+        let mut transaction = Transaction::new();
+        let mut names = Vec::new();
+        for project in projects.iter() {
+            println!("unarchiving {:?}", project.short_desc());
+            self.unarchive_project_dir_in(&project.dir(), &mut transaction)?;
+            names.push(project.short_desc());
+        };
+
+        self.finish(transaction, &format!("unarchive {}", names.join(", ")), Hooks::unarchived)
+    }
+
+    /// Moves a project folder from `/working` dir to `/archive/$year`.
+    pub fn unarchive_project(&self, project:&L) -> Result<PathBuf, AsciiiError> {
+        self.unarchive_project_dir(&project.dir())
+    }
+
+    /// Moves a project folder from `/working` dir to `/archive/$year`.
+    pub fn unarchive_project_dir(&self, archived_dir:&Path) -> Result<PathBuf, AsciiiError> {
+        let mut transaction = Transaction::new();
+        let target = self.unarchive_project_dir_in(archived_dir, &mut transaction)?;
+        let name = target.file_name().unwrap_or_default().to_string_lossy().into_owned();
+        self.finish(transaction, &format!("unarchive {}", name), Hooks::unarchived)?;
+        Ok(target)
+    }
+
+    fn unarchive_project_dir_in(&self, archived_dir:&Path, transaction:&mut Transaction) -> Result<PathBuf, AsciiiError> {
+        log::debug!("trying unarchiving {:?}", archived_dir);
+        self.ensure_writable()?;
+        if let Some(year) = self.archive_year_of(archived_dir) {
+            self.closed_years().ensure_open(year)?;
+        }
+
+        // has to be in archive_dir
+        let child_of_archive = archived_dir.starts_with(&self.archive_dir());
+
+        // must not be the archive_dir
+        let archive_itself =  archived_dir == self.archive_dir();
+
+        // must be in a dir that parses into a year
+        let parent_is_num =  archived_dir.parent()
+            .and_then(Path::file_stem)
+            .and_then(OsStr::to_str)
+            .map_or(false, |s| s.parse::<i32>().is_ok());
+
+        let name = self.get_project_name(archived_dir)?;
+        let target = self.working_dir().join(&name);
+        if target.exists() {
+            return Err(StorageError::ProjectFileExists.into());
+        }
+        log::info!("unarchiving project from {:?} to {:?}", archived_dir, target);
+
+        if child_of_archive && !archive_itself && parent_is_num{
+            transaction.rename(archived_dir, &target)?;
+        } else {
+            log::error!("moving out of archive failed");
+            return Err(StorageError::InvalidDirStructure.into());
+        };
+
+        Ok(target)
+    }
+
+    /// Matches StorageDir's content against a term and returns matching project files.
+    ///
+    /// This only searches by name
+    /// TODO: return opened `Project`, no need to reopen
+    ///
+    /// # Warning
+    /// Please be advised that this uses [`Storage::open_projects()`](struct.Storage.html#method.open_projects) and therefore opens all projects.
+    pub fn search_projects(&self, directory:StorageDir, search_term:&str) -> Result<ProjectList<L>, AsciiiError> {
+        self.search_projects_reporting(directory, search_term, &mut Vec::new())
+    }
+
+    /// Like `search_projects()`, files that can't be opened are added to `failures`.
+    fn search_projects_reporting(&self, directory:StorageDir, search_term:&str, failures: &mut Vec<OpenFailure>) -> Result<ProjectList<L>, AsciiiError> {
+        log::trace!("searching for projects by {:?} in {:?}", search_term, directory);
+        let search_index = if search_term.starts_with('N') {
+            match search_term.chars().skip(1).collect::<String>().parse::<usize>() {
+                Ok(n) => Some(n),
+                Err(_) => None,
+            }
+        } else {
+            None
+        };
+        let mut projects = self.open_projects_dir_reporting(directory, failures)?;
+        self.sort_order().sort(&mut projects);
+        let projects = projects.into_iter()
+            .enumerate()
+            .filter(|(index,project)| {
+                search_index.map_or(false, |idx| idx == index + 1)
+                    || project.matches_search(&search_term.to_lowercase())
+            })
+            .map(|(_,project)| project)
+            .collect();
+        Ok(ProjectList{projects})
+    }
+
+    /// Matches StorageDir's content against multiple terms and returns matching projects.
+    /// TODO: add search_multiple_projects_deep
+    pub fn search_projects_any(&self, dir:StorageDir, search_terms:&[&str]) -> Result<ProjectList<L>, AsciiiError> {
+        let mut projects = Vec::new();
+        for search_term in search_terms{
+            let mut found_projects = self.search_projects(dir, search_term)?;
+            projects.append(&mut found_projects);
+        }
+
+        Ok(ProjectList{projects})
+    }
+
+    /// Tries to find a concrete Project.
+    pub fn get_project_dir(&self, name:&str, directory:StorageDir) -> Result<PathBuf, AsciiiError> {
+        log::trace!("getting project directory for {:?} from {:?}", name, directory);
+        for slugged_name in self.slug_candidates(name) {
+            if let Ok(path) = match directory {
+                StorageDir::Working => Ok(self.working_dir().join(&slugged_name)),
+                StorageDir::Archive(year) => self.get_project_dir_from_archive(&slugged_name, year),
+                _ => return Err(StorageError::BadChoice.into())
+            }{
+                if path.exists(){
+                    return Ok(path);
+                }
+            }
+        }
+        Err(StorageError::ProjectDoesNotExist.into())
+    }
+
+    /// Locates the project file inside a folder.
+    ///
+    /// This is the first file with the `super::PROJECT_FILE_EXTENSION` in the folder
+    pub fn get_project_file(&self, directory:&Path) -> Result<PathBuf, AsciiiError> {
+        log::trace!("getting project file from {:?}", directory);
+        list_path_content(directory)?.iter()
+            .find(|f|f.extension().unwrap_or_else(||OsStr::new("")) == L::file_extension().as_str())
+            .map(ToOwned::to_owned)
+            .ok_or_else(|| StorageError::ProjectDoesNotExist.into())
+    }
+
+    fn get_project_name(&self, directory:&Path) -> Result<String, AsciiiError> {
+        let path = self.get_project_file(directory)?;
+        if let Some(stem) = path.file_stem(){
+            return Ok(stem.to_str().expect("this filename is no valid unicode").to_owned());
+        }
+        Err(StorageError::BadProjectFileName.into())
+    }
+
+    fn get_project_dir_from_archive(&self, slugged_name:&str, year:Year) -> Result<PathBuf, AsciiiError> {
+        for project_file in &self.list_project_files(StorageDir::Archive(year))?{
+            if project_file.ends_with(slugged_name.to_owned() + "."+ &L::file_extension()) {
+                return project_file.parent().map(ToOwned::to_owned).ok_or_else (|| StorageError::ProjectDoesNotExist.into());
+            }
+        }
+        Err(StorageError::ProjectDoesNotExist.into())
+    }
+
+    /// Produces a list of project folders.
+    pub fn list_project_folders(&self, directory:StorageDir) -> Result<Vec<PathBuf>, AsciiiError> {
+        log::trace!("listing project folders in {:?}-directory", directory);
+        match directory{
+            StorageDir::Working       => list_path_content(self.working_dir()),
+            StorageDir::Archive(year) => {
+                let path = self.archive_dir().join(year.to_string());
+                let mut list = list_path_content(&path).unwrap_or_else(|_| Vec::new());
+                list.retain(|path| !archive_index::is_index(path));
+                Ok(list)
+            },
+            StorageDir::All           => {
+                let mut all:Vec<PathBuf> = Vec::new();
+                for year in self.list_years()? {
+                    all.append(&mut list_path_content(&self.archive_dir().join(year.to_string()))?);
+                }
+                all.retain(|path| !archive_index::is_index(path));
+                all.append(&mut list_path_content(self.working_dir())?);
+                Ok(all)
+            },
+            _ => Err(StorageError::BadChoice.into())
+        }
+    }
+
+    /// Produces a list of empty project folders.
+    pub fn list_empty_project_dirs(&self, directory:StorageDir) -> Result<Vec<PathBuf>, AsciiiError> {
+        log::trace!("listing empty project dirs {:?}-directory", directory);
+        let projects = self.list_project_folders(directory)?
+            .into_iter()
+            .filter(|dir| self.get_project_file(dir).is_err())
+            .collect();
+        Ok(projects)
+    }
+
+    /// Produces a list of project files.
+    pub fn list_project_files(&self, directory:StorageDir) -> Result<Vec<PathBuf>, AsciiiError> {
+        log::trace!("listing project files in {:?}-directory", directory);
+        self.list_project_folders(directory)?
+            .iter()
+            .map(|dir| self.get_project_file(dir))
+            .collect()
+    }
+
+    pub fn filter_project_files<F>(&self, directory:StorageDir, filter:F) -> Result<Vec<PathBuf>, AsciiiError>
+        where F:FnMut(&PathBuf) -> bool
+    {
+        log::trace!("filtering project files in {:?}-directory", directory);
+        let projects = self.list_project_folders(directory)?.iter()
+            .filter_map(|dir| self.get_project_file(dir).ok())
+            .filter(filter)
+            .collect();
+        Ok(projects)
+    }
+
+    /// Behaves like `list_project_files()` but also opens projects directly.
+    ///
+    /// Files that can't be opened are logged and left out, see `open_projects_with_failures()`.
+    pub fn open_projects<I>(&self, selection:I) -> Result<ProjectList<L>, AsciiiError>
+        where I: Into<StorageSelection>
+    {
+        let (projects, failures) = self.open_projects_with_failures(selection)?;
+        for failure in &failures {
+            log::warn!("{}", failure);
+        }
+        Ok(projects)
+    }
+
+    /// Like `open_projects()`, but also returns every file that could not be opened and why.
+    ///
+    /// Searches report the failures of the whole directory, a broken file can't be told apart from a match.
+    pub fn open_projects_with_failures<I>(&self, selection:I) -> Result<(ProjectList<L>, Vec<OpenFailure>), AsciiiError>
+        where I: Into<StorageSelection>
+    {
+        use self::StorageSelection::*;
+        let mut failures = Vec::new();
+        let projects = match selection.into() {
+            DirAndSearch(dir, ref search_terms) => {
+                let mut projects = Vec::new();
+                for search_term in search_terms {
+                    projects.append(&mut self.search_projects_reporting(dir, search_term, &mut failures)?.projects);
+                }
+                if projects.is_empty() {
+                    return Err(StorageError::NothingFound(search_terms.iter().map(ToString::to_string).collect()).into());
+                }
+                ProjectList { projects }
+            },
+            Dir(dir) => self.open_projects_dir_reporting(dir, &mut failures)?,
+            Paths(ref paths) => self.open_paths(paths, &mut failures),
+            Uninitialized => unreachable!()
+        };
+        // every search term opens the directory again
+        failures.sort_by(|a, b| a.path.cmp(&b.path));
+        failures.dedup_by(|a, b| a.path == b.path);
+        Ok((projects, failures))
+    }
+
+    /// Opens projects in parallel with the `par_open` feature, unless there are fewer than `parallel/min_projects`.
+    fn open_paths(&self, paths: &[PathBuf], failures: &mut Vec<OpenFailure>) -> ProjectList<L> {
+        log::trace!("open_paths({:?})", paths);
+        let (hooks, done, total) = (&self.hooks, AtomicUsize::new(0), paths.len());
+        #[cfg(feature="rayon")]
+        {
+            let min_projects = self.config.get_f64("parallel/min_projects").unwrap_or(0.0) as usize;
+            if paths.len() >= min_projects {
+                let open = || paths.par_iter()
+                                   .map(|path| (path, Self::open_reporting(path, hooks, &done, total)))
+                                   .collect::<Vec<_>>();
+                let opened = match self.thread_pool() {
+                    Some(pool) => pool.install(open),
+                    None => open()
+                };
+                return self.with_git_statuses(Self::split_failures(opened, failures));
+            }
+        }
+        let opened = paths.iter()
+            .map(|path| (path, Self::open_reporting(path, hooks, &done, total)))
+            .collect::<Vec<_>>();
+        self.with_git_statuses(Self::split_failures(opened, failures))
+    }
+
+    /// Opened projects, the rest goes to `failures`.
+    fn split_failures(opened: Vec<(&PathBuf, Result<L, AsciiiError>)>, failures: &mut Vec<OpenFailure>) -> Vec<L> {
+        let mut projects = Vec::with_capacity(opened.len());
+        for (path, result) in opened {
+            match result {
+                Ok(project) => projects.push(project),
+                Err(error) => failures.push(OpenFailure { path: path.to_owned(), error }),
+            }
+        }
+        projects
+    }
+
+    /// Pool with `parallel/threads` threads, `None` means rayon's global pool.
+    #[cfg(feature="rayon")]
+    fn thread_pool(&self) -> Option<&rayon::ThreadPool> {
+        self.thread_pool.get_or_init(|| {
+            let threads = self.config.get_f64("parallel/threads").unwrap_or(0.0) as usize;
+            if threads == 0 {
+                return None;
+            }
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .map_err(|e| log::warn!("can't start {} threads: {}", threads, e))
+                .ok()
+        }).as_ref()
+    }
+
+    /// The client database, empty if there is none or it is broken.
+    pub fn client_database(&self) -> Arc<ClientDatabase> {
+        Arc::clone(self.clients.get_or_init(|| {
+            let path = self.extras_dir().join(CLIENTS_FILE);
+            Arc::new(ClientDatabase::open(&path).unwrap_or_else(|e| {
+                log::warn!("can't read {}: {}", path.display(), e);
+                ClientDatabase::default()
+            }))
+        }))
+    }
+
+    /// Looks up the git status of every project directory in the statuses of the whole repository
+    /// and hands out the client database and output directories.
+    fn with_git_statuses(&self, mut projects: Vec<L>) -> ProjectList<L> {
+        let clients = self.client_database();
+        for project in &mut projects {
+            project.set_client_database(Arc::clone(&clients));
+            if let Some(dir) = self.output_dir(project) {
+                project.set_output_dir(dir);
+            }
+        }
+        if let Some(ref repo) = self.repository {
+            let statuses = repo.statuses_by_path();
+            for project in &mut projects {
+                if let Some(status) = statuses.get(&project.dir()) {
+                    project.set_git_status(status.to_owned());
+                }
+            }
+        }
+        ProjectList { projects }
+    }
+
+    /// Like `open_projects()`, but opens one project at a time while the iterator is advanced.
+    ///
+    /// Nothing is collected, so callers can print each project right away or stop early.
+    /// Search results come in directory order, each project once, even if several terms match it.
+    /// Years and index searches like `N3` need every project first, those are opened up front.
+    pub fn iter_projects<'a, I>(&'a self, selection:I) -> Result<Box<dyn Iterator<Item=L> + 'a>, AsciiiError>
+        where I: Into<StorageSelection>
+    {
+        use self::StorageSelection::*;
+        let selection = selection.into();
+        let needs_all = match selection {
+            Dir(dir) | DirAndSearch(dir, _) if matches!(dir, StorageDir::Year(_)) => true,
+            DirAndSearch(_, ref terms) => terms.iter().any(|term| is_index_term(term)),
+            _ => false
+        };
+        if needs_all {
+            return Ok(Box::new(self.open_projects(selection)?.into_iter()));
+        }
+
+        let (paths, terms) = match selection {
+            DirAndSearch(dir, terms) => (self.list_project_folders(dir)?, Some(terms)),
+            Dir(dir) => (self.list_project_folders(dir)?, None),
+            Paths(paths) => (paths, None),
+            Uninitialized => unreachable!()
+        };
+        let searching = terms.is_some();
+        let lowercase = terms.iter().flatten().map(|term| term.to_lowercase()).collect::<Vec<_>>();
+        let statuses = self.repository.as_ref().map(Repository::statuses_by_path);
+        let clients = self.client_database();
+        let (hooks, done, total) = (&self.hooks, AtomicUsize::new(0), paths.len());
+
+        let mut projects = paths.into_iter()
+            .filter_map(move |path| Self::open_reporting(&path, hooks, &done, total)
+                                        .map_err(|error| log::warn!("{}", OpenFailure { path, error }))
+                                        .ok())
+            .filter(move |project| !searching || lowercase.iter().any(|term| project.matches_search(term)))
+            .map(move |mut project| {
+                project.set_client_database(Arc::clone(&clients));
+                if let Some(dir) = self.output_dir(&project) {
+                    project.set_output_dir(dir);
+                }
+                if let Some(status) = statuses.and_then(|statuses| statuses.get(&project.dir())) {
+                    project.set_git_status(status.to_owned());
+                }
+                project
+            })
+            .peekable();
+
+        // like `open_projects()`, a search without results is an error
+        if let (true, Some(terms)) = (projects.peek().is_none(), terms) {
+            return Err(StorageError::NothingFound(terms).into());
+        }
+        Ok(Box::new(projects))
+    }
+
+    /// Behaves like `list_project_files()` but also opens projects directly.
+    pub fn open_projects_dir(&self, directory:StorageDir) -> Result<ProjectList<L>, AsciiiError>{
+        self.open_projects(directory)
+    }
+
+    /// Like `open_projects_dir()`, files that can't be opened are added to `failures`.
+    fn open_projects_dir_reporting(&self, directory:StorageDir, failures: &mut Vec<OpenFailure>) -> Result<ProjectList<L>, AsciiiError>{
+        log::debug!("OPENING ALL PROJECTS in {:?}-directory", directory);
+        match directory {
+            StorageDir::Year(year) => {
+                // recursive :D
+                let mut archived = self.open_projects_dir_reporting(StorageDir::Archive(year), failures)?;
+                let mut working = self.open_projects_dir_reporting(StorageDir::Working, failures)?;
+                archived.append(working.deref_mut());
+                archived.filter_by_key_val("Year", year.to_string().as_ref());
+                Ok(archived)
+            },
+            _ =>
+                self.list_project_folders(directory)
+                .map(|p| self.open_paths(&p, failures))
+        }
+    }
+
+    pub fn open_working_dir_projects(&self) -> Result<ProjectList<L>, AsciiiError> {
+        log::debug!("OPENING ALL WORKING DIR PROJECTS");
+        self.open_projects(StorageDir::Working)
+    }
+
+    pub fn open_all_archived_projects(&self) -> Result<ProjectsByYear<L>, AsciiiError> {
+        log::debug!("OPENING ALL ARCHIVED PROJECTS");
+        let mut map = LinkedHashMap::new();
+        for year in self.list_years()? {
+            map.insert(year, self.open_projects(StorageDir::Archive(year))?);
+        }
+        Ok(map)
+    }
+
+    pub fn open_all_projects(&self) -> Result<Projects<L>, AsciiiError> {
+        log::debug!("OPENING ALL PROJECTS");
+        Ok( Projects {
+            working: self.open_projects(StorageDir::Working)?,
+            archive: self.open_all_archived_projects()?
+        })
+    }
+
+    fn open_project(path: &Path) -> Result<L, AsciiiError> {
+        let _timer = util::timing::start("reading projects");
+        let meta = path.metadata().unwrap();
+        if meta.is_dir() {
+            L::open_folder(path)
+        } else {
+            L::open_file(path)
+        }
+    }
+
+    /// Like `open_project()`, but tells `hooks` about it, `done` counts the paths tried so far.
+    fn open_reporting(path: &Path, hooks: &Hooks, done: &AtomicUsize, total: usize) -> Result<L, AsciiiError> {
+        let project = Self::open_project(path);
+        if project.is_ok() {
+            hooks.project_opened(path);
+        }
+        if hooks.wants_progress() {
+            hooks.progress(done.fetch_add(1, Ordering::Relaxed) + 1, total);
+        }
+        project
+    }
+
+}
+
+impl<P:Storable> fmt::Debug for Storage<P>{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        write!(f, "Storage: storage  = {storage:?}
+                          working  = {working:?}
+                          archive  = {archive:?}
+                          template = {template:?}",
+               storage  = self.root_dir(),
+               working  = self.working_dir(),
+               archive  = self.archive_dir(),
+               template = self.templates_dir(),
+               )
+    }
+}
@@ -0,0 +1,107 @@
+//! Async variants of the read paths of `Storage`, for the server and other long running services.
+//!
+//! Directories are listed with `tokio::fs`, projects are parsed on tokio's blocking pool,
+//! so scanning a large archive never holds up the executor.
+//! This needs the feature `async` and a tokio 0.2 runtime, which is what actix-web runs on.
+
+use std::path::{Path, PathBuf};
+
+use tokio::fs;
+use tokio::task;
+
+use crate::error::AsciiiError;
+use super::{archive_index, is_dot_file, OpenFailure, ProjectList, Storable, Storage, StorageDir, StorageError};
+
+/// Like `list_path_content()`.
+async fn list_path_content(path: &Path) -> Result<Vec<PathBuf>, AsciiiError> {
+    let mut entries = fs::read_dir(path).await?;
+    let mut list = Vec::new();
+    while let Some(entry) = entries.next_entry().await? {
+        let path = entry.path();
+        if !is_dot_file(&path) {
+            list.push(path);
+        }
+    }
+    Ok(list)
+}
+
+impl<L: Storable + Send + 'static> Storage<L> {
+
+    /// Like `list_project_folders()`.
+    pub async fn list_project_folders_async(&self, directory: StorageDir) -> Result<Vec<PathBuf>, AsciiiError> {
+        log::trace!("listing project folders in {:?}-directory", directory);
+        match directory {
+            StorageDir::Working => list_path_content(self.working_dir()).await,
+            StorageDir::Archive(year) => {
+                let mut list = list_path_content(&self.archive_dir().join(year.to_string())).await.unwrap_or_default();
+                list.retain(|path| !archive_index::is_index(path));
+                Ok(list)
+            },
+            StorageDir::All => {
+                let mut all = Vec::new();
+                for year in self.list_years()? {
+                    all.append(&mut list_path_content(&self.archive_dir().join(year.to_string())).await?);
+                }
+                all.retain(|path| !archive_index::is_index(path));
+                all.append(&mut list_path_content(self.working_dir()).await?);
+                Ok(all)
+            },
+            _ => return Err(StorageError::BadChoice.into())
+        }
+    }
+
+    /// Like `open_projects_dir()`, projects that can't be opened are left out.
+    pub async fn open_projects_dir_async(&self, directory: StorageDir) -> Result<ProjectList<L>, AsciiiError> {
+        log::debug!("OPENING ALL PROJECTS in {:?}-directory, asynchronously", directory);
+        let paths = match directory {
+            StorageDir::Year(year) => {
+                let mut paths = self.list_project_folders_async(StorageDir::Archive(year)).await?;
+                paths.append(&mut self.list_project_folders_async(StorageDir::Working).await?);
+                paths
+            },
+            _ => self.list_project_folders_async(directory).await?
+        };
+
+        let total = paths.len();
+        let mut projects = Vec::with_capacity(total);
+        for (done, path) in paths.into_iter().enumerate() {
+            let opened = path.clone();
+            match task::spawn_blocking(move || Self::open_project(&path)).await.map_err(std::io::Error::other)? {
+                Ok(project) => {
+                    self.hooks.project_opened(&opened);
+                    projects.push(project);
+                }
+                Err(error) => log::warn!("{}", OpenFailure { path: opened, error }),
+            }
+            self.hooks.progress(done + 1, total);
+        }
+
+        let mut projects = self.with_git_statuses(projects);
+        if let StorageDir::Year(year) = directory {
+            projects.filter_by_key_val("Year", year.to_string().as_ref());
+        }
+        Ok(projects)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project::Project;
+    use crate::project::spec::IsProject;
+    use tempdir::TempDir;
+
+    #[test]
+    fn opens_projects() {
+        let dir = TempDir::new("asciii-async").unwrap();
+        let storage = Storage::<Project>::builder().root(dir.path()).build().unwrap();
+        storage.create_dirs().unwrap();
+        storage.create_project_from_content("party", "event:\n  name: Party\n").unwrap();
+        std::fs::create_dir(storage.working_dir().join(".hidden")).unwrap();
+
+        let mut runtime = tokio::runtime::Builder::new().basic_scheduler().enable_all().build().unwrap();
+        let projects = runtime.block_on(storage.open_projects_dir_async(StorageDir::Working)).unwrap();
+        assert_eq!(projects.iter().map(|p| p.name().unwrap()).collect::<Vec<_>>(), vec!["Party"]);
+        assert!(runtime.block_on(storage.list_project_folders_async(StorageDir::Archive(2015))).unwrap().is_empty());
+    }
+}
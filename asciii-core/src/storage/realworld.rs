@@ -0,0 +1,82 @@
+use chrono::prelude::*;
+
+use super::*;
+use crate::project::Project;
+use crate::project::spec::{Invoicable, IsProject, Offerable, Redeemable};
+use crate::testing::{Stage, TempStorage};
+
+fn today() -> Date<Utc> {
+    Utc.ymd(2025, 6, 15)
+}
+
+#[test]
+fn list_template_files(){
+    let storage = TempStorage::new().unwrap();
+    assert!(storage.root().join("working").exists()
+            &&  storage.root().join("archive").exists()
+            &&  storage.root().join("templates").exists());
+
+    let templates = storage.storage().list_template_names().unwrap();
+    assert_eq!(templates, vec!["default"]);
+}
+
+#[test]
+fn list_archives(){
+    let storage = TempStorage::demo(today()).unwrap();
+    assert_eq!(storage.storage().list_years().unwrap(), vec![2023, 2024]);
+}
+
+#[test]
+fn list_project_folders(){
+    let storage = TempStorage::demo(today()).unwrap();
+    let storage = storage.storage();
+    let working = storage.list_project_files(StorageDir::Working).unwrap();
+    let all = storage.list_project_files(StorageDir::All).unwrap();
+    assert!(!working.is_empty());
+    assert_eq!(all.len(), 3 * crate::actions::demo::PER_YEAR as usize);
+
+    for project in storage.open_projects(StorageDir::Archive(2024)).unwrap().iter() {
+        assert!(project.canceled() || project.is_payed(), "{:?} should not be archived", project.name());
+    }
+}
+
+#[test]
+fn from_offer_to_archive(){
+    let storage = TempStorage::new().unwrap();
+    let party = storage.create_project("Party", Utc.ymd(2024, 3, 13)).unwrap();
+    assert!(party.offer().date().is_err());
+
+    let party = storage.advance(&party, Stage::Accepted, Utc.ymd(2024, 2, 1)).unwrap();
+    assert_eq!(party.offer().date().ok(), Some(Utc.ymd(2024, 2, 1)));
+    assert!(party.invoice().number().is_err());
+
+    let party = storage.advance(&party, Stage::Payed, Utc.ymd(2024, 3, 20)).unwrap();
+    assert_eq!(party.offer().date().ok(), Some(Utc.ymd(2024, 2, 1)));
+    assert_eq!(party.invoice().number().ok(), Some(1));
+    assert!(party.is_payed());
+    assert!(party.is_ready_for_archive().is_empty());
+    assert_eq!(storage.storage().ledger().numbers(2024).unwrap(), vec![1]);
+
+    storage.archive(&party, 2024).unwrap();
+    let archived = storage.storage().open_projects(StorageDir::Archive(2024)).unwrap();
+    assert_eq!(archived.len(), 1);
+    assert!(archived[0].dir().ends_with("R001_party"));
+    assert!(storage.storage().list_project_files(StorageDir::Working).unwrap().is_empty());
+}
+
+#[test]
+fn invoice_numbers_go_on(){
+    let storage = TempStorage::new().unwrap();
+    let numbers = ["Hochzeit", "Sommerfest", "Konferenz"].iter()
+        .map(|name| storage.create_project(name, Utc.ymd(2024, 6, 1)).unwrap())
+        .map(|project| storage.advance(&project, Stage::Invoiced, Utc.ymd(2024, 6, 10)).unwrap())
+        .map(|project: Project| project.invoice().number().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(numbers, vec![1, 2, 3]);
+
+    let canceled = storage.create_project("Jubiläum", Utc.ymd(2024, 7, 1)).unwrap();
+    let canceled = storage.advance(&canceled, Stage::Canceled, Utc.ymd(2024, 6, 20)).unwrap();
+    assert!(canceled.canceled());
+    assert!(canceled.invoice().number().is_err());
+    assert_eq!(storage.storage().ledger().numbers(2024).unwrap(), vec![1, 2, 3]);
+}
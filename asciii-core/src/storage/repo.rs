@@ -1,16 +1,14 @@
 #![allow(dead_code, unused_variables)]
 use std::fmt;
+use std::io;
 use std::path::{Path, PathBuf};
-#[cfg(feature="git_statuses")]
 use std::collections::HashMap;
+use std::sync::OnceLock;
 use std::process::{Command, ExitStatus};
 
 #[cfg(not(feature="git_statuses"))]
 use std::error::Error;
 
-use prettytable::{color, Attr};
-use prettytable::color::Color;
-
 /// More Rustacious way of representing a git status
 #[derive(Debug,Clone)]
 pub enum GitStatus{
@@ -19,37 +17,6 @@ pub enum GitStatus{
     Ignored, Conflict, Current, Unknown
 }
 
-impl GitStatus {
-    pub fn to_format(&self) -> Attr {
-        //Bold,
-        //Dim,
-        //Italic(bool),
-        //Underline(bool),
-        //Blink,
-        //Standout(bool),
-        //Reverse,
-        //Secure,
-        //ForegroundColor(Color),
-        //BackgroundColor(Color),
-
-        Attr::Reverse
-    }
-
-    pub fn to_style(&self) -> (Color,Option<Attr>) {
-        match *self{
-        // => write!(f, "{:?}",  self)
-         GitStatus::Current         => (color::BLUE,    None),
-         GitStatus::Conflict        => (color::RED,     None),
-         GitStatus::WorkingNew      => (color::GREEN,   None),
-         GitStatus::WorkingModified => (color::YELLOW,  None),
-         GitStatus::IndexNew        => (color::GREEN,   Some(Attr::Bold)),
-         GitStatus::IndexModified   => (color::BLUE,    Some(Attr::Bold)),
-         GitStatus::IndexDeleted    => (color::RED,     None),
-         _                          => (color::WHITE,   None)
-        }
-    }
-}
-
 impl fmt::Display for GitStatus {
 
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -97,8 +64,8 @@ pub struct Repository{
     /// Git Repository for StorageDir
     pub repo: git2::Repository,
     pub workdir: PathBuf,
-    /// Maps GitStatus to each path
-    pub statuses: HashMap<PathBuf, GitStatus>
+    /// Maps GitStatus to each path, see `statuses_by_path()`
+    statuses: OnceLock<HashMap<PathBuf, GitStatus>>
 }
 
 /// Convenience Wrapper for `git2::Repository`
@@ -113,12 +80,11 @@ impl Repository {
     #[cfg(feature="git_statuses")]
     pub fn try_new(path:&Path) -> Result<Self, git2::Error>{
         let repo = git2::Repository::open(path)?;
-        let statuses = Self::cache_statuses(&repo)?;
         Ok(
             Repository{
                 repo,
                 workdir: path.to_owned(),
-                statuses
+                statuses: OnceLock::new()
             }
           )
     }
@@ -143,10 +109,12 @@ impl Repository {
 
             if let Some(path) = entry.path(){
                 let path = repo_path.join(PathBuf::from(path));
-                if path.is_file() {
-                    if let Some(parent) = path.parent(){
-                        statuses.insert(parent.to_path_buf(), status.to_owned());
+                // directories take the status of the first changed file in them
+                for dir in path.ancestors().skip(1) {
+                    if dir == repo_path || statuses.contains_key(dir) {
+                        break;
                     }
+                    statuses.insert(dir.to_path_buf(), status.to_owned());
                 }
                 statuses.insert(path, status);
             }
@@ -155,16 +123,31 @@ impl Repository {
         Ok(statuses)
     }
 
-    /// Returns the status to a given path
+    /// Status of every changed file and every directory containing one.
+    ///
+    /// Read from git in one go when first asked for, `asciii shell` keeps it between commands.
     #[cfg(feature="git_statuses")]
-    pub fn get_status(&self,path:&Path) -> GitStatus{
-        self.statuses.get(path).unwrap_or(&GitStatus::Unknown).to_owned()
+    pub fn statuses_by_path(&self) -> &HashMap<PathBuf, GitStatus> {
+        self.statuses.get_or_init(|| {
+            let _timer = crate::util::timing::start("git status");
+            super::cache::statuses(&self.workdir, || Self::cache_statuses(&self.repo))
+                .unwrap_or_else(|e| {
+                    log::warn!("{}", lformat!("cannot read git statuses: {}", e));
+                    HashMap::new()
+                })
+        })
     }
 
-    /// INERT: Returns the status to a given path
+    /// INERT: Status of every changed file and every directory containing one.
     #[cfg(not(feature="git_statuses"))]
+    pub fn statuses_by_path(&self) -> &HashMap<PathBuf, GitStatus> {
+        static NONE: OnceLock<HashMap<PathBuf, GitStatus>> = OnceLock::new();
+        NONE.get_or_init(HashMap::new)
+    }
+
+    /// Returns the status to a given path
     pub fn get_status(&self,path:&Path) -> GitStatus{
-        GitStatus::Unknown
+        self.statuses_by_path().get(path).unwrap_or(&GitStatus::Unknown).to_owned()
     }
 
     fn execute_git(&self, command:&str, args:&[&str], paths: &[PathBuf]) -> ExitStatus{
@@ -202,6 +185,16 @@ impl Repository {
         self.execute_git("commit", &[], &[])
     }
 
+    /// Commits only `paths`, regardless of what else is staged.
+    pub fn commit_paths(&self, paths:&[PathBuf], message:&str) -> ExitStatus {
+        self.execute_git("commit", &["--message", message, "--"], paths)
+    }
+
+    /// Tags `HEAD` with an annotated tag.
+    pub fn tag(&self, name:&str, message:&str) -> ExitStatus {
+        self.execute_git("tag", &["--annotate", name, "--message", message], &[])
+    }
+
     pub fn status(&self) -> ExitStatus {
         self.execute_git("status", &[], &[])
     }
@@ -246,6 +239,58 @@ impl Repository {
     pub fn log(&self, paths:&[PathBuf]) -> ExitStatus {
         self.execute_git("log", &[ "--graph", "--pretty=format:'%Cred%h%Creset -%C(bold yellow)%d%Creset %C() %s %C(reset) ( %C(yellow)%an%Creset %C(green)%cr )'", "--abbrev-commit", "--date=relative" ], paths)
     }
+
+    /// Output of a git command that only reads, fails if git does.
+    fn read_git(&self, args:&[&str]) -> io::Result<String> {
+        let gitdir  = self.workdir.join(".git");
+        let output = Command::new("git")
+            .args(["--work-tree", self.workdir.to_str().unwrap()])
+            .args(["--git-dir",   gitdir.to_str().unwrap()])
+            .args(args)
+            .output()?;
+        if !output.status.success() {
+            return Err(io::Error::other(String::from_utf8_lossy(&output.stderr).trim().to_owned()));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    /// Number of files that are changed, staged or untracked.
+    pub fn changed_files(&self) -> io::Result<usize> {
+        Ok(self.read_git(&["status", "--porcelain"])?.lines().count())
+    }
+
+    /// Commits `HEAD` is ahead of and behind its upstream branch, as of the last fetch.
+    ///
+    /// `None` if the branch has no upstream.
+    pub fn ahead_behind(&self) -> io::Result<Option<(usize, usize)>> {
+        if self.read_git(&["rev-parse", "--abbrev-ref", "@{upstream}"]).is_err() {
+            return Ok(None);
+        }
+        let counts = self.read_git(&["rev-list", "--left-right", "--count", "HEAD...@{upstream}"])?;
+        let mut counts = counts.split_whitespace().filter_map(|count| count.parse::<usize>().ok());
+        match (counts.next(), counts.next()) {
+            (Some(ahead), Some(behind)) => Ok(Some((ahead, behind))),
+            _ => Err(io::Error::other(lformat!("unexpected output of git rev-list"))),
+        }
+    }
+
+    /// History of `paths` since `since` with minimal diffs, as read by `actions::activity::parse_log()`.
+    pub fn activity(&self, since:&str, paths:&[PathBuf]) -> io::Result<String> {
+        let gitdir  = self.workdir.join(".git");
+        let since = format!("--since={}", since);
+        let output = Command::new("git")
+            .args(["-c", "core.quotepath=off"])
+            .args(["--work-tree", self.workdir.to_str().unwrap()])
+            .args(["--git-dir",   gitdir.to_str().unwrap()])
+            .args(["log", &since, "-M", "-p", "--unified=0", "--no-color", "--no-ext-diff", "--date=short",
+                    "--format=%x1e%h%x1f%ad%x1f%an%x1f%s", "--"])
+            .args(paths)
+            .output()?;
+        if !output.status.success() {
+            return Err(io::Error::other(String::from_utf8_lossy(&output.stderr).trim().to_owned()));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
 }
 
 #[cfg(not(feature="git_statuses"))]
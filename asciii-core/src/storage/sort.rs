@@ -0,0 +1,163 @@
+//! The order projects are listed, searched (`N3`) and exported in.
+//!
+//! Every project has a `SortKey` of its date, invoice number and name. Which of these dominates is
+//! configured in `list/sort_key`, components left out there are compared last, in the default order.
+//! Missing dates and numbers sort after present ones, so unnumbered projects stay in place by date
+//! instead of piling up at the end.
+
+use std::cmp::Ordering;
+
+use chrono::{Date, Utc};
+use yaml_rust::Yaml;
+
+use crate::config::ConfigReader;
+use super::Storable;
+
+/// What projects are compared by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortComponent {
+    /// invoice date, or the event date before there is an invoice
+    Date,
+    /// invoice number
+    Number,
+    /// case insensitive
+    Name,
+}
+
+impl SortComponent {
+    pub fn parse(name: &str) -> Option<SortComponent> {
+        match name.trim().to_lowercase().as_str() {
+            "date" => Some(SortComponent::Date),
+            "number" => Some(SortComponent::Number),
+            "name" => Some(SortComponent::Name),
+            _ => None,
+        }
+    }
+}
+
+/// Everything a project is sorted by.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SortKey {
+    pub date: Option<Date<Utc>>,
+    pub number: Option<i64>,
+    pub name: String,
+}
+
+/// Present values first.
+fn cmp_present<T: Ord>(a: &Option<T>, b: &Option<T>) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => a.cmp(b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+impl SortKey {
+    pub fn cmp_by(&self, other: &SortKey, order: &SortOrder) -> Ordering {
+        order.components().iter().fold(Ordering::Equal, |ordering, component| {
+            ordering.then_with(|| match component {
+                SortComponent::Date => cmp_present(&self.date, &other.date),
+                SortComponent::Number => cmp_present(&self.number, &other.number),
+                SortComponent::Name => self.name.to_lowercase().cmp(&other.name.to_lowercase()),
+            })
+        })
+    }
+
+    /// The key as text, e.g. `20240310-00005-kaffee`, for templates and scripts.
+    ///
+    /// Missing parts are `~`, which sorts after digits and letters.
+    pub fn to_index(&self, order: &SortOrder) -> String {
+        order.components().iter()
+             .map(|component| match component {
+                 SortComponent::Date => self.date.map_or_else(|| String::from("~~~~~~~~"), |date| date.format("%Y%m%d").to_string()),
+                 SortComponent::Number => self.number.map_or_else(|| String::from("~~~~~"), |number| format!("{:05}", number)),
+                 SortComponent::Name => self.name.to_lowercase(),
+             })
+             .collect::<Vec<_>>()
+             .join("-")
+    }
+}
+
+/// Which `SortComponent` dominates, see `list/sort_key`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SortOrder(Vec<SortComponent>);
+
+impl Default for SortOrder {
+    fn default() -> SortOrder {
+        SortOrder(vec![SortComponent::Date, SortComponent::Number, SortComponent::Name])
+    }
+}
+
+impl SortOrder {
+    /// `components` first, then the rest in the default order.
+    pub fn new(components: &[SortComponent]) -> SortOrder {
+        let mut order = Vec::with_capacity(3);
+        for component in components.iter().chain(SortOrder::default().0.iter()) {
+            if !order.contains(component) {
+                order.push(*component);
+            }
+        }
+        SortOrder(order)
+    }
+
+    /// From `list/sort_key`, unknown components are ignored.
+    pub fn with_config(config: &ConfigReader) -> SortOrder {
+        let components = config.get("list/sort_key")
+                               .and_then(Yaml::as_vec)
+                               .map(|names| names.iter()
+                                                 .filter_map(Yaml::as_str)
+                                                 .filter_map(|name| {
+                                                     let component = SortComponent::parse(name);
+                                                     if component.is_none() {
+                                                         log::warn!("unknown sort key component {:?}, use date, number or name", name);
+                                                     }
+                                                     component
+                                                 })
+                                                 .collect::<Vec<_>>())
+                               .unwrap_or_default();
+        SortOrder::new(&components)
+    }
+
+    pub fn components(&self) -> &[SortComponent] {
+        &self.0
+    }
+
+    /// Sorts `projects`, computing each key once.
+    pub fn sort<L: Storable>(&self, projects: &mut Vec<L>) {
+        let mut keyed = projects.drain(..).map(|project| (project.sort_key(), project)).collect::<Vec<_>>();
+        keyed.sort_by(|(a, _), (b, _)| a.cmp_by(b, self));
+        projects.extend(keyed.into_iter().map(|(_, project)| project));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn key(date: Option<(i32, u32, u32)>, number: Option<i64>, name: &str) -> SortKey {
+        SortKey { date: date.map(|(y, m, d)| Utc.ymd(y, m, d)), number, name: name.into() }
+    }
+
+    #[test]
+    fn unnumbered_projects_stay_in_place() {
+        let mut keys = [key(None, None, "undated"),
+                        key(Some((2024, 5, 1)), None, "Sommerfest"),
+                        key(Some((2024, 3, 10)), Some(5), "kaffee"),
+                        key(Some((2024, 3, 10)), Some(4), "Brunch"),
+                        key(Some((2024, 6, 1)), Some(6), "abschluss")];
+        let order = SortOrder::default();
+        keys.sort_by(|a, b| a.cmp_by(b, &order));
+        assert_eq!(keys.iter().map(|k| k.name.as_str()).collect::<Vec<_>>(),
+                   vec!["Brunch", "kaffee", "Sommerfest", "abschluss", "undated"]);
+        assert_eq!(keys[1].to_index(&order), "20240310-00005-kaffee");
+        assert_eq!(keys[4].to_index(&order), "~~~~~~~~-~~~~~-undated");
+
+        let by_name = SortOrder::new(&[SortComponent::Name]);
+        assert_eq!(by_name.components(), &[SortComponent::Name, SortComponent::Date, SortComponent::Number]);
+        keys.sort_by(|a, b| a.cmp_by(b, &by_name));
+        assert_eq!(keys[0].name, "abschluss");
+        assert_eq!(keys[4].name, "undated");
+    }
+}
@@ -4,13 +4,17 @@
 use std::{fs,io};
 use std::ops::{Deref, DerefMut};
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use std::collections::HashMap;
 
 use chrono::{Date, Utc, Datelike};
-use anyhow::Error;
 use tempdir::TempDir;
 
+use crate::error::AsciiiError;
 use super::repo::GitStatus;
+use super::sort::SortKey;
+use super::archive_index::IndexEntry;
+use super::clients::ClientDatabase;
 
 pub type FilePath = Path;
 pub type FolderPath = Path;
@@ -21,11 +25,11 @@ pub type FolderPathBuf = PathBuf;
 pub trait Storable: Send+Sync {
 
     /// opens a project folder
-    fn open_folder(_: &FolderPath) -> Result<Self, Error> where Self: Sized;
-    fn open_file(_: &FilePath) -> Result<Self, Error> where Self: Sized;
+    fn open_folder(_: &FolderPath) -> Result<Self, AsciiiError> where Self: Sized;
+    fn open_file(_: &FilePath) -> Result<Self, AsciiiError> where Self: Sized;
 
     /// creates in tempfile
-    fn from_template(project_name: &str, template: &Path, data: &HashMap<&str, String>) -> Result<StorableAndTempDir<Self>, Error> where Self: Sized;
+    fn from_template(project_name: &str, template: &Path, data: &HashMap<&str, String>) -> Result<StorableAndTempDir<Self>, AsciiiError> where Self: Sized;
 
     /// For file names
     fn ident(&self) -> String{ self.dir().file_stem().and_then(std::ffi::OsStr::to_str).unwrap().to_owned() }
@@ -43,12 +47,22 @@ pub trait Storable: Send+Sync {
         } else {Ok(())}
     }
 
-    /// For sorting
+    /// For sorting, see `SortOrder`
+    fn sort_key(&self) -> SortKey {
+        SortKey { date: self.modified_date(), number: None, name: self.short_desc() }
+    }
+
+    /// The sort key as text
     fn index(&self) -> Option<String>;
 
     /// For archiving
     fn prefix(&self) -> Option<String>;
 
+    /// How the project is listed in the `.index.yml` of its archive year
+    fn index_entry(&self) -> IndexEntry {
+        IndexEntry { ident: self.ident(), name: self.short_desc(), invoice: self.prefix(), ..IndexEntry::default() }
+    }
+
     /// Sets the project File
     fn set_file(&mut self, new_file:&Path);
 
@@ -62,6 +76,15 @@ pub trait Storable: Send+Sync {
     /// This depends on the feature `git_statuses`
     fn get_git_status(&self) -> GitStatus{GitStatus::Unknown}
 
+    /// Hand a project the client database of its storage after opening, see `clients`
+    fn set_client_database(&mut self, _: Arc<ClientDatabase>){}
+
+    /// The `output_dir` the project asks for, as written, see `Storage::output_dir()`
+    fn output_dir_setting(&self) -> Option<String>{None}
+
+    /// Hand a project the directory its documents go to, see `Storage::output_dir()`
+    fn set_output_dir(&mut self, _: PathBuf){}
+
     /// Main project file extension
     fn file_extension() -> String {String::from("PROJECT")}
 
@@ -74,7 +97,7 @@ pub trait Storable: Send+Sync {
     }
 
     /// Path to project folder
-    fn dir(&self) -> FolderPathBuf { self.file().parent().unwrap().to_owned() }
+    fn dir(&self) -> FolderPathBuf { self.file().parent().map(ToOwned::to_owned).unwrap_or_default() }
 
     fn matches_filter(&self, key: &str, val: &str) -> bool;
     fn matches_search(&self, term: &str) -> bool;
@@ -0,0 +1,694 @@
+use std::path::{Path, PathBuf};
+use std::fs;
+use std::collections::HashMap;
+
+use chrono::prelude::*;
+use tempdir::TempDir;
+use maplit::hashmap;
+
+use crate::util;
+use crate::error::{ProjectError, SpecError};
+use super::*;
+use super::repo::GitStatus;
+
+// TODO: add tests for file or directories in return values
+
+#[allow(dead_code)]
+pub struct TestProject {
+    file_path: PathBuf,
+}
+
+impl Storable for TestProject{
+    // creates in tempfile
+    fn from_template(project_name: &str, template: &Path, _fill: &HashMap<&str, String>) -> Result<StorableAndTempDir<Self>, AsciiiError> where Self: Sized {
+        // generates a temp file
+        let temp_dir  = TempDir::new(project_name).unwrap();
+        let temp_file = temp_dir.path().join(project_name);
+
+        // just copy over template
+        fs::copy(template, &temp_file)?;
+
+        // project now lives in the temp_file
+        let project = TestProject {
+            file_path: temp_file,
+        };
+
+        Ok(StorableAndTempDir {
+            storable: project,
+            temp_dir
+        })
+    }
+
+    fn short_desc(&self) -> String{ self.file().file_stem().unwrap().to_str().unwrap().to_owned() }
+    fn modified_date(&self) -> Option<Date<Utc>>{ Some(Utc::today()) }
+    fn file(&self) -> PathBuf{ self.file_path.to_owned() }
+    fn set_file(&mut self, new_file:&Path){ self.file_path = new_file.to_owned(); }
+    fn index(&self) -> Option<String>{ Some("ZZ99".into()) }
+    fn prefix(&self) -> Option<String>{ self.index() }
+
+    fn open_folder(path:&Path) -> Result<Self, AsciiiError>{
+        // the project file is named after the folder it was created in
+        match list_path_content(path) {
+            Ok(files) if !files.is_empty() => Self::open_file(&files[0]),
+            _ => Self::open_file(path)
+        }
+    }
+
+    fn open_file(path:&Path) -> Result<Self, AsciiiError>{
+        Ok(TestProject{
+            file_path: PathBuf::from(path)
+        })
+    }
+    fn matches_filter(&self, _key: &str, _val: &str) -> bool {false}
+    fn matches_search(&self, _term: &str) -> bool {false}
+    fn is_ready_for_archive(&self) -> bool {true}
+
+}
+
+
+// TODO: implement failing cases
+const TEST_PROJECTS:[&str; 4] = [
+    "test1", "test2",
+    "foobar", "ich schreibe viel zu längliche projektnamen!",
+];
+
+
+fn setup() -> (TempDir, PathBuf, Storage<TestProject>) {
+    let dir = TempDir::new_in(Path::new("."),"storage_test").unwrap();
+    let storage_path = dir.path().join("storage_test");
+    let storage = Storage::<TestProject>::builder()
+        .root(&storage_path)
+        .working_dir("working")
+        .archive_dir("archive")
+        .templates_dir("templates")
+        .build()
+        .unwrap();
+    (dir, storage_path, storage)
+}
+
+fn assert_existence(storage_path:&Path) {
+    assert!(storage_path.exists()
+            &&  storage_path.join("working").exists()
+            &&  storage_path.join("archive").exists()
+            &&  storage_path.join("templates").exists());
+}
+
+fn copy_template(target:PathBuf) {
+    fs::copy("./templates/default.tyml", target.join("template1.tyml")).unwrap();
+    fs::copy("./templates/default.tyml", target.join("template2.tyml")).unwrap();
+}
+
+#[test]
+fn unknown_templates_are_suggested() {
+    let (_dir , storage_path, storage) = setup();
+    storage.create_dirs().unwrap();
+    copy_template(storage_path.join("templates"));
+    fs::copy("./templates/default.tyml", storage_path.join("templates").join("default.tyml")).unwrap();
+
+    let error = match storage.create_project("foobar", "defualt", &hashmap!{}) {
+        Err(error) => error,
+        Ok(_) => panic!("created a project from a template that does not exist"),
+    };
+    match error {
+        AsciiiError::Storage(StorageError::UnknownTemplate(ref name, ref available, ref suggestion)) => {
+            assert_eq!(name, "defualt");
+            assert_eq!(available, &["default", "template1", "template2"]);
+            assert_eq!(suggestion.as_deref(), Some("default"));
+        }
+        ref other => panic!("unexpected {:?}", other),
+    }
+    assert_eq!(error.to_string(), "There is no template \"defualt\". Did you mean `default`? Available templates: default, template1, template2.");
+
+    assert!(storage.get_template_file("party").is_err());
+    assert_eq!(util::closest_match("party", vec!["default", "template1"]), None);
+    assert_eq!(util::closest_match("Template", vec!["template1", "template2"]), Some("template1"));
+}
+
+#[test]
+fn repair_missing_dirs() {
+    let (_dir , storage_path, storage) = setup();
+    assert!(storage.health_check().is_err());
+
+    let repairs = storage.repair().unwrap();
+    assert!(repairs.contains(&Repair::Created(storage_path.join("working"))));
+    assert!(repairs.contains(&Repair::Created(storage_path.join("extras"))));
+    assert!(repairs.contains(&Repair::Template(storage_path.join("templates").join("default.tyml"))));
+    assert!(storage.health_check().is_ok());
+
+    #[cfg(unix)] {
+        use std::os::unix::fs::PermissionsExt;
+        let template = storage_path.join("templates").join("default.tyml");
+        fs::set_permissions(&template, fs::Permissions::from_mode(0o400)).unwrap();
+        assert_eq!(storage.repair().unwrap(), vec![Repair::Permissions(template.clone())]);
+        assert_eq!(fs::metadata(&template).unwrap().permissions().mode() & 0o777, 0o600);
+    }
+}
+
+#[test]
+fn create_dirs() {
+    let (dir , storage_path, storage) = setup();
+    storage.create_dirs().unwrap();
+    assert_existence(&storage_path);
+
+    // calling it again does not cause problems
+    assert!(storage.create_dirs().is_ok());
+    assert_existence(&storage_path);
+
+    util::ls(&dir.path().to_string_lossy());
+}
+
+#[test]
+fn list_template_files(){
+    let (_dir , storage_path, storage) = setup();
+    storage.create_dirs().unwrap();
+    assert_existence(&storage_path);
+
+    copy_template(storage_path.join("templates"));
+
+    let templates = storage.list_template_files().unwrap();
+    println!("{:#?}",templates);
+    assert!(templates.len() == 2);
+
+    ////util::freeze();
+}
+
+#[test]
+fn create_archive(){
+    let (_dir , storage_path, storage) = setup();
+    assert!(storage.create_dirs().is_ok());
+    assert_existence(&storage_path);
+    storage.create_archive(2001).unwrap();
+    storage.create_archive(2002).unwrap();
+    storage.create_archive(2002).unwrap(); // should this fail?
+    assert!(storage_path.join("archive").join("2001").exists());
+    assert!(storage_path.join("archive").join("2002").exists());
+    util::ls(&_dir.path().to_string_lossy());
+}
+
+#[test]
+fn list_archives(){
+    let (_dir , storage_path, storage) = setup();
+    assert!(storage.create_dirs().is_ok());
+    assert_existence(&storage_path);
+    storage.create_archive(2001).unwrap();
+    storage.create_archive(2002).unwrap();
+    storage.create_archive(1999).unwrap();
+    util::ls(&_dir.path().to_string_lossy());
+
+    let mut archives = storage.list_archives().unwrap();
+    let mut years = storage.list_years().unwrap();
+    archives.sort();
+    years.sort_unstable();
+    println!("ARCHIVES\n{:#?}", archives);
+
+    assert!(archives[0].ends_with("1999"));
+    assert!(archives[0].is_dir());
+    assert!(archives[1].ends_with("2001"));
+    assert!(archives[1].is_dir());
+    assert!(archives[2].ends_with("2002"));
+    assert!(archives[2].is_dir());
+
+    println!("ARCHIVES\n{:#?}", years);
+    assert_eq!(years[0], 1999);
+    assert_eq!(years[1], 2001);
+    assert_eq!(years[2], 2002);
+}
+
+#[test]
+fn template_variables_are_checked(){
+    let (_dir , storage_path, storage) = setup();
+    storage.create_dirs().unwrap();
+    let template = fs::read_to_string("./templates/default.tyml").unwrap();
+    let front_matter = "--- # asciii template\nvariables:\n- name: GUESTS\n  type: integer\n  prompt: How many guests?\n...\n";
+    fs::write(storage_path.join("templates").join("typed.tyml"), format!("{}{}", front_matter, template)).unwrap();
+
+    let variables = storage.template_variables("typed").unwrap();
+    assert_eq!(variables.len(), 1);
+    assert_eq!(variables[0].name, "GUESTS");
+
+    match storage.create_project("test1", "typed", &hashmap!{"GUESTS" => String::from("many")}) {
+        Err(AsciiiError::Spec(SpecError::Project(ProjectError::InvalidValue(ref name, _)))) => assert_eq!(name, "GUESTS"),
+        Err(other) => panic!("unexpected {:?}", other),
+        Ok(_) => panic!("created a project with an invalid variable"),
+    }
+    assert!(storage.get_project_dir("test1", StorageDir::Working).is_err());
+    assert!(storage.create_project("test1", "typed", &hashmap!{"GUESTS" => String::from("42")}).is_ok());
+}
+
+#[test]
+fn create_project(){
+    let (_dir , storage_path, storage) = setup();
+    assert!(storage.create_dirs().is_ok());
+    assert_existence(&storage_path);
+    copy_template(storage_path.join("templates"));
+
+    let templates = storage.list_template_names().unwrap();
+
+    for test_project in TEST_PROJECTS.iter() {
+        let project     = storage.create_project(test_project, &templates[0], &hashmap!()).unwrap();
+        let target_file = project.file();
+        let target_path = target_file.parent().unwrap();
+        assert!(target_path.exists());
+        assert!(target_file.exists());
+        util::ls(&target_path.display().to_string());
+        assert_eq!(target_file, storage.get_project_file(target_path).unwrap());
+
+        let project_dir = storage.get_project_dir(test_project, StorageDir::Working);
+        assert!(project_dir.unwrap().exists());
+
+        let project_dir = storage.get_project_dir(test_project, StorageDir::Working);
+        assert_eq!(project_dir.unwrap(), target_path);
+    }
+}
+
+#[test]
+fn slug_strategies() {
+    let legacy = SlugStrategy::default();
+    assert_eq!(legacy.slugify("My Test String!!!1!1"), "my-test-string-1-1");
+    assert_eq!(legacy.slugify("  --test_-_cool"), "test-cool");
+    assert_eq!(legacy.slugify("Æúű--cool?"), "aeuu-cool");
+    assert_eq!(legacy.slugify("Große Feier für Müller"), "grosse-feier-fur-muller");
+
+    let config = ConfigReader::from_content(Path::new("config.yml"), "slugs:\n  max_length: 20\n  lowercase: false").unwrap();
+    let strategy = SlugStrategy::with_config(&config);
+    assert_eq!(strategy.slugify("Große Feier für Müller"), "Grosse-Feier-fuer-Mu");
+    assert_eq!(strategy.slugify("Sommerfest 2024 – Ост"), "Sommerfest-2024-Ost");
+}
+
+#[test]
+fn projects_keep_resolving_by_their_old_slug() {
+    let (_dir , storage_path, storage) = setup();
+    assert!(storage.create_dirs().is_ok());
+    let name = "Große Feier für Müller";
+
+    // named before `slugs/transliterate`
+    let legacy_dir = storage_path.join("working").join("grosse-feier-fur-muller");
+    fs::create_dir_all(&legacy_dir).unwrap();
+    fs::write(legacy_dir.join("grosse-feier-fur-muller.PROJECT"), "").unwrap();
+    assert_eq!(storage.get_project_dir(name, StorageDir::Working).unwrap(), legacy_dir);
+
+    let (new_dir, _) = storage.new_project_paths(name).unwrap();
+    assert_eq!(new_dir, storage_path.join("working").join("grosse-feier-fuer-mueller"));
+}
+
+#[test]
+fn archive_project_by_name(){
+    let (_dir , storage_path, storage) = setup();
+    assert!(storage.create_dirs().is_ok());
+    assert_existence(&storage_path);
+    copy_template(storage_path.join("templates"));
+
+    let templates = storage.list_template_names().unwrap();
+    log::trace!("templates: {:#?}", templates);
+    for test_project in TEST_PROJECTS.iter() {
+        // tested above
+        let origin = storage.create_project( test_project, &templates[0], &hashmap!{}).unwrap();
+
+        // the actual tests
+        assert!(storage.archive_project_by_name(test_project, 2015, None).is_ok());
+        assert!(!origin.file().exists());
+
+        assert!(storage.get_project_dir(test_project, StorageDir::Working).is_err());
+        assert!(storage.get_project_dir(test_project, StorageDir::Archive(2015)).is_ok());
+
+        //let false_origin = storage.create_project(&test_project, &templates[0]).unwrap();
+        assert!(storage.archive_project_by_name(test_project, 2015, None).is_err());
+    }
+}
+
+#[test]
+fn archive_project(){
+    let (_dir , storage_path, storage) = setup();
+    assert!(storage.create_dirs().is_ok(), "could not even create storage in {:?}", storage_path);
+    assert_existence(&storage_path);
+    copy_template(storage_path.join("templates"));
+
+    let year = Utc::today().year();
+
+    let templates = storage.list_template_names().unwrap();
+    for test_project_name in TEST_PROJECTS.iter() {
+        // tested above
+        let project = storage.create_project( test_project_name, &templates[0], &hashmap!{}).unwrap();
+
+        // Before archiving
+        assert!(project.file().exists());
+        assert!(storage.get_project_dir(test_project_name, StorageDir::Working).is_ok());
+
+        // ARCHIVING
+        assert!(storage.archive_project(&project, project.year().unwrap()).is_ok());
+
+        // After archiving
+        assert!(!project.file().exists());
+        assert!(storage.get_project_dir(test_project_name, StorageDir::Working).is_err());
+        assert!(storage.get_project_dir(test_project_name, StorageDir::Archive(year)).is_ok());
+
+        assert!(storage.archive_project(&project, year).is_err());
+    }
+}
+
+#[test]
+fn archive_projects_all_or_nothing(){
+    let (_dir , storage_path, storage) = setup();
+    assert!(storage.create_dirs().is_ok());
+    copy_template(storage_path.join("templates"));
+
+    let templates = storage.list_template_names().unwrap();
+    let projects = TEST_PROJECTS.iter()
+                                .map(|name| storage.create_project(name, &templates[0], &hashmap!{}).unwrap())
+                                .collect::<Vec<_>>();
+
+    // the last one can't be moved
+    let blocked = storage.create_archive(2015).unwrap().join(format!("ZZ99_{}", projects[3].ident()));
+    fs::create_dir(&blocked).unwrap();
+
+    assert!(storage.archive_projects(projects.iter().map(|p| (p, 2015))).is_err());
+    assert_eq!(storage.list_project_folders(StorageDir::Working).unwrap().len(), TEST_PROJECTS.len());
+    assert_eq!(storage.list_project_folders(StorageDir::Archive(2015)).unwrap(), vec![blocked.clone()]);
+
+    fs::remove_dir(&blocked).unwrap();
+    assert_eq!(storage.archive_projects(projects.iter().map(|p| (p, 2015))).unwrap().len(), 2 * TEST_PROJECTS.len());
+    assert!(storage.list_project_folders(StorageDir::Working).unwrap().is_empty());
+}
+
+#[test]
+fn closed_years_are_read_only(){
+    let (_dir , storage_path, storage) = setup();
+    assert!(storage.create_dirs().is_ok());
+    copy_template(storage_path.join("templates"));
+
+    let templates = storage.list_template_names().unwrap();
+    let projects = TEST_PROJECTS.iter()
+                                .map(|name| storage.create_project(name, &templates[0], &hashmap!{}).unwrap())
+                                .collect::<Vec<_>>();
+    storage.archive_project(&projects[0], 2015).unwrap();
+    storage.closed_years().close(2015, None).unwrap();
+
+    assert!(storage.archive_project(&projects[1], 2015).is_err());
+    assert!(storage.archive_project(&projects[1], 2016).is_ok());
+
+    let archived = storage.list_project_folders(StorageDir::Archive(2015)).unwrap();
+    assert_eq!(archived.len(), 1);
+    assert!(storage.unarchive_project_dir(&archived[0]).is_err());
+    let archived = storage.open_projects(StorageDir::Archive(2015)).unwrap();
+    assert!(storage.ensure_open(&archived[0]).is_err());
+    assert!(storage.ensure_open(&projects[2]).is_ok());
+}
+
+#[test]
+fn fix_archive_prefixes(){
+    let (_dir , storage_path, storage) = setup();
+    assert!(storage.create_dirs().is_ok());
+    copy_template(storage_path.join("templates"));
+
+    let templates = storage.list_template_names().unwrap();
+    let projects = TEST_PROJECTS.iter()
+                                .map(|name| storage.create_project(name, &templates[0], &hashmap!{}).unwrap())
+                                .collect::<Vec<_>>();
+    storage.archive_projects(projects.iter().take(2).map(|p| (p, 2015))).unwrap();
+    assert!(storage.fix_archive_prefixes(2015).unwrap().is_empty());
+    assert!(storage.fix_archive_prefixes(2016).unwrap().is_empty());
+
+    // archived before it had a number
+    let archive = storage.archive_dir().join("2015");
+    let unprefixed = archive.join(projects[0].ident());
+    fs::rename(archive.join(format!("ZZ99_{}", projects[0].ident())), &unprefixed).unwrap();
+
+    let renamed = storage.fix_archive_prefixes(2015).unwrap();
+    assert_eq!(renamed, vec![(unprefixed.clone(), archive.join(format!("ZZ99_{}", projects[0].ident())))]);
+    assert!(!unprefixed.exists());
+    assert_eq!(storage.list_project_folders(StorageDir::Archive(2015)).unwrap().len(), 2);
+
+    storage.closed_years().close(2015, None).unwrap();
+    assert!(storage.fix_archive_prefixes(2015).is_err());
+}
+
+#[test]
+fn archive_index_follows_archiving(){
+    let (_dir , storage_path, storage) = setup();
+    assert!(storage.create_dirs().is_ok());
+    copy_template(storage_path.join("templates"));
+
+    let templates = storage.list_template_names().unwrap();
+    let projects = TEST_PROJECTS.iter()
+                                .map(|name| storage.create_project(name, &templates[0], &hashmap!{}).unwrap())
+                                .collect::<Vec<_>>();
+    let moved = storage.archive_projects(projects.iter().take(2).map(|p| (p, 2015))).unwrap();
+    let year_dir = storage.archive_dir().join("2015");
+    let index = archive_index::read(&year_dir).unwrap();
+    assert_eq!(index.len(), 2);
+    assert_eq!(index[0].invoice.as_deref(), Some("ZZ99"));
+
+    // the index is no project, neither is one written by older versions
+    fs::write(year_dir.join("index.yml"), "[]\n").unwrap();
+    assert_eq!(storage.list_project_folders(StorageDir::Archive(2015)).unwrap().len(), 2);
+    assert_eq!(storage.list_project_files(StorageDir::Archive(2015)).unwrap().len(), 2);
+    assert_eq!(storage.open_projects(StorageDir::Archive(2015)).unwrap().len(), 2);
+    assert_eq!(storage.open_projects_with_failures(StorageDir::All).unwrap().1.len(), 0);
+
+    storage.unarchive_project_dir(&moved[1]).unwrap();
+    let index = archive_index::read(&year_dir).unwrap();
+    assert_eq!(index.len(), 1);
+    assert_eq!(index[0].ident, format!("ZZ99_{}", projects[1].ident()));
+}
+
+#[test]
+fn hooks_see_opened_and_archived_projects(){
+    use std::sync::Mutex;
+
+    let (_dir , storage_path, mut storage) = setup();
+    assert!(storage.create_dirs().is_ok());
+    copy_template(storage_path.join("templates"));
+
+    let templates = storage.list_template_names().unwrap();
+    let projects = TEST_PROJECTS.iter()
+                                .map(|name| storage.create_project(name, &templates[0], &hashmap!{}).unwrap())
+                                .collect::<Vec<_>>();
+
+    let opened = Arc::new(Mutex::new(Vec::new()));
+    let progress = Arc::new(Mutex::new(Vec::new()));
+    let archived = Arc::new(Mutex::new(Vec::new()));
+    let (o, p, a) = (Arc::clone(&opened), Arc::clone(&progress), Arc::clone(&archived));
+    storage.on_project_opened(move |path| o.lock().unwrap().push(path.to_owned()))
+           .on_progress(move |step| p.lock().unwrap().push(step))
+           .on_archived(move |from, to| a.lock().unwrap().push((from.to_owned(), to.to_owned())));
+
+    assert_eq!(storage.open_projects(StorageDir::Working).unwrap().len(), TEST_PROJECTS.len());
+    assert_eq!(opened.lock().unwrap().len(), TEST_PROJECTS.len());
+    let mut done = progress.lock().unwrap().iter().map(|step| step.done).collect::<Vec<_>>();
+    done.sort();
+    assert_eq!(done, (1..=TEST_PROJECTS.len()).collect::<Vec<_>>());
+    assert!(progress.lock().unwrap().iter().all(|step| step.total == TEST_PROJECTS.len()));
+
+    storage.archive_project(&projects[0], 2015).unwrap();
+    let archived = archived.lock().unwrap();
+    assert_eq!(archived.len(), 1);
+    assert_eq!(archived[0].0, projects[0].dir());
+    assert!(archived[0].1.starts_with(storage.archive_dir().join("2015")));
+}
+
+#[test]
+fn memory_storage(){
+    let mut storage = MemoryStorage::new();
+    storage.insert("working/party/party.yml", "event:\n  name: Party\n".into());
+    storage.insert("working/party/notes.txt", "not a project".into());
+    storage.insert("archive/2015/R036_picnic/picnic.yml", "event:\n  name: Picnic\n".into());
+    storage.insert("archive/2014/R001_broken/broken.yml", "event: [".into());
+
+    assert_eq!(storage.list_years(), vec![2014, 2015]);
+    assert_eq!(storage.list_project_files(StorageDir::Working).unwrap(), vec![Path::new("working/party/party.yml")]);
+    assert_eq!(storage.list_project_files(StorageDir::All).unwrap().len(), 3);
+    assert_eq!(storage.open_projects(StorageDir::All).unwrap().len(), 2);
+
+    let picnic = storage.find_project(StorageDir::Archive(2015), "R036_picnic").unwrap();
+    assert_eq!(picnic.dir(), Path::new("archive/2015/R036_picnic"));
+    assert!(storage.find_project(StorageDir::Working, "R036_picnic").is_err());
+}
+
+#[test]
+fn unarchive_project_dir(){
+    let (_dir , storage_path, storage) = setup();
+    assert!(storage.create_dirs().is_ok());
+    assert_existence(&storage_path);
+    copy_template(storage_path.join("templates"));
+
+    let templates = storage.list_template_names().unwrap();
+    for test_project in TEST_PROJECTS.iter() {
+        let _origin = storage.create_project( test_project, &templates[0], &hashmap!{}).unwrap();
+        storage.archive_project_by_name(test_project, 2015, None).unwrap();
+    }
+
+    for year in storage.list_years().unwrap(){
+        println!("{:?}", year);
+        for proj in storage.list_project_folders(StorageDir::Archive(year)).unwrap() {
+            assert!(storage.unarchive_project_dir(&proj).is_ok());
+            assert!(storage.unarchive_project_dir(&proj).is_err());
+        }
+    }
+}
+
+#[test]
+fn iter_projects(){
+    let (_dir , storage_path, storage) = setup();
+    assert!(storage.create_dirs().is_ok());
+    copy_template(storage_path.join("templates"));
+
+    let templates = storage.list_template_names().unwrap();
+    for test_project in TEST_PROJECTS.iter() {
+        storage.create_project(test_project, &templates[0], &hashmap!{}).unwrap();
+    }
+
+    assert_eq!(storage.iter_projects(StorageDir::Working).unwrap().count(), TEST_PROJECTS.len());
+    assert_eq!(storage.iter_projects(StorageDir::Working).unwrap().take(1).count(), 1);
+
+    // `TestProject` matches no search
+    let search = StorageSelection::DirAndSearch(StorageDir::Working, vec!["test1".into()]);
+    assert!(storage.iter_projects(search).is_err());
+}
+
+#[test]
+#[cfg(feature="git_statuses")]
+fn statuses_by_path(){
+    let (_dir, storage_path, storage) = setup();
+    storage.create_dirs().unwrap();
+    let root = storage_path.canonicalize().unwrap();
+    // untracked directories are reported as a whole, so `working/` needs something tracked in it
+    let git = git2::Repository::init(&root).unwrap();
+    fs::write(root.join("working/old.yml"), "").unwrap();
+    let mut index = git.index().unwrap();
+    index.add_path(Path::new("working/old.yml")).unwrap();
+    index.write().unwrap();
+    let tree = git.find_tree(index.write_tree().unwrap()).unwrap();
+    let signature = git2::Signature::now("test", "test@example.com").unwrap();
+    git.commit(Some("HEAD"), &signature, &signature, "init", &tree, &[]).unwrap();
+
+    fs::create_dir_all(root.join("working/party/notes")).unwrap();
+    fs::write(root.join("working/party/notes/todo.txt"), "buy tea").unwrap();
+
+    let repo = Repository::try_new(&root).unwrap();
+    let statuses = repo.statuses_by_path();
+    assert!(matches!(repo.get_status(&root.join("working/party")), GitStatus::WorkingNew));
+    assert!(statuses.contains_key(&root.join("working")));
+    assert!(!statuses.contains_key(&root));
+    assert!(matches!(repo.get_status(&root.join("archive")), GitStatus::Unknown));
+}
+
+#[test]
+fn setup_with_config(){
+    let (dir, storage_path, _) = setup();
+    let storage = Storage::<TestProject>::builder()
+        .root(&storage_path)
+        .working_dir("current")
+        .archive_dir("old")
+        .templates_dir("blueprints")
+        .build()
+        .unwrap();
+    storage.create_dirs().unwrap();
+    fs::write(storage.templates_dir().join("party.tpl"), "").unwrap();
+    fs::write(storage.templates_dir().join("party.tyml"), "").unwrap();
+
+    let content = format!("path: {:?}\ndirs:\n  storage: storage_test\n  working: current\n  archive: old\n  templates: blueprints\nextensions:\n  project_template: tpl\n",
+                          dir.path().canonicalize().unwrap());
+    let config = ConfigReader::from_content(Path::new("/nonexistent/.asciii.yml"), &content).unwrap();
+    let storage = Storage::<TestProject>::setup(Arc::new(config)).unwrap();
+    assert!(storage.working_dir().ends_with("storage_test/current"));
+    assert_eq!(storage.list_template_names().unwrap(), vec!["party"]);
+}
+
+#[test]
+fn read_only_storage(){
+    let (_dir, storage_path, storage) = setup();
+    storage.create_dirs().unwrap();
+
+    let read_only = Storage::<TestProject>::builder()
+        .root(&storage_path)
+        .working_dir("working")
+        .archive_dir("archive")
+        .templates_dir("templates")
+        .read_only(true)
+        .build()
+        .unwrap();
+    assert!(read_only.is_read_only());
+    assert!(read_only.list_project_folders(StorageDir::Working).unwrap().is_empty());
+    assert!(matches!(read_only.create_project_from_content("party", ""),
+                     Err(AsciiiError::Storage(StorageError::ReadOnly))));
+    assert!(matches!(read_only.create_archive(2020), Err(AsciiiError::Storage(StorageError::ReadOnly))));
+    assert!(!storage_path.join("working/party").exists());
+}
+
+#[test]
+fn builder_takes_dir_names_from_config(){
+    let (_dir, storage_path, _) = setup();
+    let config = ConfigReader::from_content(Path::new("/nonexistent/.asciii.yml"), "dirs:\n  working: current\n").unwrap();
+    let storage = Storage::<TestProject>::builder()
+        .root(&storage_path)
+        .config(Arc::new(config))
+        .archive_dir("old")
+        .build()
+        .unwrap();
+    assert_eq!(storage.working_dir(), storage_path.join("current"));
+    assert_eq!(storage.archive_dir(), storage_path.join("old"));
+    assert_eq!(storage.templates_dir(), storage_path.join("templates"));
+}
+
+#[test]
+fn materialize_default_templates(){
+    let (_dir, _storage_path, storage) = setup();
+    storage.create_dirs().unwrap();
+    assert!(storage.list_template_files().is_err());
+
+    assert_eq!(storage.materialize_default_templates().unwrap().len(), DEFAULT_TEMPLATES.len());
+    assert_eq!(storage.list_template_names().unwrap(), vec!["default"]);
+    assert!(storage.materialize_default_templates().unwrap().is_empty());
+}
+
+#[test]
+#[cfg(feature="rayon")]
+fn configured_thread_pool(){
+    let (_dir, _storage_path, storage) = setup();
+    let config = ConfigReader::from_content(Path::new("/nonexistent/.asciii.yml"), "parallel:\n  threads: 2\n").unwrap();
+    let storage = storage.with_config(Arc::new(config));
+    assert_eq!(storage.thread_pool().map(rayon::ThreadPool::current_num_threads), Some(2));
+}
+
+#[test]
+fn broken_projects_are_reported(){
+    use crate::project::Project;
+    let (_dir, storage_path, _) = setup();
+    let storage = Storage::<Project>::builder().root(&storage_path).build().unwrap();
+    storage.create_dirs().unwrap();
+    storage.create_project_from_content("party", "event:\n  name: Party\n").unwrap();
+    let broken = storage.working_dir().join("broken");
+    fs::create_dir(&broken).unwrap();
+    fs::write(broken.join("broken.yml"), "client: [broken\n").unwrap();
+
+    let (projects, failures) = storage.open_projects_with_failures(StorageDir::Working).unwrap();
+    assert_eq!(projects.len(), 1);
+    assert_eq!(failures.len(), 1);
+    assert_eq!(failures[0].path, broken);
+    assert!(failures[0].to_string().contains("flow sequence"), "{}", failures[0]);
+
+    // listings just leave it out
+    assert_eq!(storage.open_projects(StorageDir::Working).unwrap().len(), 1);
+}
+
+#[test]
+fn projects_with_their_own_output_dir(){
+    use crate::project::{Exportable, Project};
+    let (_dir, storage_path, _) = setup();
+    let storage = Storage::<Project>::builder().root(&storage_path).build().unwrap();
+    storage.create_dirs().unwrap();
+    storage.create_project_from_content("party", "event:\n  name: Party\noutput_dir: clients/graf\n").unwrap();
+    storage.create_project_from_content("picnic", "event:\n  name: Picnic\noutput_dir: /srv/picnic\n").unwrap();
+    storage.create_project_from_content("plain", "event:\n  name: Plain\n").unwrap();
+
+    let projects = storage.open_projects(StorageDir::Working).unwrap();
+    let folder = |name: &str| projects.iter().find(|p| p.short_desc() == name).unwrap().output_folder();
+    assert_eq!(folder("Party"), Some(storage.root_dir().join("clients/graf")));
+    assert_eq!(folder("Picnic"), Some(PathBuf::from("/srv/picnic")));
+    assert_ne!(folder("Plain"), folder("Party"));
+
+    let config = ConfigReader::from_content(Path::new("/nonexistent/.asciii.yml"), "output_dir_root: /srv/documents\n").unwrap();
+    let storage = storage.with_config(Arc::new(config));
+    let party = storage.iter_projects(StorageDir::Working).unwrap().find(|p| p.short_desc() == "Party").unwrap();
+    assert_eq!(party.output_folder(), Some(PathBuf::from("/srv/documents/clients/graf")));
+}
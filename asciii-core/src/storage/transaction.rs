@@ -0,0 +1,111 @@
+//! Moves several directories as a whole, or not at all.
+//!
+//! A `Transaction` remembers every rename it performs.
+//! Unless it is committed, everything is moved back, newest first, when it is dropped,
+//! so returning early with `?` is enough to roll back.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use super::journal::{Change, Journal};
+
+#[derive(Debug, Default)]
+pub struct Transaction {
+    moves: Vec<(PathBuf, PathBuf)>,
+    committed: bool,
+}
+
+impl Transaction {
+    pub fn new() -> Transaction {
+        Transaction::default()
+    }
+
+    /// Renames `from` to `to` and remembers it.
+    pub fn rename(&mut self, from: &Path, to: &Path) -> io::Result<()> {
+        fs::rename(from, to)?;
+        self.moves.push((from.to_owned(), to.to_owned()));
+        Ok(())
+    }
+
+    /// What was moved so far, in order.
+    pub fn moves(&self) -> &[(PathBuf, PathBuf)] {
+        &self.moves
+    }
+
+    /// Old and new paths of everything moved, like `Storage::archive_project()` returns them.
+    pub fn paths(&self) -> Vec<PathBuf> {
+        self.moves.iter()
+                  .flat_map(|(from, to)| vec![from.clone(), to.clone()])
+                  .collect()
+    }
+
+    /// The moves as journal changes, see `Storage::record()`.
+    pub fn changes(&self, journal: &Journal) -> Vec<Change> {
+        self.moves.iter()
+                  .map(|(from, to)| journal.moved(from, to))
+                  .collect()
+    }
+
+    /// Keeps everything that was moved.
+    pub fn commit(mut self) -> Vec<(PathBuf, PathBuf)> {
+        self.committed = true;
+        ::std::mem::take(&mut self.moves)
+    }
+
+    /// Moves everything back, newest first.
+    ///
+    /// Keeps going if one of them fails and returns the first error.
+    pub fn rollback(mut self) -> io::Result<()> {
+        self.committed = true;
+        self.undo()
+    }
+
+    fn undo(&mut self) -> io::Result<()> {
+        let mut result = Ok(());
+        while let Some((from, to)) = self.moves.pop() {
+            log::warn!("{}", lformat!("moving {} back to {}", to.display(), from.display()));
+            if let Err(e) = fs::rename(&to, &from) {
+                log::error!("{}", lformat!("can't move {} back to {}: {}", to.display(), from.display(), e));
+                result = result.and(Err(e));
+            }
+        }
+        result
+    }
+}
+
+impl Drop for Transaction {
+    fn drop(&mut self) {
+        if !self.committed {
+            let _ = self.undo();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempdir::TempDir;
+
+    #[test]
+    fn rolls_back_unless_committed() {
+        let dir = TempDir::new("asciii-transaction").unwrap();
+        let (a, b, c) = (dir.path().join("a"), dir.path().join("b"), dir.path().join("c"));
+        fs::create_dir(&a).unwrap();
+        fs::create_dir(&b).unwrap();
+
+        {
+            let mut transaction = Transaction::new();
+            transaction.rename(&a, &c).unwrap();
+            assert!(transaction.rename(&b, &c.join("b")).is_ok());
+            assert!(transaction.rename(&dir.path().join("missing"), &a).is_err());
+            assert_eq!(transaction.moves().len(), 2);
+        }
+        assert!(a.exists() && b.exists() && !c.exists());
+
+        let mut transaction = Transaction::new();
+        transaction.rename(&a, &c).unwrap();
+        assert_eq!(transaction.commit(), vec![(a.clone(), c.clone())]);
+        assert!(!a.exists() && c.exists());
+    }
+}
@@ -0,0 +1,426 @@
+//! Simple templating functionality through keyword replacement.
+//!
+//! Replaces `##KEYWORDS##` in Strings.
+//!
+//! Project templates may declare their keywords in a front-matter, a yaml document of its own before the template:
+//!
+//! ```yaml
+//! --- # asciii template
+//! variables:
+//! - name: LOCATION
+//!   type: text      # text, integer, decimal, bool, date (dd.mm.yyyy) or time (hh:mm)
+//!   default: Mensa
+//!   prompt: Where does the event take place?
+//! ...
+//! --- # asciii document
+//! ```
+use std::fmt;
+use std::io;
+use std::fs;
+use std::path::Path;
+use std::error::Error;
+use std::collections::HashMap;
+
+use regex::{Regex, Captures};
+use std::ops::Deref;
+use yaml_rust::Yaml;
+
+use crate::error::AsciiiError;
+use crate::project::error::ProjectError;
+use crate::project::spec::FieldKind;
+use crate::project::FieldError;
+use crate::util::{self, yaml};
+
+/// Simple template style keyword replacement.
+///
+/// This allows replacing a known set of keywords looking like `##THIS##`.
+/// Here it is implemented for `Deref<Target=str>`.
+pub trait IsKeyword {
+    /// Checks if the whole string is a keyword
+    fn is_keyword(&self) -> bool;
+    /// Captures keywords from string.
+    fn get_keyword(&self) -> Option<String>;
+    /// Well, it lists the keywords in a string, duh!
+    fn list_keywords(&self) -> Vec<String>;
+
+    /// This one is usefull.
+    ///
+    /// Takes a clorsure that replaces keywords.
+    /// **Careful**, this replaces either way!
+    /// If you get a keywords you don't want to replace,
+    /// please place it back where you got it from.
+    ///
+    /// # Example
+    /// ```ignore
+    /// .map_keywords|keyword| match data.get(keyword){
+    ///     Some(content) => String::from(*content),
+    ///     None => format!("##{}##", keyword)
+    /// }
+    /// ```
+    ///
+    fn map_keywords<F>(&self, closure: F) -> String where F:Fn(&str) -> String;// -> Option<String>;
+}
+
+static REGEX: &str = r"##([0-9A-Z-]*)##*";
+
+/// Allows very simplistic `##KEYWORD##` replacement.
+impl<U:Deref<Target=str>> IsKeyword for U {
+
+    /// Checks if the whole string is a keyword
+    fn is_keyword(&self) -> bool{
+        Regex::new(REGEX).expect("broken regex").is_match(self)
+    }
+
+    /// Captures keywords from string.
+    fn get_keyword(&self) -> Option<String> {
+        Regex::new(REGEX).expect("broken regex")
+            .captures(self)
+            .and_then(|caps| caps.get(1).map(|c| c.as_str().to_owned()))
+    }
+
+    /// Well, it lists the keywords in a string, duh!
+    fn list_keywords(&self) -> Vec<String>{
+        Regex::new(REGEX).expect("broken regex")
+            .captures_iter(self)
+            .filter_map(|c|c.get(1))
+            .map(|c|c.as_str().to_owned())
+            .collect()
+    }
+
+    /// This one is usefull.
+    ///
+    /// Takes a clorsure that replaces keywords.
+    /// **Careful**, this replaces either way!
+    /// If you get a keywords you don't want to replace,
+    /// please place it back where you got it from.
+    ///
+    /// # Example
+    /// ```ignore
+    /// .map_keywords|keyword| match data.get(keyword){
+    ///     Some(content) => String::from(*content),
+    ///     None => format!("##{}##", keyword)
+    /// }
+    /// ```
+    ///
+    fn map_keywords<F>(&self, closure: F) -> String
+        where F:Fn(&str) -> String{
+        Regex::new(REGEX).expect("broken regex")
+            .replace_all(self, |caps:&Captures<'_>| {
+                closure(caps.get(1).unwrap().as_str())
+            }).into()
+    }
+}
+
+
+/// Simple templating module
+#[derive(Debug)]
+pub struct Templater{
+    /// content of template file after reading
+    pub original: String,
+
+    /// content of filled template
+    pub filled: String,
+}
+
+impl Templater{
+
+    pub fn new(template:&str) -> Templater{
+        Templater{
+            original:template.to_owned(),
+            filled: String::new(),
+        }
+    }
+
+    /// Reads a template, without its front-matter.
+    pub fn from_file(path: &Path) -> Result<Templater, io::Error> {
+        let template = fs::read_to_string(&path)?;
+        Ok(Templater::new(split_front_matter(&template).1))
+    }
+
+    pub fn finalize(&mut self) -> Templater {
+        self.to_owned()
+    }
+
+    /// Creates a finished version of the output.
+    ///
+    /// If any keywords remain unfilled, `Err` contains a list of left overs.
+    pub fn complete(&mut self) -> TemplateResult<Templater>{
+        let left_overs = self.filled.list_keywords();
+
+        if left_overs.is_empty(){
+            Ok(self.to_owned())
+        } else {
+            Err(TemplateError::Incomplete(left_overs))
+        }
+    }
+
+    pub fn fix(&self) -> Self{
+        Templater{
+            original: self.filled.to_owned(),
+            filled: String::new()
+        }
+    }
+
+    pub fn fill_in_field(&mut self, field: &str, value: &str) -> &mut Templater {
+        self.fill_template(|keyword|
+                           if keyword == field{
+                               value.to_owned()
+                           } else {
+                               format!("##{}##", keyword)
+                           })
+    }
+
+    pub fn fill_in_data(&mut self, data: &HashMap<&str,String>) -> &mut Templater {
+        self.fill_template(|keyword| match data.get(keyword){
+            Some(content) => content.clone(),
+            None => format!("##{}##", keyword)
+        })
+    }
+
+    pub fn list_keywords(&self) -> Vec<String>{
+        self.original.list_keywords()
+    }
+
+    pub fn fill_template<F>(&mut self, closure: F) -> &mut Templater
+        where F:Fn(&str) -> String {
+        self.filled = self.original.map_keywords(closure);
+        self
+    }
+}
+
+/// First line of a front-matter, it ends with a line of `...`.
+pub const FRONT_MATTER_START: &str = "--- # asciii template";
+
+/// Kind of value a template variable takes.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum VariableKind {
+    Text,
+    Integer,
+    Decimal,
+    Bool,
+    /// `dd.mm.yyyy`
+    Date,
+    /// `hh:mm`
+    Time,
+}
+
+impl VariableKind {
+    fn from_name(name: &str) -> Option<VariableKind> {
+        match name {
+            "text"    => Some(VariableKind::Text),
+            "integer" => Some(VariableKind::Integer),
+            "decimal" => Some(VariableKind::Decimal),
+            "bool"    => Some(VariableKind::Bool),
+            "date"    => Some(VariableKind::Date),
+            "time"    => Some(VariableKind::Time),
+            _ => None
+        }
+    }
+
+    /// Checks `value`, the message says what is wrong with it.
+    pub fn check(self, value: &str) -> Result<(), String> {
+        let field_kind = match self {
+            VariableKind::Text    => return Ok(()),
+            VariableKind::Time    => return util::naive_time_from_str(value.trim())
+                                                  .filter(|_| value.contains(':'))
+                                                  .map(|_| ())
+                                                  .ok_or_else(|| lformat!("{:?} is not a valid time", value)),
+            VariableKind::Integer => FieldKind::Integer,
+            VariableKind::Decimal => FieldKind::Decimal,
+            VariableKind::Bool    => FieldKind::Bool,
+            VariableKind::Date    => FieldKind::Date,
+        };
+        field_kind.to_yaml(value).map(|_| ()).map_err(|e| match e {
+            FieldError::Invalid(message) => message,
+            e => e.to_string(),
+        })
+    }
+}
+
+/// A keyword declared in the front-matter of a template.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Variable {
+    /// the keyword, without `##`
+    pub name: String,
+    pub kind: VariableKind,
+    pub default: Option<String>,
+    /// what to ask for it, see `asciii new --interactive`
+    pub prompt: Option<String>,
+}
+
+impl Variable {
+    fn from_yaml(yaml: &Yaml) -> Result<Variable, ProjectError> {
+        let name = yaml::get_to_string(yaml, "name")
+            .ok_or_else(|| ProjectError::TemplateVariable(String::new(), lformat!("every variable needs a name")))?;
+        let kind = match yaml::get_str(yaml, "type") {
+            None => VariableKind::Text,
+            Some(kind) => VariableKind::from_name(kind)
+                .ok_or_else(|| ProjectError::TemplateVariable(name.clone(), lformat!("unknown type {:?}", kind)))?
+        };
+        let default = yaml::get_to_string(yaml, "default");
+        if let Some(ref default) = default {
+            kind.check(default).map_err(|e| ProjectError::TemplateVariable(name.clone(), e))?;
+        }
+        Ok(Variable { name, kind, default, prompt: yaml::get_to_string(yaml, "prompt") })
+    }
+
+    /// The prompt, or the name if there is none.
+    pub fn question(&self) -> &str {
+        self.prompt.as_deref().unwrap_or(&self.name)
+    }
+
+    /// Checks a value given for this variable.
+    pub fn check(&self, value: &str) -> Result<(), ProjectError> {
+        self.kind.check(value).map_err(|e| ProjectError::InvalidValue(self.name.clone(), e))
+    }
+}
+
+/// Splits a template into its front-matter, if it has one, and the rest.
+pub fn split_front_matter(template: &str) -> (Option<&str>, &str) {
+    if !template.starts_with(FRONT_MATTER_START) {
+        return (None, template);
+    }
+    let mut offset = 0;
+    for line in template.split_inclusive('\n') {
+        offset += line.len();
+        if line.trim_end() == "..." {
+            return (Some(&template[..offset]), &template[offset..]);
+        }
+    }
+    (Some(template), "")
+}
+
+/// The variables declared in the front-matter of `template`, none without one.
+pub fn variables(template: &str) -> Result<Vec<Variable>, AsciiiError> {
+    let front_matter = match split_front_matter(template).0 {
+        Some(front_matter) => yaml::parse(front_matter)?,
+        None => return Ok(Vec::new())
+    };
+    let mut variables = Vec::new();
+    for variable in yaml::get(&front_matter, "variables").and_then(Yaml::as_vec).map(Vec::as_slice).unwrap_or_default() {
+        variables.push(Variable::from_yaml(variable)?);
+    }
+    Ok(variables)
+}
+
+/// Checks `data` against `variables` and adds the defaults of those that are missing.
+///
+/// Declared variables without a value or default are filled with nothing, other keywords in `data` are kept as they are.
+pub fn fill_variables<'a>(variables: &'a [Variable], data: &HashMap<&'a str, String>) -> Result<HashMap<&'a str, String>, ProjectError> {
+    let mut filled = data.clone();
+    for variable in variables {
+        match data.get(variable.name.as_str()) {
+            Some(value) => variable.check(value)?,
+            None => {
+                filled.insert(&variable.name, variable.default.clone().unwrap_or_default());
+            }
+        }
+    }
+    Ok(filled)
+}
+
+pub type TemplateResult<T> = Result<T, TemplateError>;
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum TemplateError{
+    Incomplete(Vec<String>)
+}
+
+impl fmt::Display for TemplateError{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result{
+        match *self {
+            TemplateError::Incomplete(ref list) => write!(f, "Incomplete ({:?})", list),
+        }
+    }
+}
+
+impl Error for TemplateError{
+    fn description(&self) -> &str{ "The template was not filled completely." }
+    fn cause(&self) -> Option<&dyn Error>{None}
+}
+
+use std::borrow::ToOwned;
+impl ToOwned for Templater{
+    type Owned = Templater;
+    fn to_owned(&self) -> Templater {
+        Templater{
+            //path :    self.path.to_owned(),
+            original: self.original.to_owned(),
+            filled:   self.filled.to_owned()
+        }
+    }
+}
+
+#[cfg(test)]
+mod test{
+    use maplit::hashmap;
+    
+    use super::Templater;
+    const TEMPLATE: &str = r##"This tests ##TEST## for ##ATTR## ##SUBJ##."##;
+
+   #[test]
+   fn complete(){
+       let filled_in = Templater::new(TEMPLATE)
+           .fill_in_data(&hashmap!{
+               "TEST" => String::from("templates"),
+               "ATTR" => String::from("complete"),
+               "SUBJ" => String::from("replacements"),
+           }).complete().unwrap();
+       assert_eq!(filled_in.filled, "This tests templates for complete replacements.")
+   }
+
+   #[test]
+   fn chain(){
+       let filled_in = Templater::new(TEMPLATE)
+           .fill_in_data(&hashmap!{
+               "TEST" => String::from("templates")}).fix()
+           .fill_in_data(&hashmap!{
+               "ATTR" => String::from("complete"),
+               "SUBJ" => String::from("replacements") })
+       .complete().unwrap();
+       assert_eq!(filled_in.filled, "This tests templates for complete replacements.")
+   }
+
+   #[test]
+   fn front_matter(){
+       use super::{fill_variables, split_front_matter, variables, VariableKind};
+
+       let template = "--- # asciii template\nvariables:\n- name: GUESTS\n  type: integer\n  default: 20\n  prompt: How many guests?\n- name: LOCATION\n...\n--- # asciii document\nevent:\n  location: ##LOCATION##\n";
+       let (front_matter, rest) = split_front_matter(template);
+       assert!(front_matter.unwrap().ends_with("...\n"));
+       assert_eq!(rest, "--- # asciii document\nevent:\n  location: ##LOCATION##\n");
+       assert_eq!(split_front_matter(rest), (None, rest));
+
+       let variables = variables(template).unwrap();
+       assert_eq!(variables.len(), 2);
+       assert_eq!(variables[0].kind, VariableKind::Integer);
+       assert_eq!(variables[0].question(), "How many guests?");
+       assert_eq!(variables[1].kind, VariableKind::Text);
+       assert_eq!(variables[1].question(), "LOCATION");
+
+       let filled = fill_variables(&variables, &hashmap!{"LOCATION" => String::from("Mensa")}).unwrap();
+       assert_eq!(filled.get("GUESTS").map(String::as_str), Some("20"));
+       assert_eq!(filled.get("LOCATION").map(String::as_str), Some("Mensa"));
+       assert!(fill_variables(&variables, &hashmap!{"GUESTS" => String::from("many")}).is_err());
+
+       assert!(VariableKind::Time.check("10:30").is_ok());
+       assert!(VariableKind::Time.check("25:00").is_err());
+       assert!(super::variables("--- # asciii template\nvariables:\n- name: DAY\n  type: date\n  default: tomorrow\n...\n").is_err());
+       assert!(super::variables("--- # asciii template\nvariables:\n- name: DAY\n  type: weekday\n...\n").is_err());
+   }
+
+   #[test]
+   fn not_complete(){
+       let filled_in = Templater::new(TEMPLATE)
+           .fill_in_data(&hashmap!{
+               "TEST" => String::from("templates"),
+           }).complete();
+       assert!(filled_in.is_err());
+       assert_eq!(filled_in.unwrap_err(), super::TemplateError::Incomplete(
+               vec![
+               String::from("ATTR"),
+               String::from("SUBJ")]
+               ))
+   }
+
+}
@@ -0,0 +1,157 @@
+//! Storages in a temporary directory to script scenarios against, needs the `testing` feature.
+//!
+//! ```ignore
+//! # use asciii_core::testing::{TempStorage, Stage};
+//! # use chrono::prelude::*;
+//! let storage = TempStorage::new().unwrap();
+//! let party = storage.create_project("Party", Utc.ymd(2024, 3, 13)).unwrap();
+//! let party = storage.advance(&party, Stage::Payed, Utc.ymd(2024, 4, 2)).unwrap();
+//! storage.archive(&party, 2024).unwrap();
+//! ```
+//!
+//! Nothing touches the configured storage, git is off and everything is gone once the `TempStorage` is dropped.
+
+use std::path::Path;
+
+use chrono::prelude::*;
+use tempdir::TempDir;
+
+use crate::actions;
+use crate::error::AsciiiError;
+use crate::project::Project;
+use crate::storage::{Storable, Storage};
+
+pub use crate::actions::demo::Stage;
+
+/// Fields `advance()` fills in for each stage, those already set are left alone.
+const STAGES: &[(Stage, &[&str])] = &[
+    (Stage::Offered, &["offer/date", "offer/sent_date"]),
+    (Stage::Accepted, &["offer/accepted_date"]),
+    (Stage::Invoiced, &["invoice/number", "invoice/date"]),
+    (Stage::Payed, &["invoice/payed_date", "hours/wages_date"]),
+];
+
+/// A `Storage` of real `Project`s in a temporary directory.
+pub struct TempStorage {
+    storage: Storage<Project>,
+    // removed last
+    _dir: TempDir,
+}
+
+impl TempStorage {
+    /// An empty storage with the default templates.
+    pub fn new() -> Result<TempStorage, AsciiiError> {
+        let dir = TempDir::new("asciii-storage")?;
+        let storage = Storage::builder().root(dir.path()).git(false).build()?;
+        storage.create_dirs()?;
+        storage.materialize_default_templates()?;
+        Ok(TempStorage { storage, _dir: dir })
+    }
+
+    /// A storage filled like `asciii init --demo` on `today`, see `actions::demo`.
+    pub fn demo(today: Date<Utc>) -> Result<TempStorage, AsciiiError> {
+        let storage = TempStorage::new()?;
+        actions::demo::populate(&storage.storage, today)?;
+        Ok(storage)
+    }
+
+    pub fn storage(&self) -> &Storage<Project> {
+        &self.storage
+    }
+
+    pub fn root(&self) -> &Path {
+        self.storage.root_dir()
+    }
+
+    /// Writes `content` as the project `name` into the working directory.
+    pub fn create_project_from_content(&self, name: &str, content: &str) -> Result<Project, AsciiiError> {
+        self.storage.create_project_from_content(name, content)
+    }
+
+    /// A complete draft: a client, the event on `event`, products and hours, nothing offered yet.
+    pub fn create_project(&self, name: &str, event: Date<Utc>) -> Result<Project, AsciiiError> {
+        let content = format!(
+"---
+client:
+  title: Frau
+  first_name: Anna
+  last_name: Albers
+  email: anna.albers@example.org
+  address: |
+    Anna Albers
+    Ahornweg 1
+    01067 Dresden
+
+event:
+  name: {name}
+  dates:
+  - begin: {date}
+
+offer:
+
+invoice:
+
+products:
+  Kaffee:
+    amount: 20
+    price: 2.5
+  Kuchen:
+    amount: 30
+    price: 0.94
+
+hours:
+  salary: 8.0
+  caterers:
+    Klaus: 4
+
+manager: Ida Jung
+canceled: false
+tax: 0.19
+
+meta:
+  format: 3.0.0
+created: {date}
+...
+", name = name, date = event.format("%d.%m.%Y"));
+        self.create_project_from_content(name, &content)
+    }
+
+    /// Fills in everything `project` needs to be at `stage`, dated `date`, and returns it reopened.
+    ///
+    /// Stages on the way are passed too, a draft advanced to `Payed` is also offered, accepted and invoiced.
+    /// The invoice number is the next one of the year of `date` and is issued in the ledger.
+    /// `Canceled` only cancels, `Draft` changes nothing.
+    pub fn advance(&self, project: &Project, stage: Stage, date: Date<Utc>) -> Result<Project, AsciiiError> {
+        let date_str = date.format("%d.%m.%Y").to_string();
+        let mut fields = Vec::new();
+        if stage == Stage::Canceled {
+            fields.push(("canceled", "true".to_owned()));
+        }
+        for &(_, paths) in STAGES.iter().take_while(|(reached, _)| stage != Stage::Canceled && *reached <= stage) {
+            for &path in paths {
+                if project.field(path).is_some() {
+                    continue;
+                }
+                let value = match path {
+                    "invoice/number" => {
+                        let number = actions::next_invoice_number(&self.storage, date.year())?;
+                        self.storage.ledger().issue(date.year(), number, &project.short_desc())?;
+                        number.to_string()
+                    }
+                    _ => date_str.clone(),
+                };
+                fields.push((path, value));
+            }
+        }
+
+        let fields = fields.iter().map(|(path, value)| (*path, value.as_str())).collect::<Vec<_>>();
+        project.write_content(&project.content_with_fields(&fields)?)?;
+        Project::open_file(&project.file())
+    }
+
+    /// Moves `project` into the archive of `year`, like `asciii archive`.
+    pub fn archive(&self, project: &Project, year: i32) -> Result<(), AsciiiError> {
+        self.storage.archive_project(project, year)?;
+        Ok(())
+    }
+}
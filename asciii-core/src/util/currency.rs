@@ -0,0 +1,138 @@
+//! The currency amounts are kept in, with its symbol for people and its ISO 4217 code for machines.
+//!
+//! It is configured with `currency` (symbol or code), `currency_code` and `currency_decimals`:
+//!
+//! ```yaml
+//! currency: "Fr."
+//! currency_code: CHF
+//! currency_decimals: 2
+//! ```
+//!
+//! For the currencies in `KNOWN` either one is enough, `currency: USD` prints amounts as `12,30$`.
+//! Projects billed in another currency name it with `currency: <code>`, see `exchange`.
+
+use bill::Currency;
+
+use crate::config::ConfigReader;
+
+/// Code, symbol and decimal places of common currencies.
+pub const KNOWN: [(&str, &str, u32); 10] = [
+    ("EUR", "€",  2),
+    ("USD", "$",  2),
+    ("GBP", "£",  2),
+    ("CHF", "Fr.", 2),
+    ("JPY", "¥",  0),
+    ("PLN", "zł", 2),
+    ("CZK", "Kč", 2),
+    ("SEK", "kr", 2),
+    ("DKK", "kr", 2),
+    ("NOK", "kr", 2),
+];
+
+/// Used when neither `currency` nor `currency_code` say otherwise.
+pub const DEFAULT_CODE: &str = "EUR";
+
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CurrencyUnit {
+    /// ISO 4217, e.g. `EUR`
+    pub code: String,
+    /// e.g. `€`
+    pub symbol: String,
+    /// digits after the decimal comma when printed
+    pub decimals: u32,
+}
+
+impl CurrencyUnit {
+    /// One of the `KNOWN` currencies by its ISO code.
+    pub fn by_code(code: &str) -> Option<CurrencyUnit> {
+        KNOWN.iter()
+             .find(|(known, _, _)| known.eq_ignore_ascii_case(code.trim()))
+             .map(|&(code, symbol, decimals)| CurrencyUnit { code: code.into(), symbol: symbol.into(), decimals })
+    }
+
+    /// The first of the `KNOWN` currencies with `symbol`.
+    pub fn by_symbol(symbol: &str) -> Option<CurrencyUnit> {
+        KNOWN.iter()
+             .find(|(_, known, _)| *known == symbol.trim())
+             .map(|&(code, symbol, decimals)| CurrencyUnit { code: code.into(), symbol: symbol.into(), decimals })
+    }
+
+    /// From `currency`, which may be a symbol or a code, and the optional `code` and `decimals`.
+    pub fn new(currency: &str, code: Option<&str>, decimals: Option<u32>) -> CurrencyUnit {
+        let currency = currency.trim();
+        let code = code.map(str::trim).filter(|code| !code.is_empty());
+        let known = code.and_then(CurrencyUnit::by_code)
+                        .or_else(|| CurrencyUnit::by_code(currency))
+                        .or_else(|| CurrencyUnit::by_symbol(currency));
+        let symbol = match known {
+            // `currency: EUR` with a known code means `€`
+            Some(ref known) if currency.is_empty() || currency.eq_ignore_ascii_case(&known.code) => known.symbol.clone(),
+            _ => currency.to_owned(),
+        };
+        let code = code.map(str::to_uppercase)
+                       .or_else(|| known.as_ref().map(|known| known.code.clone()))
+                       .unwrap_or_else(|| {
+                           log::warn!("{}", lformat!("don't know the code of the currency {:?}, set currency_code", currency));
+                           DEFAULT_CODE.to_owned()
+                       });
+        let decimals = decimals.or_else(|| known.map(|known| known.decimals)).unwrap_or(2);
+        CurrencyUnit { code, symbol, decimals }
+    }
+
+    /// From `currency`, `currency_code` and `currency_decimals` in `config`.
+    pub fn from_config(config: &ConfigReader) -> CurrencyUnit {
+        CurrencyUnit::new(config.get_str("currency"),
+                          config.get_str_or("currency_code"),
+                          config.get_f64("currency_decimals").map(|decimals| decimals as u32))
+    }
+
+    /// The currency of `crate::CONFIG`.
+    pub fn configured() -> CurrencyUnit {
+        CurrencyUnit::from_config(&crate::CONFIG)
+    }
+
+    /// The symbol as `bill::Currency` keeps it, only its first character.
+    pub fn symbol_char(&self) -> Option<char> {
+        self.symbol.chars().next()
+    }
+
+    /// `1234,50€`, `1235¥` or `1234,50Fr.`
+    pub fn format(&self, amount: &Currency) -> String {
+        let decimal = format!("{:.*}", self.decimals as usize, amount.as_float()).replace('.', ",");
+        format!("{}{}", decimal, self.symbol)
+    }
+}
+
+impl Default for CurrencyUnit {
+    fn default() -> CurrencyUnit {
+        CurrencyUnit::by_code(DEFAULT_CODE).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn symbols_and_codes() {
+        let euro = CurrencyUnit::new("€", None, None);
+        assert_eq!(euro, CurrencyUnit::by_code("eur").unwrap());
+        assert_eq!(euro.format(&Currency { symbol: Some('€'), value: 123450 }), "1234,50€");
+        assert_eq!(euro.format(&Currency { symbol: Some('€'), value: -1210 }), "-12,10€");
+
+        let dollar = CurrencyUnit::new("USD", None, None);
+        assert_eq!((dollar.code.as_str(), dollar.symbol.as_str()), ("USD", "$"));
+        assert_eq!(dollar.symbol_char(), Some('$'));
+
+        let yen = CurrencyUnit::new("¥", None, None);
+        assert_eq!(yen.format(&Currency { symbol: Some('¥'), value: 123460 }), "1235¥");
+
+        let franc = CurrencyUnit::new("SFr", Some("chf"), Some(2));
+        assert_eq!((franc.code.as_str(), franc.symbol.as_str()), ("CHF", "SFr"));
+
+        // `kr` is ambiguous
+        assert_eq!(CurrencyUnit::new("kr", Some("NOK"), None).code, "NOK");
+        assert_eq!(CurrencyUnit::new("₿", None, Some(3)).code, DEFAULT_CODE);
+    }
+}
@@ -0,0 +1,218 @@
+//! Business days, so due dates don't fall on weekends or public holidays.
+//!
+//! Holidays are computed offline for `due/holidays/country` (`DE` or `AT`) and, in Germany,
+//! `due/holidays/state` (e.g. `SN`). Further days off can be listed in `due/holidays/extra`,
+//! as `24.12.` for every year or `02.05.2025` for a single day.
+//! Nothing is skipped unless `due/skip_weekends` is set or a country is configured.
+
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+
+use crate::config::ConfigReader;
+
+/// Which days count when a due date is computed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BusinessDays {
+    pub skip_weekends: bool,
+    /// ISO code, upper case
+    pub country: Option<String>,
+    /// German state, e.g. `SN`
+    pub state: Option<String>,
+    /// `(day, month, year)`, `None` for every year
+    pub extra: Vec<(u32, u32, Option<i32>)>,
+}
+
+/// Easter Sunday, by the anonymous gregorian algorithm.
+pub fn easter(year: i32) -> NaiveDate {
+    let a = year % 19;
+    let b = year / 100;
+    let c = year % 100;
+    let d = b / 4;
+    let e = b % 4;
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15) % 30;
+    let i = c / 4;
+    let k = c % 4;
+    let l = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m = (a + 11 * h + 22 * l) / 451;
+    let month = (h + l - 7 * m + 114) / 31;
+    let day = (h + l - 7 * m + 114) % 31 + 1;
+    NaiveDate::from_ymd_opt(year, month as u32, day as u32).unwrap()
+}
+
+/// The Wednesday before November 23rd, a holiday in Saxony.
+fn repentance_day(year: i32) -> NaiveDate {
+    let nov_22 = NaiveDate::from_ymd_opt(year, 11, 22).unwrap();
+    let since_wednesday = (nov_22.weekday().num_days_from_monday() + 7 - Weekday::Wed.num_days_from_monday()) % 7;
+    nov_22 - Duration::days(i64::from(since_wednesday))
+}
+
+fn in_states(state: Option<&str>, states: &[&str]) -> bool {
+    state.is_some_and(|state| states.contains(&state))
+}
+
+/// Public holidays of `year`, `None` if there are no tables for `country`.
+pub fn public_holidays(country: &str, state: Option<&str>, year: i32) -> Option<Vec<NaiveDate>> {
+    let date = |month, day| NaiveDate::from_ymd_opt(year, month, day).unwrap();
+    let easter = easter(year);
+    let after_easter = |days| easter + Duration::days(days);
+
+    let mut days = match country {
+        "DE" => {
+            let mut days = vec![date(1, 1), after_easter(-2), after_easter(1), date(5, 1),
+                                after_easter(39), after_easter(50), date(10, 3), date(12, 25), date(12, 26)];
+            if in_states(state, &["BW", "BY", "ST"]) {
+                days.push(date(1, 6));
+            }
+            if (in_states(state, &["BE"]) && year >= 2019) || (in_states(state, &["MV"]) && year >= 2023) {
+                days.push(date(3, 8));
+            }
+            if in_states(state, &["BW", "BY", "HE", "NW", "RP", "SL"]) {
+                days.push(after_easter(60));
+            }
+            if in_states(state, &["SL"]) {
+                days.push(date(8, 15));
+            }
+            if in_states(state, &["TH"]) && year >= 2019 {
+                days.push(date(9, 20));
+            }
+            if year == 2017
+                || in_states(state, &["BB", "MV", "SN", "ST", "TH"])
+                || (in_states(state, &["HB", "HH", "NI", "SH"]) && year >= 2018) {
+                days.push(date(10, 31));
+            }
+            if in_states(state, &["BW", "BY", "NW", "RP", "SL"]) {
+                days.push(date(11, 1));
+            }
+            if in_states(state, &["SN"]) {
+                days.push(repentance_day(year));
+            }
+            days
+        }
+        "AT" => vec![date(1, 1), date(1, 6), after_easter(1), date(5, 1), after_easter(39), after_easter(50),
+                     after_easter(60), date(8, 15), date(10, 26), date(11, 1), date(12, 8), date(12, 25), date(12, 26)],
+        _ => return None,
+    };
+    days.sort();
+    days.dedup();
+    Some(days)
+}
+
+/// Reads `24.12.` or `24.12.2024`.
+fn parse_extra(day: &str) -> Option<(u32, u32, Option<i32>)> {
+    let mut parts = day.trim().split('.');
+    let d = parts.next()?.trim().parse().ok()?;
+    let m = parts.next()?.trim().parse().ok()?;
+    let y = match parts.next().map(str::trim) {
+        None | Some("") => None,
+        Some(year) => Some(year.parse().ok()?),
+    };
+    Some((d, m, y))
+}
+
+impl BusinessDays {
+    /// From `due/` in `config`.
+    pub fn from_config(config: &ConfigReader) -> BusinessDays {
+        let non_empty = |key: &str| config.get_str_or(key)
+            .map(|value| value.trim().to_uppercase())
+            .filter(|value| !value.is_empty());
+        let days = BusinessDays {
+            skip_weekends: config.get("due/skip_weekends").and_then(yaml_rust::Yaml::as_bool).unwrap_or(false),
+            country: non_empty("due/holidays/country"),
+            state: non_empty("due/holidays/state"),
+            extra: config.get_strs("due/holidays/extra").unwrap_or_default()
+                .into_iter()
+                .filter_map(|day| parse_extra(day).or_else(|| { log::warn!("ignoring day off {:?}", day); None }))
+                .collect(),
+        };
+        if let Some(country) = &days.country {
+            if public_holidays(country, None, 2000).is_none() {
+                log::warn!("{}", lformat!("there are no holidays known for {:?}", country));
+            }
+        }
+        days
+    }
+
+    /// From `crate::CONFIG`.
+    pub fn configured() -> BusinessDays {
+        BusinessDays::from_config(&crate::CONFIG)
+    }
+
+    pub fn is_holiday(&self, date: NaiveDate) -> bool {
+        let public = self.country.as_deref()
+            .and_then(|country| public_holidays(country, self.state.as_deref(), date.year()))
+            .is_some_and(|days| days.contains(&date));
+        public || self.extra.iter().any(|&(d, m, y)| d == date.day() && m == date.month() && y.is_none_or(|y| y == date.year()))
+    }
+
+    pub fn is_weekend(date: NaiveDate) -> bool {
+        matches!(date.weekday(), Weekday::Sat | Weekday::Sun)
+    }
+
+    /// Whether anybody works on `date`, weekends count only with `skip_weekends`.
+    pub fn is_business_day(&self, date: NaiveDate) -> bool {
+        let weekend = self.skip_weekends && Self::is_weekend(date);
+        !(weekend || self.is_holiday(date))
+    }
+
+    /// `date`, or the next business day after it.
+    pub fn next_business_day(&self, date: NaiveDate) -> NaiveDate {
+        let mut date = date;
+        // a year of holidays in a row would be a broken config
+        for _ in 0..366 {
+            if self.is_business_day(date) {
+                break;
+            }
+            date += Duration::days(1);
+        }
+        date
+    }
+
+    /// `days` after `start`, moved to the next business day if it isn't one.
+    pub fn due_date(&self, start: NaiveDate, days: i64) -> NaiveDate {
+        self.next_business_day(start + Duration::days(days))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn german_holidays() {
+        assert_eq!(easter(2024), date(2024, 3, 31));
+        assert_eq!(easter(2025), date(2025, 4, 20));
+        assert_eq!(repentance_day(2024), date(2024, 11, 20));
+
+        let saxony = public_holidays("DE", Some("SN"), 2024).unwrap();
+        assert!(saxony.contains(&date(2024, 3, 29)));
+        assert!(saxony.contains(&date(2024, 10, 31)));
+        assert!(saxony.contains(&date(2024, 11, 20)));
+        assert!(!saxony.contains(&date(2024, 11, 1)));
+        assert!(public_holidays("DE", Some("BY"), 2024).unwrap().contains(&date(2024, 11, 1)));
+        assert_eq!(public_holidays("XX", None, 2024), None);
+    }
+
+    #[test]
+    fn due_dates_skip_days_off() {
+        let days = BusinessDays {
+            skip_weekends: true,
+            country: Some("DE".into()),
+            state: Some("SN".into()),
+            extra: vec![parse_extra("24.12.").unwrap(), parse_extra("27.12.2024").unwrap()],
+        };
+        // Good Friday, then the weekend, then Easter Monday
+        assert_eq!(days.due_date(date(2024, 3, 15), 14), date(2024, 4, 2));
+        // Christmas Eve, Christmas, the weekend before
+        assert_eq!(days.due_date(date(2024, 12, 10), 14), date(2024, 12, 30));
+        assert!(days.is_holiday(date(2030, 12, 24)));
+        assert!(!days.is_holiday(date(2030, 12, 27)));
+
+        let every_day = BusinessDays::default();
+        assert_eq!(every_day.due_date(date(2024, 3, 15), 14), date(2024, 3, 29));
+    }
+}
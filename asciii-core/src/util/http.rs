@@ -0,0 +1,380 @@
+//! Requests to the outside world, exchange rates, webhooks, payment links, attachments and mails.
+//!
+//! They all go through `curl` with the same timeouts, proxy and retries, configured in `http`:
+//!
+//! ```yaml
+//! http:
+//!   timeout: 60          # seconds per attempt, 0 for none
+//!   connect_timeout: 10
+//!   retries: 3           # attempts after the first one
+//!   backoff: 500         # milliseconds before the first retry, doubled for every further one
+//!   min_interval: 0      # milliseconds between two requests, for providers that limit the rate
+//!   proxy: ""            # otherwise curl takes https_proxy and friends from the environment
+//! ```
+//!
+//! A request that never reached the server (no dns, no connection) is always tried again.
+//! Timeouts, dropped connections and answers like `429` or `503` are only tried again if the request is
+//! `idempotent()`, posting a payment link twice would leave two of them behind.
+//!
+//! Passwords, API keys and tokens never go on curl's command line, where every user sees them in `ps`.
+//! `Request::secret()` hands them to curl as config on its stdin, or in a file only we can read.
+
+use std::ffi::{OsStr, OsString};
+use std::fmt;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use tempdir::TempDir;
+
+use crate::config::ConfigReader;
+use crate::error::{io_context, AsciiiError};
+
+/// When the last request started, for `min_interval`.
+static LAST_REQUEST: Mutex<Option<Instant>> = Mutex::new(None);
+
+/// Timeouts, retries and proxy of every request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Policy {
+    /// per attempt, `None` for none
+    pub timeout: Option<Duration>,
+    pub connect_timeout: Duration,
+    /// attempts after the first one
+    pub retries: u32,
+    /// before the first retry, doubled for every further one
+    pub backoff: Duration,
+    pub min_interval: Duration,
+    pub proxy: Option<String>,
+}
+
+impl Default for Policy {
+    fn default() -> Policy {
+        Policy {
+            timeout: Some(Duration::from_secs(60)),
+            connect_timeout: Duration::from_secs(10),
+            retries: 3,
+            backoff: Duration::from_millis(500),
+            min_interval: Duration::ZERO,
+            proxy: None,
+        }
+    }
+}
+
+impl Policy {
+    /// From `http` in `config`, missing keys keep their defaults.
+    pub fn from_config(config: &ConfigReader) -> Policy {
+        let default = Policy::default();
+        let number = |key: &str| config.get_f64(key).filter(|value| *value >= 0.0);
+        Policy {
+            timeout: match number("http/timeout") {
+                Some(0.0) => None,
+                Some(seconds) => Some(Duration::from_secs_f64(seconds)),
+                None => default.timeout,
+            },
+            connect_timeout: number("http/connect_timeout").map_or(default.connect_timeout, Duration::from_secs_f64),
+            retries: number("http/retries").map_or(default.retries, |retries| retries as u32),
+            backoff: number("http/backoff").map_or(default.backoff, |millis| Duration::from_millis(millis as u64)),
+            min_interval: number("http/min_interval").map_or(default.min_interval, |millis| Duration::from_millis(millis as u64)),
+            proxy: config.get_str_or("http/proxy").map(str::trim).filter(|proxy| !proxy.is_empty()).map(ToOwned::to_owned),
+        }
+    }
+
+    /// From `crate::CONFIG`.
+    pub fn configured() -> Policy {
+        Policy::from_config(&crate::CONFIG)
+    }
+
+    /// How long to wait before retry number `retry`, counted from 0.
+    pub fn delay(&self, retry: u32) -> Duration {
+        self.backoff.saturating_mul(1 << retry.min(16))
+    }
+
+    fn curl_args(&self) -> Vec<String> {
+        let mut args = vec!["--connect-timeout".to_owned(), self.connect_timeout.as_secs_f64().to_string()];
+        if let Some(timeout) = self.timeout {
+            args.extend(["--max-time".to_owned(), timeout.as_secs_f64().to_string()]);
+        }
+        if let Some(ref proxy) = self.proxy {
+            args.extend(["--proxy".to_owned(), proxy.clone()]);
+        }
+        args
+    }
+}
+
+/// Credentials for one request, see `Request::secret()`.
+#[derive(Clone, PartialEq, Eq)]
+pub enum Secret {
+    /// `user:password`, like `--user`
+    BasicAuth(String),
+    /// a whole header, like `Authorization: Bearer …`
+    Header(String),
+}
+
+impl Secret {
+    pub fn basic_auth(user: &str, password: &str) -> Secret {
+        Secret::BasicAuth(format!("{}:{}", user, password))
+    }
+
+    /// A line of a curl config file.
+    fn config_line(&self) -> String {
+        let (option, value) = match self {
+            Secret::BasicAuth(auth) => ("user", auth),
+            Secret::Header(header) => ("header", header),
+        };
+        let value = value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n").replace('\r', "\\r").replace('\t', "\\t");
+        format!("{} = \"{}\"\n", option, value)
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Secret::BasicAuth(_) => f.write_str("BasicAuth(…)"),
+            Secret::Header(_) => f.write_str("Header(…)"),
+        }
+    }
+}
+
+/// Writes `content` to `name` in `dir`, readable only by us.
+fn private_file(dir: &Path, name: &str, content: &[u8]) -> io::Result<PathBuf> {
+    let path = dir.join(name);
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    options.open(&path)?.write_all(content)?;
+    Ok(path)
+}
+
+/// One call of `curl`, repeated as `Policy` allows.
+///
+/// ```ignore
+/// let output = Request::new(lformat!("curl is needed to download exchange rates"))
+///     .args(["--fail", "--location", url])
+///     .idempotent()
+///     .output()?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct Request {
+    /// error message if there is no curl
+    needs_curl: String,
+    args: Vec<OsString>,
+    body: Option<Vec<u8>>,
+    upload: Option<Vec<u8>>,
+    secrets: Vec<Secret>,
+    idempotent: bool,
+    policy: Policy,
+}
+
+impl Request {
+    /// `needs_curl` is what goes wrong without `curl`, e.g. "curl is needed for webhooks".
+    pub fn new(needs_curl: String) -> Request {
+        Request { needs_curl, args: Vec::new(), body: None, upload: None, secrets: Vec::new(), idempotent: false, policy: Policy::configured() }
+    }
+
+    pub fn arg<S: AsRef<OsStr>>(mut self, arg: S) -> Request {
+        self.args.push(arg.as_ref().to_owned());
+        self
+    }
+
+    pub fn args<I, S>(mut self, args: I) -> Request
+        where I: IntoIterator<Item = S>, S: AsRef<OsStr>
+    {
+        self.args.extend(args.into_iter().map(|arg| arg.as_ref().to_owned()));
+        self
+    }
+
+    /// Written to curl's stdin, read with `--data-binary @-` or `--upload-file -`.
+    pub fn body(mut self, body: Vec<u8>) -> Request {
+        self.body = Some(body);
+        self
+    }
+
+    /// Uploaded from a temporary file with `--upload-file`, for bodies that can't go through stdin.
+    pub fn upload_file(mut self, upload: Vec<u8>) -> Request {
+        self.upload = Some(upload);
+        self
+    }
+
+    /// Given to curl as config, on stdin or, if that carries the `body()`, in a file only we can read.
+    pub fn secret(mut self, secret: Secret) -> Request {
+        self.secrets.push(secret);
+        self
+    }
+
+    /// `user:password`, instead of `--user`.
+    pub fn basic_auth(self, user: &str, password: &str) -> Request {
+        self.secret(Secret::basic_auth(user, password))
+    }
+
+    /// A header with a token, instead of `--header`.
+    pub fn secret_header(self, header: String) -> Request {
+        self.secret(Secret::Header(header))
+    }
+
+    /// Sending it twice does no harm, so it is tried again after timeouts and server errors, too.
+    pub fn idempotent(mut self) -> Request {
+        self.idempotent = true;
+        self
+    }
+
+    /// Overrides `http/timeout`, e.g. for large uploads.
+    pub fn timeout(mut self, timeout: Option<Duration>) -> Request {
+        self.policy.timeout = timeout;
+        self
+    }
+
+    pub fn policy(mut self, policy: Policy) -> Request {
+        self.policy = policy;
+        self
+    }
+
+    /// Runs curl until it succeeds, fails for good or runs out of retries, the last attempt is returned either way.
+    pub fn output(&self) -> Result<Output, AsciiiError> {
+        // the upload and secrets that don't fit on stdin, removed with it
+        let files = TempDir::new("asciii-curl")?;
+        let mut args = Vec::<OsString>::new();
+        if let Some(ref upload) = self.upload {
+            args.extend(["--upload-file".into(), private_file(files.path(), "upload", upload)?.into()]);
+        }
+        let config = self.secrets.iter().map(Secret::config_line).collect::<String>();
+        let stdin = match (&self.body, config.is_empty()) {
+            (Some(body), true) => Some(body.as_slice()),
+            (Some(body), false) => {
+                args.extend(["--config".into(), private_file(files.path(), "config", config.as_bytes())?.into()]);
+                Some(body.as_slice())
+            }
+            (None, true) => None,
+            (None, false) => {
+                args.extend(["--config".into(), "-".into()]);
+                Some(config.as_bytes())
+            }
+        };
+
+        let mut retry = 0;
+        loop {
+            wait_for_turn(self.policy.min_interval);
+            let output = self.attempt(&args, stdin)?;
+            match transient_failure(&output, self.idempotent) {
+                Some(reason) if retry < self.policy.retries => {
+                    let delay = self.policy.delay(retry);
+                    log::warn!("{}", lformat!("{}, trying again in {:.1}s", reason, delay.as_secs_f64()));
+                    thread::sleep(delay);
+                    retry += 1;
+                }
+                _ => return Ok(output),
+            }
+        }
+    }
+
+    fn attempt(&self, extra_args: &[OsString], stdin: Option<&[u8]>) -> Result<Output, AsciiiError> {
+        let mut command = Command::new("curl");
+        command.args(["--silent", "--show-error"])
+               .args(self.policy.curl_args())
+               .args(extra_args)
+               .args(&self.args);
+        let stdin = match stdin {
+            Some(stdin) => stdin,
+            None => return Ok(command.output().map_err(io_context(self.needs_curl.clone()))?),
+        };
+        let mut child = command.stdin(Stdio::piped())
+                               .stdout(Stdio::piped())
+                               .stderr(Stdio::piped())
+                               .spawn()
+                               .map_err(io_context(self.needs_curl.clone()))?;
+        if let Some(mut pipe) = child.stdin.take() {
+            pipe.write_all(stdin)?;
+        }
+        Ok(child.wait_with_output()?)
+    }
+}
+
+/// Waits until `min_interval` passed since the last request started.
+fn wait_for_turn(min_interval: Duration) {
+    if min_interval.is_zero() {
+        return;
+    }
+    let mut last = LAST_REQUEST.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    if let Some(elapsed) = last.map(|last| last.elapsed()) {
+        if elapsed < min_interval {
+            thread::sleep(min_interval - elapsed);
+        }
+    }
+    *last = Some(Instant::now());
+}
+
+/// Why an attempt is worth repeating, `None` if it succeeded or would fail again.
+fn transient_failure(output: &Output, idempotent: bool) -> Option<String> {
+    let stderr = String::from_utf8_lossy(&output.stderr).trim().to_owned();
+    if retry_worth(output.status.code(), &stderr, idempotent) {
+        Some(if stderr.is_empty() { lformat!("curl failed") } else { stderr })
+    } else {
+        None
+    }
+}
+
+fn retry_worth(code: Option<i32>, stderr: &str, idempotent: bool) -> bool {
+    match code {
+        // proxy or host not resolved, no connection: nothing was sent
+        Some(5) | Some(6) | Some(7) => true,
+        // timeout, empty answer, connection dropped while sending or receiving
+        Some(28) | Some(52) | Some(55) | Some(56) => idempotent,
+        // --fail and an error status
+        Some(22) => idempotent && http_status(stderr).is_some_and(|status| status == 408 || status == 429 || status >= 500),
+        _ => false,
+    }
+}
+
+/// The status in "The requested URL returned error: 503".
+fn http_status(stderr: &str) -> Option<u16> {
+    let (_, status) = stderr.rsplit_once("error: ")?;
+    status.get(..3)?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+
+    #[test]
+    fn only_safe_failures_are_repeated() {
+        assert!(retry_worth(Some(6), "curl: (6) Could not resolve host: api.stripe.com", false));
+        assert!(retry_worth(Some(7), "", false));
+        assert!(!retry_worth(Some(28), "curl: (28) Operation timed out", false));
+        assert!(retry_worth(Some(28), "curl: (28) Operation timed out", true));
+        assert!(retry_worth(Some(22), "curl: (22) The requested URL returned error: 503", true));
+        assert!(retry_worth(Some(22), "curl: (22) The requested URL returned error: 429 Too Many Requests", true));
+        assert!(!retry_worth(Some(22), "curl: (22) The requested URL returned error: 404", true));
+        assert!(!retry_worth(Some(22), "curl: (22) The requested URL returned error: 503", false));
+        assert!(!retry_worth(Some(0), "", true));
+        assert!(!retry_worth(None, "", true));
+    }
+
+    #[test]
+    fn secrets_are_quoted_for_curl() {
+        assert_eq!(Secret::basic_auth("sk_test", "").config_line(), "user = \"sk_test:\"\n");
+        assert_eq!(Secret::Header("X-Token: a\"b\\c".into()).config_line(), "header = \"X-Token: a\\\"b\\\\c\"\n");
+        assert_eq!(format!("{:?}", Secret::basic_auth("user", "hunter2")), "BasicAuth(…)");
+    }
+
+    #[test]
+    fn policy_from_config() {
+        let policy = Policy::default();
+        assert_eq!(policy.delay(0), Duration::from_millis(500));
+        assert_eq!(policy.delay(2), Duration::from_millis(2000));
+
+        let content = "http:\n  timeout: 0\n  retries: 1\n  backoff: 100\n  proxy: socks5h://localhost:1080\n";
+        let config = ConfigReader::from_content(Path::new("/nonexistent/.asciii.yml"), content).unwrap();
+        let policy = Policy::from_config(&config);
+        assert_eq!(policy.timeout, None);
+        assert_eq!(policy.retries, 1);
+        assert_eq!(policy.delay(1), Duration::from_millis(200));
+        assert_eq!(policy.connect_timeout, Duration::from_secs(10));
+        assert!(policy.curl_args().ends_with(&["--proxy".to_owned(), "socks5h://localhost:1080".to_owned()]));
+    }
+}
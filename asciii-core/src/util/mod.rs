@@ -0,0 +1,368 @@
+//! Utility functions that are needed all over the places.
+#![allow(dead_code)]
+use std::{env, io, fs};
+use std::io::Write;
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use std::process::{self, Command, ExitStatus};
+use chrono::NaiveTime;
+use bill::Currency;
+
+use crate::error::AsciiiError;
+
+pub mod yaml;
+pub mod dirs;
+pub mod paths;
+pub mod timing;
+pub mod holidays;
+pub mod currency;
+pub mod http;
+
+/// Sets up logging initially.
+///
+/// After calling this function the global logger will look for the environment variable `ASCIII_LOG`.
+pub fn setup_log() {
+    let log_var ="ASCIII_LOG";
+    let mut logger = env_logger::Builder::from_env(log_var);
+
+    if env::var(log_var).is_err() {
+        logger
+            .format_level(true)
+            .format_module_path(false)
+            .filter_level(log::LevelFilter::Info);
+    }
+
+    logger.init();
+}
+
+/// Freezes the program until for inspection
+pub fn freeze() {
+    io::stdin().read_line(&mut String::new()).unwrap();
+}
+
+/// Asks for confirmation
+pub fn really(msg: &str) -> bool {
+    println!("{} [y/N]", msg);
+    let mut answer = String::new();
+    if io::stdin().read_line(&mut answer).is_err(){ return false; }
+    ["yes", "y",
+    "j", "ja",
+    "oui", "si", "da"]
+        .contains(&answer.trim())
+}
+
+/// Asks to pick one of `options`, returns its index, `default` if the answer is empty or makes no sense.
+pub fn choose(msg: &str, options: &[&str], default: usize) -> usize {
+    println!("{}", msg);
+    for (index, option) in options.iter().enumerate() {
+        println!("{} {}) {}", if index == default { "*" } else { " " }, index + 1, option);
+    }
+    let mut answer = String::new();
+    if io::stdin().read_line(&mut answer).is_err(){ return default; }
+    answer.trim().parse::<usize>().ok()
+          .filter(|&n| n >= 1 && n <= options.len())
+          .map_or(default, |n| n - 1)
+}
+
+/// Asks for a line of text, keeps `current` if the answer is empty, `-` removes it.
+pub fn ask(msg: &str, current: Option<&str>) -> Option<String> {
+    match current {
+        Some(current) => println!("{} [{}]", msg, current),
+        None => println!("{}", msg),
+    }
+    let mut answer = String::new();
+    if io::stdin().read_line(&mut answer).is_err(){ return current.map(ToOwned::to_owned); }
+    match answer.trim() {
+        "" => current.map(ToOwned::to_owned),
+        "-" => None,
+        answer => Some(answer.to_owned()),
+    }
+}
+
+pub fn git_user_name() -> Option<String> {
+    git_config("user.name")
+}
+
+pub fn git_user_email() -> Option<String> {
+    git_config("user.email").filter(|email| !email.is_empty())
+}
+
+fn git_config(key: &str) -> Option<String> {
+    Command::new("git")
+        .args(&["config", key])
+        .output()
+        .map_err(|e| {
+            log::error!("failed to execute process: {}", e);
+            e
+        })
+        .ok()
+        .and_then(|out| String::from_utf8(out.stdout).ok())
+        .map(|s| s.trim().to_owned())
+}
+
+
+/// Shells out to print directory structure
+pub fn ls(path:&str){
+    println!("find {}", path); // TODO: implement in here with walkdir
+    let output = Command::new("find")
+        .arg(&path)
+        .output()
+        .unwrap_or_else(|e| { panic!("failed to execute process: {}", e) });
+    println!("{}", String::from_utf8(output.stdout).unwrap());
+}
+
+/// Opens `path` with the program the desktop would use, there is none in the browser.
+pub fn open_file<P: AsRef<OsStr>>(path: P) -> io::Result<()> {
+    #[cfg(not(target_arch = "wasm32"))]
+    return open::that(path);
+    #[cfg(target_arch = "wasm32")]
+    return Err(io::Error::new(io::ErrorKind::Unsupported, format!("can't open {:?}", path.as_ref())));
+}
+
+/// Opens the passed paths in the editor set int config.
+///
+/// This is by far the most important function of all utility functions.
+//TODO: use https://crates.io/crates/open (supports linux, windows, mac)
+pub fn pass_to_command<T:AsRef<OsStr>>(editor: Option<&str>, paths:&[T]) -> Result<(), AsciiiError> {
+
+    let paths = paths.iter()
+                      .map(|o|PathBuf::from(&o))
+                      .filter(|p|p.exists())
+                      .collect::<Vec<PathBuf>>();
+
+
+    if paths.is_empty() {
+        log::warn!("non of the provided paths could be found")
+    } else if let Some(editor) = editor {
+        if paths.len() < 5 || really (&format!("you are about to open {} files\n{:#?}\nAre you sure about this?", paths.len(), paths))
+        {
+            let editor_config = editor
+                .split_whitespace()
+                .collect::<Vec<&str>>();
+
+            let (editor_command,args) = editor_config.split_first().unwrap() ;
+            log::info!("launching {:?} with {:?} and {:?}",
+                  editor_command,
+                  args.join(" "),
+                  paths);
+
+            Command::new(editor_command)
+                .args(args)
+                .args(&paths)
+                .status()
+                .map_err(crate::error::io_context("failed to execute process".into()))?;
+
+        }
+    } else {
+        for path in paths{
+            open_file(path).unwrap();
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes `content` to `path` so that an interrupted run never leaves a truncated file behind.
+///
+/// The content goes to a hidden file next to `path` first, which then replaces `path`.
+/// The directory is synced as well, so the new file survives a crash.
+pub fn atomic_write<P: AsRef<Path>, C: AsRef<[u8]>>(path: P, content: C) -> io::Result<()> {
+    let path = path.as_ref();
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new(".")
+    };
+    let name = path.file_name()
+                   .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, format!("{} is no file", path.display())))?;
+    let temp = dir.join(format!(".{}.{}.tmp", name.to_string_lossy(), process::id()));
+
+    let written = (|| {
+        let mut file = fs::File::create(&temp)?;
+        file.write_all(content.as_ref())?;
+        if let Ok(meta) = fs::metadata(path) {
+            file.set_permissions(meta.permissions())?;
+        }
+        file.sync_all()?;
+        fs::rename(&temp, path)?;
+        // directories can only be opened like this on unix
+        #[cfg(unix)] fs::File::open(dir)?.sync_all()?;
+        Ok(())
+    })();
+
+    if written.is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+    written
+}
+
+/// Number of single character edits that turn `a` into `b`, ignoring case.
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let a = a.to_lowercase().chars().collect::<Vec<_>>();
+    let b = b.to_lowercase().chars().collect::<Vec<_>>();
+    let mut row = (0..=b.len()).collect::<Vec<_>>();
+    for (i, ca) in a.iter().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substituted = diagonal + usize::from(ca != cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substituted.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
+/// The candidate `word` was most likely meant to be, if any is close enough.
+pub fn closest_match<'a>(word: &str, candidates: impl IntoIterator<Item = &'a str>) -> Option<&'a str> {
+    candidates.into_iter()
+              .map(|candidate| (edit_distance(word, candidate), candidate))
+              .filter(|(distance, candidate)| *distance <= (candidate.chars().count() / 3).max(1))
+              .min_by_key(|(distance, _)| *distance)
+              .map(|(_, candidate)| candidate)
+}
+
+/// Escapes everything but unreserved characters (RFC 3986), for urls and `mailto:` links.
+pub fn percent_encode(text: &str) -> String {
+    text.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// Deletes the file if the passed in closure returns `true`
+pub fn delete_file_if<F,P:AsRef<OsStr>>(path:P, confirmed:F) -> io::Result<()>
+    where F: Fn()->bool
+{
+    let path = PathBuf::from(&path);
+    if confirmed(){
+        log::debug!("$ rm {}", path.display());
+        fs::remove_file(&path)
+    } else {Ok(())}
+}
+
+/// takes a path that could be relative or contains a `~` and turn it into a path that exists
+pub fn get_valid_path<T:AsRef<OsStr>>(p:T) -> Option<PathBuf>{
+    let path = paths::absolute(Path::new(&p));
+
+    if path.exists() {
+        Some(path)
+    } else {None}
+}
+
+/// Exits with the exit status of a child process.
+pub fn exit(status:ExitStatus) -> !{
+    process::exit(status.code().unwrap_or(1));
+}
+
+/// One place to decide how to display currency, see `currency::CurrencyUnit`
+pub fn currency_to_string(currency:&Currency) -> String {
+    currency::CurrencyUnit::configured().format(currency)
+}
+
+/// Creates a currency from an `f64`
+///
+/// This is functionality which was explicitly left out of the `Claude` crate.
+pub fn to_currency(f: f64) -> Currency {
+    Currency{ symbol: currency::CurrencyUnit::configured().symbol_char(), value: (f * 1000.0) as i64} / 10
+}
+
+/// Changes the extension of a given `Path`
+pub fn to_local_file(file: &Path, ext: &str) -> PathBuf {
+    let mut _tmpfile = file.to_owned();
+    _tmpfile.set_extension(ext);
+    Path::new(_tmpfile.file_name().unwrap()).to_owned()
+}
+
+/// Creates a `chrono::NaiveTime` from a string that looks like `23:59:58` or only `12:05`.
+pub fn naive_time_from_str(string: &str) -> Option<NaiveTime> {
+    let t:Vec<u32> = string
+        .splitn(2, |p| p == '.' || p == ':')
+        .map(|s|s.parse().unwrap_or(0))
+        .collect();
+
+    if let (Some(h),m) = (t.first(), t.get(1).unwrap_or(&0)) {
+        if *h < 24 && *m < 60 {
+            return Some(NaiveTime::from_hms(*h,*m,0))
+        }
+    }
+
+    None
+}
+
+/// Lines that differ between `old` and `new`, prefixed with `-` and `+` like a diff.
+///
+/// Each block of changes is introduced by `@@ <line> @@`, counting lines of `old` from 1.
+pub fn line_diff(old: &str, new: &str) -> String {
+    let old = old.lines().collect::<Vec<_>>();
+    let new = new.lines().collect::<Vec<_>>();
+
+    // longest common subsequence, from the back
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] { lcs[i + 1][j + 1] + 1 }
+                        else { lcs[i + 1][j].max(lcs[i][j + 1]) };
+        }
+    }
+
+    let mut diff = String::new();
+    let (mut i, mut j, mut in_hunk) = (0, 0, false);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            i += 1;
+            j += 1;
+            in_hunk = false;
+            continue;
+        }
+        if !in_hunk {
+            diff.push_str(&format!("@@ {} @@\n", i + 1));
+            in_hunk = true;
+        }
+        if i < old.len() && (j == new.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            diff.push_str(&format!("-{}\n", old[i]));
+            i += 1;
+        } else {
+            diff.push_str(&format!("+{}\n", new[j]));
+            j += 1;
+        }
+    }
+    diff
+}
+
+#[test]
+fn test_line_diff() {
+    assert_eq!(line_diff("a\nb\nc\n", "a\nb\nc\n"), "");
+    assert_eq!(line_diff("a\nb\nc\n", "a\nB\nc\nd\n"), "@@ 2 @@\n-b\n+B\n@@ 4 @@\n+d\n");
+}
+
+#[test]
+fn test_naive_time_from_str() {
+    assert_eq!(Some(NaiveTime::from_hms(9,15,0)), naive_time_from_str("9.15"));
+    assert_eq!(Some(NaiveTime::from_hms(9,0,0)),  naive_time_from_str("9."));
+    assert_eq!(Some(NaiveTime::from_hms(9,0,0)),  naive_time_from_str("9"));
+    assert_eq!(Some(NaiveTime::from_hms(23,0,0)), naive_time_from_str("23.0"));
+    assert_eq!(Some(NaiveTime::from_hms(23,59,0)), naive_time_from_str("23.59"));
+    assert_eq!(None, naive_time_from_str("24.0"));
+    assert_eq!(None, naive_time_from_str("25.0"));
+    assert_eq!(None, naive_time_from_str("0.60"));
+
+    assert_eq!(Some(NaiveTime::from_hms(9,15,0)), naive_time_from_str("9:15"));
+    assert_eq!(Some(NaiveTime::from_hms(9,0,0)),  naive_time_from_str("9:"));
+    assert_eq!(Some(NaiveTime::from_hms(9,0,0)),  naive_time_from_str("9"));
+    assert_eq!(Some(NaiveTime::from_hms(23,0,0)), naive_time_from_str("23:0"));
+}
+
+
+#[test]
+fn atomic_writes() {
+    let dir = tempdir::TempDir::new("asciii-atomic").unwrap();
+    let file = dir.path().join("party.yml");
+    atomic_write(&file, "a: 1\n").unwrap();
+    atomic_write(&file, "a: 2\n").unwrap();
+    assert_eq!(fs::read_to_string(&file).unwrap(), "a: 2\n");
+    // nothing is left behind
+    assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+    assert!(atomic_write(dir.path().join("missing/party.yml"), "a: 3\n").is_err());
+}
@@ -0,0 +1,133 @@
+//! Paths from the config and the commandline: `~`, `~user` and relative paths.
+//!
+//! Only a leading `~` means a home directory, `C:\PROGRA~1` and `notes~` stay what they are.
+//! Drive letters (`C:\`, `C:/`) and UNC paths (`\\server\share`) count as absolute on every platform,
+//! so a config written on Windows doesn't end up below the current directory elsewhere.
+
+use std::env;
+use std::path::{Path, PathBuf};
+
+#[cfg(not(target_arch = "wasm32"))]
+use dirs::home_dir;
+#[cfg(target_arch = "wasm32")]
+use super::dirs::home_dir;
+
+fn is_separator(c: char) -> bool {
+    c == '/' || c == '\\'
+}
+
+/// Replaces a leading `~` or `~user` by the home directory, anything else is left alone.
+pub fn expand_tilde(path: &Path) -> PathBuf {
+    expand_tilde_with(path, home_dir().as_deref(), user_home)
+}
+
+/// Like `expand_tilde()`, with the home directories given.
+pub fn expand_tilde_with(path: &Path, home: Option<&Path>, user_home: impl Fn(&str) -> Option<PathBuf>) -> PathBuf {
+    let text = match path.to_str() {
+        Some(text) => text,
+        None => return path.to_owned(),
+    };
+    let rest = match text.strip_prefix('~') {
+        Some(rest) => rest,
+        None => return path.to_owned(),
+    };
+
+    let (user, rest) = rest.split_at(rest.find(is_separator).unwrap_or(rest.len()));
+    let home = if user.is_empty() { home.map(ToOwned::to_owned) } else { user_home(user) };
+    match home {
+        Some(home) => {
+            let rest = rest.trim_start_matches(is_separator);
+            if rest.is_empty() { home } else { home.join(rest) }
+        }
+        None => {
+            log::warn!("can't find the home directory of {:?}", text);
+            path.to_owned()
+        }
+    }
+}
+
+/// Home directory of `user`, from `/etc/passwd`.
+#[cfg(all(unix, not(target_arch = "wasm32")))]
+pub fn user_home(user: &str) -> Option<PathBuf> {
+    std::fs::read_to_string("/etc/passwd").ok()?
+        .lines()
+        .map(|line| line.split(':').collect::<Vec<_>>())
+        .find(|fields| fields.len() > 5 && fields[0] == user)
+        .map(|fields| PathBuf::from(fields[5]))
+}
+
+/// Home directory of `user`, next to the own one (`C:\Users\<user>`).
+#[cfg(windows)]
+pub fn user_home(user: &str) -> Option<PathBuf> {
+    home_dir()?.parent()
+               .map(|users| users.join(user))
+               .filter(|home| home.is_dir())
+}
+
+/// There are no other users.
+#[cfg(not(any(all(unix, not(target_arch = "wasm32")), windows)))]
+pub fn user_home(_user: &str) -> Option<PathBuf> {
+    None
+}
+
+/// `C:\…`, `C:/…` or `\\server\share`, whatever platform this runs on.
+pub fn has_windows_root(path: &Path) -> bool {
+    let text = path.to_string_lossy();
+    let mut chars = text.chars();
+    match (chars.next(), chars.next(), chars.next()) {
+        (Some(drive), Some(':'), Some(sep)) => drive.is_ascii_alphabetic() && is_separator(sep),
+        (Some('\\'), Some('\\'), _) => true,
+        _ => false,
+    }
+}
+
+/// Absolute as it is, with the leading `~` expanded, or below the current directory.
+pub fn absolute(path: &Path) -> PathBuf {
+    let path = expand_tilde(path);
+    if path.is_absolute() || has_windows_root(&path) {
+        return path;
+    }
+    let current = if cfg!(target_arch = "wasm32") {
+        env::var("PWD").map(PathBuf::from).ok()
+    } else {
+        env::current_dir().ok()
+    };
+    match current {
+        Some(dir) => dir.join(path),
+        None => path,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expand(path: &str) -> PathBuf {
+        let users = |user: &str| if user == "alice" { Some(PathBuf::from("/home/alice")) } else { None };
+        expand_tilde_with(Path::new(path), Some(Path::new("/home/me")), users)
+    }
+
+    #[test]
+    fn only_leading_tildes() {
+        assert_eq!(expand("~"), PathBuf::from("/home/me"));
+        assert_eq!(expand("~/"), PathBuf::from("/home/me"));
+        assert_eq!(expand("~/.asciii_projects"), PathBuf::from("/home/me/.asciii_projects"));
+        assert_eq!(expand("~alice/projects"), PathBuf::from("/home/alice/projects"));
+        assert_eq!(expand("~bob/projects"), PathBuf::from("~bob/projects"));
+        assert_eq!(expand("backup~/projects"), PathBuf::from("backup~/projects"));
+        assert_eq!(expand(r"C:\PROGRA~1\asciii"), PathBuf::from(r"C:\PROGRA~1\asciii"));
+        assert_eq!(expand_tilde_with(Path::new("~/x"), None, |_| None), PathBuf::from("~/x"));
+    }
+
+    #[test]
+    fn windows_roots() {
+        assert!(has_windows_root(Path::new(r"C:\Users\me")));
+        assert!(has_windows_root(Path::new("d:/projects")));
+        assert!(has_windows_root(Path::new(r"\\server\share\projects")));
+        assert!(!has_windows_root(Path::new("C:relative")));
+        assert!(!has_windows_root(Path::new("projects")));
+
+        assert_eq!(absolute(Path::new(r"\\server\share")), PathBuf::from(r"\\server\share"));
+        assert!(absolute(Path::new("projects")).is_absolute());
+    }
+}
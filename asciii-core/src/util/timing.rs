@@ -0,0 +1,119 @@
+//! A small profiler for `asciii --timing`.
+//!
+//! Code that may be slow wraps itself in a named phase, see `start()`.
+//! Phases that run several times add up, opening 500 projects shows up as one line that ran 500 times.
+//! Work done on several threads at once is summed up as well, so phases can take longer than the whole run.
+//! Phases may also contain each other, reading a project includes parsing it if anything was looked up.
+//! Nothing is recorded unless `enable()` was called.
+
+use std::fmt::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static STARTED: OnceLock<Instant> = OnceLock::new();
+static PHASES: Mutex<Vec<Phase>> = Mutex::new(Vec::new());
+
+/// How long one phase took, summed up over every time it ran.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Phase {
+    pub name: &'static str,
+    pub total: Duration,
+    pub count: usize,
+}
+
+/// Starts recording, the total time is counted from here.
+pub fn enable() {
+    STARTED.get_or_init(Instant::now);
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Measures until it is dropped.
+#[must_use = "the phase ends when the timer is dropped"]
+pub struct Timer {
+    phase: &'static str,
+    start: Option<Instant>,
+}
+
+impl Drop for Timer {
+    fn drop(&mut self) {
+        if let Some(start) = self.start {
+            record(self.phase, start.elapsed());
+        }
+    }
+}
+
+/// Measures `phase` until the returned `Timer` goes out of scope.
+pub fn start(phase: &'static str) -> Timer {
+    Timer { phase, start: if is_enabled() { Some(Instant::now()) } else { None } }
+}
+
+/// Adds `duration` to `phase`.
+pub fn record(phase: &'static str, duration: Duration) {
+    if !is_enabled() {
+        return;
+    }
+    let mut phases = PHASES.lock().unwrap_or_else(|e| e.into_inner());
+    match phases.iter_mut().find(|p| p.name == phase) {
+        Some(p) => {
+            p.total += duration;
+            p.count += 1;
+        },
+        None => phases.push(Phase { name: phase, total: duration, count: 1 })
+    }
+}
+
+/// Every phase so far, in the order they first ran.
+pub fn phases() -> Vec<Phase> {
+    PHASES.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+/// One line per phase and the total since `enable()`.
+pub fn report() -> String {
+    let phases = phases();
+    let width = phases.iter().map(|p| p.name.len()).max().unwrap_or(0).max(5);
+    let mut out = String::new();
+    for phase in &phases {
+        let _ = write!(out, "{:width$}  {:>9.2} ms", phase.name, millis(phase.total), width = width);
+        if phase.count > 1 {
+            let _ = write!(out, "  ({}×)", phase.count);
+        }
+        out.push('\n');
+    }
+    if let Some(started) = STARTED.get() {
+        let _ = writeln!(out, "{:width$}  {:>9.2} ms", lformat!("total"), millis(started.elapsed()), width = width);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adds_up_phases() {
+        {
+            let _timer = start("before");
+        }
+        assert!(phases().iter().all(|p| p.name != "before"));
+
+        enable();
+        for _ in 0..3 {
+            let _timer = start("adds up");
+        }
+        record("adds up", Duration::from_millis(5));
+        let phase = phases().into_iter().find(|p| p.name == "adds up").unwrap();
+        assert_eq!(phase.count, 4);
+        assert!(phase.total >= Duration::from_millis(5));
+        assert!(report().contains("(4×)"));
+    }
+}
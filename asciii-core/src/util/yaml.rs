@@ -0,0 +1,810 @@
+//! Yaml Utility functions.
+//!
+//! Here are some missing batteries form the `yaml-rust` crate.
+//! The cool thing about this is the simple path like access to nested elements.
+//! if the yaml looks like this:
+//!
+//! ```yaml
+//! programmer:
+//!   name: Hendrik
+//!   looks: good
+//!   languages:
+//!     * rust
+//!     * ruby
+//!     * python
+//! ```
+//!
+//! you can access "ruby" like this: `get_string("programmer/languages/1")`.
+//! Leading `/` will not be regarded.
+
+#![allow(dead_code)]
+
+use crate::error::AsciiiError;
+use std::fs;
+use std::path::Path;
+
+pub use yaml_rust::Yaml;
+use yaml_rust::YamlLoader;
+use yaml_rust::yaml::Hash as YamlHash;
+use chrono::prelude::*;
+
+/// Wrapper that opens and parses a `.yml` file.
+pub fn open(path: &Path) -> Result<Yaml, AsciiiError> {
+
+    let file_content = fs::read_to_string(&path)?;
+    parse( &file_content )
+}
+
+/// Ruby like API to yaml-rust.
+pub fn parse(file_content: &str) -> Result<Yaml, AsciiiError> {
+    Ok(
+        YamlLoader::load_from_str(file_content)?
+        .get(0)
+        .map(ToOwned::to_owned)
+        .unwrap_or_else(||Yaml::from_str("[]"))
+      )
+}
+
+/// Interprets `"25.12.2016"` as date.
+pub fn parse_dmy_date(date_str:&str) -> Option<Date<Utc>>{
+    let date = date_str.split('.')
+                       .map(|f|f.parse().unwrap_or(0))
+                       .collect::<Vec<i32>>();
+    if date.len() >=2 && date[0] > 0 && date[2] > 1900 {
+        // XXX: this neglects the old "01-05.12.2015" format
+        Utc.ymd_opt(date[2], date[1] as u32, date[0] as u32)
+        .single()
+    } else {
+        None
+    }
+}
+
+/// Interprets `"24-25.12.2016"` as date.
+///
+/// Takes care of the old, deprecated, stupid, `dd-dd.mm.yyyy` format, what was I thinking?
+/// This is not used in the current format.
+pub fn parse_dmy_date_range(date_str:&str) -> Option<Date<Utc>>{
+    let date = date_str.split('.')
+        .map(|s|s.split('-').next().unwrap_or("0"))
+        .map(|f|f.parse().unwrap_or(0))
+        .collect::<Vec<i32>>();
+    if date[0] > 0 {
+        return Some(Utc.ymd(date[2], date[1] as u32, date[0] as u32))
+    }
+    None
+}
+
+
+/// Gets `Some(Yaml::Hash)` or `None`.
+//pub fn get_hash<'a>(yaml:&'a Yaml, key:&str) -> Option<&'a BTreeMap<Yaml,Yaml>> {
+pub fn get_hash<'a>(yaml:&'a Yaml, key:&str) -> Option<&'a YamlHash> {
+    get(yaml,key).and_then(Yaml::as_hash)
+}
+
+/// Gets a `Bool` value.
+///
+/// **Careful** this is a bit sweeter then ordinary `YAML1.2`,
+/// this will interpret `"yes"` and `"no"` as booleans, similar to `YAML1.1`.
+/// Actually it will interpret any string but `"yes"` als `false`.
+pub fn get_bool(yaml:&Yaml, key:&str) -> Option<bool> {
+    get(yaml,key)
+        .and_then(|y| y
+                  .as_bool()
+                  // allowing it to be a str: "yes" or "no"
+                  .or_else(|| y.as_str()
+                       .map( |yes_or_no| yes_or_no.to_lowercase().as_str() == "yes")))
+}
+
+/// Gets a `Float` value.
+///
+/// Also takes a `Yaml::I64` and reinterprets it.
+pub fn get_f64(yaml:&Yaml, key:&str) -> Option<f64> {
+    get(yaml,key).and_then(|y| y.as_f64().or_else(|| y.as_i64().map(|y|y as f64)))
+}
+
+/// Gets an `Int` value.
+///
+/// Same mentality as `yaml_rust`, only returns `Some`, if it's a `Yaml::Int`.
+pub fn get_int(yaml:&Yaml, key:&str) -> Option<i64> {
+    get(yaml,key).and_then(Yaml::as_i64)
+}
+
+/// Gets a `&str` value.
+///
+/// Same mentality as `yaml_rust`, only returns `Some`, if it's a `Yaml::String`.
+pub fn get_str<'a>(yaml:&'a Yaml, key:&str) -> Option<&'a str> {
+    get(yaml,key).and_then(Yaml::as_str)
+}
+
+/// same as `get_str()`, but owned.
+pub fn get_string(yaml:&Yaml, key:&str) -> Option<String> {
+    get_str(yaml,key).map(ToOwned::to_owned)
+}
+
+/// Gets anything **as** `String`.
+pub fn get_to_string(yaml:&Yaml, key:&str) -> Option<String> {
+    use self::Yaml::*;
+    get(yaml,key).and_then(|i| match *i {
+        Real(ref inner) | String(ref inner) => Some(inner.to_owned()),
+        Boolean(ref inner) => Some(inner.to_string()),
+        Integer(ref inner) => Some(inner.to_string()),
+        Hash(ref inner) => Some(format!("{:?}", inner)),
+        Array(ref inner) => Some(format!("{:?}", inner)),
+        _ => None
+    })
+}
+
+/// Gets a Date in `dd.mm.YYYY` format.
+#[deprecated]
+pub fn get_dmy(yaml:&Yaml, key:&str) -> Option<Date<Utc>> {
+    get(yaml,key).and_then(Yaml::as_str).and_then(parse_dmy_date)
+}
+
+/// Wrapper around `get_path()`.
+///
+/// Splits path string
+/// and replaces `Yaml::Null` and `Yaml::BadValue`.
+//#[deprecated(note="use `ProvidesData` instead")]
+pub fn get<'a>(yaml:&'a Yaml, key:&str) -> Option<&'a Yaml>{
+    let path = key.split(|c| c == '/' || c == '.')
+                  .filter(|k|!k.is_empty())
+                  .collect::<Vec<&str>>();
+    match get_path(yaml, &path) {
+        Some(&(Yaml::Null | Yaml::BadValue)) => None,
+        content => content
+    }
+}
+
+/// Returns content at `path` in the yaml document.
+//#[deprecated(note="This is old style spec, please use the `YamlProvider` trait")]
+fn get_path<'a>(yaml:&'a Yaml, path:&[&str]) -> Option<&'a Yaml>{
+    if let Some((&key, remainder)) = path.split_first(){
+
+        return match *yaml{
+            Yaml::Hash(ref hash) =>
+            {
+                if remainder.is_empty(){
+                    hash.get(&Yaml::String(key.to_owned()))
+                } else {
+                    hash.get(&Yaml::String(key.to_owned()))
+                        .and_then(|c| get_path(c, remainder))
+                }
+            },
+
+            Yaml::Array(ref vec) =>
+            {
+                if let Ok(index) = key.parse::<usize>() {
+                    if remainder.is_empty(){
+                        vec.get(index)
+                    } else {
+                        vec.get(index).and_then(|c| get_path(c, remainder))
+                    }
+                } else { None }
+            },
+            _ => None
+        }
+
+    }
+    None
+}
+
+/// Paths and values of all scalars in a mapping, like `("invoice/number", "42")`.
+///
+/// Returns `None` if the document contains anything else than mappings and scalars.
+pub fn leaves(yaml: &Yaml) -> Option<Vec<(String, String)>> {
+    fn collect(yaml: &Yaml, prefix: &str, leaves: &mut Vec<(String, String)>) -> Option<()> {
+        match *yaml {
+            Yaml::Hash(ref hash) => {
+                for (key, value) in hash {
+                    let key = key.as_str()?;
+                    let path = if prefix.is_empty() { key.to_owned() } else { format!("{}/{}", prefix, key) };
+                    collect(value, &path, leaves)?;
+                }
+            },
+            Yaml::String(ref s) => leaves.push((prefix.to_owned(), s.to_owned())),
+            Yaml::Real(ref r)   => leaves.push((prefix.to_owned(), r.to_owned())),
+            Yaml::Integer(i)    => leaves.push((prefix.to_owned(), i.to_string())),
+            Yaml::Boolean(b)    => leaves.push((prefix.to_owned(), b.to_string())),
+            _ => return None
+        }
+        Some(())
+    }
+
+    let mut leaves = Vec::new();
+    if let Yaml::Hash(_) = *yaml {
+        collect(yaml, "", &mut leaves)?;
+        Some(leaves)
+    } else {
+        None
+    }
+}
+
+fn indentation(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+/// Lines that are neither blank nor only a comment.
+fn is_content(line: &str) -> bool {
+    let trimmed = line.trim();
+    !trimmed.is_empty() && !trimmed.starts_with('#')
+}
+
+/// Splits `key: rest` into key and rest, ignores list items and anything that is not a plain key.
+fn split_key(line: &str) -> Option<(&str, &str)> {
+    let trimmed = line.trim_start();
+    if trimmed.starts_with('-') || trimmed.starts_with('#') {
+        return None;
+    }
+    let colon = trimmed.find(':')?;
+    let (key, rest) = trimmed.split_at(colon);
+    let rest = &rest[1..];
+    if rest.is_empty() || rest.starts_with(' ') || rest.starts_with('\t') {
+        Some((key.trim_end(), rest))
+    } else {
+        None
+    }
+}
+
+/// Returns the comment at the end of a value, including the leading whitespace.
+fn trailing_comment(rest: &str) -> &str {
+    rest.find(" #").map(|i| &rest[i..]).unwrap_or("")
+}
+
+/// Formats `key: value`, values with newlines become literal blocks.
+fn key_value_lines(indent: usize, key: &str, padding: &str, value: &str, comment: &str) -> Vec<String> {
+    let prefix = " ".repeat(indent);
+    if value.contains('\n') {
+        let mut lines = vec![format!("{}{}: |{}", prefix, key, comment)];
+        lines.extend(value.lines().map(|l| format!("{}  {}", prefix, l)));
+        lines
+    } else {
+        vec![format!("{}{}:{}{}{}", prefix, key, padding, value, comment)]
+    }
+}
+
+/// Sets `key` to `value` in the text of a yaml document, without touching anything else.
+///
+/// Unlike dumping a parsed document this keeps comments, anchors and formatting intact.
+/// `value` has to be a valid yaml scalar, values containing newlines are written as literal blocks.
+/// Missing keys are appended to their parent mapping.
+/// Returns `None` if the path runs through a list, a flow mapping or a scalar.
+pub fn set_in_text(content: &str, key: &str, value: &str) -> Option<String> {
+    let path = key.split(['/', '.'])
+                  .filter(|k| !k.is_empty())
+                  .collect::<Vec<&str>>();
+    if path.is_empty() {
+        return None;
+    }
+
+    let mut lines = content.lines().map(ToOwned::to_owned).collect::<Vec<String>>();
+    let (mut start, mut end, mut indent) = (0, lines.len(), 0);
+
+    for (depth, segment) in path.iter().enumerate() {
+        let found = (start..end).find(|&i| indentation(&lines[i]) == indent
+                                          && split_key(&lines[i]).map(|(k, _)| k) == Some(segment));
+
+        let index = match found {
+            Some(index) => index,
+            None => {
+                // append the remaining path to the end of the current mapping
+                let insert_at = (start..end).rev()
+                                            .find(|&i| is_content(&lines[i]))
+                                            .map_or(start, |i| i + 1);
+                let mut new_lines = Vec::new();
+                for (level, segment) in path[depth..].iter().enumerate() {
+                    if depth + level == path.len() - 1 {
+                        new_lines.extend(key_value_lines(indent + 2 * level, segment, " ", value, ""));
+                    } else {
+                        new_lines.push(format!("{}{}:", " ".repeat(indent + 2 * level), segment));
+                    }
+                }
+                lines.splice(insert_at..insert_at, new_lines);
+                break;
+            }
+        };
+
+        // children are more indented, list items may sit on the same level
+        let block_end = (index + 1..end)
+            .filter(|&i| is_content(&lines[i]))
+            .take_while(|&i| indentation(&lines[i]) > indent
+                             || (indentation(&lines[i]) == indent && lines[i].trim_start().starts_with('-')))
+            .last()
+            .map_or(index + 1, |i| i + 1);
+
+        let (_, rest) = split_key(&lines[index])?;
+        let rest = rest.to_owned();
+        let comment = trailing_comment(&rest);
+        let inline_value = rest[..rest.len() - comment.len()].trim();
+
+        if depth == path.len() - 1 {
+            let padding = &rest[..rest.len() - rest.trim_start().len()];
+            let padding = if padding.is_empty() || inline_value.is_empty() { " " } else { padding };
+            let new_lines = key_value_lines(indent, segment, padding, value, comment);
+            lines.splice(index..block_end, new_lines);
+            break;
+        }
+
+        if !inline_value.is_empty() {
+            return None;
+        }
+
+        let first_child = (index + 1..block_end).find(|&i| is_content(&lines[i]));
+        if first_child.is_some_and(|i| lines[i].trim_start().starts_with('-')) {
+            return None;
+        }
+        indent = first_child.map_or(indent + 2, |i| indentation(&lines[i]));
+        start = index + 1;
+        end = block_end;
+    }
+
+    let mut result = lines.join("\n");
+    if content.ends_with('\n') {
+        result.push('\n');
+    }
+    Some(result)
+}
+
+/// Index of the line that holds `key`, or of its closest parent if `key` itself is missing.
+///
+/// Returns `None` if not even the first segment of the path exists.
+pub fn line_of(content: &str, key: &str) -> Option<usize> {
+    let lines = content.lines().collect::<Vec<&str>>();
+    let (mut start, mut end, mut indent) = (0, lines.len(), 0);
+    let mut line = None;
+
+    for segment in key.split(['/', '.']).filter(|k| !k.is_empty()) {
+        let index = match (start..end).find(|&i| indentation(lines[i]) == indent
+                                                && split_key(lines[i]).map(|(k, _)| k) == Some(segment)) {
+            Some(index) => index,
+            None => break
+        };
+        line = Some(index);
+
+        let block_end = (index + 1..end)
+            .filter(|&i| is_content(lines[i]))
+            .take_while(|&i| indentation(lines[i]) > indent)
+            .last()
+            .map_or(index + 1, |i| i + 1);
+
+        match (index + 1..block_end).find(|&i| is_content(lines[i])) {
+            Some(child) => {
+                indent = indentation(lines[child]);
+                start = child;
+                end = block_end;
+            }
+            None => break
+        }
+    }
+    line
+}
+
+/// Range of lines holding `key` and everything nested below it.
+///
+/// Returns `None` if `key` is not in the document or the path runs through a list.
+fn find_block(lines: &[&str], key: &str) -> Option<(usize, usize)> {
+    let (mut start, mut end, mut indent) = (0, lines.len(), 0);
+    let mut block = None;
+
+    for segment in key.split(['/', '.']).filter(|k| !k.is_empty()) {
+        if let Some((index, block_end)) = block {
+            let first_child: usize = (index + 1..block_end).find(|&i| is_content(lines[i]))?;
+            if lines[first_child].trim_start().starts_with('-') {
+                return None;
+            }
+            indent = indentation(lines[first_child]);
+            start = first_child;
+            end = block_end;
+        }
+
+        let index = (start..end).find(|&i| indentation(lines[i]) == indent
+                                          && split_key(lines[i]).map(|(k, _)| k) == Some(segment))?;
+        let block_end = (index + 1..end)
+            .filter(|&i| is_content(lines[i]))
+            .take_while(|&i| indentation(lines[i]) > indent
+                             || (indentation(lines[i]) == indent && lines[i].trim_start().starts_with('-')))
+            .last()
+            .map_or(index + 1, |i| i + 1);
+        block = Some((index, block_end));
+    }
+    block
+}
+
+fn join_like(lines: &[&str], content: &str) -> String {
+    let mut result = lines.join("\n");
+    if content.ends_with('\n') {
+        result.push('\n');
+    }
+    result
+}
+
+/// Removes `key` and everything nested below it from the text of a yaml document.
+///
+/// Like `set_in_text()` this leaves the rest of the document untouched.
+/// Returns `None` if `key` is not in the document or the path runs through a list.
+pub fn remove_in_text(content: &str, key: &str) -> Option<String> {
+    let mut lines = content.lines().collect::<Vec<&str>>();
+    let (index, block_end) = find_block(&lines, key)?;
+    lines.drain(index..block_end);
+    Some(join_like(&lines, content))
+}
+
+/// The lines of `key` and everything nested below it, exactly as they are in the text.
+pub fn block_in_text(content: &str, key: &str) -> Option<String> {
+    let lines = content.lines().collect::<Vec<&str>>();
+    let (index, block_end) = find_block(&lines, key)?;
+    Some(lines[index..block_end].join("\n"))
+}
+
+/// Inserts a `block` taken from `block_in_text()` at the end of the mapping that should contain `key`.
+///
+/// The block is indented to fit, top level blocks go before a closing `...`.
+/// Returns `None` if the parent of `key` is missing or `key` is already there.
+pub fn insert_block_in_text(content: &str, key: &str, block: &str) -> Option<String> {
+    let mut lines = content.lines().collect::<Vec<&str>>();
+    if find_block(&lines, key).is_some() {
+        return None;
+    }
+
+    let parent = key.rsplit_once(['/', '.']).map(|(parent, _)| parent);
+    let (insert_at, indent) = match parent {
+        Some(parent) => {
+            let (index, block_end) = find_block(&lines, parent)?;
+            let indent = (index + 1..block_end).find(|&i| is_content(lines[i]))
+                                               .map_or(indentation(lines[index]) + 2, |i| indentation(lines[i]));
+            (block_end, indent)
+        },
+        None => {
+            let end = lines.iter().rposition(|l| l.trim() == "...")
+                           .filter(|&i| lines[i + 1..].iter().all(|l| !is_content(l)))
+                           .unwrap_or(lines.len());
+            let insert_at = (0..end).rev().find(|&i| is_content(lines[i])).map_or(0, |i| i + 1);
+            (insert_at, 0)
+        }
+    };
+
+    let reindented = reindent_block(block, indent);
+    lines.splice(insert_at..insert_at, reindented.iter().map(String::as_str));
+    Some(join_like(&lines, content))
+}
+
+/// The lines of `block`, moved so that its first line starts at `indent`.
+fn reindent_block(block: &str, indent: usize) -> Vec<String> {
+    let block_indent = block.lines().find(|l| is_content(l)).map_or(0, indentation);
+    block.lines()
+         .map(|line| if line.trim().is_empty() {
+             String::new()
+         } else {
+             format!("{}{}", " ".repeat(indent), &line[block_indent.min(indentation(line))..])
+         })
+         .collect()
+}
+
+/// Puts `block` where `key` is, or inserts it like `insert_block_in_text()` if `key` is missing.
+pub fn replace_block_in_text(content: &str, key: &str, block: &str) -> Option<String> {
+    let mut lines = content.lines().collect::<Vec<&str>>();
+    let (index, block_end) = match find_block(&lines, key) {
+        Some(found) => found,
+        None => return insert_block_in_text(content, key, block),
+    };
+    let reindented = reindent_block(block, indentation(lines[index]));
+    lines.splice(index..block_end, reindented.iter().map(String::as_str));
+    Some(join_like(&lines, content))
+}
+
+/// `patch` merged into `base`: mappings are merged key by key, everything else is replaced, `~` removes a key.
+///
+/// Existing keys keep their place, new ones are appended.
+/// A mapping that has nothing left is `~`, like an empty section in a project file.
+pub fn merged(base: &Yaml, patch: &Yaml) -> Yaml {
+    match (base, patch) {
+        (Yaml::Hash(base), Yaml::Hash(patch)) => {
+            let mut result = base.clone();
+            for (key, value) in patch {
+                if value.is_null() {
+                    result.remove(key);
+                } else if let Some(existing) = result.get_mut(key) {
+                    *existing = merged(existing, value);
+                } else {
+                    result.insert(key.clone(), value.clone());
+                }
+            }
+            if result.is_empty() && !base.is_empty() {
+                Yaml::Null
+            } else {
+                Yaml::Hash(result)
+            }
+        }
+        _ => patch.clone(),
+    }
+}
+
+/// Merges the yaml document `patch` into the text of `content` like `merged()`, without touching anything else.
+///
+/// Replaced and new values are copied from `patch` as they are written there, comments included.
+/// Returns `None` if the patch is no mapping or the text can't be changed so that it reads like `merged()`,
+/// e.g. because a key contains a `/` or `.`.
+pub fn merge_in_text(content: &str, patch: &str) -> Option<String> {
+    fn merge(content: String, patch: &str, base: &Yaml, changes: &YamlHash, prefix: &str) -> Option<String> {
+        let mut content = content;
+        for (key, value) in changes {
+            let key = key.as_str()?;
+            let path = if prefix.is_empty() { key.to_owned() } else { format!("{}/{}", prefix, key) };
+            let existing = base.as_hash().and_then(|base| base.get(&Yaml::String(key.to_owned())));
+            content = match (existing, value) {
+                (None, Yaml::Null) => content,
+                (Some(_), Yaml::Null) => remove_in_text(&content, &path)?,
+                (Some(existing @ Yaml::Hash(_)), Yaml::Hash(changes)) => merge(content, patch, existing, changes, &path)?,
+                _ => replace_block_in_text(&content, &path, &block_in_text(patch, &path)?)?,
+            };
+        }
+        Some(content)
+    }
+
+    let base = parse(content).ok()?;
+    let changes = parse(patch).ok()?;
+    let result = merge(content.to_owned(), patch, &base, changes.as_hash()?, "")?;
+    if parse(&result).ok()? == merged(&base, &changes) {
+        Some(result)
+    } else {
+        None
+    }
+}
+
+/// `|`, `>-`, `|2` and the like, which start a block of text on the following lines.
+fn is_block_indicator(rest: &str) -> bool {
+    let value = rest[..rest.len() - trailing_comment(rest).len()].trim();
+    let mut chars = value.chars();
+    matches!(chars.next(), Some('|') | Some('>')) && chars.all(|c| c == '-' || c == '+' || c.is_ascii_digit())
+}
+
+/// Indents every nesting level of a yaml document by `width` spaces.
+///
+/// List items keep their place relative to their key, text after `- ` starts right after it.
+/// Blocks of text (`|`, `>`) are moved as a whole, comments follow the level they are on
+/// and trailing whitespace is removed outside of blocks.
+pub fn reindent_text(content: &str, width: usize) -> String {
+    struct Level { original: usize, indented: usize }
+    // key indentation of an open block of text, where its lines go, and where they were
+    struct Block { key: usize, indented: usize, original: Option<usize> }
+
+    let mut levels = vec![Level { original: 0, indented: 0 }];
+    let mut block: Option<Block> = None;
+    let mut lines = Vec::new();
+
+    for line in content.lines() {
+        if let Some(Block { key, indented, ref mut original }) = block {
+            if line.trim().is_empty() {
+                lines.push(String::new());
+                continue;
+            }
+            let indent = indentation(line);
+            if indent > key {
+                let original = *original.get_or_insert(indent);
+                lines.push(format!("{}{}", " ".repeat(indented + indent.saturating_sub(original)), &line[indent..]));
+                continue;
+            }
+            block = None;
+        }
+
+        let trimmed = line.trim();
+        let indent = indentation(line);
+        if trimmed.is_empty() {
+            lines.push(String::new());
+            continue;
+        }
+        if trimmed.starts_with('#') {
+            let indented = levels.iter().rev().find(|l| l.original == indent).map_or(indent, |l| l.indented);
+            lines.push(format!("{}{}", " ".repeat(indented), trimmed));
+            continue;
+        }
+
+        while levels.len() > 1 && levels.last().is_some_and(|l| l.original > indent) {
+            levels.pop();
+        }
+        let top = levels.last().expect("the top level is never removed");
+        let indented = if top.original == indent {
+            top.indented
+        } else {
+            let indented = top.indented + width;
+            levels.push(Level { original: indent, indented });
+            indented
+        };
+
+        // `-   key: value` starts a level of its own right after the dash
+        let mut text = trimmed.to_owned();
+        let mut key = indent;
+        let mut item_indented = indented;
+        if let Some(after) = trimmed.strip_prefix('-').filter(|after| after.starts_with(' ')) {
+            let item = after.trim_start();
+            key = indent + 1 + after.len() - item.len();
+            item_indented = indented + 2;
+            levels.push(Level { original: key, indented: item_indented });
+            text = format!("- {}", item);
+        }
+
+        let item = text.strip_prefix("- ").unwrap_or(&text);
+        if split_key(item).is_some_and(|(_, rest)| is_block_indicator(rest)) {
+            block = Some(Block { key, indented: item_indented + width, original: None });
+        }
+        lines.push(format!("{}{}", " ".repeat(indented), text));
+    }
+
+    let lines = lines.iter().map(String::as_str).collect::<Vec<_>>();
+    join_like(&lines, content)
+}
+
+/// Sorts the top level keys of a yaml document into `order`, moving everything nested below them along.
+///
+/// Comments directly above a key move with it. Keys that are not in `order` stay behind the key they follow,
+/// the start of the document (`---`) and a closing `...` stay where they are.
+pub fn reorder_in_text(content: &str, order: &[&str]) -> String {
+    let lines = content.lines().collect::<Vec<&str>>();
+    let is_key = |line: &str| indentation(line) == 0 && is_content(line)
+                              && !line.starts_with("---") && !line.starts_with("...")
+                              && split_key(line).is_some();
+
+    // (key, lines), the first chunk is everything before the first key
+    let mut chunks: Vec<(Option<&str>, Vec<&str>)> = vec![(None, Vec::new())];
+    let mut end = Vec::new();
+    for (i, &line) in lines.iter().enumerate() {
+        if line.starts_with("...") {
+            end.extend_from_slice(&lines[i..]);
+            break;
+        }
+        if is_key(line) {
+            let current = &mut chunks.last_mut().expect("there is always a chunk").1;
+            let comments = current.iter().rev().take_while(|l| l.starts_with('#')).count();
+            let mut chunk = current.split_off(current.len() - comments);
+            chunk.push(line);
+            chunks.push((split_key(line).map(|(key, _)| key), chunk));
+        } else {
+            chunks.last_mut().expect("there is always a chunk").1.push(line);
+        }
+    }
+
+    let mut chunks = chunks.into_iter();
+    let mut sorted = chunks.next().map(|(_, start)| start).unwrap_or_default();
+
+    let mut rank = 0;
+    let mut ranked = chunks.map(|(key, chunk)| {
+                                let known = key.and_then(|key| order.iter().position(|k| *k == key));
+                                rank = known.unwrap_or(rank);
+                                ((rank, known.is_none()), chunk)
+                            })
+                            .collect::<Vec<_>>();
+    ranked.sort_by_key(|(rank, _)| *rank);
+
+    sorted.extend(ranked.into_iter().flat_map(|(_, chunk)| chunk));
+    sorted.extend(end);
+    join_like(&sorted, content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOC: &str = "--- # comment\nclient:\n  title:      Herr # Frau\n  address: |\n    Graf Zahl\n    Street\n\nevent:\n  name: Party\n  dates:\n  - begin: 01.01.2000\n\ninvoice:\n  number: 41\n";
+
+    #[test]
+    fn set_existing_value() {
+        let changed = set_in_text(DOC, "invoice/number", "42").unwrap();
+        assert_eq!(get_int(&parse(&changed).unwrap(), "invoice/number"), Some(42));
+        assert!(changed.contains("--- # comment\n"));
+
+        let changed = set_in_text(DOC, "client.title", "Frau").unwrap();
+        assert!(changed.contains("  title:      Frau # Frau\n"));
+    }
+
+    #[test]
+    fn set_replaces_blocks() {
+        let changed = set_in_text(DOC, "client/address", "Somewhere").unwrap();
+        let yaml = parse(&changed).unwrap();
+        assert_eq!(get_str(&yaml, "client/address"), Some("Somewhere"));
+        assert_eq!(get_str(&yaml, "client/title"), Some("Herr"));
+
+        let changed = set_in_text(DOC, "client/address", "Graf Zahl\nElsewhere").unwrap();
+        assert_eq!(get_str(&parse(&changed).unwrap(), "client/address"), Some("Graf Zahl\nElsewhere\n"));
+    }
+
+    #[test]
+    fn set_missing_values() {
+        let changed = set_in_text(DOC, "invoice/payed_date", "08.12.2014").unwrap();
+        let yaml = parse(&changed).unwrap();
+        assert_eq!(get_str(&yaml, "invoice/payed_date"), Some("08.12.2014"));
+        assert_eq!(get_int(&yaml, "invoice/number"), Some(41));
+
+        let changed = set_in_text(DOC, "hours/salary", "8.5").unwrap();
+        let yaml = parse(&changed).unwrap();
+        assert_eq!(get_f64(&yaml, "hours/salary"), Some(8.5));
+        assert_eq!(get_str(&yaml, "event/name"), Some("Party"));
+
+        let changed = set_in_text(DOC, "event/location", "Hall").unwrap();
+        let yaml = parse(&changed).unwrap();
+        assert_eq!(get_str(&yaml, "event/location"), Some("Hall"));
+        assert_eq!(get_str(&yaml, "event/dates/0/begin"), Some("01.01.2000"));
+    }
+
+    #[test]
+    fn leaves_of_mapping() {
+        let patch = parse("tax: 0.19\nhours:\n  salary: 9\nclient:\n  title: Frau\ncanceled: false\n").unwrap();
+        assert_eq!(leaves(&patch).unwrap(),
+                   vec![("tax".to_owned(), "0.19".to_owned()),
+                        ("hours/salary".to_owned(), "9".to_owned()),
+                        ("client/title".to_owned(), "Frau".to_owned()),
+                        ("canceled".to_owned(), "false".to_owned())]);
+        assert_eq!(leaves(&parse("list:\n  - 1\n").unwrap()), None);
+    }
+
+    #[test]
+    fn merge_patches() {
+        let patch = "tax: 0.07 # reduced\nclient:\n  title: Frau\nevent:\n  dates:\n  - begin: 02.02.2000\n  - begin: 03.02.2000\nhours:\n  caterers:\n    Ida: 3\ninvoice:\n  number: ~\n";
+        let changed = merge_in_text(DOC, patch).unwrap();
+        assert_eq!(parse(&changed).unwrap(), merged(&parse(DOC).unwrap(), &parse(patch).unwrap()));
+        assert!(changed.starts_with("--- # comment\nclient:\n  title: Frau\n  address: |\n"));
+        assert!(changed.contains("event:\n  name: Party\n  dates:\n  - begin: 02.02.2000\n  - begin: 03.02.2000\n"));
+        assert!(changed.ends_with("tax: 0.07 # reduced\nhours:\n  caterers:\n    Ida: 3\n"));
+
+        let yaml = parse(&changed).unwrap();
+        assert_eq!(get_str(&yaml, "event/dates/1/begin"), Some("03.02.2000"));
+        assert_eq!(get(&yaml, "invoice/number"), None);
+
+        // only mappings can be merged into a project
+        assert_eq!(merge_in_text(DOC, "- 1\n"), None);
+        // keys with a dot can't be told from paths, unless they come with their whole section
+        let changed = merge_in_text(DOC, "products:\n  Bier 0.5l: 2\n").unwrap();
+        assert!(changed.ends_with("products:\n  Bier 0.5l: 2\n"));
+        assert_eq!(merge_in_text(&changed, "products:\n  Wein 0.2l: 2\n"), None);
+    }
+
+    #[test]
+    fn lines_of_keys() {
+        assert_eq!(line_of(DOC, "client/title"), Some(2));
+        assert_eq!(line_of(DOC, "invoice.number"), Some(13));
+        assert_eq!(line_of(DOC, "invoice/date"), Some(12));
+        assert_eq!(line_of(DOC, "offer/date"), None);
+    }
+
+    #[test]
+    fn copy_blocks() {
+        let dates = block_in_text(DOC, "event/dates").unwrap();
+        assert_eq!(dates, "  dates:\n  - begin: 01.01.2000");
+
+        let without = remove_in_text(DOC, "event/dates").unwrap();
+        assert_eq!(insert_block_in_text(DOC, "event/dates", &dates), None);
+        let changed = insert_block_in_text(&without, "event/dates", &dates).unwrap();
+        let yaml = parse(&changed).unwrap();
+        assert_eq!(get(&yaml, "event/dates").unwrap()[0]["begin"].as_str(), Some("01.01.2000"));
+        assert_eq!(get_str(&yaml, "event/name"), Some("Party"));
+
+        // top level blocks are unindented and go before the end of the document
+        let changed = insert_block_in_text("event:\n  name: Party\n...\n", "hours", "  hours:\n    salary: 8.0").unwrap();
+        assert_eq!(changed, "event:\n  name: Party\nhours:\n  salary: 8.0\n...\n");
+        assert_eq!(insert_block_in_text(DOC, "offer/dates", &dates), None);
+    }
+
+    #[test]
+    fn remove_keys() {
+        let changed = remove_in_text(DOC, "invoice/number").unwrap();
+        assert_eq!(get(&parse(&changed).unwrap(), "invoice/number"), None);
+        assert!(changed.contains("--- # comment\n"));
+
+        let changed = remove_in_text(DOC, "event.dates").unwrap();
+        let yaml = parse(&changed).unwrap();
+        assert_eq!(get(&yaml, "event/dates"), None);
+        assert_eq!(get_str(&yaml, "event/name"), Some("Party"));
+        assert_eq!(get_int(&yaml, "invoice/number"), Some(41));
+
+        let changed = remove_in_text(DOC, "client").unwrap();
+        assert_eq!(get(&parse(&changed).unwrap(), "client"), None);
+
+        assert_eq!(remove_in_text(DOC, "offer/date"), None);
+        assert_eq!(remove_in_text(DOC, "event/dates/begin"), None);
+    }
+
+    #[test]
+    fn refuse_unreachable_paths() {
+        assert_eq!(set_in_text(DOC, "invoice/number/deeper", "1"), None);
+        assert_eq!(set_in_text(DOC, "event/dates/begin", "1"), None);
+        assert_eq!(set_in_text(DOC, "", "1"), None);
+    }
+}
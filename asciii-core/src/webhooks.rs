@@ -0,0 +1,213 @@
+//! Posts projects to configured URLs when they are created, invoiced, payed or archived.
+//!
+//! The hooks are listed under `webhooks` in the config, one without `events` gets all of them:
+//!
+//! ```yaml
+//! webhooks:
+//!   - url: https://example.com/asciii
+//!     events: [invoiced, payed]
+//! ```
+//!
+//! The body is a `Payload` as JSON, posted with `curl`.
+//! A failing hook is only logged, it never fails the command that triggered it.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use yaml_rust::Yaml;
+
+use crate::config::ConfigReader;
+use crate::error::{ActionError, AsciiiError};
+use crate::project::export::{Complete, ExportTarget};
+use crate::project::Project;
+use crate::storage::{Storable, Storage};
+use crate::util::http::Request;
+
+/// What happened to a project.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Event {
+    Created,
+    /// `invoice/number` was set
+    Invoiced,
+    /// `invoice/payed_date` was set
+    Payed,
+    Archived,
+}
+
+impl Event {
+    pub const ALL: [Event; 4] = [Event::Created, Event::Invoiced, Event::Payed, Event::Archived];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Event::Created => "created",
+            Event::Invoiced => "invoiced",
+            Event::Payed => "payed",
+            Event::Archived => "archived",
+        }
+    }
+
+    fn from_str(event: &str) -> Option<Event> {
+        Event::ALL.iter().copied().find(|e| e.as_str() == event)
+    }
+
+    /// The event a change of `field` amounts to, if any.
+    pub fn for_field(field: &str) -> Option<Event> {
+        match field {
+            "invoice/number" | "INVOICE-NUMBER" => Some(Event::Invoiced),
+            "invoice/payed_date" => Some(Event::Payed),
+            _ => None,
+        }
+    }
+}
+
+/// One entry of `webhooks`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Webhook {
+    pub url: String,
+    pub events: Vec<Event>,
+}
+
+/// What a webhook receives.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Payload {
+    pub event: Event,
+    /// the project file
+    pub file: PathBuf,
+    /// everything about the project, like `asciii show --json`
+    pub project: Complete,
+}
+
+impl Payload {
+    pub fn new(event: Event, project: &Project) -> Payload {
+        Payload {
+            event,
+            file: project.file(),
+            project: project.export(),
+        }
+    }
+}
+
+impl Webhook {
+    /// Reads a webhook, `None` if it has no `url`, unknown events are skipped.
+    pub fn from_yaml(yaml: &Yaml) -> Option<Webhook> {
+        let url = yaml["url"].as_str().filter(|url| !url.is_empty())?;
+        let events = match yaml["events"].as_vec() {
+            Some(events) => events.iter()
+                .filter_map(Yaml::as_str)
+                .filter_map(|event| Event::from_str(event)
+                            .or_else(|| { log::warn!("ignoring unknown webhook event {:?}", event); None }))
+                .collect(),
+            None => Event::ALL.to_vec(),
+        };
+        Some(Webhook { url: url.to_owned(), events })
+    }
+
+    /// Webhooks from `webhooks` in `config`, broken ones are skipped with a warning.
+    pub fn configured(config: &ConfigReader) -> Vec<Webhook> {
+        config.get("webhooks")
+            .and_then(Yaml::as_vec)
+            .map(|hooks| hooks.iter()
+                 .filter_map(|hook| Webhook::from_yaml(hook)
+                             .or_else(|| { log::warn!("ignoring webhook without url: {:?}", hook); None }))
+                 .collect())
+            .unwrap_or_default()
+    }
+
+    pub fn wants(&self, event: Event) -> bool {
+        self.events.contains(&event)
+    }
+
+    /// Posts `payload` to `url`.
+    pub fn post(&self, payload: &Payload) -> Result<(), AsciiiError> {
+        log::debug!("posting {} of {} to {}", payload.event.as_str(), payload.file.display(), self.url);
+        let output = Request::new(lformat!("curl is needed for webhooks"))
+            .args(["--fail", "--output", "/dev/null"])
+            .args(["--request", "POST"])
+            .args(["--header", "Content-Type: application/json"])
+            .args(["--data-binary", "@-"])
+            .arg(&self.url)
+            .body(serde_json::to_vec(payload)?)
+            .output()?;
+
+        if !output.status.success() {
+            return Err(ActionError::Failed(lformat!("posting to webhook {} failed", self.url)).into());
+        }
+        Ok(())
+    }
+}
+
+/// Posts the project at `path`, a file or a project folder, to every hook that wants `event`.
+fn fire(hooks: &[Webhook], event: Event, path: &Path) {
+    let hooks = hooks.iter().filter(|hook| hook.wants(event)).collect::<Vec<_>>();
+    if hooks.is_empty() {
+        return;
+    }
+
+    let project = if path.is_dir() { Project::open_folder(path) } else { Project::open_file(path) };
+    let payload = match project {
+        Ok(project) => Payload::new(event, &project),
+        Err(e) => {
+            log::warn!("{}", lformat!("can't open {} for webhooks: {}", path.display(), e));
+            return;
+        }
+    };
+    for hook in hooks {
+        if let Err(e) = hook.post(&payload) {
+            log::warn!("{}", e);
+        }
+    }
+}
+
+/// Registers the hooks of `storage`s config on it, nothing happens without any.
+pub fn install<L: Storable>(storage: &mut Storage<L>) {
+    let hooks = Arc::new(Webhook::configured(storage.config()));
+    if hooks.is_empty() {
+        return;
+    }
+
+    let created = Arc::clone(&hooks);
+    storage.on_created(move |file| fire(&created, Event::Created, file));
+
+    let changed = Arc::clone(&hooks);
+    storage.on_field_changed(move |file, field| {
+        if let Some(event) = Event::for_field(field) {
+            fire(&changed, event, file);
+        }
+    });
+
+    storage.on_archived(move |_, to| fire(&hooks, Event::Archived, to));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::yaml;
+
+    fn webhook(yaml: &str) -> Option<Webhook> {
+        Webhook::from_yaml(&yaml::parse(yaml).unwrap())
+    }
+
+    #[test]
+    fn events_are_filtered() {
+        let hook = webhook("url: http://localhost/hook\nevents: [invoiced, payed, unheard]").unwrap();
+        assert_eq!(hook.events, vec![Event::Invoiced, Event::Payed]);
+        assert!(!hook.wants(Event::Created));
+
+        assert_eq!(webhook("url: http://localhost/hook").unwrap().events, Event::ALL.to_vec());
+        assert_eq!(webhook("events: [created]"), None);
+
+        assert_eq!(Event::for_field("invoice/payed_date"), Some(Event::Payed));
+        assert_eq!(Event::for_field("client/email"), None);
+    }
+
+    #[test]
+    fn payloads_carry_the_export() {
+        let project = Project::open("../tests/test_projects/current.yml").unwrap();
+        let json = serde_json::to_string(&Payload::new(Event::Payed, &project)).unwrap();
+        assert!(json.starts_with(r#"{"event":"payed","file":"#));
+
+        let parsed: Payload = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.project.client.last_name.as_deref(), Some("Zahl"));
+    }
+}
@@ -0,0 +1,65 @@
+#ifndef ASCIII_H
+#define ASCIII_H
+
+/* Generated by cbindgen from asciii-ffi/src/lib.rs, do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// A project storage, opened with `asciii_open_storage()`.
+typedef struct AsciiiStorage AsciiiStorage;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Opens the storage at `root`, or the one configured in `~/.asciii.yml` if `root` is `NULL`.
+//
+// Returns `NULL` on failure, close it with `asciii_close_storage()`.
+//
+// # Safety
+// `root` is `NULL` or a valid, nul terminated string.
+struct AsciiiStorage *asciii_open_storage(const char *root);
+
+// Closes a storage from `asciii_open_storage()`, `NULL` is ignored.
+//
+// # Safety
+// `storage` is `NULL` or came from `asciii_open_storage()` and was not closed before.
+void asciii_close_storage(struct AsciiiStorage *storage);
+
+// JSON array of the projects of `year`, archived or not, or of the working directory if `year` is `0`.
+//
+// Returns `NULL` on failure, free it with `asciii_free_string()`.
+//
+// # Safety
+// `storage` came from `asciii_open_storage()` and is still open.
+char *asciii_list_projects_json(const struct AsciiiStorage *storage,
+                                int32_t year);
+
+// JSON object of the first project that matches `search_term`, searched like `asciii show` does.
+//
+// Returns `NULL` on failure, free it with `asciii_free_string()`.
+//
+// # Safety
+// `storage` came from `asciii_open_storage()` and is still open, `search_term` is a valid, nul terminated string.
+char *asciii_export_project_json(const struct AsciiiStorage *storage,
+                                 const char *search_term);
+
+// Frees a string returned by this library, `NULL` is ignored.
+//
+// # Safety
+// `string` is `NULL` or came from this library and was not freed before.
+void asciii_free_string(char *string);
+
+// Why the last call on this thread failed, `NULL` if nothing failed yet.
+//
+// The message stays valid until the next failure on this thread, don't free it.
+const char *asciii_last_error(void);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* ASCIII_H */
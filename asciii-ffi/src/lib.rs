@@ -0,0 +1,236 @@
+//! C interface, for desktop apps that are not written in Rust.
+//!
+//! The declarations are in `include/asciii.h`. Everything is handed out as JSON,
+//! shaped like the output of `asciii show --json`, so the interface stays small and stable.
+//!
+//! * Functions that fail return `NULL`, `asciii_last_error()` tells why.
+//! * Strings returned by this library belong to the caller and are freed with `asciii_free_string()`.
+//! * A storage is only used by one thread at a time.
+
+// `guard()` can't catch anything then, a panic would take the host application down with it
+#[cfg(panic = "abort")]
+compile_error!("asciii-ffi has to be built with panic = \"unwind\", use the `release` profile, not `dist`");
+
+use std::cell::RefCell;
+use std::ffi::{c_char, CStr, CString};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+
+use asciii_core::error::AsciiiError;
+use asciii_core::project::export::{Complete, ExportTarget};
+use asciii_core::project::Project;
+use asciii_core::storage::{self, StorageDir};
+
+/// A project storage, opened with `asciii_open_storage()`.
+pub struct AsciiiStorage {
+    storage: storage::Storage<Project>,
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(message: String) {
+    let message = CString::new(message.replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(message));
+}
+
+/// Runs `f`, errors and panics end up in `asciii_last_error()` and become `None`.
+fn guard<T, F: FnOnce() -> Result<T, AsciiiError>>(f: F) -> Option<T> {
+    match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(value)) => Some(value),
+        Ok(Err(error)) => {
+            set_last_error(error.to_string());
+            None
+        }
+        Err(_) => {
+            set_last_error("asciii panicked".into());
+            None
+        }
+    }
+}
+
+fn into_c_string(string: String) -> *mut c_char {
+    CString::new(string).map_or(ptr::null_mut(), CString::into_raw)
+}
+
+/// Reads a C string, `NULL` is `None`.
+///
+/// # Safety
+/// `string` is `NULL` or a valid, nul terminated string.
+unsafe fn from_c_string<'a>(string: *const c_char) -> Result<Option<&'a str>, AsciiiError> {
+    if string.is_null() {
+        return Ok(None);
+    }
+    CStr::from_ptr(string)
+        .to_str()
+        .map(Some)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e).into())
+}
+
+/// Opens the storage at `root`, or the one configured in `~/.asciii.yml` if `root` is `NULL`.
+///
+/// Returns `NULL` on failure, close it with `asciii_close_storage()`.
+///
+/// # Safety
+/// `root` is `NULL` or a valid, nul terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn asciii_open_storage(root: *const c_char) -> *mut AsciiiStorage {
+    guard(|| {
+        let storage = match from_c_string(root)? {
+            Some(root) => storage::Storage::builder().root(root).build()?,
+            None => storage::setup::<Project>()?,
+        };
+        Ok(Box::into_raw(Box::new(AsciiiStorage { storage })))
+    }).unwrap_or(ptr::null_mut())
+}
+
+/// Closes a storage from `asciii_open_storage()`, `NULL` is ignored.
+///
+/// # Safety
+/// `storage` is `NULL` or came from `asciii_open_storage()` and was not closed before.
+#[no_mangle]
+pub unsafe extern "C" fn asciii_close_storage(storage: *mut AsciiiStorage) {
+    if !storage.is_null() {
+        drop(Box::from_raw(storage));
+    }
+}
+
+/// JSON array of the projects of `year`, archived or not, or of the working directory if `year` is `0`.
+///
+/// Returns `NULL` on failure, free it with `asciii_free_string()`.
+///
+/// # Safety
+/// `storage` came from `asciii_open_storage()` and is still open.
+#[no_mangle]
+pub unsafe extern "C" fn asciii_list_projects_json(storage: *const AsciiiStorage, year: i32) -> *mut c_char {
+    let Some(storage) = storage.as_ref() else {
+        set_last_error("no storage".into());
+        return ptr::null_mut();
+    };
+    guard(|| {
+        let dir = if year == 0 { StorageDir::Working } else { StorageDir::Year(year) };
+        let exported = storage.storage.open_projects(dir)?
+            .iter()
+            .map(|project| project.export())
+            .collect::<Vec<Complete>>();
+        Ok(serde_json::to_string(&exported)?)
+    }).map_or(ptr::null_mut(), into_c_string)
+}
+
+/// JSON object of the first project that matches `search_term`, searched like `asciii show` does.
+///
+/// Returns `NULL` on failure, free it with `asciii_free_string()`.
+///
+/// # Safety
+/// `storage` came from `asciii_open_storage()` and is still open, `search_term` is a valid, nul terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn asciii_export_project_json(storage: *const AsciiiStorage, search_term: *const c_char) -> *mut c_char {
+    let Some(storage) = storage.as_ref() else {
+        set_last_error("no storage".into());
+        return ptr::null_mut();
+    };
+    guard(|| {
+        let search_term = from_c_string(search_term)?.unwrap_or_default();
+        let project = storage.storage.search_projects(StorageDir::All, search_term)?
+            .into_iter()
+            .next()
+            .ok_or_else(|| storage::StorageError::NothingFound(vec![search_term.to_owned()]))?;
+        let exported: Complete = project.export();
+        Ok(serde_json::to_string(&exported)?)
+    }).map_or(ptr::null_mut(), into_c_string)
+}
+
+/// Frees a string returned by this library, `NULL` is ignored.
+///
+/// # Safety
+/// `string` is `NULL` or came from this library and was not freed before.
+#[no_mangle]
+pub unsafe extern "C" fn asciii_free_string(string: *mut c_char) {
+    if !string.is_null() {
+        drop(CString::from_raw(string));
+    }
+}
+
+/// Why the last call on this thread failed, `NULL` if nothing failed yet.
+///
+/// The message stays valid until the next failure on this thread, don't free it.
+#[no_mangle]
+pub extern "C" fn asciii_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| last.borrow().as_ref().map_or(ptr::null(), |message| message.as_ptr()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::Path;
+    use tempdir::TempDir;
+
+    fn take(string: *mut c_char) -> String {
+        assert!(!string.is_null(), "{}", last_error());
+        let owned = unsafe { CStr::from_ptr(string) }.to_str().unwrap().to_owned();
+        unsafe { asciii_free_string(string) };
+        owned
+    }
+
+    fn last_error() -> String {
+        unsafe { CStr::from_ptr(asciii_last_error()) }.to_str().unwrap().to_owned()
+    }
+
+    #[test]
+    fn lists_and_exports_projects() {
+        let temp = TempDir::new("asciii-ffi").unwrap();
+        let dir = temp.path();
+        let project_dir = dir.join("working/party");
+        fs::create_dir_all(&project_dir).unwrap();
+        fs::create_dir_all(dir.join("archive")).unwrap();
+        fs::copy("../tests/test_projects/current.yml", project_dir.join("party.yml")).unwrap();
+
+        let root = CString::new(dir.to_str().unwrap()).unwrap();
+        let storage = unsafe { asciii_open_storage(root.as_ptr()) };
+        assert!(!storage.is_null());
+
+        let list: Vec<Complete> = serde_json::from_str(&take(unsafe { asciii_list_projects_json(storage, 0) })).unwrap();
+        assert_eq!(list.len(), 1);
+
+        let term = CString::new("party").unwrap();
+        let party: Complete = serde_json::from_str(&take(unsafe { asciii_export_project_json(storage, term.as_ptr()) })).unwrap();
+        assert_eq!(party.client.last_name.as_deref(), Some("Zahl"));
+
+        let nothing = CString::new("nothing at all").unwrap();
+        assert!(unsafe { asciii_export_project_json(storage, nothing.as_ptr()) }.is_null());
+        assert!(last_error().contains("nothing at all"));
+
+        unsafe { asciii_close_storage(storage) };
+    }
+
+    #[test]
+    fn relative_roots_fail() {
+        let root = CString::new("relative/path").unwrap();
+        assert!(unsafe { asciii_open_storage(root.as_ptr()) }.is_null());
+        assert!(!last_error().is_empty());
+    }
+
+    #[test]
+    fn panics_become_errors() {
+        let result = guard(|| -> Result<(), AsciiiError> { panic!("on purpose") });
+        assert!(result.is_none());
+        assert_eq!(last_error(), "asciii panicked");
+    }
+
+    #[test]
+    fn header_is_up_to_date() {
+        let crate_dir = env!("CARGO_MANIFEST_DIR");
+        let mut generated = Vec::new();
+        cbindgen::Builder::new()
+            .with_crate(crate_dir)
+            .with_config(cbindgen::Config::from_file(Path::new(crate_dir).join("cbindgen.toml")).unwrap())
+            .generate()
+            .unwrap()
+            .write(&mut generated);
+        let header = fs::read_to_string(Path::new(crate_dir).join("include/asciii.h")).unwrap();
+        assert_eq!(String::from_utf8(generated).unwrap(), header,
+                   "include/asciii.h is outdated, see cbindgen.toml");
+    }
+}
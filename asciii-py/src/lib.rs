@@ -0,0 +1,174 @@
+//! Python bindings, for reading projects from notebooks instead of parsing `asciii csv`.
+//!
+//! Build and install into the current virtualenv with `maturin develop -m asciii-py/Cargo.toml`, then:
+//!
+//! ```python
+//! import asciii
+//! storage = asciii.Storage()
+//! for project in storage.year(2019):
+//!     print(project.name, project.export()["invoice"]["gross_total"])
+//! ```
+//!
+//! Exports are plain dicts, shaped like the output of `asciii show --json`.
+
+// panics become python exceptions only while they unwind, aborting would take the interpreter down
+#[cfg(panic = "abort")]
+compile_error!("asciii-py has to be built with panic = \"unwind\", use the `release` profile, not `dist`");
+
+use pyo3::create_exception;
+use pyo3::exceptions::PyException;
+use pyo3::prelude::*;
+
+use asciii_core::error::AsciiiError;
+use asciii_core::project::export::{Complete, ExportTarget};
+use asciii_core::project::Project as CoreProject;
+use asciii_core::storage::{self, Storable, StorageDir, Year};
+
+create_exception!(asciii, Error, PyException, "Anything asciii itself fails with.");
+
+fn to_py(error: AsciiiError) -> PyErr {
+    Error::new_err(error.to_string())
+}
+
+/// Turns anything serializable into python objects, by way of `json.loads()`.
+fn to_python<T: serde::Serialize>(py: Python<'_>, value: &T) -> PyResult<PyObject> {
+    let json = serde_json::to_string(value).map_err(|e| to_py(e.into()))?;
+    Ok(py.import("json")?.call_method1("loads", (json,))?.unbind())
+}
+
+/// The project storage, by default the one configured in `~/.asciii.yml`.
+#[pyclass(unsendable)]
+pub struct Storage {
+    storage: storage::Storage<CoreProject>,
+}
+
+#[pymethods]
+impl Storage {
+    #[new]
+    #[pyo3(signature = (root=None))]
+    fn new(root: Option<&str>) -> PyResult<Self> {
+        let storage = match root {
+            Some(root) => storage::Storage::builder().root(root).build(),
+            None => storage::setup::<CoreProject>(),
+        };
+        Ok(Storage { storage: storage.map_err(to_py)? })
+    }
+
+    #[getter]
+    fn root(&self) -> String {
+        self.storage.root_dir().display().to_string()
+    }
+
+    /// Archived years.
+    fn years(&self) -> PyResult<Vec<Year>> {
+        self.storage.list_years().map_err(to_py)
+    }
+
+    /// Projects in the working directory.
+    fn working(&self) -> PyResult<Vec<Project>> {
+        self.open(StorageDir::Working)
+    }
+
+    /// Projects archived in `year`.
+    fn archive(&self, year: Year) -> PyResult<Vec<Project>> {
+        self.open(StorageDir::Archive(year))
+    }
+
+    /// Projects of `year`, archived or not.
+    fn year(&self, year: Year) -> PyResult<Vec<Project>> {
+        self.open(StorageDir::Year(year))
+    }
+
+    /// Every project, archived and working.
+    fn all(&self) -> PyResult<Vec<Project>> {
+        self.open(StorageDir::All)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Storage({:?})", self.root())
+    }
+}
+
+impl Storage {
+    fn open(&self, dir: StorageDir) -> PyResult<Vec<Project>> {
+        let projects = self.storage.open_projects(dir).map_err(to_py)?;
+        Ok(projects.into_iter().map(|project| Project { project }).collect())
+    }
+}
+
+/// One project file, read only.
+#[pyclass]
+pub struct Project {
+    project: CoreProject,
+}
+
+#[pymethods]
+impl Project {
+    /// Opens a single project file.
+    #[staticmethod]
+    fn open(path: &str) -> PyResult<Self> {
+        Ok(Project { project: CoreProject::open(path).map_err(to_py)? })
+    }
+
+    #[getter]
+    fn name(&self) -> Option<String> {
+        self.project.field("event/name")
+    }
+
+    #[getter]
+    fn file(&self) -> String {
+        self.project.file().display().to_string()
+    }
+
+    #[getter]
+    fn dir(&self) -> String {
+        self.project.dir().display().to_string()
+    }
+
+    /// A single value of the project file like `"client/last_name"`, see `asciii show --field`.
+    fn field(&self, path: &str) -> Option<String> {
+        self.project.field(path)
+    }
+
+    /// Everything about the project, like `asciii show --json`.
+    fn export(&self, py: Python<'_>) -> PyResult<PyObject> {
+        let exported: Complete = self.project.export();
+        to_python(py, &exported)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Project({:?})", self.project.short_desc())
+    }
+}
+
+#[pymodule]
+#[pyo3(name = "asciii")]
+fn asciii_py(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<Storage>()?;
+    m.add_class::<Project>()?;
+    m.add("Error", m.py().get_type::<Error>())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exports_as_dicts() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let project = Project::open("../tests/test_projects/current.yml").unwrap();
+            assert_eq!(project.name().as_deref(), Some("Party Hard"));
+
+            let exported = project.export(py).unwrap().into_bound(py);
+            let last_name: String = exported.get_item("client").unwrap().get_item("last_name").unwrap().extract().unwrap();
+            assert_eq!(last_name, "Zahl");
+
+            match Project::open("../tests/test_projects/nothing.yml") {
+                Err(error) => assert!(error.is_instance_of::<Error>(py)),
+                Ok(_) => panic!("opened a missing file"),
+            }
+        });
+    }
+}
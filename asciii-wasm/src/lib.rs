@@ -0,0 +1,122 @@
+//! asciii in the browser.
+//!
+//! There is no filesystem and no git, projects are handed in as files and kept in a `MemoryStorage`.
+//! Build with `wasm-pack build asciii-wasm --target web`, then from JavaScript:
+//!
+//! ```js
+//! const asciii = new Asciii();
+//! asciii.addFile("working/party/party.yml", yaml);
+//! const projects = JSON.parse(asciii.list());
+//! const tex = asciii.export("party", template, "offer", "latex");
+//! ```
+
+use wasm_bindgen::prelude::*;
+
+use asciii_core::document_export::{backend, fill_template_content};
+use asciii_core::error::AsciiiError;
+use asciii_core::project::export::{Complete, ExportTarget};
+use asciii_core::project::{BillType, Project};
+use asciii_core::storage::{MemoryStorage, StorageDir};
+
+#[wasm_bindgen]
+#[derive(Debug, Default)]
+pub struct Asciii {
+    storage: MemoryStorage,
+}
+
+#[wasm_bindgen]
+impl Asciii {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Asciii {
+        Asciii::default()
+    }
+
+    /// Adds or replaces a file, `path` is relative to the storage root.
+    #[wasm_bindgen(js_name = addFile)]
+    pub fn add_file(&mut self, path: &str, content: String) {
+        self.storage.insert(path, content);
+    }
+
+    /// JSON list of all projects, or only those of `year`, exported like `asciii show --json`.
+    pub fn list(&self, year: Option<i32>) -> Result<String, JsValue> {
+        self.list_json(year).map_err(to_js)
+    }
+
+    /// JSON of the project in the folder `name`.
+    pub fn show(&self, name: &str) -> Result<String, JsValue> {
+        self.show_json(name).map_err(to_js)
+    }
+
+    /// Fills `template` with the `"offer"` or `"invoice"` of project `name`, for `"latex"`, `"typst"` or `"pandoc"`.
+    pub fn export(&self, name: &str, template: &str, bill_type: &str, backend: &str) -> Result<String, JsValue> {
+        self.export_document(name, template, bill_type, backend).map_err(to_js)
+    }
+}
+
+impl Asciii {
+    pub fn storage(&self) -> &MemoryStorage {
+        &self.storage
+    }
+
+    fn list_json(&self, year: Option<i32>) -> Result<String, AsciiiError> {
+        let dir = year.map_or(StorageDir::All, StorageDir::Year);
+        let exported = self.storage.open_projects(dir)?
+            .iter()
+            .map(|project| project.export())
+            .collect::<Vec<Complete>>();
+        Ok(serde_json::to_string(&exported)?)
+    }
+
+    fn show_json(&self, name: &str) -> Result<String, AsciiiError> {
+        let exported: Complete = self.find(name)?.export();
+        Ok(serde_json::to_string(&exported)?)
+    }
+
+    fn export_document(&self, name: &str, template: &str, bill_type: &str, backend: &str) -> Result<String, AsciiiError> {
+        let bill_type = match bill_type {
+            "invoice" => BillType::Invoice,
+            _ => BillType::Offer,
+        };
+        let exported: Complete = self.find(name)?.export();
+        fill_template_content(&exported, bill_type, template, backend::by_name(backend)?)
+    }
+
+    fn find(&self, name: &str) -> Result<Project, AsciiiError> {
+        self.storage.find_project(StorageDir::All, name)
+    }
+}
+
+fn to_js(error: AsciiiError) -> JsValue {
+    JsValue::from_str(&error.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn demo() -> Asciii {
+        let mut asciii = Asciii::new();
+        asciii.add_file("working/party/party.yml", include_str!("../../tests/test_projects/current.yml").into());
+        asciii.add_file("archive/2015/R036_picnic/picnic.yml", include_str!("../../tests/test_projects/inline.yml").into());
+        asciii
+    }
+
+    #[test]
+    fn lists_and_shows_projects() {
+        let asciii = demo();
+        let list: Vec<Complete> = serde_json::from_str(&asciii.list_json(None).unwrap()).unwrap();
+        assert_eq!(list.len(), 2);
+
+        let party: Complete = serde_json::from_str(&asciii.show_json("party").unwrap()).unwrap();
+        assert_eq!(party.client.last_name.as_deref(), Some("Zahl"));
+        assert!(asciii.show_json("nothing").is_err());
+    }
+
+    #[test]
+    fn exports_from_template_content() {
+        let asciii = demo();
+        let filled = asciii.export_document("party", "{{document.event.name}}", "offer", "pandoc").unwrap();
+        assert!(filled.contains("Party"));
+        assert!(asciii.export_document("party", "", "offer", "word").is_err());
+    }
+}
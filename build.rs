@@ -2,42 +2,17 @@ use std::env;
 use std::path::PathBuf;
 use std::process::Command;
 
-use chrono::prelude::*;
 use crowbook_intl::{Extractor, Localizer};
 
-fn gen_commit_file() {
-    let git_log_output = Command::new("git")
-        .arg("--no-pager")
-        .args(&["--work-tree", "."])
-        .args(&["--git-dir", "./.git"])
-        .arg("log")
-        .args(&["--oneline", r##"--format=%h"##])
-        .output()
-        .ok()
-        .and_then(|output| String::from_utf8(output.stdout).ok());
-
-    if let Some(git_log) = git_log_output {
-        let count = git_log.lines().count().to_string();
-        if let Some(last_commit) = git_log.lines().next() {
-            println!(
-                "cargo:rustc-env=GIT_DESCRIPTION=commit {} ({})",
-                count.trim(),
-                last_commit.to_string().trim()
-            );
-        } else {
-            println!(r#"cargo:rustc-env=GIT_DESCRIPTION="no git description""#);
-        }
-    } else {
-        println!(r#"cargo:rustc-env=GIT_DESCRIPTION="no git description""#);
-    }
-}
-
 fn generate_localization() {
-    // Generate a `lang/default.pot` containing strings used to call `lformat!`
+    // Generate a `lang/default.pot` containing strings used to call `lformat!`, here and in `asciii-core`
     let mut extractor = Extractor::new();
     extractor
         .add_messages_from_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/src"))
         .unwrap();
+    extractor
+        .add_messages_from_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/asciii-core/src"))
+        .unwrap();
     extractor
         .write_pot_file(concat!(env!("CARGO_MANIFEST_DIR"), "/lang/default.pot"))
         .unwrap();
@@ -69,20 +44,11 @@ fn build_webapp() {
 }
 
 fn main() {
-    // passing variables to rustc
-    println!(
-        "cargo:rustc-env=PROFILE={}",
-        env::var("PROFILE").unwrap_or_else(|_| "unknown profile".into())
-    );
-    println!("cargo:rustc-env=BUILD_DATE={}", Utc::now().format("%+"));
-
+    // version and build information are passed on in the build of `asciii-core`
     if env::var("CARGO_FEATURE_WEBAPP") == Ok(String::from("1")) {
         build_webapp();
     }
     if env::var("CARGO_FEATURE_LOCALIZE") == Ok(String::from("1")) {
         generate_localization();
     }
-    if env::var("CARGO_FEATURE_VERSION_STRING") == Ok(String::from("1")) {
-        gen_commit_file();
-    }
 }
@@ -18,16 +18,16 @@ main() {
     test -f Cargo.lock || cargo generate-lockfile
 
     # TODO Update this to build the artifacts that matter to you
-    cross rustc --bin asciii --target $TARGET --features travis_compatible --release -- -C lto
+    cross rustc --bin asciii --target $TARGET --features travis_compatible --profile dist -- -C lto
 
-	ls target/$TARGET/release/
+	ls target/$TARGET/dist/
 
     # TODO Update this to package the right artifacts
     if test "$TARGET" = "x86_64-pc-windows-gnu"
     then
-        cp target/$TARGET/release/asciii.exe $stage/
+        cp target/$TARGET/dist/asciii.exe $stage/
     else
-        cp target/$TARGET/release/asciii $stage/
+        cp target/$TARGET/dist/asciii $stage/
     fi
 
     cd $stage
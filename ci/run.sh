@@ -10,6 +10,11 @@ main() {
 
   cargo build --target $TARGET --features travis_compatible
   cargo test --target $TARGET --features travis_compatible
+  cargo build --target $TARGET --no-default-features
+  cargo check --target $TARGET --features async
+  # panics have to reach the callers of the bindings
+  cargo build --target $TARGET --release -p asciii-ffi -p asciii-py
+  cargo test --target $TARGET --release -p asciii-ffi
 }
 
 if [ -z "$SKIP_TESTS" ]; then
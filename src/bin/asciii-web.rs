@@ -37,7 +37,7 @@ lazy_static::lazy_static! {
                 count += 1;
                 if count % 6 == 0 {
                     log::debug!("updating projects");
-                    PROJECTS.lock().unwrap().update();
+                    update_projects();
                 }
                 log::debug!("call-count: {}", count);
             }
@@ -46,6 +46,21 @@ lazy_static::lazy_static! {
     };
 }
 
+/// Scans the storage without holding the lock, which is only taken to swap in the new state.
+#[cfg(feature = "async")]
+fn update_projects() {
+    let runtime = tokio::runtime::Builder::new().basic_scheduler().enable_all().build();
+    match runtime.map_err(anyhow::Error::from).and_then(|mut runtime| runtime.block_on(ProjectLoader::load()).map_err(anyhow::Error::from)) {
+        Ok(state) => PROJECTS.lock().unwrap().state = state,
+        Err(e) => log::error!("cannot update projects: {}", e)
+    }
+}
+
+#[cfg(not(feature = "async"))]
+fn update_projects() {
+    PROJECTS.lock().unwrap().update();
+}
+
 const LOG_VAR: &str = "ASCIII_LOG";
 const BIND_VAR: &str = "ASCIII_BIND";
 const BIND_HOST: &str = "127.0.0.1";
@@ -81,6 +96,11 @@ pub mod api {
         name: String
     }
 
+    #[derive(Deserialize, Debug)]
+    pub struct EmployeeRequest {
+        employee: String
+    }
+
     #[get("/version")]
     pub fn version() -> HttpResponse {
         let version: &str = asciii::VERSION_JSON.as_ref();
@@ -120,6 +140,20 @@ pub mod api {
                 .set_header(header::CONTENT_TYPE, "text/calendar")
                 .body(cal.to_string())
         }
+
+        /// events of one employee, to subscribe to
+        #[get("/calendar/{employee}")]
+        pub fn employee_calendar(param: web::Path<EmployeeRequest>) -> HttpResponse {
+            log::info!("employee_calendar({:?})", param.employee);
+            self::CHANNEL.send(()).unwrap();
+            let loader = self::PROJECTS.lock().unwrap();
+
+            let cal = actions::staffing::schedule(loader.state.working.values(), &param.employee);
+
+            HttpResponse::Ok()
+                .set_header(header::CONTENT_TYPE, "text/calendar")
+                .body(cal.to_string())
+        }
     }
 
     pub mod projects {
@@ -264,6 +298,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                     .service(api::projects::years)
                 )
                 .service(api::calendar::calendar)
+                .service(api::calendar::employee_calendar)
             )
             // .service(fs::Files::new("/", "webapp/public").index_file("index.html"))
             .service(
@@ -6,20 +6,44 @@ use std::env;
 
 use crowbook_intl_runtime::set_lang;
 
+use asciii::util::timing;
+
 pub mod cli;
 
+/// Languages asciii has messages in, besides english.
+const LANGUAGES: &[&str] = &["de"];
+
+/// Picks the language of messages from `locale` in the config, otherwise from the environment.
 fn setup_locale() {
-    if let Ok(env_lang) = env::var("LANG") {
-        if env_lang.starts_with("de") {
-            set_lang("de");
+    let locale = asciii::CONFIG.get_str_or("locale").map(ToOwned::to_owned)
+        .or_else(|| ["LC_ALL", "LC_MESSAGES", "LANG"].iter()
+                                                      .filter_map(|var| env::var(var).ok())
+                                                      .find(|value| !value.is_empty()));
+
+    if let Some(locale) = locale {
+        match LANGUAGES.iter().find(|lang| locale.starts_with(*lang)) {
+            Some(&lang) => set_lang(lang),
+            None => log::debug!("english messages for locale {:?}", locale),
         }
     }
 }
 
 fn main() {
+    // before anything is loaded, clap only sees the flag later
+    if env::args().any(|arg| arg == "--timing") {
+        timing::enable();
+    }
     color_backtrace::install();
     asciii::util::setup_log();
     setup_locale();
 
-    cli::with_cli(|app| cli::match_matches(&app.get_matches()) );
+    cli::with_cli(|app| {
+        let code = cli::match_matches(&app.get_matches());
+        if timing::is_enabled() {
+            eprint!("{}", timing::report());
+        }
+        if code != cli::exit::OK {
+            std::process::exit(code);
+        }
+    });
 }
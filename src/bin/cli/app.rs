@@ -10,7 +10,7 @@ pub fn with_cli<F> (app_handler:F) where F: Fn(App<'_, '_>) {
             .author(crate_authors!())
             .version(asciii::VERSION.as_ref())
             .about(lformat!("The ascii invoicer III").as_ref())
-            .settings(&[AppSettings::SubcommandRequiredElseHelp,AppSettings::ColoredHelp,AppSettings::DeriveDisplayOrder])
+            .settings(&[AppSettings::SubcommandRequiredElseHelp,AppSettings::ColoredHelp,AppSettings::DeriveDisplayOrder,AppSettings::AllowExternalSubcommands])
             .after_help(asciii::DOCUMENTATION_URL)
 
             .arg(Arg::with_name("debug")
@@ -19,6 +19,18 @@ pub fn with_cli<F> (app_handler:F) where F: Fn(App<'_, '_>) {
                  .short("d")
                  )
 
+            .arg(Arg::with_name("timing")
+                 .help(lformat!("Print how long loading the config, scanning the storage, parsing, git and rendering took").as_ref())
+                 .long("timing")
+                 .global(true)
+                 )
+
+            .arg(Arg::with_name("porcelain")
+                 .help(lformat!("Stable, tab separated output for scripts (list, validate, dues)").as_ref())
+                 .long("porcelain")
+                 .global(true)
+                 )
+
             .subcommand(SubCommand::with_name("bootstrap")
                         .aliases(&["boot", "clone"])
                         .about(lformat!("set's up a new instance").as_ref())
@@ -40,6 +52,18 @@ pub fn with_cli<F> (app_handler:F) where F: Fn(App<'_, '_>) {
                        )
 
 
+            .subcommand(SubCommand::with_name("init")
+                        .about(lformat!("Sets up a new storage with the default templates").as_ref())
+                        .arg(Arg::with_name("to")
+                             .help(lformat!("where to create it, defaults to the configured storage path").as_ref())
+                             .long("to")
+                             .takes_value(true)
+                             .required(false))
+                        .arg(Arg::with_name("demo")
+                             .help(lformat!("fills it with made up projects of the last three years, to try things out").as_ref())
+                             .long("demo"))
+                       )
+
             .subcommand(SubCommand::with_name("new")
                         .about(lformat!("Create a new project").as_ref())
 
@@ -96,8 +120,119 @@ pub fn with_cli<F> (app_handler:F) where F: Fn(App<'_, '_>) {
                              .help(lformat!("Do not edit the file after creation").as_ref())
                              .long("dont"))
 
+                        .arg(Arg::with_name("interactive")
+                             .help(lformat!("Asks for every variable the template declares").as_ref())
+                             .long("interactive")
+                             .short("i"))
+
+                        )
+
+            .subcommand(SubCommand::with_name("duplicate")
+                        .aliases(&["dup", "copy"])
+                        .about(lformat!("Copy a project into a new one, without invoice and dates").as_ref())
+
+                        .arg(Arg::with_name("search_term")
+                             .help(lformat!("Search term, possibly event name").as_ref())
+                             .required(true))
+
+                        .arg(Arg::with_name("name")
+                             .help(lformat!("Name of the new project").as_ref()))
+
+                        .arg(Arg::with_name("archive")
+                             .help(lformat!("Copy an archived project").as_ref())
+                             .short("a")
+                             .long("archive")
+                             .min_values(0)
+                             .takes_value(true))
+
+                        .arg(Arg::with_name("don't edit")
+                             .help(lformat!("Do not edit the file after creation").as_ref())
+                             .long("dont"))
                         )
 
+            .subcommand(SubCommand::with_name("import")
+                        .about(lformat!("Create projects from other sources").as_ref())
+                        .setting(AppSettings::SubcommandRequiredElseHelp)
+                        .subcommand(SubCommand::with_name("csv")
+                                    .about(lformat!("Create one project per row of a csv file, the header names the template keywords").as_ref())
+                                    .arg(Arg::with_name("file")
+                                         .help(lformat!("csv file, one project per row").as_ref())
+                                         .required(true))
+
+                                    .arg(Arg::with_name("template")
+                                         .help(lformat!("Use a specific template, unless a TEMPLATE column says otherwise").as_ref())
+                                         .short("t")
+                                         .long("template")
+                                         .takes_value(true))
+
+                                    .arg(Arg::with_name("map")
+                                         .help(lformat!("Map a column to a keyword, e.g. \"Kunde=CLIENT-NAME\"").as_ref())
+                                         .short("m")
+                                         .long("map")
+                                         .takes_value(true)
+                                         .multiple(true)
+                                         .number_of_values(1))
+
+                                    .arg(Arg::with_name("delimiter")
+                                         .help(lformat!("Column delimiter").as_ref())
+                                         .short("d")
+                                         .long("delimiter")
+                                         .takes_value(true)
+                                         .default_value(","))
+
+                                    .arg(Arg::with_name("dry-run")
+                                         .help(lformat!("Only check the rows, create nothing").as_ref())
+                                         .short("n")
+                                         .long("dry-run"))
+                                   )
+                        .subcommand(SubCommand::with_name("json")
+                                    .about(lformat!("Create one project per invoice exported from another tool").as_ref())
+                                    .arg(Arg::with_name("file")
+                                         .help(lformat!("exported json file").as_ref())
+                                         .required(true))
+
+                                    .arg(Arg::with_name("from")
+                                         .help(lformat!("Tool the file was exported from").as_ref())
+                                         .short("f")
+                                         .long("from")
+                                         .possible_values(&["invoiceninja", "generic"])
+                                         .default_value("invoiceninja"))
+
+                                    .arg(Arg::with_name("dry-run")
+                                         .help(lformat!("Only convert the invoices, create nothing").as_ref())
+                                         .short("n")
+                                         .long("dry-run"))
+                                   )
+                        .subcommand(SubCommand::with_name("eml")
+                                    .about(lformat!("Create a project from a booking request email").as_ref())
+                                    .arg(Arg::with_name("file")
+                                         .help(lformat!("email saved as .eml file").as_ref())
+                                         .required(true))
+
+                                    .arg(Arg::with_name("template")
+                                         .help(lformat!("Use a specific template").as_ref())
+                                         .short("t")
+                                         .long("template")
+                                         .takes_value(true))
+
+                                    .arg(Arg::with_name("dry-run")
+                                         .help(lformat!("Only print the project, create nothing").as_ref())
+                                         .short("n")
+                                         .long("dry-run"))
+                                   )
+                        .subcommand(SubCommand::with_name("legacy")
+                                    .about(lformat!("Convert projects of the old ruby ascii-invoicer into new projects").as_ref())
+                                    .arg(Arg::with_name("path")
+                                         .help(lformat!("old project file, or a folder to search for them").as_ref())
+                                         .required(true))
+
+                                    .arg(Arg::with_name("dry-run")
+                                         .help(lformat!("Only convert the files, create nothing").as_ref())
+                                         .short("n")
+                                         .long("dry-run"))
+                                   )
+                       )
+
             .subcommand(SubCommand::with_name("list")
                         .aliases(&["ls", "dir", "la", "l", "lsit"])
                         .about(lformat!("List Projects").as_ref())
@@ -149,6 +284,16 @@ pub fn with_cli<F> (app_handler:F) where F: Fn(App<'_, '_>) {
                              .short("n")
                              .conflicts_with("color")
                             )
+                        .arg(Arg::with_name("ascii")
+                             .help(lformat!("Use plain ascii instead of check marks").as_ref())
+                             .long("ascii")
+                            )
+                        .arg(Arg::with_name("output")
+                             .help(lformat!("Write to file instead, the format depends on the extension: txt, csv, json or html").as_ref())
+                             .long("output")
+                             .takes_value(true)
+                             .value_name("file")
+                            )
                         .arg(Arg::with_name("simple")
                              .help(lformat!("Show non-verbose list").as_ref())
                              .long("simple")
@@ -167,10 +312,9 @@ pub fn with_cli<F> (app_handler:F) where F: Fn(App<'_, '_>) {
                              .conflicts_with("csv")
                             )
                         .arg(Arg::with_name("sort")
-                             .help(lformat!("Sort by :").as_ref())
+                             .help(lformat!("Sort by date, index, name, manager or a custom computed field").as_ref())
                              .long("sort")
                              .short("s")
-                             .possible_values(&["date",  "index",  "name",  "manager"])
                              .takes_value(true)
                             )
                         .arg(Arg::with_name("all")
@@ -200,6 +344,11 @@ pub fn with_cli<F> (app_handler:F) where F: Fn(App<'_, '_>) {
                              .short("b")
                             )
 
+                        .arg(Arg::with_name("show_errors")
+                             .help(lformat!("Show which project files could not be opened and why").as_ref())
+                             .long("show-errors")
+                            )
+
                         .arg(Arg::with_name("computed_fields")
                              .help(lformat!("List all computed data fields that can be used with --details").as_ref())
                              .long("computed")
@@ -211,6 +360,12 @@ pub fn with_cli<F> (app_handler:F) where F: Fn(App<'_, '_>) {
                              .long("nothing")
                              .short("x")
                             )
+
+                        .arg(Arg::with_name("group")
+                             .help(lformat!("Show canceled and freshly archived projects in separate sections").as_ref())
+                             .long("group")
+                             .short("g")
+                            )
                         )
 
             .subcommand(SubCommand::with_name("open")
@@ -218,8 +373,8 @@ pub fn with_cli<F> (app_handler:F) where F: Fn(App<'_, '_>) {
                         .group(ArgGroup::with_name("flags")
                             .args(&[ "search_term", "templates", "output", "bin" ])
                          )
-                        .group(ArgGroup::with_name("bills")
-                            .args(&[ "offer", "invoice" ])
+                        .group(ArgGroup::with_name("artifact")
+                            .args(&[ "offer", "invoice", "dir", "yml" ])
                          )
                         .arg(Arg::with_name("search_term")
                              .help(lformat!("Search term, possibly event name").as_ref())
@@ -254,8 +409,34 @@ pub fn with_cli<F> (app_handler:F) where F: Fn(App<'_, '_>) {
                              .help(lformat!("Open offer file").as_ref())
                              .long("offer")
                             )
+
+                        .arg(Arg::with_name("dir")
+                             .help(lformat!("Open project folder (default)").as_ref())
+                             .long("dir")
+                             .short("d")
+                            )
+
+                        .arg(Arg::with_name("yml")
+                             .help(lformat!("Open project file in your editor").as_ref())
+                             .long("yml")
+                             .short("y")
+                            )
+
+                        .arg(Arg::with_name("archive")
+                             .help(lformat!("Pick an archived project").as_ref())
+                             .short("a")
+                             .long("archive")
+                             .min_values(0)
+                             .takes_value(true)
+                             .value_name("year")
+                            )
+
+                        .arg(Arg::with_name("editor")
+                             .help(lformat!("Override the configured editor").as_ref())
+                             .long("editor")
+                             .takes_value(true)
+                            )
                         )
-                        //# TODO: add --invoice and --offer
 
             .subcommand(SubCommand::with_name("edit")
                         .aliases(&["ed"])
@@ -316,6 +497,7 @@ pub fn with_cli<F> (app_handler:F) where F: Fn(App<'_, '_>) {
                              //.required(true)
                              .multiple(true)
                              .conflicts_with("all")
+                             .conflicts_with("fix-prefixes")
                             )
 
                         .arg(Arg::with_name("force")
@@ -336,6 +518,22 @@ pub fn with_cli<F> (app_handler:F) where F: Fn(App<'_, '_>) {
                              .short("y")
                              .takes_value(true)
                             )
+
+                        .arg(Arg::with_name("fix-prefixes")
+                             .help(lformat!("Renames the archived projects of a year to their invoice number").as_ref())
+                             .long("fix-prefixes")
+                             .value_name("year")
+                             .validator(validators::is_year)
+                             .conflicts_with("all")
+                            )
+
+                        .arg(Arg::with_name("reindex")
+                             .help(lformat!("Rewrites the .index.yml of the archive of a year").as_ref())
+                             .long("reindex")
+                             .value_name("year")
+                             .validator(validators::is_year)
+                             .conflicts_with_all(&["all", "search terms", "fix-prefixes"])
+                            )
                        )
 
             .subcommand(SubCommand::with_name("unarchive")
@@ -351,6 +549,15 @@ pub fn with_cli<F> (app_handler:F) where F: Fn(App<'_, '_>) {
                             )
                        )
 
+            .subcommand(SubCommand::with_name("close-year")
+                        .about(lformat!("Closes a year once every project of it is payed or canceled").as_ref())
+                        .arg(Arg::with_name("year")
+                             .help(lformat!("The year to close").as_ref())
+                             .required(true)
+                             .validator(validators::is_year)
+                            )
+                       )
+
             .subcommand(SubCommand::with_name("show")
                         .aliases(&["display"])
                         .about(lformat!("Display a specific project").as_ref())
@@ -371,8 +578,13 @@ pub fn with_cli<F> (app_handler:F) where F: Fn(App<'_, '_>) {
                              .short("C"))
 
                         .arg(Arg::with_name("yaml")
-                             .help(lformat!("Show project as raw yaml").as_ref())
-                             .long("yaml"))
+                             .help(lformat!("Show project as YAML, the same data as --json").as_ref())
+                             .long("yaml")
+                             .conflicts_with("json"))
+
+                        .arg(Arg::with_name("raw")
+                             .help(lformat!("Show the project file as parsed, without comments and anchors").as_ref())
+                             .long("raw"))
 
                         .arg(Arg::with_name("detail")
                              .help(lformat!("Shows a particular detail").as_ref())
@@ -424,6 +636,19 @@ pub fn with_cli<F> (app_handler:F) where F: Fn(App<'_, '_>) {
                              .short("o")
                             )
 
+                        .arg(Arg::with_name("compare")
+                             .help(lformat!("Display offer and invoice side by side").as_ref())
+                             .long("compare")
+                             .conflicts_with_all(&["offer", "invoice"])
+                            )
+
+                        .arg(Arg::with_name("output")
+                             .help(lformat!("Write to file instead, the format depends on the extension: txt, csv, json or html").as_ref())
+                             .long("output")
+                             .takes_value(true)
+                             .value_name("file")
+                            )
+
                         //.arg(Arg::with_name("hours") //# what used to be --caterers
                         //     .help(lformat!("Display hours").as_ref())
                         //     .long("hours")
@@ -435,6 +660,11 @@ pub fn with_cli<F> (app_handler:F) where F: Fn(App<'_, '_>) {
                              .short("c")
                             )
 
+                        .arg(Arg::with_name("ascii")
+                             .help(lformat!("Use plain ascii instead of box drawing characters").as_ref())
+                             .long("ascii")
+                            )
+
                         //.arg(Arg::with_name("markdown")
                         //     .help(lformat!("Show as markdown").as_ref())
                         //     .long("markdown")
@@ -442,16 +672,78 @@ pub fn with_cli<F> (app_handler:F) where F: Fn(App<'_, '_>) {
                         //    )
                     )
 
+            .subcommand(SubCommand::with_name("apply")
+                        .about(lformat!("Merge a yaml file into several projects and commit them at once").as_ref())
+                        .arg(Arg::with_name("patch")
+                             .help(lformat!("Yaml fragment that is merged into every project, ~ removes a key").as_ref())
+                             .required(true)
+                            )
+
+                        .arg(Arg::with_name("search_term")
+                             .help(lformat!("Search term, possibly event name").as_ref())
+                             .multiple(true)
+                            )
+
+                        .arg(Arg::with_name("dry-run")
+                             .help(lformat!("Only show what would change").as_ref())
+                             .long("dry-run")
+                             .short("n")
+                            )
+
+                        .arg(Arg::with_name("archive")
+                             .help(lformat!("List archived projects of a specific year, defaults to the current year").as_ref())
+                             .short("a")
+                             .long("archive")
+                             .min_values(0)
+                             .takes_value(true)
+                             .value_name("year")
+                            )
+
+                        .arg(Arg::with_name("year")
+                             .help(lformat!("List projects from that year, archived or not").as_ref())
+                             .short("y")
+                             .long("year")
+                             .min_values(0)
+                             .takes_value(true)
+                            )
+
+                        .arg(Arg::with_name("all")
+                             .help(lformat!("List all projects, ever").as_ref())
+                             .short("A")
+                             .long("all"))
+                        )
+
+            .subcommand(SubCommand::with_name("get")
+                        .about(lformat!("Print a value from a project file").as_ref())
+                        .arg(Arg::with_name("search_term")
+                             .help(lformat!("Search term, possibly event name").as_ref())
+                             .required(true)
+                            )
+
+                        .arg(Arg::with_name("field name")
+                             .help(lformat!("Which field to print, e.g. invoice/number").as_ref())
+                             .required(true)
+                            )
+
+                        .arg(Arg::with_name("archive")
+                             .help(lformat!("Pick an archived project").as_ref())
+                             .short("a")
+                             .long("archive")
+                             .min_values(0)
+                             .takes_value(true)
+                            )
+                        )
+
             .subcommand(SubCommand::with_name("set")
                         .aliases(&["ed"])
-                        .about(lformat!("Set a value in a project file").as_ref())
+                        .about(lformat!("Set a value in a project file and commit it").as_ref())
                         .arg(Arg::with_name("search_term")
                              .help(lformat!("Search term, possibly event name").as_ref())
                              .required(true)
                             )
 
                         .arg(Arg::with_name("field name")
-                             .help(lformat!("Which field to set").as_ref())
+                             .help(lformat!("Which field to set, e.g. invoice/payed_date").as_ref())
                              .required(true)
                             )
 
@@ -489,8 +781,8 @@ pub fn with_cli<F> (app_handler:F) where F: Fn(App<'_, '_>) {
                         .group(ArgGroup::with_name("flags")
                             .args(&[ "search_term", "templates", "output", "bin" ])
                          )
-                        .group(ArgGroup::with_name("bills")
-                            .args(&[ "offer", "invoice" ])
+                        .group(ArgGroup::with_name("artifact")
+                            .args(&[ "offer", "invoice", "dir", "yml" ])
                          )
                         .arg(Arg::with_name("search_term")
                              .help(lformat!("Search term, possibly event name").as_ref())
@@ -522,6 +814,27 @@ pub fn with_cli<F> (app_handler:F) where F: Fn(App<'_, '_>) {
                              .help(lformat!("Open offer file").as_ref())
                              .long("offer")
                             )
+
+                        .arg(Arg::with_name("dir")
+                             .help(lformat!("Show project folder (default)").as_ref())
+                             .long("dir")
+                             .short("d")
+                            )
+
+                        .arg(Arg::with_name("yml")
+                             .help(lformat!("Show project file").as_ref())
+                             .long("yml")
+                             .short("y")
+                            )
+
+                        .arg(Arg::with_name("archive")
+                             .help(lformat!("Pick an archived project").as_ref())
+                             .short("a")
+                             .long("archive")
+                             .min_values(0)
+                             .takes_value(true)
+                             .value_name("year")
+                            )
                         )
 
             .subcommand(SubCommand::with_name("workspace")
@@ -572,6 +885,14 @@ pub fn with_cli<F> (app_handler:F) where F: Fn(App<'_, '_>) {
                              .long("tasks")
                             )
 
+                        .arg(Arg::with_name("employee")
+                             .help(lformat!("Only the events this employee is assigned to in hours/caterers").as_ref())
+                             .short("e")
+                             .long("employee")
+                             .takes_value(true)
+                             .conflicts_with("tasks")
+                            )
+
                         .arg(Arg::with_name("year")
                              .help(lformat!("List projects from that year, archived or not").as_ref())
                              .short("y")
@@ -586,6 +907,73 @@ pub fn with_cli<F> (app_handler:F) where F: Fn(App<'_, '_>) {
                              .long("all"))
                        )
 
+            .subcommand(SubCommand::with_name("export")
+                        .about(lformat!("Export the relations between clients and projects, or projects without personal data").as_ref())
+                        .group(ArgGroup::with_name("format")
+                            .args(&[ "graphviz", "anonymize" ])
+                            .required(true)
+                         )
+                        .arg(Arg::with_name("graphviz")
+                             .help(lformat!("Clients, projects and follow-ups as a graphviz dot graph").as_ref())
+                             .long("graphviz")
+                            )
+
+                        .arg(Arg::with_name("anonymize")
+                             .help(lformat!("Project files with made up clients, manager and caterers, for bug reports and demos").as_ref())
+                             .long("anonymize")
+                            )
+
+                        .arg(Arg::with_name("search_term")
+                             .help(lformat!("Search term, possibly event name").as_ref())
+                             .multiple(true)
+                             .requires("anonymize")
+                            )
+
+                        .arg(Arg::with_name("all")
+                             .help(lformat!("Export all projects, ever").as_ref())
+                             .long("all")
+                            )
+
+                        .arg(Arg::with_name("output")
+                             .help(lformat!("Write the files into this directory, laid out like the storage").as_ref())
+                             .long("output")
+                             .short("o")
+                             .takes_value(true)
+                             .value_name("dir")
+                             .requires("anonymize")
+                            )
+
+                        .arg(Arg::with_name("archive")
+                             .help(lformat!("Only archived projects of a specific year, defaults to the current year").as_ref())
+                             .short("a")
+                             .long("archive")
+                             .min_values(0)
+                             .takes_value(true)
+                             .value_name("year")
+                             .conflicts_with("year")
+                            )
+
+                        .arg(Arg::with_name("year")
+                             .help(lformat!("Only projects from that year, archived or not").as_ref())
+                             .short("y")
+                             .long("year")
+                             .min_values(0)
+                             .takes_value(true)
+                            )
+                       )
+
+            .subcommand(SubCommand::with_name("digest")
+                        .about(lformat!("Summary of a week for the weekly meeting, as Markdown").as_ref())
+                        .long_about(lformat!("New projects, offers sent, invoices issued, payments received and the events of the week after, read from the projects and the history of the storage repository.").as_ref())
+                        .arg(Arg::with_name("week")
+                             .help(lformat!("The week, e.g. 2024-W10, defaults to last week").as_ref())
+                             .long("week")
+                             .min_values(0)
+                             .takes_value(true)
+                             .required(true)
+                            )
+                       )
+
             .subcommand(SubCommand::with_name("dues")
                         .about(lformat!("Experimental: open dues").as_ref())
 
@@ -603,57 +991,391 @@ pub fn with_cli<F> (app_handler:F) where F: Fn(App<'_, '_>) {
                             )
                        )
 
-            .subcommand(SubCommand::with_name("make")
-                        .about(lformat!("Creates documents from projects").as_ref())
-                        .aliases(&["mk"])
+            .subcommand(SubCommand::with_name("offers")
+                        .about(lformat!("Lists offers that are still open").as_ref())
 
-                        .arg(Arg::with_name("file")
-                             .help(lformat!("Manually pass a file path").as_ref())
-                             .long("file")
-                             .takes_value(true)
+                        .arg(Arg::with_name("expired")
+                             .help(lformat!("Only offers that ran out without an answer").as_ref())
+                             .long("expired")
+                             .short("e")
                             )
+                       )
 
-                        .arg(Arg::with_name("output")
-                             .help(lformat!("Manually pass a output folder").as_ref())
-                             .long("output")
-                             .takes_value(true)
+            .subcommand(SubCommand::with_name("timesheet")
+                        .about(lformat!("Printable timesheet of one employee for a month").as_ref())
+
+                        .arg(Arg::with_name("employee")
+                             .help(lformat!("Name of the employee").as_ref())
+                             .required(true)
                             )
 
-                        .arg(Arg::with_name("force")
-                             .help(lformat!("Do it against better judgement").as_ref())
-                             .long("force")
+                        .arg(Arg::with_name("month")
+                             .help(lformat!("Month as YYYY-MM, defaults to the current month").as_ref())
+                             .short("m")
+                             .long("month")
+                             .takes_value(true)
+                             .value_name("YYYY-MM")
                             )
 
-                        .arg(Arg::with_name("pdf-only")
-                             .help(lformat!("Only create the PDF file").as_ref())
-                             .long("pdf")
+                        .arg(Arg::with_name("csv")
+                             .help(lformat!("Show as csv").as_ref())
+                             .long("csv")
+                             .short("c")
                             )
 
-                        .arg(Arg::with_name("dry-run")
-                             .help(lformat!("Do not create final output file").as_ref())
-                             .short("d")
-                             .long("dry")
+                        .arg(Arg::with_name("ascii")
+                             .help(lformat!("Use plain ascii instead of box drawing characters").as_ref())
+                             .long("ascii")
                             )
+                       )
 
-                        .arg(Arg::with_name("print-only")
-                             .help(lformat!("Only prints to stdout").as_ref())
-                             .long("print")
+            .subcommand(SubCommand::with_name("staff")
+                        .about(lformat!("Upcoming events of a month with their required and assigned employees").as_ref())
+
+                        .arg(Arg::with_name("month")
+                             .help(lformat!("Month as YYYY-MM, defaults to the current month").as_ref())
+                             .value_name("YYYY-MM")
                             )
 
-                        .arg(Arg::with_name("open")
-                             .help(lformat!("Open the pdf file afterwards.").as_ref())
-                             .long("open")
+                        .arg(Arg::with_name("ascii")
+                             .help(lformat!("Use plain ascii instead of box drawing characters").as_ref())
+                             .long("ascii")
                             )
+                       )
+
+            .subcommand(SubCommand::with_name("report")
+                        .about(lformat!("Sums for the tax office").as_ref())
+                        .setting(AppSettings::SubcommandRequiredElseHelp)
+                        .subcommand(SubCommand::with_name("vat")
+                                    .about(lformat!("Revenue and VAT per tax rate of all invoices issued in a year or quarter").as_ref())
+                                    .arg(Arg::with_name("year")
+                                         .help(lformat!("Year of the invoice dates, defaults to the current year").as_ref())
+                                        )
+                                    .arg(Arg::with_name("quarter")
+                                         .help(lformat!("Only this quarter (1-4)").as_ref())
+                                         .short("q")
+                                         .long("quarter")
+                                         .takes_value(true)
+                                         .possible_values(&["1", "2", "3", "4"])
+                                        )
+                                    .arg(Arg::with_name("csv")
+                                         .help(lformat!("Show as csv").as_ref())
+                                         .long("csv")
+                                         .short("c")
+                                        )
+                                    .arg(Arg::with_name("ascii")
+                                         .help(lformat!("Use plain ascii instead of box drawing characters").as_ref())
+                                         .long("ascii")
+                                        )
+                                   )
+                        .subcommand(SubCommand::with_name("income")
+                                    .about(lformat!("Revenue, wages, expenses and profit per month of a year").as_ref())
+                                    .arg(Arg::with_name("year")
+                                         .help(lformat!("Year of the invoice dates, defaults to the current year").as_ref())
+                                        )
+                                    .arg(Arg::with_name("csv")
+                                         .help(lformat!("Show as csv").as_ref())
+                                         .long("csv")
+                                         .short("c")
+                                        )
+                                    .arg(Arg::with_name("json")
+                                         .help(lformat!("Show as json").as_ref())
+                                         .long("json")
+                                         .short("j")
+                                         .conflicts_with("csv")
+                                        )
+                                    .arg(Arg::with_name("ascii")
+                                         .help(lformat!("Use plain ascii instead of box drawing characters").as_ref())
+                                         .long("ascii")
+                                        )
+                                   )
+                        .subcommand(SubCommand::with_name("numbers")
+                                    .about(lformat!("Checks the invoice numbers of a year for gaps and duplicates, in the working dir, the archive and the ledger").as_ref())
+                                    .arg(Arg::with_name("year")
+                                         .help(lformat!("Year of the invoice dates, defaults to the current year").as_ref())
+                                        )
+                                   )
+                       )
+
+            .subcommand(SubCommand::with_name("stats")
+                        .about(lformat!("Figures for pricing decisions").as_ref())
+                        .setting(AppSettings::SubcommandRequiredElseHelp)
+                        .subcommand(SubCommand::with_name("clients")
+                                    .about(lformat!("Revenue, projects and payment delay per client, the best clients first").as_ref())
+                                    .arg(Arg::with_name("year")
+                                         .help(lformat!("Year of the invoice dates, defaults to all years").as_ref())
+                                        )
+                                    .arg(Arg::with_name("csv")
+                                         .help(lformat!("Show as csv").as_ref())
+                                         .long("csv")
+                                         .short("c")
+                                        )
+                                    .arg(Arg::with_name("ascii")
+                                         .help(lformat!("Use plain ascii instead of box drawing characters").as_ref())
+                                         .long("ascii")
+                                        )
+                                   )
+                       )
+
+            .subcommand(SubCommand::with_name("template")
+                        .about(lformat!("Create and check project and document templates").as_ref())
+                        .setting(AppSettings::SubcommandRequiredElseHelp)
+                        .subcommand(SubCommand::with_name("new")
+                                    .about(lformat!("Creates a project template with every placeholder explained").as_ref())
+                                    .arg(Arg::with_name("name")
+                                         .help(lformat!("Name of the new template").as_ref())
+                                         .required(true)
+                                        )
+                                   )
+                        .subcommand(SubCommand::with_name("check")
+                                    .about(lformat!("Renders every template against a sample project and reports unknown or missing placeholders").as_ref())
+                                   )
+                       )
+
+            .subcommand(SubCommand::with_name("user")
+                        .about(lformat!("Manage who can be responsible for projects").as_ref())
+                        .long_about(lformat!("Managers are listed in extras/users.yml of the storage. Without that file the only known manager is you, from user/name or git config.").as_ref())
+                        .setting(AppSettings::SubcommandRequiredElseHelp)
+                        .subcommand(SubCommand::with_name("list")
+                                    .about(lformat!("Lists the known managers").as_ref())
+                                   )
+                        .subcommand(SubCommand::with_name("add")
+                                    .about(lformat!("Adds a manager").as_ref())
+                                    .arg(Arg::with_name("name")
+                                         .help(lformat!("Full name, as used in the manager field").as_ref())
+                                         .required(true)
+                                        )
+                                    .arg(Arg::with_name("email")
+                                         .help(lformat!("Email address of the manager").as_ref())
+                                         .long("email")
+                                         .takes_value(true)
+                                        )
+                                   )
+                        .subcommand(SubCommand::with_name("remove")
+                                    .about(lformat!("Removes a manager").as_ref())
+                                    .arg(Arg::with_name("name")
+                                         .help(lformat!("Full name, as used in the manager field").as_ref())
+                                         .required(true)
+                                        )
+                                   )
+                       )
+
+            .subcommand(SubCommand::with_name("client")
+                        .about(lformat!("Tidy up the clients of the projects").as_ref())
+                        .setting(AppSettings::SubcommandRequiredElseHelp)
+                        .subcommand(SubCommand::with_name("dedupe")
+                                    .about(lformat!("Finds clients that are written differently in different projects").as_ref())
+                                    .long_about(lformat!("Compares the names and emails of the clients of all projects, \"Müller GmbH\" and \"Mueller GmbH\" are likely the same. The spelling in extras/clients.yml or the most used one is suggested.").as_ref())
+                                    .arg(Arg::with_name("rewrite")
+                                         .help(lformat!("Asks for every group and writes the suggested spelling into the other projects").as_ref())
+                                         .long("rewrite")
+                                        )
+                                   )
+                       )
+
+            .subcommand(SubCommand::with_name("extras")
+                        .about(lformat!("Manage the extra files of the storage").as_ref())
+                        .setting(AppSettings::SubcommandRequiredElseHelp)
+                        .subcommand(SubCommand::with_name("branding")
+                                    .about(lformat!("Sets up logo, signature, footer and bank details of the documents").as_ref())
+                                    .long_about(lformat!("Asks for each part of the letterhead, files are copied to extras/branding/ and everything is written to extras/branding.yml.").as_ref())
+                                    .arg(Arg::with_name("show")
+                                         .help(lformat!("Only print extras/branding.yml").as_ref())
+                                         .long("show")
+                                        )
+                                   )
+                       )
+
+            .subcommand(SubCommand::with_name("hours")
+                        .about(lformat!("Hours and wages per employee and project").as_ref())
 
                         .arg(Arg::with_name("search_term")
                              .help(lformat!("Search term, possibly event name").as_ref())
                              .multiple(true)
-                             )
+                            )
 
-                        .arg(Arg::with_name("offer")
-                             .help(lformat!("Produce an offer document").as_ref())
-                             .long("offer")
-                             .conflicts_with("invoice")
+                        .arg(Arg::with_name("employee")
+                             .help(lformat!("Only show this employee").as_ref())
+                             .short("e")
+                             .long("employee")
+                             .takes_value(true)
+                             .value_name("name")
+                            )
+
+                        .arg(Arg::with_name("archive")
+                             .help(lformat!("List archived projects of a specific year, defaults to the current year").as_ref())
+                             .short("a")
+                             .long("archive")
+                             .min_values(0)
+                             .takes_value(true)
+                             .value_name("year")
+                            )
+
+                        .arg(Arg::with_name("year")
+                             .help(lformat!("List projects from that year, archived or not").as_ref())
+                             .short("y")
+                             .long("year")
+                             .min_values(0)
+                             .takes_value(true)
+                            )
+
+                        .arg(Arg::with_name("all")
+                             .help(lformat!("List all projects, ever").as_ref())
+                             .short("A")
+                             .long("all"))
+
+                        .arg(Arg::with_name("ascii")
+                             .help(lformat!("Use plain ascii instead of box drawing characters").as_ref())
+                             .long("ascii")
+                            )
+                       )
+
+            .subcommand(SubCommand::with_name("validate")
+                        .about(lformat!("Checks projects for invalid and missing fields").as_ref())
+
+                        .arg(Arg::with_name("search_term")
+                             .help(lformat!("Search term, possibly event name").as_ref())
+                             .multiple(true)
+                            )
+
+                        .arg(Arg::with_name("format")
+                             .help(lformat!("Output format").as_ref())
+                             .long("format")
+                             .takes_value(true)
+                             .possible_values(&["text", "json"])
+                             .default_value("text")
+                            )
+
+                        .arg(Arg::with_name("strict")
+                             .help(lformat!("Also warn about keys that are not known, like a misspelled cilent, see validation/strict_keys").as_ref())
+                             .long("strict")
+                            )
+
+                        .arg(Arg::with_name("archive")
+                             .help(lformat!("Validate archived projects of a specific year, defaults to the current year").as_ref())
+                             .short("a")
+                             .long("archive")
+                             .min_values(0)
+                             .takes_value(true)
+                             .value_name("year")
+                            )
+
+                        .arg(Arg::with_name("year")
+                             .help(lformat!("Validate projects from that year, archived or not").as_ref())
+                             .short("y")
+                             .long("year")
+                             .min_values(0)
+                             .takes_value(true)
+                            )
+
+                        .arg(Arg::with_name("all")
+                             .help(lformat!("Validate all projects, ever").as_ref())
+                             .short("A")
+                             .long("all"))
+                       )
+
+            .subcommand(SubCommand::with_name("fmt")
+                        .about(lformat!("Normalizes indentation, section order and dates of project files").as_ref())
+
+                        .arg(Arg::with_name("search_term")
+                             .help(lformat!("Search term, possibly event name").as_ref())
+                             .multiple(true)
+                            )
+
+                        .arg(Arg::with_name("check")
+                             .help(lformat!("Only list files that are not formatted, fails if there are any").as_ref())
+                             .long("check")
+                            )
+
+                        .arg(Arg::with_name("archive")
+                             .help(lformat!("Format archived projects of a specific year, defaults to the current year").as_ref())
+                             .short("a")
+                             .long("archive")
+                             .min_values(0)
+                             .takes_value(true)
+                             .value_name("year")
+                            )
+
+                        .arg(Arg::with_name("year")
+                             .help(lformat!("Format projects from that year, archived or not").as_ref())
+                             .short("y")
+                             .long("year")
+                             .min_values(0)
+                             .takes_value(true)
+                            )
+
+                        .arg(Arg::with_name("all")
+                             .help(lformat!("Format all projects, ever").as_ref())
+                             .short("A")
+                             .long("all"))
+                       )
+
+            .subcommand(SubCommand::with_name("doctor")
+                        .about(lformat!("Checks your setup and suggests fixes").as_ref())
+                        .arg(Arg::with_name("fix")
+                             .help(lformat!("Create missing directories, repair permissions and initialize git first").as_ref())
+                             .long("fix")
+                            )
+                       )
+
+            .subcommand(SubCommand::with_name("make")
+                        .about(lformat!("Creates documents from projects").as_ref())
+                        .aliases(&["mk"])
+
+                        .arg(Arg::with_name("file")
+                             .help(lformat!("Manually pass a file path").as_ref())
+                             .long("file")
+                             .takes_value(true)
+                            )
+
+                        .arg(Arg::with_name("output")
+                             .help(lformat!("Manually pass a output folder").as_ref())
+                             .long("output")
+                             .takes_value(true)
+                            )
+
+                        .arg(Arg::with_name("force")
+                             .help(lformat!("Do it against better judgement").as_ref())
+                             .long("force")
+                            )
+
+                        .arg(Arg::with_name("pdf-only")
+                             .help(lformat!("Only create the PDF file").as_ref())
+                             .long("pdf")
+                            )
+
+                        .arg(Arg::with_name("dry-run")
+                             .help(lformat!("Do not create final output file").as_ref())
+                             .short("d")
+                             .long("dry")
+                            )
+
+                        .arg(Arg::with_name("print-only")
+                             .help(lformat!("Only prints to stdout").as_ref())
+                             .long("print")
+                            )
+
+                        .arg(Arg::with_name("open")
+                             .help(lformat!("Open the pdf file afterwards.").as_ref())
+                             .long("open")
+                            )
+
+                        .arg(Arg::with_name("watch")
+                             .help(lformat!("Rebuild whenever the project file or template changes").as_ref())
+                             .long("watch")
+                             .short("w")
+                             .conflicts_with_all(&["dry-run", "print-only", "pdf-only"])
+                            )
+
+                        .arg(Arg::with_name("search_term")
+                             .help(lformat!("Search term, possibly event name").as_ref())
+                             .multiple(true)
+                             )
+
+                        .arg(Arg::with_name("offer")
+                             .help(lformat!("Produce an offer document").as_ref())
+                             .long("offer")
+                             .conflicts_with("invoice")
                              )
 
                         .arg(Arg::with_name("invoice")
@@ -675,6 +1397,258 @@ pub fn with_cli<F> (app_handler:F) where F: Fn(App<'_, '_>) {
                              .long("template")
                              .takes_value(true)
                              )
+
+                        .arg(Arg::with_name("backend")
+                             .help(lformat!("Render with this backend instead of the one the template is made for").as_ref())
+                             .long("backend")
+                             .takes_value(true)
+                             .possible_values(&["latex", "typst", "pandoc"])
+                             )
+                       )
+
+            .subcommand(SubCommand::with_name("mail")
+                        .about(lformat!("Sends the offer or invoice to the client").as_ref())
+                        .group(ArgGroup::with_name("delivery")
+                            .args(&[ "draft", "mailto", "dry-run" ])
+                         )
+
+                        .arg(Arg::with_name("search_term")
+                             .help(lformat!("Search term, possibly event name").as_ref())
+                             .required(true)
+                             .multiple(true)
+                            )
+
+                        .arg(Arg::with_name("offer")
+                             .help(lformat!("Send the offer").as_ref())
+                             .long("offer")
+                             .conflicts_with("invoice")
+                            )
+
+                        .arg(Arg::with_name("invoice")
+                             .help(lformat!("Send the invoice (default)").as_ref())
+                             .long("invoice")
+                            )
+
+                        .arg(Arg::with_name("draft")
+                             .help(lformat!("Save the mail as .eml draft instead of sending it").as_ref())
+                             .long("draft")
+                             .takes_value(true)
+                             .value_name("file")
+                            )
+
+                        .arg(Arg::with_name("mailto")
+                             .help(lformat!("Open the mail in your mail client instead of sending it").as_ref())
+                             .long("mailto")
+                            )
+
+                        .arg(Arg::with_name("dry-run")
+                             .help(lformat!("Print the mail instead of sending it").as_ref())
+                             .long("dry-run")
+                             .short("n")
+                            )
+
+                        .arg(Arg::with_name("archive")
+                             .help(lformat!("Pick an archived project").as_ref())
+                             .short("a")
+                             .long("archive")
+                             .min_values(0)
+                             .takes_value(true)
+                             .value_name("year")
+                            )
+                       )
+
+            .subcommand(SubCommand::with_name("sent")
+                        .about(lformat!("Marks the offer or invoice as sent or the offer as accepted").as_ref())
+                        .arg(Arg::with_name("search_term")
+                             .help(lformat!("Search term, possibly event name").as_ref())
+                             .required(true)
+                             .multiple(true)
+                            )
+
+                        .arg(Arg::with_name("offer")
+                             .help(lformat!("The offer was sent").as_ref())
+                             .long("offer")
+                             .conflicts_with("invoice")
+                            )
+
+                        .arg(Arg::with_name("invoice")
+                             .help(lformat!("The invoice was sent (default)").as_ref())
+                             .long("invoice")
+                            )
+
+                        .arg(Arg::with_name("accepted")
+                             .help(lformat!("The client accepted the offer").as_ref())
+                             .long("accepted")
+                             .conflicts_with_all(&["offer", "invoice"])
+                            )
+
+                        .arg(Arg::with_name("date")
+                             .help(lformat!("When it happened, defaults to today").as_ref())
+                             .long("date")
+                             .short("d")
+                             .takes_value(true)
+                             .validator(validators::is_dmy)
+                            )
+
+                        .arg(Arg::with_name("archive")
+                             .help(lformat!("Pick an archived project").as_ref())
+                             .short("a")
+                             .long("archive")
+                             .min_values(0)
+                             .takes_value(true)
+                             .value_name("year")
+                            )
+                       )
+
+            .subcommand(SubCommand::with_name("accept")
+                        .about(lformat!("Records that the client accepted the offer").as_ref())
+                        .arg(Arg::with_name("search_term")
+                             .help(lformat!("Search term, possibly event name").as_ref())
+                             .required(true)
+                             .multiple(true)
+                            )
+
+                        .arg(Arg::with_name("via")
+                             .help(lformat!("How the offer was accepted").as_ref())
+                             .long("via")
+                             .takes_value(true)
+                             .possible_values(asciii::project::spec::ACCEPTANCE_METHODS)
+                            )
+
+                        .arg(Arg::with_name("signed_copy")
+                             .help(lformat!("Attaches the signed offer, implies --via signed").as_ref())
+                             .long("signed-copy")
+                             .takes_value(true)
+                             .value_name("file")
+                            )
+
+                        .arg(Arg::with_name("remote")
+                             .help(lformat!("Upload the signed offer to the WebDAV server instead").as_ref())
+                             .long("remote")
+                             .requires("signed_copy")
+                            )
+
+                        .arg(Arg::with_name("date")
+                             .help(lformat!("When it happened, defaults to today").as_ref())
+                             .long("date")
+                             .short("d")
+                             .takes_value(true)
+                             .validator(validators::is_dmy)
+                            )
+
+                        .arg(Arg::with_name("archive")
+                             .help(lformat!("Pick an archived project").as_ref())
+                             .short("a")
+                             .long("archive")
+                             .min_values(0)
+                             .takes_value(true)
+                             .value_name("year")
+                            )
+                       )
+
+            .subcommand(SubCommand::with_name("paylink")
+                        .about(lformat!("Creates a payment link for the invoice or checks which links were payed").as_ref())
+                        .long_about(lformat!("The link is created with Stripe or PayPal over the invoice total and stored as invoice/paylink. With --poll every invoice with a link that is not payed yet is looked up and marked payed once the provider says so.").as_ref())
+                        .arg(Arg::with_name("search_term")
+                             .help(lformat!("Search term, possibly event name").as_ref())
+                             .required_unless("poll")
+                             .multiple(true)
+                            )
+
+                        .arg(Arg::with_name("provider")
+                             .help(lformat!("Payment provider, defaults to paylink/provider").as_ref())
+                             .long("provider")
+                             .short("p")
+                             .takes_value(true)
+                             .possible_values(&["stripe", "paypal"])
+                            )
+
+                        .arg(Arg::with_name("poll")
+                             .help(lformat!("Mark invoices payed whose links were payed").as_ref())
+                             .long("poll")
+                             .conflicts_with_all(&["provider", "force", "dry-run"])
+                            )
+
+                        .arg(Arg::with_name("force")
+                             .help(lformat!("Replace an existing link").as_ref())
+                             .long("force")
+                             .short("f")
+                            )
+
+                        .arg(Arg::with_name("dry-run")
+                             .help(lformat!("Only show what the link would ask for").as_ref())
+                             .long("dry-run")
+                             .short("n")
+                            )
+
+                        .arg(Arg::with_name("archive")
+                             .help(lformat!("Pick an archived project").as_ref())
+                             .short("a")
+                             .long("archive")
+                             .min_values(0)
+                             .takes_value(true)
+                             .value_name("year")
+                            )
+                       )
+
+            .subcommand(SubCommand::with_name("attach")
+                        .about(lformat!("Adds files to a project").as_ref())
+                        .long_about(lformat!("Files are copied next to the project file. With --remote they are uploaded to attachments/webdav/url instead and listed under attachments in the project file, so large files stay out of git.").as_ref())
+                        .arg(Arg::with_name("search_term")
+                             .help(lformat!("Search term, possibly event name").as_ref())
+                             .required(true)
+                            )
+
+                        .arg(Arg::with_name("file")
+                             .help(lformat!("Files to attach").as_ref())
+                             .required(true)
+                             .multiple(true)
+                            )
+
+                        .arg(Arg::with_name("remote")
+                             .help(lformat!("Upload to the WebDAV server").as_ref())
+                             .long("remote")
+                             .short("r")
+                            )
+
+                        .arg(Arg::with_name("archive")
+                             .help(lformat!("Pick an archived project").as_ref())
+                             .short("a")
+                             .long("archive")
+                             .min_values(0)
+                             .takes_value(true)
+                             .value_name("year")
+                            )
+                       )
+
+            .subcommand(SubCommand::with_name("undo")
+                        .about(lformat!("Reverts the last change to your projects").as_ref())
+                        .arg(Arg::with_name("list")
+                             .help(lformat!("List the journal of recorded changes").as_ref())
+                             .short("l")
+                             .long("list"))
+                       )
+
+            .subcommand(SubCommand::with_name("redo")
+                        .about(lformat!("Applies the last undone change again").as_ref())
+                       )
+
+            .subcommand(SubCommand::with_name("merge")
+                        .about(lformat!("Merges two projects about the same event").as_ref())
+                        .long_about(lformat!("Keeps the richer project, fills in what it lacks from the other one and asks about fields they disagree on. Attachments are moved over, the other project is deleted after confirmation.").as_ref())
+                        .arg(Arg::with_name("a")
+                             .help(lformat!("Search term, possibly event name").as_ref())
+                             .required(true)
+                            )
+                        .arg(Arg::with_name("b")
+                             .help(lformat!("Search term, possibly event name").as_ref())
+                             .required(true)
+                            )
+                        .arg(Arg::with_name("yes")
+                             .help(lformat!("Take the longer value on every conflict and delete without asking").as_ref())
+                             .long("yes")
+                             .short("y")
+                            )
                        )
 
             .subcommand(SubCommand::with_name("delete")
@@ -753,6 +1727,39 @@ pub fn with_cli<F> (app_handler:F) where F: Fn(App<'_, '_>) {
                              .long("init")
                             )
 
+                        .subcommand(SubCommand::with_name("list")
+                                    .about(lformat!("Lists every config key with its value").as_ref())
+                                    .arg(Arg::with_name("verbose")
+                                         .help(lformat!("Also show type, default and description").as_ref())
+                                         .short("v")
+                                         .long("verbose")
+                                        )
+                                   )
+
+                        .subcommand(SubCommand::with_name("get")
+                                    .about(lformat!("Prints the value of a config key").as_ref())
+                                    .arg(Arg::with_name("key")
+                                         .help(lformat!("Key like list/sort").as_ref())
+                                         .required(true)
+                                        )
+                                   )
+
+                        .subcommand(SubCommand::with_name("set")
+                                    .about(lformat!("Sets a config key in your config file").as_ref())
+                                    .arg(Arg::with_name("key")
+                                         .help(lformat!("Key like list/sort").as_ref())
+                                         .required(true)
+                                        )
+                                    .arg(Arg::with_name("value")
+                                         .help(lformat!("New value, as yaml").as_ref())
+                                         .required(true)
+                                        )
+                                   )
+
+                        .subcommand(SubCommand::with_name("edit")
+                                    .about(lformat!("Edit your config and check it afterwards").as_ref())
+                                   )
+
                         )
 
             .subcommand(SubCommand::with_name("shell")
@@ -760,6 +1767,15 @@ pub fn with_cli<F> (app_handler:F) where F: Fn(App<'_, '_>) {
                         .about(lformat!("(experimental) starts interactive shell").as_ref())
                        )
 
+            .subcommand(SubCommand::with_name("daemon")
+                        .about(lformat!("Answer JSON-RPC requests for editor integration").as_ref())
+                        .arg(Arg::with_name("socket")
+                             .help(lformat!("Listen on a unix socket instead of stdin/stdout").as_ref())
+                             .long("socket")
+                             .takes_value(true)
+                            )
+                       )
+
             .subcommand(SubCommand::with_name("whoami")
                         .about(lformat!("Show your name from config").as_ref())
                        )
@@ -768,6 +1784,10 @@ pub fn with_cli<F> (app_handler:F) where F: Fn(App<'_, '_>) {
             .subcommand(SubCommand::with_name("status")
                         .about(lformat!("Show the working tree status").as_ref())
                         .aliases(&["st"])
+                        .arg(Arg::with_name("json")
+                             .help(lformat!("Summary of the whole storage as JSON instead, for monitoring").as_ref())
+                             .long("json")
+                            )
                        )
 
             .subcommand(SubCommand::with_name("pull")
@@ -864,7 +1884,8 @@ pub fn with_cli<F> (app_handler:F) where F: Fn(App<'_, '_>) {
 
             .subcommand(SubCommand::with_name("log")
                         .aliases(&["lg", "hist", "history"])
-                        .about(lformat!("Show commit logs").as_ref())
+                        .about(lformat!("Show what happened to projects lately").as_ref())
+                        .long_about(lformat!("Reads the history of the storage repository and shows per project when it was created, edited, payed or archived.").as_ref())
                         .arg(Arg::with_name("search_term")
                              .help(lformat!("Search term, possibly event name").as_ref())
                              .multiple(true)
@@ -881,6 +1902,16 @@ pub fn with_cli<F> (app_handler:F) where F: Fn(App<'_, '_>) {
                              .short("t")
                              .long("template")
                             )
+                        .arg(Arg::with_name("since")
+                             .help(lformat!("How far back, e.g. 3d, 2w, 6m, 1y or a date").as_ref())
+                             .long("since")
+                             .takes_value(true)
+                             .default_value("2w")
+                            )
+                        .arg(Arg::with_name("raw")
+                             .help(lformat!("Show the plain git log instead").as_ref())
+                             .long("raw")
+                            )
                        )
 
             .subcommand(SubCommand::with_name("remote")
@@ -945,21 +1976,30 @@ pub fn with_cli<F> (app_handler:F) where F: Fn(App<'_, '_>) {
 }
 
 /// Starting point for handling commandline matches
-pub fn match_matches(matches: &ArgMatches<'_>) {
+/// Runs the subcommand and reports errors, returns the exit code, see `exit`.
+pub fn match_matches(matches: &ArgMatches<'_>) -> i32 {
     let res = match matches.subcommand() {
      ("bootstrap", Some(sub_m)) => subcommands::bootstrap(sub_m),
+     ("init",      Some(sub_m)) => subcommands::init(sub_m),
      ("list",      Some(sub_m)) => subcommands::list(sub_m),
      ("csv",       Some(sub_m)) => subcommands::csv(sub_m),
      ("new",       Some(sub_m)) => subcommands::new(sub_m),
+     ("duplicate", Some(sub_m)) => subcommands::duplicate(sub_m),
+     ("import",    Some(sub_m)) => subcommands::import(sub_m),
      ("edit",      Some(sub_m)) => subcommands::edit(sub_m),
      ("meta",      Some(sub_m)) => subcommands::meta(sub_m),
      ("workspace", Some(sub_m)) => subcommands::workspace(sub_m),
+     ("apply",     Some(sub_m)) => subcommands::apply(sub_m),
+     ("get",       Some(sub_m)) => subcommands::get(sub_m),
      ("set",       Some(sub_m)) => subcommands::set(sub_m),
      ("invoice",   Some(sub_m)) => subcommands::invoice(sub_m),
      ("show",      Some(sub_m)) => subcommands::show(sub_m),
      ("calendar",  Some(sub_m)) => subcommands::calendar(sub_m),
+     ("export",    Some(sub_m)) => subcommands::export(sub_m),
+     ("digest",    Some(sub_m)) => subcommands::digest(sub_m),
      ("archive",   Some(sub_m)) => subcommands::archive(sub_m),
      ("unarchive", Some(sub_m)) => subcommands::unarchive(sub_m),
+     ("close-year", Some(sub_m)) => subcommands::close_year(sub_m),
      ("config",    Some(sub_m)) => subcommands::config(sub_m),
      ("whoami",    _          ) => subcommands::config_show("user/name"),
      ("nocommand", Some(sub_m)) => subcommands::no_command(sub_m),
@@ -970,7 +2010,15 @@ pub fn match_matches(matches: &ArgMatches<'_>) {
      ("open",      Some(sub_m)) => subcommands::open_path(sub_m),
 
      ("make",      Some(sub_m)) => subcommands::make(sub_m),
+     ("mail",      Some(sub_m)) => subcommands::mail(sub_m),
+     ("sent",      Some(sub_m)) => subcommands::sent(sub_m),
+     ("accept",    Some(sub_m)) => subcommands::accept(sub_m),
+     ("paylink",   Some(sub_m)) => subcommands::paylink(sub_m),
+     ("attach",    Some(sub_m)) => subcommands::attach(sub_m),
      ("delete",    Some(sub_m)) => subcommands::delete(sub_m),
+     ("merge",     Some(sub_m)) => subcommands::merge(sub_m),
+     ("undo",      Some(sub_m)) => subcommands::undo(sub_m),
+     ("redo",      _          ) => subcommands::redo(),
      ("spec",      Some(sub_m)) => subcommands::spec(sub_m),
 
      ("doc",       _          ) => subcommands::doc(),
@@ -978,13 +2026,27 @@ pub fn match_matches(matches: &ArgMatches<'_>) {
      ("version",   Some(sub_m)) => subcommands::version(sub_m),
 
      ("dues",      Some(sub_m)) => subcommands::dues(sub_m),
+     ("offers",    Some(sub_m)) => subcommands::offers(sub_m),
+     ("hours",     Some(sub_m)) => subcommands::hours(sub_m),
+     ("timesheet", Some(sub_m)) => subcommands::timesheet(sub_m),
+     ("staff",     Some(sub_m)) => subcommands::staff(sub_m),
+     ("report",    Some(sub_m)) => subcommands::report(sub_m),
+     ("stats",     Some(sub_m)) => subcommands::stats(sub_m),
+     ("template",  Some(sub_m)) => subcommands::template(sub_m),
+     ("user",      Some(sub_m)) => subcommands::user(sub_m),
+     ("client",    Some(sub_m)) => subcommands::client(sub_m),
+     ("extras",    Some(sub_m)) => subcommands::extras(sub_m),
+     ("validate",  Some(sub_m)) => subcommands::validate(sub_m),
+     ("fmt",       Some(sub_m)) => subcommands::fmt(sub_m),
+     ("doctor",    Some(sub_m)) => subcommands::doctor(sub_m),
      ("shell",     Some(sub_m)) => subcommands::shell(sub_m),
+     ("daemon",    Some(sub_m)) => subcommands::daemon(sub_m),
 
      ("remote",    _          ) => subcommands::git_remote(),
      ("pull",      Some(sub_m)) => subcommands::git_pull(sub_m),
      ("diff",      Some(sub_m)) => subcommands::git_diff(sub_m),
      ("cleanup",   Some(sub_m)) => subcommands::git_cleanup(sub_m),
-     ("status",    _          ) => subcommands::git_status(),
+     ("status",    Some(sub_m)) => subcommands::status(sub_m),
      ("add",       Some(sub_m)) => subcommands::git_add(sub_m),
      ("commit",    _          ) => subcommands::git_commit(),
      ("push",      _          ) => subcommands::git_push(),
@@ -992,6 +2054,7 @@ pub fn match_matches(matches: &ArgMatches<'_>) {
      ("pop",       _          ) => subcommands::git_stash_pop(),
      ("log",       Some(sub_m)) => subcommands::git_log(sub_m),
      ("complete",  Some(sub_m)) => generate_completions(sub_m),
+     (external,    Some(sub_m)) => subcommands::plugin(external, sub_m),
      _                          => Err(format_err!("unhandled command"))
     };
     if let Err(e) = res {
@@ -1001,6 +2064,9 @@ pub fn match_matches(matches: &ArgMatches<'_>) {
             log::error!("{} (Cause: {})", e, e.root_cause());
             log::info!("use --debug to see a backtrace");
         }
+        super::exit::code_of(&e)
+    } else {
+        super::exit::OK
     }
 }
 
@@ -1022,4 +2088,11 @@ pub mod validators {
             None => Err(lformat!("Date Format must be DD.MM.YYYY")),
         }
     }
+
+    pub fn is_year(val: String) -> Result<(), String> {
+        match val.parse::<i32>() {
+            Ok(_) => Ok(()),
+            Err(_) => Err(lformat!("{:?} is not a year", val)),
+        }
+    }
 }
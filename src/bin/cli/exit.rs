@@ -0,0 +1,55 @@
+//! Exit codes, so scripts can tell failures apart.
+//!
+//! | code | meaning                                                          |
+//! |------|------------------------------------------------------------------|
+//! | 0    | success                                                          |
+//! | 1    | any other error, also wrong arguments                            |
+//! | 2    | `validate`, `template check` or `doctor` found problems          |
+//! | 3    | nothing matches the search term, or a project or template is missing |
+//! | 4    | the storage is missing, misconfigured or git failed              |
+
+use anyhow::Error;
+
+use asciii::error::{ActionError, AsciiiError, StorageError};
+
+pub const OK: i32 = 0;
+pub const ERROR: i32 = 1;
+pub const VALIDATION_FAILED: i32 = 2;
+pub const NOT_FOUND: i32 = 3;
+pub const STORAGE_ERROR: i32 = 4;
+
+/// Exit code for an error, decided by the first cause asciii knows about.
+pub fn code_of(error: &Error) -> i32 {
+    for cause in error.chain() {
+        let code = if let Some(error) = cause.downcast_ref::<AsciiiError>() {
+            code_of_asciii_error(error)
+        } else {
+            cause.downcast_ref::<ActionError>().and_then(code_of_action_error)
+        };
+        if let Some(code) = code {
+            return code;
+        }
+    }
+    ERROR
+}
+
+fn code_of_asciii_error(error: &AsciiiError) -> Option<i32> {
+    match error {
+        AsciiiError::Action(error) => code_of_action_error(error),
+        AsciiiError::Storage(StorageError::NothingFound(_)) |
+        AsciiiError::Storage(StorageError::ProjectDoesNotExist) |
+        AsciiiError::Storage(StorageError::NoProjectFile(_)) |
+        AsciiiError::Storage(StorageError::TemplateNotFound) |
+        AsciiiError::Storage(StorageError::UnknownTemplate(..)) => Some(NOT_FOUND),
+        AsciiiError::Storage(_) | AsciiiError::Git(_) => Some(STORAGE_ERROR),
+        _ => None
+    }
+}
+
+fn code_of_action_error(error: &ActionError) -> Option<i32> {
+    match error {
+        ActionError::NothingFound(_) => Some(NOT_FOUND),
+        ActionError::ValidationFailed(_) => Some(VALIDATION_FAILED),
+        _ => None
+    }
+}
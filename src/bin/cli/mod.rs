@@ -7,6 +7,7 @@
 /// Contains concrete implementation of each subcommand
 pub mod app;
 pub mod subcommands;
+pub mod exit;
 
 #[cfg(feature="shell")]
 pub mod shell;
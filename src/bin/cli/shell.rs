@@ -4,14 +4,25 @@ use rustyline::Editor;
 use rustyline::Result as LineResult;
 
 use asciii::CONFIG;
+use asciii::actions::users;
+use asciii::project::Project;
+use asciii::storage::{self, cache, StorageDir};
 
 use std::collections::BTreeSet;
-use clap::App;
+use clap::{App, ArgMatches};
 use anyhow::Error;
 use super::app::with_cli;
 
 static ESCAPE_CHAR: Option<char> = Some('\\');
 
+/// Commands that leave the repository alone, git statuses stay cached after these.
+///
+/// `doctor` only while it doesn't `--fix` anything, see `is_read_only()`.
+static READ_ONLY_COMMANDS: &[&str] = &[
+    "list", "show", "path", "open", "get", "hours", "validate", "doctor", "dues", "offers",
+    "calendar", "staff", "export", "digest", "csv", "spec", "whoami", "version", "status", "diff", "log", "help",
+];
+
 struct ClapCompleter{
     commands: Vec<String>
 }
@@ -32,20 +43,63 @@ impl ClapCompleter {
                         .cloned()
                         .collect())
     }
+
+    /// Names of the project folders in the working directory.
+    pub fn project_names(start: &str) -> Vec<String> {
+        storage::setup::<Project>()
+            .and_then(|storage| storage.list_project_folders(StorageDir::Working))
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|dir| dir.file_name().and_then(|name| name.to_str()))
+            .filter(|name| name.starts_with(start))
+            .map(ToOwned::to_owned)
+            .collect()
+    }
+
+    /// Names of the known managers, see `asciii user`.
+    pub fn manager_names(start: &str) -> Vec<String> {
+        storage::setup::<Project>()
+            .and_then(|storage| users::load(&storage))
+            .unwrap_or_default()
+            .into_iter()
+            .map(|user| user.name)
+            .filter(|name| name.starts_with(start))
+            .collect()
+    }
 }
 
 impl completion::Completer for ClapCompleter {
     fn complete(&self, line: &str, pos: usize) -> LineResult<(usize, Vec<String>)> {
-        let break_chars = BTreeSet::new();
+        let break_chars = [' '].iter().cloned().collect();
         let (start, path) = completion::extract_word(line, pos, &break_chars);
         //let path = completion::unescape(path, ESCAPE_CHAR);
-        let matches = self.naive_complete(path, ESCAPE_CHAR, &break_chars)?;
+
+        // the first word is a command, everything after that is most likely a project
+        let previous = line[..start].split_whitespace().last();
+        let matches = if previous.is_none() {
+            self.naive_complete(path, ESCAPE_CHAR, &break_chars)?
+        } else if previous == Some("--manager") || previous == Some("manager") {
+            Self::manager_names(path)
+        } else {
+            Self::project_names(path)
+        };
         Ok((start, matches))
     }
 }
 
+/// Whether the command left the repository alone, judged by its arguments too.
+fn is_read_only(matches: &ArgMatches<'_>) -> bool {
+    match matches.subcommand() {
+        (name, Some(sub_m)) => READ_ONLY_COMMANDS.contains(&name) && !sub_m.is_present("fix"),
+        _ => false
+    }
+}
+
 pub fn launch_shell() -> Result<(), Error> {
 
+    // projects are parsed once and git statuses are only read again after they might have changed
+    cache::enable();
+
     with_cli( |mut app| {
 
 
@@ -83,18 +137,28 @@ pub fn launch_shell() -> Result<(), Error> {
                     continue
                 }
 
+                if line.trim() == "reload" {
+                    cache::clear();
+                    continue
+                }
+
                 // this operators are not allowed
                 if line.contains('>') || line.contains('>') || line.contains('|') {
                     log::error!("What do you think this is? A shell?");
                 }
 
-                let mut argv: Vec<_> = line.trim().split(' ').collect();
+                let mut argv: Vec<_> = line.split_whitespace().collect();
 
                 // you have to insert the binary name since clap expects it
                 argv.insert(0, "prog");
                 log::debug!("shell: {} -> {:?}", line, argv);
                 match app.get_matches_from_safe_borrow(argv) {
-                    Ok(matches) => super::match_matches(&matches),
+                    Ok(matches) => {
+                        super::match_matches(&matches);
+                        if !is_read_only(&matches) {
+                            cache::clear_statuses();
+                        }
+                    },
                     Err(e) => println!("{}", e.message)
                 }
 
@@ -1,8 +1,11 @@
 use clap::ArgMatches;
 use anyhow::{bail, format_err, Error};
 
-use asciii::{storage, util};
+use asciii::{print, storage, util};
+use asciii::actions::activity;
+use asciii::actions::error::ActionError;
 use asciii::project::Project;
+use asciii::storage::Storable;
 
 use super::matches_to_paths;
 
@@ -11,11 +14,39 @@ pub fn git_log(matches: &ArgMatches<'_>) -> Result<(), Error> {
     let storage = storage::setup_with_git::<Project>()?;
     let paths = matches_to_paths(matches, &storage)?;
     let repo = storage.repository().unwrap();
-    if !repo.log(&paths).success() {
-        bail!(format_err!("git log did not exit successfully"));
+    if matches.is_present("raw") || matches.is_present("template") {
+        if !repo.log(&paths).success() {
+            bail!(format_err!("git log did not exit successfully"));
+        }
+        return Ok(());
+    }
+
+    let paths = if let Some(search_terms) = matches.values_of("search_term") {
+        if paths.is_empty() {
+            bail!(ActionError::NothingFound(search_terms.map(ToOwned::to_owned).collect()));
+        }
+        paths
     } else {
-        Ok(())
+        vec![storage.working_dir().to_owned(), storage.archive_dir().to_owned()]
+    };
+    let since = activity::since(matches.value_of("since").unwrap_or("2w"));
+    let archive = storage.archive_dir().strip_prefix(storage.root_dir()).unwrap_or_else(|_| storage.archive_dir());
+    let log = repo.activity(&since, &paths)?;
+    let activities = activity::parse_log(&log, &archive.to_string_lossy(), &Project::file_extension());
+
+    if matches.is_present("porcelain") {
+        for activity in &activities {
+            println!("{}", print::porcelain::activity_line(activity));
+        }
+        return Ok(());
     }
+    for (project, activities) in activity::by_project(&activities) {
+        println!("{}", project);
+        for activity in activities {
+            println!("  {}  {:<12} {}", activity.date, activity.author, activity.change);
+        }
+    }
+    Ok(())
 }
 
 /// Command STATUS
@@ -1,11 +1,14 @@
 
+use std::path::Path;
+
 use chrono::prelude::*;
 use clap::ArgMatches;
 use anyhow::Error;
 
 use asciii::CONFIG;
+use asciii::util::timing;
 use asciii::print::{self, ListConfig, ListMode};
-use asciii::project::{Project, ComputedField};
+use asciii::project::{computed_field, Project, ComputedField};
 use asciii::project::spec::IsProject;
 use asciii::storage::*;
 
@@ -23,7 +26,8 @@ pub fn list(matches: &ArgMatches<'_>) -> Result<(), Error> {
                                     matches.is_present("verbose"),
                                     matches.is_present("paths"),
                                     matches.is_present("nothing"),
-                                    matches.is_present("csv"));
+                                    matches.is_present("csv"),
+                                    matches.is_present("porcelain"));
 
         let extra_details = matches.values_of("details")
                                    .map(Iterator::collect);
@@ -37,6 +41,7 @@ pub fn list(matches: &ArgMatches<'_>) -> Result<(), Error> {
             filter_by: matches.values_of("filter")
                               .map(Iterator::collect),
             show_errors: matches.is_present("errors"),
+            output: matches.value_of("output").map(Path::new),
 
             ..Default::default()
         };
@@ -47,6 +52,12 @@ pub fn list(matches: &ArgMatches<'_>) -> Result<(), Error> {
         if matches.is_present("no-colors") {
             list_config.use_colors = false;
         }
+        if matches.is_present("ascii") {
+            list_config.ascii = true;
+        }
+        if matches.is_present("group") {
+            list_config.group = true;
+        }
 
         // list archive of year `archive`
         let dir = if matches.is_present("archive") {
@@ -76,7 +87,7 @@ pub fn list(matches: &ArgMatches<'_>) -> Result<(), Error> {
         if matches.is_present("broken") {
                list_broken_projects(dir)?; // XXX Broken
            } else {
-               list_projects(dir, &list_config)?;
+               list_projects(dir, &list_config, matches.is_present("show_errors"))?;
            }
         Ok(())
     }
@@ -90,8 +101,9 @@ pub fn list(matches: &ArgMatches<'_>) -> Result<(), Error> {
 /// * `print::simple_rows()`
 /// * `print::verbose_rows()`
 ///
-/// which it prints with `print::print_projects()`
-fn list_projects(dir: StorageDir, list_config: &ListConfig<'_>) -> Result<(), Error> {
+/// which it prints with `print::print_projects()`.
+/// With `show_errors` the files that could not be opened follow on stderr.
+fn list_projects(dir: StorageDir, list_config: &ListConfig<'_>, show_errors: bool) -> Result<(), Error> {
     let storage = if CONFIG.get_bool("list/gitstatus") {
         setup_with_git::<Project>()?
     } else {
@@ -99,7 +111,12 @@ fn list_projects(dir: StorageDir, list_config: &ListConfig<'_>) -> Result<(), Er
     };
     log::debug!("listing projects: {}", storage.working_dir().display());
 
-    let mut projects = storage.open_projects(dir)?;
+    let (mut projects, failures) = if show_errors {
+        storage.open_projects_with_failures(dir)?
+    } else {
+        (storage.open_projects(dir)?, Vec::new())
+    };
+    let _timer = timing::start("rendering");
 
     // filtering, can you read this
     if let Some(ref filters) = list_config.filter_by {
@@ -107,52 +124,78 @@ fn list_projects(dir: StorageDir, list_config: &ListConfig<'_>) -> Result<(), Er
     }
 
     // sorting
+    // keys are computed once per project, not once per comparison
     match list_config.sort_by {
-        "manager" => projects.sort_by(|pa, pb| pa.responsible().cmp(&pb.responsible())),
-        "date" => projects.sort_by(|pa, pb| pa.modified_date().cmp(&pb.modified_date())),
-        "name" => projects.sort_by(|pa, pb| pa.short_desc().cmp(&pb.short_desc())),
-        "index" => {
-            projects.sort_by(|pa, pb| {
-                                 pa.index()
-                                   .unwrap_or_else(|| "zzzz".to_owned())
-                                   .cmp(&pb.index().unwrap_or_else(|| "zzzz".to_owned()))
-                             })
-        } // TODO: rename to ident
-        _ => {
-            projects.sort_by(|pa, pb| {
-                                 pa.index()
-                                   .unwrap_or_else(|| "zzzz".to_owned())
-                                   .cmp(&pb.index().unwrap_or_else(|| "zzzz".to_owned()))
-                             })
+        "manager" => projects.sort_by_cached_key(|p| p.responsible().ok().map(ToOwned::to_owned)),
+        "date" => projects.sort_by_cached_key(Project::modified_date),
+        "name" => projects.sort_by_cached_key(Project::short_desc),
+        key if computed_field::custom_field_names().iter().any(|name| name == key) => {
+            projects.sort_by_cached_key(|p| p.field(key))
+        }
+        // TODO: rename to ident
+        _ => SortOrder::with_config(&CONFIG).sort(&mut projects),
+    }
+
+    if let Some(output) = list_config.output {
+        print::write_projects(output, &projects, list_config)?;
+        print_failures(&failures);
+        return Ok(());
+    }
+
+    // revenue per month when looking at a whole year
+    if let StorageDir::Year(year) | StorageDir::Archive(year) = dir {
+        if list_config.mode == ListMode::Simple || list_config.mode == ListMode::Verbose {
+            print::print_revenue_sparkline(&projects, year, list_config.ascii);
         }
     }
 
     // fit screen
     let wide_enough = true;
 
-    if !wide_enough && list_config.mode != ListMode::Csv {
+    let plain = list_config.mode == ListMode::Csv || list_config.mode == ListMode::Porcelain;
+    if !wide_enough && !plain {
         // TODO: room for improvement
         print::print_projects(print::simple_rows(&projects, list_config));
+    } else if list_config.group && !plain {
+        for (section, projects) in print::group_projects(projects, storage.archive_dir()) {
+            if let Some(header) = section.header() {
+                println!("\n{}:", header);
+            }
+            print::print_projects(print::rows(&projects, list_config));
+        }
     } else {
         log::debug!("list_mode: {:?}", list_config.mode);
         match list_config.mode {
             ListMode::Csv => print::print_csv(&projects),
+            ListMode::Porcelain => print::print_porcelain(&projects),
             ListMode::Paths => print::print_projects(print::path_rows(&projects, list_config)),
             ListMode::Simple => print::print_projects(print::simple_rows(&projects, list_config)),
             ListMode::Verbose => print::print_projects(print::verbose_rows(&projects, list_config)),
             ListMode::Nothing => print::print_projects(print::dynamic_rows(&projects, list_config)),
         }
     }
+    print_failures(&failures);
     Ok(())
 }
 
+/// Command LIST --show-errors
+fn print_failures(failures: &[OpenFailure]) {
+    if failures.is_empty() {
+        return;
+    }
+    eprintln!("{}", lformat!("{} project files could not be opened:", failures.len()));
+    for failure in failures {
+        eprintln!("  {}", failure);
+    }
+}
+
 /// Command LIST --broken
 fn list_broken_projects(dir: StorageDir) -> Result<(), Error> {
     let storage = setup::<Project>()?;
     let invalid_files = storage.list_project_folders(dir)?;
     let errors = invalid_files.iter()
                             .filter_map(|dir| Project::open_folder(dir).err())
-                            .collect::<Vec<anyhow::Error>>();
+                            .collect::<Vec<_>>();
 
     for err in errors {
         println!("{}", err);
@@ -183,13 +226,17 @@ pub fn list_computed_fields() -> Result<(), Error> {
     println!("{:?}",
              ComputedField::iter_variant_names()
                  .filter(|v| *v != "Invalid")
-                 .collect::<Vec<&str>>());
+                 .map(ToOwned::to_owned)
+                 .chain(computed_field::custom_field_names())
+                 .collect::<Vec<String>>());
     Ok(())
 }
 
 //#[deprecated(note="move to impl ListMode and then to asciii::actions")]
-fn decide_mode(simple: bool, verbose: bool, paths: bool, nothing: bool, csv: bool) -> ListMode {
-    if csv {
+fn decide_mode(simple: bool, verbose: bool, paths: bool, nothing: bool, csv: bool, porcelain: bool) -> ListMode {
+    if porcelain {
+        ListMode::Porcelain
+    } else if csv {
         ListMode::Csv
     } else if nothing {
         ListMode::Nothing
@@ -10,10 +10,11 @@ use anyhow::{bail, format_err, Error, Context};
 use chrono::prelude::*;
 use yaml_rust::Yaml;
 
-use asciii::{self, CONFIG, config, util, actions};
+use asciii::{self, CONFIG, config, util, actions, print};
 use asciii::project::Exportable;
 
 use asciii::project::Project;
+use asciii::project::spec::IsProject;
 use asciii::storage::*;
 use asciii::actions::error::ActionError;
 use asciii::templater::Templater;
@@ -68,6 +69,11 @@ pub fn new(matches: &ArgMatches<'_>) -> Result<(), Error> {
 
     let edit = !matches.is_present("don't edit");
     let storage = setup::<Project>()?;
+    let variables = if matches.is_present("interactive") {
+        storage.template_variables(template_name)?
+    } else {
+        Vec::new()
+    };
 
     let mut fill_data: HashMap<&str, String> = HashMap::new();
 
@@ -96,6 +102,21 @@ pub fn new(matches: &ArgMatches<'_>) -> Result<(), Error> {
         fill_data.insert("MANAGER", manager.to_owned());
     }
 
+    for variable in &variables {
+        if fill_data.contains_key(variable.name.as_str()) {
+            continue;
+        }
+        while let Some(value) = util::ask(variable.question(), variable.default.as_deref()) {
+            match variable.check(&value) {
+                Ok(()) => {
+                    fill_data.insert(&variable.name, value);
+                    break;
+                }
+                Err(e) => println!("{}", e),
+            }
+        }
+    }
+
     let project_file = storage.create_project(project_name, template_name, &fill_data)?.file();
     if edit {
         util::pass_to_command(editor, &[project_file])?;
@@ -103,11 +124,168 @@ pub fn new(matches: &ArgMatches<'_>) -> Result<(), Error> {
     Ok(())
 }
 
+/// Command DUPLICATE
+pub fn duplicate(matches: &ArgMatches<'_>) -> Result<(), Error> {
+    let search_term = matches.value_of("search_term").expect("You did not pass a search term!");
+    let editor = CONFIG.get("user/editor").and_then(Yaml::as_str);
+    let storage = setup::<Project>()?;
+
+    let projects = storage.search_projects(matches_to_dir(matches), search_term)?;
+    let project = match projects.len() {
+        0 => bail!(ActionError::NothingFound(vec![search_term.to_owned()])),
+        1 => &projects[0],
+        n => bail!(lformat!("{:?} matches {} projects, please be more specific", search_term, n)),
+    };
+
+    let new_name = match matches.value_of("name") {
+        Some(name) => name.to_owned(),
+        None => lformat!("{} (copy)", project.name()?),
+    };
+
+    let content = project.duplicate_content(&new_name)?;
+    let copy = storage.create_project_from_content(&new_name, &content)?;
+    log::info!("{}", lformat!("copied {} to {}", project.short_desc(), copy.file().display()));
+
+    if !matches.is_present("don't edit") {
+        util::pass_to_command(editor, &[copy.file()])?;
+    }
+    Ok(())
+}
+
+/// Command IMPORT
+pub fn import(matches: &ArgMatches<'_>) -> Result<(), Error> {
+    match matches.subcommand() {
+        ("csv", Some(sub_m)) => import_csv(sub_m),
+        ("legacy", Some(sub_m)) => import_legacy(sub_m),
+        ("json", Some(sub_m)) => import_json(sub_m),
+        ("eml", Some(sub_m)) => import_eml(sub_m),
+        _ => bail!(format_err!("unhandled command"))
+    }
+}
+
+/// Command IMPORT CSV
+fn import_csv(matches: &ArgMatches<'_>) -> Result<(), Error> {
+    use asciii::actions::import;
+
+    let file = Path::new(matches.value_of("file").unwrap());
+    let template = matches.value_of("template")
+        .or_else(|| CONFIG.get_str_or("template"))
+        .unwrap_or("default");
+    let delimiter = match matches.value_of("delimiter").unwrap_or(",").as_bytes() {
+        [delimiter] => *delimiter,
+        _ => bail!(lformat!("the delimiter must be a single character")),
+    };
+    let mapping = matches.values_of("map")
+        .map(|maps| maps.map(|map| map.split_once('=')
+                                      .map(|(column, keyword)| (column.to_owned(), keyword.to_owned()))
+                                      .ok_or_else(|| format_err!("{}", lformat!("{:?} is not of the form COLUMN=KEYWORD", map))))
+                        .collect::<Result<Vec<_>, Error>>())
+        .transpose()?
+        .unwrap_or_default();
+
+    let storage = setup::<Project>()?;
+    let rows = import::csv_rows(file, &mapping, delimiter)?;
+    let outcomes = import::create_projects(&storage, &rows, template, matches.is_present("dry-run"));
+
+    for outcome in &outcomes {
+        println!("{}", outcome);
+    }
+
+    let failed = outcomes.iter().filter(|o| !o.is_ok()).count();
+    println!("{}", lformat!("{} of {} rows imported", outcomes.len() - failed, outcomes.len()));
+    if failed > 0 {
+        bail!(lformat!("{} rows failed", failed));
+    }
+    Ok(())
+}
+
+/// Command IMPORT JSON
+#[cfg(feature="serialization")]
+fn import_json(matches: &ArgMatches<'_>) -> Result<(), Error> {
+    use asciii::actions::import::foreign;
+
+    let file = Path::new(matches.value_of("file").unwrap());
+    let adapter = foreign::adapter(matches.value_of("from").unwrap())?;
+    let content = fs::read_to_string(file)
+        .with_context(|| lformat!("can't read {}", file.display()))?;
+    let invoices = adapter.read(&content)?;
+
+    let storage = setup::<Project>()?;
+    let outcomes = foreign::create_projects(&storage, &invoices, matches.is_present("dry-run"));
+
+    for outcome in &outcomes {
+        println!("{}", outcome);
+    }
+
+    let failed = outcomes.iter().filter(|o| !o.is_ok()).count();
+    println!("{}", lformat!("{} of {} projects imported", outcomes.len() - failed, outcomes.len()));
+    if failed > 0 {
+        bail!(lformat!("{} projects failed", failed));
+    }
+    Ok(())
+}
+
+#[cfg(not(feature="serialization"))]
+fn import_json(_matches: &ArgMatches<'_>) -> Result<(), Error> {
+    bail!(format_err!("Importing json is not built-in with this release!"));
+}
+
+/// Command IMPORT EML
+fn import_eml(matches: &ArgMatches<'_>) -> Result<(), Error> {
+    use asciii::actions::import::eml;
+
+    let file = Path::new(matches.value_of("file").unwrap());
+    let template = matches.value_of("template")
+        .or_else(|| CONFIG.get_str_or("template"))
+        .unwrap_or("default");
+
+    let storage = setup::<Project>()?;
+    let (project_file, content) = eml::import(&storage, file, template, matches.is_present("dry-run"))
+        .with_context(|| lformat!("can't import {}", file.display()))?;
+
+    if matches.is_present("dry-run") {
+        print!("{}", content);
+    } else {
+        println!("{}", lformat!("created {}", project_file.display()));
+    }
+    Ok(())
+}
+
+/// Command IMPORT LEGACY
+fn import_legacy(matches: &ArgMatches<'_>) -> Result<(), Error> {
+    use asciii::actions::import::legacy;
+
+    let path = Path::new(matches.value_of("path").unwrap());
+    let storage = setup::<Project>()?;
+    let outcomes = legacy::import(&storage, path, matches.is_present("dry-run"))?;
+
+    for outcome in &outcomes {
+        println!("{}", outcome);
+    }
+
+    let failed = outcomes.iter().filter(|o| !o.is_ok()).count();
+    println!("{}", lformat!("{} of {} projects imported", outcomes.len() - failed, outcomes.len()));
+    if failed > 0 {
+        bail!(lformat!("{} projects failed", failed));
+    }
+    Ok(())
+}
+
 fn matches_to_selection(matches: &ArgMatches<'_>) -> StorageSelection {
     let (search_terms, dir) = matches_to_search(matches);
     StorageSelection::DirAndSearch(dir, search_terms.into_iter().map(ToOwned::to_owned).collect())
 }
 
+/// Like `matches_to_selection()`, but selects the whole directory if no search term is given.
+fn matches_to_dir_selection(matches: &ArgMatches<'_>) -> StorageSelection {
+    let (search_terms, dir) = matches_to_search(matches);
+    if search_terms.is_empty() {
+        StorageSelection::Dir(dir)
+    } else {
+        matches_to_selection(matches)
+    }
+}
+
 fn matches_to_dir(matches: &ArgMatches<'_>) -> StorageDir {
         if matches.is_present("archive"){
             let archive_year = matches.value_of("archive")
@@ -200,6 +378,39 @@ pub fn bootstrap(matches: &ArgMatches<'_>) -> Result<(), Error> {
     Ok(())
 }
 
+/// Command INIT
+///
+/// Creates the storage directories, puts the default templates in place and initializes a git repository.
+pub fn init(matches: &ArgMatches<'_>) -> Result<(), Error> {
+    let root = match matches.value_of("to") {
+        Some(to) => env::current_dir()?.join(to),
+        None => get_storage_path()
+    };
+
+    let storage = Storage::<Project>::builder().root(&root).build()?;
+    storage.create_dirs()?;
+    for path in storage.materialize_default_templates()? {
+        println!("{}", lformat!("created {}", path.display()));
+    }
+    if matches.is_present("demo") {
+        if fs::read_dir(storage.working_dir())?.next().is_some() {
+            bail!(lformat!("{} already has projects, the demo needs an empty storage", root.display()));
+        }
+        let count = actions::demo::populate(&storage, Utc::today())?;
+        println!("{}", lformat!("created {} demo projects", count));
+    }
+    actions::init_repository(&root)?;
+    if matches.is_present("demo") {
+        let storage = Storage::<Project>::builder().root(&root).git(true).build()?;
+        if let Some(repo) = storage.repository() {
+            repo.add_all();
+            repo.commit_paths(std::slice::from_ref(&root), &lformat!("demo storage"));
+        }
+    }
+    println!("{}", lformat!("storage ready in {}", root.display()));
+    Ok(())
+}
+
 
 /// Command CSV
 pub fn csv(matches: &ArgMatches<'_>) -> Result<(), Error> {
@@ -224,7 +435,7 @@ pub fn edit(matches: &ArgMatches<'_>) -> Result<(), Error> {
                   .and_then(Yaml::as_str));
 
     if matches.is_present("template") {
-        with_templates(search_term, |template_paths:&[PathBuf]| util::pass_to_command(editor, template_paths))?;
+        with_templates(search_term, |template_paths:&[PathBuf]| Ok(util::pass_to_command(editor, template_paths)?))?;
 
     } else if let Some(archive) = matches.value_of("archive") {
         let archive = archive.parse::<i32>().unwrap();
@@ -251,6 +462,8 @@ fn edit_projects(dir: StorageDir, search_terms: &[&str], editor: Option<&str>) -
     if all_projects.is_empty() {
         bail!(ActionError::NothingFound(search_terms.iter().map(ToString::to_string).collect()));
     } else {
+        // held until the editor is closed
+        let _locks = all_projects.iter().map(|project| storage.lock(project)).collect::<Result<Vec<_>, _>>()?;
         let all_paths = all_projects.iter().map(Storable::file).collect::<Vec<PathBuf>>();
         util::pass_to_command(editor, &all_paths)?;
         Ok(())
@@ -317,43 +530,139 @@ pub fn with_templates<F>(name: &str, action: F) -> Result<(), Error>
 
 /// Command SET
 pub fn set(m: &ArgMatches<'_>) -> Result<(), Error> {
-    let field = m.value_of("field name")
-                            .unwrap()
-                            .chars()
-                            .flat_map(char::to_uppercase)
-                            .collect::<String>();
-    let value = m.value_of("field value").unwrap();
+    let field = m.value_of("field name").unwrap();
+    let keyword = field.chars()
+                       .flat_map(char::to_uppercase)
+                       .collect::<String>();
+    let value = m.value_of("field value");
     let (search_terms, dir) = matches_to_search(m);
 
-    actions::with_projects(dir, &search_terms, |project| {
-        println!("{}: {}", project.short_desc(), project.empty_fields().join(", "));
-        if !project.empty_fields().contains(&field) {
-            return Err(format_err!("{:?} was not found in {}", field, project.short_desc()));
-        }
-        if util::really(&format!("do you want to set the field {} in {:?}",
-                                 field,
-                                 project.short_desc())) {
-            project.replace_field(&field, value)
-        } else {
-            Err(format_err!("Don't want to"))
+    let storage = setup_with_git::<Project>()?;
+    let projects = storage.search_projects_any(dir, &search_terms)?;
+    if projects.is_empty() {
+        bail!(ActionError::NothingFound(search_terms.iter().map(ToString::to_string).collect()));
+    }
+
+    for project in projects {
+        let value = value.ok_or_else(|| format_err!("{}", lformat!("No value given for {:?}", field)))?;
+        storage.ensure_open(&project)?;
+        let _lock = storage.lock(&project)?;
+
+        // fields still marked in the template are filled in, everything else goes through the spec
+        if project.empty_fields().contains(&keyword) {
+            if !util::really(&format!("do you want to set the field {} in {:?}",
+                                     keyword,
+                                     project.short_desc())) {
+                bail!(format_err!("Don't want to"));
+            }
+            project.replace_field(&keyword, value)?;
+        } else if !project.set_field(field, value)? {
+            log::info!("{}", lformat!("{} already is {:?} in {}", field, value, project.short_desc()));
+            continue;
         }
-    })?;
+
+        let message = lformat!("set {} to {:?} in {}", field, value, project.short_desc());
+        storage.record(&message, vec![storage.journal().write(&project.file(), Some(project.file_content()))]);
+        storage.field_changed(&project.file(), field);
+
+        if let Some(repo) = storage.repository() {
+            let paths = [project.file()];
+            if !(repo.add(&paths).success() && repo.commit_paths(&paths, &message).success()) {
+                bail!(format_err!("git commit did not exit successfully"));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Command APPLY
+pub fn apply(m: &ArgMatches<'_>) -> Result<(), Error> {
+    let patch_file = Path::new(m.value_of("patch").unwrap());
+    let patch = fs::read_to_string(patch_file)
+        .with_context(|| lformat!("can't read {}", patch_file.display()))?;
+    if util::yaml::parse(&patch)?.as_hash().is_none() {
+        bail!(lformat!("{} has to be a mapping, like a project file", patch_file.display()));
+    }
+
+    let storage = setup_with_git::<Project>()?;
+    let projects = storage.open_projects(matches_to_dir_selection(m))?;
+
+    // check every project before writing anything
+    let mut changes = Vec::new();
+    for project in projects.iter() {
+        storage.ensure_open(project)?;
+        let content = project.content_with_patch(&patch)
+                             .with_context(|| project.short_desc())?;
+        if content != project.file_content() {
+            changes.push((project, content));
+        }
+    }
+
+    if changes.is_empty() {
+        log::info!("{}", lformat!("nothing to change"));
+        return Ok(());
+    }
+
+    if m.is_present("dry-run") {
+        for (project, content) in &changes {
+            println!("{}", project.file().display());
+            print!("{}", util::line_diff(project.file_content(), content));
+        }
+        return Ok(());
+    }
+
+    // nobody else may be editing any of them, and nobody did since they were read
+    let _locks = changes.iter()
+                        .map(|(project, _)| storage.lock(*project))
+                        .collect::<Result<Vec<_>, _>>()?;
+    for (project, _) in &changes {
+        if fs::read_to_string(project.file())? != project.file_content() {
+            bail!(lformat!("{} was changed in the meantime, please apply again", project.short_desc()));
+        }
+    }
+    for (project, content) in &changes {
+        project.write_content(content)?;
+    }
+
+    let message = lformat!("apply {} to {} projects", patch_file.display(), changes.len());
+    storage.record(&message, changes.iter()
+                                    .map(|(project, _)| storage.journal().write(&project.file(), Some(project.file_content())))
+                                    .collect());
+    for (project, content) in &changes {
+        let merged = util::yaml::parse(content)?;
+        for field in asciii::project::spec::changed_fields(project.yaml(), &merged) {
+            storage.field_changed(&project.file(), field);
+        }
+    }
+
+    if let Some(repo) = storage.repository() {
+        let paths = changes.iter().map(|(project, _)| project.file()).collect::<Vec<_>>();
+        if !(repo.add(&paths).success() && repo.commit_paths(&paths, &message).success()) {
+            bail!(format_err!("git commit did not exit successfully"));
+        }
+    }
     Ok(())
 }
 
+/// Command GET
+pub fn get(m: &ArgMatches<'_>) -> Result<(), Error> {
+    let field = m.value_of("field name").unwrap();
+    let (search_terms, dir) = matches_to_search(m);
+
+    actions::with_projects(dir, &search_terms, |project| {
+        let value = project.field(field)
+                           .ok_or_else(|| format_err!("{}", lformat!("{:?} not found in {}", field, project.short_desc())))?;
+        println!("{}", value);
+        Ok(())
+    })
+}
+
 
 /// Command INVOICE
 pub fn invoice(m: &ArgMatches<'_>) -> Result<(), Error> {
     let storage = setup::<Project>()?;
-    let dir = StorageDir::Year(Utc::today().year());
-    let projects = storage.open_projects(dir)?;
-    let invoice_number = 1 + projects.iter()
-                             .filter_map(|p| p.field("invoice/number"))
-                             .map(|s| s.parse::<u32>())
-                             .filter_map(Result::ok)
-                             .max()
-                             .unwrap_or(0);
-    let value = invoice_number.to_string();
+    let year = Utc::today().year();
+    let ledger = storage.ledger();
 
     let (search_terms, dir) = matches_to_search(m);
 
@@ -362,10 +671,20 @@ pub fn invoice(m: &ArgMatches<'_>) -> Result<(), Error> {
         if !project.empty_fields().iter().any(|s| s == field) {
             return Err(format_err!("Invoice number already set in {}", project.short_desc()));
         }
+        // every project gets its own number, they go through the ledger
+        let number = actions::next_invoice_number(&storage, year)?;
+        let value = number.to_string();
         if util::really(&lformat!("Do you want to set the invoice number in {:?} to {}?",
                                  project.short_desc(),
                                  value)) {
-            project.replace_field(field, &value)
+            let ledger_before = fs::read_to_string(ledger.file()).ok();
+            ledger.issue(year, number, &project.short_desc())?;
+            project.replace_field(field, &value)?;
+            storage.record(&lformat!("set invoice number {} in {}", value, project.short_desc()),
+                           vec![storage.journal().write(&project.file(), Some(project.file_content())),
+                                storage.journal().write(ledger.file(), ledger_before.as_deref())]);
+            storage.field_changed(&project.file(), "invoice/number");
+            Ok(())
         } else {
             Err(format_err!("Don't want to"))
         }
@@ -376,12 +695,93 @@ pub fn invoice(m: &ArgMatches<'_>) -> Result<(), Error> {
 
 /// Command CALENDAR
 pub fn calendar(matches: &ArgMatches<'_>) -> Result<(), Error> {
+    if let Some(employee) = matches.value_of("employee") {
+        let projects = setup::<Project>()?.open_projects(matches_to_dir(matches))?;
+        println!("{}", actions::staffing::schedule(projects.iter(), employee));
+        return Ok(());
+    }
     let calendar = actions::calendar_with_tasks(matches_to_dir(matches), matches.is_present("tasks"))?;
     println!("{}", calendar);
     Ok(())
 }
 
 
+/// Command EXPORT
+///
+/// The graph shows every project unless `--archive` or `--year` narrow it down, follow-ups span years.
+pub fn export(matches: &ArgMatches<'_>) -> Result<(), Error> {
+    if matches.is_present("anonymize") {
+        return export_anonymized(matches);
+    }
+    if !matches.is_present("graphviz") {
+        bail!(lformat!("please choose a format, --graphviz or --anonymize"));
+    }
+    let dir = if matches.is_present("archive") || matches.is_present("year") {
+        matches_to_dir(matches)
+    } else {
+        StorageDir::All
+    };
+    let projects = setup::<Project>()?.open_projects(dir)?;
+    print!("{}", actions::graph::dot(&projects));
+    Ok(())
+}
+
+/// Prints the selected projects as one yaml stream, or writes them below `--output`.
+fn export_anonymized(matches: &ArgMatches<'_>) -> Result<(), Error> {
+    let storage = setup::<Project>()?;
+    let projects = storage.open_projects(matches_to_dir_selection(matches))?;
+    let target = matches.value_of("output").map(Path::new);
+    for project in projects.iter() {
+        let content = actions::anonymize::anonymize(project)?;
+        match target {
+            Some(target) => {
+                let file = project.file();
+                let file = file.strip_prefix(storage.root_dir()).unwrap_or(&file);
+                let file = target.join(actions::anonymize::Fakes::of(project)?.path(file));
+                if let Some(dir) = file.parent() {
+                    fs::create_dir_all(dir)?;
+                }
+                fs::write(&file, content)?;
+                log::info!("{}", lformat!("wrote {}", file.display()));
+            }
+            None => {
+                if !content.trim_start().starts_with("---") {
+                    println!("---");
+                }
+                print!("{}", content);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Command DIGEST
+///
+/// Without a repository new projects are only found by their `created` date.
+pub fn digest(matches: &ArgMatches<'_>) -> Result<(), Error> {
+    use asciii::actions::activity;
+    use asciii::actions::digest::{Digest, Week};
+
+    let week = match matches.value_of("week") {
+        Some(week) => Week::parse(week).ok_or_else(|| format_err!(lformat!("{:?} is not a week like 2024-W10", week)))?,
+        None => Week::last(Utc::today()),
+    };
+    let storage = setup_with_git::<Project>()?;
+    let projects = storage.open_projects(StorageDir::All)?;
+    let activities = match storage.repository() {
+        Some(repo) => {
+            let paths = [storage.working_dir().to_owned(), storage.archive_dir().to_owned()];
+            let since = week.monday.format("%Y-%m-%d").to_string();
+            let archive = storage.archive_dir().strip_prefix(storage.root_dir()).unwrap_or_else(|_| storage.archive_dir());
+            let log = repo.activity(&since, &paths)?;
+            activity::parse_log(&log, &archive.to_string_lossy(), &Project::file_extension())
+        }
+        None => Vec::new(),
+    };
+    print!("{}", Digest::new(&projects, &activities, week).to_markdown());
+    Ok(())
+}
+
 
 /// Command SPEC
 /// TODO: make this not panic :D
@@ -416,6 +816,7 @@ fn matches_to_export_config<'a>(m: &'a ArgMatches<'_>) -> Option<ExportConfig<'a
     let mut config = ExportConfig {
             select:        StorageSelection::Uninitialized,
             template_name,
+            backend:       m.value_of("backend"),
             bill_type,
             output:        m.value_of("output").map(Path::new),
             dry_run:       m.is_present("dry-run"),
@@ -450,6 +851,9 @@ fn matches_to_export_config<'a>(m: &'a ArgMatches<'_>) -> Option<ExportConfig<'a
 pub fn make(m: &ArgMatches<'_>) -> Result<(), Error> {
     log::debug!("{:?}", m);
     if let Some(ref config) = matches_to_export_config(m) {
+        if m.is_present("watch") {
+            return Ok(document_export::watch::watch(config)?);
+        }
         document_export::projects_to_doc(config)?; // TODO: if-let this TODO should return Result
         Ok(())
     } else {
@@ -459,107 +863,556 @@ pub fn make(m: &ArgMatches<'_>) -> Result<(), Error> {
 
 
 
-/// Command DELETE
-pub fn delete(m: &ArgMatches<'_>) -> Result<(), Error> {
-    let (search_terms, dir) = matches_to_search(m);
-    if m.is_present("template") {
-        unimplemented!();
-    } else {
-        actions::delete_project_confirmation(dir, &search_terms)?;
-        Ok(())
-    }
-}
-
-#[cfg(not(feature="document_export"))]
-pub fn make(_: &ArgMatches) -> Result<(), Error> {
-    log::error!("Make functionality not built-in with this release!");
-    Ok(())
-}
-
+/// Command MAIL
+#[cfg(feature="document_export")]
+pub fn mail(m: &ArgMatches<'_>) -> Result<(), Error> {
+    use asciii::document_export::mail::Mail;
 
+    let bill_type = if m.is_present("offer") { BillType::Offer } else { BillType::Invoice };
+    let sent_field = match bill_type {
+        BillType::Offer => "offer/sent_date",
+        BillType::Invoice => "invoice/sent_date",
+    };
 
+    let storage = setup_with_git::<Project>()?;
+    let projects = storage.open_projects(matches_to_selection(m))?;
+    if projects.is_empty() {
+        let (search_terms, _) = matches_to_search(m);
+        bail!(ActionError::NothingFound(search_terms.iter().map(ToString::to_string).collect()));
+    }
 
+    for project in projects {
+        let mail = Mail::for_project(&project, bill_type)?;
 
+        if m.is_present("dry-run") {
+            println!("{}", mail.to_eml()?);
+        } else if m.is_present("mailto") {
+            if !mail.attachments.is_empty() {
+                log::warn!("{}", lformat!("attach {} yourself", mail.attachments[0].display()));
+            }
+            open::that(mail.to_mailto())?;
+        } else if let Some(draft) = m.value_of("draft") {
+            mail.save_draft(Path::new(draft))?;
+        } else {
+            if !util::really(&lformat!("send {:?} to {}?", mail.subject, mail.to)) {
+                bail!(lformat!("Don't want to"));
+            }
+            mail.send()?;
 
+            let today = Local::today().format("%d.%m.%Y").to_string();
+            project.set_field(sent_field, &today)?;
+            let message = lformat!("sent {} to {}", mail.subject, mail.to);
+            storage.record(&message, vec![storage.journal().write(&project.file(), Some(project.file_content()))]);
 
-/// TODO: make this be have like `edit`, taking multiple names
-pub fn archive(matches: &ArgMatches<'_>) -> Result<(), Error> {
-    if let Some(search_terms) = matches.values_of("search terms"){
-        let search_terms = search_terms.collect::<Vec<_>>();
-        let year = matches.value_of("year").and_then(|s| s.parse::<i32>().ok());
-        let moved_files = actions::archive_projects(&search_terms, year, matches.is_present("force"))?;
-        log::debug!("archive({:?},{:?}) :\n{:?}", search_terms, year, moved_files);
-    } else if matches.is_present("all"){
-        log::debug!("archiving all I can find");
-        let moved_files = actions::archive_all_projects()?;
-        log::debug!("git adding {:?} ", moved_files);
-    } else {
-        log::debug!("what do you wanna do?");
+            if let Some(repo) = storage.repository() {
+                let paths = [project.file()];
+                if !(repo.add(&paths).success() && repo.commit_paths(&paths, &message).success()) {
+                    bail!(format_err!("git commit did not exit successfully"));
+                }
+            }
+        }
     }
     Ok(())
 }
 
-pub fn unarchive(matches: &ArgMatches<'_>) -> Result<(), Error> {
-    let year = matches.value_of("year").unwrap();
-    let year = year.parse::<i32>()
-        .unwrap_or_else(|e| panic!("can't parse year {:?}, {:?}", year, e));
-    let search_terms = matches.values_of("name").unwrap().collect::<Vec<_>>();
-    let moved_files = actions::unarchive_projects(year, &search_terms)?;
-    log::debug!("unarchive({:?},{:?}) :\n{:?}", search_terms, year, moved_files);
-    Ok(())
+#[cfg(not(feature="document_export"))]
+pub fn mail(_: &ArgMatches) -> Result<(), Error> {
+    bail!(lformat!("Mail functionality not built-in with this release!"))
 }
 
-pub fn config(matches: &ArgMatches<'_>) -> Result<(), Error> {
-    let editor = matches.value_of("editor")
-                        .or_else(|| CONFIG.get("user.editor")
-                                  .and_then(Yaml::as_str));
-
-    if let Some(path) = matches.value_of("show") {
-        config_show(path)?;
+/// Command SENT
+pub fn sent(m: &ArgMatches<'_>) -> Result<(), Error> {
+    let field = if m.is_present("accepted") {
+        "offer/accepted_date"
+    } else if m.is_present("offer") {
+        "offer/sent_date"
+    } else {
+        "invoice/sent_date"
+    };
+    let date = m.value_of("date")
+                .map(ToOwned::to_owned)
+                .unwrap_or_else(|| Local::today().format("%d.%m.%Y").to_string());
+
+    let storage = setup_with_git::<Project>()?;
+    let projects = storage.open_projects(matches_to_selection(m))?;
+    if projects.is_empty() {
+        let (search_terms, _) = matches_to_search(m);
+        bail!(ActionError::NothingFound(search_terms.iter().map(ToString::to_string).collect()));
     }
 
-    if matches.is_present("location") {
-        println!("config location: {:?}", config::ConfigReader::path_home())
-    }
+    for project in projects {
+        if !project.set_field(field, &date)? {
+            log::info!("{}", lformat!("{} already is {:?} in {}", field, date, project.short_desc()));
+            continue;
+        }
 
-    else if matches.is_present("init") {
-        config_init(editor)?;
+        let message = lformat!("set {} to {:?} in {}", field, date, project.short_desc());
+        storage.record(&message, vec![storage.journal().write(&project.file(), Some(project.file_content()))]);
+
+        if let Some(repo) = storage.repository() {
+            let paths = [project.file()];
+            if !(repo.add(&paths).success() && repo.commit_paths(&paths, &message).success()) {
+                bail!(format_err!("git commit did not exit successfully"));
+            }
+        }
     }
+    Ok(())
+}
 
-    else if matches.is_present("edit") {
-        config_edit(editor)?;
+/// Command ACCEPT
+///
+/// Records when and how the client accepted the offer, a signed copy is attached like `asciii attach` does.
+pub fn accept(m: &ArgMatches<'_>) -> Result<(), Error> {
+    use asciii::attachments::{self, Webdav};
+
+    let date = m.value_of("date")
+                .map(ToOwned::to_owned)
+                .unwrap_or_else(|| Local::today().format("%d.%m.%Y").to_string());
+    let copy = m.value_of("signed_copy").map(PathBuf::from);
+    let via = m.value_of("via").or(copy.as_ref().map(|_| "signed"));
+
+    let storage = setup_with_git::<Project>()?;
+    let projects = storage.open_projects(matches_to_selection(m))?;
+    match projects.len() {
+        0 => {
+            let (search_terms, _) = matches_to_search(m);
+            bail!(ActionError::NothingFound(search_terms.iter().map(ToString::to_string).collect()));
+        }
+        1 => {}
+        n if copy.is_some() => bail!(lformat!("{:?} matches {} projects, please be more specific", m.value_of("search_term").unwrap_or_default(), n)),
+        _ => {}
     }
 
-    else if matches.is_present("default") {
-        config_show_default();
+    for project in projects.iter() {
+        storage.ensure_open(project)?;
+        let mut paths = vec![project.file()];
+        let mut changes = Vec::new();
+        let mut fields = vec![("offer/accepted_date", date.clone())];
+        if let Some(via) = via {
+            fields.push(("offer/accepted_via", via.to_owned()));
+        }
+
+        let mut remote = None;
+        if let Some(ref copy) = copy {
+            let name = if m.is_present("remote") {
+                let webdav = Webdav::configured()
+                    .ok_or_else(|| format_err!("{}", lformat!("no WebDAV server, set attachments/webdav/url with `asciii config --edit`")))?;
+                let folder = project.dir().file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+                let attachment = webdav.upload(copy, &folder)?;
+                println!("{}: {}", attachment.name, attachment.share);
+                let name = attachment.name.clone();
+                remote = Some(attachment);
+                name
+            } else {
+                let target = attachments::attach_local(project, copy)?;
+                println!("{}", target.display());
+                changes.push(storage.journal().write(&target, None));
+                let name = target.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+                paths.push(target);
+                name
+            };
+            fields.push(("offer/signed_copy", name));
+        }
+
+        let fields = fields.iter().map(|(field, value)| (*field, value.as_str())).collect::<Vec<_>>();
+        let mut content = project.content_with_fields(&fields)?;
+        if let Some(ref attachment) = remote {
+            content = attachments::with_remote(&content, attachment)?;
+        }
+        if content == project.file_content() && changes.is_empty() {
+            log::info!("{}", lformat!("{} was already accepted on {}", project.short_desc(), date));
+            continue;
+        }
+        project.write_content(&content)?;
+        changes.push(storage.journal().write(&project.file(), Some(project.file_content())));
+
+        let message = lformat!("{} accepted the offer on {}", project.short_desc(), date);
+        storage.record(&message, changes);
+        if let Some(repo) = storage.repository() {
+            if !(repo.add(&paths).success() && repo.commit_paths(&paths, &message).success()) {
+                bail!(format_err!("git commit did not exit successfully"));
+            }
+        }
     }
     Ok(())
 }
 
+/// Command PAYLINK
+#[cfg(feature="serialization")]
+pub fn paylink(m: &ArgMatches<'_>) -> Result<(), Error> {
+    use asciii::paylink::{self, Charge, Link, Provider, Status};
 
-/// Command CONFIG --init
-///
-/// # Warning! Interactive
-/// This command will prompt the user for input on the commandline
-///
-pub fn config_init(editor: Option<&str>) -> Result<(), Error> {
-    let local = config::ConfigReader::path_home();
-
-    if local.exists() {
-        log::error!("{:?} already exists, can't overwrite", local);
+    let storage = setup_with_git::<Project>()?;
+    let projects = storage.open_projects(matches_to_dir_selection(m))?;
+    if projects.is_empty() {
+        let (search_terms, _) = matches_to_search(m);
+        bail!(ActionError::NothingFound(search_terms.iter().map(ToString::to_string).collect()));
+    }
 
-    } else if let Ok(mut file) = fs::File::create(local){
+    if m.is_present("poll") {
+        for project in projects.iter().filter(|p| p.field("invoice/payed_date").is_none()) {
+            let link = match Link::of(project) {
+                Some(link) => link,
+                None => continue,
+            };
+            let date = match paylink::status(&link)? {
+                Status::Open => {
+                    println!("{}", lformat!("{} is not payed yet", project.short_desc()));
+                    continue;
+                }
+                Status::Payed(date) => date.format("%d.%m.%Y").to_string(),
+            };
+            // like `asciii set`, fields still marked in the template are filled in
+            if project.empty_fields().iter().any(|field| field == "PAYED-DATE") {
+                project.replace_field("PAYED-DATE", &date)?;
+            } else {
+                project.set_field("invoice/payed_date", &date)?;
+            }
 
-        let mut template = Templater::new(config::DEFAULT_CONFIG).finalize();
-        log::trace!("default config keywords: {:#?}", template.list_keywords());
+            let message = lformat!("{} was payed on {} via {}", project.short_desc(), date, link.provider.name());
+            println!("{}", message);
+            storage.record(&message, vec![storage.journal().write(&project.file(), Some(project.file_content()))]);
+            storage.field_changed(&project.file(), "invoice/payed_date");
 
-        let content=
-        if util::really(&lformat!("do you want to set your name?")) {
-            let name = util::git_user_name().and_then(|user_name| {
-                if util::really(&lformat!("Is your name {:?}", user_name)) {
-                    Some(user_name)
-                } else {
-                    None
+            if let Some(repo) = storage.repository() {
+                let paths = [project.file()];
+                if !(repo.add(&paths).success() && repo.commit_paths(&paths, &message).success()) {
+                    bail!(format_err!("git commit did not exit successfully"));
+                }
+            }
+        }
+        return Ok(());
+    }
+
+    let provider = match m.value_of("provider") {
+        Some(name) => Provider::from_name(name)?,
+        None => Provider::configured()?,
+    };
+    for project in projects {
+        if let Some(link) = Link::of(&project) {
+            if !m.is_present("force") {
+                println!("{}", lformat!("{} already has a payment link: {}", project.short_desc(), link.url));
+                continue;
+            }
+        }
+
+        let charge = Charge::for_project(&project)?;
+        if m.is_present("dry-run") {
+            println!("{}: {} {:.2} {}", provider.name(), charge.description, charge.amount as f64 / 100.0, charge.currency);
+            continue;
+        }
+
+        let link = paylink::create(provider, &charge)?;
+        let content = project.content_with_fields(&[("invoice/paylink", &link.url), ("invoice/paylink_id", &link.field_id())])?;
+        project.write_content(&content)?;
+        println!("{}: {}", project.short_desc(), link.url);
+
+        let message = lformat!("created a {} payment link for {}", provider.name(), project.short_desc());
+        storage.record(&message, vec![storage.journal().write(&project.file(), Some(project.file_content()))]);
+
+        if let Some(repo) = storage.repository() {
+            let paths = [project.file()];
+            if !(repo.add(&paths).success() && repo.commit_paths(&paths, &message).success()) {
+                bail!(format_err!("git commit did not exit successfully"));
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(feature="serialization"))]
+pub fn paylink(_: &ArgMatches<'_>) -> Result<(), Error> {
+    bail!(format_err!("Payment links are not built-in with this release!"));
+}
+
+/// Command ATTACH
+pub fn attach(m: &ArgMatches<'_>) -> Result<(), Error> {
+    use asciii::attachments::{self, Webdav};
+
+    let storage = setup_with_git::<Project>()?;
+    let mut projects = storage.open_projects(matches_to_selection(m))?;
+    let project = match projects.len() {
+        0 => {
+            let (search_terms, _) = matches_to_search(m);
+            bail!(ActionError::NothingFound(search_terms.iter().map(ToString::to_string).collect()));
+        }
+        1 => projects.remove(0),
+        n => bail!(lformat!("{:?} matches {} projects, please be more specific", m.value_of("search_term").unwrap_or_default(), n)),
+    };
+    let files = m.values_of("file").unwrap().map(PathBuf::from).collect::<Vec<_>>();
+
+    let mut paths = Vec::new();
+    let mut changes = Vec::new();
+    if m.is_present("remote") {
+        let webdav = Webdav::configured()
+            .ok_or_else(|| format_err!("{}", lformat!("no WebDAV server, set attachments/webdav/url with `asciii config --edit`")))?;
+        let folder = project.dir().file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+
+        let before = project.file_content().to_owned();
+        let mut content = before.clone();
+        for file in &files {
+            let attachment = webdav.upload(file, &folder)?;
+            println!("{}: {}", attachment.name, attachment.share);
+            content = attachments::with_remote(&content, &attachment)?;
+        }
+        project.write_content(&content)?;
+        changes.push(storage.journal().write(&project.file(), Some(&before)));
+        paths.push(project.file());
+    } else {
+        for file in &files {
+            let target = attachments::attach_local(&project, file)?;
+            println!("{}", target.display());
+            changes.push(storage.journal().write(&target, None));
+            paths.push(target);
+        }
+    }
+
+    let message = lformat!("attached {} file(s) to {}", files.len(), project.short_desc());
+    storage.record(&message, changes);
+    if let Some(repo) = storage.repository() {
+        if !(repo.add(&paths).success() && repo.commit_paths(&paths, &message).success()) {
+            bail!(format_err!("git commit did not exit successfully"));
+        }
+    }
+    Ok(())
+}
+
+/// Command UNDO
+pub fn undo(m: &ArgMatches<'_>) -> Result<(), Error> {
+    let storage = setup_with_git::<Project>()?;
+    let journal = storage.journal();
+
+    if m.is_present("list") {
+        for entry in journal.entries()? {
+            if entry.undone {
+                println!("{} {} {}", entry.date.format("%d.%m.%Y %H:%M"), entry.action, lformat!("(undone)"));
+            } else {
+                println!("{} {}", entry.date.format("%d.%m.%Y %H:%M"), entry.action);
+            }
+        }
+        return Ok(());
+    }
+
+    let entry = journal.undo()?;
+    log::info!("{}", lformat!("undid {:?}", entry.action));
+    stage_journal_entry(&storage, &entry)
+}
+
+/// Command REDO
+pub fn redo() -> Result<(), Error> {
+    let storage = setup_with_git::<Project>()?;
+    let entry = storage.journal().redo()?;
+    log::info!("{}", lformat!("redid {:?}", entry.action));
+    stage_journal_entry(&storage, &entry)
+}
+
+/// Adds the paths an undo or redo touched to git.
+fn stage_journal_entry(storage: &Storage<Project>, entry: &asciii::storage::journal::Entry) -> Result<(), Error> {
+    if let Some(repo) = storage.repository() {
+        let paths = entry.changes.iter()
+                                 .flat_map(|c| c.paths())
+                                 .map(|p| storage.root_dir().join(p))
+                                 .filter(|p| p.exists())
+                                 .collect::<Vec<_>>();
+        if !paths.is_empty() {
+            repo.add(&paths);
+        }
+    }
+    Ok(())
+}
+
+/// Command DELETE
+pub fn delete(m: &ArgMatches<'_>) -> Result<(), Error> {
+    let (search_terms, dir) = matches_to_search(m);
+    if m.is_present("template") {
+        unimplemented!();
+    } else {
+        actions::delete_project_confirmation(dir, &search_terms)?;
+        Ok(())
+    }
+}
+
+/// Command MERGE
+pub fn merge(matches: &ArgMatches<'_>) -> Result<(), Error> {
+    use asciii::actions::merge;
+
+    let storage = setup_with_git::<Project>()?;
+    let find = |term: &str| -> Result<Project, Error> {
+        let mut found = storage.search_projects(StorageDir::Working, term)?.projects;
+        match found.len() {
+            0 => bail!(ActionError::NothingFound(vec![term.to_owned()])),
+            1 => Ok(found.remove(0)),
+            n => bail!(lformat!("{:?} matches {} projects, please be more specific", term, n))
+        }
+    };
+    let (a, b) = (find(matches.value_of("a").unwrap())?, find(matches.value_of("b").unwrap())?);
+    if a.file() == b.file() {
+        bail!(lformat!("{} can't be merged with itself", a.short_desc()));
+    }
+    let (kept, other) = if merge::richness(&b) > merge::richness(&a) { (b, a) } else { (a, b) };
+    println!("{}", lformat!("keeping {}, merging {} into it", kept.short_desc(), other.short_desc()));
+
+    let yes = matches.is_present("yes");
+    let plan = merge::plan(&kept, &other);
+    let choices = plan.conflicts.iter()
+        .map(|conflict| {
+            let options = [conflict.kept.as_str(), conflict.other.as_str()];
+            let default = if conflict.richer() == conflict.kept { 0 } else { 1 };
+            let choice = if yes { default } else { util::choose(&lformat!("{} differs:", conflict.field), &options, default) };
+            (conflict.field, options[choice].to_owned())
+        })
+        .collect::<Vec<_>>();
+    for (field, value) in &plan.fill {
+        println!("  {}: {}", field, value);
+    }
+    for list in &plan.lists {
+        println!("  {}", lformat!("{}: copied", list));
+    }
+
+    let content = merge::merged_content(&kept, &other, &plan, &choices)?;
+    let before = kept.file_content().to_owned();
+    kept.write_content(&content)?;
+    let mut changes = vec![storage.journal().write(&kept.file(), Some(&before))];
+    for (from, to) in merge::move_attachments(&kept, &other)? {
+        println!("  {} -> {}", from.display(), to.display());
+        changes.push(storage.journal().moved(&from, &to));
+    }
+    storage.record(&lformat!("merge {} into {}", other.short_desc(), kept.short_desc()), changes);
+    if let Some(repo) = storage.repository() {
+        repo.add(&[kept.dir()]);
+    }
+
+    storage.delete_project_if(&other, || yes || util::really(&lformat!("do you want to delete {}?", other.short_desc())))?;
+    Ok(())
+}
+
+#[cfg(not(feature="document_export"))]
+pub fn make(_: &ArgMatches) -> Result<(), Error> {
+    log::error!("Make functionality not built-in with this release!");
+    Ok(())
+}
+
+
+
+
+
+
+
+/// TODO: make this be have like `edit`, taking multiple names
+pub fn archive(matches: &ArgMatches<'_>) -> Result<(), Error> {
+    if let Some(year) = matches.value_of("fix-prefixes").and_then(|s| s.parse::<i32>().ok()) {
+        let renamed = actions::fix_archive_prefixes(year)?;
+        for (from, to) in &renamed {
+            println!("{} -> {}", from.display(), to.display());
+        }
+        if renamed.is_empty() {
+            println!("{}", lformat!("every project of {} is named correctly", year));
+        }
+    } else if let Some(year) = matches.value_of("reindex").and_then(|s| s.parse::<i32>().ok()) {
+        let index = actions::reindex_archive(year)?;
+        println!("{}", index.display());
+    } else if let Some(search_terms) = matches.values_of("search terms"){
+        let search_terms = search_terms.collect::<Vec<_>>();
+        let year = matches.value_of("year").and_then(|s| s.parse::<i32>().ok());
+        let moved_files = actions::archive_projects(&search_terms, year, matches.is_present("force"))?;
+        log::debug!("archive({:?},{:?}) :\n{:?}", search_terms, year, moved_files);
+    } else if matches.is_present("all"){
+        log::debug!("archiving all I can find");
+        let moved_files = actions::archive_all_projects()?;
+        log::debug!("git adding {:?} ", moved_files);
+    } else {
+        log::debug!("what do you wanna do?");
+    }
+    Ok(())
+}
+
+pub fn unarchive(matches: &ArgMatches<'_>) -> Result<(), Error> {
+    let year = matches.value_of("year").unwrap();
+    let year = year.parse::<i32>()
+        .unwrap_or_else(|e| panic!("can't parse year {:?}, {:?}", year, e));
+    let search_terms = matches.values_of("name").unwrap().collect::<Vec<_>>();
+    let moved_files = actions::unarchive_projects(year, &search_terms)?;
+    log::debug!("unarchive({:?},{:?}) :\n{:?}", search_terms, year, moved_files);
+    Ok(())
+}
+
+/// Command CLOSE-YEAR
+pub fn close_year(matches: &ArgMatches<'_>) -> Result<(), Error> {
+    let year = matches_to_year(matches)?;
+    let storage = setup_with_git::<Project>()?;
+    let closing = actions::closing::close_year(&storage, year)?;
+    for moved in closing.archived.iter().filter(|path| path.starts_with(storage.archive_dir())) {
+        println!("{}", lformat!("archived {}", moved.display()));
+    }
+    for report in &closing.reports {
+        println!("{}", report.display());
+    }
+    if let Some(tag) = closing.tag {
+        println!("{}", lformat!("tagged {}", tag));
+    }
+    println!("{}", lformat!("{} is closed", year));
+    Ok(())
+}
+
+pub fn config(matches: &ArgMatches<'_>) -> Result<(), Error> {
+    let editor = matches.value_of("editor")
+                        .or_else(|| CONFIG.get("user.editor")
+                                  .and_then(Yaml::as_str));
+
+    match matches.subcommand() {
+        ("list", Some(sub_m)) => return config_list(sub_m.is_present("verbose")),
+        ("get", Some(sub_m)) => return config_get(sub_m.value_of("key").unwrap()),
+        ("set", Some(sub_m)) => return config_set(sub_m.value_of("key").unwrap(), sub_m.value_of("value").unwrap()),
+        ("edit", Some(_)) => return config_edit(editor),
+        _ => {}
+    }
+
+    if let Some(path) = matches.value_of("show") {
+        config_show(path)?;
+    }
+
+    if matches.is_present("location") {
+        println!("config location: {:?}", config::ConfigReader::path_home())
+    }
+
+    else if matches.is_present("init") {
+        config_init(editor)?;
+    }
+
+    else if matches.is_present("edit") {
+        config_edit(editor)?;
+    }
+
+    else if matches.is_present("default") {
+        config_show_default();
+    }
+    Ok(())
+}
+
+
+/// Command CONFIG --init
+///
+/// # Warning! Interactive
+/// This command will prompt the user for input on the commandline
+///
+pub fn config_init(editor: Option<&str>) -> Result<(), Error> {
+    let local = config::ConfigReader::path_home();
+
+    if local.exists() {
+        log::error!("{:?} already exists, can't overwrite", local);
+
+    } else if let Ok(mut file) = fs::File::create(local){
+
+        let mut template = Templater::new(config::DEFAULT_CONFIG).finalize();
+        log::trace!("default config keywords: {:#?}", template.list_keywords());
+
+        let content=
+        if util::really(&lformat!("do you want to set your name?")) {
+            let name = util::git_user_name().and_then(|user_name| {
+                if util::really(&lformat!("Is your name {:?}", user_name)) {
+                    Some(user_name)
+                } else {
+                    None
                 }
             }).unwrap_or_else(||{
                 println!("{}", lformat!("What is your name?"));
@@ -596,11 +1449,52 @@ pub fn config_show(path: &str) -> Result<(), Error> {
     Ok(())
 }
 
+/// Command CONFIG LIST
+fn config_list(verbose: bool) -> Result<(), Error> {
+    use asciii::config::schema::{self, KEYS};
+
+    CONFIG.merge_storage_config(&get_storage_path());
+    for key in KEYS {
+        let value = CONFIG.get(key.path).and_then(schema::to_string).unwrap_or_else(|| String::from("~"));
+        println!("{} = {}", key.path, value);
+        if verbose {
+            println!("    {} ({}, {})", key.description, key.kind,
+                     lformat!("default: {}", key.default_value().unwrap_or_else(|| String::from("~"))));
+        }
+    }
+    Ok(())
+}
+
+/// Command CONFIG GET
+fn config_get(path: &str) -> Result<(), Error> {
+    use asciii::config::schema::{self, ConfigProblem};
+
+    if schema::key(path).is_none() {
+        bail!(ConfigProblem::Unknown(path.to_owned()));
+    }
+    CONFIG.merge_storage_config(&get_storage_path());
+    if let Some(value) = CONFIG.get(path).and_then(schema::to_string) {
+        println!("{}", value);
+    }
+    Ok(())
+}
+
+/// Command CONFIG SET
+fn config_set(path: &str, value: &str) -> Result<(), Error> {
+    let file = config::ConfigReader::path_home();
+    config::ConfigReader::set_in_file(&file, path, value)?;
+    println!("{}", lformat!("set {} in {}", path, file.display()));
+    Ok(())
+}
+
 /// Command CONFIG --edit
 fn config_edit(editor: Option<&str>) -> Result<(), Error> {
     let local = config::ConfigReader::path_home();
     if local.exists() {
         util::pass_to_command(editor, &[&CONFIG.path])?;
+        for (path, problem) in config::ConfigReader::try_new()?.problems() {
+            println!("{}: {}", path.display(), problem);
+        }
     } else {
         log::error!("Cannot open {:?}, run `asciii config --init` to create it.", local)
     }
@@ -644,7 +1538,11 @@ pub fn version(matches: &ArgMatches<'_>) -> Result<(), Error> {
 /// Command DUES
 pub fn dues(matches: &ArgMatches<'_>) -> Result<(), Error> {
     let dues = actions::dues();
-    if let Ok(dues) = dues {
+    if matches.is_present("porcelain") {
+        for line in print::porcelain::dues_lines(&dues?, matches.is_present("wages")) {
+            println!("{}", line);
+        }
+    } else if let Ok(dues) = dues {
         println!("Open Payments: {}", dues.acc_sum_sold.postfix());
         println!("Open Wages:    {}", dues.acc_wages.postfix());
         if matches.is_present("wages") {
@@ -656,91 +1554,619 @@ pub fn dues(matches: &ArgMatches<'_>) -> Result<(), Error> {
     Ok(())
 }
 
-// pub fn open_path(matches:&ArgMatches){path(matches, |path| {open::that(path).unwrap();})}
-pub fn open_path(m: &ArgMatches<'_>) -> Result<(), Error> {
-    path(m, |path| {
-        log::debug!("opening {:?}", path);
-        open::that(path).map(|_| ())?;
-        Ok(())
-    })?;
+/// Command OFFERS
+///
+/// Offers in the working directory that were neither accepted nor invoiced, with `--expired` only those past their validity.
+pub fn offers(matches: &ArgMatches<'_>) -> Result<(), Error> {
+    use asciii::project::spec::Offerable;
+
+    let today = Utc::today();
+    let projects = setup::<Project>()?.open_projects(StorageDir::Working)?;
+    for project in projects.iter().filter(|project| project.has_open_offer()) {
+        let offer = project.offer();
+        let expired = project.expired_offer(today);
+        if matches.is_present("expired") && expired.is_none() {
+            continue;
+        }
+        let validity = match (expired, offer.valid_until()) {
+            (Some(until), _) => lformat!("expired {} days ago", (today - until).num_days()),
+            (None, Ok(until)) => lformat!("valid until {}", until.format("%d.%m.%Y")),
+            (None, Err(_)) => String::new(),
+        };
+        println!("{:<30} {:<14} {}", project.short_desc(), offer.number().unwrap_or_default(), validity);
+    }
     Ok(())
 }
 
-/// Command PATH
-pub fn path<F>(m: &ArgMatches<'_>, action: F) -> Result<(), Error>
-    where F: Fn(&Path) -> Result<(), Error>
-{
+/// Command HOURS
+pub fn hours(matches: &ArgMatches<'_>) -> Result<(), Error> {
+    let projects = setup::<Project>()?.open_projects(matches_to_dir_selection(matches))?;
+    let ascii = matches.is_present("ascii") || CONFIG.get_bool("list/ascii");
+    print::print_hours(&projects, matches.value_of("employee"), ascii);
+    Ok(())
+}
 
-    let path = CONFIG.get_str("path");
-    let storage_path = CONFIG.get_str("dirs/storage");
-    let templates_path = CONFIG.get_str("dirs/templates");
-    let output_path = CONFIG.get_str("output_path");
-
-    let exe = env::current_exe()?;
-
-    if m.is_present("search_term") {
-        let storage = setup::<Project>()?;
-        let selection = matches_to_selection(m);
-        let projects = storage.open_projects(&selection)?;
-        log::debug!("opening project folder {:?} -> {:#?}", selection, projects);
-
-        for project in projects.iter() {
-            if m.is_present("offer") {
-                log::debug!("offer file for {:?}", project.offer_file());
-
-                if let Some(offer_file) = &project.offer_file() {
-                    if offer_file.exists() {
-                        action(offer_file)?;
-                    } else {
-                        log::warn!("{}", lformat!("{} does not exist", offer_file.display()));
-                    }
-                }
+/// Command TIMESHEET
+pub fn timesheet(matches: &ArgMatches<'_>) -> Result<(), Error> {
+    use asciii::actions::timesheet::{self, Timesheet};
 
-            } else if m.is_present("invoice") {
-                log::debug!("invoice file for {:?}", project.invoice_file());
-                if let Some(invoice_file) = &project.invoice_file() {
-                    if invoice_file.exists() {
-                        action(invoice_file)?;
-                    } else {
-                        log::warn!("{}", lformat!("{} does not exist", invoice_file.display()));
-                    }
-                }
+    let employee = matches.value_of("employee").unwrap();
+    let (year, month) = match matches.value_of("month") {
+        Some(month) => timesheet::parse_month(month)
+            .ok_or_else(|| format_err!("{}", lformat!("{:?} is not a month like 2024-06", month)))?,
+        None => (Local::today().year(), Local::today().month()),
+    };
+
+    let projects = setup::<Project>()?.open_projects(StorageDir::All)?;
+    let sheet = Timesheet::new(&projects, employee, year, month);
+    if sheet.entries.is_empty() {
+        log::warn!("{}", lformat!("{} did not work in {:02}/{}", employee, month, year));
+    }
+
+    if matches.is_present("csv") {
+        print!("{}", sheet.to_csv()?);
+    } else {
+        let ascii = matches.is_present("ascii") || CONFIG.get_bool("list/ascii");
+        print::print_timesheet(&sheet, ascii);
+    }
+    Ok(())
+}
+
+/// Command STAFF
+pub fn staff(matches: &ArgMatches<'_>) -> Result<(), Error> {
+    use asciii::actions::{staffing, timesheet};
+
+    let today = Local::today();
+    let (year, month) = match matches.value_of("month") {
+        Some(month) => timesheet::parse_month(month)
+            .ok_or_else(|| format_err!("{}", lformat!("{:?} is not a month like 2024-06", month)))?,
+        None => (today.year(), today.month()),
+    };
+
+    let projects = setup::<Project>()?.open_projects(StorageDir::Working)?;
+    let plan = staffing::plan(&projects, year, month, today.naive_local());
+    if plan.is_empty() {
+        log::warn!("{}", lformat!("no upcoming events in {:02}/{}", month, year));
+        return Ok(());
+    }
+
+    let ascii = matches.is_present("ascii") || CONFIG.get_bool("list/ascii");
+    print::print_staffing(&plan, year, month, ascii);
+    Ok(())
+}
+
+/// Command REPORT
+pub fn report(matches: &ArgMatches<'_>) -> Result<(), Error> {
+    match matches.subcommand() {
+        ("vat", Some(sub_m)) => report_vat(sub_m),
+        ("income", Some(sub_m)) => report_income(sub_m),
+        ("numbers", Some(sub_m)) => report_numbers(sub_m),
+        _ => bail!(format_err!("unhandled command"))
+    }
+}
+
+/// Command STATS
+pub fn stats(matches: &ArgMatches<'_>) -> Result<(), Error> {
+    match matches.subcommand() {
+        ("clients", Some(sub_m)) => stats_clients(sub_m),
+        _ => bail!(format_err!("unhandled command"))
+    }
+}
+
+/// Command STATS CLIENTS
+fn stats_clients(matches: &ArgMatches<'_>) -> Result<(), Error> {
+    use asciii::actions::report::ClientReport;
+
+    let year = match matches.value_of("year") {
+        Some(_) => Some(matches_to_year(matches)?),
+        None => None,
+    };
+    let projects = setup::<Project>()?.open_projects(StorageDir::All)?;
+    let report = ClientReport::new(&projects, year)?;
+
+    if matches.is_present("csv") {
+        print!("{}", report.to_csv()?);
+    } else {
+        let ascii = matches.is_present("ascii") || CONFIG.get_bool("list/ascii");
+        print::print_client_report(&report, ascii);
+    }
+    Ok(())
+}
+
+/// Year given as `year` argument, defaults to the current year.
+fn matches_to_year(matches: &ArgMatches<'_>) -> Result<i32, Error> {
+    match matches.value_of("year") {
+        Some(year) => year.parse::<i32>()
+                          .map_err(|_| format_err!("{}", lformat!("{:?} is not a year", year))),
+        None => Ok(Local::today().year()),
+    }
+}
+
+/// Command REPORT VAT
+fn report_vat(matches: &ArgMatches<'_>) -> Result<(), Error> {
+    use asciii::actions::report::VatReport;
+
+    let year = matches_to_year(matches)?;
+    let quarter = matches.value_of("quarter").and_then(|q| q.parse::<u32>().ok());
+
+    // invoices of a year may be archived in the next one
+    let projects = setup::<Project>()?.open_projects(StorageDir::All)?;
+    let report = VatReport::new(&projects, year, quarter)?;
 
+    if matches.is_present("csv") {
+        print!("{}", report.to_csv()?);
+    } else {
+        let ascii = matches.is_present("ascii") || CONFIG.get_bool("list/ascii");
+        print::print_vat_report(&report, ascii);
+    }
+    Ok(())
+}
 
+/// Command REPORT INCOME
+fn report_income(matches: &ArgMatches<'_>) -> Result<(), Error> {
+    use asciii::actions::report::IncomeReport;
 
+    let year = matches_to_year(matches)?;
+    let projects = setup::<Project>()?.open_projects(StorageDir::All)?;
+    let report = IncomeReport::new(&projects, year)?;
+
+    if matches.is_present("csv") {
+        print!("{}", report.to_csv()?);
+    } else if matches.is_present("json") {
+        print_income_json(&report)?;
+    } else {
+        let ascii = matches.is_present("ascii") || CONFIG.get_bool("list/ascii");
+        print::print_income_report(&report, ascii);
+    }
+    Ok(())
+}
+
+/// Command REPORT NUMBERS
+fn report_numbers(matches: &ArgMatches<'_>) -> Result<(), Error> {
+    use asciii::actions::report::NumberReport;
+
+    let year = matches_to_year(matches)?;
+    let storage = setup::<Project>()?;
+    let projects = storage.open_projects(StorageDir::All)?;
+    let report = NumberReport::new(&projects, &storage.ledger().issued()?, year);
+
+    print::print_number_report(&report);
+    if !report.is_clean() {
+        bail!(ActionError::ValidationFailed(lformat!("invoice numbers of {} are not gapless", year)));
+    }
+    Ok(())
+}
+
+#[cfg(feature = "serialization")]
+fn print_income_json(report: &asciii::actions::report::IncomeReport) -> Result<(), Error> {
+    println!("{}", report.to_json()?);
+    Ok(())
+}
+
+#[cfg(not(feature = "serialization"))]
+fn print_income_json(_: &asciii::actions::report::IncomeReport) -> Result<(), Error> {
+    bail!(lformat!("json output is not enabled in this build"))
+}
+
+/// Command STATUS
+///
+/// `git status`, with `--json` a summary of the whole storage for monitoring.
+pub fn status(matches: &ArgMatches<'_>) -> Result<(), Error> {
+    if matches.is_present("json") {
+        return print_summary_json();
+    }
+    git_status()
+}
+
+#[cfg(feature = "serialization")]
+fn print_summary_json() -> Result<(), Error> {
+    use asciii::actions::summary::Summary;
+
+    let storage = setup_with_git::<Project>()?;
+    println!("{}", Summary::of_storage(&storage, Utc::today())?.to_json()?);
+    Ok(())
+}
+
+#[cfg(not(feature = "serialization"))]
+fn print_summary_json() -> Result<(), Error> {
+    bail!(lformat!("json output is not enabled in this build"))
+}
+
+/// Command TEMPLATE
+pub fn template(matches: &ArgMatches<'_>) -> Result<(), Error> {
+    match matches.subcommand() {
+        ("new", Some(sub_m)) => template_new(sub_m),
+        ("check", Some(_)) => template_check(),
+        _ => bail!(format_err!("unhandled command"))
+    }
+}
+
+/// Command USER
+pub fn user(matches: &ArgMatches<'_>) -> Result<(), Error> {
+    use asciii::actions::users::{self, User};
+
+    let storage = setup::<Project>()?;
+    match matches.subcommand() {
+        ("list", Some(_)) => for user in users::load(&storage)? {
+            if matches.is_present("porcelain") {
+                println!("{}\t{}", user.name, user.email.unwrap_or_default());
             } else {
-                log::debug!("executing action file for {:?}", project.dir());
-                action(&project.dir())?;
+                println!("{}", user);
             }
-        }
+        },
+        ("add", Some(sub_m)) => {
+            let user = User::new(sub_m.value_of("name").unwrap(), sub_m.value_of("email"));
+            println!("{}", users::add(&storage, user)?.display());
+        },
+        ("remove", Some(sub_m)) => {
+            println!("{}", users::remove(&storage, sub_m.value_of("name").unwrap())?.display());
+        },
+        _ => bail!(format_err!("unhandled command"))
     }
-    else if m.is_present("templates") {
-        action(&util::replace_home_tilde(Path::new(path))
-            .join(storage_path)
-            .join(templates_path)
-            )?
+    Ok(())
+}
+
+/// Command CLIENT
+pub fn client(matches: &ArgMatches<'_>) -> Result<(), Error> {
+    match matches.subcommand() {
+        ("dedupe", Some(sub_m)) => client_dedupe(sub_m),
+        _ => bail!(format_err!("unhandled command"))
     }
+}
 
-    else if m.is_present("output") {
-        action(&util::replace_home_tilde(Path::new(output_path)))?
+/// Command CLIENT DEDUPE
+fn client_dedupe(matches: &ArgMatches<'_>) -> Result<(), Error> {
+    use asciii::actions::dedupe;
+
+    let storage = setup::<Project>()?;
+    let projects = storage.open_projects(StorageDir::All)?;
+    let duplicates = dedupe::find(&projects, &storage.client_database());
+    if duplicates.is_empty() {
+        println!("{}", lformat!("no client is written in different ways"));
+        return Ok(());
     }
 
-    else if m.is_present("bin") {
-        action(
-            exe.parent().unwrap()
-               //.ok_or(Err("no parent".into()))? TODO
-               )?
+    let describe = |spelling: &dedupe::Spelling| match spelling.email {
+        Some(ref email) => lformat!("{} <{}> in {} projects", spelling.name(), email, spelling.projects.len()),
+        None => lformat!("{} in {} projects", spelling.name(), spelling.projects.len()),
+    };
+    for group in &duplicates {
+        println!("{}", describe(&group.canonical));
+        for other in &group.others {
+            println!("  ~ {}", describe(other));
+        }
+        if matches.is_present("rewrite") && util::really(&lformat!("write all of them as {}?", group.canonical.name())) {
+            for file in dedupe::rewrite(&storage, group)? {
+                println!("  {}", file.display());
+            }
+        }
+        println!();
     }
+    Ok(())
+}
+
+/// Command EXTRAS
+pub fn extras(matches: &ArgMatches<'_>) -> Result<(), Error> {
+    match matches.subcommand() {
+        ("branding", Some(sub_m)) => extras_branding(sub_m),
+        _ => bail!(format_err!("unhandled command"))
+    }
+}
+
+/// Command EXTRAS BRANDING
+fn extras_branding(matches: &ArgMatches<'_>) -> Result<(), Error> {
+    use asciii::actions::branding::{self, BankDetails, Branding};
 
-    else {
-        // default case
-        let path = util::replace_home_tilde(Path::new(path)).join(storage_path);
-        action(&path)?
+    let storage = setup::<Project>()?;
+    let current = branding::configured(&storage)?.unwrap_or_default();
+    if matches.is_present("show") {
+        print!("{}", current.to_yaml());
+        return Ok(());
+    }
+
+    println!("{}", lformat!("Press enter to keep what is in brackets, - removes it."));
+    let asset = |what: &str, current: &Option<PathBuf>| -> Result<Option<PathBuf>, Error> {
+        let shown = current.as_ref().map(|path| path.display().to_string());
+        match util::ask(&lformat!("{} (image file)", what), shown.as_deref()) {
+            Some(ref answer) if Some(answer) == shown.as_ref() => Ok(current.clone()),
+            Some(answer) => Ok(Some(branding::import_asset(&storage, what, &util::paths::expand_tilde(Path::new(&answer)))?)),
+            None => Ok(None),
+        }
+    };
+    let logo = asset("logo", &current.logo)?;
+    let signature = asset("signature", &current.signature)?;
+    let footer = util::ask(&lformat!("footer"), current.footer.as_deref());
+
+    let bank = current.bank.clone().unwrap_or_default();
+    let bank = BankDetails {
+        name: util::ask(&lformat!("bank"), bank.name.as_deref()),
+        holder: util::ask(&lformat!("account holder"), bank.holder.as_deref()),
+        iban: util::ask("IBAN", bank.iban.as_deref()),
+        bic: util::ask("BIC", bank.bic.as_deref()),
     };
 
+    let branding = Branding { logo, signature, footer, bank: Some(bank).filter(|bank| *bank != BankDetails::default()) };
+    for (what, path) in branding.missing_assets(storage.extras_dir()) {
+        log::warn!("{}", lformat!("the {} {} does not exist", what, path.display()));
+    }
+    println!("{}", branding::save(&storage, &branding)?.display());
+    Ok(())
+}
+
+/// Command TEMPLATE NEW
+fn template_new(matches: &ArgMatches<'_>) -> Result<(), Error> {
+    let name = matches.value_of("name").unwrap();
+    let storage = setup::<Project>()?;
+    let path = storage.templates_dir()
+                      .join(name)
+                      .with_extension(CONFIG.get_str("extensions/project_template"));
+    if path.exists() {
+        bail!(lformat!("{} already exists", path.display()));
+    }
+    util::atomic_write(&path, actions::template::scaffold())?;
+    println!("{}", path.display());
     Ok(())
 }
 
+/// Command TEMPLATE CHECK
+fn template_check() -> Result<(), Error> {
+    use asciii::actions::template::check_project_template;
+    use asciii::project::error::Severity;
+
+    let storage = setup::<Project>()?;
+    let mut findings = Vec::new();
+    let mut sample = None;
+    for path in storage.list_template_files()? {
+        let (mut found, project) = check_project_template(&path);
+        findings.append(&mut found);
+        // documents are rendered against the default template if it works, otherwise against the first that does
+        if path.file_stem() == Some(OsStr::new("default")) || sample.is_none() {
+            sample = project.or(sample);
+        }
+    }
+
+    match sample {
+        Some(ref sample) => findings.append(&mut check_document_templates(storage.templates_dir(), sample)?),
+        None => println!("{}", lformat!("no usable project template, document templates are not checked"))
+    }
+
+    for finding in &findings {
+        println!("{}", finding);
+    }
+
+    let errors = findings.iter().filter(|f| f.severity == Severity::Error).count();
+    if errors > 0 {
+        bail!(ActionError::ValidationFailed(lformat!("{} broken templates", errors)));
+    }
+    Ok(())
+}
+
+#[cfg(feature="document_export")]
+fn check_document_templates(dir: &Path, sample: &Project) -> Result<Vec<actions::template::Finding>, Error> {
+    use asciii::document_export::backend::BACKENDS;
+
+    let mut findings = Vec::new();
+    let mut paths = list_path_content(dir)?;
+    paths.sort();
+    for backend in BACKENDS {
+        let extension = format!(".{}", backend.template_extension());
+        for path in paths.iter().filter(|p| p.to_string_lossy().ends_with(&extension)) {
+            findings.append(&mut actions::template::check_document_template(path, *backend, sample));
+        }
+    }
+    Ok(findings)
+}
+
+#[cfg(not(feature="document_export"))]
+fn check_document_templates(_: &Path, _: &Project) -> Result<Vec<actions::template::Finding>, Error> {
+    Ok(Vec::new())
+}
+
+/// Command FMT
+pub fn fmt(matches: &ArgMatches<'_>) -> Result<(), Error> {
+    let check = matches.is_present("check");
+    let storage = if check { setup::<Project>()? } else { setup_with_git::<Project>()? };
+    let projects = storage.open_projects(matches_to_dir_selection(matches))?;
+
+    let mut unformatted = 0;
+    let mut failed = 0;
+    for project in projects.iter() {
+        let formatted = match project.formatted_content() {
+            Ok(formatted) => formatted,
+            Err(e) => {
+                log::error!("{}: {}", project.file().display(), e);
+                failed += 1;
+                continue;
+            }
+        };
+        if formatted == project.file_content() {
+            continue;
+        }
+        if check {
+            println!("{}", project.file().display());
+            unformatted += 1;
+            continue;
+        }
+
+        let _lock = storage.lock(project)?;
+        project.write_content(&formatted)?;
+        let message = lformat!("formatted {}", project.short_desc());
+        storage.record(&message, vec![storage.journal().write(&project.file(), Some(project.file_content()))]);
+        if let Some(repo) = storage.repository() {
+            let paths = [project.file()];
+            if !(repo.add(&paths).success() && repo.commit_paths(&paths, &message).success()) {
+                bail!(format_err!("git commit did not exit successfully"));
+            }
+        }
+        println!("{}", message);
+    }
+
+    if unformatted > 0 {
+        bail!(ActionError::ValidationFailed(lformat!("{} project files are not formatted, run asciii fmt", unformatted)));
+    }
+    if failed > 0 {
+        bail!(ActionError::Failed(lformat!("{} project files can't be formatted", failed)));
+    }
+    Ok(())
+}
+
+/// Command VALIDATE
+pub fn validate(matches: &ArgMatches<'_>) -> Result<(), Error> {
+    use asciii::project::error::Severity;
+
+    let storage = setup::<Project>()?;
+    let projects = storage.open_projects(matches_to_dir_selection(matches))?;
+    let mut diagnostics = projects.iter()
+                                  .flat_map(Project::diagnostics)
+                                  .collect::<Vec<_>>();
+    // `validation/strict_keys` already includes them
+    if matches.is_present("strict") && !CONFIG.get_bool("validation/strict_keys") {
+        diagnostics.extend(projects.iter().flat_map(Project::unknown_keys));
+    }
+    if let Some(users) = actions::users::listed(&storage)? {
+        diagnostics.extend(actions::users::unknown_managers(&projects, &users));
+    }
+    diagnostics.extend(actions::inventory::diagnostics(&projects));
+
+    match matches.value_of("format") {
+        Some("json") => print_diagnostics_json(&diagnostics)?,
+        _ if matches.is_present("porcelain") => for diagnostic in &diagnostics {
+            println!("{}", print::porcelain::diagnostic_line(diagnostic));
+        },
+        _ => for diagnostic in &diagnostics {
+            println!("{}", diagnostic);
+        }
+    }
+
+    let errors = diagnostics.iter().filter(|d| d.severity == Severity::Error).count();
+    if errors > 0 {
+        bail!(ActionError::ValidationFailed(lformat!("{} invalid fields in {} projects", errors, projects.len())));
+    }
+    Ok(())
+}
+
+#[cfg(feature = "serialization")]
+fn print_diagnostics_json(diagnostics: &[asciii::project::error::Diagnostic]) -> Result<(), Error> {
+    println!("{}", serde_json::to_string_pretty(diagnostics)?);
+    Ok(())
+}
+
+#[cfg(not(feature = "serialization"))]
+fn print_diagnostics_json(_: &[asciii::project::error::Diagnostic]) -> Result<(), Error> {
+    bail!(lformat!("json output is not enabled in this build"))
+}
+
+/// Command DOCTOR
+pub fn doctor(m: &ArgMatches<'_>) -> Result<(), Error> {
+    use asciii::actions::doctor::{self, Status};
+
+    if m.is_present("fix") {
+        for repair in doctor::fix(env::var("ASCIII_NO_GIT").is_err()) {
+            println!("{}", repair);
+        }
+    }
+
+    let checks = doctor::run();
+    for check in &checks {
+        println!("{}", check);
+    }
+
+    let failed = checks.iter().filter(|c| c.status == Status::Failed).count();
+    if failed > 0 {
+        bail!(ActionError::ValidationFailed(lformat!("{} checks failed", failed)));
+    }
+    Ok(())
+}
+
+/// Command OPEN
+///
+/// Project files (`--yml`) are opened in the configured editor, everything else with the platform opener.
+pub fn open_path(m: &ArgMatches<'_>) -> Result<(), Error> {
+    let editor = m.value_of("editor")
+                  .or_else(|| CONFIG.get("user/editor").and_then(Yaml::as_str))
+                  .map(ToOwned::to_owned)
+                  .or_else(|| env::var("EDITOR").ok());
+
+    let paths = resolve_paths(m)?;
+    if m.is_present("yml") {
+        util::pass_to_command(editor.as_deref(), &paths)?;
+    } else {
+        for path in paths {
+            log::debug!("opening {:?}", path);
+            open::that(&path).map(|_| ())?;
+        }
+    }
+    Ok(())
+}
+
+/// Command PATH
+pub fn path<F>(m: &ArgMatches<'_>, action: F) -> Result<(), Error>
+    where F: Fn(&Path) -> Result<(), Error>
+{
+    for path in resolve_paths(m)? {
+        action(&path)?;
+    }
+    Ok(())
+}
+
+/// Paths of the projects and artifacts that `open` and `path` refer to.
+fn resolve_paths(m: &ArgMatches<'_>) -> Result<Vec<PathBuf>, Error> {
+    if m.is_present("bin") {
+        let exe = env::current_exe()?;
+        let bin_dir = exe.parent().ok_or_else(|| format_err!("{:?} has no parent", exe))?;
+        return Ok(vec![bin_dir.to_owned()]);
+    }
+
+    if m.is_present("output") {
+        return Ok(vec![util::paths::expand_tilde(Path::new(CONFIG.get_str("output_path")))]);
+    }
+
+    let storage = setup::<Project>()?;
+
+    if m.is_present("templates") {
+        return Ok(vec![storage.templates_dir().to_owned()]);
+    }
+
+    if !m.is_present("search_term") {
+        return Ok(vec![storage.root_dir().to_owned()]);
+    }
+
+    let selection = matches_to_selection(m);
+    let projects = storage.open_projects(&selection)?;
+    log::debug!("resolving paths for {:?} -> {:#?}", selection, projects);
+    if projects.is_empty() {
+        let (search_terms, _) = matches_to_search(m);
+        bail!(ActionError::NothingFound(search_terms.iter().map(ToString::to_string).collect()));
+    }
+
+    let mut paths = Vec::new();
+    for project in projects.iter() {
+        if m.is_present("offer") || m.is_present("invoice") {
+            let (file, bill_type) = if m.is_present("offer") {
+                (project.offer_file(), lformat!("offer"))
+            } else {
+                (project.invoice_file(), lformat!("invoice"))
+            };
+            log::debug!("{} file for {:?}: {:?}", bill_type, project.short_desc(), file);
+
+            match file {
+                Some(ref file) if file.exists() => paths.push(file.to_owned()),
+                Some(file) => log::warn!("{}", lformat!("{} does not exist, run `asciii make` first", file.display())),
+                None => log::warn!("{}", lformat!("{} has no {} file, its number or date is missing", project.short_desc(), bill_type)),
+            }
+        } else if m.is_present("yml") {
+            paths.push(project.file());
+        } else {
+            paths.push(project.dir());
+        }
+    }
+
+    if paths.is_empty() {
+        bail!(lformat!("nothing to open"));
+    }
+    Ok(paths)
+}
+
 #[cfg(feature="shell")]
 pub fn shell(_matches: &ArgMatches<'_>) -> Result<(), Error> {
     shell::launch_shell()
@@ -751,3 +2177,98 @@ pub fn shell(_matches: &ArgMatches<'_>) -> Result<(), Error> {
     bail!(format_err!("Shell functionality not built-in with this release!"));
 }
 
+/// Command DAEMON
+#[cfg(feature="serialization")]
+pub fn daemon(matches: &ArgMatches<'_>) -> Result<(), Error> {
+    use asciii::daemon::Daemon;
+
+    let mut daemon = Daemon::new();
+    match matches.value_of("socket") {
+        Some(socket) => daemon_socket(&mut daemon, Path::new(socket)),
+        None => {
+            let stdin = io::stdin();
+            daemon.serve(stdin.lock(), io::stdout())?;
+            Ok(())
+        }
+    }
+}
+
+#[cfg(all(feature="serialization", unix))]
+fn daemon_socket(daemon: &mut asciii::daemon::Daemon, socket: &Path) -> Result<(), Error> {
+    use std::os::unix::fs::FileTypeExt;
+    use std::os::unix::net::UnixListener;
+
+    // a socket left behind by an earlier daemon, anything else is not ours to remove
+    if let Ok(meta) = fs::symlink_metadata(socket) {
+        if !meta.file_type().is_socket() {
+            bail!(lformat!("{} exists and is not a socket, please choose another path", socket.display()));
+        }
+        fs::remove_file(socket)?;
+    }
+    let listener = UnixListener::bind(socket)?;
+    log::info!("{}", lformat!("listening on {}", socket.display()));
+
+    for stream in listener.incoming() {
+        // one client going away must not stop the daemon
+        let served = stream.and_then(|stream| daemon.serve(io::BufReader::new(stream.try_clone()?), stream));
+        if let Err(e) = served {
+            log::warn!("{}", lformat!("connection failed: {}", e));
+        }
+        if !daemon.is_running() {
+            break;
+        }
+    }
+    fs::remove_file(socket)?;
+    Ok(())
+}
+
+#[cfg(all(feature="serialization", not(unix)))]
+fn daemon_socket(_daemon: &mut asciii::daemon::Daemon, _socket: &Path) -> Result<(), Error> {
+    bail!(lformat!("unix sockets are not available on this platform"))
+}
+
+#[cfg(not(feature="serialization"))]
+pub fn daemon(_matches: &ArgMatches<'_>) -> Result<(), Error> {
+    bail!(format_err!("Daemon functionality not built-in with this release!"));
+}
+
+/// Runs `asciii-<command>` from the `PATH`, see `asciii::plugin`.
+#[cfg(feature="serialization")]
+pub fn plugin(command: &str, matches: &ArgMatches<'_>) -> Result<(), Error> {
+    use asciii::plugin::{self, Invocation};
+
+    let executable = plugin::find(command)
+        .ok_or_else(|| format_err!("{}", lformat!("unknown command {:?}, there is no {}{} on your PATH", command, plugin::PREFIX, command)))?;
+
+    let mut args = matches.values_of("").map(Iterator::collect::<Vec<&str>>).unwrap_or_default().into_iter();
+    let search_terms = args.by_ref().take_while(|arg| *arg != "--").map(ToOwned::to_owned).collect::<Vec<String>>();
+    let plugin_args = args.map(ToOwned::to_owned).collect::<Vec<String>>();
+
+    let invocation = match setup::<Project>() {
+        Ok(storage) => {
+            let selection = if search_terms.is_empty() {
+                StorageSelection::Dir(StorageDir::Working)
+            } else {
+                StorageSelection::DirAndSearch(StorageDir::Working, search_terms.clone())
+            };
+            let projects = storage.open_projects(selection)?;
+            Invocation::new(command, &search_terms, Some(storage.root_dir()), &projects)
+        }
+        Err(e) => {
+            log::warn!("{}", lformat!("running {} without projects: {}", command, e));
+            Invocation::new(command, &search_terms, None, &[])
+        }
+    };
+
+    let status = plugin::run(&executable, &plugin_args, &invocation)?;
+    if !status.success() {
+        bail!(lformat!("{} failed with {}", executable.display(), status));
+    }
+    Ok(())
+}
+
+#[cfg(not(feature="serialization"))]
+pub fn plugin(command: &str, _matches: &ArgMatches<'_>) -> Result<(), Error> {
+    bail!(format_err!("Plugins are not supported in this build, {:?} is not a command", command));
+}
+
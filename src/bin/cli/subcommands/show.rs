@@ -1,6 +1,7 @@
 use clap::ArgMatches;
 use anyhow::Error;
 
+use asciii::CONFIG;
 use asciii::print;
 use asciii::storage::*;
 //use asciii::storage::error::*;
@@ -14,6 +15,7 @@ use super::{matches_to_search, matches_to_selection};
 use super::path;
 
 use std::fs;
+use std::path::Path;
 
 /// Command SHOW
 pub fn show(m: &ArgMatches<'_>) -> Result<(), Error> {
@@ -27,7 +29,10 @@ pub fn show(m: &ArgMatches<'_>) -> Result<(), Error> {
         _ => BillType::Invoice, //TODO: be intelligent here ( use date )
     };
 
-    if m.is_present("files") {
+    if let Some(output) = m.value_of("output") {
+        let projects = setup::<Project>()?.open_projects(selection)?;
+        print::write_details(Path::new(output), &projects, &details_config(m, bill_type))
+    } else if m.is_present("files") {
         show_files(selection)
     } else if let Some(detail) = m.value_of("detail") {
         show_detail(&selection, detail)
@@ -37,6 +42,8 @@ pub fn show(m: &ArgMatches<'_>) -> Result<(), Error> {
         show_errors(selection)
     } else if m.is_present("yaml") {
         show_yaml(selection)
+    } else if m.is_present("raw") {
+        show_raw(selection)
     } else if m.is_present("json") {
         show_json(selection)
     } else if m.is_present("ical") {
@@ -46,15 +53,24 @@ pub fn show(m: &ArgMatches<'_>) -> Result<(), Error> {
     } else if m.is_present("template") {
         show_template(search_terms[0])
     } else {
-        for p in setup::<Project>()?.open_projects(selection)? {
-            print::show_details(&p, bill_type)
+        let config = details_config(m, bill_type);
+        for p in setup::<Project>()?.iter_projects(selection)? {
+            print::show_details(&p, &config)
         }
         Ok(())
     }
 }
 
+fn details_config(m: &ArgMatches<'_>, bill_type: BillType) -> print::DetailsConfig {
+    print::DetailsConfig {
+        bill_type,
+        ascii: m.is_present("ascii") || CONFIG.get_bool("list/ascii"),
+        side_by_side: m.is_present("compare"),
+    }
+}
+
 fn show_files(selection: StorageSelection) -> Result<(), Error> {
-    for project in setup::<Project>()?.open_projects(selection)? {
+    for project in setup::<Project>()?.iter_projects(selection)? {
         println!("{}: ", project.dir().display());
         for entry in fs::read_dir(project.dir()).unwrap() {
             println!("  {}", entry.unwrap().path().display())
@@ -73,7 +89,7 @@ fn print_spec_result(label: &str, result: &[String]) {
 
 
 fn show_errors(selection: StorageSelection) -> Result<(), Error> {
-    for p in setup::<Project>()?.open_projects(selection)? {
+    for p in setup::<Project>()?.iter_projects(selection)? {
         println!("{}: ", p.short_desc());
         print_spec_result("offer", &p.is_missing_for_offer());
         print_spec_result("invoice", &p.is_missing_for_invoice());
@@ -83,36 +99,45 @@ fn show_errors(selection: StorageSelection) -> Result<(), Error> {
 }
 
 fn show_empty_fields(selection: StorageSelection) -> Result<(), Error> {
-    for p in setup::<Project>()?.open_projects(selection)? {
+    for p in setup::<Project>()?.iter_projects(selection)? {
         println!("{}: {}", p.short_desc(), p.empty_fields().join(", "))
     }
     Ok(())
 }
 
 
+/// One line per project.
 fn show_json(selection: StorageSelection) -> Result<(), Error> {
-    for p in setup::<Project>()?.open_projects(selection)? {
+    for p in setup::<Project>()?.iter_projects(selection)? {
         println!("{}", p.to_json()?)
     }
     Ok(())
 }
 
+/// One document per project, starting with `---`.
 fn show_yaml(selection: StorageSelection) -> Result<(), Error> {
-    for p in setup::<Project>()?.open_projects(selection)? {
+    for p in setup::<Project>()?.iter_projects(selection)? {
+        println!("{}", p.to_yaml()?)
+    }
+    Ok(())
+}
+
+fn show_raw(selection: StorageSelection) -> Result<(), Error> {
+    for p in setup::<Project>()?.iter_projects(selection)? {
         println!("{}", p.dump_yaml())
     }
     Ok(())
 }
 
 fn show_ical(selection: StorageSelection) -> Result<(), Error> {
-    for p in setup::<Project>()?.open_projects(selection)? {
+    for p in setup::<Project>()?.iter_projects(selection)? {
         p.to_ical().print()?
     }
     Ok(())
 }
 
 fn show_detail(selection: &StorageSelection, detail: &str) -> Result<(), Error> {
-    for p in setup::<Project>()?.open_projects(selection.clone())? {
+    for p in setup::<Project>()?.iter_projects(selection.clone())? {
         println!("{}",
                  p.field(detail)
                   .unwrap_or_else(|| format!("No {:?} found", selection)))
@@ -121,7 +146,7 @@ fn show_detail(selection: &StorageSelection, detail: &str) -> Result<(), Error>
 }
 
 fn show_csv(selection: StorageSelection) -> Result<(), Error> {
-    for p in setup::<Project>()?.open_projects(selection)? {
+    for p in setup::<Project>()?.iter_projects(selection)? {
         println!("{}", p.to_csv(BillType::Invoice)?)
     }
     Ok(())
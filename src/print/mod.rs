@@ -0,0 +1,891 @@
+//! All the printing code lives here.
+
+use chrono::prelude::*;
+use prettytable::Table;
+use prettytable::Row;
+use prettytable::Cell;
+use prettytable::format::{LineSeparator, LinePosition, FormatBuilder};
+use prettytable::{Attr, color};
+use prettytable::{cell, row};
+
+
+use crate::config::ConfigReader;
+use crate::actions::report::{self, ClientReport, IncomeReport, NumberReport, VatReport};
+use crate::actions::staffing::Staffing;
+use crate::actions::timesheet::Timesheet;
+use crate::project::{BillType, Project, Exportable};
+use crate::project::product::Product;
+use crate::project::spec::{IsProject, Redeemable, Invoicable, HasEmployees, HasEvents, Employee};
+use crate::storage::Storable;
+use crate::storage::repo::GitStatus;
+use crate::util::{currency_to_string, to_currency};
+use crate::util::currency::CurrencyUnit;
+
+use bill::{Bill, BillItem, Currency};
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+pub mod row_style;
+use self::row_style::RowStyleRule;
+
+mod output;
+pub use self::output::{OutputFormat, write_projects, write_details};
+
+pub mod porcelain;
+
+/// Configuration for this list output.
+#[derive(Debug)]
+pub struct ListConfig<'a>{
+    pub mode:         ListMode,
+    pub show_errors:  bool,
+    pub git_status:   bool,
+    pub sort_by:      &'a str,
+    pub filter_by:    Option<Vec<&'a str>>,
+    pub use_colors:   bool,
+    pub ascii:        bool,
+    pub details:      Option<Vec<&'a str>>,
+    pub row_styles:   Vec<RowStyleRule>,
+    /// write to this file instead of stdout, see `write_projects()`
+    pub output:       Option<&'a Path>,
+    /// print canceled and freshly archived projects in their own sections, see `group_projects()`
+    pub group:        bool,
+    /// marks payed projects
+    pub currency:     String,
+}
+
+/// Configuration for `show_details()`.
+#[derive(Debug)]
+pub struct DetailsConfig {
+    pub bill_type:    BillType,
+    pub ascii:        bool,
+    /// render offer and invoice next to each other
+    pub side_by_side: bool,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum ListMode{ Simple, Verbose, Nothing, Paths, Csv, Porcelain }
+
+impl<'a> ListConfig<'a>{
+    /// Defaults from the `list` section of `config`.
+    pub fn from_config(config: &'a ConfigReader) -> ListConfig<'a>{
+        ListConfig{
+            mode:         if config.get_bool("list/verbose"){ ListMode::Verbose } else{ ListMode::Simple },
+            git_status:   config.get_bool("list/gitstatus"),
+            show_errors:  false,
+            sort_by:      config.get_str("list/sort"),
+            filter_by:    None,
+            use_colors:   config.get_bool("list/colors"),
+            ascii:        config.get_bool("list/ascii"),
+            details:      None,
+            row_styles:   row_style::rules_from_config(config),
+            output:       None,
+            group:        config.get_bool("list/group"),
+            currency:     CurrencyUnit::from_config(config).symbol,
+        }
+    }
+}
+
+impl<'a> Default for ListConfig<'a>{
+    fn default() -> ListConfig<'a>{
+        ListConfig::from_config(&crate::CONFIG)
+    }
+}
+
+/// Color and attribute of a project's row in the git status column.
+fn git_status_style(status: &GitStatus) -> (color::Color, Option<Attr>) {
+    match *status {
+        GitStatus::Current         => (color::BLUE,    None),
+        GitStatus::Conflict        => (color::RED,     None),
+        GitStatus::WorkingNew      => (color::GREEN,   None),
+        GitStatus::WorkingModified => (color::YELLOW,  None),
+        GitStatus::IndexNew        => (color::GREEN,   Some(Attr::Bold)),
+        GitStatus::IndexModified   => (color::BLUE,    Some(Attr::Bold)),
+        GitStatus::IndexDeleted    => (color::RED,     None),
+        _                          => (color::WHITE,   None)
+    }
+}
+
+// TODO: move `payed_to_cell` into computed_field.rs
+fn payed_to_cell(project:&Project, ascii: bool, sym: &str) -> Cell {
+    let (cross, partial, done) = if ascii { ("x", "~", "+") } else { ("✗", sym, sym) };
+
+    match (project.is_payed(), project.hours().employees_payed()) {
+        (false, false) => Cell::new(cross).with_style(Attr::ForegroundColor(color::RED)),
+        (_,     false) |
+        (false,  _   ) => Cell::new(partial).with_style(Attr::ForegroundColor(color::YELLOW)),
+        (true,  true ) => Cell::new(done).with_style(Attr::ForegroundColor(color::GREEN)),
+    }
+}
+
+fn result_to_cell(res: &[String], bold:bool, ascii: bool) -> Cell{
+    let (check, cross) = if ascii { ("+", "x") } else { ("✓", "✗") };
+    match (res.is_empty(), bold){
+        (true, false) => Cell::new(check).with_style(Attr::ForegroundColor(color::GREEN)),
+        (true,  true) => Cell::new(check).with_style(Attr::ForegroundColor(color::GREEN))
+                                                   .with_style(Attr::Bold),
+        (false,    _) => Cell::new(cross).with_style(Attr::ForegroundColor(color::RED))// + &errors.join(", ") )
+        //&Err(ref errors) => Cell::new( &format!("✗ {}",  &errors.join(", ") )) .with_style(Attr::ForegroundColor(color::RED))
+    }
+}
+
+/// create a Style string from the properties of a project
+///
+/// The rules are configured in `list/row_styles`, see `print::row_style`.
+fn project_to_style<'a>(project:&Project, list_config:&'a ListConfig<'_>) -> &'a str{
+    if list_config.use_colors {
+        row_style::style_for(project, &list_config.row_styles)
+    } else {
+        ""
+    }
+}
+
+/// produces the rows used in `print_projects()`
+pub fn path_rows(projects:&[Project], list_config:&ListConfig<'_>) -> Vec<Row>{
+    projects
+        .iter()
+        .map(|project| {
+            let row_style = project_to_style(project, list_config);
+            Row::new(vec![
+                     cell!(project.invoice().number_str().unwrap_or_default()),
+                     cell!(project.short_desc()).style_spec(row_style),
+                     cell!(project.file().display()),
+
+                     //cell!(project.date().map(|d|d.format("%d.%m.%Y").to_string()).unwrap_or("no_date".into())),
+                     //cell!(project.file().display()),
+            ])
+        })
+    .collect()
+}
+
+/// Triggered by `list --simple`, usually you set this in your config under `list/verbose: false`.
+pub fn simple_rows(projects:&[Project], list_config:&ListConfig<'_>) -> Vec<Row>{
+    projects
+        .iter()
+        .map(|project| {
+            let row_style = project_to_style(project, list_config);
+            Row::new(vec![
+                     cell!(
+                         if project.canceled() {
+                             format!("X {name}", name=project.short_desc())
+                         } else{
+                             project.short_desc()
+                         })
+                     .style_spec(row_style),
+
+                     //cell!(project.manager()),
+                     cell!(project.invoice().number_str().unwrap_or_default()),
+
+                     cell!(project.modified_date().map(|d|d.format("%d.%m.%Y").to_string()).unwrap_or_else(|| "no_date".into())),
+                     //cell!(project.file().display()),
+            ])
+        })
+    .collect()
+}
+
+/// Triggered by `list --verbose`, usually you set this in your config under `list/verbose`.
+///
+/// produces the rows used in `print_projects()`
+#[inline]
+pub fn verbose_rows(projects:&[Project], list_config:&ListConfig<'_>) -> Vec<Row>{
+    log::trace!("verbose_rows {:#?}", list_config);
+    projects.iter().enumerate()
+        .map(|(i, project)| {
+            //trace!("configuring row: {:?}", project.name());
+            let row_style = project_to_style(project, list_config);
+            let mut cells = Vec::new();
+
+            // TODO: how can we illustrate that a project has been removed? what about a red x
+            // for every project that was just moved to the archive?
+            // Or just git-add them when archiving automatically, that is what ascii2 would
+            // have done
+            let status = project.get_git_status();
+            let (color, style) = git_status_style(&status);
+
+            cells.push( Cell::new( &status.to_string() )
+                        .with_style( Attr::ForegroundColor(color) )
+                        .with_style( style.unwrap_or(Attr::Standout(false)) )
+                      );
+
+
+            let validation1 = project.is_missing_for_offer();
+            let validation2 = project.is_missing_for_invoice();
+            let validation3 = project.is_ready_for_archive();
+
+            cells.extend_from_slice( &[
+                cell!(r->i+1),
+
+                cell!(
+                    if project.canceled() {
+                        format!("CANCELED: {name}", name=project.short_desc())
+                    } else{ project.short_desc() }
+                    ).style_spec(row_style),
+
+                // Hendrik Sollich
+                cell!(project.responsible().unwrap_or(""))
+                    .style_spec(row_style),
+
+                // sort index
+                //cell!(project.index().unwrap_or(String::from(""))),
+
+                // R042
+                cell!(project.invoice().number_str().unwrap_or_default())
+                    .style_spec(row_style),
+
+                // Date
+                cell!(project.modified_date().unwrap_or_else(Utc::today).format("%d.%m.%Y").to_string())
+                    .style_spec(row_style),
+
+                // status "✓  ✓  ✗"
+                result_to_cell(&validation1, project.offer_file_exists(), list_config.ascii),
+                result_to_cell(&validation2, project.invoice_file_exists(), list_config.ascii),
+                payed_to_cell(project, list_config.ascii, &list_config.currency),
+                result_to_cell(&validation3, false, list_config.ascii),
+
+                // "offer sent 12.03.2024"
+                cell!(project.document_state()
+                             .map(|(state, date)| format!("{} {}", state, date.format("%d.%m.%Y")))
+                             .unwrap_or_default())
+                    .style_spec(row_style),
+
+                //cell!(output_file_exists(project, Project::offer_file_name)),
+                //cell!(output_file_exists(project, Project::invoice_file_name)),
+
+                cell!(r->project.sum_sold().map(|i|currency_to_string(&i)).unwrap_or_else(|e| format!("{}", e))),
+                //cell!(project.wages().map(|i|i.to_string()).unwrap_or(String::from("none"))),
+                //cell!(project.sum_sold_and_wages().map(|i|i.to_string()).unwrap_or(String::from("none"))),
+            ]);
+
+
+            if let Some(ref details) = list_config.details{
+                cells.extend_from_slice(
+                    &details.iter().map(|d|
+                                 cell!( project.field(d).unwrap_or_default()),
+                                 ).collect::<Vec<Cell>>()
+                    );
+            }
+
+
+            //if list_config.details{
+            //    // TODO
+            //    //
+            //}
+
+            if list_config.show_errors{
+                cells.extend_from_slice( &[
+                    // Errors
+                    cell!(validation1.join(",")),
+                    cell!(validation2.join(",")),
+                    cell!(validation3.join(",")),
+                ]);
+            }
+
+            Row::new(cells)
+        }).collect()
+}
+
+/// Triggered by `list --nothing`
+///
+/// This prints nothing unless you tell it to with `--details`
+pub fn dynamic_rows(projects:&[Project], list_config:&ListConfig<'_>) -> Vec<Row>{
+    projects
+        .iter()
+        .map(|project| {
+            let row_style = project_to_style(project, list_config);
+
+            let mut cells = Vec::new();
+
+            if let Some(ref details) = list_config.details{
+                cells.extend_from_slice(
+                    &details.iter().map(|d|
+                                        cell!( project.field(d).unwrap_or_default()).style_spec(row_style),
+                                        ).collect::<Vec<Cell>>()
+                    );
+                if list_config.show_errors{
+                    let validation = (project.is_missing_for_offer(), project.is_missing_for_invoice(), project.is_ready_for_archive());
+
+                    cells.extend_from_slice( &[
+                        // Errors
+                        cell!(validation.0.join("|")),
+                        cell!(validation.1.join("|")),
+                        cell!(validation.2.join("|")),
+                    ]);
+                }
+            }
+            Row::new(cells)
+        })
+    .collect()
+}
+
+/// Section of a grouped listing.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Section {
+    Open,
+    Canceled,
+    /// moved into the archive, but not yet committed
+    Archived,
+}
+
+impl Section {
+    /// Header printed above a section, open projects come first and need none.
+    pub fn header(self) -> Option<String> {
+        match self {
+            Section::Open     => None,
+            Section::Canceled => Some(lformat!("Canceled")),
+            Section::Archived => Some(lformat!("Freshly archived")),
+        }
+    }
+
+    fn of(project: &Project, archive_dir: &Path) -> Section {
+        let uncommitted = matches!(project.get_git_status(),
+                                   GitStatus::IndexNew | GitStatus::WorkingNew |
+                                   GitStatus::IndexRenamed | GitStatus::WorkingRenamed);
+
+        if uncommitted && project.file().starts_with(archive_dir) {
+            Section::Archived
+        } else if project.canceled() {
+            Section::Canceled
+        } else {
+            Section::Open
+        }
+    }
+}
+
+/// Splits projects into sections, keeping their order within each section.
+///
+/// Empty sections are left out.
+pub fn group_projects<I>(projects: I, archive_dir: &Path) -> Vec<(Section, Vec<Project>)>
+    where I: IntoIterator<Item=Project>
+{
+    let mut sections = vec![(Section::Open, Vec::new()),
+                            (Section::Canceled, Vec::new()),
+                            (Section::Archived, Vec::new())];
+    for project in projects {
+        let section = Section::of(&project, archive_dir);
+        if let Some(&mut (_, ref mut list)) = sections.iter_mut().find(|&&mut (s, _)| s == section) {
+            list.push(project);
+        }
+    }
+    sections.retain(|(_, list)| !list.is_empty());
+    sections
+}
+
+/// Rows for the configured `ListMode`, csv and porcelain lists fall back to `verbose_rows()`.
+pub fn rows(projects:&[Project], list_config:&ListConfig<'_>) -> Vec<Row>{
+    match list_config.mode {
+        ListMode::Paths   => path_rows(projects, list_config),
+        ListMode::Simple  => simple_rows(projects, list_config),
+        ListMode::Nothing => dynamic_rows(projects, list_config),
+        ListMode::Verbose |
+        ListMode::Csv |
+        ListMode::Porcelain => verbose_rows(projects, list_config),
+    }
+}
+
+/// Prints Projects Rows
+///
+/// This doesn't do much, except taking a Vec of Rows and printing it,
+/// the interesting code is in `dynamic_rows()`, `verbose_rows()`, `path_rows()` or `simple_rows()`.
+/// This Documentations is redundant, infact, it is already longer than the function itself.
+pub fn print_projects(rows: Vec<Row>){
+    log::trace!("starting table print");
+    let mut table = Table::init(rows);
+    table.set_format(FormatBuilder::new().column_separator(' ').padding(0,0).build());
+    table.printstd();
+    log::debug!("this table has {} lines", table.len());
+    if let Some(term_dims) = term_size::dimensions() {
+        log::debug!("terminal dimension {:?}", term_dims);
+    }
+    log::trace!("done printing table.");
+}
+
+/// Monthly revenue of `year` in cents, by invoice date.
+///
+/// Plain values, an empty month has no currency symbol to add up with the others.
+pub fn monthly_revenue(projects:&[Project], year:i32) -> [i64; 12] {
+    let mut months = [0; 12];
+    for project in projects {
+        if let (Ok(date), Ok(sum)) = (project.invoice().date(), project.sum_sold()) {
+            if date.year() == year {
+                months[date.month0() as usize] += sum.value;
+            }
+        }
+    }
+    months
+}
+
+/// Renders values as a line of bars, empty values stay blank.
+pub fn sparkline(values:&[i64], ascii: bool) -> String {
+    let bars: &[char] = if ascii { &['.', ':', '-', '=', '+', '*', '#', '@'] }
+                        else     { &['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'] };
+    let max = values.iter().cloned().max().unwrap_or(0);
+
+    values.iter()
+          .map(|&value| if value <= 0 || max <= 0 { ' ' }
+                        else { bars[((value * (bars.len() as i64 - 1)) / max) as usize] })
+          .collect()
+}
+
+/// Per month revenue sparkline of `year` and the total.
+fn revenue_sparkline(projects:&[Project], year:i32, ascii: bool) -> String {
+    let months = monthly_revenue(projects, year);
+    let total = Currency { value: months.iter().sum(), ..to_currency(0.0) };
+    format!("{:04} {} {}", year, sparkline(&months, ascii), currency_to_string(&total))
+}
+
+/// Prints a per month revenue sparkline, used above yearly listings.
+pub fn print_revenue_sparkline(projects:&[Project], year:i32, ascii: bool) {
+    println!("     JFMAMJJASOND");
+    println!("{}", revenue_sparkline(projects, year, ascii));
+}
+
+/// Prints hours and wages of every employee per project, with totals.
+///
+/// `employee` narrows the table down to names containing it.
+pub fn print_hours(projects:&[Project], employee: Option<&str>, ascii: bool) {
+    let needle = employee.map(str::to_lowercase);
+
+    let mut by_employee: BTreeMap<String, Vec<(String, Employee)>> = BTreeMap::new();
+    for project in projects {
+        for e in project.hours().employees().unwrap_or_default() {
+            if let Some(ref needle) = needle {
+                if !e.name.to_lowercase().contains(needle) {
+                    continue;
+                }
+            }
+            by_employee.entry(e.name.clone())
+                       .or_default()
+                       .push((project.short_desc(), e));
+        }
+    }
+
+    let mut table = Table::new();
+    table_with_borders(&mut table, ascii);
+    table.set_titles(row![lformat!("employee"), lformat!("project"),
+                          r->lformat!("hours"), r->lformat!("salary"), r->lformat!("wage")]);
+
+    let mut total_time = 0.0;
+    let mut total_wage = Currency::default();
+    for (name, entries) in &by_employee {
+        let mut time = 0.0;
+        let mut wage = Currency::default();
+        for (project, e) in entries {
+            table.add_row(row![name, project, r->e.time, r->currency_to_string(&e.salary), r->currency_to_string(&e.wage)]);
+            time += e.time;
+            wage = wage + e.wage;
+        }
+        table.add_row(row!["", b->lformat!("sum"), rb->time, "", rb->currency_to_string(&wage)]);
+        total_time += time;
+        total_wage = total_wage + wage;
+    }
+    table.add_row(row![b->lformat!("Total"), "", rb->total_time, "", rb->currency_to_string(&total_wage)]);
+
+    table.printstd();
+}
+
+/// Prints a timesheet with dates, projects, hours and wages.
+pub fn print_timesheet(sheet: &Timesheet, ascii: bool) {
+    println!("{}", lformat!("Timesheet {} {:02}/{}", sheet.employee, sheet.month, sheet.year));
+
+    let mut table = Table::new();
+    table_with_borders(&mut table, ascii);
+    table.set_titles(row![lformat!("date"), lformat!("project"), lformat!("employee"),
+                          r->lformat!("hours"), r->lformat!("salary"), r->lformat!("wage")]);
+
+    for entry in &sheet.entries {
+        table.add_row(row![entry.date.format("%d.%m.%Y"), entry.project, entry.employee,
+                           r->entry.time, r->currency_to_string(&entry.salary), r->currency_to_string(&entry.wage)]);
+    }
+    table.add_row(row![b->lformat!("Total"), "", "", rb->sheet.total_time(), "", rb->currency_to_string(&sheet.total_wage())]);
+
+    table.printstd();
+}
+
+/// Prints who works at which event, understaffed events in red.
+pub fn print_staffing(plan: &[Staffing<'_>], year: i32, month: u32, ascii: bool) {
+    println!("{}", lformat!("Staff {:02}/{}", month, year));
+
+    let mut table = Table::new();
+    table_with_borders(&mut table, ascii);
+    table.set_titles(row![lformat!("date"), lformat!("event"), r->lformat!("required"), r->lformat!("assigned"), lformat!("employees")]);
+
+    for staffing in plan {
+        let date = if staffing.first == staffing.last {
+            staffing.first.format("%d.%m.%Y").to_string()
+        } else {
+            format!("{}-{}", staffing.first.format("%d.%m."), staffing.last.format("%d.%m.%Y"))
+        };
+        let name = staffing.project.short_desc();
+        let required = staffing.required.map(|n| n.to_string()).unwrap_or_default();
+        let assigned = staffing.assigned.join(", ");
+        if staffing.is_understaffed() {
+            table.add_row(row![Fr->date, Fr->name, Frr->required, Frr->staffing.assigned.len(), Fr->assigned]);
+        } else {
+            table.add_row(row![date, name, r->required, r->staffing.assigned.len(), assigned]);
+        }
+    }
+
+    table.printstd();
+}
+
+/// Prints revenue and VAT per tax rate, as needed for the advance VAT return.
+pub fn print_vat_report(report: &VatReport, ascii: bool) {
+    println!("{}", lformat!("VAT {} ({} invoices)", report.period(), report.invoices));
+
+    let mut table = Table::new();
+    table_with_borders(&mut table, ascii);
+    table.set_titles(row![lformat!("tax rate"), r->lformat!("net"), r->lformat!("VAT"), r->lformat!("gross")]);
+
+    for row in &report.rows {
+        table.add_row(row![report::percent(row.tax),
+                           r->currency_to_string(&row.net),
+                           r->currency_to_string(&row.vat),
+                           r->currency_to_string(&(row.net + row.vat))]);
+    }
+    table.add_row(row![b->lformat!("Total"),
+                       rb->currency_to_string(&report.total_net()),
+                       rb->currency_to_string(&report.total_vat()),
+                       rb->currency_to_string(&(report.total_net() + report.total_vat()))]);
+
+    table.printstd();
+}
+
+pub fn print_number_report(report: &NumberReport) {
+    println!("{}", lformat!("Invoice numbers {}, up to {}", report.year, report.highest));
+    for number in &report.gaps {
+        println!("  {}", lformat!("{} is missing", number));
+    }
+    for (number, names) in &report.duplicates {
+        println!("  {}", lformat!("{} is used by {}", number, names.join(", ")));
+    }
+    for (number, name) in &report.unrecorded {
+        println!("  {}", lformat!("{} of {} is not in the ledger", number, name));
+    }
+    for issued in &report.orphaned {
+        println!("  {}", lformat!("{} was issued to {} on {}, no project has it", issued.number, issued.project, issued.date.format("%d.%m.%Y")));
+    }
+    if report.is_clean() {
+        println!("  {}", lformat!("no gaps, no duplicates"));
+    }
+}
+
+pub fn print_income_report(report: &IncomeReport, ascii: bool) {
+    println!("{}", lformat!("Income {}", report.year));
+
+    let mut table = Table::new();
+    table_with_borders(&mut table, ascii);
+    table.set_titles(row![lformat!("month"), r->lformat!("revenue"), r->lformat!("wages"), r->lformat!("expenses"), r->lformat!("profit")]);
+
+    for (month, row) in report.months.iter().enumerate() {
+        table.add_row(row![format!("{:02}/{}", month + 1, report.year),
+                           r->currency_to_string(&row.revenue),
+                           r->currency_to_string(&row.wages),
+                           r->currency_to_string(&row.expenses),
+                           r->currency_to_string(&row.profit())]);
+    }
+    let total = report.total();
+    table.add_row(row![b->lformat!("Total"),
+                       rb->currency_to_string(&total.revenue),
+                       rb->currency_to_string(&total.wages),
+                       rb->currency_to_string(&total.expenses),
+                       rb->currency_to_string(&total.profit())]);
+
+    table.printstd();
+}
+
+pub fn print_client_report(report: &ClientReport, ascii: bool) {
+    match report.year {
+        Some(year) => println!("{}", lformat!("Clients {}", year)),
+        None => println!("{}", lformat!("Clients")),
+    }
+
+    let mut table = Table::new();
+    table_with_borders(&mut table, ascii);
+    table.set_titles(row![lformat!("client"), r->lformat!("projects"), r->lformat!("revenue"), r->lformat!("average"), r->lformat!("payment delay")]);
+
+    for row in &report.rows {
+        table.add_row(row![row.client,
+                           r->row.projects,
+                           r->currency_to_string(&row.revenue),
+                           r->currency_to_string(&row.average_revenue()),
+                           r->row.payment_delay.map(|days| lformat!("{:.0} days", days)).unwrap_or_default()]);
+    }
+    table.add_row(row![b->lformat!("Total"),
+                       rb->report.rows.iter().map(|r| r.projects).sum::<usize>(),
+                       rb->currency_to_string(&report.total_revenue()),
+                       "",
+                       ""]);
+
+    table.printstd();
+}
+
+/// Prints Projects as CSV
+pub fn print_csv_year(year:i32){
+    match crate::actions::csv(year) {
+        Ok(csv) => println!("{}", csv),
+        Err(err) => println!("{}", err),
+    }
+}
+
+/// Prints Projects for scripts, see `porcelain`.
+pub fn print_porcelain(projects:&[Project]){
+    for project in projects {
+        println!("{}", porcelain::project_line(project));
+    }
+}
+
+/// Prints Projects as CSV
+pub fn print_csv(projects:&[Project]){
+    match crate::actions::projects_to_csv(projects) {
+        Ok(csv) => println!("{}", csv),
+        Err(err) => println!("{}", err),
+    }
+}
+
+//fn table_for_arrangement(table:&mut Table){
+//    table.set_format(FormatBuilder::new() .padding(0, 0) .build());
+//}
+
+fn table_with_borders(table:&mut Table, ascii: bool){
+    let format = if ascii {
+        FormatBuilder::new()
+            .borders('|').padding(1, 1)
+            .separators( &[LinePosition::Title, LinePosition::Top, LinePosition::Bottom], LineSeparator::new('-', '-', '+', '+'))
+            .build()
+    } else {
+        FormatBuilder::new()
+            .borders('│').padding(1, 1)
+            .separators( &[LinePosition::Title], LineSeparator::new('─', '─', '├', '┤'))
+            .separators( &[LinePosition::Top],    LineSeparator::new('─', '─', '┌', '┐'))
+            .separators( &[LinePosition::Bottom], LineSeparator::new('─', '─', '└', '┘'))
+            .build()
+    };
+    table.set_format(format);
+}
+
+fn bill_table(bill: &Bill<Product<'_>>, ascii: bool) -> Table {
+    // TODO: move to Project::product_table(&self) {
+    let mut table = Table::new();
+    log::trace!("                   - created table");
+    //table.set_format(*format::consts::FORMAT_BORDERS_ONLY);
+    table_with_borders(&mut table, ascii);
+    //table.set_titles( row![cell!(""), bill_type, cell!(project.name())]);
+    //table.add_row( row![cell!(""), cell!("name"), cell!("amount"), cell!("price"), cell!("cost")]);
+    log::trace!("                   - added a row");
+    for (index,item) in bill.as_items().iter().enumerate(){
+        table.add_row(
+            row![ cell!((index+1).to_string()),
+                  item.product.name,
+                  r->item.amount.to_string(),
+                  r->currency_to_string(&item.product.price),
+                  r->currency_to_string(&(item.gross()))
+            ]);
+    }
+
+    table.add_row( row![cell!(""), cell!("======="), cell!(r->"======"), cell!(r->"======"), cell!(r->"======")]);
+    for (&tax, itemlist) in bill.iter() {
+        table.add_row( row!["",
+                            "",
+                            "",
+                            "",
+                            cell!(r->itemlist.gross_sum().postfix())
+        ]);
+        if itemlist.tax_sum().value() > 0 {
+            table.add_row( row!["",
+                                "",
+                                "",
+                                cell!(r->format!("+{}%",**tax*100f64)),
+                                cell!(r->format!("{}", itemlist.tax_sum().postfix()))
+                                //cell!(r->itemlist.net_sum().postfix())
+            ]);
+        }
+    }
+    table.add_row( row!["", "Total", "", "", bill.net_total().postfix()]);
+    // }
+
+    table
+}
+
+/// How much more is invoiced than offered.
+///
+/// Missing or empty sides count as zero, `Currency::default()` has no symbol and can't be subtracted from one that has.
+fn cost_delta(offered: Option<Currency>, invoiced: Option<Currency>) -> Currency {
+    Currency {
+        symbol: invoiced.and_then(|cost| cost.symbol).or_else(|| offered.and_then(|cost| cost.symbol)),
+        value: invoiced.map_or(0, |cost| cost.value) - offered.map_or(0, |cost| cost.value),
+    }
+}
+
+/// Offer and invoice next to each other, matched by product name.
+///
+/// The last column shows how much more (or less) is invoiced than was offered.
+fn bill_comparison_table(offer: &Bill<Product<'_>>, invoice: &Bill<Product<'_>>, ascii: bool) -> Table {
+    let offer_items = offer.as_items();
+    let invoice_items = invoice.as_items();
+
+    let mut names = offer_items.iter().map(|item| item.product.name).collect::<Vec<_>>();
+    for item in &invoice_items {
+        if !names.contains(&item.product.name) {
+            names.push(item.product.name);
+        }
+    }
+
+    let mut table = Table::new();
+    table_with_borders(&mut table, ascii);
+    table.set_titles(row![ "", lformat!("product"),
+                           r->lformat!("offer"), "",
+                           r->lformat!("invoice"), "",
+                           r->lformat!("delta")]);
+
+    let find = |items: &[&BillItem<Product<'_>>], name: &str| items.iter()
+        .find(|item| item.product.name == name)
+        .map(|item| (item.amount, item.gross()));
+
+    for (index, name) in names.iter().enumerate() {
+        let offered  = find(&offer_items, name);
+        let invoiced = find(&invoice_items, name);
+        let amount_cell = |entry: Option<(f64, Currency)>| cell!(r->entry.map(|(amount, _)| amount.to_string()).unwrap_or_default());
+        let cost_cell   = |entry: Option<(f64, Currency)>| cell!(r->entry.map(|(_, cost)| currency_to_string(&cost)).unwrap_or_default());
+        let delta = cost_delta(offered.map(|(_, cost)| cost), invoiced.map(|(_, cost)| cost));
+
+        let delta_style = match delta.value { 0 => "r", v if v > 0 => "rFg", _ => "rFr" };
+
+        table.add_row(Row::new(vec![
+            cell!((index+1).to_string()),
+            cell!(name),
+            amount_cell(offered),
+            cost_cell(offered),
+            amount_cell(invoiced),
+            cost_cell(invoiced),
+            cell!(if delta.value == 0 { String::new() } else { currency_to_string(&delta) })
+                .style_spec(delta_style),
+        ]));
+    }
+
+    let delta = cost_delta(Some(offer.net_total()), Some(invoice.net_total()));
+    table.add_row( row!["", "Total", "", offer.net_total().postfix(), "", invoice.net_total().postfix(), r->currency_to_string(&delta)]);
+    table
+}
+
+/// Prints the products of a bill in a table.
+///
+/// With `side_by_side` offer and invoice are printed next to each other, see `bill_comparison_table()`.
+pub fn show_details(project:&Project, config: &DetailsConfig) {
+    log::trace!("print::show_details()");
+    println!("{}", details_header(project, config));
+
+    if let Some(table) = details_table(project, config) {
+        table.printstd();
+    }
+
+    println!("{}", details_footer(project));
+}
+
+fn details_header(project:&Project, config: &DetailsConfig) -> String {
+    if config.side_by_side {
+        format!("{}: {}", lformat!("offer vs invoice"), project.short_desc())
+    } else {
+        format!("{}: {}", config.bill_type.to_string(), project.short_desc())
+    }
+}
+
+fn details_table(project:&Project, config: &DetailsConfig) -> Option<Table> {
+    let (offer, invoice) = match project.bills() {
+        Ok(tuple) => tuple,
+        Err(e) => {
+            log::error!("{}, sorry", e);
+            return None
+        }
+    };
+
+    if config.side_by_side {
+        Some(bill_comparison_table(&offer, &invoice, config.ascii))
+    } else {
+        let bill = match config.bill_type {
+            BillType::Offer => offer,
+            BillType::Invoice => invoice
+        };
+        Some(bill_table(&bill, config.ascii))
+    }
+}
+
+/// times and employees
+fn details_footer(project:&Project) -> String {
+    let mut footer = String::new();
+    if let Some(events) = project.events() {
+        for event in events {
+            footer.push_str(&format!("{}\n", event));
+        }
+    }
+
+    footer.push_str(&project.hours().employees_string().unwrap_or_default());
+    footer
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use crate::project::Project;
+    use crate::project::spec::Redeemable;
+    use super::{sparkline, monthly_revenue, revenue_sparkline, bill_comparison_table, cost_delta, group_projects, Section};
+    use bill::Currency;
+
+    #[test]
+    fn sparkline_scales_to_max() {
+        assert_eq!(sparkline(&[0, 1, 4, 8], false), " ▁▄█");
+        assert_eq!(sparkline(&[0, 1, 4, 8], true),  " .=@");
+        assert_eq!(sparkline(&[0, 0], false), "  ");
+        assert_eq!(sparkline(&[], false), "");
+    }
+
+    #[test]
+    fn revenue_with_an_empty_month() {
+        let project = |date: &str| Project::from_file_content(&format!(
+            "invoice:\n  number: 1\n  date: {}\nproducts:\n  Kaffee:\n    amount: 4\n    price: 2.5\nhours:\n  salary: 8.0\n  caterers:\n    Klaus: 1\ntax: 0.19\n", date)).unwrap();
+        let projects = vec![project("10.01.2025"), project("20.03.2025"), project("01.02.2024")];
+
+        let sold = projects[0].sum_sold().unwrap().value;
+        let months = monthly_revenue(&projects, 2025);
+        assert_eq!(&months[..4], &[sold, 0, sold, 0]);
+        assert!(revenue_sparkline(&projects, 2025, true).starts_with("2025 @ @ "));
+    }
+
+    #[test]
+    fn compare_item_missing_from_invoice() {
+        let project = Project::from_file_content("---
+products:
+  Kaffee:
+    amount: 4
+    price: 2.5
+  Kuchen:
+    amount: 12
+    returned: 12
+    price: 0.94
+hours:
+  salary: 8.0
+  caterers:
+    Klaus: 1
+tax: 0.19
+").unwrap();
+        let (offer, invoice) = project.bills().unwrap();
+        assert_eq!(invoice.as_items().iter().filter(|item| item.product.name == "Kuchen").count(), 0);
+
+        let kuchen = offer.as_items().iter().find(|item| item.product.name == "Kuchen").unwrap().gross();
+        assert_eq!(cost_delta(Some(kuchen), None), Currency { symbol: kuchen.symbol, value: -kuchen.value });
+        assert_eq!(cost_delta(None, Some(kuchen)), kuchen);
+        assert_eq!(cost_delta(Some(Currency::default()), Some(kuchen)), kuchen);
+
+        // not rendered, printing any table crashes with prettytable 0.8 on current compilers
+        bill_comparison_table(&offer, &invoice, true);
+    }
+
+    #[test]
+    fn groups_canceled_projects() {
+        let open = Project::from_file_content("event:\n  name: open\n").unwrap();
+        let canceled = Project::from_file_content("canceled: true\n").unwrap();
+
+        let sections = group_projects(vec![canceled, open], Path::new("/archive"));
+        let sections = sections.iter().map(|(section, list)| (*section, list.len())).collect::<Vec<_>>();
+        assert_eq!(sections, vec![(Section::Open, 1), (Section::Canceled, 1)]);
+    }
+}
@@ -0,0 +1,134 @@
+//! Writes listings and project details into files instead of stdout.
+//!
+//! The format is picked by the extension of the target file.
+
+use std::ffi::OsStr;
+use std::path::Path;
+
+use anyhow::Error;
+use prettytable::Table;
+use prettytable::format::FormatBuilder;
+
+use crate::project::Project;
+use crate::util;
+use super::{ListConfig, DetailsConfig};
+
+/// Representation of a file written with `--output`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum OutputFormat { Text, Csv, Json, Html }
+
+impl OutputFormat {
+    /// Picks the format by file extension, anything unknown is plain text.
+    pub fn from_path(path: &Path) -> OutputFormat {
+        match path.extension().and_then(OsStr::to_str).map(str::to_lowercase).as_deref() {
+            Some("csv")            => OutputFormat::Csv,
+            Some("json")           => OutputFormat::Json,
+            Some("html" | "htm")   => OutputFormat::Html,
+            _                      => OutputFormat::Text,
+        }
+    }
+}
+
+fn projects_to_json(projects: &[&Project]) -> Result<String, Error> {
+    let objects = projects.iter()
+                          .map(|p| p.to_json())
+                          .collect::<Result<Vec<_>, _>>()?;
+    Ok(format!("[{}]", objects.join(",")))
+}
+
+fn escape_html(content: &str) -> String {
+    content.replace('&', "&amp;")
+           .replace('<', "&lt;")
+           .replace('>', "&gt;")
+           .replace('"', "&quot;")
+}
+
+fn table_to_html(table: &Table) -> String {
+    let mut html = String::from("<table>\n");
+    for row in table.row_iter() {
+        html.push_str("  <tr>");
+        for cell in row.iter() {
+            html.push_str(&format!("<td>{}</td>", escape_html(&cell.get_content())));
+        }
+        html.push_str("</tr>\n");
+    }
+    html.push_str("</table>\n");
+    html
+}
+
+fn table_to_text(table: &Table) -> Result<String, Error> {
+    let mut buffer = Vec::new();
+    table.print(&mut buffer)?;
+    Ok(String::from_utf8(buffer)?)
+}
+
+fn write_file(path: &Path, content: &str) -> Result<(), Error> {
+    util::atomic_write(path, content)?;
+    log::info!("{}", lformat!("wrote {}", path.display()));
+    Ok(())
+}
+
+/// Writes a project listing into `path`.
+///
+/// Text and html contain the same rows as `asciii list` would print with this `ListConfig`.
+pub fn write_projects(path: &Path, projects: &[Project], list_config: &ListConfig<'_>) -> Result<(), Error> {
+    let content = match OutputFormat::from_path(path) {
+        OutputFormat::Csv  => crate::actions::projects_to_csv(projects)?,
+        OutputFormat::Json => projects_to_json(&projects.iter().collect::<Vec<_>>())?,
+        format => {
+            let mut table = Table::init(super::rows(projects, list_config));
+            table.set_format(FormatBuilder::new().column_separator(' ').padding(0,0).build());
+            if format == OutputFormat::Html { table_to_html(&table) } else { table_to_text(&table)? }
+        }
+    };
+    write_file(path, &content)
+}
+
+/// Writes the details of projects into `path`, like `asciii show` would print them.
+pub fn write_details(path: &Path, projects: &[Project], config: &DetailsConfig) -> Result<(), Error> {
+    let format = OutputFormat::from_path(path);
+    let content = match format {
+        OutputFormat::Json => projects_to_json(&projects.iter().collect::<Vec<_>>())?,
+        _ => {
+            let mut content = String::new();
+            for project in projects {
+                match format {
+                    OutputFormat::Csv => content.push_str(&project.to_csv(config.bill_type)?),
+                    OutputFormat::Html => {
+                        content.push_str(&format!("<h1>{}</h1>\n", escape_html(&super::details_header(project, config))));
+                        content.push_str(&super::details_table(project, config).as_ref().map(table_to_html).unwrap_or_default());
+                        content.push_str(&format!("<pre>{}</pre>\n", escape_html(&super::details_footer(project))));
+                    },
+                    _ => {
+                        content.push_str(&format!("{}\n", super::details_header(project, config)));
+                        if let Some(ref table) = super::details_table(project, config) {
+                            content.push_str(&table_to_text(table)?);
+                        }
+                        content.push_str(&format!("{}\n", super::details_footer(project)));
+                    }
+                }
+            }
+            content
+        }
+    };
+    write_file(path, &content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_from_extension() {
+        assert_eq!(OutputFormat::from_path(Path::new("list.csv")),  OutputFormat::Csv);
+        assert_eq!(OutputFormat::from_path(Path::new("list.JSON")), OutputFormat::Json);
+        assert_eq!(OutputFormat::from_path(Path::new("list.htm")),  OutputFormat::Html);
+        assert_eq!(OutputFormat::from_path(Path::new("list.txt")),  OutputFormat::Text);
+        assert_eq!(OutputFormat::from_path(Path::new("list")),      OutputFormat::Text);
+    }
+
+    #[test]
+    fn html_is_escaped() {
+        assert_eq!(escape_html("<b>\"Kaffee & Kuchen\"</b>"), "&lt;b&gt;&quot;Kaffee &amp; Kuchen&quot;&lt;/b&gt;");
+    }
+}
@@ -0,0 +1,103 @@
+//! Output for scripts, see `--porcelain`.
+//!
+//! One record per line, fields separated by tabs, never colored or translated.
+//! Tabs and newlines inside values become spaces, missing values are empty fields.
+//! Dates are `YYYY-MM-DD`, amounts are plain numbers with two decimals.
+//! Columns are only ever appended, so scripts can rely on their position.
+//!
+//! * `list`: invoice number, name, event date, manager, invoice date, payed date, total, canceled, file
+//! * `validate`: file, severity, stage, field, message
+//! * `dues`: `payments` or `wages` and the amount, with `--wages` also `employee`, name and amount
+//! * `log`: date, commit, author, project, `created`, `edited`, `payed`, `archived` or `deleted` and the edited fields
+
+use bill::Currency;
+use chrono::prelude::*;
+
+use crate::actions::Dues;
+use crate::actions::activity::{Activity, Change};
+use crate::project::error::Diagnostic;
+use crate::project::spec::{Invoicable, IsProject, Redeemable};
+use crate::project::Project;
+use crate::storage::Storable;
+
+fn field(value: &str) -> String {
+    value.replace(['\t', '\n', '\r'], " ")
+}
+
+fn line(fields: &[String]) -> String {
+    fields.iter().map(|f| field(f)).collect::<Vec<_>>().join("\t")
+}
+
+fn date<E>(date: Result<Date<Utc>, E>) -> String {
+    date.map(|d| d.format("%Y-%m-%d").to_string()).unwrap_or_default()
+}
+
+fn amount(currency: Currency) -> String {
+    format!("{:.2}", currency.as_float())
+}
+
+pub fn project_line(project: &Project) -> String {
+    line(&[project.invoice().number_long_str().unwrap_or_default(),
+           project.name().map(ToOwned::to_owned).unwrap_or_default(),
+           date(project.event_date()),
+           project.responsible().map(ToOwned::to_owned).unwrap_or_default(),
+           date(project.invoice().date()),
+           date(project.payed_date()),
+           project.sum_sold().map(amount).unwrap_or_default(),
+           project.canceled().to_string(),
+           project.file().display().to_string()])
+}
+
+pub fn diagnostic_line(diagnostic: &Diagnostic) -> String {
+    line(&[diagnostic.file.display().to_string(),
+           diagnostic.severity.to_string(),
+           diagnostic.stage.clone(),
+           diagnostic.field.clone(),
+           diagnostic.message.clone()])
+}
+
+/// Employees are sorted by name.
+pub fn dues_lines(dues: &Dues, wages: bool) -> Vec<String> {
+    let mut lines = vec![line(&["payments".into(), amount(dues.acc_sum_sold)]),
+                         line(&["wages".into(), amount(dues.acc_wages)])];
+    if wages {
+        let mut employees = dues.unpayed_employees.iter().collect::<Vec<_>>();
+        employees.sort_by(|a, b| a.0.cmp(b.0));
+        lines.extend(employees.into_iter()
+                              .map(|(name, open)| line(&["employee".into(), name.clone(), amount(*open)])));
+    }
+    lines
+}
+
+pub fn activity_line(activity: &Activity) -> String {
+    let fields = match activity.change {
+        Change::Edited(ref fields) => fields.join(","),
+        _ => String::new()
+    };
+    line(&[activity.date.clone(),
+           activity.commit.clone(),
+           activity.author.clone(),
+           activity.project.clone(),
+           activity.change.kind().to_owned(),
+           fields])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use crate::project::error::Severity;
+
+    #[test]
+    fn one_line_per_record() {
+        let project = Project::from_file_content("event:\n  name: \"Tab\\there\"\n  dates:\n  - begin: 24.12.2024\nmanager: Max\ncanceled: false\n").unwrap();
+        let fields = project_line(&project).split('\t').map(ToOwned::to_owned).collect::<Vec<_>>();
+        assert_eq!(fields.len(), 9);
+        assert_eq!(&fields[1..4], &["Tab here", "2024-12-24", "Max"]);
+        assert_eq!(fields[7], "false");
+
+        let diagnostic = Diagnostic { file: PathBuf::from("a.yml"), field: "invoice/date".into(), stage: "invoice".into(),
+                                      severity: Severity::Error, message: "not\na date".into() };
+        assert_eq!(diagnostic_line(&diagnostic), "a.yml\terror\tinvoice\tinvoice/date\tnot a date");
+    }
+}
@@ -0,0 +1,342 @@
+//! User defined row styles for `asciii list`.
+//!
+//! Rules are read from `list/row_styles` and look like this:
+//!
+//! ```yaml
+//! list:
+//!   row_styles:
+//!     - if field invoice/payed == false and age > 30 then red bold
+//!     - if canceled then none
+//! ```
+//!
+//! A condition is either one of the builtin subjects (`age`, `canceled`, `payed`,
+//! `employees_payed`, `ready_for_offer`, `ready_for_invoice`, `ready_for_archive`)
+//! or `field <path>` which is looked up through `Project::field()`.
+//! Conditions may be compared with `==`, `!=`, `<`, `<=`, `>`, `>=`, negated with `not`
+//! and joined with `and`/`or` (`and` binds stronger).
+//! The first rule that matches decides the style of the row.
+
+use std::fmt;
+use std::str::FromStr;
+
+use thiserror::Error;
+
+use crate::config::ConfigReader;
+use crate::project::Project;
+use crate::project::spec::{IsProject, IsProjectExt, Redeemable, HasEmployees};
+
+#[derive(Debug, Error, PartialEq)]
+pub enum RuleError {
+    #[error("rule must look like \"if <condition> then <style>\"")]
+    Syntax,
+
+    #[error("condition is empty")]
+    EmptyCondition,
+
+    #[error("unknown subject {0:?}")]
+    UnknownSubject(String),
+
+    #[error("\"field\" needs a path")]
+    MissingPath,
+
+    #[error("comparison with {0:?} needs a value")]
+    MissingValue(String),
+
+    #[error("unexpected {0:?}")]
+    Unexpected(String),
+
+    #[error("unknown style {0:?}")]
+    UnknownStyle(String),
+}
+
+/// What a condition looks at.
+#[derive(Debug, Clone, PartialEq)]
+enum Subject {
+    Age,
+    Canceled,
+    Payed,
+    EmployeesPayed,
+    ReadyForOffer,
+    ReadyForInvoice,
+    ReadyForArchive,
+    Field(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Op { Eq, Ne, Lt, Le, Gt, Ge }
+
+impl Op {
+    fn parse(token: &str) -> Option<Op> {
+        match token {
+            "==" | "=" => Some(Op::Eq),
+            "!="       => Some(Op::Ne),
+            "<"        => Some(Op::Lt),
+            "<="       => Some(Op::Le),
+            ">"        => Some(Op::Gt),
+            ">="       => Some(Op::Ge),
+            _          => None
+        }
+    }
+}
+
+/// A value either found in a project or written in a rule.
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Missing,
+    Bool(bool),
+    Number(f64),
+    Text(String),
+}
+
+impl Value {
+    fn parse(token: &str) -> Value {
+        match token {
+            "none"  => Value::Missing,
+            "true"  => Value::Bool(true),
+            "false" => Value::Bool(false),
+            _ => token.parse::<f64>()
+                      .map(Value::Number)
+                      .unwrap_or_else(|_| Value::Text(token.trim_matches('"').to_owned()))
+        }
+    }
+
+    fn is_truthy(&self) -> bool {
+        match *self {
+            Value::Missing => false,
+            Value::Bool(b) => b,
+            Value::Number(n) => n != 0.0,
+            Value::Text(ref t) => !t.is_empty(),
+        }
+    }
+
+    fn compare(&self, op: Op, other: &Value) -> bool {
+        use std::cmp::Ordering;
+        let ordering = match (self, other) {
+            // a missing value is only equal to `none` or `false`
+            (Value::Missing, Value::Missing) |
+            (Value::Missing, Value::Bool(false)) => Some(Ordering::Equal),
+            (Value::Missing, _) => None,
+            (Value::Bool(a), Value::Bool(b)) => Some(a.cmp(b)),
+            (Value::Number(a), Value::Number(b)) => a.partial_cmp(b),
+            (Value::Text(a), Value::Text(b)) => Some(a.cmp(b)),
+            _ => None
+        };
+
+        match (op, ordering) {
+            (Op::Ne, None) => true,
+            (_, None) => false,
+            (Op::Eq, Some(o)) => o == Ordering::Equal,
+            (Op::Ne, Some(o)) => o != Ordering::Equal,
+            (Op::Lt, Some(o)) => o == Ordering::Less,
+            (Op::Le, Some(o)) => o != Ordering::Greater,
+            (Op::Gt, Some(o)) => o == Ordering::Greater,
+            (Op::Ge, Some(o)) => o != Ordering::Less,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Condition {
+    negated: bool,
+    subject: Subject,
+    comparison: Option<(Op, Value)>,
+}
+
+impl Condition {
+    fn parse(tokens: &[&str]) -> Result<Condition, RuleError> {
+        let mut tokens = tokens.iter().cloned().peekable();
+
+        let negated = if tokens.peek() == Some(&"not") { tokens.next(); true } else { false };
+
+        let subject = match tokens.next().ok_or(RuleError::EmptyCondition)? {
+            "age"               => Subject::Age,
+            "canceled"          => Subject::Canceled,
+            "payed"             => Subject::Payed,
+            "employees_payed"   => Subject::EmployeesPayed,
+            "ready_for_offer"   => Subject::ReadyForOffer,
+            "ready_for_invoice" => Subject::ReadyForInvoice,
+            "ready_for_archive" => Subject::ReadyForArchive,
+            "field"             => Subject::Field(tokens.next().ok_or(RuleError::MissingPath)?.to_owned()),
+            unknown             => return Err(RuleError::UnknownSubject(unknown.to_owned()))
+        };
+
+        let comparison = match tokens.next() {
+            None => None,
+            Some(token) => {
+                let op = Op::parse(token).ok_or_else(|| RuleError::Unexpected(token.to_owned()))?;
+                let value = tokens.next().ok_or_else(|| RuleError::MissingValue(token.to_owned()))?;
+                Some((op, Value::parse(value)))
+            }
+        };
+
+        if let Some(token) = tokens.next() {
+            return Err(RuleError::Unexpected(token.to_owned()));
+        }
+
+        Ok(Condition { negated, subject, comparison })
+    }
+
+    fn value_of(&self, project: &Project) -> Value {
+        match self.subject {
+            Subject::Age             => project.age().map(|a| Value::Number(a as f64)).unwrap_or(Value::Missing),
+            Subject::Canceled        => Value::Bool(project.canceled()),
+            Subject::Payed           => Value::Bool(project.is_payed()),
+            Subject::EmployeesPayed  => Value::Bool(project.hours().employees_payed()),
+            Subject::ReadyForOffer   => Value::Bool(project.is_missing_for_offer().is_empty()),
+            Subject::ReadyForInvoice => Value::Bool(project.is_missing_for_invoice().is_empty()),
+            Subject::ReadyForArchive => Value::Bool(project.is_ready_for_archive().is_empty()),
+            Subject::Field(ref path) => project.field(path).map(|v| Value::parse(&v)).unwrap_or(Value::Missing),
+        }
+    }
+
+    fn matches(&self, project: &Project) -> bool {
+        let value = self.value_of(project);
+        let result = match self.comparison {
+            Some((op, ref other)) => value.compare(op, other),
+            None => value.is_truthy()
+        };
+        result != self.negated
+    }
+}
+
+/// One rule of `list/row_styles`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RowStyleRule {
+    /// conditions joined by `or`, each of which is joined by `and`
+    any_of: Vec<Vec<Condition>>,
+    style: String,
+    source: String,
+}
+
+impl RowStyleRule {
+    /// Style spec as understood by `prettytable::Cell::style_spec()`.
+    pub fn style(&self) -> &str {
+        &self.style
+    }
+
+    pub fn matches(&self, project: &Project) -> bool {
+        self.any_of.iter()
+            .any(|all_of| all_of.iter().all(|condition| condition.matches(project)))
+    }
+}
+
+impl fmt::Display for RowStyleRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
+
+impl FromStr for RowStyleRule {
+    type Err = RuleError;
+
+    fn from_str(source: &str) -> Result<RowStyleRule, RuleError> {
+        let tokens = source.split_whitespace().collect::<Vec<_>>();
+
+        if tokens.first() != Some(&"if") {
+            return Err(RuleError::Syntax);
+        }
+        let then = tokens.iter().position(|t| *t == "then").ok_or(RuleError::Syntax)?;
+
+        let any_of = tokens[1..then]
+            .split(|t| *t == "or")
+            .map(|alternative| alternative.split(|t| *t == "and")
+                                          .map(Condition::parse)
+                                          .collect::<Result<Vec<_>, _>>())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let style = style_spec(&tokens[then + 1..])?;
+
+        Ok(RowStyleRule { any_of, style, source: source.to_owned() })
+    }
+}
+
+/// Translates style words into a prettytable style spec.
+fn style_spec(words: &[&str]) -> Result<String, RuleError> {
+    if words.is_empty() {
+        return Err(RuleError::Syntax);
+    }
+    words.iter().map(|word| Ok(match *word {
+        "none"      => "",
+        "default"   => "d",
+        "bold"      => "b",
+        "italic"    => "i",
+        "underline" => "u",
+        "black"     => "Fd",
+        "red"       => "Fr",
+        "green"     => "Fg",
+        "yellow"    => "Fy",
+        "blue"      => "Fb",
+        "magenta"   => "Fm",
+        "cyan"      => "Fc",
+        "white"     => "Fw",
+        unknown     => return Err(RuleError::UnknownStyle(unknown.to_owned()))
+    })).collect()
+}
+
+/// Parses every rule, invalid rules are reported and skipped.
+pub fn parse_rules(sources: &[&str]) -> Vec<RowStyleRule> {
+    sources.iter()
+           .filter_map(|source| source.parse()
+                                      .map_err(|e| log::warn!("ignoring row style {:?}: {}", source, e))
+                                      .ok())
+           .collect()
+}
+
+/// Rules configured in `list/row_styles`.
+pub fn rules_from_config(config: &ConfigReader) -> Vec<RowStyleRule> {
+    parse_rules(&config.get_strs("list/row_styles").unwrap_or_default())
+}
+
+/// Style of the first rule matching this project, or the default style.
+pub fn style_for<'a>(project: &Project, rules: &'a [RowStyleRule]) -> &'a str {
+    rules.iter()
+         .find(|rule| rule.matches(project))
+         .map(RowStyleRule::style)
+         .unwrap_or("")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project(content: &str) -> Project {
+        Project::from_file_content(content).unwrap()
+    }
+
+    #[test]
+    fn parses_rules() {
+        let rule: RowStyleRule = "if field invoice/payed == false and age > 30 then red bold".parse().unwrap();
+        assert_eq!(rule.style(), "Frb");
+        assert_eq!(rule.any_of.len(), 1);
+        assert_eq!(rule.any_of[0].len(), 2);
+
+        assert_eq!("red".parse::<RowStyleRule>(), Err(RuleError::Syntax));
+        assert_eq!("if canceled then".parse::<RowStyleRule>(), Err(RuleError::Syntax));
+        assert_eq!("if bogus then red".parse::<RowStyleRule>(), Err(RuleError::UnknownSubject("bogus".into())));
+        assert_eq!("if age > then red".parse::<RowStyleRule>(), Err(RuleError::MissingValue(">".into())));
+        assert_eq!("if canceled then sparkly".parse::<RowStyleRule>(), Err(RuleError::UnknownStyle("sparkly".into())));
+    }
+
+    #[test]
+    fn skips_invalid_rules() {
+        let rules = parse_rules(&["if canceled then none", "if what then red", "if age then green"]);
+        assert_eq!(rules.len(), 2);
+    }
+
+    #[test]
+    fn evaluates_rules() {
+        let canceled = project("canceled: true\nevent:\n  dates:\n    - begin: 01.01.2000\n");
+        let plain = project("event:\n  name: test\n");
+
+        let rules = parse_rules(&[
+            "if not canceled and age == none then yellow",
+            "if canceled and age > 30 then red bold",
+            "if field event/name == test or payed then cyan",
+        ]);
+
+        assert_eq!(style_for(&canceled, &rules), "Frb");
+        assert_eq!(style_for(&plain, &rules), "Fy");
+        assert_eq!(style_for(&plain, &rules[1..]), "Fc");
+        assert_eq!(style_for(&canceled, &rules[2..]), "");
+    }
+}
@@ -1,5 +1,5 @@
 use asciii::{
-    document_export::fill_template,
+    document_export::{backend::Latex, fill_template, fill_template_content},
     project::{
         export::{Complete, ExportTarget},
         BillType, Project,
@@ -11,7 +11,7 @@ fn export(path: &str, bill_type: BillType) -> String {
     let p = Project::open(path).unwrap();
     let exported: Complete = p.export();
     std::env::set_var("ASCIII_PATH", "");
-    fill_template(&exported, bill_type, "./templates/export.tex.hbs").unwrap()
+    fill_template(&exported, bill_type, "./asciii-core/templates/export.tex.hbs", &Latex).unwrap()
 }
 
 #[test]
@@ -21,6 +21,16 @@ fn current_offer() {
     assert_eq!(exported, expected)
 }
 
+#[test]
+fn template_content_fills_like_template_file() {
+    let p = Project::open("./tests/test_projects/current.yml").unwrap();
+    let exported: Complete = p.export();
+    let template = include_str!("../asciii-core/templates/export.tex.hbs");
+    std::env::set_var("ASCIII_PATH", "");
+    assert_eq!(fill_template_content(&exported, BillType::Offer, template, &Latex).unwrap(),
+               export("./tests/test_projects/current.yml", BillType::Offer))
+}
+
 #[test]
 fn current_invoice() {
     let exported = export("./tests/test_projects/current.yml", BillType::Invoice);